@@ -0,0 +1,16 @@
+//! Browser bindings for the PA prover.
+//!
+//! ```js
+//! import init, { prove } from "./pkg/corpus_wasm.js";
+//! await init();
+//! const report = JSON.parse(prove("0 + x = x", 10000));
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+/// Settle `formula` within `max_nodes` states; the JSON report of
+/// `peano_arithmetic::web::prove_json`.
+#[wasm_bindgen]
+pub fn prove(formula: &str, max_nodes: usize) -> String {
+    peano_arithmetic::web::prove_json(formula, max_nodes).to_string()
+}
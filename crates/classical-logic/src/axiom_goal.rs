@@ -0,0 +1,204 @@
+//! Closing goals that are instances of axioms.
+//!
+//! An axiom's leading `∀`s become pattern variables and the rest of its body
+//! is matched against the goal, with atom arguments compared by unification.
+//! So `S(0) = S(0)` is recognised as an instance of `∀x. x = x`.
+//!
+//! A pattern variable may occur under further binders in the axiom; the term
+//! it matches there must not mention those binders, and is shifted out so
+//! all of its occurrences must agree at the axiom's top level.
+
+use crate::first_order::FirstOrderDomain;
+use crate::{BinaryTruth, ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::HashNode;
+use corpus_core::proving::GoalChecker;
+use corpus_core::rewriting::{Pattern, Substitution, Unifiable};
+
+type Formula<D> = ClassicalLogicalExpression<BinaryTruth, D>;
+
+/// Goal checker proving instances of its axioms and refuting negations of them.
+pub struct AxiomGoalChecker<D, F>
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+    F: FirstOrderDomain<BinaryTruth, D>,
+{
+    domain: F,
+    axioms: Vec<(String, HashNode<Formula<D>>)>,
+}
+
+impl<D, F> AxiomGoalChecker<D, F>
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+    F: FirstOrderDomain<BinaryTruth, D>,
+{
+    pub fn new(domain: F) -> Self {
+        Self { domain, axioms: Vec::new() }
+    }
+
+    pub fn add_axiom(&mut self, name: impl Into<String>, axiom: HashNode<Formula<D>>) {
+        self.axioms.push((name.into(), axiom));
+    }
+
+    pub fn axioms(&self) -> impl Iterator<Item = (&str, &HashNode<Formula<D>>)> {
+        self.axioms.iter().map(|(name, axiom)| (name.as_str(), axiom))
+    }
+
+    /// The name of the first axiom `goal` is an instance of.
+    pub fn matching_axiom(&self, goal: &HashNode<Formula<D>>) -> Option<&str> {
+        self.axioms
+            .iter()
+            .find(|(_, axiom)| self.instance_of(axiom, goal).is_some())
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// The substitution for `axiom`'s outer `∀`s (`/0` the innermost) that
+    /// turns its body into `goal`, if there is one.
+    pub fn instance_of(&self, axiom: &HashNode<Formula<D>>, goal: &HashNode<Formula<D>>) -> Option<Substitution<F::Term>> {
+        let mut body = axiom;
+        let mut parameters = 0;
+        while let LogicalExpression::Compound { operator: ClassicalOperator::Forall, operands, .. } = body.value.as_ref() {
+            body = operands.first()?;
+            parameters += 1;
+        }
+        let mut matcher = Matcher { domain: &self.domain, parameters, bindings: Substitution::new() };
+        matcher.formula(body, goal, 0).then_some(matcher.bindings)
+    }
+}
+
+impl<D, F> GoalChecker<Formula<D>, BinaryTruth> for AxiomGoalChecker<D, F>
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+    F: FirstOrderDomain<BinaryTruth, D>,
+{
+    fn check(&self, expr: &HashNode<Formula<D>>) -> Option<BinaryTruth> {
+        if self.matching_axiom(expr).is_some() {
+            return Some(BinaryTruth::True);
+        }
+        match expr.value.as_ref() {
+            LogicalExpression::Compound { operator: ClassicalOperator::Not, operands, .. } if operands.len() == 1 => {
+                self.matching_axiom(&operands[0]).map(|_| BinaryTruth::False)
+            }
+            _ => None,
+        }
+    }
+}
+
+struct Matcher<'a, F, D>
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+    F: FirstOrderDomain<BinaryTruth, D>,
+{
+    domain: &'a F,
+    /// Number of stripped `∀`s; indices below this at the top level are pattern variables.
+    parameters: u32,
+    bindings: Substitution<F::Term>,
+}
+
+impl<F, D> Matcher<'_, F, D>
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+    F: FirstOrderDomain<BinaryTruth, D>,
+{
+    /// Match `pattern`, under `depth` binders inside the axiom body, against `goal`.
+    fn formula(&mut self, pattern: &HashNode<Formula<D>>, goal: &HashNode<Formula<D>>, depth: u32) -> bool {
+        match (pattern.value.as_ref(), goal.value.as_ref()) {
+            (LogicalExpression::Atomic(pattern), LogicalExpression::Atomic(goal)) => self.atom(pattern, goal, depth),
+            (
+                LogicalExpression::Compound { operator, operands, .. },
+                LogicalExpression::Compound { operator: goal_operator, operands: goal_operands, .. },
+            ) => {
+                if operator != goal_operator || operands.len() != goal_operands.len() {
+                    return false;
+                }
+                let depth = depth + u32::from(matches!(operator, ClassicalOperator::Forall | ClassicalOperator::Exists));
+                operands.iter().zip(goal_operands).all(|(pattern, goal)| self.formula(pattern, goal, depth))
+            }
+            _ => false,
+        }
+    }
+
+    fn atom(&mut self, pattern: &HashNode<D>, goal: &HashNode<D>, depth: u32) -> bool {
+        let (Some((symbol, arguments)), Some((goal_symbol, goal_arguments))) =
+            (self.domain.predicate(pattern), self.domain.predicate(goal))
+        else {
+            return self.parameters == 0 && pattern == goal;
+        };
+        symbol == goal_symbol
+            && arguments.len() == goal_arguments.len()
+            && arguments.iter().zip(&goal_arguments).all(|(argument, goal)| self.term(argument, goal, depth))
+    }
+
+    fn term(&mut self, pattern: &HashNode<F::Term>, goal: &HashNode<F::Term>, depth: u32) -> bool {
+        let parameters = self.parameters;
+        let pattern = Pattern::from_term(pattern, &|term| {
+            let index = self.domain.bound_index(term)?.checked_sub(depth)?;
+            Some(if index < parameters {
+                Pattern::var(index)
+            } else {
+                // A free variable of the axiom, with the stripped binders removed.
+                Pattern::constant(self.domain.variable(index - parameters + depth).value.as_ref().clone())
+            })
+        });
+        let Ok(local) = F::Term::unify(&pattern, goal, &Substitution::new(), self.domain.terms()) else {
+            return false;
+        };
+        for (index, term) in local.iter() {
+            let shifted = self.domain.map_variables(term, &|i| i.checked_sub(depth).map(|i| self.domain.variable(i)));
+            let Some(shifted) = shifted else {
+                return false;
+            };
+            match self.bindings.get(*index) {
+                Some(bound) if *bound != shifted => return false,
+                Some(_) => {}
+                None => self.bindings.bind(*index, shifted),
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FoDomain, Formulas};
+
+    #[test]
+    fn test_instances_of_quantified_axioms() {
+        let f = Formulas::first_order();
+        let mut checker = AxiomGoalChecker::new(FoDomain(&f));
+        let equal = |a, b| f.pred(0, vec![a, b]);
+        let succ = |a| f.app(0, vec![a]);
+        let zero = f.constant(0);
+
+        checker.add_axiom("reflexivity", f.forall(&equal(f.var(0), f.var(0))));
+        let goal = equal(succ(zero.clone()), succ(zero.clone()));
+        assert_eq!(checker.matching_axiom(&goal), Some("reflexivity"));
+        assert_eq!(checker.check(&goal), Some(BinaryTruth::True));
+        assert_eq!(checker.check(&f.not(&goal)), Some(BinaryTruth::False));
+        assert_eq!(checker.check(&equal(succ(zero.clone()), zero.clone())), None);
+
+        // ∀x ∀y. x = y → S(x) = S(y), with y as /0 and x as /1.
+        let congruence = f.forall(&f.forall(&f.implies(&equal(f.var(1), f.var(0)), &equal(succ(f.var(1)), succ(f.var(0))))));
+        checker.add_axiom("congruence", congruence.clone());
+        let instance = f.implies(&equal(zero.clone(), f.constant(1)), &equal(succ(zero.clone()), succ(f.constant(1))));
+        let substitution = checker.instance_of(&congruence, &instance).unwrap();
+        assert_eq!(substitution.get(1), Some(&zero));
+        assert_eq!(checker.matching_axiom(&instance), Some("congruence"));
+    }
+
+    #[test]
+    fn test_parameters_under_inner_binders() {
+        let f = Formulas::first_order();
+        let mut checker = AxiomGoalChecker::new(FoDomain(&f));
+        let less = |a, b| f.pred(1, vec![a, b]);
+
+        // ∀x ∃y. x < y: inside ∃, x is /1.
+        checker.add_axiom("unbounded", f.forall(&f.exists(&less(f.var(1), f.var(0)))));
+        let c = f.constant(3);
+        assert_eq!(checker.check(&f.exists(&less(c.clone(), f.var(0)))), Some(BinaryTruth::True));
+        assert_eq!(checker.check(&f.exists(&less(f.var(0), c))), None);
+        // x cannot be instantiated with the variable bound by ∃.
+        assert_eq!(checker.check(&f.exists(&less(f.var(0), f.var(0)))), None);
+    }
+}
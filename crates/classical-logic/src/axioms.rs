@@ -6,6 +6,7 @@ use crate::operators::ClassicalOperator;
 use corpus_core::base::axioms::{AxiomConverter, InferenceDirection, InferenceDirectional};
 use corpus_core::base::expression::{DomainContent, LogicalExpression};
 use corpus_core::base::nodes::{HashNode, HashNodeInner};
+use corpus_core::logic::LogicalOperator;
 use corpus_core::rewriting::{Pattern, RewriteDirection, RewriteRule};
 use corpus_core::truth::TruthValue;
 use std::clone::Clone;
@@ -89,14 +90,15 @@ where
 }
 
 /// Create a bidirectional rewrite rule from an equality axiom.
-fn create_equality_rule<T: TruthValue, D: DomainContent<T>>(
+pub(crate) fn create_equality_rule<T: TruthValue, D: DomainContent<T>, Op>(
     name: &str,
-    lhs: &HashNode<LogicalExpression<T, D, ClassicalOperator>>,
-    rhs: &HashNode<LogicalExpression<T, D, ClassicalOperator>>,
-) -> RewriteRule<LogicalExpression<T, D, ClassicalOperator>>
+    lhs: &HashNode<LogicalExpression<T, D, Op>>,
+    rhs: &HashNode<LogicalExpression<T, D, Op>>,
+) -> RewriteRule<LogicalExpression<T, D, Op>>
 where
     T: HashNodeInner,
     D: HashNodeInner + Clone,
+    Op: LogicalOperator<T> + HashNodeInner,
 {
     let lhs_pattern = expression_to_pattern(lhs);
     let rhs_pattern = expression_to_pattern(rhs);
@@ -104,14 +106,15 @@ where
 }
 
 /// Create a forward rewrite rule from an implication axiom.
-fn create_implication_rule<T: TruthValue, D: DomainContent<T>>(
+pub(crate) fn create_implication_rule<T: TruthValue, D: DomainContent<T>, Op>(
     name: &str,
-    antecedent: &HashNode<LogicalExpression<T, D, ClassicalOperator>>,
-    consequent: &HashNode<LogicalExpression<T, D, ClassicalOperator>>,
-) -> RewriteRule<LogicalExpression<T, D, ClassicalOperator>>
+    antecedent: &HashNode<LogicalExpression<T, D, Op>>,
+    consequent: &HashNode<LogicalExpression<T, D, Op>>,
+) -> RewriteRule<LogicalExpression<T, D, Op>>
 where
     T: HashNodeInner,
     D: HashNodeInner + Clone,
+    Op: LogicalOperator<T> + HashNodeInner,
 {
     let antecedent_pattern = expression_to_pattern(antecedent);
     let consequent_pattern = expression_to_pattern(consequent);
@@ -119,12 +122,13 @@ where
 }
 
 /// Convert a LogicalExpression to a Pattern.
-fn expression_to_pattern<T: TruthValue, D: DomainContent<T>>(
-    expr: &HashNode<LogicalExpression<T, D, ClassicalOperator>>,
-) -> Pattern<LogicalExpression<T, D, ClassicalOperator>>
+pub(crate) fn expression_to_pattern<T: TruthValue, D: DomainContent<T>, Op>(
+    expr: &HashNode<LogicalExpression<T, D, Op>>,
+) -> Pattern<LogicalExpression<T, D, Op>>
 where
     T: HashNodeInner,
     D: HashNodeInner + Clone,
+    Op: LogicalOperator<T> + HashNodeInner,
 {
     match expr.value.as_ref() {
         LogicalExpression::Atomic(_) => {
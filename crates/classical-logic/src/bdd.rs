@@ -0,0 +1,280 @@
+//! Reduced ordered binary decision diagrams.
+//!
+//! A BDD represents a propositional function as a decision graph in which
+//! variables are tested in a fixed order, no node has equal branches and no
+//! two nodes are structurally equal. Nodes are hash-consed in a
+//! `NodeStorage`, so two formulas over the same `Bdd` are equivalent exactly
+//! when their diagrams are the same node.
+//!
+//! Atoms are numbered in order of first appearance, with equalities counted
+//! as atoms as in `truth_table`.
+
+use crate::tables::TruthTableError;
+use crate::{BinaryTruth, ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner, Hashing, NodeStorage};
+use std::collections::{HashMap, HashSet};
+
+/// A BDD node: a constant, or a test of `variable` with its two branches.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BddNode {
+    Terminal(bool),
+    Decision {
+        variable: u32,
+        low: HashNode<BddNode>,
+        high: HashNode<BddNode>,
+    },
+}
+
+impl HashNodeInner for BddNode {
+    fn hash(&self) -> u64 {
+        match self {
+            BddNode::Terminal(value) => Hashing::root_hash(Hashing::opcode("bdd_terminal"), &[u64::from(*value)]),
+            BddNode::Decision { variable, low, high } => {
+                Hashing::root_hash(Hashing::opcode("bdd_decision"), &[u64::from(*variable), low.hash(), high.hash()])
+            }
+        }
+    }
+
+    fn size(&self) -> u64 {
+        match self {
+            BddNode::Terminal(_) => 1,
+            BddNode::Decision { low, high, .. } => 1 + low.size() + high.size(),
+        }
+    }
+}
+
+/// A manager owning BDD nodes and the atoms of the formulas converted so far.
+pub struct Bdd<D: DomainContent<BinaryTruth, Operator = ClassicalOperator>> {
+    nodes: NodeStorage<BddNode>,
+    atoms: Vec<HashNode<ClassicalLogicalExpression<BinaryTruth, D>>>,
+    index: HashMap<u64, u32>,
+    /// `ite` results by the hashes of its arguments.
+    cache: HashMap<(u64, u64, u64), HashNode<BddNode>>,
+}
+
+impl<D: DomainContent<BinaryTruth, Operator = ClassicalOperator>> Default for Bdd<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: DomainContent<BinaryTruth, Operator = ClassicalOperator>> Bdd<D> {
+    pub fn new() -> Self {
+        Self {
+            nodes: NodeStorage::new(),
+            atoms: Vec::new(),
+            index: HashMap::new(),
+            cache: HashMap::new(),
+        }
+    }
+
+    /// The atoms seen so far; variable `i` is `atoms()[i]`.
+    pub fn atoms(&self) -> &[HashNode<ClassicalLogicalExpression<BinaryTruth, D>>] {
+        &self.atoms
+    }
+
+    pub fn constant(&self, value: bool) -> HashNode<BddNode> {
+        HashNode::from_store(BddNode::Terminal(value), &self.nodes)
+    }
+
+    /// The function that is true exactly when `variable` is.
+    pub fn variable(&self, variable: u32) -> HashNode<BddNode> {
+        self.decision(variable, self.constant(false), self.constant(true))
+    }
+
+    /// `if f then g else h`, the operation every connective reduces to.
+    pub fn ite(&mut self, f: &HashNode<BddNode>, g: &HashNode<BddNode>, h: &HashNode<BddNode>) -> HashNode<BddNode> {
+        match (f.value.as_ref(), g.value.as_ref(), h.value.as_ref()) {
+            (BddNode::Terminal(true), _, _) => return g.clone(),
+            (BddNode::Terminal(false), _, _) => return h.clone(),
+            (_, BddNode::Terminal(true), BddNode::Terminal(false)) => return f.clone(),
+            _ if g == h => return g.clone(),
+            _ => {}
+        }
+        let key = (f.hash(), g.hash(), h.hash());
+        if let Some(result) = self.cache.get(&key) {
+            return result.clone();
+        }
+
+        let variable = [f, g, h].iter().filter_map(|node| top_variable(node)).min().expect("a non-terminal argument");
+        let (f0, f1) = cofactors(f, variable);
+        let (g0, g1) = cofactors(g, variable);
+        let (h0, h1) = cofactors(h, variable);
+        let low = self.ite(&f0, &g0, &h0);
+        let high = self.ite(&f1, &g1, &h1);
+        let result = self.decision(variable, low, high);
+        self.cache.insert(key, result.clone());
+        result
+    }
+
+    pub fn not(&mut self, a: &HashNode<BddNode>) -> HashNode<BddNode> {
+        let (t, f) = (self.constant(true), self.constant(false));
+        self.ite(a, &f, &t)
+    }
+
+    pub fn and(&mut self, a: &HashNode<BddNode>, b: &HashNode<BddNode>) -> HashNode<BddNode> {
+        let f = self.constant(false);
+        self.ite(a, b, &f)
+    }
+
+    pub fn or(&mut self, a: &HashNode<BddNode>, b: &HashNode<BddNode>) -> HashNode<BddNode> {
+        let t = self.constant(true);
+        self.ite(a, &t, b)
+    }
+
+    pub fn implies(&mut self, a: &HashNode<BddNode>, b: &HashNode<BddNode>) -> HashNode<BddNode> {
+        let t = self.constant(true);
+        self.ite(a, b, &t)
+    }
+
+    pub fn iff(&mut self, a: &HashNode<BddNode>, b: &HashNode<BddNode>) -> HashNode<BddNode> {
+        let not_b = self.not(b);
+        self.ite(a, b, &not_b)
+    }
+
+    /// The BDD of the quantifier-free formula `expr`.
+    pub fn from_formula(
+        &mut self,
+        expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+    ) -> Result<HashNode<BddNode>, TruthTableError> {
+        let (operator, operands) = match expr.value.as_ref() {
+            LogicalExpression::Compound { operator, operands, .. } if *operator != ClassicalOperator::Equals => {
+                (*operator, operands)
+            }
+            _ => {
+                let next = self.atoms.len() as u32;
+                let variable = *self.index.entry(expr.hash()).or_insert(next);
+                if variable == next {
+                    self.atoms.push(expr.clone());
+                }
+                return Ok(self.variable(variable));
+            }
+        };
+        match operator {
+            ClassicalOperator::Forall | ClassicalOperator::Exists => return Err(TruthTableError::Quantified),
+            _ if operands.len() != operator.arity() => return Err(TruthTableError::Malformed(operator)),
+            _ => {}
+        }
+        let operands = operands.iter().map(|operand| self.from_formula(operand)).collect::<Result<Vec<_>, _>>()?;
+        Ok(match operator {
+            ClassicalOperator::Not => self.not(&operands[0]),
+            ClassicalOperator::And => self.and(&operands[0], &operands[1]),
+            ClassicalOperator::Or => self.or(&operands[0], &operands[1]),
+            ClassicalOperator::Implies => self.implies(&operands[0], &operands[1]),
+            ClassicalOperator::Iff => self.iff(&operands[0], &operands[1]),
+            ClassicalOperator::Equals | ClassicalOperator::Forall | ClassicalOperator::Exists => {
+                unreachable!("handled above")
+            }
+        })
+    }
+
+    /// Number of distinct decision nodes reachable from `node`.
+    pub fn node_count(&self, node: &HashNode<BddNode>) -> usize {
+        fn visit(node: &HashNode<BddNode>, seen: &mut HashSet<u64>) {
+            if let BddNode::Decision { low, high, .. } = node.value.as_ref() {
+                if seen.insert(node.hash()) {
+                    visit(low, seen);
+                    visit(high, seen);
+                }
+            }
+        }
+        let mut seen = HashSet::new();
+        visit(node, &mut seen);
+        seen.len()
+    }
+
+    /// Values for some of the variables that make `node` true, or `None`
+    /// if it is unsatisfiable. Variables left out may take either value.
+    pub fn satisfying_assignment(&self, node: &HashNode<BddNode>) -> Option<Vec<(u32, bool)>> {
+        match node.value.as_ref() {
+            BddNode::Terminal(value) => value.then(Vec::new),
+            BddNode::Decision { variable, low, high } => {
+                let (branch, value) = if matches!(low.value.as_ref(), BddNode::Terminal(false)) { (high, true) } else { (low, false) };
+                let mut assignment = self.satisfying_assignment(branch)?;
+                assignment.insert(0, (*variable, value));
+                Some(assignment)
+            }
+        }
+    }
+
+    /// The reduced node testing `variable`: `low` itself if both branches agree.
+    fn decision(&self, variable: u32, low: HashNode<BddNode>, high: HashNode<BddNode>) -> HashNode<BddNode> {
+        if low == high {
+            return low;
+        }
+        HashNode::from_store(BddNode::Decision { variable, low, high }, &self.nodes)
+    }
+}
+
+fn top_variable(node: &HashNode<BddNode>) -> Option<u32> {
+    match node.value.as_ref() {
+        BddNode::Terminal(_) => None,
+        BddNode::Decision { variable, .. } => Some(*variable),
+    }
+}
+
+/// The branches of `node` for `variable` false and true, where `variable`
+/// is at or above the node's own test.
+fn cofactors(node: &HashNode<BddNode>, variable: u32) -> (HashNode<BddNode>, HashNode<BddNode>) {
+    match node.value.as_ref() {
+        BddNode::Decision { variable: v, low, high } if *v == variable => (low.clone(), high.clone()),
+        _ => (node.clone(), node.clone()),
+    }
+}
+
+/// Whether the quantifier-free formulas `a` and `b` are equivalent.
+pub fn are_equivalent<D>(
+    a: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+    b: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+) -> Result<bool, TruthTableError>
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+{
+    let mut bdd = Bdd::new();
+    Ok(bdd.from_formula(a)? == bdd.from_formula(b)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::is_tautology;
+    use crate::testing::Formulas;
+
+    #[test]
+    fn test_equivalence_agrees_with_truth_tables() {
+        let f = Formulas::new();
+        let (p, q, r) = (f.atom(0), f.atom(1), f.atom(2));
+        let pairs = [
+            (f.and(&p, &f.or(&q, &r)), f.or(&f.and(&p, &q), &f.and(&p, &r))),
+            (f.not(&f.and(&p, &q)), f.or(&f.not(&p), &f.not(&q))),
+            (f.implies(&p, &q), f.implies(&f.not(&q), &f.not(&p))),
+            (f.iff(&p, &q), f.or(&f.and(&p, &q), &f.and(&q, &r))),
+            (f.or(&p, &f.not(&p)), f.implies(&q, &q)),
+            (f.implies(&p, &q), f.implies(&q, &p)),
+        ];
+
+        for (a, b) in &pairs {
+            assert_eq!(are_equivalent(a, b), is_tautology(&f.iff(a, b)), "{} vs {}", a, b);
+        }
+        assert_eq!(are_equivalent(&f.forall(&p), &p), Err(TruthTableError::Quantified));
+    }
+
+    #[test]
+    fn test_diagrams_are_canonical() {
+        let f = Formulas::new();
+        let (p, q) = (f.atom(0), f.atom(1));
+        let mut bdd = Bdd::new();
+
+        let pq = bdd.from_formula(&f.and(&p, &q)).unwrap();
+        let qp = bdd.from_formula(&f.and(&q, &p)).unwrap();
+        assert_eq!(pq, qp);
+        assert_eq!(bdd.node_count(&pq), 2);
+        assert_eq!(bdd.satisfying_assignment(&pq), Some(vec![(0, true), (1, true)]));
+
+        let contradiction = bdd.from_formula(&f.and(&p, &f.not(&p))).unwrap();
+        assert_eq!(contradiction, bdd.constant(false));
+        assert_eq!(bdd.satisfying_assignment(&contradiction), None);
+        assert_eq!(bdd.atoms().len(), 2);
+    }
+}
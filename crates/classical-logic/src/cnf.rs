@@ -0,0 +1,453 @@
+//! Conjunctive and disjunctive normal forms.
+//!
+//! Formulas are abstracted propositionally: atomic expressions, equalities
+//! and quantified subformulas become variables (hash-consing makes identical
+//! subformulas share one), and `∧`, `∨`, `¬`, `->` and `<->` are normalized.
+//! `to_cnf` and `to_dnf` distribute, which can blow up exponentially;
+//! `to_cnf_tseitin` names every connective with a fresh variable instead and
+//! produces an equisatisfiable clause set of linear size.
+
+use crate::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner};
+use corpus_core::truth::TruthValue;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// A propositional variable or its negation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Literal {
+    pub variable: u32,
+    pub positive: bool,
+}
+
+impl Literal {
+    pub fn positive(variable: u32) -> Self {
+        Self { variable, positive: true }
+    }
+
+    pub fn negative(variable: u32) -> Self {
+        Self { variable, positive: false }
+    }
+
+    pub fn negated(self) -> Self {
+        Self {
+            variable: self.variable,
+            positive: !self.positive,
+        }
+    }
+
+    /// Value of the literal when variable `i` is `assignment[i]`.
+    pub fn evaluate(&self, assignment: &[bool]) -> bool {
+        assignment[self.variable as usize] == self.positive
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.positive {
+            write!(f, "x{}", self.variable)
+        } else {
+            write!(f, "¬x{}", self.variable)
+        }
+    }
+}
+
+/// A disjunction of literals in a `ClauseSet`, a conjunction in a `CubeSet`.
+pub type Clause = Vec<Literal>;
+
+/// What a propositional variable stands for.
+pub enum Atom<E: HashNodeInner> {
+    /// A subformula the normal form treats as opaque.
+    Formula(HashNode<E>),
+    /// A fresh Tseitin variable, equivalent to the given subformula.
+    Definition(HashNode<E>),
+}
+
+impl<E: HashNodeInner> Atom<E> {
+    pub fn formula(&self) -> &HashNode<E> {
+        match self {
+            Atom::Formula(formula) | Atom::Definition(formula) => formula,
+        }
+    }
+
+    pub fn is_definition(&self) -> bool {
+        matches!(self, Atom::Definition(_))
+    }
+}
+
+/// Numbering of the propositional variables of a normal form.
+pub struct AtomTable<E: HashNodeInner> {
+    atoms: Vec<Atom<E>>,
+    index: HashMap<u64, u32>,
+}
+
+impl<E: HashNodeInner> AtomTable<E> {
+    pub fn new() -> Self {
+        Self {
+            atoms: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.atoms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.atoms.is_empty()
+    }
+
+    pub fn get(&self, variable: u32) -> Option<&Atom<E>> {
+        self.atoms.get(variable as usize)
+    }
+
+    /// The variable standing for `formula`, if any.
+    pub fn variable_of(&self, formula: &HashNode<E>) -> Option<u32> {
+        self.index.get(&formula.hash()).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &Atom<E>)> {
+        self.atoms.iter().enumerate().map(|(i, atom)| (i as u32, atom))
+    }
+
+    /// The variable for `atom`, and whether it was newly added.
+    pub(crate) fn intern(&mut self, atom: Atom<E>) -> (u32, bool) {
+        let hash = atom.formula().hash();
+        if let Some(&variable) = self.index.get(&hash) {
+            return (variable, false);
+        }
+        let variable = self.atoms.len() as u32;
+        self.atoms.push(atom);
+        self.index.insert(hash, variable);
+        (variable, true)
+    }
+}
+
+impl<E: HashNodeInner> Default for AtomTable<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A formula in conjunctive normal form: every clause must hold.
+pub struct ClauseSet<E: HashNodeInner> {
+    pub atoms: AtomTable<E>,
+    pub clauses: Vec<Clause>,
+}
+
+impl<E: HashNodeInner> ClauseSet<E> {
+    pub fn num_variables(&self) -> usize {
+        self.atoms.len()
+    }
+
+    /// Whether every clause has a true literal under `assignment`.
+    pub fn evaluate(&self, assignment: &[bool]) -> bool {
+        self.clauses
+            .iter()
+            .all(|clause| clause.iter().any(|literal| literal.evaluate(assignment)))
+    }
+}
+
+/// A formula in disjunctive normal form: some cube must hold.
+pub struct CubeSet<E: HashNodeInner> {
+    pub atoms: AtomTable<E>,
+    pub cubes: Vec<Clause>,
+}
+
+impl<E: HashNodeInner> CubeSet<E> {
+    pub fn num_variables(&self) -> usize {
+        self.atoms.len()
+    }
+
+    /// Whether some cube has only true literals under `assignment`.
+    pub fn evaluate(&self, assignment: &[bool]) -> bool {
+        self.cubes
+            .iter()
+            .any(|cube| cube.iter().all(|literal| literal.evaluate(assignment)))
+    }
+}
+
+type Operands<T, D> = [HashNode<ClassicalLogicalExpression<T, D>>];
+
+/// A formula in negation normal form over numbered atoms.
+enum Nnf {
+    Literal(Literal),
+    And(Vec<Nnf>),
+    Or(Vec<Nnf>),
+}
+
+/// The connective and operands of `expr` if it is a propositional connective
+/// with the right number of operands.
+fn connective<T, D>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+) -> Option<(ClassicalOperator, &Operands<T, D>)>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let LogicalExpression::Compound { operator, operands, .. } = expr.value.as_ref() else {
+        return None;
+    };
+    match operator {
+        ClassicalOperator::Not if operands.len() == 1 => Some((*operator, operands)),
+        ClassicalOperator::And | ClassicalOperator::Or | ClassicalOperator::Implies | ClassicalOperator::Iff
+            if operands.len() == 2 =>
+        {
+            Some((*operator, operands))
+        }
+        _ => None,
+    }
+}
+
+fn nnf<T, D>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    positive: bool,
+    atoms: &mut AtomTable<ClassicalLogicalExpression<T, D>>,
+) -> Nnf
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let Some((operator, operands)) = connective(expr) else {
+        let (variable, _) = atoms.intern(Atom::Formula(expr.clone()));
+        return Nnf::Literal(Literal { variable, positive });
+    };
+
+    let mut nnf = |index: usize, positive: bool| nnf(&operands[index], positive, atoms);
+    match (operator, positive) {
+        (ClassicalOperator::Not, _) => nnf(0, !positive),
+        (ClassicalOperator::And, true) => Nnf::And(vec![nnf(0, true), nnf(1, true)]),
+        (ClassicalOperator::And, false) => Nnf::Or(vec![nnf(0, false), nnf(1, false)]),
+        (ClassicalOperator::Or, true) => Nnf::Or(vec![nnf(0, true), nnf(1, true)]),
+        (ClassicalOperator::Or, false) => Nnf::And(vec![nnf(0, false), nnf(1, false)]),
+        (ClassicalOperator::Implies, true) => Nnf::Or(vec![nnf(0, false), nnf(1, true)]),
+        (ClassicalOperator::Implies, false) => Nnf::And(vec![nnf(0, true), nnf(1, false)]),
+        (ClassicalOperator::Iff, true) => Nnf::And(vec![
+            Nnf::Or(vec![nnf(0, false), nnf(1, true)]),
+            Nnf::Or(vec![nnf(0, true), nnf(1, false)]),
+        ]),
+        (ClassicalOperator::Iff, false) => Nnf::Or(vec![
+            Nnf::And(vec![nnf(0, true), nnf(1, false)]),
+            Nnf::And(vec![nnf(0, false), nnf(1, true)]),
+        ]),
+        _ => unreachable!("connective only returns propositional connectives"),
+    }
+}
+
+/// Sort and deduplicate `clause`; `None` if it contains a literal and its negation.
+fn normalize(mut clause: Clause) -> Option<Clause> {
+    clause.sort();
+    clause.dedup();
+    let complementary = clause
+        .windows(2)
+        .any(|pair| pair[0].variable == pair[1].variable);
+    (!complementary).then_some(clause)
+}
+
+/// Clauses of `nnf` when `conjunctive`, cubes when not. The outer connective
+/// concatenates and the inner one distributes.
+fn distribute(nnf: &Nnf, conjunctive: bool) -> Vec<Clause> {
+    match nnf {
+        Nnf::Literal(literal) => vec![vec![*literal]],
+        Nnf::And(children) if conjunctive => children.iter().flat_map(|child| distribute(child, true)).collect(),
+        Nnf::Or(children) if !conjunctive => children.iter().flat_map(|child| distribute(child, false)).collect(),
+        Nnf::And(children) | Nnf::Or(children) => children.iter().fold(vec![Vec::new()], |acc, child| {
+            let parts = distribute(child, conjunctive);
+            acc.iter()
+                .flat_map(|left| {
+                    parts.iter().filter_map(move |right| normalize(left.iter().chain(right).copied().collect()))
+                })
+                .collect()
+        }),
+    }
+    .into_iter()
+    .filter_map(normalize)
+    .collect()
+}
+
+/// Conjunctive normal form of `expr` by distribution.
+pub fn to_cnf<T, D>(expr: &HashNode<ClassicalLogicalExpression<T, D>>) -> ClauseSet<ClassicalLogicalExpression<T, D>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let mut atoms = AtomTable::new();
+    let nnf = nnf(expr, true, &mut atoms);
+    ClauseSet {
+        clauses: distribute(&nnf, true),
+        atoms,
+    }
+}
+
+/// Disjunctive normal form of `expr` by distribution.
+pub fn to_dnf<T, D>(expr: &HashNode<ClassicalLogicalExpression<T, D>>) -> CubeSet<ClassicalLogicalExpression<T, D>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let mut atoms = AtomTable::new();
+    let nnf = nnf(expr, true, &mut atoms);
+    CubeSet {
+        cubes: distribute(&nnf, false),
+        atoms,
+    }
+}
+
+/// Equisatisfiable CNF of `expr` with one `Atom::Definition` variable per
+/// distinct connective (negations excepted).
+///
+/// Definitions are full equivalences, so every model of the clauses restricts
+/// to a model of `expr` and every model of `expr` extends to one of the clauses.
+pub fn to_cnf_tseitin<T, D>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+) -> ClauseSet<ClassicalLogicalExpression<T, D>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    tseitin_asserting(expr, true)
+}
+
+/// `to_cnf_tseitin` for `¬expr`, without building the negation: `expr` is
+/// valid exactly when these clauses are unsatisfiable.
+pub fn to_cnf_tseitin_negated<T, D>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+) -> ClauseSet<ClassicalLogicalExpression<T, D>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    tseitin_asserting(expr, false)
+}
+
+fn tseitin_asserting<T, D>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    positive: bool,
+) -> ClauseSet<ClassicalLogicalExpression<T, D>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let mut set = ClauseSet {
+        atoms: AtomTable::new(),
+        clauses: Vec::new(),
+    };
+    let root = tseitin(expr, &mut set);
+    set.clauses.push(vec![if positive { root } else { root.negated() }]);
+    set
+}
+
+fn tseitin<T, D>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    set: &mut ClauseSet<ClassicalLogicalExpression<T, D>>,
+) -> Literal
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let Some((operator, operands)) = connective(expr) else {
+        return Literal::positive(set.atoms.intern(Atom::Formula(expr.clone())).0);
+    };
+    if operator == ClassicalOperator::Not {
+        return tseitin(&operands[0], set).negated();
+    }
+
+    let (variable, fresh) = set.atoms.intern(Atom::Definition(expr.clone()));
+    let v = Literal::positive(variable);
+    if !fresh {
+        return v;
+    }
+
+    let a = tseitin(&operands[0], set);
+    let b = tseitin(&operands[1], set);
+    let (na, nb, nv) = (a.negated(), b.negated(), v.negated());
+    let definition = match operator {
+        ClassicalOperator::And => vec![vec![nv, a], vec![nv, b], vec![na, nb, v]],
+        ClassicalOperator::Or => vec![vec![nv, a, b], vec![na, v], vec![nb, v]],
+        ClassicalOperator::Implies => vec![vec![nv, na, b], vec![a, v], vec![nb, v]],
+        ClassicalOperator::Iff => vec![vec![nv, na, b], vec![nv, a, nb], vec![v, a, b], vec![v, na, nb]],
+        _ => unreachable!("negation is handled above"),
+    };
+    set.clauses.extend(definition.into_iter().filter_map(normalize));
+    v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{Formula, Formulas};
+
+    /// All assignments to `n` variables.
+    fn assignments(n: usize) -> impl Iterator<Item = Vec<bool>> {
+        (0..1u32 << n).map(move |bits| (0..n).map(|i| bits & (1 << i) != 0).collect())
+    }
+
+    /// Reorder `assignment` (indexed by `from`'s variables) for `to`'s variables.
+    fn translate(assignment: &[bool], from: &AtomTable<Formula>, to: &AtomTable<Formula>) -> Vec<bool> {
+        let mut translated = vec![false; to.len()];
+        for (variable, atom) in from.iter() {
+            if let Some(target) = to.variable_of(atom.formula()) {
+                translated[target as usize] = assignment[variable as usize];
+            }
+        }
+        translated
+    }
+
+    #[test]
+    fn test_implication_and_negation() {
+        let f = Formulas::new();
+        let (p, q) = (f.atom(0), f.atom(1));
+
+        let cnf = to_cnf(&f.implies(&p, &q));
+        assert_eq!(cnf.clauses, vec![vec![Literal::negative(0), Literal::positive(1)]]);
+
+        // ¬(p ∧ ¬p) is a tautology: its only clause is dropped.
+        assert!(to_cnf(&f.not(&f.and(&p, &f.not(&p)))).clauses.is_empty());
+        assert!(to_dnf(&f.and(&p, &f.not(&p))).cubes.is_empty());
+    }
+
+    #[test]
+    fn test_quantified_subformulas_are_atoms() {
+        let f = Formulas::new();
+        let all = f.forall(&f.and(&f.atom(0), &f.atom(1)));
+        let some = f.exists(&f.atom(2));
+        let cnf = to_cnf(&f.or(&all, &f.not(&some)));
+
+        assert_eq!(cnf.num_variables(), 2);
+        assert_eq!(cnf.clauses, vec![vec![Literal::positive(0), Literal::negative(1)]]);
+        assert!(matches!(cnf.atoms.get(0), Some(Atom::Formula(formula)) if *formula == all));
+    }
+
+    #[test]
+    fn test_normal_forms_agree() {
+        let f = Formulas::new();
+        let (p, q, r, s) = (f.atom(0), f.atom(1), f.atom(2), f.atom(3));
+        let expr = f.iff(&f.or(&f.and(&p, &q), &f.and(&r, &s)), &f.implies(&p, &s));
+
+        let cnf = to_cnf(&expr);
+        let dnf = to_dnf(&expr);
+        let tseitin = to_cnf_tseitin(&expr);
+        let definitions = tseitin.atoms.iter().filter(|(_, atom)| atom.is_definition()).count();
+        assert_eq!(definitions, 5);
+
+        for assignment in assignments(cnf.num_variables()) {
+            let value = cnf.evaluate(&assignment);
+            assert_eq!(dnf.evaluate(&translate(&assignment, &cnf.atoms, &dnf.atoms)), value);
+
+            // Tseitin clauses are satisfiable by some values of the definitions exactly when `expr` holds.
+            let base = translate(&assignment, &cnf.atoms, &tseitin.atoms);
+            let extendable = assignments(definitions).any(|extra| {
+                let mut full = base.clone();
+                let mut extra = extra.into_iter();
+                for (variable, atom) in tseitin.atoms.iter() {
+                    if atom.is_definition() {
+                        full[variable as usize] = extra.next().unwrap();
+                    }
+                }
+                tseitin.evaluate(&full)
+            });
+            assert_eq!(extendable, value);
+        }
+    }
+}
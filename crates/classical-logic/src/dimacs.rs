@@ -0,0 +1,234 @@
+//! DIMACS CNF, the input format of external SAT solvers.
+//!
+//! ```text
+//! c 1 := (p0 ∧ p1)
+//! c 2 = p0
+//! c 3 = p1
+//! p cnf 3 4
+//! -1 2 0
+//! -1 3 0
+//! 1 -2 -3 0
+//! 1 0
+//! ```
+//!
+//! Variables are numbered from 1, so variable `i` of a `ClauseSet` is
+//! written `i + 1`, and the comments before the header map each number to
+//! the formula it stands for (`:=` marks Tseitin definitions). Solver output
+//! in the competition format (`s SATISFIABLE` and `v` lines) reads back as a
+//! `SatResult` over the same variables.
+
+use std::fmt;
+
+use crate::cnf::{Atom, AtomTable, Clause, ClauseSet, Literal};
+use crate::sat::SatResult;
+use corpus_core::nodes::{HashNode, HashNodeInner};
+use corpus_core::printing::{DisplayPrinter, ExprPrinter};
+
+/// Malformed DIMACS text, at a line counting from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DimacsError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DimacsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for DimacsError {}
+
+/// The DIMACS number of each variable and the formula it stands for, with
+/// Tseitin definitions written `n := formula`.
+pub fn variable_table<E: HashNodeInner>(atoms: &AtomTable<E>, printer: &dyn ExprPrinter<E>) -> Vec<String> {
+    atoms
+        .iter()
+        .map(|(variable, atom)| {
+            let relation = if atom.is_definition() { ":=" } else { "=" };
+            format!("{} {} {}", variable + 1, relation, printer.print(&atom.formula().value))
+        })
+        .collect()
+}
+
+/// `set` as a DIMACS CNF problem, its variables described by their display
+/// text.
+pub fn to_dimacs<E: HashNodeInner + fmt::Display>(set: &ClauseSet<E>) -> String {
+    to_dimacs_with(set, &DisplayPrinter)
+}
+
+/// `to_dimacs` with formulas rendered by `printer`.
+pub fn to_dimacs_with<E: HashNodeInner>(set: &ClauseSet<E>, printer: &dyn ExprPrinter<E>) -> String {
+    let mut lines: Vec<String> = variable_table(&set.atoms, printer)
+        .into_iter()
+        .map(|entry| format!("c {}", entry.replace('\n', " ")))
+        .collect();
+    lines.push(format!("p cnf {} {}", set.num_variables(), set.clauses.len()));
+    for clause in &set.clauses {
+        let mut literals: Vec<String> = clause.iter().map(|literal| dimacs_literal(*literal).to_string()).collect();
+        literals.push("0".to_string());
+        lines.push(literals.join(" "));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Read a DIMACS CNF problem, letting `atom` supply the formula of each of
+/// its variables, numbered from 0.
+///
+/// Clauses may span lines; a line starting with `%` ends the problem, as in
+/// the SATLIB benchmarks.
+pub fn from_dimacs<E: HashNodeInner>(
+    text: &str,
+    mut atom: impl FnMut(u32) -> HashNode<E>,
+) -> Result<ClauseSet<E>, DimacsError> {
+    let mut header: Option<(usize, usize, usize)> = None;
+    let mut clauses: Vec<Clause> = Vec::new();
+    let mut clause: Clause = Vec::new();
+    let mut last = 0;
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let error = |message: String| DimacsError { line, message };
+        let content = raw.trim();
+        if content.is_empty() || content.starts_with('c') {
+            continue;
+        }
+        if content.starts_with('%') {
+            break;
+        }
+        last = line;
+
+        if let Some(rest) = content.strip_prefix("p ") {
+            let fields: Vec<&str> = rest.split_whitespace().collect();
+            let counts = match fields.as_slice() {
+                ["cnf", variables, clauses] => variables.parse().ok().zip(clauses.parse().ok()),
+                _ => None,
+            };
+            if header.is_some() {
+                return Err(error("a second problem line".into()));
+            }
+            let (variables, count) = counts.ok_or_else(|| error(format!("expected 'p cnf <variables> <clauses>', found '{}'", content)))?;
+            header = Some((variables, count, line));
+            continue;
+        }
+
+        let (variables, _, _) = header.ok_or_else(|| error("clause before the problem line".into()))?;
+        for word in content.split_whitespace() {
+            let number: i64 = word.parse().map_err(|_| error(format!("not a literal: {}", word)))?;
+            if number == 0 {
+                clauses.push(std::mem::take(&mut clause));
+                continue;
+            }
+            if number.unsigned_abs() > variables as u64 {
+                return Err(error(format!("variable {} exceeds the {} declared", number.unsigned_abs(), variables)));
+            }
+            clause.push(Literal { variable: number.unsigned_abs() as u32 - 1, positive: number > 0 });
+        }
+    }
+
+    let (variables, count, header_line) = header.ok_or_else(|| DimacsError { line: text.lines().count(), message: "missing problem line".into() })?;
+    if !clause.is_empty() {
+        clauses.push(clause);
+    }
+    if clauses.len() != count {
+        let message = format!("{} clauses declared, {} found", count, clauses.len());
+        return Err(DimacsError { line: last, message });
+    }
+
+    let mut atoms = AtomTable::new();
+    for variable in 0..variables as u32 {
+        let (number, _) = atoms.intern(Atom::Formula(atom(variable)));
+        if number != variable {
+            let message = format!("variables {} and {} stand for the same formula", number + 1, variable + 1);
+            return Err(DimacsError { line: header_line, message });
+        }
+    }
+    Ok(ClauseSet { atoms, clauses })
+}
+
+/// Read a solver's answer in the competition format for a problem with
+/// `num_variables` variables. Variables the model leaves out are false.
+pub fn read_solver_output(text: &str, num_variables: usize) -> Result<SatResult, DimacsError> {
+    let mut status: Option<bool> = None;
+    let mut model = vec![false; num_variables];
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let error = |message: String| DimacsError { line, message };
+        let content = raw.trim();
+        if let Some(answer) = content.strip_prefix("s ") {
+            status = match answer.trim() {
+                "SATISFIABLE" => Some(true),
+                "UNSATISFIABLE" => Some(false),
+                other => return Err(error(format!("unknown status '{}'", other))),
+            };
+        } else if let Some(values) = content.strip_prefix("v ") {
+            for word in values.split_whitespace() {
+                let number: i64 = word.parse().map_err(|_| error(format!("not a literal: {}", word)))?;
+                if number == 0 {
+                    continue;
+                }
+                let variable = number.unsigned_abs() as usize - 1;
+                let value = model.get_mut(variable).ok_or_else(|| error(format!("variable {} out of range", number.unsigned_abs())))?;
+                *value = number > 0;
+            }
+        }
+    }
+    match status {
+        Some(true) => Ok(SatResult::Satisfiable(model)),
+        Some(false) => Ok(SatResult::Unsatisfiable),
+        None => Err(DimacsError { line: text.lines().count(), message: "no 's' status line".into() }),
+    }
+}
+
+fn dimacs_literal(literal: Literal) -> i64 {
+    let number = i64::from(literal.variable) + 1;
+    if literal.positive {
+        number
+    } else {
+        -number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cnf::to_cnf_tseitin;
+    use crate::sat::solve;
+    use crate::testing::Formulas;
+
+    #[test]
+    fn test_round_trip() {
+        let f = Formulas::new();
+        let (p, q) = (f.atom(0), f.atom(1));
+        let set = to_cnf_tseitin(&f.and(&p, &f.not(&q)));
+        let text = to_dimacs(&set);
+        assert!(text.starts_with("c 1 := "));
+        assert!(text.contains(&format!("\nc 2 = {}\n", p)));
+        assert!(text.contains(&format!("\np cnf {} {}\n", set.num_variables(), set.clauses.len())));
+
+        let formulas: Vec<_> = set.atoms.iter().map(|(_, atom)| atom.formula().clone()).collect();
+        let read = from_dimacs(&text, |variable| formulas[variable as usize].clone()).unwrap();
+        assert_eq!(read.clauses, set.clauses);
+        assert_eq!(read.atoms.variable_of(&q), set.atoms.variable_of(&q));
+
+        let model = solve(&set);
+        let answer: Vec<String> = model.model().unwrap().iter().enumerate().map(|(i, value)| {
+            if *value { format!("{}", i + 1) } else { format!("-{}", i + 1) }
+        }).collect();
+        let output = format!("c solver\ns SATISFIABLE\nv {} 0\n", answer.join(" "));
+        assert_eq!(read_solver_output(&output, set.num_variables()).unwrap(), model);
+        assert_eq!(read_solver_output("s UNSATISFIABLE\n", 2).unwrap(), SatResult::Unsatisfiable);
+    }
+
+    #[test]
+    fn test_errors() {
+        let f = Formulas::new();
+        let read = |text: &str| from_dimacs(text, |variable| f.atom(variable)).err().map(|e| e.to_string());
+        assert_eq!(read("p cnf 2 1\n1 -2\n0\n"), None);
+        assert_eq!(read("1 2 0\n").as_deref(), Some("line 1: clause before the problem line"));
+        assert_eq!(read("p cnf 1 1\n1 2 0\n").as_deref(), Some("line 2: variable 2 exceeds the 1 declared"));
+        assert_eq!(read("p cnf 2 2\n1 2 0\n%\n0\n").as_deref(), Some("line 2: 2 clauses declared, 1 found"));
+        assert_eq!(read("c empty\n").as_deref(), Some("line 1: missing problem line"));
+        let same = from_dimacs("p cnf 2 0\n", |_| f.atom(0)).err().map(|e| e.message);
+        assert_eq!(same.as_deref(), Some("variables 1 and 2 stand for the same formula"));
+    }
+}
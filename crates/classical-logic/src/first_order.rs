@@ -0,0 +1,97 @@
+//! The first-order structure of a domain's atoms.
+//!
+//! Classical formulas treat domain content as opaque atoms. Procedures that
+//! look inside atoms (unification, Skolemization, instantiation) go through a
+//! `FirstOrderDomain`, which splits an atom into a predicate symbol and
+//! argument terms and says which terms are variables bound by `∀`/`∃`.
+//!
+//! Bound variables are de Bruijn indices: `/0` refers to the innermost
+//! enclosing quantifier. Term structure below the arguments comes from the
+//! term type's `HashNodeInner::decompose` and `construct_from_parts`.
+
+use corpus_core::base::expression::DomainContent;
+use corpus_core::nodes::{HashNode, HashNodeInner, Hashing, NodeStorage};
+use corpus_core::truth::TruthValue;
+
+/// Access to predicate symbols, argument terms and variables of atoms in `D`.
+pub trait FirstOrderDomain<T: TruthValue, D: DomainContent<T>> {
+    /// The terms atoms are applied to.
+    type Term: HashNodeInner + Clone;
+
+    /// Predicate symbol and arguments of `atom`, or `None` if it has no
+    /// first-order structure and should be treated as a proposition.
+    fn predicate(&self, atom: &HashNode<D>) -> Option<(u64, Vec<HashNode<Self::Term>>)>;
+
+    /// Rebuild an atom from a predicate symbol returned by `predicate` and new arguments.
+    fn atom(&self, predicate: u64, arguments: Vec<HashNode<Self::Term>>) -> Option<HashNode<D>>;
+
+    /// The de Bruijn index of `term` if it is a variable.
+    fn bound_index(&self, term: &HashNode<Self::Term>) -> Option<u32>;
+
+    /// The variable with de Bruijn index `index`.
+    fn variable(&self, index: u32) -> HashNode<Self::Term>;
+
+    /// The `index`th Skolem function applied to `arguments`, if the domain
+    /// can represent one.
+    fn skolem(&self, _index: u32, _arguments: Vec<HashNode<Self::Term>>) -> Option<HashNode<Self::Term>> {
+        None
+    }
+
+    /// Store for terms built by `construct_from_parts`.
+    fn terms(&self) -> &NodeStorage<Self::Term>;
+
+    /// Every variable occurrence in `term`, left to right.
+    fn term_variables(&self, term: &HashNode<Self::Term>) -> Vec<u32> {
+        if let Some(index) = self.bound_index(term) {
+            return vec![index];
+        }
+        match term.value.decompose() {
+            Some((_, children)) => children.iter().flat_map(|child| self.term_variables(child)).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Rebuild `term` with every variable `/i` replaced by `replace(i)`.
+    ///
+    /// Returns `None` if `replace` does, or if a compound term cannot be
+    /// reconstructed.
+    fn map_variables(
+        &self,
+        term: &HashNode<Self::Term>,
+        replace: &dyn Fn(u32) -> Option<HashNode<Self::Term>>,
+    ) -> Option<HashNode<Self::Term>> {
+        if let Some(index) = self.bound_index(term) {
+            return replace(index);
+        }
+        let Some((opcode, children)) = term.value.decompose() else {
+            return Some(term.clone());
+        };
+        let children = children
+            .iter()
+            .map(|child| self.map_variables(child, replace))
+            .collect::<Option<Vec<_>>>()?;
+        Self::Term::construct_from_parts(opcode, children, self.terms())
+    }
+
+    /// `atom` with `map_variables` applied to each argument; atoms without
+    /// first-order structure are returned unchanged.
+    fn map_atom_variables(
+        &self,
+        atom: &HashNode<D>,
+        replace: &dyn Fn(u32) -> Option<HashNode<Self::Term>>,
+    ) -> Option<HashNode<D>> {
+        let Some((predicate, arguments)) = self.predicate(atom) else {
+            return Some(atom.clone());
+        };
+        let arguments = arguments
+            .iter()
+            .map(|argument| self.map_variables(argument, replace))
+            .collect::<Option<Vec<_>>>()?;
+        self.atom(predicate, arguments)
+    }
+}
+
+/// Opcode of the `index`th Skolem function, as passed to `FirstOrderDomain::skolem`.
+pub fn skolem_opcode(index: u32) -> u64 {
+    Hashing::root_hash(Hashing::opcode("skolem"), &[u64::from(index)])
+}
@@ -0,0 +1,120 @@
+//! Universal instantiation.
+//!
+//! From `∀x φ(x)` and a term `t`, derive `φ(t)`. The bound variable is
+//! replaced by `t` wherever it occurs; under `k` further quantifiers it
+//! appears as `/k`, and there `t` is shifted up by `k` so its free variables
+//! still point past those quantifiers rather than being captured by them.
+//! Variables free in the body that pointed past the removed `∀` move down by
+//! one.
+
+use crate::first_order::FirstOrderDomain;
+use crate::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner, NodeStorage};
+use corpus_core::truth::TruthValue;
+
+/// Instantiate the outermost `∀` of `expr` with `term`.
+///
+/// Returns `None` if `expr` is not a universal quantification or `domain`
+/// cannot rebuild an atom.
+pub fn instantiate<T, D, F>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    term: &HashNode<F::Term>,
+    domain: &F,
+    store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+) -> Option<HashNode<ClassicalLogicalExpression<T, D>>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    let LogicalExpression::Compound { operator: ClassicalOperator::Forall, operands, .. } = expr.value.as_ref() else {
+        return None;
+    };
+    let [body] = operands.as_slice() else {
+        return None;
+    };
+    substitute(body, term, 0, domain, store)
+}
+
+/// `expr` with `/depth` replaced by `term` shifted past `depth` binders.
+fn substitute<T, D, F>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    term: &HashNode<F::Term>,
+    depth: u32,
+    domain: &F,
+    store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+) -> Option<HashNode<ClassicalLogicalExpression<T, D>>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    match expr.value.as_ref() {
+        LogicalExpression::Atomic(atom) => {
+            let atom = domain.map_atom_variables(atom, &|index| match index.cmp(&depth) {
+                std::cmp::Ordering::Less => Some(domain.variable(index)),
+                std::cmp::Ordering::Equal => shift(term, depth, domain),
+                std::cmp::Ordering::Greater => Some(domain.variable(index - 1)),
+            })?;
+            Some(HashNode::from_store(ClassicalLogicalExpression::atomic(atom), store))
+        }
+        LogicalExpression::Compound { operator, operands, .. } => {
+            let depth = match operator {
+                ClassicalOperator::Forall | ClassicalOperator::Exists => depth + 1,
+                _ => depth,
+            };
+            let operands = operands
+                .iter()
+                .map(|operand| substitute(operand, term, depth, domain, store))
+                .collect::<Option<Vec<_>>>()?;
+            Some(HashNode::from_store(ClassicalLogicalExpression::compound(*operator, operands), store))
+        }
+    }
+}
+
+/// `term` with every variable raised by `amount`.
+fn shift<T, D, F>(term: &HashNode<F::Term>, amount: u32, domain: &F) -> Option<HashNode<F::Term>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    if amount == 0 {
+        return Some(term.clone());
+    }
+    domain.map_variables(term, &|index| Some(domain.variable(index + amount)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FoDomain, Formulas};
+
+    #[test]
+    fn test_instantiates_outermost_universal() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let p = |a, b| f.pred(0, vec![a, b]);
+        let c = f.constant(0);
+
+        // ∀x (P(x, /1)) with c  ~>  P(c, /0)
+        let expr = f.forall(&p(f.var(0), f.var(1)));
+        assert_eq!(instantiate(&expr, &c, &domain, &f.store), Some(p(c.clone(), f.var(0))));
+        assert_eq!(instantiate(&p(c.clone(), c.clone()), &c, &domain, &f.store), None);
+    }
+
+    #[test]
+    fn test_avoids_capture_under_inner_binders() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let p = |a, b| f.pred(0, vec![a, b]);
+
+        // ∀x ∃y P(x, y) with the free variable /0: under ∃ it must become /1,
+        // not /0, which ∃ would capture.
+        let expr = f.forall(&f.exists(&p(f.var(1), f.var(0))));
+        let g = f.app(0, vec![f.var(0)]);
+        let expected = f.exists(&p(f.app(0, vec![f.var(1)]), f.var(0)));
+        assert_eq!(instantiate(&expr, &g, &domain, &f.store), Some(expected));
+    }
+}
@@ -0,0 +1,469 @@
+//! Intuitionistic propositional logic.
+//!
+//! `IntuitionisticOperator` has the same connectives as `ClassicalOperator`,
+//! but a formula over it is only valid if it has a constructive proof:
+//! excluded middle `A ∨ ¬A`, double-negation elimination `¬¬A -> A` and
+//! Peirce's law are not theorems. `¬A` means `A -> ⊥` and `A <-> B` means
+//! `(A -> B) ∧ (B -> A)`.
+//!
+//! `prove_intuitionistic` decides validity with Dyckhoff's contraction-free
+//! calculus G4ip, a variant of LJ whose sequents have at most one succedent.
+//! Its left implication rules are split by the shape of the antecedent so
+//! that every premise is smaller than its conclusion, and search terminates
+//! without loop checking. Quantified formulas and equalities are atoms.
+
+use crate::axioms::{create_equality_rule, create_implication_rule};
+use crate::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::axioms::{AxiomConverter, AxiomError, InferenceDirection, InferenceDirectional};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::logic::LogicalOperatorSet;
+use corpus_core::nodes::{HashNode, HashNodeInner, NodeStorage};
+use corpus_core::rewriting::RewriteRule;
+use corpus_core::truth::TruthValue;
+use std::fmt::Display;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+/// Intuitionistic logical operators.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntuitionisticOperator {
+    Equals,
+    And,
+    Or,
+    Implies,
+    Not,
+    Iff,
+    Forall,
+    Exists,
+}
+
+/// A logical expression built from intuitionistic operators over domain `D`.
+pub type IntuitionisticLogicalExpression<T, D> = LogicalExpression<T, D, IntuitionisticOperator>;
+
+impl Display for IntuitionisticOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl IntuitionisticOperator {
+    pub fn symbol(&self) -> &'static str {
+        ClassicalOperator::from(*self).symbol()
+    }
+
+    pub fn arity(&self) -> usize {
+        ClassicalOperator::from(*self).arity()
+    }
+}
+
+impl From<ClassicalOperator> for IntuitionisticOperator {
+    fn from(operator: ClassicalOperator) -> Self {
+        match operator {
+            ClassicalOperator::Equals => IntuitionisticOperator::Equals,
+            ClassicalOperator::And => IntuitionisticOperator::And,
+            ClassicalOperator::Or => IntuitionisticOperator::Or,
+            ClassicalOperator::Implies => IntuitionisticOperator::Implies,
+            ClassicalOperator::Not => IntuitionisticOperator::Not,
+            ClassicalOperator::Iff => IntuitionisticOperator::Iff,
+            ClassicalOperator::Forall => IntuitionisticOperator::Forall,
+            ClassicalOperator::Exists => IntuitionisticOperator::Exists,
+        }
+    }
+}
+
+impl From<IntuitionisticOperator> for ClassicalOperator {
+    fn from(operator: IntuitionisticOperator) -> Self {
+        match operator {
+            IntuitionisticOperator::Equals => ClassicalOperator::Equals,
+            IntuitionisticOperator::And => ClassicalOperator::And,
+            IntuitionisticOperator::Or => ClassicalOperator::Or,
+            IntuitionisticOperator::Implies => ClassicalOperator::Implies,
+            IntuitionisticOperator::Not => ClassicalOperator::Not,
+            IntuitionisticOperator::Iff => ClassicalOperator::Iff,
+            IntuitionisticOperator::Forall => ClassicalOperator::Forall,
+            IntuitionisticOperator::Exists => ClassicalOperator::Exists,
+        }
+    }
+}
+
+impl<T: TruthValue> corpus_core::logic::LogicalOperator<T> for IntuitionisticOperator {
+    type Symbol = &'static str;
+
+    fn symbol(&self) -> Self::Symbol {
+        self.symbol()
+    }
+
+    fn arity(&self) -> usize {
+        self.arity()
+    }
+
+    fn from_opcode(opcode: u64) -> Option<Self> {
+        Some(match opcode {
+            0 => IntuitionisticOperator::Equals,
+            1 => IntuitionisticOperator::And,
+            2 => IntuitionisticOperator::Or,
+            3 => IntuitionisticOperator::Implies,
+            4 => IntuitionisticOperator::Not,
+            5 => IntuitionisticOperator::Iff,
+            6 => IntuitionisticOperator::Forall,
+            7 => IntuitionisticOperator::Exists,
+            _ => return None,
+        })
+    }
+}
+
+impl HashNodeInner for IntuitionisticOperator {
+    fn hash(&self) -> u64 {
+        match self {
+            IntuitionisticOperator::Equals => 0,
+            IntuitionisticOperator::And => 1,
+            IntuitionisticOperator::Or => 2,
+            IntuitionisticOperator::Implies => 3,
+            IntuitionisticOperator::Not => 4,
+            IntuitionisticOperator::Iff => 5,
+            IntuitionisticOperator::Forall => 6,
+            IntuitionisticOperator::Exists => 7,
+        }
+    }
+
+    fn size(&self) -> u64 {
+        1
+    }
+}
+
+impl InferenceDirectional for IntuitionisticOperator {
+    fn inference_direction(&self) -> InferenceDirection {
+        ClassicalOperator::from(*self).inference_direction()
+    }
+}
+
+#[repr(transparent)]
+pub struct IntuitionisticLogicalSystem<T>(LogicalOperatorSet<T, IntuitionisticOperator>)
+where
+    T: TruthValue;
+
+impl<T: TruthValue> From<LogicalOperatorSet<T, IntuitionisticOperator>> for IntuitionisticLogicalSystem<T> {
+    fn from(set: LogicalOperatorSet<T, IntuitionisticOperator>) -> Self {
+        IntuitionisticLogicalSystem(set)
+    }
+}
+
+impl<T: TruthValue> Deref for IntuitionisticLogicalSystem<T> {
+    type Target = LogicalOperatorSet<T, IntuitionisticOperator>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: TruthValue> DerefMut for IntuitionisticLogicalSystem<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: TruthValue> IntuitionisticLogicalSystem<T> {
+    pub fn with_intuitionistic_operators() -> Self {
+        let mut system = LogicalOperatorSet::new();
+
+        system.add_operator(IntuitionisticOperator::And);
+        system.add_operator(IntuitionisticOperator::Or);
+        system.add_operator(IntuitionisticOperator::Implies);
+        system.add_operator(IntuitionisticOperator::Iff);
+        system.add_operator(IntuitionisticOperator::Not);
+        system.add_operator(IntuitionisticOperator::Forall);
+        system.add_operator(IntuitionisticOperator::Exists);
+
+        system.into()
+    }
+}
+
+/// Converts intuitionistic axioms to rewrite rules.
+///
+/// Implications become forward rules, and biconditionals and equalities
+/// bidirectional ones. Unlike `ClassicalAxiomConverter`, an axiom is only
+/// accepted if `prove_intuitionistic` finds it constructively valid or it is
+/// not a propositional tautology at all (a genuine domain axiom), so classical
+/// principles such as `¬¬A <-> A` cannot sneak in as rewrites.
+pub struct IntuitionisticAxiomConverter;
+
+impl<T, D> AxiomConverter<T, D, IntuitionisticOperator> for IntuitionisticAxiomConverter
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone,
+{
+    fn convert_axiom(
+        &self,
+        expr: &HashNode<IntuitionisticLogicalExpression<T, D>>,
+        name: &str,
+    ) -> Result<Vec<RewriteRule<IntuitionisticLogicalExpression<T, D>>>, AxiomError> {
+        let LogicalExpression::Compound { operator, operands, .. } = expr.value.as_ref() else {
+            return Err(AxiomError::NotAnAxiom);
+        };
+        if !matches!(
+            operator,
+            IntuitionisticOperator::Equals | IntuitionisticOperator::Implies | IntuitionisticOperator::Iff
+        ) {
+            return Err(AxiomError::UnsupportedOperator);
+        }
+        if operands.len() != 2 {
+            return Err(AxiomError::MalformedAxiom { expected: 2, found: operands.len() });
+        }
+        if is_classical_only(expr) {
+            return Err(AxiomError::UnsupportedOperator);
+        }
+        match operator {
+            IntuitionisticOperator::Implies => Ok(vec![create_implication_rule(name, &operands[0], &operands[1])]),
+            _ => Ok(vec![create_equality_rule(name, &operands[0], &operands[1])]),
+        }
+    }
+}
+
+/// Whether `expr` is a classical tautology without an intuitionistic proof.
+fn is_classical_only<T, D>(expr: &HashNode<IntuitionisticLogicalExpression<T, D>>) -> bool
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let formula = Formula::from_expression(expr);
+    formula.is_classical_tautology() && !prove(Vec::new(), formula)
+}
+
+/// Convert a classical formula to the same formula over intuitionistic operators.
+pub fn to_intuitionistic<T, D>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    store: &NodeStorage<IntuitionisticLogicalExpression<T, D>>,
+) -> HashNode<IntuitionisticLogicalExpression<T, D>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let converted = match expr.value.as_ref() {
+        LogicalExpression::Atomic(atom) => IntuitionisticLogicalExpression::atomic(atom.clone()),
+        LogicalExpression::Compound { operator, operands, .. } => IntuitionisticLogicalExpression::compound(
+            IntuitionisticOperator::from(*operator),
+            operands.iter().map(|operand| to_intuitionistic(operand, store)).collect(),
+        ),
+    };
+    HashNode::from_store(converted, store)
+}
+
+/// Whether `goal` follows intuitionistically from `antecedents`.
+pub fn prove_intuitionistic<T, D>(
+    antecedents: &[HashNode<IntuitionisticLogicalExpression<T, D>>],
+    goal: &HashNode<IntuitionisticLogicalExpression<T, D>>,
+) -> bool
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let context = antecedents.iter().map(Formula::from_expression).collect();
+    prove(context, Formula::from_expression(goal))
+}
+
+/// Whether `expr` is intuitionistically valid.
+pub fn is_intuitionistic_tautology<T, D>(expr: &HashNode<IntuitionisticLogicalExpression<T, D>>) -> bool
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    prove_intuitionistic(&[], expr)
+}
+
+/// Propositional formulas over `⊥`, `∧`, `∨` and `->`, with atoms by hash.
+#[derive(Debug, PartialEq)]
+enum Formula {
+    Atom(u64),
+    Bottom,
+    And(Rc<Formula>, Rc<Formula>),
+    Or(Rc<Formula>, Rc<Formula>),
+    Implies(Rc<Formula>, Rc<Formula>),
+}
+
+impl Formula {
+    fn from_expression<T, D>(expr: &HashNode<IntuitionisticLogicalExpression<T, D>>) -> Rc<Self>
+    where
+        T: TruthValue + HashNodeInner,
+        D: DomainContent<T>,
+    {
+        let LogicalExpression::Compound { operator, operands, .. } = expr.value.as_ref() else {
+            return Rc::new(Formula::Atom(expr.hash()));
+        };
+        if operands.len() != operator.arity() {
+            return Rc::new(Formula::Atom(expr.hash()));
+        }
+        let operand = |i: usize| Self::from_expression(&operands[i]);
+        Rc::new(match operator {
+            IntuitionisticOperator::And => Formula::And(operand(0), operand(1)),
+            IntuitionisticOperator::Or => Formula::Or(operand(0), operand(1)),
+            IntuitionisticOperator::Implies => Formula::Implies(operand(0), operand(1)),
+            IntuitionisticOperator::Not => Formula::Implies(operand(0), Rc::new(Formula::Bottom)),
+            IntuitionisticOperator::Iff => {
+                let (a, b) = (operand(0), operand(1));
+                Formula::And(
+                    Rc::new(Formula::Implies(a.clone(), b.clone())),
+                    Rc::new(Formula::Implies(b, a)),
+                )
+            }
+            IntuitionisticOperator::Equals | IntuitionisticOperator::Forall | IntuitionisticOperator::Exists => {
+                Formula::Atom(expr.hash())
+            }
+        })
+    }
+
+    fn atoms(&self, atoms: &mut Vec<u64>) {
+        match self {
+            Formula::Atom(atom) if !atoms.contains(atom) => atoms.push(*atom),
+            Formula::Atom(_) | Formula::Bottom => {}
+            Formula::And(a, b) | Formula::Or(a, b) | Formula::Implies(a, b) => {
+                a.atoms(atoms);
+                b.atoms(atoms);
+            }
+        }
+    }
+
+    fn evaluate(&self, atoms: &[u64], row: u64) -> bool {
+        match self {
+            Formula::Atom(atom) => (row >> atoms.iter().position(|a| a == atom).unwrap_or(0)) & 1 == 1,
+            Formula::Bottom => false,
+            Formula::And(a, b) => a.evaluate(atoms, row) && b.evaluate(atoms, row),
+            Formula::Or(a, b) => a.evaluate(atoms, row) || b.evaluate(atoms, row),
+            Formula::Implies(a, b) => !a.evaluate(atoms, row) || b.evaluate(atoms, row),
+        }
+    }
+
+    /// Whether every row of the truth table is true; `false` above
+    /// `MAX_TABLE_ATOMS` atoms.
+    fn is_classical_tautology(&self) -> bool {
+        let mut atoms = Vec::new();
+        self.atoms(&mut atoms);
+        atoms.len() <= crate::tables::MAX_TABLE_ATOMS && (0..1u64 << atoms.len()).all(|row| self.evaluate(&atoms, row))
+    }
+}
+
+/// G4ip search for `context ⊢ goal`.
+fn prove(context: Vec<Rc<Formula>>, goal: Rc<Formula>) -> bool {
+    if context.contains(&goal) || context.iter().any(|f| **f == Formula::Bottom) {
+        return true;
+    }
+
+    // Invertible left rules.
+    for (i, formula) in context.iter().enumerate() {
+        let rest = || {
+            let mut rest = context.clone();
+            rest.remove(i);
+            rest
+        };
+        let with = |added: &[Rc<Formula>]| {
+            let mut rest = rest();
+            rest.extend(added.iter().cloned());
+            rest
+        };
+        match formula.as_ref() {
+            Formula::And(a, b) => return prove(with(&[a.clone(), b.clone()]), goal),
+            Formula::Or(a, b) => {
+                return prove(with(std::slice::from_ref(a)), goal.clone()) && prove(with(std::slice::from_ref(b)), goal)
+            }
+            Formula::Implies(a, b) => match a.as_ref() {
+                Formula::Atom(_) if context.contains(a) => return prove(with(std::slice::from_ref(b)), goal),
+                Formula::Bottom => return prove(rest(), goal),
+                Formula::And(c, d) => {
+                    let nested = Rc::new(Formula::Implies(d.clone(), b.clone()));
+                    return prove(with(&[Rc::new(Formula::Implies(c.clone(), nested))]), goal);
+                }
+                Formula::Or(c, d) => {
+                    let left = Rc::new(Formula::Implies(c.clone(), b.clone()));
+                    let right = Rc::new(Formula::Implies(d.clone(), b.clone()));
+                    return prove(with(&[left, right]), goal);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    // Invertible right rules.
+    match goal.as_ref() {
+        Formula::And(a, b) => return prove(context.clone(), a.clone()) && prove(context, b.clone()),
+        Formula::Implies(a, b) => {
+            let mut context = context;
+            context.push(a.clone());
+            return prove(context, b.clone());
+        }
+        _ => {}
+    }
+
+    // Non-invertible rules: pick a disjunct, or use a nested implication.
+    if let Formula::Or(a, b) = goal.as_ref() {
+        if prove(context.clone(), a.clone()) || prove(context.clone(), b.clone()) {
+            return true;
+        }
+    }
+    context.iter().enumerate().any(|(i, formula)| {
+        let Formula::Implies(antecedent, c) = formula.as_ref() else {
+            return false;
+        };
+        let Formula::Implies(_, b) = antecedent.as_ref() else {
+            return false;
+        };
+        let mut rest = context.clone();
+        rest.remove(i);
+        let mut left = rest.clone();
+        left.push(Rc::new(Formula::Implies(b.clone(), c.clone())));
+        let mut right = rest;
+        right.push(c.clone());
+        prove(left, antecedent.clone()) && prove(right, goal.clone())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::is_tautology;
+    use crate::testing::Formulas;
+    use corpus_core::base::axioms::AxiomConverter;
+
+    #[test]
+    fn test_constructive_validity() {
+        let f = Formulas::new();
+        let store = NodeStorage::new();
+        let (p, q) = (f.atom(0), f.atom(1));
+
+        let valid = [
+            f.implies(&p, &f.not(&f.not(&p))),
+            f.not(&f.not(&f.or(&p, &f.not(&p)))),
+            f.implies(&f.implies(&p, &q), &f.implies(&f.not(&q), &f.not(&p))),
+            f.iff(&f.not(&f.or(&p, &q)), &f.and(&f.not(&p), &f.not(&q))),
+            f.implies(&f.not(&f.not(&f.not(&p))), &f.not(&p)),
+        ];
+        let classical_only = [
+            f.or(&p, &f.not(&p)),
+            f.implies(&f.not(&f.not(&p)), &p),
+            f.implies(&f.implies(&f.implies(&p, &q), &p), &p),
+            f.implies(&f.not(&f.and(&p, &q)), &f.or(&f.not(&p), &f.not(&q))),
+        ];
+
+        for formula in &valid {
+            assert!(is_intuitionistic_tautology(&to_intuitionistic(formula, &store)), "{}", formula);
+        }
+        for formula in &classical_only {
+            assert!(is_tautology(formula).unwrap());
+            assert!(!is_intuitionistic_tautology(&to_intuitionistic(formula, &store)), "{}", formula);
+        }
+        let from_hypotheses = [to_intuitionistic(&f.or(&p, &q), &store), to_intuitionistic(&f.not(&p), &store)];
+        assert!(prove_intuitionistic(&from_hypotheses, &to_intuitionistic(&q, &store)));
+    }
+
+    #[test]
+    fn test_converter_rejects_classical_axioms() {
+        let f = Formulas::new();
+        let store = NodeStorage::new();
+        let (p, q) = (f.atom(0), f.atom(1));
+
+        let double_negation = to_intuitionistic(&f.iff(&f.not(&f.not(&p)), &p), &store);
+        assert!(IntuitionisticAxiomConverter.convert_axiom(&double_negation, "dne").is_err());
+
+        let domain_axiom = to_intuitionistic(&f.implies(&p, &q), &store);
+        assert_eq!(IntuitionisticAxiomConverter.convert_axiom(&domain_axiom, "p_q").unwrap().len(), 1);
+    }
+}
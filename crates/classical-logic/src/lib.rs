@@ -1,16 +1,64 @@
 pub mod axioms;
+pub mod axiom_goal;
+pub mod bdd;
+pub mod cnf;
+pub mod dimacs;
+pub mod first_order;
+pub mod instantiate;
+pub mod intuitionistic;
+pub mod metrics;
+pub mod modal;
+pub mod models;
 pub mod operators;
+pub mod prenex;
+pub mod resolution;
+pub mod sat;
+pub mod semantics;
+pub mod sequent;
+pub mod simplify;
+pub mod skolem;
+pub mod tables;
+pub mod tptp;
 pub mod truth;
+pub mod variables;
+
+#[cfg(test)]
+pub(crate) mod testing;
 
 use std::ops::{Deref, DerefMut};
 
+use corpus_core::base::expression::LogicalExpression;
 use corpus_core::logic::LogicalOperatorSet;
 use corpus_core::truth::TruthValue;
 
+pub use axiom_goal::AxiomGoalChecker;
 pub use axioms::ClassicalAxiomConverter;
+pub use bdd::{are_equivalent, Bdd, BddNode};
+pub use dimacs::{from_dimacs, to_dimacs, DimacsError};
 pub use corpus_core::base::axioms::{InferenceDirection, InferenceDirectional, NamedAxiom};
+pub use first_order::FirstOrderDomain;
+pub use instantiate::instantiate;
+pub use intuitionistic::{
+    is_intuitionistic_tautology, prove_intuitionistic, to_intuitionistic, IntuitionisticAxiomConverter, IntuitionisticOperator,
+};
+pub use metrics::{FormulaMetrics, Polarity};
+pub use modal::{KripkeFrame, KripkeModel, ModalError, ModalOperator};
+pub use models::{find_countermodel, find_model, Model};
 pub use operators::ClassicalOperator;
-pub use truth::BinaryTruth;
+pub use prenex::{to_prenex, Prenex, Quantifier};
+pub use resolution::{Refutation, ResolutionFailure, ResolutionProver};
+pub use sat::{SatGoalChecker, SatResult};
+pub use semantics::{evaluate, satisfies, Assignment, EvaluationError, Interpretation};
+pub use sequent::{prove_sequent, Sequent, SequentProof, SequentRule};
+pub use simplify::{constant_rules, simplification_system};
+pub use skolem::{skolemize, Skolemizer};
+pub use tables::{is_satisfiable, is_tautology, truth_table, ClassicalTruthChecker, TruthTable};
+pub use tptp::{AnnotatedFormula, TptpSignature};
+pub use truth::{BinaryTruth, KleeneTruth};
+pub use variables::{free_variables, is_closed, max_de_bruijn_index};
+
+/// A logical expression built from classical operators over domain `D`.
+pub type ClassicalLogicalExpression<T, D> = LogicalExpression<T, D, ClassicalOperator>;
 
 #[repr(transparent)]
 pub struct ClassicalLogicalSystem<T>(LogicalOperatorSet<T, ClassicalOperator>)
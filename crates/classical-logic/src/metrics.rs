@@ -0,0 +1,254 @@
+//! Structural measures of formulas.
+//!
+//! `FormulaMetrics` gathers the numbers cost estimators and premise selection
+//! use beyond `size()`: nesting and quantifier depth, quantifier alternations,
+//! distinct atoms, an estimate of the clause count of the naive CNF, and the
+//! polarity of every subformula.
+
+use crate::prenex::Quantifier;
+use crate::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner};
+use corpus_core::truth::TruthValue;
+use std::collections::{HashMap, HashSet};
+
+/// Whether a subformula occurs positively, negatively, or both.
+///
+/// A subformula is negative under an odd number of negations and left-hand
+/// sides of `->`; operands of `<->` and `=` occur in both polarities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Polarity {
+    Positive,
+    Negative,
+    Both,
+}
+
+impl Polarity {
+    fn flip(self) -> Self {
+        match self {
+            Polarity::Positive => Polarity::Negative,
+            Polarity::Negative => Polarity::Positive,
+            Polarity::Both => Polarity::Both,
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        if self == other {
+            self
+        } else {
+            Polarity::Both
+        }
+    }
+}
+
+/// Measures of one formula.
+#[derive(Debug, Clone)]
+pub struct FormulaMetrics {
+    /// Longest path from the root to an atom, counting the root as 1.
+    pub depth: usize,
+    /// Most quantifiers on one path from the root.
+    pub quantifier_depth: usize,
+    /// Most switches between `∀` and `∃` on one path, after accounting for
+    /// polarity: `¬∀x ∃y` alternates zero times, as it is `∃x ¬∃y`.
+    pub alternations: usize,
+    /// Distinct atoms; equalities count as atoms.
+    pub atoms: usize,
+    /// Clauses in the CNF obtained by distribution alone, saturating at `u64::MAX`.
+    pub clause_estimate: u64,
+    polarities: HashMap<u64, Polarity>,
+}
+
+impl FormulaMetrics {
+    pub fn of<T, D>(expr: &HashNode<ClassicalLogicalExpression<T, D>>) -> Self
+    where
+        T: TruthValue + HashNodeInner,
+        D: DomainContent<T>,
+    {
+        let mut atoms = HashSet::new();
+        let mut polarities = HashMap::new();
+        collect(expr, Polarity::Positive, &mut atoms, &mut polarities);
+        Self {
+            depth: depth(expr),
+            quantifier_depth: quantifier_depth(expr),
+            alternations: alternations(expr, true, None),
+            atoms: atoms.len(),
+            clause_estimate: clause_counts(expr).0,
+            polarities,
+        }
+    }
+
+    /// Polarity of `subformula` in the measured formula, or `None` if it does not occur.
+    pub fn polarity<E: HashNodeInner>(&self, subformula: &HashNode<E>) -> Option<Polarity> {
+        self.polarities.get(&subformula.hash()).copied()
+    }
+}
+
+fn is_atom<T, D>(expr: &HashNode<ClassicalLogicalExpression<T, D>>) -> bool
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    !matches!(expr.value.as_ref(), LogicalExpression::Compound { operator, .. } if *operator != ClassicalOperator::Equals)
+}
+
+/// Polarity each operand of `operator` has, relative to the compound's.
+fn operand_polarity(operator: ClassicalOperator, index: usize, polarity: Polarity) -> Polarity {
+    match operator {
+        ClassicalOperator::Not => polarity.flip(),
+        ClassicalOperator::Implies if index == 0 => polarity.flip(),
+        ClassicalOperator::Iff | ClassicalOperator::Equals => Polarity::Both,
+        _ => polarity,
+    }
+}
+
+fn collect<T, D>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    polarity: Polarity,
+    atoms: &mut HashSet<u64>,
+    polarities: &mut HashMap<u64, Polarity>,
+) where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    polarities
+        .entry(expr.hash())
+        .and_modify(|seen| *seen = seen.merge(polarity))
+        .or_insert(polarity);
+    if is_atom(expr) {
+        atoms.insert(expr.hash());
+    }
+    if let LogicalExpression::Compound { operator, operands, .. } = expr.value.as_ref() {
+        for (i, operand) in operands.iter().enumerate() {
+            collect(operand, operand_polarity(*operator, i, polarity), atoms, polarities);
+        }
+    }
+}
+
+fn depth<T, D>(expr: &HashNode<ClassicalLogicalExpression<T, D>>) -> usize
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    match expr.value.as_ref() {
+        LogicalExpression::Atomic(_) => 1,
+        LogicalExpression::Compound { operands, .. } => 1 + operands.iter().map(depth).max().unwrap_or(0),
+    }
+}
+
+fn quantifier_depth<T, D>(expr: &HashNode<ClassicalLogicalExpression<T, D>>) -> usize
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    match expr.value.as_ref() {
+        LogicalExpression::Atomic(_) => 0,
+        LogicalExpression::Compound { operator, operands, .. } => {
+            let own = usize::from(matches!(operator, ClassicalOperator::Forall | ClassicalOperator::Exists));
+            own + operands.iter().map(quantifier_depth).max().unwrap_or(0)
+        }
+    }
+}
+
+/// Alternations below `expr` in `positive` position, given the effective
+/// kind of the nearest enclosing quantifier.
+fn alternations<T, D>(expr: &HashNode<ClassicalLogicalExpression<T, D>>, positive: bool, last: Option<Quantifier>) -> usize
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let LogicalExpression::Compound { operator, operands, .. } = expr.value.as_ref() else {
+        return 0;
+    };
+    match operator {
+        ClassicalOperator::Forall | ClassicalOperator::Exists => {
+            let written = if *operator == ClassicalOperator::Forall { Quantifier::Forall } else { Quantifier::Exists };
+            let effective = if positive { written } else { written.dual() };
+            let own = usize::from(last.is_some_and(|last| last != effective));
+            own + operands.iter().map(|operand| alternations(operand, positive, Some(effective))).max().unwrap_or(0)
+        }
+        _ => operands
+            .iter()
+            .enumerate()
+            .map(|(i, operand)| match operand_polarity(*operator, i, Polarity::Positive) {
+                Polarity::Positive => alternations(operand, positive, last),
+                Polarity::Negative => alternations(operand, !positive, last),
+                Polarity::Both => alternations(operand, true, last).max(alternations(operand, false, last)),
+            })
+            .max()
+            .unwrap_or(0),
+    }
+}
+
+/// Clauses in the distributed CNF of `expr` and of its negation.
+fn clause_counts<T, D>(expr: &HashNode<ClassicalLogicalExpression<T, D>>) -> (u64, u64)
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let LogicalExpression::Compound { operator, operands, .. } = expr.value.as_ref() else {
+        return (1, 1);
+    };
+    if *operator == ClassicalOperator::Equals || operands.len() != operator.arity() {
+        return (1, 1);
+    }
+    let (pa, na) = clause_counts(&operands[0]);
+    if operands.len() == 1 {
+        return match operator {
+            ClassicalOperator::Not => (na, pa),
+            _ => (pa, na),
+        };
+    }
+    let (pb, nb) = clause_counts(&operands[1]);
+    match operator {
+        ClassicalOperator::And => (pa.saturating_add(pb), na.saturating_mul(nb)),
+        ClassicalOperator::Or => (pa.saturating_mul(pb), na.saturating_add(nb)),
+        ClassicalOperator::Implies => (na.saturating_mul(pb), pa.saturating_add(nb)),
+        _ => (
+            na.saturating_mul(pb).saturating_add(nb.saturating_mul(pa)),
+            pa.saturating_mul(pb).saturating_add(na.saturating_mul(nb)),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cnf::to_cnf;
+    use crate::testing::Formulas;
+
+    #[test]
+    fn test_propositional_metrics() {
+        let f = Formulas::new();
+        let (p, q, r) = (f.atom(0), f.atom(1), f.atom(2));
+
+        // (p ∧ q) ∨ (p ∧ r) distributes to four clauses over three atoms.
+        let expr = f.or(&f.and(&p, &q), &f.and(&p, &r));
+        let metrics = FormulaMetrics::of(&expr);
+        assert_eq!(metrics.atoms, 3);
+        assert_eq!(metrics.depth, 3);
+        assert_eq!(metrics.clause_estimate, 4);
+        assert_eq!(to_cnf(&expr).clauses.len() as u64, metrics.clause_estimate);
+
+        let expr = f.implies(&f.not(&p), &f.iff(&q, &r));
+        let metrics = FormulaMetrics::of(&expr);
+        assert_eq!(metrics.polarity(&p), Some(Polarity::Positive));
+        assert_eq!(metrics.polarity(&f.not(&p)), Some(Polarity::Negative));
+        assert_eq!(metrics.polarity(&q), Some(Polarity::Both));
+        assert_eq!(metrics.polarity(&f.atom(3)), None);
+    }
+
+    #[test]
+    fn test_quantifier_alternations_follow_polarity() {
+        let f = Formulas::first_order();
+        let p = f.pred(0, vec![f.var(0), f.var(1)]);
+
+        let forall_exists = f.forall(&f.exists(&p));
+        let metrics = FormulaMetrics::of(&forall_exists);
+        assert_eq!((metrics.quantifier_depth, metrics.alternations), (2, 1));
+
+        // ¬∀∃ is ∃∀, still one alternation; ∀¬∃ is ∀∀.
+        assert_eq!(FormulaMetrics::of(&f.not(&forall_exists)).alternations, 1);
+        assert_eq!(FormulaMetrics::of(&f.forall(&f.not(&f.exists(&p)))).alternations, 0);
+        assert_eq!(FormulaMetrics::of(&f.exists(&f.implies(&f.exists(&p), &p))).alternations, 1);
+    }
+}
@@ -0,0 +1,376 @@
+//! Propositional modal logic with Kripke semantics.
+//!
+//! `ModalOperator` adds necessity `□` and possibility `◇` to the classical
+//! connectives. A `KripkeModel` is a finite set of worlds with an
+//! accessibility relation and a valuation of atoms at each world: `□A` holds
+//! at a world when `A` holds at every world it can access, and `◇A` when `A`
+//! holds at some of them. Atoms without a valuation are false everywhere.
+
+use crate::BinaryTruth;
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::logic::LogicalOperatorSet;
+use corpus_core::nodes::{HashNode, HashNodeInner};
+use corpus_core::truth::TruthValue;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::ops::{Deref, DerefMut};
+
+/// Modal logical operators.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModalOperator {
+    And,
+    Or,
+    Implies,
+    Not,
+    Iff,
+    Necessarily,
+    Possibly,
+}
+
+/// A logical expression built from modal operators over domain `D`.
+pub type ModalLogicalExpression<T, D> = LogicalExpression<T, D, ModalOperator>;
+
+impl Display for ModalOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl ModalOperator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ModalOperator::And => "∧",
+            ModalOperator::Or => "∨",
+            ModalOperator::Implies => "->",
+            ModalOperator::Not => "¬",
+            ModalOperator::Iff => "<->",
+            ModalOperator::Necessarily => "□",
+            ModalOperator::Possibly => "◇",
+        }
+    }
+
+    pub fn arity(&self) -> usize {
+        match self {
+            ModalOperator::And => 2,
+            ModalOperator::Or => 2,
+            ModalOperator::Implies => 2,
+            ModalOperator::Iff => 2,
+            ModalOperator::Not => 1,
+            ModalOperator::Necessarily => 1,
+            ModalOperator::Possibly => 1,
+        }
+    }
+}
+
+impl<T: TruthValue> corpus_core::logic::LogicalOperator<T> for ModalOperator {
+    type Symbol = &'static str;
+
+    fn symbol(&self) -> Self::Symbol {
+        self.symbol()
+    }
+
+    fn arity(&self) -> usize {
+        self.arity()
+    }
+
+    fn from_opcode(opcode: u64) -> Option<Self> {
+        Some(match opcode {
+            1 => ModalOperator::And,
+            2 => ModalOperator::Or,
+            3 => ModalOperator::Implies,
+            4 => ModalOperator::Not,
+            5 => ModalOperator::Iff,
+            8 => ModalOperator::Necessarily,
+            9 => ModalOperator::Possibly,
+            _ => return None,
+        })
+    }
+}
+
+impl HashNodeInner for ModalOperator {
+    fn hash(&self) -> u64 {
+        match self {
+            ModalOperator::And => 1,
+            ModalOperator::Or => 2,
+            ModalOperator::Implies => 3,
+            ModalOperator::Not => 4,
+            ModalOperator::Iff => 5,
+            ModalOperator::Necessarily => 8,
+            ModalOperator::Possibly => 9,
+        }
+    }
+
+    fn size(&self) -> u64 {
+        1
+    }
+}
+
+#[repr(transparent)]
+pub struct ModalLogicalSystem<T>(LogicalOperatorSet<T, ModalOperator>)
+where
+    T: TruthValue;
+
+impl<T: TruthValue> From<LogicalOperatorSet<T, ModalOperator>> for ModalLogicalSystem<T> {
+    fn from(set: LogicalOperatorSet<T, ModalOperator>) -> Self {
+        ModalLogicalSystem(set)
+    }
+}
+
+impl<T: TruthValue> Deref for ModalLogicalSystem<T> {
+    type Target = LogicalOperatorSet<T, ModalOperator>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: TruthValue> DerefMut for ModalLogicalSystem<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: TruthValue> ModalLogicalSystem<T> {
+    pub fn with_modal_operators() -> Self {
+        let mut system = LogicalOperatorSet::new();
+
+        system.add_operator(ModalOperator::And);
+        system.add_operator(ModalOperator::Or);
+        system.add_operator(ModalOperator::Implies);
+        system.add_operator(ModalOperator::Iff);
+        system.add_operator(ModalOperator::Not);
+        system.add_operator(ModalOperator::Necessarily);
+        system.add_operator(ModalOperator::Possibly);
+
+        system.into()
+    }
+}
+
+/// Why a formula could not be evaluated in a model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModalError {
+    /// A connective has the wrong number of operands.
+    Malformed(ModalOperator),
+    /// A world index is not below the frame's number of worlds.
+    NoSuchWorld(usize),
+}
+
+impl Display for ModalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModalError::Malformed(operator) => write!(f, "{} applied to the wrong number of operands", operator),
+            ModalError::NoSuchWorld(world) => write!(f, "world {} is not in the frame", world),
+        }
+    }
+}
+
+impl std::error::Error for ModalError {}
+
+/// Worlds `0..worlds` and an accessibility relation between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KripkeFrame {
+    successors: Vec<Vec<usize>>,
+}
+
+impl KripkeFrame {
+    /// A frame of `worlds` worlds with no accessibility edges.
+    pub fn new(worlds: usize) -> Self {
+        Self {
+            successors: vec![Vec::new(); worlds],
+        }
+    }
+
+    pub fn worlds(&self) -> usize {
+        self.successors.len()
+    }
+
+    /// Make `to` accessible from `from`.
+    pub fn add_edge(&mut self, from: usize, to: usize) -> Result<(), ModalError> {
+        for world in [from, to] {
+            if world >= self.worlds() {
+                return Err(ModalError::NoSuchWorld(world));
+            }
+        }
+        if !self.successors[from].contains(&to) {
+            self.successors[from].push(to);
+        }
+        Ok(())
+    }
+
+    /// Whether `to` is accessible from `from`.
+    pub fn accesses(&self, from: usize, to: usize) -> bool {
+        self.successors.get(from).is_some_and(|successors| successors.contains(&to))
+    }
+
+    /// The worlds accessible from `world`.
+    pub fn successors(&self, world: usize) -> &[usize] {
+        &self.successors[world]
+    }
+
+    /// Every world accesses itself (frames of logic T).
+    pub fn is_reflexive(&self) -> bool {
+        (0..self.worlds()).all(|w| self.accesses(w, w))
+    }
+
+    /// Accessibility is symmetric (frames of logic B with reflexivity).
+    pub fn is_symmetric(&self) -> bool {
+        (0..self.worlds()).all(|w| self.successors[w].iter().all(|&v| self.accesses(v, w)))
+    }
+
+    /// Accessibility is transitive (frames of logic S4 with reflexivity).
+    pub fn is_transitive(&self) -> bool {
+        (0..self.worlds()).all(|w| {
+            self.successors[w]
+                .iter()
+                .all(|&v| self.successors[v].iter().all(|&u| self.accesses(w, u)))
+        })
+    }
+}
+
+/// A Kripke frame together with the worlds at which each atom holds.
+pub struct KripkeModel<D: HashNodeInner> {
+    pub frame: KripkeFrame,
+    /// Atom hash to its truth value at each world.
+    valuation: HashMap<u64, Vec<bool>>,
+    _atoms: std::marker::PhantomData<D>,
+}
+
+impl<D: HashNodeInner> KripkeModel<D> {
+    pub fn new(frame: KripkeFrame) -> Self {
+        Self {
+            frame,
+            valuation: HashMap::new(),
+            _atoms: std::marker::PhantomData,
+        }
+    }
+
+    /// Set whether `atom` holds at `world`.
+    pub fn set(&mut self, atom: &HashNode<D>, world: usize, value: bool) -> Result<(), ModalError> {
+        let worlds = self.frame.worlds();
+        if world >= worlds {
+            return Err(ModalError::NoSuchWorld(world));
+        }
+        self.valuation.entry(atom.hash()).or_insert_with(|| vec![false; worlds])[world] = value;
+        Ok(())
+    }
+
+    /// Whether `atom` holds at `world`.
+    pub fn holds(&self, atom: &HashNode<D>, world: usize) -> bool {
+        self.valuation
+            .get(&atom.hash())
+            .is_some_and(|values| values.get(world).copied().unwrap_or(false))
+    }
+
+    /// The value of `expr` at `world`.
+    pub fn evaluate<T>(&self, expr: &HashNode<ModalLogicalExpression<T, D>>, world: usize) -> Result<BinaryTruth, ModalError>
+    where
+        T: TruthValue + HashNodeInner,
+        D: DomainContent<T>,
+    {
+        if world >= self.frame.worlds() {
+            return Err(ModalError::NoSuchWorld(world));
+        }
+        Ok(BinaryTruth::from(self.truth_set(expr)?[world]))
+    }
+
+    /// The worlds at which `expr` holds.
+    pub fn worlds_satisfying<T>(&self, expr: &HashNode<ModalLogicalExpression<T, D>>) -> Result<Vec<usize>, ModalError>
+    where
+        T: TruthValue + HashNodeInner,
+        D: DomainContent<T>,
+    {
+        let set = self.truth_set(expr)?;
+        Ok((0..set.len()).filter(|&w| set[w]).collect())
+    }
+
+    /// Whether `expr` holds at every world of the model.
+    pub fn is_valid<T>(&self, expr: &HashNode<ModalLogicalExpression<T, D>>) -> Result<bool, ModalError>
+    where
+        T: TruthValue + HashNodeInner,
+        D: DomainContent<T>,
+    {
+        Ok(self.truth_set(expr)?.into_iter().all(|value| value))
+    }
+
+    /// The value of `expr` at each world.
+    fn truth_set<T>(&self, expr: &HashNode<ModalLogicalExpression<T, D>>) -> Result<Vec<bool>, ModalError>
+    where
+        T: TruthValue + HashNodeInner,
+        D: DomainContent<T>,
+    {
+        let worlds = self.frame.worlds();
+        let (operator, operands) = match expr.value.as_ref() {
+            LogicalExpression::Atomic(atom) => return Ok((0..worlds).map(|w| self.holds(atom, w)).collect()),
+            LogicalExpression::Compound { operator, operands, .. } => (*operator, operands),
+        };
+        if operands.len() != operator.arity() {
+            return Err(ModalError::Malformed(operator));
+        }
+        let sets = operands.iter().map(|operand| self.truth_set(operand)).collect::<Result<Vec<_>, _>>()?;
+        let combine = |f: fn(bool, bool) -> bool| (0..worlds).map(|w| f(sets[0][w], sets[1][w])).collect();
+        Ok(match operator {
+            ModalOperator::And => combine(|a, b| a && b),
+            ModalOperator::Or => combine(|a, b| a || b),
+            ModalOperator::Implies => combine(|a, b| !a || b),
+            ModalOperator::Iff => combine(|a, b| a == b),
+            ModalOperator::Not => sets[0].iter().map(|value| !value).collect(),
+            ModalOperator::Necessarily => (0..worlds).map(|w| self.frame.successors(w).iter().all(|&v| sets[0][v])).collect(),
+            ModalOperator::Possibly => (0..worlds).map(|w| self.frame.successors(w).iter().any(|&v| sets[0][v])).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Prop;
+    use corpus_core::nodes::NodeStorage;
+
+    type Modal = ModalLogicalExpression<BinaryTruth, Prop>;
+
+    #[test]
+    fn test_box_and_diamond_follow_accessibility() {
+        let atoms = NodeStorage::new();
+        let store = NodeStorage::<Modal>::new();
+        let p = HashNode::from_store(Prop(0), &atoms);
+        let atom = HashNode::from_store(Modal::atomic(p.clone()), &store);
+        let apply = |operator, operand: &HashNode<Modal>| HashNode::from_store(Modal::compound(operator, vec![operand.clone()]), &store);
+
+        // 0 -> 1, 0 -> 2, p holds at 1 only; world 2 has no successors.
+        let mut frame = KripkeFrame::new(3);
+        frame.add_edge(0, 1).unwrap();
+        frame.add_edge(0, 2).unwrap();
+        let mut model = KripkeModel::new(frame);
+        model.set(&p, 1, true).unwrap();
+
+        let necessarily = apply(ModalOperator::Necessarily, &atom);
+        let possibly = apply(ModalOperator::Possibly, &atom);
+        assert_eq!(model.worlds_satisfying(&necessarily), Ok(vec![1, 2]));
+        assert_eq!(model.worlds_satisfying(&possibly), Ok(vec![0]));
+        assert_eq!(model.evaluate(&apply(ModalOperator::Not, &possibly), 2), Ok(BinaryTruth::True));
+        assert_eq!(model.evaluate(&atom, 3), Err(ModalError::NoSuchWorld(3)));
+    }
+
+    #[test]
+    fn test_axiom_t_holds_on_reflexive_frames() {
+        let atoms = NodeStorage::new();
+        let store = NodeStorage::<Modal>::new();
+        let p = HashNode::from_store(Prop(0), &atoms);
+        let atom = HashNode::from_store(Modal::atomic(p.clone()), &store);
+        let necessarily = HashNode::from_store(Modal::compound(ModalOperator::Necessarily, vec![atom.clone()]), &store);
+        let axiom_t = HashNode::from_store(Modal::compound(ModalOperator::Implies, vec![necessarily, atom]), &store);
+
+        let mut frame = KripkeFrame::new(2);
+        frame.add_edge(0, 1).unwrap();
+        let mut model = KripkeModel::new(frame.clone());
+        model.set(&p, 1, true).unwrap();
+        assert_eq!(model.is_valid(&axiom_t), Ok(false));
+
+        frame.add_edge(0, 0).unwrap();
+        frame.add_edge(1, 1).unwrap();
+        assert!(frame.is_reflexive() && frame.is_transitive() && !frame.is_symmetric());
+        let mut model = KripkeModel::new(frame);
+        model.set(&p, 1, true).unwrap();
+        assert_eq!(model.is_valid(&axiom_t), Ok(true));
+    }
+}
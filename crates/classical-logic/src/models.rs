@@ -0,0 +1,317 @@
+//! Finite model search.
+//!
+//! For each universe size up to a bound, every interpretation of the symbols
+//! in a formula is enumerated and evaluated with `semantics::evaluate` until
+//! one gives the wanted truth value. A satisfying model shows a formula is
+//! consistent; a falsifying one shows it is not valid and says why.
+//!
+//! Symbols are read through a `FirstOrderDomain`: predicate symbols from
+//! atoms, function symbols from terms that decompose, and constants from
+//! terms that neither decompose nor are variables. Atoms without first-order
+//! structure are propositions. The search is exhaustive, so it is only
+//! practical for small signatures and universes.
+
+use crate::first_order::FirstOrderDomain;
+use crate::semantics::{evaluate, Assignment, Interpretation};
+use crate::{BinaryTruth, ClassicalLogicalExpression};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Largest number of interpretations tried for one universe size.
+pub const MAX_INTERPRETATIONS: u64 = 1 << 20;
+
+/// A finite structure over the universe `0..size`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Model {
+    pub size: u32,
+    /// Tuples each predicate symbol holds of.
+    pub predicates: HashMap<u64, HashSet<Vec<u32>>>,
+    /// Value of each function symbol on each tuple of arguments.
+    pub functions: HashMap<u64, HashMap<Vec<u32>, u32>>,
+    /// Value of each constant, by the constant term's hash.
+    pub constants: HashMap<u64, u32>,
+    /// Truth value of each proposition, by the atom's hash.
+    pub propositions: HashMap<u64, bool>,
+}
+
+impl Model {
+    /// Whether `expr` holds in this model; `None` if it has free variables
+    /// or symbols the model does not interpret.
+    pub fn satisfies<D, F>(&self, expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>, domain: &F) -> Option<bool>
+    where
+        D: DomainContent<BinaryTruth>,
+        F: FirstOrderDomain<BinaryTruth, D>,
+    {
+        let structure = Structure { model: self, domain };
+        evaluate(expr, &structure, &Assignment::new()).ok().map(bool::from)
+    }
+}
+
+/// A model of `expr` with at most `max_size` elements, smallest first.
+pub fn find_model<D, F>(expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>, domain: &F, max_size: u32) -> Option<Model>
+where
+    D: DomainContent<BinaryTruth>,
+    F: FirstOrderDomain<BinaryTruth, D>,
+{
+    search(expr, domain, max_size, true)
+}
+
+/// A model with at most `max_size` elements in which `expr` is false.
+pub fn find_countermodel<D, F>(
+    expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+    domain: &F,
+    max_size: u32,
+) -> Option<Model>
+where
+    D: DomainContent<BinaryTruth>,
+    F: FirstOrderDomain<BinaryTruth, D>,
+{
+    search(expr, domain, max_size, false)
+}
+
+/// The symbols of a formula, with arities.
+#[derive(Default)]
+struct Signature {
+    predicates: BTreeMap<u64, usize>,
+    functions: BTreeMap<u64, usize>,
+    constants: BTreeSet<u64>,
+    propositions: BTreeSet<u64>,
+}
+
+impl Signature {
+    fn of<D, F>(expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>, domain: &F) -> Self
+    where
+        D: DomainContent<BinaryTruth>,
+        F: FirstOrderDomain<BinaryTruth, D>,
+    {
+        let mut signature = Signature::default();
+        signature.formula(expr, domain);
+        signature
+    }
+
+    fn formula<D, F>(&mut self, expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>, domain: &F)
+    where
+        D: DomainContent<BinaryTruth>,
+        F: FirstOrderDomain<BinaryTruth, D>,
+    {
+        match expr.value.as_ref() {
+            LogicalExpression::Atomic(atom) => match domain.predicate(atom) {
+                Some((symbol, arguments)) => {
+                    self.predicates.insert(symbol, arguments.len());
+                    for argument in &arguments {
+                        self.term(argument, domain);
+                    }
+                }
+                None => {
+                    self.propositions.insert(atom.hash());
+                }
+            },
+            LogicalExpression::Compound { operands, .. } => {
+                for operand in operands {
+                    self.formula(operand, domain);
+                }
+            }
+        }
+    }
+
+    fn term<D, F>(&mut self, term: &HashNode<F::Term>, domain: &F)
+    where
+        D: DomainContent<BinaryTruth>,
+        F: FirstOrderDomain<BinaryTruth, D>,
+    {
+        if domain.bound_index(term).is_some() {
+            return;
+        }
+        match term.value.decompose() {
+            Some((opcode, children)) => {
+                self.functions.insert(opcode, children.len());
+                for child in &children {
+                    self.term(child, domain);
+                }
+            }
+            None => {
+                self.constants.insert(term.hash());
+            }
+        }
+    }
+
+    /// Number of choices for each independent part of an interpretation
+    /// over `size` elements, in the order `decode` reads them.
+    fn bases(&self, size: u32) -> Option<Vec<u32>> {
+        let mut bases = Vec::new();
+        for &arity in self.predicates.values() {
+            bases.extend(std::iter::repeat_n(2, table_size(size, arity)?));
+        }
+        for &arity in self.functions.values() {
+            bases.extend(std::iter::repeat_n(size, table_size(size, arity)?));
+        }
+        bases.extend(std::iter::repeat_n(size, self.constants.len()));
+        bases.extend(std::iter::repeat_n(2, self.propositions.len()));
+        Some(bases)
+    }
+
+    fn decode(&self, size: u32, digits: &[u32]) -> Model {
+        let mut digits = digits.iter().copied();
+        let mut model = Model { size, ..Model::default() };
+        for (&symbol, &arity) in &self.predicates {
+            let holds = tuples(size, arity).filter(|_| digits.next() == Some(1)).collect();
+            model.predicates.insert(symbol, holds);
+        }
+        for (&symbol, &arity) in &self.functions {
+            let table = tuples(size, arity).map(|tuple| (tuple, digits.next().unwrap_or(0))).collect();
+            model.functions.insert(symbol, table);
+        }
+        for &constant in &self.constants {
+            model.constants.insert(constant, digits.next().unwrap_or(0));
+        }
+        for &proposition in &self.propositions {
+            model.propositions.insert(proposition, digits.next() == Some(1));
+        }
+        model
+    }
+}
+
+fn table_size(size: u32, arity: usize) -> Option<usize> {
+    (size as usize).checked_pow(u32::try_from(arity).ok()?)
+}
+
+/// Every tuple of `arity` elements of `0..size`, in lexicographic order.
+fn tuples(size: u32, arity: usize) -> impl Iterator<Item = Vec<u32>> {
+    let count = table_size(size, arity).unwrap_or(0);
+    (0..count).map(move |mut n| {
+        let mut tuple = vec![0; arity];
+        for slot in tuple.iter_mut().rev() {
+            *slot = (n % size as usize) as u32;
+            n /= size as usize;
+        }
+        tuple
+    })
+}
+
+fn search<D, F>(
+    expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+    domain: &F,
+    max_size: u32,
+    wanted: bool,
+) -> Option<Model>
+where
+    D: DomainContent<BinaryTruth>,
+    F: FirstOrderDomain<BinaryTruth, D>,
+{
+    let signature = Signature::of(expr, domain);
+    for size in 1..=max_size {
+        let Some(bases) = signature.bases(size) else {
+            break;
+        };
+        let count = bases.iter().try_fold(1u64, |count, &base| count.checked_mul(u64::from(base)));
+        if count.is_none_or(|count| count > MAX_INTERPRETATIONS) {
+            break;
+        }
+
+        let mut digits = vec![0; bases.len()];
+        loop {
+            let model = signature.decode(size, &digits);
+            if model.satisfies(expr, domain) == Some(wanted) {
+                return Some(model);
+            }
+            // Advance the odometer; stop once every digit has wrapped.
+            let Some(position) = digits.iter().zip(&bases).rposition(|(&digit, &base)| digit + 1 < base) else {
+                break;
+            };
+            digits[position] += 1;
+            digits[position + 1..].fill(0);
+        }
+    }
+    None
+}
+
+/// A `Model` read through the domain that splits its atoms and terms.
+struct Structure<'a, F> {
+    model: &'a Model,
+    domain: &'a F,
+}
+
+impl<F> Structure<'_, F> {
+    fn term<D>(&self, term: &HashNode<F::Term>, assignment: &Assignment<u32>) -> Option<u32>
+    where
+        D: DomainContent<BinaryTruth>,
+        F: FirstOrderDomain<BinaryTruth, D>,
+    {
+        if let Some(index) = self.domain.bound_index(term) {
+            return assignment.get(index).copied();
+        }
+        match term.value.decompose() {
+            Some((opcode, children)) => {
+                let arguments = children.iter().map(|child| self.term(child, assignment)).collect::<Option<Vec<_>>>()?;
+                self.model.functions.get(&opcode)?.get(&arguments).copied()
+            }
+            None => self.model.constants.get(&term.hash()).copied(),
+        }
+    }
+}
+
+impl<D, F> Interpretation<BinaryTruth, D> for Structure<'_, F>
+where
+    D: DomainContent<BinaryTruth>,
+    F: FirstOrderDomain<BinaryTruth, D>,
+{
+    type Value = u32;
+
+    fn universe(&self) -> Vec<u32> {
+        (0..self.model.size).collect()
+    }
+
+    fn holds(&self, atom: &HashNode<D>, assignment: &Assignment<u32>) -> Option<bool> {
+        let Some((symbol, arguments)) = self.domain.predicate(atom) else {
+            return self.model.propositions.get(&atom.hash()).copied();
+        };
+        let arguments = arguments.iter().map(|argument| self.term(argument, assignment)).collect::<Option<Vec<_>>>()?;
+        Some(self.model.predicates.get(&symbol)?.contains(&arguments))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FoDomain, Formulas};
+
+    #[test]
+    fn test_propositional_models() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let (p, q) = (f.pred(0, vec![]), f.pred(1, vec![]));
+
+        let expr = f.and(&p, &f.not(&q));
+        let model = find_model(&expr, &domain, 1).unwrap();
+        assert_eq!(model.satisfies(&p, &domain), Some(true));
+        assert_eq!(model.satisfies(&q, &domain), Some(false));
+
+        assert!(find_model(&f.and(&p, &f.not(&p)), &domain, 3).is_none());
+        assert!(find_countermodel(&f.or(&p, &f.not(&p)), &domain, 3).is_none());
+    }
+
+    #[test]
+    fn test_first_order_countermodels() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let p = |term| f.pred(0, vec![term]);
+
+        // ∀x P(x) → P(c) is valid.
+        let valid = f.implies(&f.forall(&p(f.var(0))), &p(f.constant(0)));
+        assert!(find_countermodel(&valid, &domain, 3).is_none());
+
+        // ∃x P(x) → ∀x P(x) needs two elements to fail.
+        let invalid = f.implies(&f.exists(&p(f.var(0))), &f.forall(&p(f.var(0))));
+        let model = find_countermodel(&invalid, &domain, 3).unwrap();
+        assert_eq!(model.size, 2);
+        assert_eq!(model.satisfies(&invalid, &domain), Some(false));
+
+        // With P1 reflexive, ∀x ¬P1(f(x), x) forces f to move every element.
+        let no_fixpoint = f.forall(&f.not(&f.pred(1, vec![f.app(0, vec![f.var(0)]), f.var(0)])));
+        let equal = f.forall(&f.pred(1, vec![f.var(0), f.var(0)]));
+        let model = find_model(&f.and(&equal, &no_fixpoint), &domain, 3).unwrap();
+        assert_eq!(model.size, 2);
+        assert_eq!(model.satisfies(&no_fixpoint, &domain), Some(true));
+    }
+}
@@ -58,6 +58,20 @@ impl<T: TruthValue> corpus_core::logic::LogicalOperator<T> for ClassicalOperator
     fn arity(&self) -> usize {
         self.arity()
     }
+
+    fn from_opcode(opcode: u64) -> Option<Self> {
+        Some(match opcode {
+            0 => ClassicalOperator::Equals,
+            1 => ClassicalOperator::And,
+            2 => ClassicalOperator::Or,
+            3 => ClassicalOperator::Implies,
+            4 => ClassicalOperator::Not,
+            5 => ClassicalOperator::Iff,
+            6 => ClassicalOperator::Forall,
+            7 => ClassicalOperator::Exists,
+            _ => return None,
+        })
+    }
 }
 
 impl corpus_core::nodes::HashNodeInner for ClassicalOperator {
@@ -0,0 +1,239 @@
+//! Prenex normal form.
+//!
+//! Every quantifier is pulled to the front, giving a quantifier prefix and a
+//! quantifier-free matrix. Quantifiers keep their left-to-right order, which
+//! respects nesting, and flip under negation and on the left of `->`.
+//! Variables are renumbered so each still refers to its own quantifier in
+//! the new prefix; free variables stay free.
+//!
+//! Biconditionals and formula equalities that contain quantifiers are
+//! expanded into two implications first, as their operands occur in both
+//! polarities.
+
+use crate::first_order::FirstOrderDomain;
+use crate::skolem::contains_quantifier;
+use crate::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner, NodeStorage};
+use corpus_core::truth::TruthValue;
+
+/// A quantifier of a prenex prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantifier {
+    Forall,
+    Exists,
+}
+
+impl Quantifier {
+    pub fn operator(self) -> ClassicalOperator {
+        match self {
+            Quantifier::Forall => ClassicalOperator::Forall,
+            Quantifier::Exists => ClassicalOperator::Exists,
+        }
+    }
+
+    pub(crate) fn dual(self) -> Self {
+        match self {
+            Quantifier::Forall => Quantifier::Exists,
+            Quantifier::Exists => Quantifier::Forall,
+        }
+    }
+}
+
+/// A formula split into its quantifier prefix (outermost first) and matrix.
+pub struct Prenex<E: HashNodeInner> {
+    pub prefix: Vec<Quantifier>,
+    pub matrix: HashNode<E>,
+}
+
+impl<T, D> Prenex<ClassicalLogicalExpression<T, D>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    /// The matrix under its prefix, as a single formula.
+    pub fn to_formula(&self, store: &NodeStorage<ClassicalLogicalExpression<T, D>>) -> HashNode<ClassicalLogicalExpression<T, D>> {
+        self.prefix.iter().rev().fold(self.matrix.clone(), |body, quantifier| {
+            HashNode::from_store(ClassicalLogicalExpression::compound(quantifier.operator(), vec![body]), store)
+        })
+    }
+}
+
+/// Convert `expr` to prenex normal form.
+///
+/// Returns `None` if `domain` cannot rebuild an atom with renumbered variables.
+pub fn to_prenex<T, D, F>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    domain: &F,
+    store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+) -> Option<Prenex<ClassicalLogicalExpression<T, D>>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    let expr = expand_biconditionals(expr, store);
+    let mut puller = Puller {
+        domain,
+        store,
+        total: count_quantifiers(&expr),
+        prefix: Vec::new(),
+    };
+    let matrix = puller.walk(&expr, true, &mut Vec::new())?;
+    Some(Prenex {
+        prefix: puller.prefix,
+        matrix,
+    })
+}
+
+/// Rewrite `A <-> B` and `A = B` to `(A -> B) ∧ (B -> A)` wherever A or B contains a quantifier.
+fn expand_biconditionals<T, D>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+) -> HashNode<ClassicalLogicalExpression<T, D>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let LogicalExpression::Compound { operator, operands, .. } = expr.value.as_ref() else {
+        return expr.clone();
+    };
+    if !contains_quantifier(expr) {
+        return expr.clone();
+    }
+    let build = |operator, operands| HashNode::from_store(ClassicalLogicalExpression::compound(operator, operands), store);
+    let operands: Vec<_> = operands.iter().map(|operand| expand_biconditionals(operand, store)).collect();
+    match operator {
+        ClassicalOperator::Iff | ClassicalOperator::Equals if operands.len() == 2 => {
+            let forward = build(ClassicalOperator::Implies, vec![operands[0].clone(), operands[1].clone()]);
+            let backward = build(ClassicalOperator::Implies, vec![operands[1].clone(), operands[0].clone()]);
+            build(ClassicalOperator::And, vec![forward, backward])
+        }
+        _ => build(*operator, operands),
+    }
+}
+
+/// Number of quantifier occurrences in `expr`, counting shared subterms once per occurrence.
+fn count_quantifiers<T, D>(expr: &HashNode<ClassicalLogicalExpression<T, D>>) -> usize
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    match expr.value.as_ref() {
+        LogicalExpression::Atomic(_) => 0,
+        LogicalExpression::Compound { operator, operands, .. } => {
+            let own = usize::from(matches!(operator, ClassicalOperator::Forall | ClassicalOperator::Exists));
+            own + operands.iter().map(count_quantifiers).sum::<usize>()
+        }
+    }
+}
+
+struct Puller<'a, T, D, F>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    domain: &'a F,
+    store: &'a NodeStorage<ClassicalLogicalExpression<T, D>>,
+    /// Length the prefix will have once every quantifier is pulled out.
+    total: usize,
+    prefix: Vec<Quantifier>,
+}
+
+impl<T, D, F> Puller<'_, T, D, F>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    /// The matrix of `expr`, pushing its quantifiers onto the prefix.
+    /// `scope` holds the prefix positions of the enclosing quantifiers, innermost last.
+    fn walk(
+        &mut self,
+        expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+        positive: bool,
+        scope: &mut Vec<usize>,
+    ) -> Option<HashNode<ClassicalLogicalExpression<T, D>>> {
+        let (operator, operands) = match expr.value.as_ref() {
+            LogicalExpression::Atomic(atom) => {
+                let atom = self.domain.map_atom_variables(atom, &|index| Some(self.domain.variable(self.renumber(index, scope))))?;
+                return Some(HashNode::from_store(ClassicalLogicalExpression::atomic(atom), self.store));
+            }
+            LogicalExpression::Compound { operator, operands, .. } => (*operator, operands),
+        };
+
+        if matches!(operator, ClassicalOperator::Forall | ClassicalOperator::Exists) && operands.len() == 1 {
+            let quantifier = if operator == ClassicalOperator::Forall { Quantifier::Forall } else { Quantifier::Exists };
+            scope.push(self.prefix.len());
+            self.prefix.push(if positive { quantifier } else { quantifier.dual() });
+            let body = self.walk(&operands[0], positive, scope);
+            scope.pop();
+            return body;
+        }
+
+        let operands = operands
+            .iter()
+            .enumerate()
+            .map(|(i, operand)| {
+                let flips = operator == ClassicalOperator::Not || (operator == ClassicalOperator::Implies && i == 0);
+                self.walk(operand, positive != flips, scope)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(HashNode::from_store(ClassicalLogicalExpression::compound(operator, operands), self.store))
+    }
+
+    /// The index variable `/index` has in the matrix, given the quantifiers in `scope`.
+    fn renumber(&self, index: u32, scope: &[usize]) -> u32 {
+        match scope.len().checked_sub(index as usize + 1) {
+            Some(binder) => (self.total - 1 - scope[binder]) as u32,
+            None => index - scope.len() as u32 + self.total as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolution::ResolutionProver;
+    use crate::testing::{FoDomain, Formulas};
+
+    #[test]
+    fn test_pulls_quantifiers_out_of_connectives() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let p = |t| f.pred(0, vec![t]);
+        let q = |t| f.pred(1, vec![t]);
+
+        // (∀x P(x)) -> ∃y Q(y)  ~>  ∃x ∃y (P(x) -> Q(y))
+        let expr = f.implies(&f.forall(&p(f.var(0))), &f.exists(&q(f.var(0))));
+        let prenex = to_prenex(&expr, &domain, &f.store).unwrap();
+        assert_eq!(prenex.prefix, vec![Quantifier::Exists, Quantifier::Exists]);
+        assert_eq!(prenex.matrix, f.implies(&p(f.var(1)), &q(f.var(0))));
+
+        // Free variables move past the new prefix: ∀x P(/1) ∧ Q(/0) ~> ∀x (P(/1) ∧ Q(/1)).
+        let expr = f.and(&f.forall(&p(f.var(1))), &q(f.var(0)));
+        let prenex = to_prenex(&expr, &domain, &f.store).unwrap();
+        assert_eq!(prenex.matrix, f.and(&p(f.var(1)), &q(f.var(1))));
+    }
+
+    #[test]
+    fn test_prenex_form_is_equivalent() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let p = |a, b| f.pred(0, vec![a, b]);
+        let q = |t| f.pred(1, vec![t]);
+
+        let expr = f.iff(
+            &f.forall(&f.exists(&p(f.var(1), f.var(0)))),
+            &f.not(&f.exists(&q(f.var(0)))),
+        );
+        let prenex = to_prenex(&expr, &domain, &f.store).unwrap();
+        assert_eq!(prenex.prefix.len(), 6);
+        assert!(!contains_quantifier(&prenex.matrix));
+
+        let prover = ResolutionProver::new(&domain);
+        let formula = prenex.to_formula(&f.store);
+        assert!(prover.prove(&f.implies(&expr, &formula)).is_ok());
+        assert!(prover.prove(&f.implies(&formula, &expr)).is_ok());
+    }
+}
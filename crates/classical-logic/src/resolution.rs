@@ -0,0 +1,787 @@
+//! First-order resolution.
+//!
+//! An alternative to rewrite search for classical goals: the premises and
+//! the negated goal are clausified (negation normal form, Skolemization,
+//! distribution), and binary resolution and factoring are applied with
+//! most general unifiers until the empty clause appears. Clauses are
+//! processed smallest first, so the search is fair and finds a refutation
+//! of every unsatisfiable clause set given enough room.
+//!
+//! Atoms are taken apart with a `FirstOrderDomain`; atoms without
+//! first-order structure, and equalities between formulas, are propositions.
+
+use crate::first_order::{skolem_opcode, FirstOrderDomain};
+use crate::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner, Hashing, NodeStorage};
+use corpus_core::proving::{ProofCertificate, ProofStep};
+use corpus_core::rewriting::{unify_patterns, Pattern, PatternBindings, RewriteDirection, Substitution};
+use corpus_core::truth::TruthValue;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+/// Clauses kept before `prove` gives up, unless `set_max_clauses` is called.
+pub const DEFAULT_MAX_CLAUSES: usize = 10_000;
+
+/// The predicate of a resolution literal.
+pub enum Predicate<E: HashNodeInner> {
+    /// A predicate symbol from `FirstOrderDomain::predicate`.
+    Symbol(u64),
+    /// A formula treated as a proposition.
+    Opaque(HashNode<E>),
+}
+
+impl<E: HashNodeInner> Predicate<E> {
+    fn key(&self) -> u64 {
+        match self {
+            Predicate::Symbol(symbol) => *symbol,
+            Predicate::Opaque(formula) => formula.hash(),
+        }
+    }
+}
+
+impl<E: HashNodeInner> Clone for Predicate<E> {
+    fn clone(&self) -> Self {
+        match self {
+            Predicate::Symbol(symbol) => Predicate::Symbol(*symbol),
+            Predicate::Opaque(formula) => Predicate::Opaque(formula.clone()),
+        }
+    }
+}
+
+/// A possibly negated predicate applied to argument patterns, whose
+/// variables are implicitly universally quantified over the clause.
+pub struct ResolutionLiteral<E: HashNodeInner, Term: HashNodeInner + Clone> {
+    pub positive: bool,
+    pub predicate: Predicate<E>,
+    pub arguments: Vec<Pattern<Term>>,
+}
+
+impl<E: HashNodeInner, Term: HashNodeInner + Clone> ResolutionLiteral<E, Term> {
+    /// Hash of the atom, ignoring the sign.
+    fn atom_key(&self) -> u64 {
+        let hashes: Vec<u64> = self.arguments.iter().map(|arg| arg.structural_hash()).collect();
+        Hashing::root_hash(self.predicate.key(), &hashes)
+    }
+
+    fn key(&self) -> u64 {
+        Hashing::root_hash(self.atom_key(), &[u64::from(self.positive)])
+    }
+
+    fn map_arguments(&self, f: impl Fn(&Pattern<Term>) -> Pattern<Term>) -> Self {
+        Self {
+            positive: self.positive,
+            predicate: self.predicate.clone(),
+            arguments: self.arguments.iter().map(f).collect(),
+        }
+    }
+
+    fn substitute(&self, bindings: &PatternBindings<Term>) -> Self {
+        self.map_arguments(|arg| arg.substitute(bindings))
+    }
+
+    fn variables(&self) -> impl Iterator<Item = u32> + '_ {
+        self.arguments.iter().flat_map(|arg| arg.vars())
+    }
+
+    /// A most general unifier of the atoms of `self` and `other`, if any.
+    fn unify_atoms(&self, other: &Self) -> Option<PatternBindings<Term>> {
+        if self.predicate.key() != other.predicate.key() || self.arguments.len() != other.arguments.len() {
+            return None;
+        }
+        let mut bindings = PatternBindings::new();
+        for (a, b) in self.arguments.iter().zip(&other.arguments) {
+            unify_patterns(a, b, &mut bindings).ok()?;
+        }
+        Some(bindings)
+    }
+}
+
+impl<E: HashNodeInner, Term: HashNodeInner + Clone> Clone for ResolutionLiteral<E, Term> {
+    fn clone(&self) -> Self {
+        self.map_arguments(Pattern::clone)
+    }
+}
+
+/// A disjunction of literals; the empty clause is false.
+pub type ResolutionClause<E, Term> = Vec<ResolutionLiteral<E, Term>>;
+
+/// How a clause of a refutation was obtained. Indices refer to earlier steps.
+pub enum Inference<E: HashNodeInner, Term: HashNodeInner + Clone> {
+    /// A clause of premise `premise`, or of the negated goal when `premise`
+    /// equals the number of premises.
+    Input { premise: usize },
+    /// Resolution of `left` and `right` on `literal` (as it occurs in
+    /// `left`, under the unifier).
+    Resolution {
+        left: usize,
+        right: usize,
+        literal: ResolutionLiteral<E, Term>,
+    },
+    /// Unification of two literals of `parent`.
+    Factoring { parent: usize },
+}
+
+impl<E: HashNodeInner, Term: HashNodeInner + Clone> Inference<E, Term> {
+    fn parents(&self) -> Vec<usize> {
+        match self {
+            Inference::Input { .. } => Vec::new(),
+            Inference::Resolution { left, right, .. } => vec![*left, *right],
+            Inference::Factoring { parent } => vec![*parent],
+        }
+    }
+
+    fn renumbered(self, map: &HashMap<usize, usize>) -> Self {
+        match self {
+            Inference::Input { premise } => Inference::Input { premise },
+            Inference::Resolution { left, right, literal } => Inference::Resolution {
+                left: map[&left],
+                right: map[&right],
+                literal,
+            },
+            Inference::Factoring { parent } => Inference::Factoring { parent: map[&parent] },
+        }
+    }
+}
+
+/// One clause of a refutation with its justification.
+pub struct RefutationStep<E: HashNodeInner, Term: HashNodeInner + Clone> {
+    pub clause: ResolutionClause<E, Term>,
+    pub inference: Inference<E, Term>,
+}
+
+/// A derivation of the empty clause, containing only the clauses it uses.
+pub struct Refutation<E: HashNodeInner, Term: HashNodeInner + Clone> {
+    /// Steps in derivation order; the last derives the empty clause.
+    pub steps: Vec<RefutationStep<E, Term>>,
+    /// Clauses generated by the search, including unused ones.
+    pub clauses_generated: usize,
+    premises: usize,
+    skolems: HashMap<u64, u32>,
+}
+
+impl<E: HashNodeInner, Term: HashNodeInner + Clone> Refutation<E, Term> {
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl<T, D, Term> Refutation<ClassicalLogicalExpression<T, D>, Term>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    Term: HashNodeInner + Clone,
+{
+    /// Present the refutation as a certificate for `goal`.
+    ///
+    /// Each step rewrites the previous expression to the next clause, as a
+    /// disjunction of literals with its variables free, and is named after
+    /// its inference (`input(premise 0)`, `resolution(2, 5)`, ...). The empty
+    /// clause is shown as the contradiction `L ∧ ¬L` it was resolved from.
+    /// Returns `None` if `domain` cannot rebuild an atom or Skolem term.
+    pub fn certificate<F>(
+        &self,
+        goal: &HashNode<ClassicalLogicalExpression<T, D>>,
+        domain: &F,
+        store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+    ) -> Option<ProofCertificate<ClassicalLogicalExpression<T, D>>>
+    where
+        F: FirstOrderDomain<T, D, Term = Term>,
+    {
+        let mut steps = Vec::new();
+        let mut previous = goal.clone();
+        for step in &self.steps {
+            let formula = match (&step.inference, step.clause.is_empty()) {
+                (Inference::Resolution { literal, .. }, true) => {
+                    let atom = self.literal_formula(&ResolutionLiteral { positive: true, ..literal.clone() }, domain, store)?;
+                    let negated = HashNode::from_store(ClassicalLogicalExpression::compound(ClassicalOperator::Not, vec![atom.clone()]), store);
+                    HashNode::from_store(ClassicalLogicalExpression::compound(ClassicalOperator::And, vec![atom, negated]), store)
+                }
+                _ => self.clause_formula(&step.clause, domain, store)?,
+            };
+            let rule_name = match &step.inference {
+                Inference::Input { premise } if *premise == self.premises => "input(negated goal)".to_string(),
+                Inference::Input { premise } => format!("input(premise {})", premise),
+                Inference::Resolution { left, right, .. } => format!("resolution({}, {})", left, right),
+                Inference::Factoring { parent } => format!("factoring({})", parent),
+            };
+            steps.push(ProofStep {
+                rule_name,
+                direction: RewriteDirection::Forward,
+                position: Vec::new(),
+                substitution: Substitution::new(),
+                old_expr: previous,
+                new_expr: formula.clone(),
+            });
+            previous = formula;
+        }
+        Some(ProofCertificate::new(goal.clone(), steps))
+    }
+
+    fn clause_formula<F>(
+        &self,
+        clause: &ResolutionClause<ClassicalLogicalExpression<T, D>, Term>,
+        domain: &F,
+        store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+    ) -> Option<HashNode<ClassicalLogicalExpression<T, D>>>
+    where
+        F: FirstOrderDomain<T, D, Term = Term>,
+    {
+        let literals = clause
+            .iter()
+            .map(|literal| self.literal_formula(literal, domain, store))
+            .collect::<Option<Vec<_>>>()?;
+        literals.into_iter().rev().reduce(|rest, literal| {
+            HashNode::from_store(ClassicalLogicalExpression::compound(ClassicalOperator::Or, vec![literal, rest]), store)
+        })
+    }
+
+    fn literal_formula<F>(
+        &self,
+        literal: &ResolutionLiteral<ClassicalLogicalExpression<T, D>, Term>,
+        domain: &F,
+        store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+    ) -> Option<HashNode<ClassicalLogicalExpression<T, D>>>
+    where
+        F: FirstOrderDomain<T, D, Term = Term>,
+    {
+        let atom = match &literal.predicate {
+            Predicate::Opaque(formula) => formula.clone(),
+            Predicate::Symbol(symbol) => {
+                let arguments = literal
+                    .arguments
+                    .iter()
+                    .map(|arg| self.term(arg, domain))
+                    .collect::<Option<Vec<_>>>()?;
+                let atom = domain.atom(*symbol, arguments)?;
+                HashNode::from_store(ClassicalLogicalExpression::atomic(atom), store)
+            }
+        };
+        if literal.positive {
+            return Some(atom);
+        }
+        Some(HashNode::from_store(ClassicalLogicalExpression::compound(ClassicalOperator::Not, vec![atom]), store))
+    }
+
+    fn term<F>(&self, pattern: &Pattern<Term>, domain: &F) -> Option<HashNode<Term>>
+    where
+        F: FirstOrderDomain<T, D, Term = Term>,
+    {
+        match pattern {
+            Pattern::Variable(index) => Some(domain.variable(*index)),
+            Pattern::Wildcard => None,
+            Pattern::Constant(c) => Some(HashNode::from_store(c.clone(), domain.terms())),
+            Pattern::Compound { opcode, args } => {
+                let args = args.iter().map(|arg| self.term(arg, domain)).collect::<Option<Vec<_>>>()?;
+                match self.skolems.get(opcode) {
+                    Some(index) => domain.skolem(*index, args),
+                    None => Term::construct_from_parts(*opcode, args, domain.terms()),
+                }
+            }
+        }
+    }
+}
+
+/// Why resolution found no refutation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionFailure {
+    /// Every inference was made without deriving the empty clause: the
+    /// premises and negated goal are satisfiable, so the goal does not follow.
+    Saturated,
+    /// The clause limit was reached.
+    ClauseLimit(usize),
+}
+
+impl Display for ResolutionFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolutionFailure::Saturated => write!(f, "clause set saturated without a refutation"),
+            ResolutionFailure::ClauseLimit(limit) => write!(f, "clause limit of {} reached", limit),
+        }
+    }
+}
+
+impl std::error::Error for ResolutionFailure {}
+
+/// Negation normal form over resolution literals.
+enum Nnf<E: HashNodeInner, Term: HashNodeInner + Clone> {
+    Literal(ResolutionLiteral<E, Term>),
+    And(Vec<Nnf<E, Term>>),
+    Or(Vec<Nnf<E, Term>>),
+}
+
+/// Clausification state: fresh variables and Skolem functions.
+struct Clausifier<'a, F> {
+    domain: &'a F,
+    next_variable: u32,
+    skolems: HashMap<u64, u32>,
+}
+
+impl<'a, F> Clausifier<'a, F> {
+    fn new(domain: &'a F) -> Self {
+        Self {
+            domain,
+            next_variable: 0,
+            skolems: HashMap::new(),
+        }
+    }
+
+    fn clauses<T, D>(
+        &mut self,
+        expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+        positive: bool,
+    ) -> Vec<ResolutionClause<ClassicalLogicalExpression<T, D>, F::Term>>
+    where
+        T: TruthValue + HashNodeInner,
+        D: DomainContent<T>,
+        F: FirstOrderDomain<T, D>,
+    {
+        let nnf = self.nnf(expr, positive, &mut Vec::new(), &mut Vec::new());
+        distribute(&nnf)
+    }
+
+    /// `expr` (negated unless `positive`) in negation normal form, with
+    /// `binders` holding the pattern each enclosing quantifier's variable
+    /// stands for (innermost last) and `universals` the variables in scope.
+    fn nnf<T, D>(
+        &mut self,
+        expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+        positive: bool,
+        binders: &mut Vec<Pattern<F::Term>>,
+        universals: &mut Vec<u32>,
+    ) -> Nnf<ClassicalLogicalExpression<T, D>, F::Term>
+    where
+        T: TruthValue + HashNodeInner,
+        D: DomainContent<T>,
+        F: FirstOrderDomain<T, D>,
+    {
+        let opaque = || {
+            Nnf::Literal(ResolutionLiteral {
+                positive,
+                predicate: Predicate::Opaque(expr.clone()),
+                arguments: Vec::new(),
+            })
+        };
+        let (operator, operands) = match expr.value.as_ref() {
+            LogicalExpression::Atomic(atom) => return self.atom(atom, positive, binders).unwrap_or_else(opaque),
+            LogicalExpression::Compound { operator, operands, .. } if operands.len() == operator.arity() => {
+                (*operator, operands)
+            }
+            LogicalExpression::Compound { .. } => return opaque(),
+        };
+
+        let mut nnf = |index: usize, positive: bool| self.nnf(&operands[index], positive, binders, universals);
+        match (operator, positive) {
+            (ClassicalOperator::Equals, _) => opaque(),
+            (ClassicalOperator::Not, _) => nnf(0, !positive),
+            (ClassicalOperator::And, true) | (ClassicalOperator::Or, false) => Nnf::And(vec![nnf(0, positive), nnf(1, positive)]),
+            (ClassicalOperator::Or, true) | (ClassicalOperator::And, false) => Nnf::Or(vec![nnf(0, positive), nnf(1, positive)]),
+            (ClassicalOperator::Implies, true) => Nnf::Or(vec![nnf(0, false), nnf(1, true)]),
+            (ClassicalOperator::Implies, false) => Nnf::And(vec![nnf(0, true), nnf(1, false)]),
+            (ClassicalOperator::Iff, true) => Nnf::And(vec![
+                Nnf::Or(vec![nnf(0, false), nnf(1, true)]),
+                Nnf::Or(vec![nnf(0, true), nnf(1, false)]),
+            ]),
+            (ClassicalOperator::Iff, false) => Nnf::Or(vec![
+                Nnf::And(vec![nnf(0, true), nnf(1, false)]),
+                Nnf::And(vec![nnf(0, false), nnf(1, true)]),
+            ]),
+            (ClassicalOperator::Forall, true) | (ClassicalOperator::Exists, false) => {
+                let variable = self.next_variable;
+                self.next_variable += 1;
+                binders.push(Pattern::Variable(variable));
+                universals.push(variable);
+                let body = self.nnf(&operands[0], positive, binders, universals);
+                universals.pop();
+                binders.pop();
+                body
+            }
+            (ClassicalOperator::Exists, true) | (ClassicalOperator::Forall, false) => {
+                let index = self.skolems.len() as u32;
+                let opcode = skolem_opcode(index);
+                self.skolems.insert(opcode, index);
+                binders.push(Pattern::compound(opcode, universals.iter().map(|&v| Pattern::Variable(v)).collect()));
+                let body = self.nnf(&operands[0], positive, binders, universals);
+                binders.pop();
+                body
+            }
+        }
+    }
+
+    fn atom<T, D>(
+        &self,
+        atom: &HashNode<D>,
+        positive: bool,
+        binders: &[Pattern<F::Term>],
+    ) -> Option<Nnf<ClassicalLogicalExpression<T, D>, F::Term>>
+    where
+        T: TruthValue + HashNodeInner,
+        D: DomainContent<T>,
+        F: FirstOrderDomain<T, D>,
+    {
+        let (symbol, arguments) = self.domain.predicate(atom)?;
+        // Bound variables become the pattern of their binder; free ones stay as they are.
+        let variable = |term: &HashNode<F::Term>| {
+            let index = self.domain.bound_index(term)? as usize;
+            Some(match binders.len().checked_sub(index + 1) {
+                Some(binder) => binders[binder].clone(),
+                None => Pattern::Constant(term.value.as_ref().clone()),
+            })
+        };
+        Some(Nnf::Literal(ResolutionLiteral {
+            positive,
+            predicate: Predicate::Symbol(symbol),
+            arguments: arguments.iter().map(|arg| Pattern::from_term(arg, &variable)).collect(),
+        }))
+    }
+}
+
+fn distribute<E: HashNodeInner, Term: HashNodeInner + Clone>(nnf: &Nnf<E, Term>) -> Vec<ResolutionClause<E, Term>> {
+    match nnf {
+        Nnf::Literal(literal) => vec![vec![literal.clone()]],
+        Nnf::And(children) => children.iter().flat_map(distribute).collect(),
+        Nnf::Or(children) => children.iter().fold(vec![Vec::new()], |acc, child| {
+            let parts = distribute(child);
+            acc.iter()
+                .flat_map(|left| {
+                    parts.iter().map(move |right| left.iter().chain(right).cloned().collect::<Vec<_>>())
+                })
+                .collect()
+        }),
+    }
+}
+
+/// Rename the variables of `clause` to `0..` in order of appearance, sort
+/// and deduplicate its literals; `None` if it is a tautology.
+fn normalize<E: HashNodeInner, Term: HashNodeInner + Clone>(
+    clause: ResolutionClause<E, Term>,
+) -> Option<ResolutionClause<E, Term>> {
+    let mut clause = clause;
+    clause.sort_by_key(|literal| literal.key());
+    let mut renaming = PatternBindings::new();
+    for variable in clause.iter().flat_map(|literal| literal.variables().collect::<Vec<_>>()) {
+        let next = renaming.len() as u32;
+        renaming.entry(variable).or_insert(Pattern::Variable(next));
+    }
+    let mut clause: ResolutionClause<E, Term> = clause.iter().map(|literal| rename(literal, &renaming)).collect();
+    clause.sort_by_key(|literal| literal.key());
+    clause.dedup_by_key(|literal| literal.key());
+
+    let atoms: HashMap<u64, bool> = clause.iter().map(|literal| (literal.atom_key(), literal.positive)).collect();
+    let complementary = clause.iter().any(|literal| atoms.get(&literal.atom_key()) != Some(&literal.positive));
+    (!complementary).then_some(clause)
+}
+
+/// Apply a variable renaming in one pass; unlike `substitute`, the targets
+/// are not themselves looked up.
+fn rename<E: HashNodeInner, Term: HashNodeInner + Clone>(
+    literal: &ResolutionLiteral<E, Term>,
+    renaming: &PatternBindings<Term>,
+) -> ResolutionLiteral<E, Term> {
+    fn go<Term: HashNodeInner + Clone>(pattern: &Pattern<Term>, renaming: &PatternBindings<Term>) -> Pattern<Term> {
+        match pattern {
+            Pattern::Variable(index) => renaming.get(index).cloned().unwrap_or(Pattern::Variable(*index)),
+            Pattern::Compound { opcode, args } => {
+                Pattern::compound(*opcode, args.iter().map(|arg| go(arg, renaming)).collect())
+            }
+            other => other.clone(),
+        }
+    }
+    literal.map_arguments(|arg| go(arg, renaming))
+}
+
+fn weight<E: HashNodeInner, Term: HashNodeInner + Clone>(clause: &ResolutionClause<E, Term>) -> usize {
+    clause
+        .iter()
+        .map(|literal| 1 + literal.arguments.iter().map(|arg| arg.size()).sum::<usize>())
+        .sum()
+}
+
+/// A resolution theorem prover over premises in a first-order domain.
+pub struct ResolutionProver<'a, T, D, F>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    domain: &'a F,
+    premises: Vec<HashNode<ClassicalLogicalExpression<T, D>>>,
+    max_clauses: usize,
+}
+
+impl<'a, T, D, F> ResolutionProver<'a, T, D, F>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    pub fn new(domain: &'a F) -> Self {
+        Self {
+            domain,
+            premises: Vec::new(),
+            max_clauses: DEFAULT_MAX_CLAUSES,
+        }
+    }
+
+    /// Assume `premise` in every proof.
+    pub fn add_premise(&mut self, premise: HashNode<ClassicalLogicalExpression<T, D>>) {
+        self.premises.push(premise);
+    }
+
+    pub fn set_max_clauses(&mut self, max_clauses: usize) {
+        self.max_clauses = max_clauses;
+    }
+
+    /// Refute the premises together with the negation of `goal`.
+    pub fn prove(
+        &self,
+        goal: &HashNode<ClassicalLogicalExpression<T, D>>,
+    ) -> Result<Refutation<ClassicalLogicalExpression<T, D>, F::Term>, ResolutionFailure> {
+        let mut clausifier = Clausifier::new(self.domain);
+        let mut search = Search::new(self.premises.len());
+        let inputs = self
+            .premises
+            .iter()
+            .map(|premise| (premise, true))
+            .chain(std::iter::once((goal, false)));
+        for (premise, (expr, positive)) in inputs.enumerate() {
+            for clause in clausifier.clauses(expr, positive) {
+                if let Some(empty) = search.add(clause, Inference::Input { premise }) {
+                    return Ok(search.refutation(empty, clausifier.skolems));
+                }
+            }
+        }
+
+        while let Some(Reverse((_, given))) = search.queue.pop() {
+            if search.clauses.len() > self.max_clauses {
+                return Err(ResolutionFailure::ClauseLimit(self.max_clauses));
+            }
+            search.processed.push(given);
+            if let Some(empty) = search.infer(given) {
+                return Ok(search.refutation(empty, clausifier.skolems));
+            }
+        }
+        Err(ResolutionFailure::Saturated)
+    }
+}
+
+/// The given-clause loop: every clause ever kept, the processed ones, and
+/// the unprocessed ones ordered by weight.
+struct Search<E: HashNodeInner, Term: HashNodeInner + Clone> {
+    clauses: Vec<RefutationStep<E, Term>>,
+    seen: HashSet<Vec<u64>>,
+    processed: Vec<usize>,
+    queue: BinaryHeap<Reverse<(usize, usize)>>,
+    premises: usize,
+}
+
+impl<E: HashNodeInner, Term: HashNodeInner + Clone> Search<E, Term> {
+    fn new(premises: usize) -> Self {
+        Self {
+            clauses: Vec::new(),
+            seen: HashSet::new(),
+            processed: Vec::new(),
+            queue: BinaryHeap::new(),
+            premises,
+        }
+    }
+
+    /// Keep `clause` unless it is a tautology or already known; returns
+    /// its index if it is the empty clause.
+    fn add(&mut self, clause: ResolutionClause<E, Term>, inference: Inference<E, Term>) -> Option<usize> {
+        let clause = normalize(clause)?;
+        if !self.seen.insert(clause.iter().map(|literal| literal.key()).collect()) {
+            return None;
+        }
+        let index = self.clauses.len();
+        let empty = clause.is_empty();
+        self.queue.push(Reverse((weight(&clause), index)));
+        self.clauses.push(RefutationStep { clause, inference });
+        empty.then_some(index)
+    }
+
+    /// Add every factor of `given` and every resolvent of `given` with a processed clause.
+    fn infer(&mut self, given: usize) -> Option<usize> {
+        let clause = self.clauses[given].clause.clone();
+        for i in 0..clause.len() {
+            for j in i + 1..clause.len() {
+                if clause[i].positive != clause[j].positive {
+                    continue;
+                }
+                if let Some(bindings) = clause[i].unify_atoms(&clause[j]) {
+                    let factor = clause
+                        .iter()
+                        .enumerate()
+                        .filter(|&(k, _)| k != j)
+                        .map(|(_, literal)| literal.substitute(&bindings))
+                        .collect();
+                    if let Some(empty) = self.add(factor, Inference::Factoring { parent: given }) {
+                        return Some(empty);
+                    }
+                }
+            }
+        }
+
+        for other in self.processed.clone() {
+            // Rename the other clause apart from the given one.
+            let offset = clause.iter().flat_map(|literal| literal.variables()).max().map_or(0, |v| v + 1);
+            let renaming: PatternBindings<Term> = self.clauses[other]
+                .clause
+                .iter()
+                .flat_map(|literal| literal.variables().collect::<Vec<_>>())
+                .map(|v| (v, Pattern::Variable(v + offset)))
+                .collect();
+            let partner: Vec<_> = self.clauses[other].clause.iter().map(|literal| rename(literal, &renaming)).collect();
+
+            for (i, left) in clause.iter().enumerate() {
+                for (j, right) in partner.iter().enumerate() {
+                    if left.positive == right.positive {
+                        continue;
+                    }
+                    let Some(bindings) = left.unify_atoms(right) else {
+                        continue;
+                    };
+                    let resolvent = clause
+                        .iter()
+                        .enumerate()
+                        .filter(|&(k, _)| k != i)
+                        .map(|(_, literal)| literal.substitute(&bindings))
+                        .chain(
+                            partner
+                                .iter()
+                                .enumerate()
+                                .filter(|&(k, _)| k != j)
+                                .map(|(_, literal)| literal.substitute(&bindings)),
+                        )
+                        .collect();
+                    let inference = Inference::Resolution {
+                        left: given,
+                        right: other,
+                        literal: left.substitute(&bindings),
+                    };
+                    if let Some(empty) = self.add(resolvent, inference) {
+                        return Some(empty);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// The steps the empty clause at `empty` depends on, renumbered.
+    fn refutation(self, empty: usize, skolems: HashMap<u64, u32>) -> Refutation<E, Term> {
+        let mut needed = HashSet::new();
+        let mut pending = vec![empty];
+        while let Some(index) = pending.pop() {
+            if needed.insert(index) {
+                pending.extend(self.clauses[index].inference.parents());
+            }
+        }
+        let mut needed: Vec<usize> = needed.into_iter().collect();
+        needed.sort_unstable();
+        let map: HashMap<usize, usize> = needed.iter().enumerate().map(|(new, &old)| (old, new)).collect();
+
+        let clauses_generated = self.clauses.len();
+        let steps = self
+            .clauses
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| map.contains_key(index))
+            .map(|(_, step)| RefutationStep {
+                clause: step.clause,
+                inference: step.inference.renumbered(&map),
+            })
+            .collect();
+        Refutation {
+            steps,
+            clauses_generated,
+            premises: self.premises,
+            skolems,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FoDomain, Formulas};
+
+    #[test]
+    fn test_syllogism() {
+        // ∀x (P0(x) -> P1(x)), P0(c0) ⊢ P1(c0)
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let x = f.var(0);
+        let c = f.constant(0);
+
+        let mut prover = ResolutionProver::new(&domain);
+        prover.add_premise(f.forall(&f.implies(&f.pred(0, vec![x.clone()]), &f.pred(1, vec![x]))));
+        prover.add_premise(f.pred(0, vec![c.clone()]));
+        let goal = f.pred(1, vec![c]);
+
+        let refutation = prover.prove(&goal).expect("the goal follows");
+        assert!(refutation.steps.last().unwrap().clause.is_empty());
+
+        let certificate = refutation.certificate(&goal, &domain, &f.store).unwrap();
+        assert!(certificate.is_connected());
+        assert_eq!(certificate.len(), refutation.len());
+        assert!(certificate.rule_names().contains(&"input(negated goal)"));
+    }
+
+    #[test]
+    fn test_quantifier_validities() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let prover = ResolutionProver::new(&domain);
+        let p = |args| f.pred(0, args);
+
+        // ∃y ∀x P(x, y) -> ∀x ∃y P(x, y)
+        let inner = f.exists(&f.forall(&p(vec![f.var(0), f.var(1)])));
+        let outer = f.forall(&f.exists(&p(vec![f.var(1), f.var(0)])));
+        assert!(prover.prove(&f.implies(&inner, &outer)).is_ok());
+
+        // The converse is not valid; its clause set saturates.
+        assert_eq!(
+            prover.prove(&f.implies(&outer, &inner)).err(),
+            Some(ResolutionFailure::Saturated)
+        );
+
+        // Drinker paradox: ∃x (P(x) -> ∀y P(y)).
+        let drinker = f.exists(&f.implies(&p(vec![f.var(0)]), &f.forall(&p(vec![f.var(0)]))));
+        assert!(prover.prove(&drinker).is_ok());
+    }
+
+    #[test]
+    fn test_factoring_and_limits() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let p = |t| f.pred(0, vec![t]);
+        let (x, y) = (f.var(1), f.var(0));
+
+        // ∀x ∀y (P(x) ∨ P(y)) and ∀x ∀y (¬P(x) ∨ ¬P(y)) refute only with factoring.
+        let mut prover = ResolutionProver::new(&domain);
+        prover.add_premise(f.forall(&f.forall(&f.or(&p(x.clone()), &p(y.clone())))));
+        prover.add_premise(f.forall(&f.forall(&f.or(&f.not(&p(x)), &f.not(&p(y))))));
+        let contradiction = f.and(&p(f.constant(0)), &f.not(&p(f.constant(0))));
+        let refutation = prover.prove(&contradiction).expect("the premises are inconsistent");
+        assert!(refutation
+            .steps
+            .iter()
+            .any(|step| matches!(step.inference, Inference::Factoring { .. })));
+
+        // P(c0) ∧ ∀x (P(x) -> P(f0(x))) never saturates when asked for an unrelated goal.
+        let mut prover = ResolutionProver::new(&domain);
+        prover.add_premise(p(f.constant(0)));
+        prover.add_premise(f.forall(&f.implies(&p(f.var(0)), &p(f.app(0, vec![f.var(0)])))));
+        prover.set_max_clauses(50);
+        assert_eq!(
+            prover.prove(&f.pred(1, vec![f.constant(0)])).err(),
+            Some(ResolutionFailure::ClauseLimit(50))
+        );
+    }
+}
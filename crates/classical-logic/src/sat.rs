@@ -0,0 +1,360 @@
+//! A CDCL SAT solver for `ClauseSet`s.
+//!
+//! The solver is deliberately small: unit propagation scans the clause list,
+//! conflicts are analyzed to the first unique implication point, and the
+//! learned clause drives a non-chronological backjump. It is meant for the
+//! clause sets produced from goals, not for industrial instances.
+
+use crate::cnf::{to_cnf_tseitin, to_cnf_tseitin_negated, Clause, ClauseSet, Literal};
+use crate::{BinaryTruth, ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::DomainContent;
+use corpus_core::nodes::{HashNode, HashNodeInner};
+use corpus_core::proving::GoalChecker;
+
+/// Outcome of solving a clause set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SatResult {
+    /// A satisfying assignment, indexed by variable.
+    Satisfiable(Vec<bool>),
+    Unsatisfiable,
+}
+
+impl SatResult {
+    pub fn is_satisfiable(&self) -> bool {
+        matches!(self, SatResult::Satisfiable(_))
+    }
+
+    pub fn model(&self) -> Option<&[bool]> {
+        match self {
+            SatResult::Satisfiable(model) => Some(model),
+            SatResult::Unsatisfiable => None,
+        }
+    }
+}
+
+/// Counters describing a solver run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SolverStats {
+    pub decisions: usize,
+    pub propagations: usize,
+    pub conflicts: usize,
+    pub learned: usize,
+}
+
+/// Conflict-driven clause-learning solver over variables `0..num_variables`.
+pub struct Solver {
+    clauses: Vec<Clause>,
+    values: Vec<Option<bool>>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    trail: Vec<Literal>,
+    trail_limits: Vec<usize>,
+    /// Set when an empty clause is added.
+    trivially_unsat: bool,
+    stats: SolverStats,
+}
+
+impl Solver {
+    pub fn new(num_variables: usize) -> Self {
+        Self {
+            clauses: Vec::new(),
+            values: vec![None; num_variables],
+            levels: vec![0; num_variables],
+            reasons: vec![None; num_variables],
+            trail: Vec::new(),
+            trail_limits: Vec::new(),
+            trivially_unsat: false,
+            stats: SolverStats::default(),
+        }
+    }
+
+    /// A solver loaded with the clauses of `set`.
+    pub fn from_clause_set<E: HashNodeInner>(set: &ClauseSet<E>) -> Self {
+        let mut solver = Self::new(set.num_variables());
+        for clause in &set.clauses {
+            solver.add_clause(clause.clone());
+        }
+        solver
+    }
+
+    /// Add a clause. Duplicate literals are removed and tautological clauses ignored.
+    ///
+    /// Panics if a literal names a variable out of range.
+    pub fn add_clause(&mut self, mut clause: Clause) {
+        assert!(
+            clause.iter().all(|literal| (literal.variable as usize) < self.values.len()),
+            "clause mentions a variable the solver does not have"
+        );
+        clause.sort();
+        clause.dedup();
+        if clause.windows(2).any(|pair| pair[0].variable == pair[1].variable) {
+            return;
+        }
+        if clause.is_empty() {
+            self.trivially_unsat = true;
+        }
+        self.clauses.push(clause);
+    }
+
+    pub fn stats(&self) -> SolverStats {
+        self.stats
+    }
+
+    /// Decide satisfiability of the clauses added so far.
+    pub fn solve(&mut self) -> SatResult {
+        self.backtrack(0);
+        if self.trivially_unsat {
+            return SatResult::Unsatisfiable;
+        }
+
+        loop {
+            if let Some(conflict) = self.propagate() {
+                self.stats.conflicts += 1;
+                if self.trail_limits.is_empty() {
+                    return SatResult::Unsatisfiable;
+                }
+                let (learned, level) = self.analyze(conflict);
+                self.backtrack(level);
+                let asserting = learned[0];
+                self.clauses.push(learned);
+                self.stats.learned += 1;
+                self.assign(asserting, Some(self.clauses.len() - 1));
+                continue;
+            }
+
+            let Some(variable) = self.values.iter().position(Option::is_none) else {
+                let model = self.values.iter().map(|value| value.unwrap_or(false)).collect();
+                return SatResult::Satisfiable(model);
+            };
+            self.stats.decisions += 1;
+            self.trail_limits.push(self.trail.len());
+            self.assign(Literal::negative(variable as u32), None);
+        }
+    }
+
+    fn value(&self, literal: Literal) -> Option<bool> {
+        self.values[literal.variable as usize].map(|value| value == literal.positive)
+    }
+
+    fn assign(&mut self, literal: Literal, reason: Option<usize>) {
+        let variable = literal.variable as usize;
+        self.values[variable] = Some(literal.positive);
+        self.levels[variable] = self.trail_limits.len();
+        self.reasons[variable] = reason;
+        self.trail.push(literal);
+    }
+
+    /// Undo every assignment above decision level `level`.
+    fn backtrack(&mut self, level: usize) {
+        if level >= self.trail_limits.len() {
+            return;
+        }
+        for literal in self.trail.drain(self.trail_limits[level]..) {
+            self.values[literal.variable as usize] = None;
+            self.reasons[literal.variable as usize] = None;
+        }
+        self.trail_limits.truncate(level);
+    }
+
+    /// Assign every unit clause's remaining literal until a fixpoint, or
+    /// return the index of a clause whose literals are all false.
+    fn propagate(&mut self) -> Option<usize> {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for index in 0..self.clauses.len() {
+                let mut unassigned = None;
+                let mut open = 0;
+                let mut satisfied = false;
+                for &literal in &self.clauses[index] {
+                    match self.value(literal) {
+                        Some(true) => {
+                            satisfied = true;
+                            break;
+                        }
+                        Some(false) => {}
+                        None => {
+                            open += 1;
+                            unassigned = Some(literal);
+                        }
+                    }
+                }
+                match (satisfied, open) {
+                    (true, _) => {}
+                    (false, 0) => return Some(index),
+                    (false, 1) => {
+                        self.stats.propagations += 1;
+                        self.assign(unassigned.unwrap(), Some(index));
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    /// First-UIP conflict analysis: the learned clause, with its asserting
+    /// literal first, and the level to backjump to.
+    fn analyze(&self, conflict: usize) -> (Clause, usize) {
+        let current = self.trail_limits.len();
+        let mut seen = vec![false; self.values.len()];
+        let mut learned = vec![Literal::positive(0)];
+        let mut pending = 0;
+        let mut index = self.trail.len();
+        let mut clause = conflict;
+        let mut implied: Option<Literal> = None;
+
+        loop {
+            for &literal in &self.clauses[clause] {
+                let variable = literal.variable as usize;
+                if implied.is_some_and(|implied| implied.variable == literal.variable) {
+                    continue;
+                }
+                if seen[variable] || self.levels[variable] == 0 {
+                    continue;
+                }
+                seen[variable] = true;
+                if self.levels[variable] == current {
+                    pending += 1;
+                } else {
+                    learned.push(literal);
+                }
+            }
+
+            loop {
+                index -= 1;
+                if seen[self.trail[index].variable as usize] {
+                    break;
+                }
+            }
+            let literal = self.trail[index];
+            seen[literal.variable as usize] = false;
+            implied = Some(literal);
+            pending -= 1;
+            if pending == 0 {
+                break;
+            }
+            clause = self.reasons[literal.variable as usize].expect("only decisions lack a reason");
+        }
+
+        learned[0] = implied.unwrap().negated();
+        let level = learned[1..]
+            .iter()
+            .map(|literal| self.levels[literal.variable as usize])
+            .max()
+            .unwrap_or(0);
+        (learned, level)
+    }
+}
+
+/// Decide satisfiability of `set`.
+pub fn solve<E: HashNodeInner>(set: &ClauseSet<E>) -> SatResult {
+    Solver::from_clause_set(set).solve()
+}
+
+/// Goal checker closing propositional goals with the SAT solver.
+///
+/// Goals are Tseitin-encoded, with quantified subformulas and equalities as
+/// opaque atoms. A goal whose negation is unsatisfiable checks as true and an
+/// unsatisfiable goal as false; anything else is left to the search.
+pub struct SatGoalChecker;
+
+impl<D> GoalChecker<ClassicalLogicalExpression<BinaryTruth, D>, BinaryTruth> for SatGoalChecker
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+{
+    fn check(&self, expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>) -> Option<BinaryTruth> {
+        if !solve(&to_cnf_tseitin_negated(expr)).is_satisfiable() {
+            Some(BinaryTruth::True)
+        } else if !solve(&to_cnf_tseitin(expr)).is_satisfiable() {
+            Some(BinaryTruth::False)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::is_tautology;
+    use crate::testing::{Formula, Formulas};
+
+    fn clause(literals: &[i32]) -> Clause {
+        literals
+            .iter()
+            .map(|&l| Literal {
+                variable: l.unsigned_abs() - 1,
+                positive: l > 0,
+            })
+            .collect()
+    }
+
+    /// Pigeonhole: `n + 1` pigeons into `n` holes; unsatisfiable and needs learning.
+    fn pigeonhole(n: usize) -> Solver {
+        let var = |pigeon: usize, hole: usize| (pigeon * n + hole + 1) as i32;
+        let mut solver = Solver::new((n + 1) * n);
+        for pigeon in 0..=n {
+            solver.add_clause(clause(&(0..n).map(|hole| var(pigeon, hole)).collect::<Vec<_>>()));
+        }
+        for hole in 0..n {
+            for a in 0..=n {
+                for b in a + 1..=n {
+                    solver.add_clause(clause(&[-var(a, hole), -var(b, hole)]));
+                }
+            }
+        }
+        solver
+    }
+
+    #[test]
+    fn test_models_satisfy_clauses() {
+        let clauses = [clause(&[1, 2]), clause(&[-1, 3]), clause(&[-3, -2]), clause(&[2, 3])];
+        let mut solver = Solver::new(3);
+        for c in &clauses {
+            solver.add_clause(c.clone());
+        }
+
+        let result = solver.solve();
+        let model = result.model().expect("satisfiable");
+        assert!(clauses.iter().all(|c| c.iter().any(|literal| literal.evaluate(model))));
+    }
+
+    #[test]
+    fn test_unsatisfiable_instances() {
+        let mut solver = Solver::new(1);
+        solver.add_clause(clause(&[1]));
+        solver.add_clause(clause(&[-1]));
+        assert_eq!(solver.solve(), SatResult::Unsatisfiable);
+
+        let mut solver = pigeonhole(4);
+        assert_eq!(solver.solve(), SatResult::Unsatisfiable);
+        assert!(solver.stats().learned > 0);
+    }
+
+    #[test]
+    fn test_checker_agrees_with_truth_tables() {
+        let f = Formulas::new();
+        let (p, q, r) = (f.atom(0), f.atom(1), f.atom(2));
+        let formulas: Vec<HashNode<Formula>> = vec![
+            f.implies(&f.and(&f.implies(&p, &q), &f.implies(&q, &r)), &f.implies(&p, &r)),
+            f.iff(&f.not(&f.and(&p, &q)), &f.or(&f.not(&p), &f.not(&q))),
+            f.and(&f.or(&p, &q), &f.not(&p)),
+            f.and(&f.iff(&p, &q), &f.iff(&p, &f.not(&q))),
+        ];
+
+        for expr in &formulas {
+            let expected = match is_tautology(expr).unwrap() {
+                true => Some(BinaryTruth::True),
+                false if !crate::tables::is_satisfiable(expr).unwrap() => Some(BinaryTruth::False),
+                false => None,
+            };
+            assert_eq!(SatGoalChecker.check(expr), expected, "{}", expr);
+        }
+
+        // Quantified subformulas are opaque atoms: ∀p ∨ ¬∀p is still valid.
+        let all = f.forall(&p);
+        assert_eq!(SatGoalChecker.check(&f.or(&all, &f.not(&all))), Some(BinaryTruth::True));
+    }
+}
@@ -0,0 +1,231 @@
+//! Evaluation of formulas in a finite structure.
+//!
+//! An `Interpretation` supplies a finite universe and decides atoms under an
+//! `Assignment` of universe elements to de Bruijn indices. Connectives are
+//! classical, quantifiers range over the universe, and an equality between
+//! formulas holds when both sides have the same value.
+
+use crate::{BinaryTruth, ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner};
+use corpus_core::truth::TruthValue;
+use std::fmt::{Display, Formatter};
+
+/// Values for the variables in scope; `/0` is the most recently bound.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Assignment<V> {
+    /// Outermost first, so `/i` is `values[len - 1 - i]`.
+    values: Vec<V>,
+}
+
+impl<V> Assignment<V> {
+    pub fn new() -> Self {
+        Self { values: Vec::new() }
+    }
+
+    /// An assignment giving `/i` the value `values[i]`.
+    pub fn from_indices(mut values: Vec<V>) -> Self {
+        values.reverse();
+        Self { values }
+    }
+
+    /// The value of `/index`, if it is in scope.
+    pub fn get(&self, index: u32) -> Option<&V> {
+        let position = self.values.len().checked_sub(index as usize + 1)?;
+        self.values.get(position)
+    }
+
+    /// Bind a new `/0`, shifting every other variable up by one.
+    pub fn push(&mut self, value: V) {
+        self.values.push(value);
+    }
+
+    /// Unbind `/0`, returning its value.
+    pub fn pop(&mut self) -> Option<V> {
+        self.values.pop()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Values in index order: element `i` is the value of `/i`.
+    pub fn by_index(&self) -> impl Iterator<Item = &V> {
+        self.values.iter().rev()
+    }
+}
+
+/// A finite structure for the atoms of `D`.
+pub trait Interpretation<T: TruthValue, D: DomainContent<T>> {
+    /// Elements of the universe.
+    type Value: Clone;
+
+    /// The universe quantifiers range over.
+    fn universe(&self) -> Vec<Self::Value>;
+
+    /// Whether `atom` holds under `assignment`, or `None` if the
+    /// interpretation cannot decide it (e.g. a variable is out of scope).
+    fn holds(&self, atom: &HashNode<D>, assignment: &Assignment<Self::Value>) -> Option<bool>;
+}
+
+/// Why a formula could not be evaluated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EvaluationError {
+    /// The interpretation could not decide the atom with this hash.
+    Undecided(u64),
+    /// A connective has the wrong number of operands.
+    Malformed(ClassicalOperator),
+}
+
+impl Display for EvaluationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvaluationError::Undecided(hash) => write!(f, "interpretation cannot decide atom {:#x}", hash),
+            EvaluationError::Malformed(operator) => write!(f, "{} applied to the wrong number of operands", operator),
+        }
+    }
+}
+
+impl std::error::Error for EvaluationError {}
+
+/// The value of `expr` in `interpretation` under `assignment`.
+pub fn evaluate<T, D, I>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    interpretation: &I,
+    assignment: &Assignment<I::Value>,
+) -> Result<BinaryTruth, EvaluationError>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    I: Interpretation<T, D>,
+{
+    let universe = interpretation.universe();
+    let mut assignment = assignment.clone();
+    evaluate_in(expr, interpretation, &universe, &mut assignment).map(BinaryTruth::from)
+}
+
+/// Whether the sentence `expr` holds in `interpretation`.
+pub fn satisfies<T, D, I>(interpretation: &I, expr: &HashNode<ClassicalLogicalExpression<T, D>>) -> Result<bool, EvaluationError>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    I: Interpretation<T, D>,
+{
+    evaluate(expr, interpretation, &Assignment::new()).map(bool::from)
+}
+
+fn evaluate_in<T, D, I>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    interpretation: &I,
+    universe: &[I::Value],
+    assignment: &mut Assignment<I::Value>,
+) -> Result<bool, EvaluationError>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    I: Interpretation<T, D>,
+{
+    let (operator, operands) = match expr.value.as_ref() {
+        LogicalExpression::Atomic(atom) => {
+            return interpretation.holds(atom, assignment).ok_or(EvaluationError::Undecided(atom.hash()));
+        }
+        LogicalExpression::Compound { operator, operands, .. } => (*operator, operands),
+    };
+    if operands.len() != operator.arity() {
+        return Err(EvaluationError::Malformed(operator));
+    }
+
+    if matches!(operator, ClassicalOperator::Forall | ClassicalOperator::Exists) {
+        let universal = operator == ClassicalOperator::Forall;
+        for value in universe {
+            assignment.push(value.clone());
+            let holds = evaluate_in(&operands[0], interpretation, universe, assignment);
+            assignment.pop();
+            if holds? != universal {
+                return Ok(!universal);
+            }
+        }
+        return Ok(universal);
+    }
+
+    let mut value = |i: usize| evaluate_in(&operands[i], interpretation, universe, assignment);
+    Ok(match operator {
+        ClassicalOperator::Not => !value(0)?,
+        ClassicalOperator::And => value(0)? && value(1)?,
+        ClassicalOperator::Or => value(0)? || value(1)?,
+        ClassicalOperator::Implies => !value(0)? || value(1)?,
+        ClassicalOperator::Iff | ClassicalOperator::Equals => value(0)? == value(1)?,
+        ClassicalOperator::Forall | ClassicalOperator::Exists => unreachable!("handled above"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FoTerm, Formulas, Pred};
+
+    /// Integers `0..size` with `P0(x, y)` meaning `x < y` and `P1(x)` meaning `x` is even.
+    struct Ordering {
+        size: u32,
+    }
+
+    impl Interpretation<BinaryTruth, Pred> for Ordering {
+        type Value = u32;
+
+        fn universe(&self) -> Vec<u32> {
+            (0..self.size).collect()
+        }
+
+        fn holds(&self, atom: &HashNode<Pred>, assignment: &Assignment<u32>) -> Option<bool> {
+            let Pred(symbol, arguments) = atom.value.as_ref();
+            let values = arguments
+                .iter()
+                .map(|argument| match argument.value.as_ref() {
+                    FoTerm::Var(index) => assignment.get(*index).copied(),
+                    FoTerm::Const(value) => Some(*value),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            match (symbol, values.as_slice()) {
+                (0, [x, y]) => Some(x < y),
+                (1, [x]) => Some(x % 2 == 0),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_quantifiers_range_over_universe() {
+        let f = Formulas::first_order();
+        let less = |a, b| f.pred(0, vec![a, b]);
+        let model = Ordering { size: 4 };
+
+        // ∃x ∀y ¬(y < x): there is a least element.
+        let least = f.exists(&f.forall(&f.not(&less(f.var(0), f.var(1)))));
+        assert_eq!(satisfies(&model, &least), Ok(true));
+
+        // ∀x ∃y x < y fails at the top of a finite order.
+        let unbounded = f.forall(&f.exists(&less(f.var(1), f.var(0))));
+        assert_eq!(satisfies(&model, &unbounded), Ok(false));
+    }
+
+    #[test]
+    fn test_assignment_gives_free_variables() {
+        let f = Formulas::first_order();
+        let model = Ordering { size: 4 };
+        let even = f.pred(1, vec![f.var(0)]);
+
+        assert_eq!(evaluate(&even, &model, &Assignment::from_indices(vec![2])), Ok(BinaryTruth::True));
+        assert_eq!(evaluate(&even, &model, &Assignment::from_indices(vec![3])), Ok(BinaryTruth::False));
+        assert!(matches!(evaluate(&even, &model, &Assignment::new()), Err(EvaluationError::Undecided(_))));
+
+        // Under ∃, the free /0 is /1.
+        let expr = f.exists(&f.pred(0, vec![f.var(1), f.var(0)]));
+        assert_eq!(evaluate(&expr, &model, &Assignment::from_indices(vec![3])), Ok(BinaryTruth::False));
+        assert_eq!(evaluate(&expr, &model, &Assignment::from_indices(vec![1])), Ok(BinaryTruth::True));
+    }
+}
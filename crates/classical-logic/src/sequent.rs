@@ -0,0 +1,388 @@
+//! Sequent calculus.
+//!
+//! A `Sequent` `Γ ⊢ Δ` holds when the conjunction of its antecedents implies
+//! the disjunction of its succedents. `prove_sequent` searches backwards
+//! with the invertible rules of the G3 variant of LK, so it decides every
+//! propositional sequent and produces a `SequentProof` tree that
+//! `SequentProof::check` verifies rule by rule.
+//!
+//! Quantified formulas and equalities are not decomposed: they only close
+//! branches through the axiom rule.
+
+use crate::axioms::ClassicalAxiomConverter;
+use crate::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner};
+use corpus_core::truth::TruthValue;
+use std::fmt::{Display, Formatter};
+
+/// `antecedents ⊢ succedents`.
+pub struct Sequent<E: HashNodeInner> {
+    pub antecedents: Vec<HashNode<E>>,
+    pub succedents: Vec<HashNode<E>>,
+}
+
+impl<E: HashNodeInner> Sequent<E> {
+    pub fn new(antecedents: Vec<HashNode<E>>, succedents: Vec<HashNode<E>>) -> Self {
+        Self { antecedents, succedents }
+    }
+
+    /// `⊢ formula`.
+    pub fn goal(formula: HashNode<E>) -> Self {
+        Self::new(Vec::new(), vec![formula])
+    }
+
+    /// Whether some formula occurs on both sides.
+    pub fn is_axiom(&self) -> bool {
+        self.antecedents.iter().any(|a| self.succedents.contains(a))
+    }
+
+    /// Whether `self` and `other` have the same formulas on each side, in any order.
+    pub fn same_as(&self, other: &Self) -> bool {
+        fn hashes<E: HashNodeInner>(formulas: &[HashNode<E>]) -> Vec<u64> {
+            let mut hashes: Vec<u64> = formulas.iter().map(|f| f.hash()).collect();
+            hashes.sort_unstable();
+            hashes
+        }
+        hashes(&self.antecedents) == hashes(&other.antecedents) && hashes(&self.succedents) == hashes(&other.succedents)
+    }
+
+    /// The sequent with `index` removed from `side` and `added` formulas added to each side.
+    fn replace(&self, side: Side, index: usize, left: &[&HashNode<E>], right: &[&HashNode<E>]) -> Self {
+        let mut antecedents = self.antecedents.clone();
+        let mut succedents = self.succedents.clone();
+        match side {
+            Side::Left => antecedents.remove(index),
+            Side::Right => succedents.remove(index),
+        };
+        antecedents.extend(left.iter().map(|f| (*f).clone()));
+        succedents.extend(right.iter().map(|f| (*f).clone()));
+        Self::new(antecedents, succedents)
+    }
+}
+
+impl<E: HashNodeInner> Clone for Sequent<E> {
+    fn clone(&self) -> Self {
+        Self::new(self.antecedents.clone(), self.succedents.clone())
+    }
+}
+
+impl<E: HashNodeInner + Display> Display for Sequent<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let join = |formulas: &[HashNode<E>]| formulas.iter().map(|f| f.to_string()).collect::<Vec<_>>().join(", ");
+        write!(f, "{} ⊢ {}", join(&self.antecedents), join(&self.succedents))
+    }
+}
+
+/// Which side of the turnstile a principal formula is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// The inference rules of the calculus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequentRule {
+    Axiom,
+    NotLeft,
+    NotRight,
+    AndLeft,
+    AndRight,
+    OrLeft,
+    OrRight,
+    ImpliesLeft,
+    ImpliesRight,
+    IffLeft,
+    IffRight,
+}
+
+impl SequentRule {
+    fn for_operator(operator: ClassicalOperator, side: Side) -> Option<Self> {
+        Some(match (operator, side) {
+            (ClassicalOperator::Not, Side::Left) => SequentRule::NotLeft,
+            (ClassicalOperator::Not, Side::Right) => SequentRule::NotRight,
+            (ClassicalOperator::And, Side::Left) => SequentRule::AndLeft,
+            (ClassicalOperator::And, Side::Right) => SequentRule::AndRight,
+            (ClassicalOperator::Or, Side::Left) => SequentRule::OrLeft,
+            (ClassicalOperator::Or, Side::Right) => SequentRule::OrRight,
+            (ClassicalOperator::Implies, Side::Left) => SequentRule::ImpliesLeft,
+            (ClassicalOperator::Implies, Side::Right) => SequentRule::ImpliesRight,
+            (ClassicalOperator::Iff, Side::Left) => SequentRule::IffLeft,
+            (ClassicalOperator::Iff, Side::Right) => SequentRule::IffRight,
+            _ => return None,
+        })
+    }
+}
+
+impl Display for SequentRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SequentRule::Axiom => "Ax",
+            SequentRule::NotLeft => "¬L",
+            SequentRule::NotRight => "¬R",
+            SequentRule::AndLeft => "∧L",
+            SequentRule::AndRight => "∧R",
+            SequentRule::OrLeft => "∨L",
+            SequentRule::OrRight => "∨R",
+            SequentRule::ImpliesLeft => "→L",
+            SequentRule::ImpliesRight => "→R",
+            SequentRule::IffLeft => "↔L",
+            SequentRule::IffRight => "↔R",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A proof tree: `conclusion` follows from the proofs of `premises` by `rule`.
+pub struct SequentProof<E: HashNodeInner> {
+    pub conclusion: Sequent<E>,
+    pub rule: SequentRule,
+    /// The formula `rule` decomposes, and its side; `None` for axioms.
+    pub principal: Option<(Side, HashNode<E>)>,
+    pub premises: Vec<SequentProof<E>>,
+}
+
+impl<E: HashNodeInner> SequentProof<E> {
+    /// Number of rule applications in the tree.
+    pub fn size(&self) -> usize {
+        1 + self.premises.iter().map(SequentProof::size).sum::<usize>()
+    }
+
+    pub fn depth(&self) -> usize {
+        1 + self.premises.iter().map(SequentProof::depth).max().unwrap_or(0)
+    }
+}
+
+impl<T, D> SequentProof<ClassicalLogicalExpression<T, D>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    /// Whether every node is a correct application of its rule: axioms share
+    /// a formula, and every other node's premises are exactly those its rule
+    /// produces from the principal formula.
+    pub fn check(&self) -> bool {
+        let valid = match (&self.rule, &self.principal) {
+            (SequentRule::Axiom, None) => self.conclusion.is_axiom() && self.premises.is_empty(),
+            (rule, Some((side, principal))) => {
+                let position = match side {
+                    Side::Left => &self.conclusion.antecedents,
+                    Side::Right => &self.conclusion.succedents,
+                }
+                .iter()
+                .position(|formula| formula == principal);
+                match position.and_then(|index| decompose(&self.conclusion, *side, index)) {
+                    Some((expected_rule, expected)) => {
+                        expected_rule == *rule
+                            && expected.len() == self.premises.len()
+                            && expected.iter().zip(&self.premises).all(|(e, p)| e.same_as(&p.conclusion))
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        };
+        valid && self.premises.iter().all(SequentProof::check)
+    }
+}
+
+impl<E: HashNodeInner + Display> SequentProof<E> {
+    fn fmt_indented(&self, f: &mut Formatter<'_>, depth: usize) -> std::fmt::Result {
+        writeln!(f, "{}{}    [{}]", "  ".repeat(depth), self.conclusion, self.rule)?;
+        self.premises.iter().try_for_each(|premise| premise.fmt_indented(f, depth + 1))
+    }
+}
+
+impl<E: HashNodeInner + Display> Display for SequentProof<E> {
+    /// One sequent per line, premises indented below their conclusion.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+type Decomposition<T, D> = (SequentRule, Vec<Sequent<ClassicalLogicalExpression<T, D>>>);
+
+/// The rule decomposing the formula at `index` on `side` of `sequent`, and
+/// the premises it leaves, or `None` if the formula is not decomposable.
+fn decompose<T, D>(
+    sequent: &Sequent<ClassicalLogicalExpression<T, D>>,
+    side: Side,
+    index: usize,
+) -> Option<Decomposition<T, D>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let formula = match side {
+        Side::Left => &sequent.antecedents[index],
+        Side::Right => &sequent.succedents[index],
+    };
+    let LogicalExpression::Compound { operator, operands, .. } = formula.value.as_ref() else {
+        return None;
+    };
+    if operands.len() != operator.arity() {
+        return None;
+    }
+    let rule = SequentRule::for_operator(*operator, side)?;
+    let replace = |left: &[&HashNode<_>], right: &[&HashNode<_>]| sequent.replace(side, index, left, right);
+    let premises = match rule {
+        SequentRule::NotLeft => vec![replace(&[], &[&operands[0]])],
+        SequentRule::NotRight => vec![replace(&[&operands[0]], &[])],
+        SequentRule::AndLeft => vec![replace(&[&operands[0], &operands[1]], &[])],
+        SequentRule::AndRight => vec![replace(&[], &[&operands[0]]), replace(&[], &[&operands[1]])],
+        SequentRule::OrLeft => vec![replace(&[&operands[0]], &[]), replace(&[&operands[1]], &[])],
+        SequentRule::OrRight => vec![replace(&[], &[&operands[0], &operands[1]])],
+        SequentRule::ImpliesLeft => vec![replace(&[], &[&operands[0]]), replace(&[&operands[1]], &[])],
+        SequentRule::ImpliesRight => vec![replace(&[&operands[0]], &[&operands[1]])],
+        SequentRule::IffLeft => vec![
+            replace(&[&operands[0], &operands[1]], &[]),
+            replace(&[], &[&operands[0], &operands[1]]),
+        ],
+        SequentRule::IffRight => vec![
+            replace(&[&operands[0]], &[&operands[1]]),
+            replace(&[&operands[1]], &[&operands[0]]),
+        ],
+        SequentRule::Axiom => unreachable!("for_operator never returns Axiom"),
+    };
+    Some((rule, premises))
+}
+
+/// Search for a proof of `sequent`, or `None` if it is not provable.
+///
+/// Rules that do not branch are tried before rules that do, which keeps
+/// proofs small. Every rule is invertible, so the first decomposable
+/// formula can always be chosen without backtracking.
+pub fn prove_sequent<T, D>(
+    sequent: &Sequent<ClassicalLogicalExpression<T, D>>,
+) -> Option<SequentProof<ClassicalLogicalExpression<T, D>>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    if sequent.is_axiom() {
+        return Some(SequentProof {
+            conclusion: sequent.clone(),
+            rule: SequentRule::Axiom,
+            principal: None,
+            premises: Vec::new(),
+        });
+    }
+
+    let candidates = (0..sequent.antecedents.len())
+        .map(|i| (Side::Left, i))
+        .chain((0..sequent.succedents.len()).map(|i| (Side::Right, i)));
+    let (side, index, rule, premises) = candidates
+        .filter_map(|(side, index)| decompose(sequent, side, index).map(|(rule, premises)| (side, index, rule, premises)))
+        .min_by_key(|(_, _, _, premises)| premises.len())?;
+
+    let principal = match side {
+        Side::Left => sequent.antecedents[index].clone(),
+        Side::Right => sequent.succedents[index].clone(),
+    };
+    let premises = premises.iter().map(prove_sequent).collect::<Option<Vec<_>>>()?;
+    Some(SequentProof {
+        conclusion: sequent.clone(),
+        rule,
+        principal: Some((side, principal)),
+        premises,
+    })
+}
+
+/// The sequents that justify the rewrite rules `ClassicalAxiomConverter`
+/// produces for `axiom`: `A → B, A ⊢ B` for an implication, and both
+/// directions for a biconditional. Empty for axioms the converter rejects
+/// and for equalities, which the calculus treats as atoms.
+pub fn conversion_sequents<T, D>(
+    axiom: &HashNode<ClassicalLogicalExpression<T, D>>,
+) -> Vec<Sequent<ClassicalLogicalExpression<T, D>>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    let LogicalExpression::Compound { operator, operands, .. } = axiom.value.as_ref() else {
+        return Vec::new();
+    };
+    if operands.len() != 2 {
+        return Vec::new();
+    }
+    let (a, b) = (&operands[0], &operands[1]);
+    let sequent = |from: &HashNode<_>, to: &HashNode<_>| Sequent::new(vec![axiom.clone(), from.clone()], vec![to.clone()]);
+    match operator {
+        ClassicalOperator::Implies => vec![sequent(a, b)],
+        ClassicalOperator::Iff => vec![sequent(a, b), sequent(b, a)],
+        _ => Vec::new(),
+    }
+}
+
+impl ClassicalAxiomConverter {
+    /// Sequent proofs that the rewrite rules converted from `axiom` are
+    /// sound, or `None` if there is nothing the calculus can justify.
+    pub fn justify<T, D>(
+        &self,
+        axiom: &HashNode<ClassicalLogicalExpression<T, D>>,
+    ) -> Option<Vec<SequentProof<ClassicalLogicalExpression<T, D>>>>
+    where
+        T: TruthValue + HashNodeInner,
+        D: DomainContent<T>,
+    {
+        let sequents = conversion_sequents(axiom);
+        if sequents.is_empty() {
+            return None;
+        }
+        sequents.iter().map(prove_sequent).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::is_tautology;
+    use crate::testing::Formulas;
+
+    #[test]
+    fn test_search_agrees_with_truth_tables() {
+        let f = Formulas::new();
+        let (p, q, r) = (f.atom(0), f.atom(1), f.atom(2));
+        let formulas = [
+            f.implies(&f.implies(&f.implies(&p, &q), &p), &p),
+            f.iff(&f.and(&p, &f.or(&q, &r)), &f.or(&f.and(&p, &q), &f.and(&p, &r))),
+            f.implies(&f.or(&p, &q), &f.and(&p, &q)),
+            f.or(&f.iff(&p, &q), &f.iff(&p, &f.not(&q))),
+            f.not(&f.iff(&p, &p)),
+        ];
+
+        for formula in &formulas {
+            let proof = prove_sequent(&Sequent::goal(formula.clone()));
+            assert_eq!(proof.is_some(), is_tautology(formula).unwrap(), "{}", formula);
+            if let Some(proof) = proof {
+                assert!(proof.check(), "{}", proof);
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_rejects_tampered_proofs() {
+        let f = Formulas::new();
+        let (p, q) = (f.atom(0), f.atom(1));
+        let mut proof = prove_sequent(&Sequent::goal(f.implies(&f.and(&p, &q), &p))).unwrap();
+        assert_eq!(proof.rule, SequentRule::ImpliesRight);
+        assert!(proof.check());
+
+        proof.premises[0].conclusion.succedents.push(q);
+        assert!(!proof.check());
+    }
+
+    #[test]
+    fn test_justify_converted_axioms() {
+        let f = Formulas::new();
+        let (p, q) = (f.atom(0), f.atom(1));
+
+        let proofs = ClassicalAxiomConverter.justify(&f.iff(&f.not(&f.not(&p)), &p)).unwrap();
+        assert_eq!(proofs.len(), 2);
+        assert!(proofs.iter().all(SequentProof::check));
+
+        assert!(ClassicalAxiomConverter.justify(&f.implies(&p, &q)).is_some());
+        assert!(ClassicalAxiomConverter.justify(&f.and(&p, &q)).is_none());
+    }
+}
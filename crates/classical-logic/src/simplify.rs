@@ -0,0 +1,185 @@
+//! Standard propositional simplification rules.
+//!
+//! `simplification_system` rewrites towards disjunctive normal form:
+//! implications and biconditionals are unfolded, negations pushed inwards
+//! with De Morgan's laws and double negation, and `∧` distributed over `∨`.
+//! Idempotence and absorption come first so they fire before distribution
+//! duplicates their operands. Every rule is a classical equivalence, applied
+//! forwards only so that `RewriteSystem::normalize` terminates.
+//!
+//! Formulas have no built-in truth constants, so the identity and domination
+//! laws are in `constant_rules`, over whichever formulas the caller uses for
+//! `true` and `false`.
+
+use crate::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::DomainContent;
+use corpus_core::nodes::{HashNode, HashNodeInner};
+use corpus_core::rewriting::{Pattern, RewriteDirection, RewriteRule, RewriteSystem};
+use corpus_core::truth::TruthValue;
+
+type Rule<T, D> = RewriteRule<ClassicalLogicalExpression<T, D>>;
+type Formula<T, D> = Pattern<ClassicalLogicalExpression<T, D>>;
+
+fn var<T, D>(index: u32) -> Formula<T, D>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone,
+{
+    Pattern::var(index)
+}
+
+fn apply<T, D>(operator: ClassicalOperator, operands: Vec<Formula<T, D>>) -> Formula<T, D>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone,
+{
+    Pattern::compound(operator.hash(), operands)
+}
+
+fn not<T, D>(a: Formula<T, D>) -> Formula<T, D>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone,
+{
+    apply(ClassicalOperator::Not, vec![a])
+}
+
+fn and<T, D>(a: Formula<T, D>, b: Formula<T, D>) -> Formula<T, D>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone,
+{
+    apply(ClassicalOperator::And, vec![a, b])
+}
+
+fn or<T, D>(a: Formula<T, D>, b: Formula<T, D>) -> Formula<T, D>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone,
+{
+    apply(ClassicalOperator::Or, vec![a, b])
+}
+
+fn implies<T, D>(a: Formula<T, D>, b: Formula<T, D>) -> Formula<T, D>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone,
+{
+    apply(ClassicalOperator::Implies, vec![a, b])
+}
+
+fn rule<T, D>(name: &str, pattern: Formula<T, D>, replacement: Formula<T, D>) -> Rule<T, D>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone,
+{
+    RewriteRule::new(name, pattern, replacement, RewriteDirection::Forward)
+}
+
+/// De Morgan, double negation, idempotence, absorption, distributivity and
+/// implication unfolding, in the order `normalize` should try them.
+pub fn simplification_system<T, D>() -> RewriteSystem<ClassicalLogicalExpression<T, D>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone,
+{
+    let (a, b, c) = (var::<T, D>(0), var::<T, D>(1), var::<T, D>(2));
+    let rules = vec![
+        rule("double_negation", not(not(a.clone())), a.clone()),
+        rule("and_idempotence", and(a.clone(), a.clone()), a.clone()),
+        rule("or_idempotence", or(a.clone(), a.clone()), a.clone()),
+        rule("and_absorption", and(a.clone(), or(a.clone(), b.clone())), a.clone()),
+        rule("or_absorption", or(a.clone(), and(a.clone(), b.clone())), a.clone()),
+        rule("implication", implies(a.clone(), b.clone()), or(not(a.clone()), b.clone())),
+        rule(
+            "biconditional",
+            apply(ClassicalOperator::Iff, vec![a.clone(), b.clone()]),
+            and(implies(a.clone(), b.clone()), implies(b.clone(), a.clone())),
+        ),
+        rule("de_morgan_and", not(and(a.clone(), b.clone())), or(not(a.clone()), not(b.clone()))),
+        rule("de_morgan_or", not(or(a.clone(), b.clone())), and(not(a.clone()), not(b.clone()))),
+        rule(
+            "distribute_left",
+            and(a.clone(), or(b.clone(), c.clone())),
+            or(and(a.clone(), b.clone()), and(a.clone(), c.clone())),
+        ),
+        rule(
+            "distribute_right",
+            and(or(a.clone(), b.clone()), c.clone()),
+            or(and(a.clone(), c.clone()), and(b, c)),
+        ),
+    ];
+    RewriteSystem::with_rules("propositional_simplification", rules)
+}
+
+/// Identity, domination, complement and constant negation laws, with `top`
+/// and `bottom` standing for `true` and `false`.
+pub fn constant_rules<T, D>(
+    top: &HashNode<ClassicalLogicalExpression<T, D>>,
+    bottom: &HashNode<ClassicalLogicalExpression<T, D>>,
+) -> RewriteSystem<ClassicalLogicalExpression<T, D>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone,
+{
+    let a = var::<T, D>(0);
+    let top = Pattern::constant(top.value.as_ref().clone());
+    let bottom = Pattern::constant(bottom.value.as_ref().clone());
+    let rules = vec![
+        rule("and_identity", and(a.clone(), top.clone()), a.clone()),
+        rule("and_identity_left", and(top.clone(), a.clone()), a.clone()),
+        rule("and_domination", and(a.clone(), bottom.clone()), bottom.clone()),
+        rule("and_domination_left", and(bottom.clone(), a.clone()), bottom.clone()),
+        rule("or_identity", or(a.clone(), bottom.clone()), a.clone()),
+        rule("or_identity_left", or(bottom.clone(), a.clone()), a.clone()),
+        rule("or_domination", or(a.clone(), top.clone()), top.clone()),
+        rule("or_domination_left", or(top.clone(), a.clone()), top.clone()),
+        rule("and_complement", and(a.clone(), not(a.clone())), bottom.clone()),
+        rule("or_complement", or(a.clone(), not(a)), top.clone()),
+        rule("not_top", not(top.clone()), bottom.clone()),
+        rule("not_bottom", not(bottom), top),
+    ];
+    RewriteSystem::with_rules("propositional_constants", rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::is_tautology;
+    use crate::testing::Formulas;
+    use corpus_core::rewriting::Substitution;
+
+    #[test]
+    fn test_every_rule_is_an_equivalence() {
+        let f = Formulas::new();
+        let mut substitution = Substitution::new();
+        for i in 0..3 {
+            substitution.bind(i, f.atom(i));
+        }
+
+        for rule in simplification_system().rules() {
+            let lhs = rule.pattern.instantiate(&substitution, &f.store).unwrap();
+            let rhs = rule.replacement.instantiate(&substitution, &f.store).unwrap();
+            assert!(is_tautology(&f.iff(&lhs, &rhs)).unwrap(), "{}", rule.name);
+        }
+    }
+
+    #[test]
+    fn test_normalize_to_disjunctive_form() {
+        let f = Formulas::new();
+        let (p, q, r) = (f.atom(0), f.atom(1), f.atom(2));
+        let system = simplification_system();
+
+        let expr = f.not(&f.implies(&p, &q));
+        assert_eq!(system.normalize(&expr, &f.store), f.and(&p, &f.not(&q)));
+
+        let expr = f.and(&p, &f.or(&q, &f.not(&f.not(&r))));
+        assert_eq!(system.normalize(&expr, &f.store), f.or(&f.and(&p, &q), &f.and(&p, &r)));
+
+        let (top, bottom) = (f.atom(8), f.atom(9));
+        let mut system = constant_rules(&top, &bottom);
+        system.extend(simplification_system());
+        let expr = f.or(&f.and(&p, &top), &f.and(&q, &f.not(&q)));
+        assert_eq!(system.normalize(&expr, &f.store), p);
+    }
+}
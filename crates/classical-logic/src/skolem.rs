@@ -0,0 +1,261 @@
+//! Skolemization.
+//!
+//! An existential quantifier in positive position, `∀x ∃y φ(x, y)`, is
+//! replaced by a fresh function of the universally quantified variables in
+//! scope, `∀x φ(x, sk(x))`. The result is equisatisfiable with the input,
+//! not equivalent. Universal quantifiers in negative position (under `¬` or
+//! to the left of `->`) are existential in disguise and are eliminated the
+//! same way; the remaining quantifiers are kept in place.
+//!
+//! Biconditionals and formula equalities that contain quantifiers are
+//! expanded into two implications first, since their operands occur in
+//! both polarities.
+
+use crate::first_order::{skolem_opcode, FirstOrderDomain};
+use crate::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner, NodeStorage};
+use corpus_core::truth::TruthValue;
+
+/// A Skolem function introduced by a `Skolemizer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkolemFunction {
+    /// Index passed to `FirstOrderDomain::skolem`.
+    pub index: u32,
+    /// Number of universal variables it is applied to.
+    pub arity: usize,
+    /// Its opcode, from `skolem_opcode`.
+    pub opcode: u64,
+}
+
+/// Skolemizes formulas, numbering Skolem functions so that separate calls
+/// never reuse one.
+#[derive(Debug, Clone, Default)]
+pub struct Skolemizer {
+    next_index: u32,
+    /// Every function introduced so far, in order.
+    pub functions: Vec<SkolemFunction>,
+}
+
+/// What an enclosing quantifier became.
+enum Binder {
+    /// Still a quantifier in the output.
+    Kept,
+    /// Replaced by Skolem function `index`, applied to the kept binders at
+    /// these stack positions.
+    Skolem { index: u32, arguments: Vec<usize> },
+}
+
+impl Skolemizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A skolemizer whose first function has index `first_index`, to avoid
+    /// functions already present in the formulas.
+    pub fn starting_at(first_index: u32) -> Self {
+        Self {
+            next_index: first_index,
+            functions: Vec::new(),
+        }
+    }
+
+    /// Eliminate the existential quantifiers of `expr`.
+    ///
+    /// Returns `None` if `domain` cannot build Skolem terms or rebuild an atom.
+    pub fn skolemize<T, D, F>(
+        &mut self,
+        expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+        domain: &F,
+        store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+    ) -> Option<HashNode<ClassicalLogicalExpression<T, D>>>
+    where
+        T: TruthValue + HashNodeInner,
+        D: DomainContent<T>,
+        F: FirstOrderDomain<T, D>,
+    {
+        self.walk(expr, true, &mut Vec::new(), domain, store)
+    }
+
+    fn walk<T, D, F>(
+        &mut self,
+        expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+        positive: bool,
+        binders: &mut Vec<Binder>,
+        domain: &F,
+        store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+    ) -> Option<HashNode<ClassicalLogicalExpression<T, D>>>
+    where
+        T: TruthValue + HashNodeInner,
+        D: DomainContent<T>,
+        F: FirstOrderDomain<T, D>,
+    {
+        let build = |operator, operands| HashNode::from_store(ClassicalLogicalExpression::compound(operator, operands), store);
+        let (operator, operands) = match expr.value.as_ref() {
+            LogicalExpression::Atomic(atom) => {
+                let atom = domain.map_atom_variables(atom, &|index| replacement(index, binders, domain))?;
+                return Some(HashNode::from_store(ClassicalLogicalExpression::atomic(atom), store));
+            }
+            LogicalExpression::Compound { operator, operands, .. } => (*operator, operands),
+        };
+
+        match operator {
+            ClassicalOperator::Forall | ClassicalOperator::Exists if operands.len() == 1 => {
+                let universal = (operator == ClassicalOperator::Forall) == positive;
+                if universal {
+                    binders.push(Binder::Kept);
+                    let body = self.walk(&operands[0], positive, binders, domain, store);
+                    binders.pop();
+                    return Some(build(operator, vec![body?]));
+                }
+                let arguments = (0..binders.len())
+                    .filter(|&position| matches!(binders[position], Binder::Kept))
+                    .collect::<Vec<_>>();
+                let index = self.next_index;
+                self.next_index += 1;
+                self.functions.push(SkolemFunction {
+                    index,
+                    arity: arguments.len(),
+                    opcode: skolem_opcode(index),
+                });
+                binders.push(Binder::Skolem { index, arguments });
+                let body = self.walk(&operands[0], positive, binders, domain, store);
+                binders.pop();
+                body
+            }
+            ClassicalOperator::Iff | ClassicalOperator::Equals
+                if operands.len() == 2 && operands.iter().any(contains_quantifier) =>
+            {
+                let forward = build(ClassicalOperator::Implies, vec![operands[0].clone(), operands[1].clone()]);
+                let backward = build(ClassicalOperator::Implies, vec![operands[1].clone(), operands[0].clone()]);
+                self.walk(&build(ClassicalOperator::And, vec![forward, backward]), positive, binders, domain, store)
+            }
+            _ => {
+                let operands = operands
+                    .iter()
+                    .enumerate()
+                    .map(|(i, operand)| {
+                        let flips = operator == ClassicalOperator::Not || (operator == ClassicalOperator::Implies && i == 0);
+                        self.walk(operand, positive != flips, binders, domain, store)
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(build(operator, operands))
+            }
+        }
+    }
+}
+
+/// The term replacing variable `/index` under `binders` in the output.
+fn replacement<T, D, F>(index: u32, binders: &[Binder], domain: &F) -> Option<HashNode<F::Term>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    // Index of the variable bound at stack `position`, seen from the innermost binder.
+    let kept_index = |position: usize| binders[position + 1..].iter().filter(|b| matches!(b, Binder::Kept)).count() as u32;
+
+    let Some(position) = binders.len().checked_sub(index as usize + 1) else {
+        // Free: only the removed binders disappear from the count.
+        let removed = binders.iter().filter(|b| matches!(b, Binder::Skolem { .. })).count() as u32;
+        return Some(domain.variable(index - removed));
+    };
+    match &binders[position] {
+        Binder::Kept => Some(domain.variable(kept_index(position))),
+        Binder::Skolem { index, arguments } => {
+            let arguments = arguments.iter().map(|&p| domain.variable(kept_index(p))).collect();
+            domain.skolem(*index, arguments)
+        }
+    }
+}
+
+/// Whether `expr` contains `∀` or `∃`.
+pub fn contains_quantifier<T, D>(expr: &HashNode<ClassicalLogicalExpression<T, D>>) -> bool
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    match expr.value.as_ref() {
+        LogicalExpression::Atomic(_) => false,
+        LogicalExpression::Compound { operator, operands, .. } => {
+            matches!(operator, ClassicalOperator::Forall | ClassicalOperator::Exists) || operands.iter().any(contains_quantifier)
+        }
+    }
+}
+
+/// Skolemize `expr` with a fresh `Skolemizer`.
+pub fn skolemize<T, D, F>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    domain: &F,
+    store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+) -> Option<HashNode<ClassicalLogicalExpression<T, D>>>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    Skolemizer::new().skolemize(expr, domain, store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FoDomain, FoTerm, Formulas};
+
+    #[test]
+    fn test_skolem_functions_take_enclosing_universals() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let p = |a, b| f.pred(0, vec![a, b]);
+
+        // ∀x ∃y P(x, y)  ~>  ∀x P(x, sk0(x))
+        let expr = f.forall(&f.exists(&p(f.var(1), f.var(0))));
+        let mut skolemizer = Skolemizer::new();
+        let result = skolemizer.skolemize(&expr, &domain, &f.store).unwrap();
+
+        let sk = HashNode::from_store(FoTerm::Skolem(0, vec![f.var(0)]), &f.terms);
+        assert_eq!(result, f.forall(&p(f.var(0), sk)));
+        assert_eq!(skolemizer.functions.len(), 1);
+        assert_eq!(skolemizer.functions[0].arity, 1);
+    }
+
+    #[test]
+    fn test_polarity_and_reindexing() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let p = |args| f.pred(0, args);
+
+        // ¬∀x ∀y P(x, y): both universals are negative and become constants.
+        let expr = f.not(&f.forall(&f.forall(&p(vec![f.var(1), f.var(0)]))));
+        let sk = |i| HashNode::from_store(FoTerm::Skolem(i, Vec::new()), &f.terms);
+        let result = skolemize(&expr, &domain, &f.store).unwrap();
+        assert_eq!(result, f.not(&p(vec![sk(0), sk(1)])));
+
+        // ∀x ∃y ∀z P(x, y, z): z keeps index 0, x moves from /2 to /1.
+        let expr = f.forall(&f.exists(&f.forall(&p(vec![f.var(2), f.var(1), f.var(0)]))));
+        let result = skolemize(&expr, &domain, &f.store).unwrap();
+        let y = HashNode::from_store(FoTerm::Skolem(0, vec![f.var(1)]), &f.terms);
+        assert_eq!(result, f.forall(&f.forall(&p(vec![f.var(1), y, f.var(0)]))));
+        assert!(!contains_quantifier(&skolemize(&f.exists(&p(vec![f.var(0)])), &domain, &f.store).unwrap()));
+    }
+
+    #[test]
+    fn test_preserves_satisfiability() {
+        use crate::resolution::{ResolutionFailure, ResolutionProver};
+
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let p = |t| f.pred(0, vec![t]);
+        let prover = ResolutionProver::new(&domain);
+
+        // ∃x P(x) ∧ ∀x ¬P(x) is unsatisfiable before and after.
+        let unsat = f.and(&f.exists(&p(f.var(0))), &f.forall(&f.not(&p(f.var(0)))));
+        let skolemized = skolemize(&unsat, &domain, &f.store).unwrap();
+        assert!(prover.prove(&f.not(&skolemized)).is_ok());
+
+        // ∃x P(x) ∧ ∃x ¬P(x) is satisfiable before and after.
+        let sat = f.and(&f.exists(&p(f.var(0))), &f.exists(&f.not(&p(f.var(0)))));
+        let skolemized = skolemize(&sat, &domain, &f.store).unwrap();
+        assert_eq!(prover.prove(&f.not(&skolemized)).err(), Some(ResolutionFailure::Saturated));
+    }
+}
@@ -0,0 +1,254 @@
+//! Truth tables for the propositional fragment.
+//!
+//! A quantifier-free formula over `BinaryTruth` is decided by evaluating it
+//! under every assignment to its atoms. Atoms are the distinct atomic
+//! subformulas and equalities; hash-consing makes repeated occurrences of the
+//! same atom share one column.
+
+use crate::{BinaryTruth, ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::HashNode;
+use corpus_core::proving::GoalChecker;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// Largest number of atoms `truth_table` will enumerate (2^20 rows).
+pub const MAX_TABLE_ATOMS: usize = 20;
+
+/// Why a formula has no truth table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TruthTableError {
+    /// The formula contains `∀` or `∃`.
+    Quantified,
+    /// A connective has the wrong number of operands.
+    Malformed(ClassicalOperator),
+    /// The formula has more than `MAX_TABLE_ATOMS` atoms.
+    TooManyAtoms(usize),
+}
+
+impl Display for TruthTableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TruthTableError::Quantified => write!(f, "formula is not quantifier-free"),
+            TruthTableError::Malformed(operator) => write!(f, "{} applied to the wrong number of operands", operator),
+            TruthTableError::TooManyAtoms(count) => {
+                write!(f, "formula has {} atoms, more than the limit of {}", count, MAX_TABLE_ATOMS)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TruthTableError {}
+
+/// The value of a formula under every assignment to its atoms.
+///
+/// Row `r` assigns atom `i` the value of bit `i` of `r`.
+pub struct TruthTable<D: DomainContent<BinaryTruth, Operator = ClassicalOperator>> {
+    pub atoms: Vec<HashNode<ClassicalLogicalExpression<BinaryTruth, D>>>,
+    values: Vec<bool>,
+}
+
+impl<D: DomainContent<BinaryTruth, Operator = ClassicalOperator>> TruthTable<D> {
+    /// Value of the formula in row `row`.
+    pub fn value(&self, row: usize) -> bool {
+        self.values[row]
+    }
+
+    /// Assignment to the atoms in row `row`.
+    pub fn assignment(&self, row: usize) -> Vec<bool> {
+        (0..self.atoms.len()).map(|i| row & (1 << i) != 0).collect()
+    }
+
+    /// Each row's assignment together with the formula's value.
+    pub fn rows(&self) -> impl Iterator<Item = (Vec<bool>, bool)> + '_ {
+        self.values.iter().enumerate().map(|(row, &value)| (self.assignment(row), value))
+    }
+
+    /// Assignments under which the formula holds.
+    pub fn models(&self) -> impl Iterator<Item = Vec<bool>> + '_ {
+        self.rows().filter(|(_, value)| *value).map(|(assignment, _)| assignment)
+    }
+
+    pub fn is_tautology(&self) -> bool {
+        self.values.iter().all(|&value| value)
+    }
+
+    pub fn is_satisfiable(&self) -> bool {
+        self.values.iter().any(|&value| value)
+    }
+
+    pub fn is_contradiction(&self) -> bool {
+        !self.is_satisfiable()
+    }
+}
+
+impl<D> Display for TruthTable<D>
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator> + Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for atom in &self.atoms {
+            write!(f, "{} | ", atom)?;
+        }
+        writeln!(f, "value")?;
+        for (assignment, value) in self.rows() {
+            for (atom, bit) in self.atoms.iter().zip(assignment) {
+                let width = atom.to_string().chars().count();
+                write!(f, "{:<width$} | ", if bit { "T" } else { "F" }, width = width)?;
+            }
+            writeln!(f, "{}", if value { "T" } else { "F" })?;
+        }
+        Ok(())
+    }
+}
+
+/// Truth table of the quantifier-free formula `expr`.
+pub fn truth_table<D>(
+    expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+) -> Result<TruthTable<D>, TruthTableError>
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+{
+    let mut atoms = Vec::new();
+    let mut index = HashMap::new();
+    collect_atoms(expr, &mut atoms, &mut index)?;
+    if atoms.len() > MAX_TABLE_ATOMS {
+        return Err(TruthTableError::TooManyAtoms(atoms.len()));
+    }
+
+    let values = (0..1usize << atoms.len())
+        .map(|row| evaluate(expr, &|atom| row & (1 << index[&atom.hash()]) != 0))
+        .collect();
+    Ok(TruthTable { atoms, values })
+}
+
+/// Whether the quantifier-free formula `expr` holds under every assignment.
+pub fn is_tautology<D>(expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>) -> Result<bool, TruthTableError>
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+{
+    truth_table(expr).map(|table| table.is_tautology())
+}
+
+/// Whether the quantifier-free formula `expr` holds under some assignment.
+pub fn is_satisfiable<D>(expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>) -> Result<bool, TruthTableError>
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+{
+    truth_table(expr).map(|table| table.is_satisfiable())
+}
+
+fn collect_atoms<D>(
+    expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+    atoms: &mut Vec<HashNode<ClassicalLogicalExpression<BinaryTruth, D>>>,
+    index: &mut HashMap<u64, usize>,
+) -> Result<(), TruthTableError>
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+{
+    match expr.value.as_ref() {
+        LogicalExpression::Compound { operator, operands, .. } if *operator != ClassicalOperator::Equals => {
+            match operator {
+                ClassicalOperator::Forall | ClassicalOperator::Exists => return Err(TruthTableError::Quantified),
+                _ if operands.len() != operator.arity() => return Err(TruthTableError::Malformed(*operator)),
+                _ => {}
+            }
+            operands.iter().try_for_each(|operand| collect_atoms(operand, atoms, index))
+        }
+        _ => {
+            index.entry(expr.hash()).or_insert_with(|| {
+                atoms.push(expr.clone());
+                atoms.len() - 1
+            });
+            Ok(())
+        }
+    }
+}
+
+/// Value of a formula already checked by `collect_atoms`, given the values of its atoms.
+fn evaluate<D>(
+    expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+    atom: &impl Fn(&HashNode<ClassicalLogicalExpression<BinaryTruth, D>>) -> bool,
+) -> bool
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+{
+    let LogicalExpression::Compound { operator, operands, .. } = expr.value.as_ref() else {
+        return atom(expr);
+    };
+    let value = |i: usize| evaluate(&operands[i], atom);
+    match operator {
+        ClassicalOperator::Equals => atom(expr),
+        ClassicalOperator::Not => !value(0),
+        ClassicalOperator::And => value(0) && value(1),
+        ClassicalOperator::Or => value(0) || value(1),
+        ClassicalOperator::Implies => !value(0) || value(1),
+        ClassicalOperator::Iff => value(0) == value(1),
+        ClassicalOperator::Forall | ClassicalOperator::Exists => unreachable!("rejected by collect_atoms"),
+    }
+}
+
+/// Goal checker deciding the propositional fragment by truth tables.
+///
+/// Tautologies check as true and contradictions as false. Contingent
+/// formulas, and formulas without a truth table, are left to the search.
+pub struct ClassicalTruthChecker;
+
+impl<D> GoalChecker<ClassicalLogicalExpression<BinaryTruth, D>, BinaryTruth> for ClassicalTruthChecker
+where
+    D: DomainContent<BinaryTruth, Operator = ClassicalOperator>,
+{
+    fn check(&self, expr: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>) -> Option<BinaryTruth> {
+        let table = truth_table(expr).ok()?;
+        if table.is_tautology() {
+            Some(BinaryTruth::True)
+        } else if table.is_contradiction() {
+            Some(BinaryTruth::False)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Formulas;
+
+    #[test]
+    fn test_classical_tautologies() {
+        let f = Formulas::new();
+        let (p, q) = (f.atom(0), f.atom(1));
+
+        let excluded_middle = f.or(&p, &f.not(&p));
+        let peirce = f.implies(&f.implies(&f.implies(&p, &q), &p), &p);
+        let contraposition = f.iff(&f.implies(&p, &q), &f.implies(&f.not(&q), &f.not(&p)));
+        for expr in [&excluded_middle, &peirce, &contraposition] {
+            assert_eq!(is_tautology(expr), Ok(true), "{}", expr);
+        }
+
+        assert_eq!(is_tautology(&f.implies(&p, &q)), Ok(false));
+        assert_eq!(is_satisfiable(&f.and(&p, &f.not(&p))), Ok(false));
+        assert_eq!(is_tautology(&f.forall(&excluded_middle)), Err(TruthTableError::Quantified));
+    }
+
+    #[test]
+    fn test_repeated_atoms_share_a_column() {
+        let f = Formulas::new();
+        let (p, q) = (f.atom(0), f.atom(1));
+        let table = truth_table(&f.and(&f.or(&p, &q), &f.not(&p))).unwrap();
+
+        assert_eq!(table.atoms.len(), 2);
+        assert_eq!(table.models().collect::<Vec<_>>(), vec![vec![false, true]]);
+    }
+
+    #[test]
+    fn test_checker_decides_tautologies_and_contradictions() {
+        let f = Formulas::new();
+        let p = f.atom(0);
+
+        assert_eq!(ClassicalTruthChecker.check(&f.implies(&p, &p)), Some(BinaryTruth::True));
+        assert_eq!(ClassicalTruthChecker.check(&f.iff(&p, &f.not(&p))), Some(BinaryTruth::False));
+        assert_eq!(ClassicalTruthChecker.check(&p), None);
+    }
+}
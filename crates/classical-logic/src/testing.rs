@@ -0,0 +1,273 @@
+//! Atoms, terms and formula builders used by the classical-logic tests.
+
+use crate::first_order::{skolem_opcode, FirstOrderDomain};
+use crate::{BinaryTruth, ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::expression::DomainContent;
+use corpus_core::nodes::{HashNode, HashNodeInner, Hashing, NodeStorage};
+use std::fmt::{Display, Formatter};
+
+/// A propositional variable `p<n>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prop(pub u32);
+
+impl HashNodeInner for Prop {
+    fn hash(&self) -> u64 {
+        Hashing::root_hash(Hashing::opcode("prop"), &[u64::from(self.0)])
+    }
+
+    fn size(&self) -> u64 {
+        1
+    }
+}
+
+impl DomainContent<BinaryTruth> for Prop {
+    type Operator = ClassicalOperator;
+}
+
+impl Display for Prop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "p{}", self.0)
+    }
+}
+
+pub type Formula = ClassicalLogicalExpression<BinaryTruth, Prop>;
+
+/// Largest function or predicate symbol the opcode decoders search for.
+const MAX_SYMBOL: u32 = 64;
+
+/// A first-order term: de Bruijn variables `/n`, constants `c<n>`, function
+/// applications `f<n>(..)` and Skolem functions `sk<n>(..)`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FoTerm {
+    Var(u32),
+    Const(u32),
+    App(u32, Vec<HashNode<FoTerm>>),
+    Skolem(u32, Vec<HashNode<FoTerm>>),
+}
+
+pub fn function_opcode(symbol: u32) -> u64 {
+    Hashing::root_hash(Hashing::opcode("function"), &[u64::from(symbol)])
+}
+
+pub fn predicate_opcode(symbol: u32) -> u64 {
+    Hashing::root_hash(Hashing::opcode("predicate"), &[u64::from(symbol)])
+}
+
+fn decode(opcode: u64, encode: fn(u32) -> u64) -> Option<u32> {
+    (0..MAX_SYMBOL).find(|&symbol| encode(symbol) == opcode)
+}
+
+impl HashNodeInner for FoTerm {
+    fn hash(&self) -> u64 {
+        match self {
+            FoTerm::Var(i) => Hashing::root_hash(Hashing::opcode("var"), &[u64::from(*i)]),
+            FoTerm::Const(c) => Hashing::root_hash(Hashing::opcode("const"), &[u64::from(*c)]),
+            FoTerm::App(f, args) => {
+                Hashing::root_hash(function_opcode(*f), &args.iter().map(|a| a.hash()).collect::<Vec<_>>())
+            }
+            FoTerm::Skolem(f, args) => {
+                Hashing::root_hash(skolem_opcode(*f), &args.iter().map(|a| a.hash()).collect::<Vec<_>>())
+            }
+        }
+    }
+
+    fn size(&self) -> u64 {
+        match self {
+            FoTerm::Var(_) | FoTerm::Const(_) => 1,
+            FoTerm::App(_, args) | FoTerm::Skolem(_, args) => 1 + args.iter().map(|a| a.size()).sum::<u64>(),
+        }
+    }
+
+    fn decompose(&self) -> Option<(u64, Vec<HashNode<Self>>)> {
+        match self {
+            FoTerm::App(f, args) => Some((function_opcode(*f), args.clone())),
+            FoTerm::Skolem(f, args) => Some((skolem_opcode(*f), args.clone())),
+            FoTerm::Var(_) | FoTerm::Const(_) => None,
+        }
+    }
+
+    fn construct_from_parts(opcode: u64, children: Vec<HashNode<Self>>, store: &NodeStorage<Self>) -> Option<HashNode<Self>> {
+        if let Some(f) = decode(opcode, function_opcode) {
+            return Some(HashNode::from_store(FoTerm::App(f, children), store));
+        }
+        decode(opcode, skolem_opcode).map(|f| HashNode::from_store(FoTerm::Skolem(f, children), store))
+    }
+}
+
+impl Display for FoTerm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (name, args) = match self {
+            FoTerm::Var(i) => return write!(f, "/{}", i),
+            FoTerm::Const(c) => return write!(f, "c{}", c),
+            FoTerm::App(g, args) => (format!("f{}", g), args),
+            FoTerm::Skolem(g, args) => (format!("sk{}", g), args),
+        };
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        write!(f, "{}({})", name, args.join(", "))
+    }
+}
+
+/// A first-order atom `P<n>(..)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pred(pub u32, pub Vec<HashNode<FoTerm>>);
+
+impl HashNodeInner for Pred {
+    fn hash(&self) -> u64 {
+        Hashing::root_hash(predicate_opcode(self.0), &self.1.iter().map(|a| a.hash()).collect::<Vec<_>>())
+    }
+
+    fn size(&self) -> u64 {
+        1 + self.1.iter().map(|a| a.size()).sum::<u64>()
+    }
+}
+
+impl DomainContent<BinaryTruth> for Pred {
+    type Operator = ClassicalOperator;
+}
+
+impl Display for Pred {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let args: Vec<String> = self.1.iter().map(|a| a.to_string()).collect();
+        write!(f, "P{}({})", self.0, args.join(", "))
+    }
+}
+
+pub type FoFormula = ClassicalLogicalExpression<BinaryTruth, Pred>;
+
+/// Storage for atoms, terms and formulas, with constructors for each operator.
+pub struct Formulas<D: DomainContent<BinaryTruth, Operator = ClassicalOperator>> {
+    pub atoms: NodeStorage<D>,
+    pub terms: NodeStorage<FoTerm>,
+    pub store: NodeStorage<ClassicalLogicalExpression<BinaryTruth, D>>,
+}
+
+impl Formulas<Prop> {
+    pub fn new() -> Self {
+        Self {
+            atoms: NodeStorage::new(),
+            terms: NodeStorage::new(),
+            store: NodeStorage::new(),
+        }
+    }
+
+    pub fn atom(&self, index: u32) -> HashNode<Formula> {
+        let prop = HashNode::from_store(Prop(index), &self.atoms);
+        HashNode::from_store(Formula::atomic(prop), &self.store)
+    }
+}
+
+impl Formulas<Pred> {
+    pub fn first_order() -> Self {
+        Self {
+            atoms: NodeStorage::new(),
+            terms: NodeStorage::new(),
+            store: NodeStorage::new(),
+        }
+    }
+
+    pub fn var(&self, index: u32) -> HashNode<FoTerm> {
+        HashNode::from_store(FoTerm::Var(index), &self.terms)
+    }
+
+    pub fn constant(&self, index: u32) -> HashNode<FoTerm> {
+        HashNode::from_store(FoTerm::Const(index), &self.terms)
+    }
+
+    pub fn app(&self, symbol: u32, args: Vec<HashNode<FoTerm>>) -> HashNode<FoTerm> {
+        HashNode::from_store(FoTerm::App(symbol, args), &self.terms)
+    }
+
+    pub fn pred(&self, symbol: u32, args: Vec<HashNode<FoTerm>>) -> HashNode<FoFormula> {
+        let atom = HashNode::from_store(Pred(symbol, args), &self.atoms);
+        HashNode::from_store(FoFormula::atomic(atom), &self.store)
+    }
+}
+
+impl<D: DomainContent<BinaryTruth, Operator = ClassicalOperator>> Formulas<D> {
+    pub fn compound(
+        &self,
+        operator: ClassicalOperator,
+        operands: Vec<HashNode<ClassicalLogicalExpression<BinaryTruth, D>>>,
+    ) -> HashNode<ClassicalLogicalExpression<BinaryTruth, D>> {
+        HashNode::from_store(ClassicalLogicalExpression::compound(operator, operands), &self.store)
+    }
+
+    pub fn not(&self, a: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>) -> HashNode<ClassicalLogicalExpression<BinaryTruth, D>> {
+        self.compound(ClassicalOperator::Not, vec![a.clone()])
+    }
+
+    pub fn and(
+        &self,
+        a: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+        b: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+    ) -> HashNode<ClassicalLogicalExpression<BinaryTruth, D>> {
+        self.compound(ClassicalOperator::And, vec![a.clone(), b.clone()])
+    }
+
+    pub fn or(
+        &self,
+        a: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+        b: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+    ) -> HashNode<ClassicalLogicalExpression<BinaryTruth, D>> {
+        self.compound(ClassicalOperator::Or, vec![a.clone(), b.clone()])
+    }
+
+    pub fn implies(
+        &self,
+        a: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+        b: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+    ) -> HashNode<ClassicalLogicalExpression<BinaryTruth, D>> {
+        self.compound(ClassicalOperator::Implies, vec![a.clone(), b.clone()])
+    }
+
+    pub fn iff(
+        &self,
+        a: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+        b: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>,
+    ) -> HashNode<ClassicalLogicalExpression<BinaryTruth, D>> {
+        self.compound(ClassicalOperator::Iff, vec![a.clone(), b.clone()])
+    }
+
+    pub fn forall(&self, body: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>) -> HashNode<ClassicalLogicalExpression<BinaryTruth, D>> {
+        self.compound(ClassicalOperator::Forall, vec![body.clone()])
+    }
+
+    pub fn exists(&self, body: &HashNode<ClassicalLogicalExpression<BinaryTruth, D>>) -> HashNode<ClassicalLogicalExpression<BinaryTruth, D>> {
+        self.compound(ClassicalOperator::Exists, vec![body.clone()])
+    }
+}
+
+/// `FirstOrderDomain` for `Pred` atoms over `FoTerm`s, using the term store of a `Formulas`.
+pub struct FoDomain<'a>(pub &'a Formulas<Pred>);
+
+impl FirstOrderDomain<BinaryTruth, Pred> for FoDomain<'_> {
+    type Term = FoTerm;
+
+    fn predicate(&self, atom: &HashNode<Pred>) -> Option<(u64, Vec<HashNode<FoTerm>>)> {
+        Some((predicate_opcode(atom.value.0), atom.value.1.clone()))
+    }
+
+    fn atom(&self, predicate: u64, arguments: Vec<HashNode<FoTerm>>) -> Option<HashNode<Pred>> {
+        let symbol = decode(predicate, predicate_opcode)?;
+        Some(HashNode::from_store(Pred(symbol, arguments), &self.0.atoms))
+    }
+
+    fn bound_index(&self, term: &HashNode<FoTerm>) -> Option<u32> {
+        match term.value.as_ref() {
+            FoTerm::Var(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    fn variable(&self, index: u32) -> HashNode<FoTerm> {
+        self.0.var(index)
+    }
+
+    fn skolem(&self, index: u32, arguments: Vec<HashNode<FoTerm>>) -> Option<HashNode<FoTerm>> {
+        Some(HashNode::from_store(FoTerm::Skolem(index, arguments), &self.0.terms))
+    }
+
+    fn terms(&self) -> &NodeStorage<FoTerm> {
+        &self.0.terms
+    }
+}
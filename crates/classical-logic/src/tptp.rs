@@ -0,0 +1,650 @@
+//! Reading and writing TPTP first-order form.
+//!
+//! A TPTP problem is a sequence of annotated formulas `fof(name, role, φ).`,
+//! with `%` and `/* … */` comments. Formulas use the connectives
+//! `~ & | => <= <=> <~> ~| ~&` and the quantifiers `! [X, Y] : φ` and
+//! `? [X] : φ`; atoms are predicate applications `p(t, …)` and equations
+//! `s = t` or `s != t`.
+//!
+//! Atoms and terms are built and taken apart through a `FirstOrderDomain`,
+//! whose predicate and function symbols are opcodes. A `TptpSignature` names
+//! them. Named variables become de Bruijn indices when read; when written,
+//! the variable of the quantifier at nesting depth `d` is named `X<d>`.
+//! First-order form has no free variables, so reading or writing an open
+//! formula is an error.
+
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+use crate::first_order::FirstOrderDomain;
+use crate::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner, NodeStorage};
+use corpus_core::truth::TruthValue;
+
+/// The name of the equality predicate in a `TptpSignature`.
+pub const EQUALITY: &str = "=";
+
+/// Names for the symbols of a domain: predicate and function opcodes, and
+/// constants, which are terms without an opcode.
+pub struct TptpSignature<Term: HashNodeInner> {
+    predicates: Vec<(String, u64)>,
+    functions: Vec<(String, u64)>,
+    constants: Vec<(String, HashNode<Term>)>,
+}
+
+impl<Term: HashNodeInner> Default for TptpSignature<Term> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Term: HashNodeInner> TptpSignature<Term> {
+    pub fn new() -> Self {
+        Self {
+            predicates: Vec::new(),
+            functions: Vec::new(),
+            constants: Vec::new(),
+        }
+    }
+
+    /// Name the predicate `opcode`. Naming one `EQUALITY` reads and writes
+    /// it as the infix `=`.
+    pub fn predicate(mut self, name: impl Into<String>, opcode: u64) -> Self {
+        self.predicates.push((name.into(), opcode));
+        self
+    }
+
+    /// Name the function `opcode`; nullary functions are written as constants.
+    pub fn function(mut self, name: impl Into<String>, opcode: u64) -> Self {
+        self.functions.push((name.into(), opcode));
+        self
+    }
+
+    /// Name the constant `term`.
+    pub fn constant(mut self, name: impl Into<String>, term: HashNode<Term>) -> Self {
+        self.constants.push((name.into(), term));
+        self
+    }
+}
+
+/// The symbol named `name`.
+fn lookup<'a, V>(symbols: &'a [(String, V)], name: &str) -> Option<&'a V> {
+    symbols.iter().find(|(symbol, _)| symbol == name).map(|(_, value)| value)
+}
+
+/// The name of the symbol `value`.
+fn name_of<V: PartialEq>(symbols: &[(String, V)], value: &V) -> Option<String> {
+    symbols.iter().find(|(_, symbol)| symbol == value).map(|(name, _)| name.clone())
+}
+
+/// A formula of a TPTP problem with its name and role, e.g. `axiom` or
+/// `conjecture`.
+pub struct AnnotatedFormula<T, D>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    pub name: String,
+    pub role: String,
+    pub formula: HashNode<ClassicalLogicalExpression<T, D>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Dot,
+    Colon,
+    Not,
+    And,
+    Or,
+    Nand,
+    Nor,
+    Implies,
+    /// `<=`, implication from right to left.
+    Implied,
+    Iff,
+    Xor,
+    Eq,
+    Neq,
+    Forall,
+    Exists,
+    /// A symbol, role or name, starting with a lower-case letter or digit.
+    Word(String),
+    /// A variable, starting with an upper-case letter.
+    Variable(String),
+    /// A single-quoted name.
+    Quoted(String),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '%' => {
+                chars.by_ref().find(|&c| c == '\n');
+                continue;
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                continue;
+            }
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            ',' => Token::Comma,
+            '.' => Token::Dot,
+            ':' => Token::Colon,
+            '&' => Token::And,
+            '|' => Token::Or,
+            '?' => Token::Exists,
+            '~' if chars.next_if_eq(&'&').is_some() => Token::Nand,
+            '~' if chars.next_if_eq(&'|').is_some() => Token::Nor,
+            '~' => Token::Not,
+            '!' if chars.next_if_eq(&'=').is_some() => Token::Neq,
+            '!' => Token::Forall,
+            '=' if chars.next_if_eq(&'>').is_some() => Token::Implies,
+            '=' => Token::Eq,
+            '<' if chars.next_if_eq(&'~').is_some() => {
+                if chars.next_if_eq(&'>').is_none() {
+                    return Err("Expected '<~>'".to_string());
+                }
+                Token::Xor
+            }
+            '<' if chars.next_if_eq(&'=').is_some() => match chars.next_if_eq(&'>') {
+                Some(_) => Token::Iff,
+                None => Token::Implied,
+            },
+            '\'' => Token::Quoted(chars.by_ref().take_while(|&c| c != '\'').collect()),
+            c if c.is_alphanumeric() || c == '$' => {
+                let mut word = c.to_string();
+                while let Some(c) = chars.next_if(|&c| c.is_alphanumeric() || c == '_') {
+                    word.push(c);
+                }
+                if c.is_uppercase() {
+                    Token::Variable(word)
+                } else {
+                    Token::Word(word)
+                }
+            }
+            c => return Err(format!("Unexpected character '{}'", c)),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a, T, D, F>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    tokens: Peekable<IntoIter<Token>>,
+    signature: &'a TptpSignature<F::Term>,
+    domain: &'a F,
+    store: &'a NodeStorage<ClassicalLogicalExpression<T, D>>,
+    /// Variables bound by the enclosing quantifiers, innermost last.
+    bound: Vec<String>,
+}
+
+impl<T, D, F> Parser<'_, T, D, F>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    fn next(&mut self) -> Result<Token, String> {
+        self.tokens.next().ok_or_else(|| "Unexpected end of input".to_string())
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next()? {
+            token if token == expected => Ok(()),
+            token => Err(format!("Expected {:?}, found {:?}", expected, token)),
+        }
+    }
+
+    fn annotated_formulas(&mut self) -> Result<Vec<AnnotatedFormula<T, D>>, String> {
+        let mut formulas = Vec::new();
+        while self.tokens.peek().is_some() {
+            match self.next()? {
+                Token::Word(language) if language == "fof" => {}
+                token => return Err(format!("Expected 'fof', found {:?}", token)),
+            }
+            self.expect(Token::LParen)?;
+            let name = match self.next()? {
+                Token::Word(name) | Token::Quoted(name) => name,
+                token => return Err(format!("Expected a formula name, found {:?}", token)),
+            };
+            self.expect(Token::Comma)?;
+            let role = match self.next()? {
+                Token::Word(role) => role,
+                token => return Err(format!("Expected the role of {}, found {:?}", name, token)),
+            };
+            self.expect(Token::Comma)?;
+            let formula = self.formula()?;
+
+            // Annotations, such as the source of the formula, are skipped.
+            let mut depth = 0;
+            while depth > 0 || self.tokens.peek() != Some(&Token::RParen) {
+                match self.next()? {
+                    Token::LParen | Token::LBracket => depth += 1,
+                    Token::RParen | Token::RBracket => depth -= 1,
+                    _ => {}
+                }
+            }
+            self.expect(Token::RParen)?;
+            self.expect(Token::Dot)?;
+            formulas.push(AnnotatedFormula { name, role, formula });
+        }
+        Ok(formulas)
+    }
+
+    fn formula(&mut self) -> Result<HashNode<ClassicalLogicalExpression<T, D>>, String> {
+        let left = self.unitary()?;
+        let connective = match self.tokens.peek() {
+            Some(token @ (Token::And | Token::Or)) => token.clone(),
+            Some(Token::Implies | Token::Implied | Token::Iff | Token::Xor | Token::Nand | Token::Nor) => {
+                let connective = self.next()?;
+                let right = self.unitary()?;
+                return Ok(match connective {
+                    Token::Implies => self.compound(ClassicalOperator::Implies, vec![left, right]),
+                    Token::Implied => self.compound(ClassicalOperator::Implies, vec![right, left]),
+                    Token::Iff => self.compound(ClassicalOperator::Iff, vec![left, right]),
+                    Token::Xor => self.negated(ClassicalOperator::Iff, vec![left, right]),
+                    Token::Nand => self.negated(ClassicalOperator::And, vec![left, right]),
+                    _ => self.negated(ClassicalOperator::Or, vec![left, right]),
+                });
+            }
+            _ => return Ok(left),
+        };
+
+        // `&` and `|` associate, but do not mix without parentheses.
+        let operator = if connective == Token::And {
+            ClassicalOperator::And
+        } else {
+            ClassicalOperator::Or
+        };
+        let mut operands = vec![left];
+        while self.tokens.peek() == Some(&connective) {
+            self.tokens.next();
+            operands.push(self.unitary()?);
+        }
+        Ok(operands
+            .into_iter()
+            .reduce(|left, right| self.compound(operator, vec![left, right]))
+            .expect("at least one operand"))
+    }
+
+    fn unitary(&mut self) -> Result<HashNode<ClassicalLogicalExpression<T, D>>, String> {
+        match self.tokens.peek() {
+            Some(Token::Not) => {
+                self.tokens.next();
+                let inner = self.unitary()?;
+                Ok(self.compound(ClassicalOperator::Not, vec![inner]))
+            }
+            Some(Token::Forall) => {
+                self.tokens.next();
+                self.quantified(ClassicalOperator::Forall)
+            }
+            Some(Token::Exists) => {
+                self.tokens.next();
+                self.quantified(ClassicalOperator::Exists)
+            }
+            Some(Token::LParen) => {
+                self.tokens.next();
+                let formula = self.formula()?;
+                self.expect(Token::RParen)?;
+                Ok(formula)
+            }
+            _ => self.atom(),
+        }
+    }
+
+    /// Parse `[X, Y] : body` after a quantifier.
+    fn quantified(&mut self, quantifier: ClassicalOperator) -> Result<HashNode<ClassicalLogicalExpression<T, D>>, String> {
+        self.expect(Token::LBracket)?;
+        let mut count = 0;
+        loop {
+            match self.next()? {
+                Token::Variable(name) => self.bound.push(name),
+                token => return Err(format!("Expected a variable, found {:?}", token)),
+            }
+            count += 1;
+            match self.next()? {
+                Token::Comma => {}
+                Token::RBracket => break,
+                token => return Err(format!("Expected ',' or ']', found {:?}", token)),
+            }
+        }
+        self.expect(Token::Colon)?;
+
+        let mut body = self.unitary()?;
+        for _ in 0..count {
+            self.bound.pop();
+            body = self.compound(quantifier, vec![body]);
+        }
+        Ok(body)
+    }
+
+    fn atom(&mut self) -> Result<HashNode<ClassicalLogicalExpression<T, D>>, String> {
+        if let Some(Token::Word(name)) = self.tokens.peek() {
+            if let Some(&opcode) = lookup(&self.signature.predicates, name) {
+                let name = name.clone();
+                self.tokens.next();
+                let arguments = self.arguments()?;
+                return self.relation(&name, opcode, arguments);
+            }
+        }
+
+        let left = self.term()?;
+        let negated = match self.next()? {
+            Token::Eq => false,
+            Token::Neq => true,
+            token => return Err(format!("Expected '=' or '!=' after a term, found {:?}", token)),
+        };
+        let right = self.term()?;
+        let opcode = *lookup(&self.signature.predicates, EQUALITY)
+            .ok_or("Equality is not in the signature")?;
+        let equation = self.relation(EQUALITY, opcode, vec![left, right])?;
+        Ok(match negated {
+            true => self.compound(ClassicalOperator::Not, vec![equation]),
+            false => equation,
+        })
+    }
+
+    fn term(&mut self) -> Result<HashNode<F::Term>, String> {
+        let name = match self.next()? {
+            Token::Variable(name) => {
+                let position = self
+                    .bound
+                    .iter()
+                    .rposition(|bound| *bound == name)
+                    .ok_or_else(|| format!("Unbound variable {}", name))?;
+                return Ok(self.domain.variable((self.bound.len() - 1 - position) as u32));
+            }
+            Token::Word(name) | Token::Quoted(name) => name,
+            token => return Err(format!("Expected a term, found {:?}", token)),
+        };
+        let arguments = self.arguments()?;
+        if arguments.is_empty() {
+            if let Some(constant) = lookup(&self.signature.constants, &name) {
+                return Ok(constant.clone());
+            }
+        }
+        let &opcode = lookup(&self.signature.functions, &name)
+            .ok_or_else(|| format!("Unknown function symbol '{}'", name))?;
+        F::Term::construct_from_parts(opcode, arguments, self.domain.terms())
+            .ok_or_else(|| format!("The domain has no term {}", name))
+    }
+
+    /// Parse the arguments of a symbol, if it is applied to any.
+    fn arguments(&mut self) -> Result<Vec<HashNode<F::Term>>, String> {
+        if self.tokens.peek() != Some(&Token::LParen) {
+            return Ok(Vec::new());
+        }
+        self.tokens.next();
+        let mut arguments = vec![self.term()?];
+        while self.tokens.peek() == Some(&Token::Comma) {
+            self.tokens.next();
+            arguments.push(self.term()?);
+        }
+        self.expect(Token::RParen)?;
+        Ok(arguments)
+    }
+
+    fn relation(
+        &self,
+        name: &str,
+        opcode: u64,
+        arguments: Vec<HashNode<F::Term>>,
+    ) -> Result<HashNode<ClassicalLogicalExpression<T, D>>, String> {
+        let atom = self.domain.atom(opcode, arguments).ok_or_else(|| format!("The domain has no atom {}", name))?;
+        Ok(HashNode::from_store(LogicalExpression::atomic(atom), self.store))
+    }
+
+    fn compound(
+        &self,
+        operator: ClassicalOperator,
+        operands: Vec<HashNode<ClassicalLogicalExpression<T, D>>>,
+    ) -> HashNode<ClassicalLogicalExpression<T, D>> {
+        HashNode::from_store(LogicalExpression::compound(operator, operands), self.store)
+    }
+
+    fn negated(
+        &self,
+        operator: ClassicalOperator,
+        operands: Vec<HashNode<ClassicalLogicalExpression<T, D>>>,
+    ) -> HashNode<ClassicalLogicalExpression<T, D>> {
+        let inner = self.compound(operator, operands);
+        self.compound(ClassicalOperator::Not, vec![inner])
+    }
+}
+
+fn parser<'a, T, D, F>(
+    text: &str,
+    signature: &'a TptpSignature<F::Term>,
+    domain: &'a F,
+    store: &'a NodeStorage<ClassicalLogicalExpression<T, D>>,
+) -> Result<Parser<'a, T, D, F>, String>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    Ok(Parser {
+        tokens: tokenize(text)?.into_iter().peekable(),
+        signature,
+        domain,
+        store,
+        bound: Vec::new(),
+    })
+}
+
+/// Read the annotated formulas of the TPTP problem `text`.
+pub fn parse_problem<T, D, F>(
+    text: &str,
+    signature: &TptpSignature<F::Term>,
+    domain: &F,
+    store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+) -> Result<Vec<AnnotatedFormula<T, D>>, String>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    parser(text, signature, domain, store)?.annotated_formulas()
+}
+
+/// Read a single closed formula, e.g. `! [X] : p(X)`.
+pub fn parse_formula<T, D, F>(
+    text: &str,
+    signature: &TptpSignature<F::Term>,
+    domain: &F,
+    store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+) -> Result<HashNode<ClassicalLogicalExpression<T, D>>, String>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    let mut parser = parser(text, signature, domain, store)?;
+    let formula = parser.formula()?;
+    match parser.tokens.next() {
+        Some(token) => Err(format!("Unexpected {:?} after the formula", token)),
+        None => Ok(formula),
+    }
+}
+
+/// Write `expr` in first-order form.
+///
+/// Fails if `expr` has a free variable, or a symbol `signature` does not name.
+pub fn format_formula<T, D, F>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    signature: &TptpSignature<F::Term>,
+    domain: &F,
+) -> Result<String, String>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    formula_at(expr, signature, domain, 0)
+}
+
+/// Write `formula` as `fof(name, role, φ).`
+pub fn format_annotated<T, D, F>(
+    formula: &AnnotatedFormula<T, D>,
+    signature: &TptpSignature<F::Term>,
+    domain: &F,
+) -> Result<String, String>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    let body = format_formula(&formula.formula, signature, domain)?;
+    Ok(format!("fof({}, {}, {}).", formula.name, formula.role, body))
+}
+
+fn formula_at<T, D, F>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    signature: &TptpSignature<F::Term>,
+    domain: &F,
+    depth: u32,
+) -> Result<String, String>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    let (operator, operands) = match expr.value.as_ref() {
+        LogicalExpression::Atomic(atom) => return format_atom(atom, signature, domain, depth),
+        LogicalExpression::Compound { operator, operands, .. } => (operator, operands),
+    };
+    let operand = |index: usize, depth| match operands.get(index) {
+        Some(operand) => formula_at(operand, signature, domain, depth),
+        None => Err(format!("{} is missing an operand", operator)),
+    };
+    let connective = match operator {
+        ClassicalOperator::Not => return Ok(format!("~ {}", operand(0, depth)?)),
+        ClassicalOperator::Forall => return Ok(format!("! [X{}] : {}", depth, operand(0, depth + 1)?)),
+        ClassicalOperator::Exists => return Ok(format!("? [X{}] : {}", depth, operand(0, depth + 1)?)),
+        ClassicalOperator::And => " & ",
+        ClassicalOperator::Or => " | ",
+        ClassicalOperator::Implies => " => ",
+        ClassicalOperator::Iff | ClassicalOperator::Equals => " <=> ",
+    };
+    let operands = (0..operands.len()).map(|index| operand(index, depth)).collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("({})", operands.join(connective)))
+}
+
+fn format_atom<T, D, F>(atom: &HashNode<D>, signature: &TptpSignature<F::Term>, domain: &F, depth: u32) -> Result<String, String>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    let (predicate, arguments) = domain.predicate(atom).ok_or("An atom has no first-order structure")?;
+    let name = name_of(&signature.predicates, &predicate).ok_or("Unnamed predicate symbol")?;
+    let arguments = arguments.iter().map(|argument| format_term(argument, signature, domain, depth)).collect::<Result<Vec<_>, _>>()?;
+    match arguments.as_slice() {
+        [left, right] if name == EQUALITY => Ok(format!("{} = {}", left, right)),
+        [] => Ok(name),
+        _ => Ok(format!("{}({})", name, arguments.join(", "))),
+    }
+}
+
+fn format_term<T, D, F>(term: &HashNode<F::Term>, signature: &TptpSignature<F::Term>, domain: &F, depth: u32) -> Result<String, String>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    if let Some(index) = domain.bound_index(term) {
+        return match depth.checked_sub(index + 1) {
+            Some(binder) => Ok(format!("X{}", binder)),
+            None => Err(format!("Free variable /{}", index)),
+        };
+    }
+    if let Some(name) = name_of(&signature.constants, term) {
+        return Ok(name);
+    }
+    let (opcode, arguments) = term.value.decompose().ok_or("Unnamed constant")?;
+    let name = name_of(&signature.functions, &opcode).ok_or("Unnamed function symbol")?;
+    if arguments.is_empty() {
+        return Ok(name);
+    }
+    let arguments = arguments.iter().map(|argument| format_term(argument, signature, domain, depth)).collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("{}({})", name, arguments.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{function_opcode, predicate_opcode, FoDomain, Formulas};
+
+    #[test]
+    fn test_round_trip() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let signature = TptpSignature::new()
+            .predicate("p", predicate_opcode(0))
+            .predicate("q", predicate_opcode(1))
+            .predicate(EQUALITY, predicate_opcode(2))
+            .function("f", function_opcode(0))
+            .constant("a", f.constant(0));
+
+        let problem = "\
+% Every p has a q-successor.
+fof(ax, axiom, ! [X] : (p(X) => ? [Y] : q(f(X), Y)), file('x.p', ax)).
+/* The conjecture. */
+fof(goal, conjecture, ~ (p(a) & ! [Z, W] : (Z != W | q(Z, a)))).
+";
+        let formulas = parse_problem(problem, &signature, &domain, &f.store).unwrap();
+        assert_eq!(formulas.len(), 2);
+        assert_eq!((formulas[1].name.as_str(), formulas[1].role.as_str()), ("goal", "conjecture"));
+
+        // ∀x. p(x) → ∃y. q(f(x), y)
+        let x = f.var(1);
+        let body = f.pred(1, vec![f.app(0, vec![x.clone()]), f.var(0)]);
+        let expected = f.forall(&f.implies(&f.pred(0, vec![f.var(0)]), &f.exists(&body)));
+        assert_eq!(formulas[0].formula, expected);
+
+        let written = format_annotated(&formulas[0], &signature, &domain).unwrap();
+        assert_eq!(written, "fof(ax, axiom, ! [X0] : (p(X0) => ? [X1] : q(f(X0), X1))).");
+        let written = format_formula(&formulas[1].formula, &signature, &domain).unwrap();
+        assert_eq!(written, "~ (p(a) & ! [X0] : ! [X1] : (~ X0 = X1 | q(X0, a)))");
+        assert_eq!(parse_formula(&written, &signature, &domain, &f.store), Ok(formulas[1].formula.clone()));
+    }
+
+    #[test]
+    fn test_errors() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let signature = TptpSignature::new().predicate("p", predicate_opcode(0));
+
+        let parse = |text| parse_formula(text, &signature, &domain, &f.store).err();
+        assert_eq!(parse("p(X)"), Some("Unbound variable X".to_string()));
+        assert_eq!(parse("! [X] : p(g(X))"), Some("Unknown function symbol 'g'".to_string()));
+        assert!(parse("! [X] : X = X").is_some());
+        assert!(parse("p & p p").is_some());
+
+        // Open formulas have no first-order form.
+        assert_eq!(format_formula(&f.pred(0, vec![f.var(0)]), &signature, &domain), Err("Free variable /0".to_string()));
+    }
+}
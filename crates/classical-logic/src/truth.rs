@@ -141,3 +141,138 @@ impl HashNodeInner for BinaryTruth {
         1
     }
 }
+
+/// Three-valued truth values with strong Kleene connectives.
+///
+/// `Unknown` sits between `False` and `True`: conjunction is the minimum,
+/// disjunction the maximum and negation swaps the ends, so a connective is
+/// determined whenever its known operands already settle it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum KleeneTruth {
+    False,
+    #[default]
+    Unknown,
+    True,
+}
+
+impl Display for KleeneTruth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KleeneTruth::True => write!(f, "true"),
+            KleeneTruth::False => write!(f, "false"),
+            KleeneTruth::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl From<bool> for KleeneTruth {
+    fn from(value: bool) -> Self {
+        if value {
+            KleeneTruth::True
+        } else {
+            KleeneTruth::False
+        }
+    }
+}
+
+impl From<Option<bool>> for KleeneTruth {
+    fn from(value: Option<bool>) -> Self {
+        value.map_or(KleeneTruth::Unknown, KleeneTruth::from)
+    }
+}
+
+impl From<BinaryTruth> for KleeneTruth {
+    fn from(value: BinaryTruth) -> Self {
+        KleeneTruth::from(bool::from(value))
+    }
+}
+
+impl corpus_core::truth::TruthValue for KleeneTruth {
+    fn is_true(&self) -> bool {
+        matches!(self, KleeneTruth::True)
+    }
+
+    fn is_false(&self) -> bool {
+        matches!(self, KleeneTruth::False)
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            KleeneTruth::True => Some(true),
+            KleeneTruth::False => Some(false),
+            KleeneTruth::Unknown => None,
+        }
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Self::from(value)
+    }
+
+    fn and(&self, other: &Self) -> Self {
+        (*self).min(*other)
+    }
+
+    fn or(&self, other: &Self) -> Self {
+        (*self).max(*other)
+    }
+
+    fn not(&self) -> Self {
+        match self {
+            KleeneTruth::True => KleeneTruth::False,
+            KleeneTruth::False => KleeneTruth::True,
+            KleeneTruth::Unknown => KleeneTruth::Unknown,
+        }
+    }
+
+    fn implies(&self, other: &Self) -> Self {
+        self.not().or(other)
+    }
+
+    fn conjunction(values: &[Self]) -> Self {
+        values.iter().copied().min().unwrap_or(KleeneTruth::True)
+    }
+
+    fn disjunction(values: &[Self]) -> Self {
+        values.iter().copied().max().unwrap_or(KleeneTruth::False)
+    }
+}
+
+impl HashNodeInner for KleeneTruth {
+    fn hash(&self) -> u64 {
+        match self {
+            KleeneTruth::False => 0,
+            KleeneTruth::True => 1,
+            KleeneTruth::Unknown => 2,
+        }
+    }
+
+    fn size(&self) -> u64 {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use corpus_core::truth::TruthValue;
+
+    #[test]
+    fn test_strong_kleene_tables() {
+        use KleeneTruth::{False as F, True as T, Unknown as U};
+
+        assert_eq!(U.and(&F), F);
+        assert_eq!(U.and(&T), U);
+        assert_eq!(U.or(&T), T);
+        assert_eq!(U.or(&F), U);
+        assert_eq!(U.not(), U);
+        assert_eq!(F.implies(&U), T);
+        assert_eq!(U.implies(&T), T);
+        assert_eq!(U.implies(&U), U);
+        assert_eq!(T.implies(&U), U);
+        assert_eq!(KleeneTruth::conjunction(&[T, U, F]), F);
+        assert_eq!(KleeneTruth::disjunction(&[F, U]), U);
+        assert_eq!(KleeneTruth::conjunction(&[]), T);
+        assert_eq!(U.as_bool(), None);
+        assert_eq!(KleeneTruth::from(BinaryTruth::True), T);
+    }
+}
@@ -0,0 +1,123 @@
+//! Free and bound variables of formulas.
+//!
+//! Variables are de Bruijn indices inside atoms: under `k` quantifiers,
+//! `/i` with `i < k` is bound by the `i`th enclosing quantifier (innermost
+//! first) and `/i` with `i >= k` is free, referring to `/(i - k)` outside the
+//! formula. All functions report free variables by their outside index.
+
+use crate::first_order::FirstOrderDomain;
+use crate::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::base::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner};
+use corpus_core::truth::TruthValue;
+use std::collections::HashSet;
+
+/// Call `visit(index, depth)` for every variable occurrence in `expr`,
+/// where `depth` is the number of quantifiers enclosing it within `expr`.
+pub fn visit_variables<T, D, F>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    domain: &F,
+    visit: &mut impl FnMut(u32, u32),
+) where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    walk(expr, domain, 0, visit);
+}
+
+fn walk<T, D, F>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    domain: &F,
+    depth: u32,
+    visit: &mut impl FnMut(u32, u32),
+) where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    match expr.value.as_ref() {
+        LogicalExpression::Atomic(atom) => {
+            let Some((_, arguments)) = domain.predicate(atom) else {
+                return;
+            };
+            for index in arguments.iter().flat_map(|argument| domain.term_variables(argument)) {
+                visit(index, depth);
+            }
+        }
+        LogicalExpression::Compound { operator, operands, .. } => {
+            let depth = match operator {
+                ClassicalOperator::Forall | ClassicalOperator::Exists => depth + 1,
+                _ => depth,
+            };
+            for operand in operands {
+                walk(operand, domain, depth, visit);
+            }
+        }
+    }
+}
+
+/// The free variables of `expr`, by their index outside it.
+pub fn free_variables<T, D, F>(expr: &HashNode<ClassicalLogicalExpression<T, D>>, domain: &F) -> HashSet<u32>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    let mut free = HashSet::new();
+    visit_variables(expr, domain, &mut |index, depth| {
+        if index >= depth {
+            free.insert(index - depth);
+        }
+    });
+    free
+}
+
+/// The largest de Bruijn index occurring anywhere in `expr`, bound or free,
+/// or `None` if it has no variables.
+pub fn max_de_bruijn_index<T, D, F>(expr: &HashNode<ClassicalLogicalExpression<T, D>>, domain: &F) -> Option<u32>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    let mut max = None;
+    visit_variables(expr, domain, &mut |index, _| max = max.max(Some(index)));
+    max
+}
+
+/// Whether every variable of `expr` is bound by one of its quantifiers.
+pub fn is_closed<T, D, F>(expr: &HashNode<ClassicalLogicalExpression<T, D>>, domain: &F) -> bool
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    let mut closed = true;
+    visit_variables(expr, domain, &mut |index, depth| closed &= index < depth);
+    closed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FoDomain, Formulas};
+
+    #[test]
+    fn test_free_variables_respect_binder_depth() {
+        let f = Formulas::first_order();
+        let domain = FoDomain(&f);
+        let p = |args| f.pred(0, args);
+
+        // ∀ P(/0, /1) ∧ P(/0): /1 under one binder is outside /0.
+        let expr = f.and(&f.forall(&p(vec![f.var(0), f.var(1)])), &p(vec![f.var(0)]));
+        assert_eq!(free_variables(&expr, &domain), HashSet::from([0]));
+        assert_eq!(max_de_bruijn_index(&expr, &domain), Some(1));
+        assert!(!is_closed(&expr, &domain));
+
+        let closed = f.forall(&f.exists(&p(vec![f.app(0, vec![f.var(1)]), f.var(0)])));
+        assert!(free_variables(&closed, &domain).is_empty());
+        assert!(is_closed(&closed, &domain));
+        assert_eq!(max_de_bruijn_index(&p(vec![f.constant(0)]), &domain), None);
+    }
+}
@@ -322,6 +322,75 @@ where
     }
 }
 
+/// Builds a schema instance from its metavariable arguments.
+pub type SchemaBuilder<T, D, Op> =
+    dyn Fn(&[HashNode<LogicalExpression<T, D, Op>>]) -> Result<HashNode<LogicalExpression<T, D, Op>>, AxiomError>;
+
+/// An axiom with formula metavariables, such as the induction schema
+/// `P(0) ∧ ∀x (P(x) → P(S(x))) → ∀x P(x)`.
+///
+/// A schema stands for infinitely many axioms, so it is not an `Axiom`
+/// itself; `instantiate` produces the `NamedAxiom` for particular arguments.
+pub struct AxiomSchema<T, D, Op>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone + Debug,
+    Op: LogicalOperator<T> + HashNodeInner,
+{
+    pub name: String,
+    /// Number of metavariables.
+    pub arity: usize,
+    builder: Box<SchemaBuilder<T, D, Op>>,
+}
+
+impl<T, D, Op> AxiomSchema<T, D, Op>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone + Debug,
+    Op: LogicalOperator<T> + HashNodeInner,
+{
+    pub fn new(
+        name: impl Into<String>,
+        arity: usize,
+        builder: impl Fn(&[HashNode<LogicalExpression<T, D, Op>>]) -> Result<HashNode<LogicalExpression<T, D, Op>>, AxiomError>
+            + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            arity,
+            builder: Box::new(builder),
+        }
+    }
+
+    /// The axiom for `arguments`, named after the schema and the hash of the
+    /// instance so equal instances get equal names.
+    pub fn instantiate(
+        &self,
+        arguments: &[HashNode<LogicalExpression<T, D, Op>>],
+    ) -> Result<NamedAxiom<T, D, Op>, AxiomError> {
+        if arguments.len() != self.arity {
+            return Err(AxiomError::MalformedAxiom { expected: self.arity, found: arguments.len() });
+        }
+        let expression = (self.builder)(arguments)?;
+        let name = format!("{}[{:016x}]", self.name, expression.hash());
+        Ok(NamedAxiom::new(name, expression))
+    }
+}
+
+impl<T, D, Op> Debug for AxiomSchema<T, D, Op>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T> + Clone + Debug,
+    Op: LogicalOperator<T> + HashNodeInner,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AxiomSchema")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,334 @@
+//! Compact binary encoding of node DAGs.
+//!
+//! Nodes are written once each, children before parents, so shared subterms
+//! cost a single table entry no matter how often they occur. The layout is:
+//!
+//! ```text
+//! magic "CPDG", version byte
+//! opcode count, opcodes as u64 little-endian
+//! node count, nodes: 0, length, atom text
+//!                  | 1 + opcode index, arity, (id - child id) per child
+//! root count, root ids
+//! ```
+//!
+//! Every count, index and id is an unsigned LEB128 varint. Children are
+//! written as the distance back to their entry, which stays small for the
+//! nearby nodes a postorder walk produces. Leaves are the text of the
+//! domain's `AtomCodec`.
+
+use crate::base::codec::AtomCodec;
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+const MAGIC: &[u8; 4] = b"CPDG";
+const VERSION: u8 = 1;
+
+/// Errors writing or reading the binary node format.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinaryError {
+    /// The input does not start with the format's magic bytes.
+    BadMagic,
+    /// The input was written by an unknown version of the format.
+    UnsupportedVersion(u8),
+    /// The input ends in the middle of a record.
+    Truncated,
+    /// A record could not be decoded.
+    Malformed { offset: usize, message: String },
+    /// Table entry `index` could not be encoded or rebuilt by the domain.
+    InvalidNode { index: usize },
+}
+
+impl Display for BinaryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BinaryError::BadMagic => write!(f, "Not a binary node table"),
+            BinaryError::UnsupportedVersion(version) => write!(f, "Unsupported format version {}", version),
+            BinaryError::Truncated => write!(f, "Unexpected end of input"),
+            BinaryError::Malformed { offset, message } => write!(f, "Malformed input at byte {}: {}", offset, message),
+            BinaryError::InvalidNode { index } => write!(f, "Invalid node {}", index),
+        }
+    }
+}
+
+impl std::error::Error for BinaryError {}
+
+/// Encode `roots` and every node below them.
+pub fn write_binary<T: HashNodeInner>(roots: &[HashNode<T>], codec: &dyn AtomCodec<T>) -> Result<Vec<u8>, BinaryError> {
+    let mut writer = BinaryWriter::new(codec);
+    let ids = roots.iter().map(|root| writer.node_id(root)).collect::<Result<Vec<_>, _>>()?;
+    Ok(writer.finish(&ids))
+}
+
+/// Decode nodes written by `write_binary`, interning them into `store`, and
+/// return the roots in the order they were written.
+pub fn read_binary<T: HashNodeInner>(
+    bytes: &[u8],
+    codec: &dyn AtomCodec<T>,
+    store: &NodeStorage<T>,
+) -> Result<Vec<HashNode<T>>, BinaryError> {
+    let mut reader = BinaryReader { bytes, offset: 0 };
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(BinaryError::BadMagic);
+    }
+    let version = reader.take(1)?[0];
+    if version != VERSION {
+        return Err(BinaryError::UnsupportedVersion(version));
+    }
+
+    let opcode_count = reader.count()?;
+    let mut opcodes = Vec::with_capacity(opcode_count.min(bytes.len() / 8));
+    for _ in 0..opcode_count {
+        opcodes.push(u64::from_le_bytes(reader.take(8)?.try_into().unwrap()));
+    }
+
+    let node_count = reader.count()?;
+    let mut nodes: Vec<HashNode<T>> = Vec::with_capacity(node_count.min(bytes.len()));
+    for index in 0..node_count {
+        let node = match reader.count()? {
+            0 => {
+                let length = reader.count()?;
+                let start = reader.offset;
+                let text = std::str::from_utf8(reader.take(length)?).map_err(|_| reader.malformed(start, "atom is not UTF-8"))?;
+                codec.decode_atom(text, store)
+            }
+            tag => {
+                let start = reader.offset;
+                let opcode = *opcodes.get(tag - 1).ok_or_else(|| reader.malformed(start, "unknown opcode index"))?;
+                let arity = reader.count()?;
+                let mut children = Vec::with_capacity(arity.min(index));
+                for _ in 0..arity {
+                    let start = reader.offset;
+                    let distance = reader.count()?;
+                    if distance == 0 || distance > index {
+                        return Err(reader.malformed(start, "child is not an earlier node"));
+                    }
+                    children.push(nodes[index - distance].clone());
+                }
+                T::construct_from_parts(opcode, children, store)
+            }
+        };
+        nodes.push(node.ok_or(BinaryError::InvalidNode { index })?);
+    }
+
+    let root_count = reader.count()?;
+    let mut roots = Vec::with_capacity(root_count.min(nodes.len()));
+    for _ in 0..root_count {
+        let start = reader.offset;
+        let id = reader.count()?;
+        roots.push(nodes.get(id).cloned().ok_or_else(|| reader.malformed(start, "unknown root id"))?);
+    }
+    if reader.offset != bytes.len() {
+        return Err(reader.malformed(reader.offset, "trailing bytes"));
+    }
+    Ok(roots)
+}
+
+/// Encode every node interned in `store`.
+pub fn write_store_binary<T: HashNodeInner>(store: &NodeStorage<T>, codec: &dyn AtomCodec<T>) -> Result<Vec<u8>, BinaryError> {
+    let mut nodes = store.nodes();
+    nodes.sort_by_key(HashNode::hash);
+    write_binary(&nodes, codec)
+}
+
+/// Intern a snapshot written by `write_store_binary` into `store`, returning
+/// the number of nodes it held.
+pub fn read_store_binary<T: HashNodeInner>(
+    bytes: &[u8],
+    codec: &dyn AtomCodec<T>,
+    store: &NodeStorage<T>,
+) -> Result<usize, BinaryError> {
+    read_binary(bytes, codec, store).map(|nodes| nodes.len())
+}
+
+/// A node still to be written: first visited, then emitted once its
+/// children have ids.
+enum Pending<T: HashNodeInner> {
+    Visit(HashNode<T>),
+    Emit(u64, u64, Vec<HashNode<T>>),
+}
+
+/// Assigns table ids to nodes and encodes their entries in postorder.
+struct BinaryWriter<'a, T: HashNodeInner> {
+    codec: &'a dyn AtomCodec<T>,
+    ids: HashMap<u64, usize>,
+    opcodes: HashMap<u64, usize>,
+    opcode_table: Vec<u64>,
+    entries: Vec<u8>,
+}
+
+impl<'a, T: HashNodeInner> BinaryWriter<'a, T> {
+    fn new(codec: &'a dyn AtomCodec<T>) -> Self {
+        Self { codec, ids: HashMap::new(), opcodes: HashMap::new(), opcode_table: Vec::new(), entries: Vec::new() }
+    }
+
+    /// The id of `root`, writing it and any unwritten nodes below it. The
+    /// walk keeps its own stack, since terms such as long numerals are
+    /// deeper than the call stack allows.
+    fn node_id(&mut self, root: &HashNode<T>) -> Result<usize, BinaryError> {
+        let mut pending = vec![Pending::Visit(root.clone())];
+        while let Some(next) = pending.pop() {
+            match next {
+                Pending::Visit(node) if self.ids.contains_key(&node.hash()) => {}
+                Pending::Visit(node) => match node.value.decompose() {
+                    Some((opcode, children)) => {
+                        let unwritten: Vec<_> =
+                            children.iter().rev().filter(|child| !self.ids.contains_key(&child.hash())).cloned().collect();
+                        pending.push(Pending::Emit(node.hash(), opcode, children));
+                        pending.extend(unwritten.into_iter().map(Pending::Visit));
+                    }
+                    None => {
+                        let text = self.codec.encode_atom(&node.value).ok_or(BinaryError::InvalidNode { index: self.ids.len() })?;
+                        self.entries.push(0);
+                        write_varint(&mut self.entries, text.len() as u64);
+                        self.entries.extend_from_slice(text.as_bytes());
+                        self.ids.insert(node.hash(), self.ids.len());
+                    }
+                },
+                Pending::Emit(hash, _, _) if self.ids.contains_key(&hash) => {}
+                Pending::Emit(hash, opcode, children) => {
+                    let id = self.ids.len();
+                    let next_index = self.opcode_table.len();
+                    let index = *self.opcodes.entry(opcode).or_insert(next_index);
+                    if index == next_index {
+                        self.opcode_table.push(opcode);
+                    }
+                    write_varint(&mut self.entries, index as u64 + 1);
+                    write_varint(&mut self.entries, children.len() as u64);
+                    for child in &children {
+                        write_varint(&mut self.entries, (id - self.ids[&child.hash()]) as u64);
+                    }
+                    self.ids.insert(hash, id);
+                }
+            }
+        }
+        Ok(self.ids[&root.hash()])
+    }
+
+    fn finish(self, roots: &[usize]) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        write_varint(&mut bytes, self.opcode_table.len() as u64);
+        for opcode in &self.opcode_table {
+            bytes.extend_from_slice(&opcode.to_le_bytes());
+        }
+        write_varint(&mut bytes, self.ids.len() as u64);
+        bytes.extend_from_slice(&self.entries);
+        write_varint(&mut bytes, roots.len() as u64);
+        for &root in roots {
+            write_varint(&mut bytes, root as u64);
+        }
+        bytes
+    }
+}
+
+pub(crate) fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+pub(crate) struct BinaryReader<'a> {
+    pub(crate) bytes: &'a [u8],
+    pub(crate) offset: usize,
+}
+
+impl<'a> BinaryReader<'a> {
+    pub(crate) fn malformed(&self, offset: usize, message: &str) -> BinaryError {
+        BinaryError::Malformed { offset, message: message.to_string() }
+    }
+
+    pub(crate) fn take(&mut self, length: usize) -> Result<&'a [u8], BinaryError> {
+        let end = self.offset.checked_add(length).filter(|&end| end <= self.bytes.len()).ok_or(BinaryError::Truncated)?;
+        let bytes = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    pub(crate) fn varint(&mut self) -> Result<u64, BinaryError> {
+        let start = self.offset;
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(self.malformed(start, "varint is too long"))
+    }
+
+    pub(crate) fn count(&mut self) -> Result<usize, BinaryError> {
+        let start = self.offset;
+        let value = self.varint()?;
+        usize::try_from(value).map_err(|_| self.malformed(start, "count does not fit in memory"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proving::testing::{add, eq, numeral, Term};
+
+    struct TermCodec;
+
+    impl AtomCodec<Term> for TermCodec {
+        fn encode_atom(&self, atom: &Term) -> Option<String> {
+            match atom {
+                Term::Zero => Some("0".to_string()),
+                Term::Var(index) => Some(format!("x{}", index)),
+                _ => None,
+            }
+        }
+
+        fn decode_atom(&self, text: &str, store: &NodeStorage<Term>) -> Option<HashNode<Term>> {
+            let atom = match text {
+                "0" => Term::Zero,
+                _ => Term::Var(text.strip_prefix('x')?.parse().ok()?),
+            };
+            Some(HashNode::from_store(atom, store))
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let store = NodeStorage::new();
+        let variable = HashNode::from_store(Term::Var(300), &store);
+        let sum = add(numeral(200, &store), variable, &store);
+        let goal = eq(sum.clone(), numeral(3, &store), &store);
+        let bytes = write_binary(&[goal.clone(), sum.clone()], &TermCodec).unwrap();
+
+        // 0, 200 successors, the variable, the sum and the equation: the
+        // numeral 3 shares its nodes with 200.
+        let other = NodeStorage::new();
+        assert_eq!(read_binary(&bytes, &TermCodec, &other).unwrap(), [goal, sum]);
+        assert_eq!(other.len(), 204);
+
+        let snapshot = write_store_binary(&store, &TermCodec).unwrap();
+        let copy = NodeStorage::new();
+        assert_eq!(read_store_binary(&snapshot, &TermCodec, &copy), Ok(store.len()));
+        assert!(store.nodes().iter().all(|node| copy.get(node.hash()).is_some()));
+    }
+
+    #[test]
+    fn test_errors() {
+        let store = NodeStorage::new();
+        let bytes = write_binary(&[numeral(2, &store)], &TermCodec).unwrap();
+        let read = |bytes: &[u8]| read_binary(bytes, &TermCodec, &store);
+
+        assert_eq!(read(b"CPDX\x01"), Err(BinaryError::BadMagic));
+        assert_eq!(read(b"CPDG\x02"), Err(BinaryError::UnsupportedVersion(2)));
+        assert_eq!(read(&bytes[..bytes.len() - 1]), Err(BinaryError::Truncated));
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(read(&trailing), Err(BinaryError::Malformed { .. })));
+        // The inner successor's child pointing at itself.
+        let mut cyclic = bytes.clone();
+        let child = bytes.len() - 6;
+        assert_eq!(cyclic[child], 1);
+        cyclic[child] = 0;
+        assert!(matches!(read(&cyclic), Err(BinaryError::Malformed { .. })));
+    }
+}
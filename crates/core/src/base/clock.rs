@@ -0,0 +1,40 @@
+//! Measuring search time on targets with and without a clock.
+//!
+//! `std::time::Instant::now` panics on `wasm32-unknown-unknown`. There a
+//! `Stopwatch` always reads zero, so timeouts never fire; a search is
+//! stopped by polling instead, through a `SearchObserver` such as
+//! `Cancellation`.
+
+use std::time::Duration;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+
+/// Time since the stopwatch was started.
+#[derive(Debug, Clone, Copy)]
+pub struct Stopwatch {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    started: Instant,
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+impl Stopwatch {
+    pub fn start() -> Self {
+        Self { started: Instant::now() }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+impl Stopwatch {
+    pub fn start() -> Self {
+        Self {}
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
@@ -0,0 +1,25 @@
+//! Domain hook for turning leaf nodes into text and back.
+//!
+//! Compound nodes can be written generically through `decompose` and rebuilt
+//! through `construct_from_parts`. Leaves (nodes `decompose` returns `None`
+//! for, such as numerals or variables) carry domain data the core cannot see,
+//! so each domain supplies an `AtomCodec` for them. Text formats that show
+//! opcodes, such as S-expressions, also ask the codec for their names.
+
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+
+/// Encodes and decodes the leaf nodes of a domain.
+pub trait AtomCodec<T: HashNodeInner> {
+    /// Encode a leaf node as a single-line string.
+    ///
+    /// Returns `None` if `atom` cannot be encoded.
+    fn encode_atom(&self, atom: &T) -> Option<String>;
+
+    /// Decode a string produced by `encode_atom`, interning the node into `store`.
+    fn decode_atom(&self, text: &str, store: &NodeStorage<T>) -> Option<HashNode<T>>;
+
+    /// The name `opcode` was hashed from by `Hashing::opcode`, if known.
+    fn opcode_name(&self, _opcode: u64) -> Option<String> {
+        None
+    }
+}
@@ -116,6 +116,23 @@ where
             } => 1 + operator.size() + operands.iter().map(|node| node.size()).sum::<u64>(),
         }
     }
+
+    /// Compounds decompose into the operator's hash and their operands, so
+    /// patterns built with `Pattern::compound(operator.hash(), ..)` match them.
+    fn decompose(&self) -> Option<(u64, Vec<HashNode<Self>>)> {
+        match self {
+            LogicalExpression::Atomic(_) => None,
+            LogicalExpression::Compound { operator, operands, .. } => Some((operator.hash(), operands.clone())),
+        }
+    }
+
+    fn construct_from_parts(opcode: u64, children: Vec<HashNode<Self>>, store: &NodeStorage<Self>) -> Option<HashNode<Self>> {
+        let operator = <Op as LogicalOperator<T>>::from_opcode(opcode)?;
+        if children.len() != operator.arity() {
+            return None;
+        }
+        Some(HashNode::from_store(LogicalExpression::compound(operator, children), store))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -6,6 +6,16 @@ pub trait LogicalOperator<T: TruthValue>: Clone + Debug + Send + Sync {
 
     fn symbol(&self) -> Self::Symbol;
     fn arity(&self) -> usize;
+
+    /// The operator whose `HashNodeInner::hash` is `opcode`, used to rebuild
+    /// compound expressions from their parts. Operators that cannot be
+    /// recovered leave their expressions opaque to rewriting.
+    fn from_opcode(_opcode: u64) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 pub struct LogicalOperatorSet<T: TruthValue, Op: LogicalOperator<T>> {
@@ -2,18 +2,28 @@
 
 // Declare all submodules
 pub mod axioms;
+pub mod binary;
+pub mod clock;
+pub mod codec;
 pub mod expression;
 pub mod logic;
 pub mod nodes;
 pub mod patterns;
+pub mod printing;
+pub mod sexpr;
 pub mod truth;
 pub mod variables;
 
 // Re-export all submodule items for convenience
 pub use axioms::*;
+pub use binary::*;
+pub use clock::*;
+pub use codec::*;
 pub use expression::*;
 pub use logic::*;
 pub use nodes::*;
 pub use patterns::*;
+pub use printing::*;
+pub use sexpr::*;
 pub use truth::*;
 pub use variables::*;
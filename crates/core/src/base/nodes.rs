@@ -2,14 +2,20 @@ use std::{
     collections::HashMap,
     fmt::{Display, Formatter},
     hash::{Hash, Hasher},
-    rc::Rc,
-    sync::RwLock,
+    sync::{Arc, RwLock},
 };
 
 // --- Public Interface ---
 
-pub trait HashNodeInner: Sized {
+pub trait HashNodeInner: Sized + PartialEq {
+    /// Structural hash, locating the node in its store. Called once when a
+    /// node is built, like `size`. Values with equal hashes may still be
+    /// distinct; the store tells them apart with `PartialEq`.
     fn hash(&self) -> u64;
+
+    /// Number of nodes in the tree. Called once when a node is built;
+    /// `HashNode::size` returns the stored result, so implementations can
+    /// sum their children's `HashNode::size` in constant time.
     fn size(&self) -> u64;
 
     fn decompose(&self) -> Option<(u64, Vec<HashNode<Self>>)> {
@@ -33,75 +39,150 @@ pub struct Hashing;
 
 #[derive(Debug)]
 pub struct HashNode<T: HashNodeInner> {
-    pub value: Rc<T>,
+    pub value: Arc<T>,
+    /// `value.hash()` and `value.size()`, computed once when the node is
+    /// built.
+    hash: u64,
+    size: u64,
 }
 
+/// Nodes by hash. A bucket holds more than one node only when distinct
+/// values collide.
+type Shard<T> = RwLock<HashMap<u64, Vec<HashNode<T>>, std::hash::BuildHasherDefault<IdentityHasher>>>;
+
+/// Number of independently locked parts of a `NodeStorage`.
+const SHARDS: usize = 16;
+
+/// Interns nodes by hash, telling colliding values apart by structure, so
+/// equal values share one node and distinct values never do. The table is
+/// split into shards locked independently, picked by the hash, so threads
+/// interning different nodes rarely wait on each other; nodes already
+/// interned are found under a read lock.
 pub struct NodeStorage<T: HashNodeInner> {
-    nodes: RwLock<HashMap<u64, HashNode<T>, std::hash::BuildHasherDefault<IdentityHasher>>>,
+    shards: Box<[Shard<T>]>,
 }
 
 impl<T: HashNodeInner> NodeStorage<T> {
     pub fn new() -> Self {
         Self {
-            nodes: RwLock::new(HashMap::default()),
+            shards: (0..SHARDS).map(|_| RwLock::new(HashMap::default())).collect(),
         }
     }
 
+    fn shard(&self, hash: u64) -> &Shard<T> {
+        // Leaf hashes can be small integers, so mix before taking high bits.
+        &self.shards[(Hashing::mix(hash) >> (u64::BITS - SHARDS.ilog2())) as usize]
+    }
+
     pub fn get_or_insert(&self, value: T) -> HashNode<T> {
         let hash = value.hash();
-        let mut nodes = self.nodes.write().unwrap();
-
-        if let Some(existing) = nodes.get(&hash) {
-            existing.clone()
-        } else {
-            let node = HashNode {
-                value: Rc::new(value),
-            };
-            nodes.insert(hash, node.clone());
-            node
+        let shard = self.shard(hash);
+        if let Some(existing) = shard.read().unwrap().get(&hash).and_then(|bucket| Self::find(bucket, &value)) {
+            return existing;
+        }
+        // Another thread may have interned the node since the read lock was
+        // released; the first one stays.
+        let mut shard = shard.write().unwrap();
+        let bucket = shard.entry(hash).or_default();
+        if let Some(existing) = Self::find(bucket, &value) {
+            return existing;
         }
+        let node = HashNode::with_hash(value, hash);
+        bucket.push(node.clone());
+        node
     }
 
+    fn find(bucket: &[HashNode<T>], value: &T) -> Option<HashNode<T>> {
+        bucket.iter().find(|node| HashNode::same_value(node.value.as_ref(), value)).cloned()
+    }
+
+    /// The first node interned with `hash`.
     pub fn get(&self, hash: u64) -> Option<HashNode<T>> {
-        let nodes = self.nodes.read().unwrap();
-        nodes.get(&hash).cloned()
+        self.shard(hash).read().unwrap().get(&hash).and_then(|bucket| bucket.first()).cloned()
+    }
+
+    /// Every interned node, in no particular order.
+    pub fn nodes(&self) -> Vec<HashNode<T>> {
+        self.shards.iter().flat_map(|shard| shard.read().unwrap().values().flatten().cloned().collect::<Vec<_>>()).collect()
     }
 
     pub fn len(&self) -> usize {
-        let nodes = self.nodes.read().unwrap();
-        nodes.len()
+        self.shards.iter().map(|shard| shard.read().unwrap().values().map(Vec::len).sum::<usize>()).sum()
     }
     
     pub fn is_empty(&self) -> bool {
-        let nodes = self.nodes.read().unwrap();
-        nodes.is_empty()
+        self.shards.iter().all(|shard| shard.read().unwrap().is_empty())
     }
 
     pub fn clear(&self) {
-        let mut nodes = self.nodes.write().unwrap();
-        nodes.clear();
+        for shard in self.shards.iter() {
+            shard.write().unwrap().clear();
+        }
     }
 }
 
 impl<T: HashNodeInner> HashNode<T> {
+    fn with_hash(value: T, hash: u64) -> Self {
+        let size = value.size();
+        Self { value: Arc::new(value), hash, size }
+    }
+
     pub fn size(&self) -> u64 {
-        self.value.size()
+        self.size
     }
     
     pub fn hash(&self) -> u64 {
-        self.value.hash()
+        self.hash
+    }
+
+    /// Compare two nodes by structure rather than by hash alone.
+    ///
+    /// Compound nodes are equal when their opcodes match and their children
+    /// are pairwise structurally equal. Leaves are compared with `PartialEq`.
+    pub fn structurally_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.value, &other.value) || (self.hash() == other.hash() && Self::same_value(&self.value, &other.value))
+    }
+
+    fn same_value(a: &T, b: &T) -> bool {
+        match (a.decompose(), b.decompose()) {
+            (Some((op1, children1)), Some((op2, children2))) => {
+                op1 == op2
+                    && children1.len() == children2.len()
+                    && children1
+                        .iter()
+                        .zip(&children2)
+                        .all(|(a, b)| a.structurally_eq(b))
+            }
+            // Derived `PartialEq` compares children by hash, so compound
+            // values only fall back to it when they cannot be decomposed.
+            _ => a == b,
+        }
     }
 }
 
 impl Hashing {
+    /// Fold `hash2` into `hash1`.
+    ///
+    /// Node hashes are built from small inputs (leaf values, short opcodes,
+    /// argument positions), so the combination must not let them cancel: a
+    /// shift-and-add mix merges most `root_hash(op, [a, b])` with `op`, `a`
+    /// and `b` below 16, and `HashNode` equality compares hashes. Both inputs
+    /// therefore go through a full-avalanche mix.
+    ///
+    /// Hashes are recorded in search checkpoints, so changing this function
+    /// invalidates them; the values are pinned by a test.
     pub const fn hash_combine(hash1: u64, hash2: u64) -> u64 {
-        const MAGIC: u64 = 0x9e3779b9;
+        const MAGIC: u64 = 0x9e3779b97f4a7c15;
+
+        Self::mix(Self::mix(hash1).wrapping_add(MAGIC) ^ hash2)
+    }
 
-        hash1
-            ^ (hash1
-                .wrapping_add(MAGIC)
-                .wrapping_add(hash2 << 6)
-                .wrapping_add(hash2 >> 2))
+    /// SplitMix64 finalizer: a bijection on `u64` where each input bit
+    /// affects every output bit.
+    const fn mix(mut hash: u64) -> u64 {
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+        hash ^ (hash >> 31)
     }
 
     pub fn root_hash(root_opcode: u64, children: &[u64]) -> u64 {
@@ -159,14 +240,16 @@ impl<T: HashNodeInner + Clone> Default for NodeStorage<T> {
 impl<T: HashNodeInner> Clone for HashNode<T> {
     fn clone(&self) -> Self {
         Self {
-            value: self.value.clone()
+            value: self.value.clone(),
+            hash: self.hash,
+            size: self.size,
         }
     }
 }
 
 impl<T: HashNodeInner> PartialEq for HashNode<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.value.hash() == other.value.hash()
+        self.hash == other.hash
     }
 }
 
@@ -178,7 +261,24 @@ impl<T: Display + HashNodeInner> Display for HashNode<T> {
 
 impl<T: HashNodeInner> Hash for HashNode<T> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        state.write_u64(self.value.hash());
+        state.write_u64(self.hash);
+    }
+}
+
+impl<T: HashNodeInner + Clone> HashNode<T> {
+    /// Intern this node, and every node below it, into `store`.
+    ///
+    /// Compound nodes are rebuilt from their transferred children so the
+    /// result shares no nodes with the original store. Leaves, and nodes
+    /// that cannot be rebuilt from parts, are cloned.
+    pub fn transfer_to(&self, store: &NodeStorage<T>) -> HashNode<T> {
+        if let Some((opcode, children)) = self.value.decompose() {
+            let children = children.iter().map(|child| child.transfer_to(store)).collect();
+            if let Some(node) = T::construct_from_parts(opcode, children, store) {
+                return node;
+            }
+        }
+        store.get_or_insert(self.value.as_ref().clone())
     }
 }
 
@@ -207,3 +307,85 @@ impl HashNodeInner for u32 {
         1
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrent_interning() {
+        let store = NodeStorage::<u64>::new();
+        let interned: Vec<Vec<HashNode<u64>>> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..4).map(|_| scope.spawn(|| (0..1000).map(|n| store.get_or_insert(n)).collect())).collect();
+            workers.into_iter().map(|worker| worker.join().unwrap()).collect()
+        });
+
+        assert_eq!(store.len(), 1000);
+        assert_eq!(store.nodes().len(), 1000);
+        for nodes in &interned[1..] {
+            assert!(nodes.iter().zip(&interned[0]).all(|(a, b)| Arc::ptr_eq(&a.value, &b.value)));
+        }
+        assert_eq!(store.get(999).map(|node| *node.value), Some(999));
+        store.clear();
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_hashes_are_stable() {
+        assert_eq!(Hashing::hash_combine(0, 0), 0xe220a8397b1dcdaf);
+        assert_eq!(Hashing::opcode("add"), 0xf9dd8fe09cd71e1c);
+        assert_eq!(Hashing::root_hash(Hashing::opcode("add"), &[1, 2]), 0xebb33bb01f01b1d1);
+    }
+
+    #[test]
+    fn test_small_inputs_do_not_collide() {
+        let mut hashes = std::collections::HashSet::new();
+        for opcode in 0..16 {
+            for a in 0..16 {
+                for b in 0..16 {
+                    assert!(hashes.insert(Hashing::root_hash(opcode, &[a, b])), "({opcode}, {a}, {b}) collides");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_leaves_spread_over_shards() {
+        let store = NodeStorage::<u64>::new();
+        for n in 0..64 {
+            store.get_or_insert(n);
+        }
+        let used = store.shards.iter().filter(|shard| !shard.read().unwrap().is_empty()).count();
+        assert!(used > SHARDS / 2, "only {used} shards used");
+    }
+
+    /// Hashes to its value modulo 4, so `Mod(1)` and `Mod(5)` collide.
+    #[derive(Debug, PartialEq)]
+    struct Mod(u64);
+
+    impl HashNodeInner for Mod {
+        fn hash(&self) -> u64 {
+            self.0 % 4
+        }
+
+        fn size(&self) -> u64 {
+            1
+        }
+    }
+
+    #[test]
+    fn test_colliding_values_are_kept_apart() {
+        let store = NodeStorage::new();
+        let one = store.get_or_insert(Mod(1));
+        let five = store.get_or_insert(Mod(5));
+
+        assert_eq!(one.hash(), five.hash());
+        assert!(!Arc::ptr_eq(&one.value, &five.value));
+        assert!(!one.structurally_eq(&five));
+        assert_eq!(*five.value, Mod(5));
+        assert!(Arc::ptr_eq(&store.get_or_insert(Mod(5)).value, &five.value));
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.nodes().len(), 2);
+        assert_eq!(store.get(1).map(|node| node.value.0), Some(1));
+    }
+}
@@ -95,14 +95,14 @@ where
     /// Free variables are those that are not bound by any quantifier in the
     /// given expression. Returns a set of variable indices.
     ///
-    /// The default implementation uses `extract_quantified_variables` and
-    /// assumes variables are represented as de Bruijn indices >= the quantifier depth.
+    /// Core cannot see inside domain atoms, so the default reports none.
+    /// Implementors should override it; for classical operators,
+    /// `corpus_classical_logic::variables::free_variables` computes it from a
+    /// `FirstOrderDomain`, counting `/i` under `k` binders as free when `i >= k`.
     fn free_variables(
         &self,
         _expr: &HashNode<LogicalExpression<T, D, Op>>,
     ) -> HashSet<u32> {
-        // Default implementation assumes all variables are bound
-        // Subtraits should override this for their specific representation
         HashSet::new()
     }
 }
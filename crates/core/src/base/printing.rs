@@ -0,0 +1,239 @@
+//! Rendering expressions as text.
+//!
+//! Everything that shows an expression to a user (proof output, error
+//! messages, exports) takes an `ExprPrinter`, so a domain decides once how
+//! its expressions read. `DisplayPrinter` falls back on `Display`, and
+//! closures `Fn(&T) -> String` are printers too.
+//!
+//! `NotationPrinter` is the generic printer: it walks nodes through
+//! `decompose`, writes each opcode as its `SymbolTable` entry says (infix
+//! with a precedence, prefix, postfix or function application) and hands
+//! leaves to a domain function. It can drop the parentheses precedence makes
+//! redundant and elide subterms below a maximum depth.
+
+use crate::base::nodes::{HashNodeInner, Hashing};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Renders expressions of type `T`.
+pub trait ExprPrinter<T> {
+    fn print(&self, expr: &T) -> String;
+}
+
+/// Prints expressions with their `Display` impl.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DisplayPrinter;
+
+impl<T: Display> ExprPrinter<T> for DisplayPrinter {
+    fn print(&self, expr: &T) -> String {
+        expr.to_string()
+    }
+}
+
+impl<T, F: Fn(&T) -> String> ExprPrinter<T> for F {
+    fn print(&self, expr: &T) -> String {
+        self(expr)
+    }
+}
+
+/// How operators of equal precedence group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+    /// Neither side may hold the same operator unparenthesized.
+    None,
+}
+
+/// How an opcode is written. Higher precedence binds tighter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notation {
+    /// `a + b`
+    Infix { symbol: String, precedence: u8, associativity: Associativity },
+    /// `-a`
+    Prefix { symbol: String, precedence: u8 },
+    /// `a⁻¹`
+    Postfix { symbol: String, precedence: u8 },
+    /// `f(a, b)`
+    Function(String),
+}
+
+/// Notations of opcodes, keyed by the names they are hashed from.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    notations: HashMap<u64, Notation>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write `opcode` as `notation`.
+    pub fn with(mut self, opcode: &str, notation: Notation) -> Self {
+        self.notations.insert(Hashing::opcode(opcode), notation);
+        self
+    }
+
+    pub fn infix(self, opcode: &str, symbol: &str, precedence: u8, associativity: Associativity) -> Self {
+        self.with(opcode, Notation::Infix { symbol: symbol.to_string(), precedence, associativity })
+    }
+
+    pub fn prefix(self, opcode: &str, symbol: &str, precedence: u8) -> Self {
+        self.with(opcode, Notation::Prefix { symbol: symbol.to_string(), precedence })
+    }
+
+    pub fn postfix(self, opcode: &str, symbol: &str, precedence: u8) -> Self {
+        self.with(opcode, Notation::Postfix { symbol: symbol.to_string(), precedence })
+    }
+
+    pub fn function(self, opcode: &str, name: &str) -> Self {
+        self.with(opcode, Notation::Function(name.to_string()))
+    }
+
+    pub fn get(&self, opcode: u64) -> Option<&Notation> {
+        self.notations.get(&opcode)
+    }
+}
+
+/// Which parentheses a `NotationPrinter` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Parentheses {
+    /// Only those precedence and associativity require.
+    #[default]
+    Minimal,
+    /// Around every infix operation, the outermost included.
+    Full,
+}
+
+/// Options of a `NotationPrinter`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrintOptions {
+    pub parentheses: Parentheses,
+    /// Subterms nested deeper than this, the root being at depth 0, are
+    /// written as `ellipsis`.
+    pub max_depth: Option<usize>,
+    pub ellipsis: String,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self { parentheses: Parentheses::Minimal, max_depth: None, ellipsis: "…".to_string() }
+    }
+}
+
+/// Prints any node type from a symbol table and a printer for its leaves.
+#[derive(Debug, Clone)]
+pub struct NotationPrinter<T> {
+    symbols: SymbolTable,
+    leaf: fn(&T) -> String,
+    options: PrintOptions,
+}
+
+impl<T: HashNodeInner> NotationPrinter<T> {
+    /// A printer writing compound nodes as `symbols` says and leaves with
+    /// `leaf`. Opcodes missing from `symbols` are applied as `#<opcode>(…)`.
+    pub fn new(symbols: SymbolTable, leaf: fn(&T) -> String) -> Self {
+        Self { symbols, leaf, options: PrintOptions::default() }
+    }
+
+    pub fn with_options(mut self, options: PrintOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_parentheses(mut self, parentheses: Parentheses) -> Self {
+        self.options.parentheses = parentheses;
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.options.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn options(&self) -> &PrintOptions {
+        &self.options
+    }
+
+    /// `expr` and the precedence it binds with; atoms bind tightest.
+    fn render(&self, expr: &T, depth: usize) -> (String, u8) {
+        if self.options.max_depth.is_some_and(|max| depth > max) {
+            return (self.options.ellipsis.clone(), u8::MAX);
+        }
+        let Some((opcode, children)) = expr.decompose() else {
+            return ((self.leaf)(expr), u8::MAX);
+        };
+        let full = self.options.parentheses == Parentheses::Full;
+        let child = |index: usize, least: u8, strict: bool| {
+            let (text, precedence) = self.render(&children[index].value, depth + 1);
+            let loose = precedence < least || (strict && precedence == least);
+            if loose { format!("({})", text) } else { text }
+        };
+        match (self.symbols.get(opcode), children.len()) {
+            (Some(Notation::Infix { symbol, precedence, associativity }), 2) => {
+                let left = child(0, *precedence, *associativity != Associativity::Left);
+                let right = child(1, *precedence, *associativity != Associativity::Right);
+                let text = format!("{} {} {}", left, symbol, right);
+                if full { (format!("({})", text), u8::MAX) } else { (text, *precedence) }
+            }
+            (Some(Notation::Prefix { symbol, precedence }), 1) => (format!("{}{}", symbol, child(0, *precedence, false)), *precedence),
+            (Some(Notation::Postfix { symbol, precedence }), 1) => (format!("{}{}", child(0, *precedence, false), symbol), *precedence),
+            (notation, _) => {
+                let name = match notation {
+                    Some(Notation::Function(name)) => name.clone(),
+                    _ => format!("#{}", opcode),
+                };
+                let args: Vec<_> = (0..children.len()).map(|index| self.render(&children[index].value, depth + 1).0).collect();
+                (format!("{}({})", name, args.join(", ")), u8::MAX)
+            }
+        }
+    }
+}
+
+impl<T: HashNodeInner> ExprPrinter<T> for NotationPrinter<T> {
+    fn print(&self, expr: &T) -> String {
+        self.render(expr, 0).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::{HashNode, NodeStorage};
+    use crate::proving::testing::{add, numeral, succ, zero, Term};
+
+    fn printer() -> NotationPrinter<Term> {
+        let symbols = SymbolTable::new()
+            .infix("add", "+", 1, Associativity::Left)
+            .infix("eq", "=", 0, Associativity::None)
+            .function("succ", "S");
+        NotationPrinter::new(symbols, |leaf| match leaf {
+            Term::Var(index) => format!("x{}", index),
+            _ => "0".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_precedence_and_depth() {
+        let store = NodeStorage::new();
+        let x = HashNode::from_store(Term::Var(0), &store);
+        let left = add(add(x.clone(), zero(&store), &store), succ(x.clone(), &store), &store);
+        let right = add(x.clone(), add(numeral(1, &store), x, &store), &store);
+        let eq = HashNode::from_store(Term::Eq(left, right), &store);
+
+        assert_eq!(printer().print(&eq.value), "x0 + 0 + S(x0) = x0 + (S(0) + x0)");
+        let full = printer().with_parentheses(Parentheses::Full);
+        assert_eq!(full.print(&eq.value), "(((x0 + 0) + S(x0)) = (x0 + (S(0) + x0)))");
+        assert_eq!(printer().with_max_depth(2).print(&eq.value), "… + … + S(…) = x0 + (… + …)");
+        assert_eq!(DisplayPrinter.print(&eq.value), eq.value.to_string());
+    }
+
+    #[test]
+    fn test_unknown_opcodes() {
+        let store = NodeStorage::new();
+        let term = add(zero(&store), zero(&store), &store);
+        let bare = NotationPrinter::new(SymbolTable::new(), |_: &Term| "0".to_string());
+        assert_eq!(bare.print(&term.value), format!("#{}(0, 0)", Hashing::opcode("add")));
+    }
+}
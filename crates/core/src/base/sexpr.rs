@@ -0,0 +1,284 @@
+//! Domain-independent S-expression encoding of nodes.
+//!
+//! A compound node is written as a list headed by its opcode, followed by its
+//! children: `(add (succ 0) x0)`. The head is the name the domain's
+//! `AtomCodec` gives the opcode, or `#` and the opcode in decimal when it has
+//! none; a name is read back through `Hashing::opcode`. Leaves are the text
+//! of `AtomCodec::encode_atom`, bare when it is a single word and quoted
+//! otherwise. Reading skips whitespace and `;` comments, and rebuilds each
+//! list with `construct_from_parts`, interning into the given store.
+
+use crate::base::codec::AtomCodec;
+use crate::base::nodes::{HashNode, HashNodeInner, Hashing, NodeStorage};
+use std::fmt::{Display, Formatter};
+
+/// Errors writing or reading S-expressions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SExprError {
+    /// A leaf the codec cannot encode, or an atom it cannot decode.
+    InvalidAtom(String),
+    /// A list the domain cannot rebuild from its head and children.
+    InvalidCompound { offset: usize },
+    /// Text that is not a well-formed S-expression.
+    Syntax { offset: usize, message: String },
+}
+
+impl Display for SExprError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SExprError::InvalidAtom(text) => write!(f, "invalid atom {}", text),
+            SExprError::InvalidCompound { offset } => write!(f, "offset {}: the domain cannot build this node", offset),
+            SExprError::Syntax { offset, message } => write!(f, "offset {}: {}", offset, message),
+        }
+    }
+}
+
+/// Write `node` as an S-expression.
+pub fn to_sexpr<T: HashNodeInner>(node: &HashNode<T>, codec: &dyn AtomCodec<T>) -> Result<String, SExprError> {
+    let mut text = String::new();
+    write_node(node, codec, &mut text)?;
+    Ok(text)
+}
+
+fn write_node<T: HashNodeInner>(node: &HashNode<T>, codec: &dyn AtomCodec<T>, text: &mut String) -> Result<(), SExprError> {
+    let Some((opcode, children)) = node.value.decompose() else {
+        let atom = codec
+            .encode_atom(&node.value)
+            .ok_or_else(|| SExprError::InvalidAtom(format!("with hash {}", node.hash())))?;
+        text.push_str(&word(&atom));
+        return Ok(());
+    };
+    text.push('(');
+    match codec.opcode_name(opcode) {
+        Some(name) => text.push_str(&word(&name)),
+        None => text.push_str(&format!("#{}", opcode)),
+    }
+    for child in &children {
+        text.push(' ');
+        write_node(child, codec, text)?;
+    }
+    text.push(')');
+    Ok(())
+}
+
+/// `text` as a bare word if it is one, and quoted otherwise.
+fn word(text: &str) -> String {
+    if !text.is_empty() && !text.starts_with('#') && !text.chars().any(|c| c.is_whitespace() || "()\";\\".contains(c)) {
+        return text.to_string();
+    }
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Read the single S-expression in `text`, interning its nodes into `store`.
+pub fn from_sexpr<T: HashNodeInner>(
+    text: &str,
+    codec: &dyn AtomCodec<T>,
+    store: &NodeStorage<T>,
+) -> Result<HashNode<T>, SExprError> {
+    let mut nodes = from_sexprs(text, codec, store)?;
+    match nodes.len() {
+        1 => Ok(nodes.remove(0)),
+        n => Err(SExprError::Syntax { offset: 0, message: format!("expected one expression, found {}", n) }),
+    }
+}
+
+/// Read every S-expression in `text`, in order.
+pub fn from_sexprs<T: HashNodeInner>(
+    text: &str,
+    codec: &dyn AtomCodec<T>,
+    store: &NodeStorage<T>,
+) -> Result<Vec<HashNode<T>>, SExprError> {
+    read_sexprs(text, 0)?.iter().map(|expr| build_node(expr, codec, store)).collect()
+}
+
+fn build_node<T: HashNodeInner>(
+    expr: &SExpr,
+    codec: &dyn AtomCodec<T>,
+    store: &NodeStorage<T>,
+) -> Result<HashNode<T>, SExprError> {
+    match expr {
+        SExpr::Word { text, .. } => codec.decode_atom(text, store).ok_or_else(|| SExprError::InvalidAtom(text.clone())),
+        SExpr::List { items, offset } => {
+            let (opcode, children) = split_list(items, *offset)?;
+            let children = children.iter().map(|child| build_node(child, codec, store)).collect::<Result<_, _>>()?;
+            T::construct_from_parts(opcode, children, store).ok_or(SExprError::InvalidCompound { offset: *offset })
+        }
+    }
+}
+
+/// A parsed S-expression, before it is read as a node or a pattern.
+pub(crate) enum SExpr {
+    Word { text: String, quoted: bool, offset: usize },
+    List { items: Vec<SExpr>, offset: usize },
+}
+
+/// Read the S-expressions in `text` from byte `start` on.
+pub(crate) fn read_sexprs(text: &str, start: usize) -> Result<Vec<SExpr>, SExprError> {
+    let mut reader = Reader { text, offset: start };
+    let mut exprs = Vec::new();
+    while reader.skip_blank() {
+        exprs.push(reader.expr()?);
+    }
+    Ok(exprs)
+}
+
+/// The opcode heading the list `items` at `offset`, and its children.
+pub(crate) fn split_list(items: &[SExpr], offset: usize) -> Result<(u64, &[SExpr]), SExprError> {
+    let Some((SExpr::Word { text, quoted, offset: head }, children)) = items.split_first() else {
+        return Err(SExprError::Syntax { offset: offset + 1, message: "expected an opcode".to_string() });
+    };
+    let opcode = match text.strip_prefix('#') {
+        Some(number) if !quoted => number
+            .parse()
+            .map_err(|_| SExprError::Syntax { offset: *head, message: "bad opcode number".to_string() })?,
+        _ => Hashing::opcode(text),
+    };
+    Ok((opcode, children))
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.offset..].chars().next()
+    }
+
+    fn syntax(&self, message: &str) -> SExprError {
+        SExprError::Syntax { offset: self.offset, message: message.to_string() }
+    }
+
+    /// Skip whitespace and comments; false at the end of the text.
+    fn skip_blank(&mut self) -> bool {
+        while let Some(c) = self.peek() {
+            if c == ';' {
+                self.offset = self.text[self.offset..].find('\n').map_or(self.text.len(), |end| self.offset + end);
+            } else if c.is_whitespace() {
+                self.offset += c.len_utf8();
+            } else {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn expr(&mut self) -> Result<SExpr, SExprError> {
+        let offset = self.offset;
+        if self.peek() != Some('(') {
+            let quoted = self.peek() == Some('"');
+            return Ok(SExpr::Word { text: self.word()?, quoted, offset });
+        }
+        self.offset += 1;
+        let mut items = Vec::new();
+        loop {
+            if !self.skip_blank() {
+                return Err(self.syntax("unclosed list"));
+            }
+            if self.peek() == Some(')') {
+                self.offset += 1;
+                return Ok(SExpr::List { items, offset });
+            }
+            items.push(self.expr()?);
+        }
+    }
+
+    /// A bare or quoted word.
+    fn word(&mut self) -> Result<String, SExprError> {
+        let rest = &self.text[self.offset..];
+        if !rest.starts_with('"') {
+            let end = rest.find(|c: char| c.is_whitespace() || "()\";".contains(c)).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(self.syntax("unexpected `)`"));
+            }
+            self.offset += end;
+            return Ok(rest[..end].to_string());
+        }
+        let mut word = String::new();
+        let mut chars = rest.char_indices().skip(1);
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.offset += i + 1;
+                    return Ok(word);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => word.push('\n'),
+                    Some((_, escaped)) => word.push(escaped),
+                    None => break,
+                },
+                c => word.push(c),
+            }
+        }
+        Err(self.syntax("unclosed string"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proving::testing::{add, eq, numeral, Term};
+
+    /// Writes `Zero` as `0` and variables as `x0`, `x1`, …, naming every
+    /// opcode but `eq`.
+    struct TermCodec;
+
+    impl AtomCodec<Term> for TermCodec {
+        fn encode_atom(&self, atom: &Term) -> Option<String> {
+            match atom {
+                Term::Zero => Some("0".to_string()),
+                Term::Var(index) => Some(format!("x{}", index)),
+                _ => None,
+            }
+        }
+
+        fn decode_atom(&self, text: &str, store: &NodeStorage<Term>) -> Option<HashNode<Term>> {
+            let atom = match text {
+                "0" => Term::Zero,
+                _ => Term::Var(text.strip_prefix('x')?.parse().ok()?),
+            };
+            Some(HashNode::from_store(atom, store))
+        }
+
+        fn opcode_name(&self, opcode: u64) -> Option<String> {
+            ["succ", "add"].into_iter().find(|name| Hashing::opcode(name) == opcode).map(str::to_string)
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let store = NodeStorage::new();
+        let variable = HashNode::from_store(Term::Var(3), &store);
+        let node = eq(add(numeral(1, &store), variable, &store), numeral(0, &store), &store);
+        let text = to_sexpr(&node, &TermCodec).unwrap();
+        assert_eq!(text, format!("(#{} (add (succ 0) x3) 0)", Hashing::opcode("eq")));
+
+        let other = NodeStorage::new();
+        assert_eq!(from_sexpr(&text, &TermCodec, &other).unwrap(), node);
+        let nodes = from_sexprs("; two terms\n(succ 0)\n  (add x1 0)", &TermCodec, &other).unwrap();
+        assert_eq!(nodes.iter().map(|node| node.to_string()).collect::<Vec<_>>(), ["S(0)", "(x1 + 0)"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let store = NodeStorage::new();
+        assert_eq!(from_sexpr("(succ 0 0)", &TermCodec, &store), Err(SExprError::InvalidCompound { offset: 0 }));
+        assert_eq!(from_sexpr("(succ y)", &TermCodec, &store), Err(SExprError::InvalidAtom("y".to_string())));
+        assert!(matches!(from_sexpr("(add 0", &TermCodec, &store), Err(SExprError::Syntax { offset: 6, .. })));
+        assert!(matches!(from_sexpr("0 0", &TermCodec, &store), Err(SExprError::Syntax { .. })));
+        assert_eq!(word("a \"b\""), "\"a \\\"b\\\"\"");
+    }
+}
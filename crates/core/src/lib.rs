@@ -7,11 +7,29 @@ pub use base::*;
 
 // Re-export proving for convenience
 pub use proving::{
-    CostEstimator, GoalChecker, ProofResult, ProofState, ProofStep, Prover,
-    ReflexiveGoalChecker, SizeCostEstimator,
+    verify_proof, CostEstimator, EquationSides, GoalChecker, LemmaCache, ProofCertificate,
+    ProofCheckError, ProofFailure, ProofResult, ProofState, ProofStep, Prover,
+    ReflexiveGoalChecker, SearchStats, SizeCostEstimator, StepCost,
 };
 
 // Re-export rewriting for convenience
 pub use rewriting::{
-    Pattern, RewriteDirection, RewriteRule, Substitution, Unifiable, UnificationError,
+    Pattern, Position, RewriteDirection, RewriteRule, RewriteSystem, Substitution, Unifiable, UnificationError,
+};
+
+// Nodes and everything built from them can be shared between threads
+// whenever the node type can.
+#[allow(dead_code)]
+const _: () = {
+    fn assert_send_sync<S: Send + Sync>() {}
+
+    fn node_types_are_send_sync<T: HashNodeInner + Unifiable + Send + Sync>() {
+        assert_send_sync::<HashNode<T>>();
+        assert_send_sync::<NodeStorage<T>>();
+        assert_send_sync::<Pattern<T>>();
+        assert_send_sync::<Substitution<T>>();
+        assert_send_sync::<RewriteRule<T>>();
+        assert_send_sync::<ProofStep<T>>();
+        assert_send_sync::<ProofState<T>>();
+    }
 };
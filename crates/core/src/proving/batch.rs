@@ -0,0 +1,188 @@
+//! Proving many goals with one prover.
+//!
+//! Theory files are proven a goal at a time, and neighbouring goals tend to
+//! pass through the same intermediate expressions. `Prover::prove_all` keeps
+//! what earlier searches learned about those expressions:
+//!
+//! - every expression on a found proof path has a known route to a goal, so a
+//!   later search reaching it finishes immediately by appending that route,
+//!   provided the joined proof stays within the depth limit;
+//! - every expression visited by a search that exhausted its frontier cannot
+//!   reach a goal, so later searches prune it (only without a depth limit or
+//!   lemma cache, either of which can make an exhausted frontier incomplete).
+//!
+//! The lemma cache, when attached, is shared as usual.
+
+use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::proving::session::{SearchSession, SessionStatus};
+use crate::proving::{
+    CostEstimator, GoalChecker, ProofFailure, ProofResult, ProofState, ProofStep, Prover,
+    SearchStats,
+};
+use crate::truth::TruthValue;
+use std::collections::{HashMap, HashSet};
+
+/// A known route from some expression to a goal.
+struct Route<Node: HashNodeInner, T: TruthValue> {
+    steps: Vec<ProofStep<Node>>,
+    final_expr: HashNode<Node>,
+    truth: T,
+}
+
+/// What the searches of a batch have learned so far.
+pub(crate) struct SharedProgress<Node: HashNodeInner, T: TruthValue> {
+    solved: HashMap<u64, Route<Node, T>>,
+    dead: HashSet<u64>,
+}
+
+impl<Node: HashNodeInner, T: TruthValue> SharedProgress<Node, T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            solved: HashMap::new(),
+            dead: HashSet::new(),
+        }
+    }
+
+    /// Finish `state` along a known route, if there is one that keeps the
+    /// whole proof within `max_depth` steps.
+    pub(crate) fn complete(
+        &self,
+        state: &ProofState<Node>,
+        nodes_explored: usize,
+        max_depth: Option<usize>,
+    ) -> Option<ProofResult<Node, T>> {
+        let route = self
+            .solved
+            .get(&state.expr.hash())
+            .filter(|route| max_depth.is_none_or(|max_depth| state.steps.len() + route.steps.len() <= max_depth))?;
+        let mut steps = state.steps.to_vec();
+        steps.extend(route.steps.iter().cloned());
+
+        Some(ProofResult {
+            steps,
+            nodes_explored,
+            final_expr: route.final_expr.clone(),
+            truth_result: route.truth.clone(),
+            stats: SearchStats::new(),
+        })
+    }
+
+    /// Whether an earlier exhaustive search showed `expr` cannot reach a goal.
+    pub(crate) fn is_dead(&self, expr: &HashNode<Node>) -> bool {
+        self.dead.contains(&expr.hash())
+    }
+
+    /// Learn from a finished search. Visited states are only marked dead when
+    /// the search was `exhaustive`: no depth limit cut it short and no later
+    /// rules can open new paths from them.
+    pub(crate) fn record(&mut self, session: &SearchSession<Node>, status: &SessionStatus<Node, T>, exhaustive: bool) {
+        match status {
+            SessionStatus::Proved(result) => {
+                for (i, step) in result.steps.iter().enumerate() {
+                    // Keep the shortest route, which fits the most depth limits.
+                    let remaining = result.steps.len() - i;
+                    if self.solved.get(&step.old_expr.hash()).is_some_and(|route| route.steps.len() <= remaining) {
+                        continue;
+                    }
+                    self.solved.insert(
+                        step.old_expr.hash(),
+                        Route {
+                            steps: result.steps[i..].to_vec(),
+                            final_expr: result.final_expr.clone(),
+                            truth: result.truth_result.clone(),
+                        },
+                    );
+                }
+            }
+            SessionStatus::Exhausted if exhaustive => self.dead.extend(session.visited.hashes()),
+            _ => {}
+        }
+    }
+}
+
+impl<Node: HashNodeInner + Clone, C: CostEstimator<Node>, T: TruthValue, G: GoalChecker<Node, T>>
+    Prover<Node, C, T, G>
+{
+    /// Prove each of `goals` in order, sharing progress between the searches.
+    ///
+    /// Returns one outcome per goal. Each successful result is a complete
+    /// proof of its own goal, even when its tail was borrowed from an earlier
+    /// goal's proof. Known routes and dead states are identified by hash.
+    pub fn prove_all(&self, goals: &[HashNode<Node>]) -> Vec<Result<ProofResult<Node, T>, ProofFailure<Node>>> {
+        let mut shared = SharedProgress::new();
+        goals
+            .iter()
+            .map(|goal| self.with_observer(|observer| self.prove_shared(goal, None, observer, Some(&mut shared))))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, succ, Term, TermReflexivity};
+    use crate::proving::{verify_proof, FailureReason, Prover, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    fn prover() -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover
+    }
+
+    #[test]
+    fn test_later_goal_reuses_proof_path() {
+        let store = NodeStorage::new();
+        let four = numeral(4, &store);
+        let first = eq(add(numeral(2, &store), numeral(2, &store), &store), four.clone(), &store);
+        // S(2 + 1) = 4 is the first intermediate state of the proof above.
+        let second = eq(succ(add(numeral(2, &store), numeral(1, &store), &store), &store), four, &store);
+
+        let outcomes = prover().prove_all(&[first, second.clone()]);
+        let proofs: Vec<_> = outcomes.into_iter().map(|outcome| outcome.expect("goal should be provable")).collect();
+
+        assert_eq!(proofs[1].nodes_explored, 1);
+        let certificate = proofs[1].certificate();
+        assert!(certificate.initial_expr == second);
+        assert_eq!(verify_proof(&certificate, &addition_rules(), &store), Ok(()));
+    }
+
+    #[test]
+    fn test_borrowed_routes_respect_max_depth() {
+        let store = NodeStorage::new();
+        let four = numeral(4, &store);
+        let first = eq(add(numeral(2, &store), numeral(2, &store), &store), four.clone(), &store);
+        // Rewriting 1 + 1 to 2 takes two steps and reaches the first goal,
+        // whose proof takes three more.
+        let second = eq(add(numeral(2, &store), add(numeral(1, &store), numeral(1, &store), &store), &store), four, &store);
+
+        let mut prover = prover();
+        prover.set_max_depth(Some(4));
+        let outcomes = prover.prove_all(&[first.clone(), second.clone()]);
+        assert_eq!(outcomes[0].as_ref().map(|proof| proof.steps.len()).ok(), Some(3));
+        assert!(outcomes[1].as_ref().is_err_and(|failure| failure.reason == FailureReason::Exhausted));
+
+        prover.set_max_depth(Some(5));
+        let outcomes = prover.prove_all(&[first, second]);
+        let proof = outcomes[1].as_ref().ok().expect("five steps are allowed");
+        assert!(proof.steps.len() <= 5);
+    }
+
+    #[test]
+    fn test_exhausted_states_are_pruned() {
+        let store = NodeStorage::new();
+        let two = numeral(2, &store);
+        let first = eq(add(numeral(2, &store), numeral(1, &store), &store), two.clone(), &store);
+        // S(2 + 0) = 2 was visited, fruitlessly, while refuting the goal above.
+        let second = eq(succ(add(numeral(2, &store), numeral(0, &store), &store), &store), two, &store);
+
+        let outcomes = prover().prove_all(&[first, second]);
+        let failure = outcomes[1].as_ref().err().expect("goal is false");
+
+        assert_eq!(failure.reason, FailureReason::Exhausted);
+        assert_eq!(failure.nodes_explored, 1);
+        assert_eq!(failure.stats.duplicate_hits, 1);
+    }
+}
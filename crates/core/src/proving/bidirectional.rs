@@ -0,0 +1,392 @@
+//! Meet-in-the-middle search for equations.
+//!
+//! Instead of rewriting a whole equation until a single state has equal
+//! sides, `Prover::prove_bidirectional` rewrites the two sides independently
+//! and keeps a hash-indexed table of every form reached from each. As soon as
+//! a form reached from the left side has also been reached from the right
+//! side, the two step sequences are joined at that meeting point. A proof of
+//! depth `d` is then usually found after searching roughly depth `d / 2` from
+//! each side.
+
+use crate::base::clock::Stopwatch;
+use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::proving::certificate::ProofCertificate;
+use crate::proving::{
+    CostEstimator, FailureReason, GoalChecker, ProofFailure, ProofState, ProofStep, Prover, SearchControl, SearchObserver,
+    SearchStats, StepList,
+};
+use crate::rewriting::RewriteDirection;
+use crate::truth::TruthValue;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Result of a successful meet-in-the-middle search.
+pub struct BidirectionalProof<T: HashNodeInner> {
+    /// The left side of the equation.
+    pub lhs: HashNode<T>,
+    /// The right side of the equation.
+    pub rhs: HashNode<T>,
+    /// The form both sides were rewritten to.
+    pub meeting_point: HashNode<T>,
+    /// Steps rewriting `lhs` into `meeting_point`.
+    pub lhs_steps: Vec<ProofStep<T>>,
+    /// Steps rewriting `rhs` into `meeting_point`.
+    pub rhs_steps: Vec<ProofStep<T>>,
+    /// Number of states popped from both frontiers.
+    pub nodes_explored: usize,
+    /// Statistics for the search, covering both frontiers.
+    pub stats: SearchStats,
+}
+
+impl<T: HashNodeInner> BidirectionalProof<T> {
+    /// Certificates for each half: `lhs` to the meeting point, and `rhs` to
+    /// the meeting point. Both only use rules in the directions the search
+    /// applied them, so each can be checked with `verify_proof`.
+    pub fn certificates(&self) -> (ProofCertificate<T>, ProofCertificate<T>) {
+        (
+            ProofCertificate::new(self.lhs.clone(), self.lhs_steps.clone()),
+            ProofCertificate::new(self.rhs.clone(), self.rhs_steps.clone()),
+        )
+    }
+
+    /// A single chain of steps from `lhs` to `rhs`.
+    ///
+    /// The right-hand steps are reversed: each is swapped end for end and its
+    /// direction flipped, so rules that only allow `Forward` appear as
+    /// `Backward` in the second half.
+    pub fn stitched(&self) -> Vec<ProofStep<T>> {
+        let mut steps = self.lhs_steps.clone();
+        steps.extend(self.rhs_steps.iter().rev().map(|step| ProofStep {
+            rule_name: step.rule_name.clone(),
+            direction: match step.direction {
+                RewriteDirection::Forward => RewriteDirection::Backward,
+                RewriteDirection::Backward => RewriteDirection::Forward,
+                RewriteDirection::Both => RewriteDirection::Both,
+            },
+            position: step.position.clone(),
+            substitution: step.substitution.clone(),
+            old_expr: step.new_expr.clone(),
+            new_expr: step.old_expr.clone(),
+        }));
+        steps
+    }
+}
+
+/// One direction of the search: a frontier and every form reached so far.
+struct Side<Node: HashNodeInner> {
+    heap: BinaryHeap<ProofState<Node>>,
+    reached: HashMap<u64, StepList<Node>>,
+}
+
+impl<Node: HashNodeInner> Side<Node> {
+    fn new(start: &HashNode<Node>, cost: u64) -> Self {
+        let mut heap = BinaryHeap::new();
+        heap.push(ProofState {
+            expr: start.clone(),
+            steps: StepList::new(),
+            path_cost: 0,
+            estimated_cost: cost,
+        });
+
+        let mut reached = HashMap::new();
+        reached.insert(start.hash(), StepList::new());
+        Self { heap, reached }
+    }
+}
+
+impl<Node: HashNodeInner + Clone, C: CostEstimator<Node>, T: TruthValue, G: GoalChecker<Node, T>>
+    Prover<Node, C, T, G>
+{
+    /// Prove `lhs = rhs` by rewriting both sides towards a common form.
+    ///
+    /// The side with the smaller frontier is expanded next. Successors are
+    /// checked against the other side's reached forms as they are generated,
+    /// so a meeting point is detected without waiting for it to be popped.
+    ///
+    /// A `max_depth` limit bounds the joined proof: a meeting only counts if
+    /// the two routes to it take at most `max_depth` steps together, so one
+    /// side may use every step while the other uses none. The node limit,
+    /// timeout, observer and failure cache apply as they do for `prove`.
+    pub fn prove_bidirectional(
+        &self,
+        lhs: &HashNode<Node>,
+        rhs: &HashNode<Node>,
+    ) -> Result<BidirectionalProof<Node>, ProofFailure<Node>> {
+        self.with_observer(|observer| self.prove_bidirectional_with_observer(lhs, rhs, observer))
+    }
+
+    /// Like `prove_bidirectional`, but reports search events to `observer`,
+    /// which may also abort the search.
+    pub fn prove_bidirectional_with_observer(
+        &self,
+        lhs: &HashNode<Node>,
+        rhs: &HashNode<Node>,
+        observer: &mut dyn SearchObserver<Node, T>,
+    ) -> Result<BidirectionalProof<Node>, ProofFailure<Node>> {
+        if let Some(failure) = self.cached_meeting_failure(lhs, rhs, self.max_nodes) {
+            return Err(failure);
+        }
+        let result = self.meet(lhs, rhs, observer);
+        if let Err(failure) = &result {
+            self.record_meeting_failure(lhs, rhs, self.max_nodes, failure.reason);
+        }
+        result
+    }
+
+    fn meet(
+        &self,
+        lhs: &HashNode<Node>,
+        rhs: &HashNode<Node>,
+        observer: &mut dyn SearchObserver<Node, T>,
+    ) -> Result<BidirectionalProof<Node>, ProofFailure<Node>> {
+        let started = Stopwatch::start();
+        let mut stats = SearchStats::new();
+        let mut nodes_explored = 0usize;
+        let mut closest: Option<ProofState<Node>> = None;
+
+        let mut sides = [
+            Side::new(lhs, self.cost_estimator.estimate_cost(lhs)),
+            Side::new(rhs, self.cost_estimator.estimate_cost(rhs)),
+        ];
+
+        let finish = |meeting_point: &HashNode<Node>, lhs_steps: &StepList<Node>, rhs_steps: &StepList<Node>, nodes_explored, mut stats: SearchStats| {
+            stats.elapsed = started.elapsed();
+            Ok(BidirectionalProof {
+                lhs: lhs.clone(),
+                rhs: rhs.clone(),
+                meeting_point: meeting_point.clone(),
+                lhs_steps: lhs_steps.to_vec(),
+                rhs_steps: rhs_steps.to_vec(),
+                nodes_explored,
+                stats,
+            })
+        };
+
+        if lhs == rhs {
+            return finish(lhs, &StepList::new(), &StepList::new(), 0, stats);
+        }
+
+        let reason = loop {
+            if nodes_explored >= self.max_nodes {
+                break FailureReason::NodeLimitReached;
+            }
+            if self.timeout.is_some_and(|timeout| started.elapsed() >= timeout) {
+                break FailureReason::TimedOut;
+            }
+
+            let current = match (sides[0].heap.len(), sides[1].heap.len()) {
+                (0, 0) => break FailureReason::Exhausted,
+                (0, _) => 1,
+                (_, 0) => 0,
+                (left, right) => usize::from(right < left),
+            };
+            let other = 1 - current;
+
+            let Some(state) = sides[current].heap.pop() else {
+                break FailureReason::Exhausted;
+            };
+            nodes_explored += 1;
+            stats.record_state(state.steps.len());
+            if closest.as_ref().is_none_or(|closest| state.estimated_cost < closest.estimated_cost) {
+                closest = Some(state.clone());
+            }
+
+            // The other side's start is always reached, so a side may spend
+            // the whole limit on its own.
+            if self.max_depth.is_some_and(|max_depth| state.steps.len() >= max_depth) {
+                continue;
+            }
+
+            if observer.on_state_expanded(&state, nodes_explored) == SearchControl::Abort {
+                break FailureReason::Aborted;
+            }
+
+            for rule in &self.rules {
+                for rewrite in rule.rewrites_at_all_positions(&state.expr, RewriteDirection::Forward, &self.store) {
+                    stats.record_rule(&rewrite.rule_name);
+
+                    // Under a depth limit, a form reached again by a shorter
+                    // route is kept, since it has more steps left to spend.
+                    let key = rewrite.term.hash();
+                    if sides[current].reached.get(&key).is_some_and(|reached| {
+                        self.max_depth.is_none() || reached.len() <= state.steps.len() + 1
+                    }) {
+                        stats.record_duplicate();
+                        continue;
+                    }
+
+                    let step = ProofStep {
+                        rule_name: rewrite.rule_name,
+                        direction: rewrite.direction,
+                        position: rewrite.position,
+                        substitution: rewrite.substitution,
+                        old_expr: state.expr.clone(),
+                        new_expr: rewrite.term.clone(),
+                    };
+                    observer.on_rule_applied(&step);
+                    let steps = state.steps.pushed(step);
+
+                    if let Some(other_steps) = sides[other].reached.get(&key)
+                        && self.max_depth.is_none_or(|max_depth| steps.len() + other_steps.len() <= max_depth)
+                    {
+                        let (lhs_steps, rhs_steps) = if current == 0 {
+                            (&steps, other_steps)
+                        } else {
+                            (other_steps, &steps)
+                        };
+                        return finish(&rewrite.term, lhs_steps, rhs_steps, nodes_explored, stats);
+                    }
+
+                    sides[current].reached.insert(key, steps.clone());
+                    sides[current].heap.push(ProofState {
+                        expr: rewrite.term.clone(),
+                        steps,
+                        path_cost: 0,
+                        estimated_cost: self.cost_estimator.estimate_cost(&rewrite.term),
+                    });
+                }
+            }
+            stats.record_frontier(sides[0].heap.len() + sides[1].heap.len());
+        };
+
+        stats.elapsed = started.elapsed();
+        Err(ProofFailure {
+            reason,
+            nodes_explored,
+            stats: Box::new(stats),
+            closest,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::{verify_proof, Cancellation, FailureCache, SizeCostEstimator};
+    use crate::BinaryTruth;
+    use std::time::Duration;
+
+    fn prover(max_nodes: usize) -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(max_nodes, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover
+    }
+
+    #[test]
+    fn test_sides_meet() {
+        let store = NodeStorage::new();
+        let lhs = add(numeral(2, &store), numeral(2, &store), &store);
+        let rhs = add(numeral(1, &store), numeral(3, &store), &store);
+
+        let proof = prover(1000).prove_bidirectional(&lhs, &rhs).ok().expect("sides should meet");
+
+        let (left, right) = proof.certificates();
+        assert_eq!(left.final_expr, proof.meeting_point);
+        assert_eq!(right.final_expr, proof.meeting_point);
+        assert_eq!(verify_proof(&left, &addition_rules(), &store), Ok(()));
+        assert_eq!(verify_proof(&right, &addition_rules(), &store), Ok(()));
+
+        let chain = ProofCertificate::new(lhs.clone(), proof.stitched());
+        assert!(chain.is_connected());
+        assert!(chain.final_expr == rhs);
+    }
+
+    #[test]
+    fn test_identical_sides() {
+        let store = NodeStorage::new();
+        let term = numeral(3, &store);
+
+        let proof = prover(10).prove_bidirectional(&term, &term).ok().expect("trivially equal");
+        assert!(proof.lhs_steps.is_empty() && proof.rhs_steps.is_empty());
+        assert_eq!(proof.nodes_explored, 0);
+    }
+
+    #[test]
+    fn test_depth_limit_bounds_joined_proof() {
+        let store = NodeStorage::new();
+        // The left side needs three steps to reach 4, the right side two.
+        let lhs = add(numeral(2, &store), numeral(2, &store), &store);
+        let rhs = add(numeral(3, &store), numeral(1, &store), &store);
+
+        let mut prover = prover(1000);
+        prover.set_max_depth(Some(4));
+        let failure = prover.prove_bidirectional(&lhs, &rhs).err().expect("five steps are needed");
+        assert_eq!(failure.reason, FailureReason::Exhausted);
+
+        prover.set_max_depth(Some(5));
+        let proof = prover.prove_bidirectional(&lhs, &rhs).ok().expect("sides should meet");
+        assert_eq!((proof.lhs_steps.len(), proof.rhs_steps.len()), (3, 2));
+    }
+
+    #[test]
+    fn test_depth_limit_allows_uneven_sides() {
+        let store = NodeStorage::new();
+        // Every step is spent on the left side.
+        let lhs = add(numeral(2, &store), numeral(2, &store), &store);
+        let rhs = numeral(4, &store);
+
+        let mut prover = prover(1000);
+        prover.set_max_depth(Some(4));
+        let proof = prover.prove_bidirectional(&lhs, &rhs).ok().expect("sides should meet");
+        assert_eq!((proof.lhs_steps.len(), proof.rhs_steps.len()), (3, 0));
+    }
+
+    #[test]
+    fn test_search_limits_apply() {
+        let store = NodeStorage::new();
+        let lhs = add(numeral(2, &store), numeral(1, &store), &store);
+        let rhs = numeral(2, &store);
+
+        let mut prover = prover(1000);
+        prover.set_timeout(Some(Duration::ZERO));
+        let failure = prover.prove_bidirectional(&lhs, &rhs).err().expect("no time to search");
+        assert_eq!(failure.reason, FailureReason::TimedOut);
+
+        prover.set_timeout(None);
+        let cancellation = Cancellation::new();
+        cancellation.cancel();
+        let failure = prover
+            .prove_bidirectional_with_observer(&lhs, &rhs, &mut cancellation.clone())
+            .err()
+            .expect("the search was cancelled");
+        assert_eq!(failure.reason, FailureReason::Aborted);
+
+        prover.set_failure_cache(FailureCache::new(16));
+        let failure = prover.prove_bidirectional(&lhs, &rhs).err().expect("2 + 1 is not 2");
+        assert_eq!(failure.reason, FailureReason::Exhausted);
+        let failure = prover.prove_bidirectional(&lhs, &rhs).err().expect("2 + 1 is not 2");
+        assert_eq!(failure.reason, FailureReason::Cached);
+        // A whole-statement search of the same sides is not affected.
+        assert!(!prover.take_failure_cache().unwrap().known_to_fail(&eq(lhs, rhs, &store), None, 1000));
+    }
+
+    #[test]
+    fn test_depth_limit_keeps_shorter_routes() {
+        use crate::proving::testing::detour_rules;
+
+        let store = NodeStorage::new();
+        let var = |index| HashNode::from_store(Term::Var(index), &store);
+        let mut prover: Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> =
+            Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in detour_rules(&store) {
+            prover.add_rule(rule);
+        }
+        prover.set_max_depth(Some(4));
+
+        let proof = prover.prove_bidirectional(&var(0), &var(9)).ok().expect("the detour is short enough");
+        assert_eq!((proof.lhs_steps.len(), proof.rhs_steps.len()), (4, 0));
+    }
+
+    #[test]
+    fn test_distinct_normal_forms_fail() {
+        let store = NodeStorage::new();
+        let lhs = add(numeral(2, &store), numeral(1, &store), &store);
+        let rhs = numeral(2, &store);
+
+        let failure = prover(1000).prove_bidirectional(&lhs, &rhs).err().expect("2 + 1 is not 2");
+        assert_eq!(failure.reason, FailureReason::Exhausted);
+    }
+}
@@ -0,0 +1,223 @@
+//! Fluent construction of `Prover`s.
+//!
+//! `Prover::new` takes only the essentials; everything else is set afterwards
+//! one call at a time. `ProverBuilder` gathers the whole configuration in a
+//! single expression:
+//!
+//! ```ignore
+//! let prover = Prover::builder(SizeCostEstimator, checker)
+//!     .rules(rules)
+//!     .strategy(SearchStrategy::AStar)
+//!     .max_nodes(10_000)
+//!     .timeout(Duration::from_secs(5))
+//!     .build();
+//! ```
+
+use crate::base::nodes::HashNodeInner;
+use crate::proving::failures::FailureCache;
+use crate::proving::lemmas::LemmaCache;
+use crate::proving::observer::SearchObserver;
+use crate::proving::{CostEstimator, GoalChecker, Prover, StepCost};
+use crate::rewriting::RewriteRule;
+use crate::truth::TruthValue;
+use std::time::Duration;
+
+/// How the search orders its frontier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchStrategy {
+    /// Estimated cost plus the step costs of the path so far (see `StepCost`).
+    AStar,
+    /// Estimated cost alone; step costs are ignored.
+    BestFirst,
+}
+
+/// Builder for a fully configured `Prover`.
+pub struct ProverBuilder<Node: HashNodeInner + Clone, C: CostEstimator<Node>, T: TruthValue, G: GoalChecker<Node, T>> {
+    cost_estimator: C,
+    goal_checker: G,
+    rules: Vec<RewriteRule<Node>>,
+    max_nodes: usize,
+    max_depth: Option<usize>,
+    strict: bool,
+    strategy: SearchStrategy,
+    timeout: Option<Duration>,
+    step_cost: Option<Box<dyn StepCost<Node>>>,
+    lemmas: Option<LemmaCache<Node, T>>,
+    failures: Option<FailureCache<Node>>,
+    observer: Option<Box<dyn SearchObserver<Node, T>>>,
+}
+
+impl<Node: HashNodeInner + Clone, C: CostEstimator<Node>, T: TruthValue, G: GoalChecker<Node, T>>
+    ProverBuilder<Node, C, T, G>
+{
+    /// Node budget used unless `max_nodes` is called.
+    pub const DEFAULT_MAX_NODES: usize = 10_000;
+
+    pub fn new(cost_estimator: C, goal_checker: G) -> Self {
+        Self {
+            cost_estimator,
+            goal_checker,
+            rules: Vec::new(),
+            max_nodes: Self::DEFAULT_MAX_NODES,
+            max_depth: None,
+            strict: false,
+            strategy: SearchStrategy::AStar,
+            timeout: None,
+            step_cost: None,
+            lemmas: None,
+            failures: None,
+            observer: None,
+        }
+    }
+
+    pub fn rule(mut self, rule: RewriteRule<Node>) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Add every rule of `rules`, such as the output of an `AxiomConverter`.
+    pub fn rules(mut self, rules: impl IntoIterator<Item = RewriteRule<Node>>) -> Self {
+        self.rules.extend(rules);
+        self
+    }
+
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    /// See `Prover::set_max_depth`.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// See `Prover::set_strict`.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn strategy(mut self, strategy: SearchStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// See `Prover::set_timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn step_cost(mut self, step_cost: Box<dyn StepCost<Node>>) -> Self {
+        self.step_cost = Some(step_cost);
+        self
+    }
+
+    pub fn lemma_cache(mut self, cache: LemmaCache<Node, T>) -> Self {
+        self.lemmas = Some(cache);
+        self
+    }
+
+    pub fn failure_cache(mut self, cache: FailureCache<Node>) -> Self {
+        self.failures = Some(cache);
+        self
+    }
+
+    /// Observer for `prove` and `prove_all`; see `Prover::set_observer`.
+    pub fn observer(mut self, observer: Box<dyn SearchObserver<Node, T>>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    pub fn build(self) -> Prover<Node, C, T, G> {
+        let mut prover = Prover::new(self.max_nodes, self.cost_estimator, self.goal_checker);
+        for rule in self.rules {
+            prover.add_rule(rule);
+        }
+        prover.set_max_depth(self.max_depth);
+        prover.set_strict(self.strict);
+        prover.set_strategy(self.strategy);
+        prover.set_timeout(self.timeout);
+        if let Some(step_cost) = self.step_cost {
+            prover.set_step_cost(step_cost);
+        }
+        if let Some(cache) = self.lemmas {
+            prover.set_lemma_cache(cache);
+        }
+        if let Some(cache) = self.failures {
+            prover.set_failure_cache(cache);
+        }
+        if let Some(observer) = self.observer {
+            prover.set_observer(observer);
+        }
+        prover
+    }
+}
+
+impl<Node: HashNodeInner + Clone, C: CostEstimator<Node>, T: TruthValue, G: GoalChecker<Node, T>>
+    Prover<Node, C, T, G>
+{
+    /// Start configuring a prover with the given cost estimator and goal checker.
+    pub fn builder(cost_estimator: C, goal_checker: G) -> ProverBuilder<Node, C, T, G> {
+        ProverBuilder::new(cost_estimator, goal_checker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::{HashNode, NodeStorage};
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::{FailureReason, ProofState, SearchControl, SizeCostEstimator, UniformStepCost};
+    use crate::BinaryTruth;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct CountExpansions(Arc<AtomicUsize>);
+
+    impl SearchObserver<Term, BinaryTruth> for CountExpansions {
+        fn on_state_expanded(&mut self, _state: &ProofState<Term>, _nodes_explored: usize) -> SearchControl {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            SearchControl::Continue
+        }
+    }
+
+    #[test]
+    fn test_builder_configures_prover() {
+        let store = NodeStorage::new();
+        let goal = eq(add(numeral(2, &store), numeral(2, &store), &store), numeral(4, &store), &store);
+        let expansions = Arc::new(AtomicUsize::new(0));
+
+        let prover: Prover<_, _, BinaryTruth, _> = Prover::builder(SizeCostEstimator, TermReflexivity)
+            .rules(addition_rules())
+            .strategy(SearchStrategy::AStar)
+            .step_cost(Box::new(UniformStepCost(1)))
+            .max_nodes(100)
+            .timeout(Duration::from_secs(60))
+            .observer(Box::new(CountExpansions(expansions.clone())))
+            .build();
+
+        let result = prover.prove(&goal).expect("2 + 2 = 4");
+        assert_eq!(result.steps.len(), 3);
+        assert!(expansions.load(Ordering::Relaxed) > 0);
+
+        let seen = expansions.load(Ordering::Relaxed);
+        assert_eq!(prover.prove_all(std::slice::from_ref(&goal)).len(), 1);
+        assert!(expansions.load(Ordering::Relaxed) > seen);
+    }
+
+    #[test]
+    fn test_zero_timeout_fails_immediately() {
+        let store = NodeStorage::new();
+        let goal: HashNode<Term> = eq(numeral(1, &store), numeral(1, &store), &store);
+
+        let prover: Prover<_, _, BinaryTruth, _> = Prover::builder(SizeCostEstimator, TermReflexivity)
+            .timeout(Duration::ZERO)
+            .build();
+        let Err(failure) = prover.prove(&goal) else {
+            panic!("a zero timeout leaves no time to search");
+        };
+        assert_eq!(failure.reason, FailureReason::TimedOut);
+    }
+}
@@ -0,0 +1,157 @@
+//! Checkable proof certificates.
+//!
+//! A `ProofResult` tells the caller that the search succeeded; a
+//! `ProofCertificate` records enough about each step (rule, direction,
+//! position and substitution) for an independent checker to re-derive it
+//! without trusting the search code or the goal checker's hash comparisons.
+
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use crate::proving::generalize::{replace_subterm, Generalization};
+use crate::proving::{ProofResult, ProofStep};
+use crate::truth::TruthValue;
+
+/// A replayable record of a proof: the starting expression, every rewrite
+/// step applied to it, and the expression the steps end at.
+pub struct ProofCertificate<T: HashNodeInner> {
+    /// The statement the proof starts from.
+    pub initial_expr: HashNode<T>,
+    /// Rewrite steps, each carrying its rule, direction, position and substitution.
+    pub steps: Vec<ProofStep<T>>,
+    /// The expression accepted by the goal checker.
+    pub final_expr: HashNode<T>,
+    /// Variables of `initial_expr` that stand for subterms of the statement
+    /// actually proven; empty unless the goal was generalized.
+    pub instantiation: Vec<Generalization<T>>,
+}
+
+impl<T: HashNodeInner> ProofCertificate<T> {
+    /// Create a certificate from an initial expression and its steps.
+    ///
+    /// The final expression is the result of the last step, or the initial
+    /// expression when there are no steps.
+    pub fn new(initial_expr: HashNode<T>, steps: Vec<ProofStep<T>>) -> Self {
+        let final_expr = steps
+            .last()
+            .map(|step| step.new_expr.clone())
+            .unwrap_or_else(|| initial_expr.clone());
+
+        Self {
+            initial_expr,
+            steps,
+            final_expr,
+            instantiation: Vec::new(),
+        }
+    }
+
+    /// Build a certificate from a successful search result.
+    pub fn from_result<V: TruthValue>(result: &ProofResult<T, V>) -> Self {
+        let initial_expr = result
+            .steps
+            .first()
+            .map(|step| step.old_expr.clone())
+            .unwrap_or_else(|| result.final_expr.clone());
+
+        Self {
+            initial_expr,
+            steps: result.steps.clone(),
+            final_expr: result.final_expr.clone(),
+            instantiation: Vec::new(),
+        }
+    }
+
+    /// Number of rewrite steps in the certificate.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Names of the rules used, in application order.
+    pub fn rule_names(&self) -> Vec<&str> {
+        self.steps.iter().map(|step| step.rule_name.as_str()).collect()
+    }
+
+    /// Check that consecutive steps chain together, starting at `initial_expr`
+    /// and ending at `final_expr`.
+    ///
+    /// This is a purely structural check; it does not re-apply any rules.
+    pub fn is_connected(&self) -> bool {
+        let mut current = &self.initial_expr;
+
+        for step in &self.steps {
+            if step.old_expr != *current {
+                return false;
+            }
+            current = &step.new_expr;
+        }
+
+        *current == self.final_expr
+    }
+}
+
+impl<T: HashNodeInner + Clone> ProofCertificate<T> {
+    /// The statement proven: `initial_expr` with the instantiation applied.
+    pub fn instantiated_statement(&self, store: &NodeStorage<T>) -> HashNode<T> {
+        self.instantiation.iter().fold(self.initial_expr.clone(), |expr, generalization| {
+            replace_subterm(&expr, &generalization.variable, &generalization.instance, store)
+        })
+    }
+}
+
+impl<T: HashNodeInner> Clone for ProofCertificate<T> {
+    fn clone(&self) -> Self {
+        Self {
+            initial_expr: self.initial_expr.clone(),
+            steps: self.steps.clone(),
+            final_expr: self.final_expr.clone(),
+            instantiation: self.instantiation.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::rewriting::{RewriteDirection, Substitution};
+
+    fn step(rule: &str, old: &HashNode<u64>, new: &HashNode<u64>) -> ProofStep<u64> {
+        ProofStep {
+            rule_name: rule.to_string(),
+            direction: RewriteDirection::Forward,
+            position: Vec::new(),
+            substitution: Substitution::new(),
+            old_expr: old.clone(),
+            new_expr: new.clone(),
+        }
+    }
+
+    #[test]
+    fn test_empty_certificate() {
+        let store = NodeStorage::new();
+        let expr = HashNode::from_store(1u64, &store);
+        let cert = ProofCertificate::new(expr.clone(), Vec::new());
+
+        assert!(cert.is_empty());
+        assert!(cert.is_connected());
+        assert_eq!(cert.final_expr, expr);
+    }
+
+    #[test]
+    fn test_connected_chain() {
+        let store = NodeStorage::new();
+        let a = HashNode::from_store(1u64, &store);
+        let b = HashNode::from_store(2u64, &store);
+        let c = HashNode::from_store(3u64, &store);
+
+        let cert = ProofCertificate::new(a.clone(), vec![step("r1", &a, &b), step("r2", &b, &c)]);
+        assert!(cert.is_connected());
+        assert_eq!(cert.rule_names(), vec!["r1", "r2"]);
+        assert_eq!(cert.final_expr, c);
+
+        let broken = ProofCertificate::new(a.clone(), vec![step("r1", &a, &b), step("r2", &a, &c)]);
+        assert!(!broken.is_connected());
+    }
+}
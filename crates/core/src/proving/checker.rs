@@ -0,0 +1,275 @@
+//! Independent proof checking.
+//!
+//! `verify_proof` replays a `ProofCertificate` step by step using only the
+//! rewrite rules and the position/substitution data recorded in the
+//! certificate. It shares no code with the search loop, so a bug in the
+//! prover cannot make an invalid proof pass.
+
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use crate::proving::certificate::ProofCertificate;
+use crate::proving::ProofStep;
+use crate::rewriting::{replace_at, subterm_at, RewriteDirection, RewriteRule, Substitution, Unifiable};
+use std::fmt::{Display, Formatter};
+
+/// Reasons a certificate can be rejected by `verify_proof`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProofCheckError {
+    /// A step does not start from the expression the previous step produced.
+    Disconnected { step: usize },
+    /// A step names a rule that is not in the provided rule set.
+    UnknownRule { step: usize, rule: String },
+    /// A step applies a rule in a direction the rule does not allow.
+    InvalidDirection { step: usize, rule: String },
+    /// A step's position does not address a subterm of its input.
+    InvalidPosition { step: usize },
+    /// The rule does not match the subterm at the recorded position.
+    RuleMismatch { step: usize, rule: String },
+    /// The recorded substitution disagrees with the one obtained by re-matching.
+    SubstitutionMismatch { step: usize, variable: u32 },
+    /// Re-applying the rule produces a different expression than recorded.
+    ResultMismatch { step: usize },
+    /// The last step does not end at the certificate's final expression.
+    FinalMismatch,
+    /// The certificate does not start from the statement it should prove.
+    InitialMismatch,
+}
+
+impl Display for ProofCheckError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProofCheckError::Disconnected { step } => {
+                write!(f, "Step {} does not start where the previous step ended", step + 1)
+            }
+            ProofCheckError::UnknownRule { step, rule } => {
+                write!(f, "Step {} uses unknown rule '{}'", step + 1, rule)
+            }
+            ProofCheckError::InvalidDirection { step, rule } => {
+                write!(f, "Step {} applies rule '{}' in a direction it does not allow", step + 1, rule)
+            }
+            ProofCheckError::InvalidPosition { step } => {
+                write!(f, "Step {} has a position outside its input expression", step + 1)
+            }
+            ProofCheckError::RuleMismatch { step, rule } => {
+                write!(f, "Step {}: rule '{}' does not match at the recorded position", step + 1, rule)
+            }
+            ProofCheckError::SubstitutionMismatch { step, variable } => {
+                write!(f, "Step {}: recorded binding for /{} is inconsistent", step + 1, variable)
+            }
+            ProofCheckError::ResultMismatch { step } => {
+                write!(f, "Step {} does not produce its recorded result", step + 1)
+            }
+            ProofCheckError::FinalMismatch => {
+                write!(f, "Proof does not end at the certificate's final expression")
+            }
+            ProofCheckError::InitialMismatch => {
+                write!(f, "Proof does not start from the statement it should prove")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProofCheckError {}
+
+/// Re-check every step of `certificate` against `rules`.
+///
+/// For each step the named rule is re-matched at the recorded position in
+/// the recorded direction, the recorded substitution is compared with the
+/// match, and the rewritten expression is rebuilt in `store` and compared
+/// with the step's result.
+pub fn verify_proof<T: HashNodeInner + Unifiable>(
+    certificate: &ProofCertificate<T>,
+    rules: &[RewriteRule<T>],
+    store: &NodeStorage<T>,
+) -> Result<(), ProofCheckError> {
+    let mut current = certificate.initial_expr.clone();
+
+    for (index, step) in certificate.steps.iter().enumerate() {
+        if step.old_expr != current {
+            return Err(ProofCheckError::Disconnected { step: index });
+        }
+
+        let (produced, matched) = apply_step_rule(index, step, &current, rules, store)?;
+        check_substitution(index, &step.substitution, &matched)?;
+
+        if produced != step.new_expr {
+            return Err(ProofCheckError::ResultMismatch { step: index });
+        }
+
+        current = produced;
+    }
+
+    if current != certificate.final_expr {
+        return Err(ProofCheckError::FinalMismatch);
+    }
+
+    Ok(())
+}
+
+/// Apply the rule named by `step` to `current` at the step's position and direction.
+///
+/// The recorded substitution and result of `step` are ignored; the rule is
+/// re-matched against `current`. This lets callers re-derive a step on an
+/// expression other than the one it was recorded on (e.g. after earlier steps
+/// were dropped). On success the returned step describes the new application.
+pub fn replay_step<T: HashNodeInner + Unifiable>(
+    step: &ProofStep<T>,
+    current: &HashNode<T>,
+    rules: &[RewriteRule<T>],
+    store: &NodeStorage<T>,
+) -> Result<ProofStep<T>, ProofCheckError> {
+    replay_step_at(0, step, current, rules, store)
+}
+
+/// `replay_step` for the step at `index`, which errors are reported against.
+pub(crate) fn replay_step_at<T: HashNodeInner + Unifiable>(
+    index: usize,
+    step: &ProofStep<T>,
+    current: &HashNode<T>,
+    rules: &[RewriteRule<T>],
+    store: &NodeStorage<T>,
+) -> Result<ProofStep<T>, ProofCheckError> {
+    let (produced, substitution) = apply_step_rule(index, step, current, rules, store)?;
+
+    Ok(ProofStep {
+        rule_name: step.rule_name.clone(),
+        direction: step.direction,
+        position: step.position.clone(),
+        substitution,
+        old_expr: current.clone(),
+        new_expr: produced,
+    })
+}
+
+fn apply_step_rule<T: HashNodeInner + Unifiable>(
+    index: usize,
+    step: &ProofStep<T>,
+    current: &HashNode<T>,
+    rules: &[RewriteRule<T>],
+    store: &NodeStorage<T>,
+) -> Result<(HashNode<T>, Substitution<T>), ProofCheckError> {
+    let rule = rules
+        .iter()
+        .find(|rule| rule.name == step.rule_name)
+        .ok_or_else(|| ProofCheckError::UnknownRule {
+            step: index,
+            rule: step.rule_name.clone(),
+        })?;
+
+    let (source, target) = match (step.direction, rule.direction) {
+        (RewriteDirection::Forward, RewriteDirection::Forward | RewriteDirection::Both) => {
+            (&rule.pattern, &rule.replacement)
+        }
+        (RewriteDirection::Backward, RewriteDirection::Backward | RewriteDirection::Both) => {
+            (&rule.replacement, &rule.pattern)
+        }
+        _ => {
+            return Err(ProofCheckError::InvalidDirection {
+                step: index,
+                rule: rule.name.clone(),
+            });
+        }
+    };
+
+    let subterm = subterm_at(current, &step.position)
+        .ok_or(ProofCheckError::InvalidPosition { step: index })?;
+
+    let matched = T::unify(source, &subterm, &Substitution::new(), store).map_err(|_| {
+        ProofCheckError::RuleMismatch {
+            step: index,
+            rule: rule.name.clone(),
+        }
+    })?;
+
+    let rewritten = target
+        .instantiate(&matched, store)
+        .ok_or(ProofCheckError::ResultMismatch { step: index })?;
+    let produced = replace_at(current, &step.position, rewritten, store)
+        .ok_or(ProofCheckError::InvalidPosition { step: index })?;
+
+    Ok((produced, matched))
+}
+
+/// Every binding recorded in the certificate must agree with the re-match.
+fn check_substitution<T: HashNodeInner>(
+    step: usize,
+    recorded: &Substitution<T>,
+    matched: &Substitution<T>,
+) -> Result<(), ProofCheckError> {
+    for (variable, term) in recorded.iter() {
+        let consistent = matched
+            .get(*variable)
+            .is_some_and(|bound: &HashNode<T>| bound == term);
+
+        if !consistent {
+            return Err(ProofCheckError::SubstitutionMismatch {
+                step,
+                variable: *variable,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, TermReflexivity};
+    use crate::proving::{Prover, SizeCostEstimator};
+
+    fn proved_certificate(store: &NodeStorage<crate::proving::testing::Term>) -> ProofCertificate<crate::proving::testing::Term> {
+        let mut prover = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+
+        // S(0) + S(0) = S(S(0))
+        let goal = eq(add(numeral(1, store), numeral(1, store), store), numeral(2, store), store);
+        prover.prove(&goal).expect("goal should be provable").certificate()
+    }
+
+    #[test]
+    fn test_prover_certificate_verifies() {
+        let store = NodeStorage::new();
+        let cert = proved_certificate(&store);
+
+        assert!(!cert.is_empty());
+        assert_eq!(verify_proof(&cert, &addition_rules(), &store), Ok(()));
+    }
+
+    #[test]
+    fn test_unknown_rule_rejected() {
+        let store = NodeStorage::new();
+        let mut cert = proved_certificate(&store);
+        cert.steps[0].rule_name = "made_up".to_string();
+
+        assert!(matches!(
+            verify_proof(&cert, &addition_rules(), &store),
+            Err(ProofCheckError::UnknownRule { step: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_wrong_position_rejected() {
+        let store = NodeStorage::new();
+        let mut cert = proved_certificate(&store);
+        cert.steps[0].position = vec![1];
+
+        assert!(verify_proof(&cert, &addition_rules(), &store).is_err());
+    }
+
+    #[test]
+    fn test_tampered_result_rejected() {
+        let store = NodeStorage::new();
+        let mut cert = proved_certificate(&store);
+        let bogus = eq(numeral(3, &store), numeral(3, &store), &store);
+        let last = cert.steps.len() - 1;
+        cert.steps[last].new_expr = bogus.clone();
+        cert.final_expr = bogus;
+
+        assert_eq!(
+            verify_proof(&cert, &addition_rules(), &store),
+            Err(ProofCheckError::ResultMismatch { step: last })
+        );
+    }
+}
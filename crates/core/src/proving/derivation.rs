@@ -0,0 +1,282 @@
+//! Natural-deduction (Fitch-style) rendering of proofs.
+//!
+//! The search rewrites a statement until the goal checker accepts it, so a
+//! certificate reads backwards as a derivation: the accepted final expression
+//! comes first, and each earlier expression follows from the one after it by
+//! the rule that was applied. Steps by rules derived from implications (rules
+//! that only rewrite forwards) are shown as implication elimination, steps by
+//! bidirectional rules as substitution of equals. Hypotheses from
+//! `Prover::prove_with_hypotheses` open nested subproofs that are closed by
+//! implication introduction.
+
+use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::base::printing::{DisplayPrinter, ExprPrinter};
+use crate::proving::certificate::ProofCertificate;
+use crate::rewriting::{RewriteDirection, RewriteRule, Unifiable};
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+/// How a rule is cited in a derivation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InferenceRule {
+    /// Implication elimination (modus ponens), cited as `→E`.
+    ImplicationElim,
+    /// Substitution of equals or equivalents, cited as `=E`.
+    Replacement,
+    /// Any other rewrite, cited by rule name only.
+    Rewrite,
+}
+
+/// One numbered line of a derivation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationLine {
+    /// Line number, starting at 1.
+    pub number: usize,
+    /// Subproof nesting depth; 0 is the main proof.
+    pub depth: usize,
+    /// The formula derived on this line.
+    pub formula: String,
+    /// Why the formula holds.
+    pub justification: String,
+    /// Whether the line is an assumption opening a subproof.
+    pub assumption: bool,
+}
+
+/// A rendered derivation. `Display` prints it with Fitch bars.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Derivation {
+    pub lines: Vec<DerivationLine>,
+}
+
+impl Display for Derivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number_width = self.lines.len().to_string().len();
+        let formula_width = self
+            .lines
+            .iter()
+            .map(|line| 2 * line.depth + line.formula.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        for line in &self.lines {
+            let bars = "│ ".repeat(line.depth + 1);
+            let padding = formula_width - 2 * line.depth - line.formula.chars().count();
+            writeln!(
+                f,
+                "{:>width$} {}{}{}   {}",
+                line.number,
+                bars,
+                line.formula,
+                " ".repeat(padding),
+                line.justification,
+                width = number_width
+            )?;
+            if line.assumption {
+                writeln!(f, "{:>width$} {}├──", "", "│ ".repeat(line.depth), width = number_width)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders `ProofCertificate`s as natural-deduction derivations.
+pub struct NaturalDeduction {
+    kinds: HashMap<String, InferenceRule>,
+    default: InferenceRule,
+    closing: String,
+}
+
+impl Default for NaturalDeduction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NaturalDeduction {
+    /// A renderer citing every rule by name only.
+    pub fn new() -> Self {
+        Self {
+            kinds: HashMap::new(),
+            default: InferenceRule::Rewrite,
+            closing: "goal".to_string(),
+        }
+    }
+
+    /// A renderer classifying `rules` by direction: forward-only rules (as
+    /// produced from `Implies` axioms) are implication eliminations, and
+    /// bidirectional rules are replacements.
+    pub fn from_rules<Node: HashNodeInner + Unifiable>(rules: &[RewriteRule<Node>]) -> Self {
+        rules.iter().fold(Self::new(), |renderer, rule| {
+            let kind = match rule.direction {
+                RewriteDirection::Both => InferenceRule::Replacement,
+                RewriteDirection::Forward | RewriteDirection::Backward => InferenceRule::ImplicationElim,
+            };
+            renderer.with_rule(&rule.name, kind)
+        })
+    }
+
+    /// Cite the rule named `name` as `kind`.
+    pub fn with_rule(mut self, name: &str, kind: InferenceRule) -> Self {
+        self.kinds.insert(name.to_string(), kind);
+        self
+    }
+
+    /// How to cite rules that were not registered.
+    pub fn with_default(mut self, kind: InferenceRule) -> Self {
+        self.default = kind;
+        self
+    }
+
+    /// Justification of the first line, the expression the goal checker accepted.
+    pub fn with_closing(mut self, justification: &str) -> Self {
+        self.closing = justification.to_string();
+        self
+    }
+
+    /// Derive the statement proven by `certificate` without hypotheses.
+    pub fn derive<Node: HashNodeInner + Display>(&self, certificate: &ProofCertificate<Node>) -> Derivation {
+        self.derive_with_hypotheses(certificate, &[])
+    }
+
+    /// Derive `h_0 → (h_1 → ... → statement)` from a certificate proven under
+    /// `hypotheses`. Steps by the rules `hyp_i` cite the assumption line of
+    /// the `i`th hypothesis.
+    pub fn derive_with_hypotheses<Node: HashNodeInner + Display>(
+        &self,
+        certificate: &ProofCertificate<Node>,
+        hypotheses: &[HashNode<Node>],
+    ) -> Derivation {
+        self.derive_with(certificate, hypotheses, &DisplayPrinter)
+    }
+
+    /// `derive_with_hypotheses` with formulas rendered by `printer`.
+    pub fn derive_with<Node: HashNodeInner>(
+        &self,
+        certificate: &ProofCertificate<Node>,
+        hypotheses: &[HashNode<Node>],
+        printer: &dyn ExprPrinter<Node>,
+    ) -> Derivation {
+        let print = |expr: &HashNode<Node>| printer.print(&expr.value);
+        let mut lines = Vec::new();
+        let mut push = |depth: usize, formula: String, justification: String, assumption: bool| {
+            let number = lines.len() + 1;
+            lines.push(DerivationLine { number, depth, formula, justification, assumption });
+            number
+        };
+
+        let assumptions: Vec<usize> = hypotheses
+            .iter()
+            .enumerate()
+            .map(|(i, hypothesis)| push(i + 1, print(hypothesis), "assumption".to_string(), true))
+            .collect();
+
+        let depth = hypotheses.len();
+        let mut previous = push(depth, print(&certificate.final_expr), self.closing.clone(), false);
+        for step in certificate.steps.iter().rev() {
+            let justification = self.justify(&step.rule_name, step.direction, previous, &assumptions);
+            previous = push(depth, print(&step.old_expr), justification, false);
+        }
+
+        let mut conclusion = print(&certificate.initial_expr);
+        for (i, hypothesis) in hypotheses.iter().enumerate().rev() {
+            conclusion = if i + 1 == hypotheses.len() {
+                format!("{} → {}", print(hypothesis), conclusion)
+            } else {
+                format!("{} → ({})", print(hypothesis), conclusion)
+            };
+            let justification = format!("→I {}–{}", assumptions[i], previous);
+            previous = push(i, conclusion.clone(), justification, false);
+        }
+
+        Derivation { lines }
+    }
+
+    fn justify(&self, rule_name: &str, direction: RewriteDirection, from: usize, assumptions: &[usize]) -> String {
+        let (kind, cited) = match rule_name
+            .strip_prefix("hyp_")
+            .and_then(|index| index.parse::<usize>().ok())
+            .and_then(|index| assumptions.get(index))
+        {
+            Some(line) => (InferenceRule::Replacement, line.to_string()),
+            None => (self.kinds.get(rule_name).copied().unwrap_or(self.default), rule_name.to_string()),
+        };
+        let reversed = if direction == RewriteDirection::Backward { "⁻¹" } else { "" };
+
+        match kind {
+            InferenceRule::ImplicationElim => format!("→E {}{}, {}", cited, reversed, from),
+            InferenceRule::Replacement => format!("=E {}{}, {}", cited, reversed, from),
+            InferenceRule::Rewrite => format!("{}{}, {}", cited, reversed, from),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::hypotheses::EquationRules;
+    use crate::proving::lemmas::EquationSides;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, succ, Term, TermReflexivity};
+    use crate::proving::{Prover, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    struct TermSides;
+
+    impl EquationSides<Term> for TermSides {
+        fn sides(&self, expr: &HashNode<Term>) -> Option<(HashNode<Term>, HashNode<Term>)> {
+            match expr.value.as_ref() {
+                Term::Eq(l, r) => Some((l.clone(), r.clone())),
+                _ => None,
+            }
+        }
+    }
+
+    fn prover() -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover
+    }
+
+    #[test]
+    fn test_derivation_runs_backwards() {
+        let store = NodeStorage::new();
+        let statement = eq(add(numeral(1, &store), numeral(1, &store), &store), numeral(2, &store), &store);
+        let result = prover().prove(&statement).expect("1 + 1 = 2");
+        let certificate = ProofCertificate::from_result(&result);
+
+        // The addition rules only rewrite forwards, like rules from `Implies` axioms.
+        let derivation = NaturalDeduction::from_rules(&addition_rules()).with_closing("=I").derive(&certificate);
+
+        let lines = &derivation.lines;
+        assert_eq!(lines.len(), certificate.len() + 1);
+        assert_eq!(lines[0].justification, "=I");
+        assert_eq!(lines[0].formula, certificate.final_expr.to_string());
+        assert_eq!(lines.last().unwrap().formula, statement.to_string());
+        assert!(lines[1..].iter().all(|line| line.justification.starts_with("→E ")));
+        assert!(derivation.to_string().lines().all(|line| line.contains('│')));
+    }
+
+    #[test]
+    fn test_hypotheses_are_discharged() {
+        let store = NodeStorage::new();
+        let hypothesis = eq(add(numeral(1, &store), numeral(1, &store), &store), numeral(3, &store), &store);
+        let goal = eq(succ(add(numeral(1, &store), numeral(1, &store), &store), &store), numeral(4, &store), &store);
+        let result = prover()
+            .prove_with_hypotheses(&goal, std::slice::from_ref(&hypothesis), &EquationRules(TermSides))
+            .expect("goal follows from the hypothesis");
+
+        let derivation = NaturalDeduction::new().derive_with_hypotheses(&ProofCertificate::from_result(&result), std::slice::from_ref(&hypothesis));
+        let lines = &derivation.lines;
+
+        assert!(lines[0].assumption);
+        assert_eq!((lines[0].number, lines[0].depth), (1, 1));
+        assert!(lines.iter().any(|line| line.justification.starts_with("=E 1")));
+
+        let last = lines.last().unwrap();
+        assert_eq!(last.depth, 0);
+        assert_eq!(last.formula, format!("{} → {}", hypothesis, goal));
+        assert_eq!(last.justification, format!("→I 1–{}", last.number - 1));
+    }
+}
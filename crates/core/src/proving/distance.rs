@@ -0,0 +1,192 @@
+//! Cost estimators measuring how far apart the sides of an equation are.
+//!
+//! An equation is proven once its sides coincide, so the distance between
+//! them is a natural heuristic. These estimators are the recommended
+//! defaults for equational domains; `SizeCostEstimator` only rewards small
+//! expressions, whatever their sides look like. Expressions that are not
+//! equations (according to the given `EquationSides`) fall back to their size.
+
+use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::proving::lemmas::EquationSides;
+use crate::proving::CostEstimator;
+use std::collections::HashMap;
+
+/// Approximate tree edit distance between the sides of an equation.
+///
+/// Subtrees are aligned top-down: identical subtrees cost nothing, nodes with
+/// the same arity are relabelled (cost 1 if the symbol differs) and their
+/// children compared pairwise, a subtree nested directly inside the other
+/// costs the wrapping nodes, and anything else costs the larger subtree.
+/// Comparison stops once `bound` is reached.
+pub struct TreeEditDistanceEstimator<Node: HashNodeInner> {
+    sides: Box<dyn EquationSides<Node>>,
+    bound: u64,
+}
+
+impl<Node: HashNodeInner> TreeEditDistanceEstimator<Node> {
+    pub fn new(sides: Box<dyn EquationSides<Node>>) -> Self {
+        Self { sides, bound: u64::MAX }
+    }
+
+    /// Stop comparing once the distance reaches `bound`.
+    pub fn with_bound(mut self, bound: u64) -> Self {
+        self.bound = bound;
+        self
+    }
+
+    /// Approximate edit distance between `a` and `b`, at most `bound`.
+    pub fn distance(&self, a: &HashNode<Node>, b: &HashNode<Node>) -> u64 {
+        tree_distance(a, b, self.bound)
+    }
+}
+
+fn tree_distance<Node: HashNodeInner>(a: &HashNode<Node>, b: &HashNode<Node>, bound: u64) -> u64 {
+    if a == b || bound == 0 {
+        return 0;
+    }
+
+    match (a.value.decompose(), b.value.decompose()) {
+        (Some((op_a, children_a)), Some((op_b, children_b))) if children_a.len() == children_b.len() => {
+            let mut cost = u64::from(op_a != op_b);
+            for (x, y) in children_a.iter().zip(&children_b) {
+                if cost >= bound {
+                    return bound;
+                }
+                cost = cost.saturating_add(tree_distance(x, y, bound - cost));
+            }
+            cost.min(bound)
+        }
+        (None, None) => 1,
+        (decomposed_a, decomposed_b) => {
+            let nested = |outer: &HashNode<Node>, parts: Option<(u64, Vec<HashNode<Node>>)>, inner: &HashNode<Node>| {
+                parts
+                    .is_some_and(|(_, children)| children.contains(inner))
+                    .then(|| outer.size().saturating_sub(inner.size()))
+            };
+            nested(a, decomposed_a, b)
+                .or_else(|| nested(b, decomposed_b, a))
+                .unwrap_or_else(|| a.size().max(b.size()))
+                .min(bound)
+        }
+    }
+}
+
+impl<Node: HashNodeInner> CostEstimator<Node> for TreeEditDistanceEstimator<Node> {
+    fn estimate_cost(&self, expr: &HashNode<Node>) -> u64 {
+        match self.sides.sides(expr) {
+            Some((lhs, rhs)) => self.distance(&lhs, &rhs),
+            None => expr.size(),
+        }
+    }
+}
+
+/// Size of the symmetric difference between the symbol multisets of the
+/// sides of an equation.
+///
+/// Compound nodes contribute their opcode and leaves their hash. Cheaper than
+/// the tree edit distance and blind to structure, but never zero for sides
+/// that differ in what they contain.
+pub struct SymbolMultisetDistanceEstimator<Node: HashNodeInner> {
+    sides: Box<dyn EquationSides<Node>>,
+}
+
+impl<Node: HashNodeInner> SymbolMultisetDistanceEstimator<Node> {
+    pub fn new(sides: Box<dyn EquationSides<Node>>) -> Self {
+        Self { sides }
+    }
+
+    /// Number of symbol occurrences in one of `a` and `b` but not the other.
+    pub fn distance(&self, a: &HashNode<Node>, b: &HashNode<Node>) -> u64 {
+        let mut counts: HashMap<u64, i64> = HashMap::new();
+        count_symbols(a, 1, &mut counts);
+        count_symbols(b, -1, &mut counts);
+        counts.values().map(|count| count.unsigned_abs()).sum()
+    }
+}
+
+fn count_symbols<Node: HashNodeInner>(expr: &HashNode<Node>, sign: i64, counts: &mut HashMap<u64, i64>) {
+    match expr.value.decompose() {
+        Some((opcode, children)) => {
+            *counts.entry(opcode).or_insert(0) += sign;
+            for child in &children {
+                count_symbols(child, sign, counts);
+            }
+        }
+        None => *counts.entry(expr.hash()).or_insert(0) += sign,
+    }
+}
+
+impl<Node: HashNodeInner> CostEstimator<Node> for SymbolMultisetDistanceEstimator<Node> {
+    fn estimate_cost(&self, expr: &HashNode<Node>) -> u64 {
+        match self.sides.sides(expr) {
+            Some((lhs, rhs)) => self.distance(&lhs, &rhs),
+            None => expr.size(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, succ, Term, TermReflexivity};
+    use crate::proving::Prover;
+    use crate::BinaryTruth;
+
+    struct TermSides;
+
+    impl EquationSides<Term> for TermSides {
+        fn sides(&self, expr: &HashNode<Term>) -> Option<(HashNode<Term>, HashNode<Term>)> {
+            match expr.value.as_ref() {
+                Term::Eq(l, r) => Some((l.clone(), r.clone())),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_tree_edit_distance() {
+        let store = NodeStorage::new();
+        let estimator = TreeEditDistanceEstimator::new(Box::new(TermSides));
+        let two = numeral(2, &store);
+
+        assert_eq!(estimator.estimate_cost(&eq(two.clone(), two.clone(), &store)), 0);
+        // S(S(S(0))) vs S(S(0)): one successor to peel off.
+        assert_eq!(estimator.distance(&numeral(3, &store), &two), 1);
+        // S(1 + 1) vs S(S(1)): add and succ differ in arity, so the subtree is replaced.
+        let sum = succ(add(numeral(1, &store), numeral(1, &store), &store), &store);
+        assert!(estimator.distance(&sum, &numeral(3, &store)) >= 1);
+        assert_eq!(estimator.with_bound(1).distance(&numeral(5, &store), &add(two.clone(), two, &store)), 1);
+    }
+
+    #[test]
+    fn test_symbol_multiset_distance() {
+        let store = NodeStorage::new();
+        let estimator = SymbolMultisetDistanceEstimator::new(Box::new(TermSides));
+
+        // 1 + 1 vs 2: same successors, but the sum has an extra add and an extra zero.
+        let sum = add(numeral(1, &store), numeral(1, &store), &store);
+        assert_eq!(estimator.distance(&sum, &numeral(2, &store)), 2);
+        assert_eq!(estimator.distance(&numeral(3, &store), &numeral(3, &store)), 0);
+    }
+
+    #[test]
+    fn test_estimators_drive_search() {
+        let store = NodeStorage::new();
+        let goal = eq(add(numeral(3, &store), numeral(2, &store), &store), numeral(5, &store), &store);
+
+        let mut prover: Prover<_, _, BinaryTruth, _> =
+            Prover::new(1000, TreeEditDistanceEstimator::new(Box::new(TermSides)), TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        assert!(prover.prove(&goal).is_ok());
+
+        let mut prover: Prover<_, _, BinaryTruth, _> =
+            Prover::new(1000, SymbolMultisetDistanceEstimator::new(Box::new(TermSides)), TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        assert!(prover.prove(&goal).is_ok());
+    }
+}
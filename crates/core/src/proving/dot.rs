@@ -0,0 +1,183 @@
+//! Graphviz export of the explored search space.
+//!
+//! `SearchGraph` is a `SearchObserver` that records every expression the
+//! search reaches and every rule application between them. `to_dot` writes
+//! the result in DOT: one node per distinct expression, labeled with its
+//! display text, and one edge per rule application, labeled with the rule
+//! name. Expanded states are drawn solid, states left on the frontier
+//! dashed, and states the goal checker accepted with a double border.
+//! `restrict_to_proof` keeps only the path a proof took.
+
+use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::base::printing::{DisplayPrinter, ExprPrinter};
+use crate::proving::observer::{SearchControl, SearchObserver};
+use crate::proving::{ProofState, ProofStep};
+use crate::rewriting::RewriteDirection;
+use crate::truth::TruthValue;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+/// The states and rule applications seen during a search.
+pub struct SearchGraph<Node: HashNodeInner> {
+    /// Distinct expressions, in the order they were first seen.
+    nodes: Vec<HashNode<Node>>,
+    /// Position in `nodes` of each expression, keyed by hash.
+    ids: HashMap<u64, usize>,
+    /// Rule applications as (from, to, rule name, direction).
+    edges: Vec<(usize, usize, String, RewriteDirection)>,
+    expanded: HashSet<usize>,
+    accepted: HashSet<usize>,
+}
+
+impl<Node: HashNodeInner> Default for SearchGraph<Node> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Node: HashNodeInner> SearchGraph<Node> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+            edges: Vec::new(),
+            expanded: HashSet::new(),
+            accepted: HashSet::new(),
+        }
+    }
+
+    /// Number of distinct expressions seen.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Number of rule applications seen.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    fn id(&mut self, expr: &HashNode<Node>) -> usize {
+        let next = self.nodes.len();
+        let id = *self.ids.entry(expr.hash()).or_insert(next);
+        if id == next {
+            self.nodes.push(expr.clone());
+        }
+        id
+    }
+
+    fn add_step(&mut self, step: &ProofStep<Node>) {
+        let from = self.id(&step.old_expr);
+        let to = self.id(&step.new_expr);
+        self.edges.push((from, to, step.rule_name.clone(), step.direction));
+    }
+
+    /// The subgraph made of the states and steps of `steps`, a proof found
+    /// by the search this graph observed.
+    pub fn restrict_to_proof(&self, steps: &[ProofStep<Node>]) -> Self {
+        let mut path = Self::new();
+        for step in steps {
+            path.add_step(step);
+        }
+        for (id, expr) in path.nodes.iter().enumerate() {
+            if let Some(original) = self.ids.get(&expr.hash()) {
+                if self.expanded.contains(original) {
+                    path.expanded.insert(id);
+                }
+                if self.accepted.contains(original) {
+                    path.accepted.insert(id);
+                }
+            }
+        }
+        path
+    }
+}
+
+impl<Node: HashNodeInner + Display> SearchGraph<Node> {
+    /// The graph in Graphviz DOT syntax.
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DisplayPrinter)
+    }
+}
+
+impl<Node: HashNodeInner> SearchGraph<Node> {
+    /// `to_dot` with node labels rendered by `printer`.
+    pub fn to_dot_with(&self, printer: &dyn ExprPrinter<Node>) -> String {
+        let mut lines = vec!["digraph search {".to_string(), "    node [shape=box];".to_string()];
+        for (id, expr) in self.nodes.iter().enumerate() {
+            let mut attributes = vec![format!("label=\"{}\"", escape(&printer.print(&expr.value)))];
+            if !self.expanded.contains(&id) && !self.accepted.contains(&id) {
+                attributes.push("style=dashed".to_string());
+            }
+            if self.accepted.contains(&id) {
+                attributes.push("peripheries=2".to_string());
+            }
+            lines.push(format!("    n{} [{}];", id, attributes.join(", ")));
+        }
+        for (from, to, rule, direction) in &self.edges {
+            let label = match direction {
+                RewriteDirection::Backward => format!("{} (backward)", rule),
+                _ => rule.clone(),
+            };
+            lines.push(format!("    n{} -> n{} [label=\"{}\"];", from, to, escape(&label)));
+        }
+        lines.push("}".to_string());
+        lines.join("\n") + "\n"
+    }
+}
+
+impl<Node: HashNodeInner, T: TruthValue> SearchObserver<Node, T> for SearchGraph<Node> {
+    fn on_state_expanded(&mut self, state: &ProofState<Node>, _nodes_explored: usize) -> SearchControl {
+        let id = self.id(&state.expr);
+        self.expanded.insert(id);
+        SearchControl::Continue
+    }
+
+    fn on_goal_checked(&mut self, expr: &HashNode<Node>, result: Option<&T>) {
+        if result.is_some_and(|truth| truth.is_true()) {
+            let id = self.id(expr);
+            self.accepted.insert(id);
+        }
+    }
+
+    fn on_rule_applied(&mut self, step: &ProofStep<Node>) {
+        self.add_step(step);
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::{Prover, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    #[test]
+    fn test_search_graph_dot() {
+        let store = NodeStorage::new();
+        let mut prover: Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> =
+            Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        let goal = eq(add(numeral(2, &store), numeral(1, &store), &store), numeral(3, &store), &store);
+        let mut graph = SearchGraph::new();
+        let result = prover.prove_with_observer(&goal, &mut graph).expect("2 + 1 = 3 should be provable");
+
+        assert!(graph.edge_count() >= result.steps.len());
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph search {"));
+        assert!(dot.contains("[label=\"(S(S(0)) + S(0)) = S(S(S(0)))\"];"));
+
+        let path = graph.restrict_to_proof(&result.steps);
+        assert_eq!(path.node_count(), result.steps.len() + 1);
+        assert_eq!(path.edge_count(), result.steps.len());
+        let dot = path.to_dot();
+        assert!(dot.contains("n0 -> n1 [label=\"add_succ\"];"));
+        assert!(dot.contains("peripheries=2"));
+    }
+}
@@ -0,0 +1,359 @@
+//! Certificates written to files and read back.
+//!
+//! A checker in another process, such as `corpus-verify`, needs the
+//! expressions of a certificate and not their display text, so these formats
+//! write them through the domain's `AtomCodec`. The JSON form has the fields
+//! of the certificates in `json`, under schema `corpus-certificate/1`, with
+//! every expression an S-expression. The binary form is
+//!
+//! ```text
+//! magic "CPCF", version byte
+//! node table length, node table (see `binary`) whose roots are the initial
+//!     and final expressions, each step's old and new expressions and the
+//!     terms of its bindings, then each instantiation's variable and instance
+//! step count, steps: rule name length and bytes, direction (0 forward,
+//!     1 backward), position length and indices, bound variables count and
+//!     indices
+//! instantiation count
+//! ```
+//!
+//! with every count and index an unsigned LEB128 varint.
+
+use crate::base::binary::{read_binary, write_binary, write_varint, BinaryError, BinaryReader};
+use crate::base::codec::AtomCodec;
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use crate::base::sexpr::{from_sexpr, to_sexpr, SExprError};
+use crate::proving::json::{Json, JsonError};
+use crate::proving::{Generalization, ProofCertificate, ProofStep};
+use crate::rewriting::{RewriteDirection, Substitution};
+use std::fmt::{Display, Formatter};
+
+/// Schema of the JSON form.
+pub const CERTIFICATE_SCHEMA: &str = "corpus-certificate/1";
+
+const MAGIC: &[u8; 4] = b"CPCF";
+const VERSION: u8 = 1;
+
+/// Errors reading a certificate.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CertificateError {
+    /// The document names no schema, or one this version cannot read.
+    Schema(String),
+    /// A field is missing or holds the wrong kind of value.
+    Field(String),
+    Json(JsonError),
+    SExpr(SExprError),
+    Binary(BinaryError),
+}
+
+impl Display for CertificateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CertificateError::Schema(schema) => write!(f, "Unknown certificate schema '{}'", schema),
+            CertificateError::Field(field) => write!(f, "Missing or invalid field '{}'", field),
+            CertificateError::Json(error) => write!(f, "Invalid JSON at {}", error),
+            CertificateError::SExpr(error) => write!(f, "Invalid expression: {}", error),
+            CertificateError::Binary(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for CertificateError {}
+
+impl<T: HashNodeInner> ProofCertificate<T> {
+    /// The certificate in the JSON form, which `from_json` reads back.
+    pub fn to_json_with(&self, codec: &dyn AtomCodec<T>) -> Result<Json, SExprError> {
+        let expression = |expr: &HashNode<T>| to_sexpr(expr, codec).map(Json::String);
+        let steps = self
+            .steps
+            .iter()
+            .map(|step| {
+                let mut bindings: Vec<_> = step.substitution.iter().collect();
+                bindings.sort_by_key(|(variable, _)| **variable);
+                let substitution = bindings
+                    .into_iter()
+                    .map(|(variable, term)| Ok((variable.to_string(), expression(term)?)))
+                    .collect::<Result<_, SExprError>>()?;
+                Ok(Json::object([
+                    ("rule", Json::string(step.rule_name.as_str())),
+                    ("direction", Json::string(if step.direction == RewriteDirection::Backward { "backward" } else { "forward" })),
+                    ("position", Json::Array(step.position.iter().map(|&index| Json::Number(index as u64)).collect())),
+                    ("substitution", Json::Object(substitution)),
+                    ("from", expression(&step.old_expr)?),
+                    ("to", expression(&step.new_expr)?),
+                ]))
+            })
+            .collect::<Result<_, SExprError>>()?;
+        let instantiation = self
+            .instantiation
+            .iter()
+            .map(|generalization| {
+                Ok(Json::object([
+                    ("variable", expression(&generalization.variable)?),
+                    ("instance", expression(&generalization.instance)?),
+                ]))
+            })
+            .collect::<Result<_, SExprError>>()?;
+        Ok(Json::object([
+            ("schema", Json::string(CERTIFICATE_SCHEMA)),
+            ("initial", expression(&self.initial_expr)?),
+            ("final", expression(&self.final_expr)?),
+            ("steps", Json::Array(steps)),
+            ("instantiation", Json::Array(instantiation)),
+        ]))
+    }
+
+    /// Read a certificate written by `to_json_with`, interning its
+    /// expressions into `store`.
+    pub fn from_json(json: &Json, codec: &dyn AtomCodec<T>, store: &NodeStorage<T>) -> Result<Self, CertificateError> {
+        match json.get("schema") {
+            Some(Json::String(schema)) if schema == CERTIFICATE_SCHEMA => {}
+            Some(Json::String(schema)) => return Err(CertificateError::Schema(schema.clone())),
+            _ => return Err(CertificateError::Field("schema".to_string())),
+        }
+        let field = |name: &str| CertificateError::Field(name.to_string());
+        let expression = |value: Option<&Json>, name: &str| match value {
+            Some(Json::String(text)) => from_sexpr(text, codec, store).map_err(CertificateError::SExpr),
+            _ => Err(field(name)),
+        };
+        let array = |value: Option<&Json>, name: &str| match value {
+            Some(Json::Array(items)) => Ok(items.clone()),
+            _ => Err(field(name)),
+        };
+
+        let mut steps = Vec::new();
+        for step in array(json.get("steps"), "steps")? {
+            let direction = match step.get("direction") {
+                Some(Json::String(direction)) if direction == "forward" => RewriteDirection::Forward,
+                Some(Json::String(direction)) if direction == "backward" => RewriteDirection::Backward,
+                _ => return Err(field("direction")),
+            };
+            let position = array(step.get("position"), "position")?
+                .iter()
+                .map(|index| match index {
+                    Json::Number(index) => usize::try_from(*index).map_err(|_| field("position")),
+                    _ => Err(field("position")),
+                })
+                .collect::<Result<_, _>>()?;
+            let mut substitution = Substitution::new();
+            let Some(Json::Object(bindings)) = step.get("substitution") else {
+                return Err(field("substitution"));
+            };
+            for (variable, term) in bindings {
+                let variable = variable.parse().map_err(|_| field("substitution"))?;
+                substitution.bind(variable, expression(Some(term), "substitution")?);
+            }
+            let Some(Json::String(rule_name)) = step.get("rule") else {
+                return Err(field("rule"));
+            };
+            steps.push(ProofStep {
+                rule_name: rule_name.clone(),
+                direction,
+                position,
+                substitution,
+                old_expr: expression(step.get("from"), "from")?,
+                new_expr: expression(step.get("to"), "to")?,
+            });
+        }
+
+        let instantiation = array(json.get("instantiation"), "instantiation")?
+            .iter()
+            .map(|entry| {
+                Ok(Generalization {
+                    variable: expression(entry.get("variable"), "variable")?,
+                    instance: expression(entry.get("instance"), "instance")?,
+                })
+            })
+            .collect::<Result<_, CertificateError>>()?;
+        Ok(Self {
+            initial_expr: expression(json.get("initial"), "initial")?,
+            steps,
+            final_expr: expression(json.get("final"), "final")?,
+            instantiation,
+        })
+    }
+
+    /// The certificate in the binary form, which `from_binary` reads back.
+    pub fn to_binary(&self, codec: &dyn AtomCodec<T>) -> Result<Vec<u8>, BinaryError> {
+        let mut roots = vec![self.initial_expr.clone(), self.final_expr.clone()];
+        let mut steps = Vec::new();
+        write_varint(&mut steps, self.steps.len() as u64);
+        for step in &self.steps {
+            roots.push(step.old_expr.clone());
+            roots.push(step.new_expr.clone());
+            write_varint(&mut steps, step.rule_name.len() as u64);
+            steps.extend_from_slice(step.rule_name.as_bytes());
+            steps.push(u8::from(step.direction == RewriteDirection::Backward));
+            write_varint(&mut steps, step.position.len() as u64);
+            for &index in &step.position {
+                write_varint(&mut steps, index as u64);
+            }
+            let mut bindings: Vec<_> = step.substitution.iter().collect();
+            bindings.sort_by_key(|(variable, _)| **variable);
+            write_varint(&mut steps, bindings.len() as u64);
+            for (variable, term) in bindings {
+                write_varint(&mut steps, u64::from(*variable));
+                roots.push(term.clone());
+            }
+        }
+        write_varint(&mut steps, self.instantiation.len() as u64);
+        for generalization in &self.instantiation {
+            roots.push(generalization.variable.clone());
+            roots.push(generalization.instance.clone());
+        }
+
+        let table = write_binary(&roots, codec)?;
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        write_varint(&mut bytes, table.len() as u64);
+        bytes.extend_from_slice(&table);
+        bytes.extend_from_slice(&steps);
+        Ok(bytes)
+    }
+
+    /// Read a certificate written by `to_binary`, interning its expressions
+    /// into `store`.
+    pub fn from_binary(bytes: &[u8], codec: &dyn AtomCodec<T>, store: &NodeStorage<T>) -> Result<Self, CertificateError> {
+        Self::read_binary(bytes, codec, store).map_err(CertificateError::Binary)
+    }
+
+    /// Read either form, telling them apart by the binary magic.
+    pub fn read(bytes: &[u8], codec: &dyn AtomCodec<T>, store: &NodeStorage<T>) -> Result<Self, CertificateError> {
+        if bytes.starts_with(MAGIC) {
+            return Self::from_binary(bytes, codec, store);
+        }
+        let text = std::str::from_utf8(bytes).map_err(|error| {
+            CertificateError::Json(JsonError { offset: error.valid_up_to(), message: "not UTF-8".to_string() })
+        })?;
+        Self::from_json(&Json::parse(text).map_err(CertificateError::Json)?, codec, store)
+    }
+
+    fn read_binary(bytes: &[u8], codec: &dyn AtomCodec<T>, store: &NodeStorage<T>) -> Result<Self, BinaryError> {
+        let mut reader = BinaryReader { bytes, offset: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(BinaryError::BadMagic);
+        }
+        let version = reader.take(1)?[0];
+        if version != VERSION {
+            return Err(BinaryError::UnsupportedVersion(version));
+        }
+        let length = reader.count()?;
+        let mut roots = read_binary(reader.take(length)?, codec, store)?.into_iter();
+        let table_end = reader.offset;
+        let mut root = || roots.next().ok_or(BinaryError::Malformed { offset: table_end, message: "too few expressions".to_string() });
+
+        let initial_expr = root()?;
+        let final_expr = root()?;
+        let mut steps = Vec::new();
+        for _ in 0..reader.count()? {
+            let length = reader.count()?;
+            let start = reader.offset;
+            let rule_name = std::str::from_utf8(reader.take(length)?).map_err(|_| reader.malformed(start, "rule name is not UTF-8"))?.to_string();
+            let start = reader.offset;
+            let direction = match reader.take(1)?[0] {
+                0 => RewriteDirection::Forward,
+                1 => RewriteDirection::Backward,
+                _ => return Err(reader.malformed(start, "unknown direction")),
+            };
+            let position = (0..reader.count()?).map(|_| reader.count()).collect::<Result<_, _>>()?;
+            let (old_expr, new_expr) = (root()?, root()?);
+            let mut substitution = Substitution::new();
+            for _ in 0..reader.count()? {
+                let start = reader.offset;
+                let variable = u32::try_from(reader.varint()?).map_err(|_| reader.malformed(start, "variable index is too large"))?;
+                substitution.bind(variable, root()?);
+            }
+            steps.push(ProofStep { rule_name, direction, position, substitution, old_expr, new_expr });
+        }
+        let instantiation = (0..reader.count()?)
+            .map(|_| Ok(Generalization { variable: root()?, instance: root()? }))
+            .collect::<Result<_, BinaryError>>()?;
+
+        if roots.next().is_some() {
+            return Err(reader.malformed(table_end, "unused expressions"));
+        }
+        if reader.offset != bytes.len() {
+            return Err(reader.malformed(reader.offset, "trailing bytes"));
+        }
+        Ok(Self { initial_expr, steps, final_expr, instantiation })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::{verify_proof, Prover, SizeCostEstimator};
+
+    struct TermCodec;
+
+    impl AtomCodec<Term> for TermCodec {
+        fn encode_atom(&self, atom: &Term) -> Option<String> {
+            matches!(atom, Term::Zero).then(|| "0".to_string())
+        }
+
+        fn decode_atom(&self, text: &str, store: &NodeStorage<Term>) -> Option<HashNode<Term>> {
+            (text == "0").then(|| HashNode::from_store(Term::Zero, store))
+        }
+
+        fn opcode_name(&self, opcode: u64) -> Option<String> {
+            ["succ", "add", "eq"].into_iter().find(|name| crate::base::nodes::Hashing::opcode(name) == opcode).map(String::from)
+        }
+    }
+
+    fn certificate(store: &NodeStorage<Term>) -> ProofCertificate<Term> {
+        let mut prover = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        let goal = eq(add(numeral(1, store), numeral(1, store), store), numeral(2, store), store);
+        prover.prove(&goal).unwrap().certificate()
+    }
+
+    fn same(left: &ProofCertificate<Term>, right: &ProofCertificate<Term>) -> bool {
+        let bindings = |step: &ProofStep<Term>| {
+            let mut bindings: Vec<_> = step.substitution.iter().map(|(variable, term)| (*variable, term.clone())).collect();
+            bindings.sort_by_key(|(variable, _)| *variable);
+            bindings
+        };
+        left.initial_expr == right.initial_expr
+            && left.final_expr == right.final_expr
+            && left.steps.len() == right.steps.len()
+            && left.steps.iter().zip(&right.steps).all(|(a, b)| {
+                (&a.rule_name, a.direction, &a.position, &a.old_expr, &a.new_expr, bindings(a))
+                    == (&b.rule_name, b.direction, &b.position, &b.old_expr, &b.new_expr, bindings(b))
+            })
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let store = NodeStorage::new();
+        let original = certificate(&store);
+
+        let json = original.to_json_with(&TermCodec).unwrap().to_string();
+        assert!(json.starts_with(r#"{"schema":"corpus-certificate/1","initial":"(eq (add (succ 0) (succ 0)) (succ (succ 0)))""#));
+        let elsewhere = NodeStorage::new();
+        let read = ProofCertificate::read(json.as_bytes(), &TermCodec, &elsewhere).unwrap();
+        assert!(same(&read, &original));
+        assert_eq!(verify_proof(&read, &addition_rules(), &elsewhere), Ok(()));
+
+        let bytes = original.to_binary(&TermCodec).unwrap();
+        let read = ProofCertificate::read(&bytes, &TermCodec, &NodeStorage::new()).unwrap();
+        assert!(same(&read, &original));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let store = NodeStorage::new();
+        let read = |bytes: &[u8]| ProofCertificate::<Term>::read(bytes, &TermCodec, &store).err();
+        let json = certificate(&store).to_json_with(&TermCodec).unwrap().to_string();
+        assert_eq!(read(json.replace("certificate/1", "proof/1").as_bytes()), Some(CertificateError::Schema("corpus-proof/1".to_string())));
+        assert_eq!(read(json.replace("\"forward\"", "\"sideways\"").as_bytes()), Some(CertificateError::Field("direction".to_string())));
+        assert!(matches!(read(json.replace("(succ 0)", "(succ 1)").as_bytes()), Some(CertificateError::SExpr(_))));
+        assert!(matches!(read(b"{\"schema\""), Some(CertificateError::Json(_))));
+
+        let bytes = certificate(&store).to_binary(&TermCodec).unwrap();
+        assert_eq!(read(&bytes[..bytes.len() - 1]), Some(CertificateError::Binary(BinaryError::Truncated)));
+        assert!(matches!(read(&[bytes.as_slice(), &[0]].concat()), Some(CertificateError::Binary(BinaryError::Malformed { .. }))));
+    }
+}
@@ -0,0 +1,392 @@
+//! Caching of failed searches across `prove` calls.
+//!
+//! Batches and tactic scripts often pose the same hopeless subgoal many
+//! times. A `FailureCache` remembers the largest node budget each statement
+//! has already failed within, so a later search with no more nodes than that
+//! fails immediately instead of repeating the work, while a search with a
+//! larger budget still runs. Failures are kept per depth limit, since a
+//! statement out of reach at one depth may be proven at another, and per
+//! number of lemmas the search could use, since a new lemma may open a path.
+//! Meet-in-the-middle searches are kept apart from whole-statement ones.
+
+use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::proving::lemmas::EquationSides;
+use crate::proving::{CostEstimator, FailureReason, GoalChecker, ProofFailure, Prover, SearchStats};
+use crate::truth::TruthValue;
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+
+/// What a failure is recorded against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Entry {
+    statement: (u64, u64),
+    max_depth: Option<usize>,
+    /// Number of lemmas from the prover's `LemmaCache` the search used as
+    /// rules. Lemmas are only ever added, so until the lemma cache is
+    /// cleared the count tells its states apart.
+    lemmas: usize,
+    /// Whether the failure is of `prove_bidirectional` on the statement's
+    /// two sides, which explores different states than `prove`.
+    meeting: bool,
+}
+
+/// Bounded cache of statements that could not be proven, with the depth
+/// limit, lemma count and node budget they failed within.
+///
+/// Keys are normalized like those of `LemmaCache`: with an `EquationSides`
+/// implementation, `a = b` and `b = a` share an entry. When full, the oldest
+/// entry is evicted.
+pub struct FailureCache<Node: HashNodeInner> {
+    failures: HashMap<Entry, usize>,
+    order: VecDeque<Entry>,
+    capacity: usize,
+    sides: Option<Box<dyn EquationSides<Node>>>,
+}
+
+impl<Node: HashNodeInner> FailureCache<Node> {
+    /// Create a cache holding at most `capacity` statements, keyed by
+    /// whole-statement hashes.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            failures: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+            sides: None,
+        }
+    }
+
+    /// Create a cache whose keys ignore the orientation of equations.
+    pub fn with_sides(capacity: usize, sides: Box<dyn EquationSides<Node>>) -> Self {
+        Self {
+            sides: Some(sides),
+            ..Self::new(capacity)
+        }
+    }
+
+    /// Normalized key for `expr`.
+    pub fn key(&self, expr: &HashNode<Node>) -> (u64, u64) {
+        match self.sides.as_ref().and_then(|sides| sides.sides(expr)) {
+            Some((lhs, rhs)) => {
+                let (a, b) = (lhs.hash(), rhs.hash());
+                (a.min(b), a.max(b))
+            }
+            None => (expr.hash(), expr.hash()),
+        }
+    }
+
+    /// The largest budget `expr` is known to have failed within under the
+    /// depth limit `max_depth`, without lemmas.
+    pub fn failed_budget(&self, expr: &HashNode<Node>, max_depth: Option<usize>) -> Option<usize> {
+        self.failed_budget_with_lemmas(expr, max_depth, 0)
+    }
+
+    /// The largest budget `expr` is known to have failed within under the
+    /// depth limit `max_depth`, with `lemmas` lemmas available as rules.
+    pub fn failed_budget_with_lemmas(&self, expr: &HashNode<Node>, max_depth: Option<usize>, lemmas: usize) -> Option<usize> {
+        self.failures.get(&self.entry(expr, max_depth, lemmas)).copied()
+    }
+
+    /// Whether searching `expr` with `budget` nodes under the depth limit
+    /// `max_depth`, without lemmas, is known to fail.
+    pub fn known_to_fail(&self, expr: &HashNode<Node>, max_depth: Option<usize>, budget: usize) -> bool {
+        self.known_to_fail_with_lemmas(expr, max_depth, 0, budget)
+    }
+
+    /// Whether searching `expr` with `budget` nodes under the depth limit
+    /// `max_depth`, with `lemmas` lemmas available as rules, is known to fail.
+    pub fn known_to_fail_with_lemmas(
+        &self,
+        expr: &HashNode<Node>,
+        max_depth: Option<usize>,
+        lemmas: usize,
+        budget: usize,
+    ) -> bool {
+        self.failed_budget_with_lemmas(expr, max_depth, lemmas).is_some_and(|failed| failed >= budget)
+    }
+
+    /// Record that `expr` could not be proven within `budget` nodes under the
+    /// depth limit `max_depth`, without lemmas.
+    ///
+    /// Use `usize::MAX` for searches that exhausted the states within the
+    /// limit.
+    pub fn record(&mut self, expr: &HashNode<Node>, max_depth: Option<usize>, budget: usize) {
+        self.record_with_lemmas(expr, max_depth, 0, budget);
+    }
+
+    /// Record that `expr` could not be proven within `budget` nodes under the
+    /// depth limit `max_depth`, with `lemmas` lemmas available as rules.
+    pub fn record_with_lemmas(&mut self, expr: &HashNode<Node>, max_depth: Option<usize>, lemmas: usize, budget: usize) {
+        self.insert(self.entry(expr, max_depth, lemmas), budget);
+    }
+
+    /// Whether rewriting `lhs` and `rhs` towards a common form with `budget`
+    /// nodes under the depth limit `max_depth` is known to fail.
+    pub fn known_meeting_to_fail(
+        &self,
+        lhs: &HashNode<Node>,
+        rhs: &HashNode<Node>,
+        max_depth: Option<usize>,
+        budget: usize,
+    ) -> bool {
+        self.failures
+            .get(&Self::meeting_entry(lhs, rhs, max_depth))
+            .is_some_and(|&failed| failed >= budget)
+    }
+
+    /// Record that `lhs` and `rhs` could not be rewritten to a common form
+    /// within `budget` nodes under the depth limit `max_depth`.
+    pub fn record_meeting(&mut self, lhs: &HashNode<Node>, rhs: &HashNode<Node>, max_depth: Option<usize>, budget: usize) {
+        self.insert(Self::meeting_entry(lhs, rhs, max_depth), budget);
+    }
+
+    fn entry(&self, expr: &HashNode<Node>, max_depth: Option<usize>, lemmas: usize) -> Entry {
+        Entry {
+            statement: self.key(expr),
+            max_depth,
+            lemmas,
+            meeting: false,
+        }
+    }
+
+    /// The sides are kept in order: which side is expanded first depends on
+    /// it, so a budget failing for `a = b` may succeed for `b = a`.
+    fn meeting_entry(lhs: &HashNode<Node>, rhs: &HashNode<Node>, max_depth: Option<usize>) -> Entry {
+        Entry {
+            statement: (lhs.hash(), rhs.hash()),
+            max_depth,
+            lemmas: 0,
+            meeting: true,
+        }
+    }
+
+    fn insert(&mut self, entry: Entry, budget: usize) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if let Some(failed) = self.failures.get_mut(&entry) {
+            *failed = (*failed).max(budget);
+            return;
+        }
+
+        if self.order.len() == self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.failures.remove(&oldest);
+        }
+        self.failures.insert(entry, budget);
+        self.order.push_back(entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.failures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.failures.clear();
+        self.order.clear();
+    }
+}
+
+impl<Node: HashNodeInner + Clone, C: CostEstimator<Node>, T: TruthValue, G: GoalChecker<Node, T>>
+    Prover<Node, C, T, G>
+{
+    /// Attach a failure cache that persists across `prove` calls and tactic searches.
+    ///
+    /// Entries assume the rules stay fixed; clear the cache after adding
+    /// rules. With a lemma cache attached, failures are kept per number of
+    /// cached lemmas, so a lemma learned since a failure lets the statement
+    /// be searched again; clear both caches together.
+    pub fn set_failure_cache(&mut self, cache: FailureCache<Node>) {
+        self.failures = Some(RwLock::new(cache));
+    }
+
+    /// Detach and return the failure cache, if any.
+    pub fn take_failure_cache(&mut self) -> Option<FailureCache<Node>> {
+        self.failures.take().map(|cache| cache.into_inner().unwrap())
+    }
+
+    /// A failure for `expr` if the cache shows it cannot be proven within
+    /// `budget` nodes using `lemmas` lemmas.
+    pub(crate) fn cached_failure(&self, expr: &HashNode<Node>, budget: usize, lemmas: usize) -> Option<ProofFailure<Node>> {
+        let failures = self.failures.as_ref()?;
+        failures
+            .read()
+            .unwrap()
+            .known_to_fail_with_lemmas(expr, self.max_depth, lemmas, budget)
+            .then(|| ProofFailure {
+                reason: FailureReason::Cached,
+                nodes_explored: 0,
+                stats: Box::new(SearchStats::new()),
+                closest: None,
+            })
+    }
+
+    /// Record a search for `expr` with `budget` nodes and `lemmas` lemmas
+    /// that ended for `reason`.
+    pub(crate) fn record_failure(&self, expr: &HashNode<Node>, budget: usize, lemmas: usize, reason: FailureReason) {
+        if let (Some(failures), Some(budget)) = (&self.failures, Self::failed_budget(budget, reason)) {
+            failures.write().unwrap().record_with_lemmas(expr, self.max_depth, lemmas, budget);
+        }
+    }
+
+    /// A failure for `prove_bidirectional` on `lhs` and `rhs` if the cache
+    /// shows the sides cannot meet within `budget` nodes.
+    pub(crate) fn cached_meeting_failure(
+        &self,
+        lhs: &HashNode<Node>,
+        rhs: &HashNode<Node>,
+        budget: usize,
+    ) -> Option<ProofFailure<Node>> {
+        let failures = self.failures.as_ref()?;
+        failures
+            .read()
+            .unwrap()
+            .known_meeting_to_fail(lhs, rhs, self.max_depth, budget)
+            .then(|| ProofFailure {
+                reason: FailureReason::Cached,
+                nodes_explored: 0,
+                stats: Box::new(SearchStats::new()),
+                closest: None,
+            })
+    }
+
+    /// Record a `prove_bidirectional` search with `budget` nodes that ended
+    /// for `reason`.
+    pub(crate) fn record_meeting_failure(&self, lhs: &HashNode<Node>, rhs: &HashNode<Node>, budget: usize, reason: FailureReason) {
+        if let (Some(failures), Some(budget)) = (&self.failures, Self::failed_budget(budget, reason)) {
+            failures.write().unwrap().record_meeting(lhs, rhs, self.max_depth, budget);
+        }
+    }
+
+    /// The budget a search ending for `reason` is known to fail within, if
+    /// the failure is worth caching at all.
+    fn failed_budget(budget: usize, reason: FailureReason) -> Option<usize> {
+        match reason {
+            FailureReason::Exhausted => Some(usize::MAX),
+            FailureReason::NodeLimitReached => Some(budget),
+            FailureReason::Aborted | FailureReason::TimedOut | FailureReason::Cached => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::{tactics, ProofGoal, SizeCostEstimator, TacticError};
+    use crate::BinaryTruth;
+
+    struct TermSides;
+
+    impl EquationSides<Term> for TermSides {
+        fn sides(&self, expr: &HashNode<Term>) -> Option<(HashNode<Term>, HashNode<Term>)> {
+            match expr.value.as_ref() {
+                Term::Eq(l, r) => Some((l.clone(), r.clone())),
+                _ => None,
+            }
+        }
+    }
+
+    fn prover(max_nodes: usize) -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(max_nodes, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover
+    }
+
+    #[test]
+    fn test_cache_is_budget_aware_and_bounded() {
+        let store = NodeStorage::new();
+        let mut cache = FailureCache::with_sides(2, Box::new(TermSides));
+        let goal = eq(numeral(1, &store), numeral(2, &store), &store);
+        let flipped = eq(numeral(2, &store), numeral(1, &store), &store);
+
+        cache.record(&goal, None, 1_000);
+        assert!(cache.known_to_fail(&flipped, None, 1_000));
+        assert!(!cache.known_to_fail(&goal, None, 100_000));
+        assert!(!cache.known_to_fail(&goal, Some(5), 1_000));
+
+        cache.record(&goal, None, 100);
+        assert_eq!(cache.failed_budget(&goal, None), Some(1_000));
+
+        cache.record(&numeral(3, &store), None, 10);
+        cache.record(&numeral(4, &store), None, 10);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.failed_budget(&goal, None), None);
+    }
+
+    #[test]
+    fn test_prover_skips_known_failures() {
+        let store = NodeStorage::new();
+        // 2 + 2 = 5 is false, and its search space is small enough to exhaust.
+        let hopeless = eq(add(numeral(2, &store), numeral(2, &store), &store), numeral(5, &store), &store);
+
+        let mut prover = prover(1000);
+        prover.set_failure_cache(FailureCache::new(16));
+
+        let Err(first) = prover.prove(&hopeless) else {
+            panic!("2 + 2 = 5 is false");
+        };
+        assert_eq!(first.reason, FailureReason::Exhausted);
+        let results = prover.prove_all(&[hopeless.clone(), hopeless.clone()]);
+        assert!(results.iter().all(|result| matches!(result, Err(failure) if failure.reason == FailureReason::Cached)));
+
+        let goal = ProofGoal::new(&hopeless);
+        assert!(matches!(
+            tactics::auto(10).apply(&prover, &goal),
+            Err(TacticError::SearchFailed(FailureReason::Cached))
+        ));
+
+        let cache = prover.take_failure_cache().unwrap();
+        assert!(cache.known_to_fail(&hopeless, None, 1000));
+    }
+
+    #[test]
+    fn test_failures_are_kept_per_lemma_count() {
+        use crate::proving::LemmaCache;
+
+        let store = NodeStorage::new();
+        let hopeless = eq(add(numeral(2, &store), numeral(2, &store), &store), numeral(5, &store), &store);
+        let lemma = eq(add(numeral(1, &store), numeral(1, &store), &store), numeral(2, &store), &store);
+
+        let mut prover = prover(1000);
+        prover.set_lemma_cache(LemmaCache::with_sides(Box::new(TermSides)));
+        prover.set_failure_cache(FailureCache::new(16));
+        assert!(matches!(prover.prove(&hopeless), Err(failure) if failure.reason == FailureReason::Exhausted));
+        assert!(matches!(prover.prove(&hopeless), Err(failure) if failure.reason == FailureReason::Cached));
+
+        // A new lemma may open a path, so the goal is searched again.
+        assert!(prover.prove(&lemma).is_ok());
+        assert!(matches!(prover.prove(&hopeless), Err(failure) if failure.reason == FailureReason::Exhausted));
+        assert!(matches!(prover.prove(&hopeless), Err(failure) if failure.reason == FailureReason::Cached));
+
+        let cache = prover.take_failure_cache().unwrap();
+        assert!(cache.known_to_fail_with_lemmas(&hopeless, None, 1, 1000));
+        assert!(!cache.known_to_fail_with_lemmas(&hopeless, None, 2, 1000));
+    }
+
+    #[test]
+    fn test_depth_limited_failures_do_not_carry_over() {
+        let store = NodeStorage::new();
+        // 2 + 2 = 4 takes three steps.
+        let goal = eq(add(numeral(2, &store), numeral(2, &store), &store), numeral(4, &store), &store);
+
+        let mut prover = prover(1000);
+        prover.set_failure_cache(FailureCache::new(16));
+        prover.set_max_depth(Some(2));
+        assert!(matches!(prover.prove(&goal), Err(failure) if failure.reason == FailureReason::Exhausted));
+        assert!(matches!(prover.prove(&goal), Err(failure) if failure.reason == FailureReason::Cached));
+
+        prover.set_max_depth(Some(3));
+        assert!(prover.prove(&goal).is_ok());
+        prover.set_max_depth(None);
+        assert!(prover.prove(&goal).is_ok());
+    }
+}
@@ -0,0 +1,181 @@
+//! Numeric features of proof states, for learned heuristics.
+//!
+//! A `StateFeatures` implementation turns an expression into a fixed-length
+//! vector of numbers. Vectors can be logged during search (for example from a
+//! `SearchObserver`) to train a model offline; the learned weights are then
+//! plugged back in through `WeightedCostEstimator`. No learning happens here,
+//! so the crate needs no ML dependency.
+
+use crate::base::nodes::{HashNode, HashNodeInner, Hashing};
+use crate::proving::lemmas::EquationSides;
+use crate::proving::{CostEstimator, ProofState};
+use std::collections::HashSet;
+
+/// Extracts a feature vector from an expression.
+pub trait StateFeatures<Node: HashNodeInner> {
+    /// Names of the features, in vector order.
+    fn names(&self) -> Vec<String>;
+
+    /// Feature vector of `expr`; always `names().len()` long.
+    fn extract(&self, expr: &HashNode<Node>) -> Vec<f64>;
+
+    /// Feature vector of a search state. Defaults to the features of its expression.
+    fn extract_state(&self, state: &ProofState<Node>) -> Vec<f64> {
+        self.extract(&state.expr)
+    }
+}
+
+/// Structural features: size, depth, distinct subterms, counts of chosen
+/// symbols, and for equations the sizes of the sides and how many subterms
+/// they share.
+pub struct StructuralFeatures<Node: HashNodeInner> {
+    symbols: Vec<(String, u64)>,
+    sides: Option<Box<dyn EquationSides<Node>>>,
+}
+
+impl<Node: HashNodeInner> StructuralFeatures<Node> {
+    /// Features counting occurrences of each named opcode in `symbols`.
+    pub fn new(symbols: &[&str]) -> Self {
+        Self {
+            symbols: symbols
+                .iter()
+                .map(|name| (name.to_string(), Hashing::opcode(name)))
+                .collect(),
+            sides: None,
+        }
+    }
+
+    /// Also extract per-side features of equations.
+    pub fn with_sides(mut self, sides: Box<dyn EquationSides<Node>>) -> Self {
+        self.sides = Some(sides);
+        self
+    }
+}
+
+/// Walk `expr`, recording distinct subterm hashes, opcode occurrences and depth.
+fn walk<Node: HashNodeInner>(expr: &HashNode<Node>, subterms: &mut HashSet<u64>, opcodes: &mut Vec<u64>) -> usize {
+    subterms.insert(expr.hash());
+    let Some((opcode, children)) = expr.value.decompose() else {
+        return 1;
+    };
+    opcodes.push(opcode);
+    1 + children
+        .iter()
+        .map(|child| walk(child, subterms, opcodes))
+        .max()
+        .unwrap_or(0)
+}
+
+impl<Node: HashNodeInner> StateFeatures<Node> for StructuralFeatures<Node> {
+    fn names(&self) -> Vec<String> {
+        let mut names = vec!["size".to_string(), "depth".to_string(), "distinct_subterms".to_string()];
+        names.extend(self.symbols.iter().map(|(name, _)| format!("count:{}", name)));
+        if self.sides.is_some() {
+            names.extend(["lhs_size", "rhs_size", "shared_subterms"].map(String::from));
+        }
+        names
+    }
+
+    fn extract(&self, expr: &HashNode<Node>) -> Vec<f64> {
+        let mut subterms = HashSet::new();
+        let mut opcodes = Vec::new();
+        let depth = walk(expr, &mut subterms, &mut opcodes);
+
+        let mut features = vec![expr.size() as f64, depth as f64, subterms.len() as f64];
+        features.extend(
+            self.symbols
+                .iter()
+                .map(|(_, opcode)| opcodes.iter().filter(|&op| op == opcode).count() as f64),
+        );
+
+        if let Some(sides) = &self.sides {
+            match sides.sides(expr) {
+                Some((lhs, rhs)) => {
+                    let (mut left, mut right) = (HashSet::new(), HashSet::new());
+                    walk(&lhs, &mut left, &mut Vec::new());
+                    walk(&rhs, &mut right, &mut Vec::new());
+                    features.extend([lhs.size() as f64, rhs.size() as f64, left.intersection(&right).count() as f64]);
+                }
+                None => features.extend([0.0; 3]),
+            }
+        }
+
+        features
+    }
+}
+
+/// Cost estimator computing `bias + weights · features(expr)`.
+///
+/// Negative results are clamped to zero and the cost is rounded to the
+/// nearest integer.
+pub struct WeightedCostEstimator<F> {
+    features: F,
+    weights: Vec<f64>,
+    bias: f64,
+}
+
+impl<F> WeightedCostEstimator<F> {
+    /// Combine `features` with one weight per feature.
+    pub fn new(features: F, weights: Vec<f64>, bias: f64) -> Self {
+        Self { features, weights, bias }
+    }
+}
+
+impl<Node: HashNodeInner, F: StateFeatures<Node>> CostEstimator<Node> for WeightedCostEstimator<F> {
+    fn estimate_cost(&self, expr: &HashNode<Node>) -> u64 {
+        let score: f64 = self
+            .features
+            .extract(expr)
+            .iter()
+            .zip(&self.weights)
+            .map(|(feature, weight)| feature * weight)
+            .sum();
+        (self.bias + score).max(0.0).round() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, eq, numeral, Term};
+
+    struct TermSides;
+
+    impl EquationSides<Term> for TermSides {
+        fn sides(&self, expr: &HashNode<Term>) -> Option<(HashNode<Term>, HashNode<Term>)> {
+            match expr.value.as_ref() {
+                Term::Eq(l, r) => Some((l.clone(), r.clone())),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_structural_features() {
+        let store = NodeStorage::new();
+        // 1 + 1 = 2: sides share the subterms 0 and S(0).
+        let expr = eq(add(numeral(1, &store), numeral(1, &store), &store), numeral(2, &store), &store);
+        let features = StructuralFeatures::new(&["succ", "add"]).with_sides(Box::new(TermSides));
+
+        let names = features.names();
+        let values = features.extract(&expr);
+        assert_eq!(names.len(), values.len());
+
+        let value = |name: &str| values[names.iter().position(|n| n == name).unwrap()];
+        assert_eq!(value("depth"), 4.0);
+        assert_eq!(value("count:succ"), 4.0);
+        assert_eq!(value("count:add"), 1.0);
+        assert_eq!(value("shared_subterms"), 2.0);
+    }
+
+    #[test]
+    fn test_weighted_estimator() {
+        let store = NodeStorage::new();
+        let expr = add(numeral(1, &store), numeral(2, &store), &store);
+        let estimator = WeightedCostEstimator::new(StructuralFeatures::<Term>::new(&["add"]), vec![0.0, 0.0, 0.0, 10.0], 1.5);
+
+        assert_eq!(estimator.estimate_cost(&expr), 12);
+        assert_eq!(estimator.estimate_cost(&numeral(2, &store)), 2);
+    }
+}
@@ -0,0 +1,257 @@
+//! Generalization of common subterms.
+//!
+//! When the same compound subterm appears on both sides of an equation, the
+//! search can often ignore what it is: `t + S(0) = S(t)` holds for any `t`,
+//! and proving `x + S(0) = S(x)` for a fresh variable `x` avoids rewriting
+//! inside `t` at all. The generalized statement implies the original by
+//! instantiating `x := t`; certificates record that instantiation.
+
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use crate::proving::certificate::ProofCertificate;
+use crate::proving::lemmas::EquationSides;
+use crate::proving::{CostEstimator, GoalChecker, ProofFailure, ProofResult, Prover};
+use crate::truth::TruthValue;
+use std::collections::HashSet;
+
+/// Builds variable nodes of a domain.
+pub trait VariableFactory<Node: HashNodeInner> {
+    /// The variable with the given index.
+    fn variable(&self, index: u32, store: &NodeStorage<Node>) -> HashNode<Node>;
+}
+
+/// A variable that replaced a subterm, and the subterm it stands for.
+pub struct Generalization<Node: HashNodeInner> {
+    pub variable: HashNode<Node>,
+    pub instance: HashNode<Node>,
+}
+
+impl<Node: HashNodeInner> Clone for Generalization<Node> {
+    fn clone(&self) -> Self {
+        Self {
+            variable: self.variable.clone(),
+            instance: self.instance.clone(),
+        }
+    }
+}
+
+/// A proof of a goal, possibly via a generalization of it.
+pub struct GeneralizedProof<Node: HashNodeInner, T: TruthValue> {
+    /// Proof of the generalized goal, or of the goal itself.
+    pub result: ProofResult<Node, T>,
+    /// Substitutions turning the proven statement back into the goal.
+    pub instantiation: Vec<Generalization<Node>>,
+}
+
+impl<Node: HashNodeInner, T: TruthValue> GeneralizedProof<Node, T> {
+    /// Certificate of the proven statement, carrying the instantiation.
+    pub fn certificate(&self) -> ProofCertificate<Node> {
+        let mut certificate = ProofCertificate::from_result(&self.result);
+        certificate.instantiation = self.instantiation.clone();
+        certificate
+    }
+}
+
+/// Replace every occurrence of `target` in `expr` by `replacement`.
+pub fn replace_subterm<Node: HashNodeInner + Clone>(
+    expr: &HashNode<Node>,
+    target: &HashNode<Node>,
+    replacement: &HashNode<Node>,
+    store: &NodeStorage<Node>,
+) -> HashNode<Node> {
+    if expr == target {
+        return replacement.clone();
+    }
+
+    let Some((opcode, children)) = expr.value.decompose() else {
+        return expr.clone();
+    };
+    let replaced: Vec<_> = children
+        .iter()
+        .map(|child| replace_subterm(child, target, replacement, store))
+        .collect();
+    if replaced.iter().zip(&children).all(|(new, old)| new == old) {
+        return expr.clone();
+    }
+    Node::construct_from_parts(opcode, replaced, store).unwrap_or_else(|| expr.clone())
+}
+
+fn collect_compound<Node: HashNodeInner>(expr: &HashNode<Node>, found: &mut HashSet<u64>) {
+    if let Some((_, children)) = expr.value.decompose() {
+        found.insert(expr.hash());
+        for child in &children {
+            collect_compound(child, found);
+        }
+    }
+}
+
+fn collect_common<Node: HashNodeInner>(expr: &HashNode<Node>, other: &HashSet<u64>, common: &mut Vec<HashNode<Node>>) {
+    let Some((_, children)) = expr.value.decompose() else {
+        return;
+    };
+    if other.contains(&expr.hash()) {
+        if !common.contains(expr) {
+            common.push(expr.clone());
+        }
+        return;
+    }
+    for child in &children {
+        collect_common(child, other, common);
+    }
+}
+
+/// Compound subterms occurring on both sides of `expr`, largest first.
+///
+/// Only maximal occurrences in the right-hand side are reported, so a
+/// subterm of another candidate appears only if it also occurs elsewhere.
+pub fn common_subterms<Node: HashNodeInner>(
+    expr: &HashNode<Node>,
+    sides: &dyn EquationSides<Node>,
+) -> Vec<HashNode<Node>> {
+    let Some((lhs, rhs)) = sides.sides(expr) else {
+        return Vec::new();
+    };
+
+    let mut left = HashSet::new();
+    collect_compound(&lhs, &mut left);
+    let mut common = Vec::new();
+    collect_common(&rhs, &left, &mut common);
+    common.sort_by_key(|subterm| std::cmp::Reverse(subterm.size()));
+    common
+}
+
+/// A variable of `variables` not occurring in `expr`.
+fn fresh_variable<Node: HashNodeInner>(
+    expr: &HashNode<Node>,
+    variables: &dyn VariableFactory<Node>,
+    store: &NodeStorage<Node>,
+) -> HashNode<Node> {
+    fn collect<Node: HashNodeInner>(expr: &HashNode<Node>, found: &mut HashSet<u64>) {
+        found.insert(expr.hash());
+        for child in expr.value.decompose().map(|(_, children)| children).unwrap_or_default() {
+            collect(&child, found);
+        }
+    }
+
+    let mut occurring = HashSet::new();
+    collect(expr, &mut occurring);
+    (0..)
+        .map(|index| variables.variable(index, store))
+        .find(|variable| !occurring.contains(&variable.hash()))
+        .expect("an expression has finitely many subterms")
+}
+
+impl<Node: HashNodeInner + Clone, C: CostEstimator<Node>, T: TruthValue, G: GoalChecker<Node, T>>
+    Prover<Node, C, T, G>
+{
+    /// Prove `expr`, first trying to generalize a subterm common to both of
+    /// its sides into a fresh variable.
+    ///
+    /// Candidates from `common_subterms` are tried one at a time, largest
+    /// first; a generalization can be false even when the goal is true, so
+    /// the goal itself is searched last. The returned failure is that of the
+    /// search for the goal itself.
+    pub fn prove_generalized(
+        &self,
+        expr: &HashNode<Node>,
+        sides: &dyn EquationSides<Node>,
+        variables: &dyn VariableFactory<Node>,
+    ) -> Result<GeneralizedProof<Node, T>, ProofFailure<Node>> {
+        let variable = fresh_variable(expr, variables, &self.store);
+
+        for instance in common_subterms(expr, sides) {
+            let generalized = replace_subterm(expr, &instance, &variable, &self.store);
+            if let Ok(result) = self.prove(&generalized) {
+                return Ok(GeneralizedProof {
+                    result,
+                    instantiation: vec![Generalization { variable, instance }],
+                });
+            }
+        }
+
+        self.prove(expr).map(|result| GeneralizedProof {
+            result,
+            instantiation: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, succ, Term, TermReflexivity};
+    use crate::proving::{verify_proof, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    struct TermSides;
+
+    impl EquationSides<Term> for TermSides {
+        fn sides(&self, expr: &HashNode<Term>) -> Option<(HashNode<Term>, HashNode<Term>)> {
+            match expr.value.as_ref() {
+                Term::Eq(l, r) => Some((l.clone(), r.clone())),
+                _ => None,
+            }
+        }
+    }
+
+    struct TermVariables;
+
+    impl VariableFactory<Term> for TermVariables {
+        fn variable(&self, index: u32, store: &NodeStorage<Term>) -> HashNode<Term> {
+            HashNode::from_store(Term::Var(index), store)
+        }
+    }
+
+    fn prover(max_nodes: usize) -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(max_nodes, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover
+    }
+
+    #[test]
+    fn test_common_subterms() {
+        let store = NodeStorage::new();
+        let t = add(numeral(2, &store), numeral(1, &store), &store);
+        let goal = eq(add(t.clone(), numeral(1, &store), &store), succ(t.clone(), &store), &store);
+
+        let common = common_subterms(&goal, &TermSides);
+        assert_eq!(common[0], t);
+        assert!(common_subterms(&numeral(2, &store), &TermSides).is_empty());
+
+        let x = TermVariables.variable(0, &store);
+        let generalized = replace_subterm(&goal, &t, &x, &store);
+        assert_eq!(generalized, eq(add(x.clone(), numeral(1, &store), &store), succ(x, &store), &store));
+    }
+
+    #[test]
+    fn test_generalized_proof_instantiates_to_goal() {
+        let store = NodeStorage::new();
+        let t = add(add(numeral(3, &store), numeral(3, &store), &store), numeral(3, &store), &store);
+        let goal = eq(add(t.clone(), numeral(1, &store), &store), succ(t.clone(), &store), &store);
+
+        let proof = prover(1000)
+            .prove_generalized(&goal, &TermSides, &TermVariables)
+            .expect("x + S(0) = S(x) needs two steps");
+        assert_eq!(proof.instantiation.len(), 1);
+        assert_eq!(proof.instantiation[0].instance, t);
+        assert!(proof.result.steps.iter().all(|step| step.old_expr.size() <= 7));
+
+        let certificate = proof.certificate();
+        assert_eq!(verify_proof(&certificate, &addition_rules(), &store), Ok(()));
+        assert_eq!(certificate.instantiated_statement(&store), goal);
+    }
+
+    #[test]
+    fn test_falls_back_to_the_goal() {
+        let store = NodeStorage::new();
+        // Generalizing S(0) gives x + x = S(x), which does not hold.
+        let goal = eq(add(numeral(1, &store), numeral(1, &store), &store), numeral(2, &store), &store);
+        assert_eq!(common_subterms(&goal, &TermSides), vec![numeral(1, &store)]);
+
+        let proof = prover(1000)
+            .prove_generalized(&goal, &TermSides, &TermVariables)
+            .expect("the goal holds");
+        assert!(proof.instantiation.is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+//! Standalone HTML pages for proof certificates.
+//!
+//! The page needs nothing but a browser: styles and script are inline. A
+//! slider steps through the expressions of the proof one rewrite at a time,
+//! and below it every step is a collapsible section naming its rule,
+//! direction and position, with the expressions before and after it and the
+//! bindings of the rule's variables. Expressions are rendered by an
+//! `ExprPrinter`, so domains can use their own printers.
+
+use crate::base::nodes::HashNodeInner;
+use crate::base::printing::{DisplayPrinter, ExprPrinter};
+use crate::proving::json::Json;
+use crate::proving::{ProofCertificate, ProofStep};
+use crate::rewriting::RewriteDirection;
+use std::fmt::Display;
+
+const STYLE: &str = "\
+body { font-family: sans-serif; max-width: 60em; margin: 2em auto; padding: 0 1em; color: #222; }
+pre { background: #f4f4f4; padding: 0.5em; overflow-x: auto; }
+#viewer { border: 1px solid #ccc; border-radius: 4px; padding: 1em; margin-bottom: 2em; }
+#viewer input { width: 100%; }
+details { border-left: 3px solid #ccc; margin: 0.5em 0; padding-left: 0.75em; }
+details.current { border-left-color: #36c; }
+summary { cursor: pointer; }
+.rule { font-weight: bold; }
+";
+
+const SCRIPT: &str = "\
+const slider = document.getElementById('position');
+const steps = document.querySelectorAll('#steps details');
+function show(index) {
+  slider.value = index;
+  document.getElementById('counter').textContent = 'Step ' + index + ' of ' + (states.length - 1);
+  document.getElementById('current').textContent = states[index].expr;
+  document.getElementById('by').textContent = states[index].rule;
+  steps.forEach((step, i) => step.classList.toggle('current', i + 1 === index));
+}
+slider.oninput = () => show(Number(slider.value));
+document.getElementById('previous').onclick = () => show(Math.max(0, Number(slider.value) - 1));
+document.getElementById('next').onclick = () => show(Math.min(states.length - 1, Number(slider.value) + 1));
+show(0);
+";
+
+impl<T: HashNodeInner + Display> ProofCertificate<T> {
+    /// The certificate as a standalone HTML page titled `title`, with
+    /// expressions in their display text.
+    pub fn to_html(&self, title: &str) -> String {
+        self.to_html_with(title, &DisplayPrinter)
+    }
+}
+
+impl<T: HashNodeInner> ProofCertificate<T> {
+    /// `to_html` with expressions rendered by `printer`.
+    pub fn to_html_with(&self, title: &str, printer: &dyn ExprPrinter<T>) -> String {
+        let render = |expr: &T| printer.print(expr);
+        let title = escape(title);
+        let mut states = vec![Json::object([
+            ("expr", Json::string(render(&self.initial_expr.value))),
+            ("rule", Json::string("the statement")),
+        ])];
+        for step in &self.steps {
+            states.push(Json::object([
+                ("expr", Json::string(render(&step.new_expr.value))),
+                ("rule", Json::string(format!("by {}", step_label(step)))),
+            ]));
+        }
+
+        let mut html = vec![
+            "<!DOCTYPE html>".to_string(),
+            "<html lang=\"en\">".to_string(),
+            "<head>".to_string(),
+            "<meta charset=\"utf-8\">".to_string(),
+            format!("<title>{}</title>", title),
+            format!("<style>\n{}</style>", STYLE),
+            "</head>".to_string(),
+            "<body>".to_string(),
+            format!("<h1>{}</h1>", title),
+            format!(
+                "<p>Proves <code>{}</code> in {} step{}.</p>",
+                escape(&render(&self.initial_expr.value)),
+                self.len(),
+                if self.len() == 1 { "" } else { "s" }
+            ),
+            "<section id=\"viewer\">".to_string(),
+            format!("<input type=\"range\" id=\"position\" min=\"0\" max=\"{}\" value=\"0\">", self.len()),
+            "<button id=\"previous\">&larr; Previous</button> <button id=\"next\">Next &rarr;</button>".to_string(),
+            "<span id=\"counter\"></span>".to_string(),
+            "<pre id=\"current\"></pre>".to_string(),
+            "<p id=\"by\"></p>".to_string(),
+            "</section>".to_string(),
+            "<section id=\"steps\">".to_string(),
+            "<h2>Steps</h2>".to_string(),
+        ];
+        for (index, step) in self.steps.iter().enumerate() {
+            html.push("<details open>".to_string());
+            html.push(format!("<summary>{}. <span class=\"rule\">{}</span></summary>", index + 1, escape(&step_label(step))));
+            html.push(format!("<pre>{}</pre>", escape(&render(&step.old_expr.value))));
+            html.push("<p>rewrites to</p>".to_string());
+            html.push(format!("<pre>{}</pre>", escape(&render(&step.new_expr.value))));
+            let mut bindings: Vec<_> = step.substitution.iter().collect();
+            bindings.sort_by_key(|(variable, _)| **variable);
+            if !bindings.is_empty() {
+                html.push("<ul>".to_string());
+                for (variable, term) in bindings {
+                    html.push(format!("<li>variable {} &#8614; <code>{}</code></li>", variable, escape(&render(&term.value))));
+                }
+                html.push("</ul>".to_string());
+            }
+            html.push("</details>".to_string());
+        }
+        html.push(format!("<p>Closed at <code>{}</code>.</p>", escape(&render(&self.final_expr.value))));
+        html.push("</section>".to_string());
+        let states = Json::Array(states).to_string().replace("</", "<\\/");
+        html.push(format!("<script>\nconst states = {};\n{}</script>", states, SCRIPT));
+        html.push("</body>".to_string());
+        html.push("</html>".to_string());
+        html.join("\n") + "\n"
+    }
+}
+
+/// The rule of `step`, with its direction and position when they matter.
+fn step_label<T: HashNodeInner>(step: &ProofStep<T>) -> String {
+    let mut label = step.rule_name.clone();
+    if step.direction == RewriteDirection::Backward {
+        label.push_str(" (backward)");
+    }
+    if !step.position.is_empty() {
+        let path: Vec<_> = step.position.iter().map(usize::to_string).collect();
+        label.push_str(&format!(" at {}", path.join(".")));
+    }
+    label
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::{Prover, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    #[test]
+    fn test_certificate_page() {
+        let store = NodeStorage::new();
+        let mut prover: Prover<_, _, BinaryTruth, _> = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        let goal = eq(add(numeral(1, &store), numeral(1, &store), &store), numeral(2, &store), &store);
+        let certificate = prover.prove(&goal).expect("1 + 1 = 2 should be provable").certificate();
+
+        let html = certificate.to_html("1 + 1 < 3 </script>");
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.contains("<title>1 + 1 &lt; 3 &lt;/script&gt;</title>"));
+        assert!(html.contains(&format!("max=\"{}\"", certificate.len())));
+        assert!(html.contains("<summary>1. <span class=\"rule\">add_succ at 0</span></summary>"));
+        assert!(html.contains("<li>variable 0 &#8614; <code>S(0)</code></li>"));
+        assert!(html.contains("const states = [{\"expr\":\"(S(0) + S(0)) = S(S(0))\",\"rule\":\"the statement\"}"));
+        assert_eq!(html.matches("<details open>").count(), certificate.len());
+
+        let bracketed = certificate.to_html_with("proof", &|expr: &Term| format!("[{}]", expr));
+        assert!(bracketed.contains("<pre>[S((S(0) + 0)) = S(S(0))]</pre>"));
+    }
+}
@@ -0,0 +1,125 @@
+//! Proving under local hypotheses.
+//!
+//! To prove `A → B` by implication introduction, assume `A` and prove `B`.
+//! `Prover::prove_with_hypotheses` does the assuming: each hypothesis is
+//! turned into rewrite rules by an `AsRewriteRules` implementation, and those
+//! rules are available only for that one call. Results proven under
+//! hypotheses are never added to the lemma cache.
+
+use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::proving::lemmas::EquationSides;
+use crate::proving::{CostEstimator, GoalChecker, NoopObserver, ProofFailure, ProofResult, Prover};
+use crate::rewriting::{Pattern, RewriteRule, Unifiable};
+use crate::truth::TruthValue;
+
+/// Converts a statement into rewrite rules that may be used while it is assumed.
+pub trait AsRewriteRules<Node: HashNodeInner + Unifiable> {
+    /// Rules for assuming `statement`, named after `name`.
+    ///
+    /// Statements that cannot be used for rewriting produce no rules.
+    fn as_rewrite_rules(&self, statement: &HashNode<Node>, name: &str) -> Vec<RewriteRule<Node>>;
+}
+
+/// Turns each equation `lhs = rhs` into a bidirectional rule between its two
+/// sides, exactly as the lemma cache does for proven equations.
+pub struct EquationRules<S>(pub S);
+
+impl<Node: HashNodeInner + Unifiable + Clone, S: EquationSides<Node>> AsRewriteRules<Node> for EquationRules<S> {
+    fn as_rewrite_rules(&self, statement: &HashNode<Node>, name: &str) -> Vec<RewriteRule<Node>> {
+        let Some((lhs, rhs)) = self.0.sides(statement) else {
+            return Vec::new();
+        };
+
+        vec![RewriteRule::bidirectional(
+            name,
+            Pattern::constant(lhs.value.as_ref().clone()),
+            Pattern::constant(rhs.value.as_ref().clone()),
+        )]
+    }
+}
+
+impl<Node: HashNodeInner + Clone, C: CostEstimator<Node>, T: TruthValue, G: GoalChecker<Node, T>>
+    Prover<Node, C, T, G>
+{
+    /// Prove `goal` assuming every statement in `hypotheses`.
+    ///
+    /// The rules `converter` produces for the hypotheses (named `hyp_0`,
+    /// `hyp_1`, ...) are added to the prover's rules and cached lemmas for
+    /// this call only.
+    pub fn prove_with_hypotheses(
+        &self,
+        goal: &HashNode<Node>,
+        hypotheses: &[HashNode<Node>],
+        converter: &dyn AsRewriteRules<Node>,
+    ) -> Result<ProofResult<Node, T>, ProofFailure<Node>> {
+        let mut rules: Vec<RewriteRule<Node>> = hypotheses
+            .iter()
+            .enumerate()
+            .flat_map(|(i, hypothesis)| converter.as_rewrite_rules(hypothesis, &format!("hyp_{}", i)))
+            .collect();
+
+        if let Some(lemmas) = &self.lemmas {
+            rules.extend(lemmas.read().unwrap().lemma_rules());
+        }
+
+        self.search(goal, &rules, None, &mut NoopObserver, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, succ, Term, TermReflexivity};
+    use crate::proving::{LemmaCache, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    struct TermSides;
+
+    impl EquationSides<Term> for TermSides {
+        fn sides(&self, expr: &HashNode<Term>) -> Option<(HashNode<Term>, HashNode<Term>)> {
+            match expr.value.as_ref() {
+                Term::Eq(l, r) => Some((l.clone(), r.clone())),
+                _ => None,
+            }
+        }
+    }
+
+    fn prover() -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover
+    }
+
+    #[test]
+    fn test_hypothesis_enables_proof() {
+        let store = NodeStorage::new();
+        // Assuming 1 + 1 = 3, S(1 + 1) = 4 follows by rewriting inside the successor.
+        let hypothesis = eq(add(numeral(1, &store), numeral(1, &store), &store), numeral(3, &store), &store);
+        let goal = eq(succ(add(numeral(1, &store), numeral(1, &store), &store), &store), numeral(4, &store), &store);
+
+        let prover = prover();
+        assert!(prover.prove(&goal).is_err());
+
+        let result = prover
+            .prove_with_hypotheses(&goal, &[hypothesis], &EquationRules(TermSides))
+            .expect("goal follows from the hypothesis");
+        assert!(result.steps.iter().any(|step| step.rule_name == "hyp_0"));
+    }
+
+    #[test]
+    fn test_hypotheses_are_scoped_to_the_call() {
+        let store = NodeStorage::new();
+        let hypothesis = eq(numeral(0, &store), numeral(1, &store), &store);
+        let goal = eq(numeral(0, &store), numeral(1, &store), &store);
+
+        let mut prover = prover();
+        prover.set_lemma_cache(LemmaCache::with_sides(Box::new(TermSides)));
+
+        assert!(prover.prove_with_hypotheses(&goal, &[hypothesis], &EquationRules(TermSides)).is_ok());
+        assert_eq!(prover.lemma_count(), 0);
+        assert!(prover.prove(&goal).is_err());
+    }
+}
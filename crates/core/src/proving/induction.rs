@@ -0,0 +1,265 @@
+//! Proof by structural induction over the naturals.
+//!
+//! Rewriting alone cannot prove statements like `0 + x = x`: no rule applies
+//! to the variable `x`. Induction on `x` reduces such a statement `P(x)` to a
+//! base case `P(0)` and a step case `P(S(x))`, the latter proven under the
+//! hypothesis `P(x)`. Both cases are ordinary searches, so the equational
+//! engine does all the work.
+
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use crate::proving::certificate::ProofCertificate;
+use crate::proving::checker::{verify_proof, ProofCheckError};
+use crate::proving::generalize::replace_subterm;
+use crate::proving::hypotheses::AsRewriteRules;
+use crate::proving::{CostEstimator, GoalChecker, ProofFailure, ProofResult, Prover};
+use crate::rewriting::{RewriteRule, Unifiable};
+use crate::truth::TruthValue;
+use std::fmt::{Display, Formatter};
+
+/// The constructors an induction ranges over.
+pub trait InductionSchema<Node: HashNodeInner> {
+    /// The base value, `0`.
+    fn zero(&self, store: &NodeStorage<Node>) -> HashNode<Node>;
+
+    /// The successor of `term`, `S(term)`.
+    fn successor(&self, term: &HashNode<Node>, store: &NodeStorage<Node>) -> HashNode<Node>;
+}
+
+/// The two subgoals of an induction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InductionCase {
+    Base,
+    Step,
+}
+
+impl Display for InductionCase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InductionCase::Base => write!(f, "base case"),
+            InductionCase::Step => write!(f, "step case"),
+        }
+    }
+}
+
+/// The subgoals of proving `statement` by induction on `variable`.
+pub struct InductionGoals<Node: HashNodeInner> {
+    /// `P(0)`.
+    pub base: HashNode<Node>,
+    /// `P(S(x))`, to be proven assuming `statement`.
+    pub step: HashNode<Node>,
+}
+
+/// Build the base and step subgoals for induction on `variable` in `statement`.
+pub fn induction_goals<Node: HashNodeInner + Clone>(
+    statement: &HashNode<Node>,
+    variable: &HashNode<Node>,
+    schema: &dyn InductionSchema<Node>,
+    store: &NodeStorage<Node>,
+) -> InductionGoals<Node> {
+    InductionGoals {
+        base: replace_subterm(statement, variable, &schema.zero(store), store),
+        step: replace_subterm(statement, variable, &schema.successor(variable, store), store),
+    }
+}
+
+/// A subgoal of an induction that could not be proven.
+pub struct InductionFailure<Node: HashNodeInner> {
+    pub case: InductionCase,
+    pub failure: ProofFailure<Node>,
+}
+
+impl<Node: HashNodeInner> std::fmt::Debug for InductionFailure<Node> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InductionFailure")
+            .field("case", &self.case)
+            .field("failure", &self.failure)
+            .finish()
+    }
+}
+
+impl<Node: HashNodeInner> Display for InductionFailure<Node> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Induction failed in the {}: {}", self.case, self.failure)
+    }
+}
+
+impl<Node: HashNodeInner> std::error::Error for InductionFailure<Node> {}
+
+/// A successful proof by induction.
+pub struct InductionProof<Node: HashNodeInner, T: TruthValue> {
+    /// The statement proven for every value of `variable`.
+    pub statement: HashNode<Node>,
+    pub variable: HashNode<Node>,
+    /// Proof of `P(0)`.
+    pub base: ProofResult<Node, T>,
+    /// Proof of `P(S(x))` under the hypothesis `P(x)`, named `hyp_0`.
+    pub step: ProofResult<Node, T>,
+}
+
+impl<Node: HashNodeInner, T: TruthValue> InductionProof<Node, T> {
+    /// Combined certificate for both cases.
+    pub fn certificate(&self) -> InductionCertificate<Node> {
+        InductionCertificate {
+            statement: self.statement.clone(),
+            variable: self.variable.clone(),
+            base: ProofCertificate::from_result(&self.base),
+            step: ProofCertificate::from_result(&self.step),
+        }
+    }
+}
+
+/// Checkable record of a proof by induction.
+pub struct InductionCertificate<Node: HashNodeInner> {
+    pub statement: HashNode<Node>,
+    pub variable: HashNode<Node>,
+    pub base: ProofCertificate<Node>,
+    /// May use the rules `converter` gives for `statement` under the name `hyp_0`.
+    pub step: ProofCertificate<Node>,
+}
+
+impl<Node: HashNodeInner + Unifiable> InductionCertificate<Node> {
+    /// Check that the cases prove `P(0)` and `P(S(x))` for this statement,
+    /// and replay both with `verify_proof`.
+    ///
+    /// The step case may additionally use the induction hypothesis, turned
+    /// into rules by `converter` just as `Prover::prove_by_induction` did.
+    pub fn verify(
+        &self,
+        rules: &[RewriteRule<Node>],
+        schema: &dyn InductionSchema<Node>,
+        converter: &dyn AsRewriteRules<Node>,
+        store: &NodeStorage<Node>,
+    ) -> Result<(), (InductionCase, ProofCheckError)> {
+        let goals = induction_goals(&self.statement, &self.variable, schema, store);
+
+        if self.base.initial_expr != goals.base {
+            return Err((InductionCase::Base, ProofCheckError::InitialMismatch));
+        }
+        verify_proof(&self.base, rules, store).map_err(|error| (InductionCase::Base, error))?;
+
+        if self.step.initial_expr != goals.step {
+            return Err((InductionCase::Step, ProofCheckError::InitialMismatch));
+        }
+        let mut step_rules = rules.to_vec();
+        step_rules.extend(converter.as_rewrite_rules(&self.statement, "hyp_0"));
+        verify_proof(&self.step, &step_rules, store).map_err(|error| (InductionCase::Step, error))
+    }
+}
+
+impl<Node: HashNodeInner + Clone, C: CostEstimator<Node>, T: TruthValue, G: GoalChecker<Node, T>>
+    Prover<Node, C, T, G>
+{
+    /// Prove `statement` for every value of `variable` by induction.
+    ///
+    /// The base case is an ordinary search. The step case is searched with
+    /// `prove_with_hypotheses`, assuming `statement` itself.
+    pub fn prove_by_induction(
+        &self,
+        statement: &HashNode<Node>,
+        variable: &HashNode<Node>,
+        schema: &dyn InductionSchema<Node>,
+        converter: &dyn AsRewriteRules<Node>,
+    ) -> Result<InductionProof<Node, T>, InductionFailure<Node>> {
+        let goals = induction_goals(statement, variable, schema, &self.store);
+
+        let base = self.prove(&goals.base).map_err(|failure| InductionFailure {
+            case: InductionCase::Base,
+            failure,
+        })?;
+        let step = self
+            .prove_with_hypotheses(&goals.step, std::slice::from_ref(statement), converter)
+            .map_err(|failure| InductionFailure {
+                case: InductionCase::Step,
+                failure,
+            })?;
+
+        Ok(InductionProof {
+            statement: statement.clone(),
+            variable: variable.clone(),
+            base,
+            step,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proving::hypotheses::EquationRules;
+    use crate::proving::lemmas::EquationSides;
+    use crate::proving::testing::{add, addition_rules, eq, succ, zero, Term, TermReflexivity};
+    use crate::proving::SizeCostEstimator;
+    use crate::BinaryTruth;
+
+    struct TermSides;
+
+    impl EquationSides<Term> for TermSides {
+        fn sides(&self, expr: &HashNode<Term>) -> Option<(HashNode<Term>, HashNode<Term>)> {
+            match expr.value.as_ref() {
+                Term::Eq(l, r) => Some((l.clone(), r.clone())),
+                _ => None,
+            }
+        }
+    }
+
+    struct Naturals;
+
+    impl InductionSchema<Term> for Naturals {
+        fn zero(&self, store: &NodeStorage<Term>) -> HashNode<Term> {
+            zero(store)
+        }
+
+        fn successor(&self, term: &HashNode<Term>, store: &NodeStorage<Term>) -> HashNode<Term> {
+            succ(term.clone(), store)
+        }
+    }
+
+    fn prover() -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover
+    }
+
+    #[test]
+    fn test_left_identity_by_induction() {
+        let store = NodeStorage::new();
+        let x = HashNode::from_store(Term::Var(0), &store);
+        let statement = eq(add(zero(&store), x.clone(), &store), x.clone(), &store);
+
+        let prover = prover();
+        assert!(prover.prove(&statement).is_err());
+
+        let proof = prover
+            .prove_by_induction(&statement, &x, &Naturals, &EquationRules(TermSides))
+            .expect("0 + x = x by induction on x");
+        assert!(proof.step.steps.iter().any(|step| step.rule_name == "hyp_0"));
+
+        let certificate = proof.certificate();
+        assert_eq!(
+            certificate.verify(&addition_rules(), &Naturals, &EquationRules(TermSides), &store),
+            Ok(())
+        );
+
+        let mut swapped = proof.certificate();
+        std::mem::swap(&mut swapped.base, &mut swapped.step);
+        assert_eq!(
+            swapped.verify(&addition_rules(), &Naturals, &EquationRules(TermSides), &store),
+            Err((InductionCase::Base, ProofCheckError::InitialMismatch))
+        );
+    }
+
+    #[test]
+    fn test_failing_case_is_reported() {
+        let store = NodeStorage::new();
+        let x = HashNode::from_store(Term::Var(0), &store);
+        // x = S(0) holds for no x, and already fails at 0.
+        let statement = eq(x.clone(), succ(zero(&store), &store), &store);
+
+        let Err(failure) = prover().prove_by_induction(&statement, &x, &Naturals, &EquationRules(TermSides)) else {
+            panic!("0 = S(0) is false");
+        };
+        assert_eq!(failure.case, InductionCase::Base);
+    }
+}
@@ -0,0 +1,187 @@
+//! Step-by-step proving driven by a user.
+//!
+//! An `InteractiveSession` is the backend for a REPL or GUI: it shows which
+//! rules apply where, applies the one the user picks, can undo, and hands
+//! back a `ProofCertificate` for the steps taken.
+
+use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::proving::certificate::ProofCertificate;
+use crate::proving::tactics::{ProofGoal, Tactic, TacticContext, TacticError};
+use crate::proving::ProofStep;
+use crate::rewriting::{Position, RewriteDirection};
+use crate::truth::TruthValue;
+
+/// A rewrite the user can choose in the current state.
+pub struct Candidate<Node: HashNodeInner> {
+    /// Name of the rule.
+    pub rule_name: String,
+    /// Direction the rule would be applied in.
+    pub direction: RewriteDirection,
+    /// Where in the current expression it matches.
+    pub position: Position,
+    /// The expression applying it would produce.
+    pub result: HashNode<Node>,
+}
+
+/// A proof in progress, advanced one user-chosen step at a time.
+pub struct InteractiveSession<'a, Node: HashNodeInner + Clone, T: TruthValue> {
+    ctx: &'a dyn TacticContext<Node, T>,
+    history: Vec<ProofGoal<Node, T>>,
+}
+
+impl<'a, Node: HashNodeInner + Clone, T: TruthValue> InteractiveSession<'a, Node, T> {
+    /// Start proving `statement` with the rules of `ctx` (usually a `Prover`).
+    pub fn new(ctx: &'a dyn TacticContext<Node, T>, statement: &HashNode<Node>) -> Self {
+        Self {
+            ctx,
+            history: vec![ProofGoal::new(statement)],
+        }
+    }
+
+    /// The current goal state.
+    pub fn goal(&self) -> &ProofGoal<Node, T> {
+        self.history.last().expect("history always holds the initial goal")
+    }
+
+    /// The current expression.
+    pub fn current(&self) -> &HashNode<Node> {
+        &self.goal().current
+    }
+
+    /// Every rule application available in the current expression.
+    pub fn candidates(&self) -> Vec<Candidate<Node>> {
+        let mut candidates = Vec::new();
+        for rule in self.ctx.rules() {
+            for direction in [RewriteDirection::Forward, RewriteDirection::Backward] {
+                for rewrite in rule.rewrites_at_all_positions(self.current(), direction, self.ctx.store()) {
+                    candidates.push(Candidate {
+                        rule_name: rewrite.rule_name,
+                        direction,
+                        position: rewrite.position,
+                        result: rewrite.term,
+                    });
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Apply the rule named `rule_name` at `position`, forwards if it matches
+    /// there and otherwise backwards.
+    pub fn apply(&mut self, rule_name: &str, position: &[usize]) -> Result<&ProofGoal<Node, T>, TacticError> {
+        if self.goal().is_closed() {
+            return Err(TacticError::GoalClosed);
+        }
+
+        let rule = self
+            .ctx
+            .rules()
+            .iter()
+            .find(|rule| rule.name == rule_name)
+            .ok_or_else(|| TacticError::UnknownRule(rule_name.to_string()))?;
+
+        let rewrite = [RewriteDirection::Forward, RewriteDirection::Backward]
+            .into_iter()
+            .flat_map(|direction| rule.rewrites_at_all_positions(self.current(), direction, self.ctx.store()))
+            .find(|rewrite| rewrite.position == position)
+            .ok_or_else(|| TacticError::RuleNotApplicable(rule_name.to_string()))?;
+
+        let step = ProofStep {
+            rule_name: rewrite.rule_name,
+            direction: rewrite.direction,
+            position: rewrite.position,
+            substitution: rewrite.substitution,
+            old_expr: self.current().clone(),
+            new_expr: rewrite.term,
+        };
+        let next = self.goal().advanced(step);
+        self.history.push(next);
+        Ok(self.goal())
+    }
+
+    /// Run a tactic on the current goal, recording its result as one undoable move.
+    pub fn run(&mut self, tactic: &dyn Tactic<Node, T>) -> Result<&ProofGoal<Node, T>, TacticError> {
+        let next = tactic.apply(self.ctx, self.goal())?;
+        self.history.push(next);
+        Ok(self.goal())
+    }
+
+    /// Close the goal if the goal checker accepts the current expression.
+    pub fn close(&mut self) -> Option<&T> {
+        if !self.goal().is_closed() {
+            let truth = self.ctx.check_goal(self.current())?;
+            let mut closed = self.goal().clone();
+            closed.closed = Some(truth);
+            self.history.push(closed);
+        }
+        self.goal().closed.as_ref()
+    }
+
+    /// Undo the last move. Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        if self.history.len() == 1 {
+            return false;
+        }
+        self.history.pop();
+        true
+    }
+
+    /// The steps taken so far.
+    pub fn certificate(&self) -> ProofCertificate<Node> {
+        self.goal().certificate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::{tactics, verify_proof, Prover, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    fn prover() -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover
+    }
+
+    #[test]
+    fn test_step_through_proof() {
+        let prover = prover();
+        let store = NodeStorage::new();
+        let statement = eq(add(numeral(1, &store), numeral(1, &store), &store), numeral(2, &store), &store);
+        let mut session = InteractiveSession::new(&prover, &statement);
+
+        let candidates = session.candidates();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!((candidates[0].rule_name.as_str(), candidates[0].position.as_slice()), ("add_succ", &[0][..]));
+
+        session.apply("add_succ", &[0]).expect("add_succ applies");
+        session.apply("add_zero", &[0, 0]).expect("add_zero applies");
+        assert_eq!(session.close(), Some(&BinaryTruth::True));
+
+        let certificate = session.certificate();
+        assert_eq!(certificate.len(), 2);
+        assert_eq!(verify_proof(&certificate, &addition_rules(), &store), Ok(()));
+    }
+
+    #[test]
+    fn test_undo_and_errors() {
+        let prover = prover();
+        let store = NodeStorage::new();
+        let statement = eq(add(numeral(1, &store), numeral(1, &store), &store), numeral(2, &store), &store);
+        let mut session = InteractiveSession::new(&prover, &statement);
+
+        assert!(!session.undo());
+        assert!(matches!(session.apply("add_zero", &[0]), Err(TacticError::RuleNotApplicable(_))));
+        assert!(session.close().is_none());
+
+        session.run(&*tactics::auto(100)).expect("auto closes the goal");
+        assert!(session.goal().is_closed());
+        assert!(session.undo());
+        assert!(*session.current() == statement);
+    }
+}
@@ -0,0 +1,423 @@
+//! JSON export of proofs, proof states and certificates.
+//!
+//! The schema is fixed so that web front ends and external checkers can read
+//! prover output without linking against this crate. Expressions are written
+//! as strings in the textual syntax of their `Display` implementation; every
+//! top-level document carries a `"schema"` field naming its version.
+//!
+//! A step is written as
+//!
+//! ```text
+//! {"rule": "add_succ", "direction": "forward", "position": [0],
+//!  "substitution": {"0": "S(0)", "1": "0"}, "from": "...", "to": "..."}
+//! ```
+//!
+//! with substitution keys the indices of the rule's variables, in ascending
+//! order. Object fields are always written in the order documented on each
+//! `ToJson` implementation.
+
+use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::proving::{ProofCertificate, ProofResult, ProofState, ProofStep, SearchStats};
+use crate::rewriting::{RewriteDirection, Substitution};
+use crate::truth::TruthValue;
+use std::fmt::{self, Display, Formatter};
+
+/// Version tag written into every top-level document.
+pub const SCHEMA: &str = "corpus-proof/1";
+
+/// A JSON value. Objects keep their fields in insertion order, so output is
+/// stable across runs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(u64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn string(text: impl Into<String>) -> Self {
+        Json::String(text.into())
+    }
+
+    pub fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Json)>) -> Self {
+        Json::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    /// The value of field `key`, if this is an object that has one.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(field, _)| field == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Read a JSON document. Numbers must be unsigned integers, the only
+    /// ones this schema writes.
+    pub fn parse(text: &str) -> Result<Json, JsonError> {
+        let mut reader = JsonReader { text, offset: 0 };
+        let value = reader.value()?;
+        reader.skip_whitespace();
+        if reader.offset != text.len() {
+            return Err(reader.error("trailing characters"));
+        }
+        Ok(value)
+    }
+}
+
+/// Malformed JSON text, at a byte offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "offset {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+struct JsonReader<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl JsonReader<'_> {
+    fn error(&self, message: &str) -> JsonError {
+        JsonError { offset: self.offset, message: message.to_string() }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.offset..];
+        self.offset += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.offset..].chars().next()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        if self.peek() != Some(c) {
+            return Err(self.error(&format!("expected '{}'", c)));
+        }
+        self.offset += 1;
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Json, JsonError> {
+        self.skip_whitespace();
+        for (word, value) in [("null", Json::Null), ("true", Json::Bool(true)), ("false", Json::Bool(false))] {
+            if self.text[self.offset..].starts_with(word) {
+                self.offset += word.len();
+                return Ok(value);
+            }
+        }
+        match self.peek() {
+            Some('"') => self.string().map(Json::String),
+            Some('[') => {
+                self.offset += 1;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(']') {
+                    self.offset += 1;
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(',') => self.offset += 1,
+                        Some(']') => break,
+                        _ => return Err(self.error("expected ',' or ']'")),
+                    }
+                }
+                self.offset += 1;
+                Ok(Json::Array(items))
+            }
+            Some('{') => {
+                self.offset += 1;
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.offset += 1;
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    fields.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(',') => self.offset += 1,
+                        Some('}') => break,
+                        _ => return Err(self.error("expected ',' or '}'")),
+                    }
+                }
+                self.offset += 1;
+                Ok(Json::Object(fields))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let digits = self.text[self.offset..].len() - self.text[self.offset..].trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                let number = self.text[self.offset..self.offset + digits].parse().map_err(|_| self.error("number out of range"))?;
+                self.offset += digits;
+                if matches!(self.peek(), Some('.' | 'e' | 'E')) {
+                    return Err(self.error("only unsigned integers are supported"));
+                }
+                Ok(Json::Number(number))
+            }
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        if self.peek() != Some('"') {
+            return Err(self.error("expected a string"));
+        }
+        self.offset += 1;
+        let mut text = String::new();
+        loop {
+            let c = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+            self.offset += c.len_utf8();
+            match c {
+                '"' => return Ok(text),
+                '\\' => {
+                    let escape = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+                    self.offset += 1;
+                    text.push(match escape {
+                        '"' | '\\' | '/' => escape,
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'u' => {
+                            let code = self.text.get(self.offset..self.offset + 4).and_then(|hex| u32::from_str_radix(hex, 16).ok());
+                            let c = code.and_then(char::from_u32).ok_or_else(|| self.error("invalid \\u escape"))?;
+                            self.offset += 4;
+                            c
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    });
+                }
+                c => text.push(c),
+            }
+        }
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(text) => write_string(f, text),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut Formatter<'_>, text: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in text.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Conversion into the JSON schema of this module.
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+fn expression<T: HashNodeInner + Display>(expr: &HashNode<T>) -> Json {
+    Json::String(expr.to_string())
+}
+
+fn direction(direction: &RewriteDirection) -> Json {
+    Json::string(match direction {
+        RewriteDirection::Forward => "forward",
+        RewriteDirection::Backward => "backward",
+        RewriteDirection::Both => "both",
+    })
+}
+
+fn substitution<T: HashNodeInner + Display>(substitution: &Substitution<T>) -> Json {
+    let mut bindings: Vec<_> = substitution.iter().collect();
+    bindings.sort_by_key(|(index, _)| **index);
+    Json::Object(bindings.into_iter().map(|(index, term)| (index.to_string(), expression(term))).collect())
+}
+
+fn steps<'a, T: HashNodeInner + Display + 'a>(steps: impl IntoIterator<Item = &'a ProofStep<T>>) -> Json {
+    Json::Array(steps.into_iter().map(ToJson::to_json).collect())
+}
+
+/// Fields: `rule`, `direction`, `position`, `substitution`, `from`, `to`.
+impl<T: HashNodeInner + Display> ToJson for ProofStep<T> {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("rule", Json::string(&self.rule_name)),
+            ("direction", direction(&self.direction)),
+            ("position", Json::Array(self.position.iter().map(|&i| Json::Number(i as u64)).collect())),
+            ("substitution", substitution(&self.substitution)),
+            ("from", expression(&self.old_expr)),
+            ("to", expression(&self.new_expr)),
+        ])
+    }
+}
+
+/// Fields: `schema`, `expr`, `estimated_cost`, `steps`.
+impl<T: HashNodeInner + Display> ToJson for ProofState<T> {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("schema", Json::string(SCHEMA)),
+            ("expr", expression(&self.expr)),
+            ("estimated_cost", Json::Number(self.estimated_cost)),
+            ("steps", steps(&self.steps)),
+        ])
+    }
+}
+
+/// Fields: `max_heap_size`, `duplicate_hits`, `states_per_depth`,
+/// `rule_applications`, `elapsed_ms`.
+impl ToJson for SearchStats {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("max_heap_size", Json::Number(self.max_heap_size as u64)),
+            ("duplicate_hits", Json::Number(self.duplicate_hits as u64)),
+            (
+                "states_per_depth",
+                Json::Array(self.states_per_depth.iter().map(|&n| Json::Number(n as u64)).collect()),
+            ),
+            (
+                "rule_applications",
+                Json::Object(
+                    self.rule_applications
+                        .iter()
+                        .map(|(rule, &n)| (rule.clone(), Json::Number(n as u64)))
+                        .collect(),
+                ),
+            ),
+            ("elapsed_ms", Json::Number(self.elapsed.as_millis() as u64)),
+        ])
+    }
+}
+
+/// Fields: `schema`, `truth`, `final`, `nodes_explored`, `steps`, `stats`.
+impl<Node: HashNodeInner + Display, T: TruthValue> ToJson for ProofResult<Node, T> {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("schema", Json::string(SCHEMA)),
+            ("truth", Json::String(self.truth_result.to_string())),
+            ("final", expression(&self.final_expr)),
+            ("nodes_explored", Json::Number(self.nodes_explored as u64)),
+            ("steps", steps(&self.steps)),
+            ("stats", self.stats.to_json()),
+        ])
+    }
+}
+
+/// Fields: `schema`, `initial`, `final`, `steps`, `instantiation`; each
+/// instantiation entry has fields `variable` and `instance`.
+impl<T: HashNodeInner + Display> ToJson for ProofCertificate<T> {
+    fn to_json(&self) -> Json {
+        let instantiation = self
+            .instantiation
+            .iter()
+            .map(|generalization| {
+                Json::object([
+                    ("variable", expression(&generalization.variable)),
+                    ("instance", expression(&generalization.instance)),
+                ])
+            })
+            .collect();
+        Json::object([
+            ("schema", Json::string(SCHEMA)),
+            ("initial", expression(&self.initial_expr)),
+            ("final", expression(&self.final_expr)),
+            ("steps", steps(&self.steps)),
+            ("instantiation", Json::Array(instantiation)),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::{Prover, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    #[test]
+    fn test_escaping() {
+        let value = Json::object([("a\"b", Json::string("line\n\ttab\\\u{1}")), ("n", Json::Null)]);
+        assert_eq!(value.to_string(), r#"{"a\"b":"line\n\ttab\\\u0001","n":null}"#);
+        assert_eq!(Json::parse(&value.to_string()), Ok(value));
+        let nested = Json::parse(" { \"xs\" : [1, true, [], {}, \"\\u00e9\"] } ").unwrap();
+        assert_eq!(nested.to_string(), "{\"xs\":[1,true,[],{},\"é\"]}");
+        assert_eq!(Json::parse("[1, 2.5]").unwrap_err().to_string(), "offset 5: only unsigned integers are supported");
+        assert_eq!(Json::parse("{\"a\" 1}").unwrap_err().to_string(), "offset 5: expected ':'");
+    }
+
+    #[test]
+    fn test_result_and_certificate() {
+        let store = NodeStorage::new();
+        let mut prover: Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> =
+            Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        let goal = eq(add(numeral(0, &store), numeral(1, &store), &store), numeral(1, &store), &store);
+        let result = prover.prove(&goal).expect("0 + 1 = 1 should be provable");
+
+        let json = result.to_json();
+        assert_eq!(json.get("schema"), Some(&Json::string(SCHEMA)));
+        assert_eq!(json.get("final"), Some(&Json::string("S(0) = S(0)")));
+
+        let Some(Json::Array(steps)) = json.get("steps") else { panic!("steps should be an array") };
+        assert_eq!(
+            steps[0].to_string(),
+            r#"{"rule":"add_succ","direction":"forward","position":[0],"substitution":{"0":"0","1":"0"},"from":"(0 + S(0)) = S(0)","to":"S((0 + 0)) = S(0)"}"#
+        );
+
+        let certificate = result.certificate().to_json();
+        assert_eq!(certificate.get("initial"), Some(&Json::string("(0 + S(0)) = S(0)")));
+        assert_eq!(certificate.get("instantiation"), Some(&Json::Array(Vec::new())));
+    }
+}
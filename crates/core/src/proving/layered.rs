@@ -0,0 +1,184 @@
+//! Search over nodes whose rewrite rules live one layer down.
+//!
+//! Some domains wrap terms in a different node type: a PA equation is a
+//! relation between arithmetic terms, and its axioms are rules over the
+//! terms, not over the equation. `Prover` only applies rules over its own
+//! node type, so such domains hand their rewrites to the search through a
+//! `StateExpander` instead (see `Prover::prove_with_expander`).
+//!
+//! `SubtermRewritable` exposes the inner terms of an outer node, and
+//! `LayeredRewriter` applies inner rules at every position of them. Rewritten
+//! terms are interned into the inner store and rebuilt nodes into the outer
+//! one, so a caller sharing both stores with its parser keeps a single copy
+//! of every node the search reaches.
+
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use crate::rewriting::{positions, replace_at, subterm_at, RewriteDirection, RewriteResult, RewriteRule, Substitution, Unifiable};
+
+/// A node built from terms of another node type.
+pub trait SubtermRewritable<Inner: HashNodeInner>: HashNodeInner + Sized {
+    /// The inner terms directly below this node, e.g. the sides of an equation.
+    fn inner_terms(&self) -> Vec<HashNode<Inner>>;
+
+    /// This node with its inner terms replaced by `terms`, or `None` if
+    /// `terms` does not fit.
+    fn with_inner_terms(&self, terms: Vec<HashNode<Inner>>) -> Option<Self>;
+}
+
+/// Successors of a search state beyond those of the prover's own rules.
+pub trait StateExpander<Node: HashNodeInner> {
+    /// Every rewrite of `expr`, each recording how it was produced.
+    fn expand(&self, expr: &HashNode<Node>) -> Vec<RewriteResult<Node>>;
+}
+
+/// A replacement for one inner subterm: the new subterm, the rule that
+/// produced it and the direction it was applied in.
+pub type InnerRewrite<Inner> = (HashNode<Inner>, String, RewriteDirection);
+
+/// Applies rules over `Inner` terms to the inner terms of `Outer` nodes.
+///
+/// Positions of the results start with the index of the rewritten inner
+/// term, followed by the path to the rewritten subterm within it. Their
+/// substitutions are empty, since the rules bind `Inner` terms.
+pub struct LayeredRewriter<'a, Outer: HashNodeInner, Inner: HashNodeInner + Unifiable> {
+    rules: &'a [RewriteRule<Inner>],
+    outer_store: &'a NodeStorage<Outer>,
+    inner_store: &'a NodeStorage<Inner>,
+}
+
+impl<'a, Outer: SubtermRewritable<Inner>, Inner: HashNodeInner + Unifiable> LayeredRewriter<'a, Outer, Inner> {
+    pub fn new(rules: &'a [RewriteRule<Inner>], outer_store: &'a NodeStorage<Outer>, inner_store: &'a NodeStorage<Inner>) -> Self {
+        Self {
+            rules,
+            outer_store,
+            inner_store,
+        }
+    }
+
+    /// The store rewritten inner terms are interned into.
+    pub fn inner_store(&self) -> &'a NodeStorage<Inner> {
+        self.inner_store
+    }
+
+    /// The replacements of `subterm` by each rule, forward and then backward.
+    pub fn rule_rewrites(&self, subterm: &HashNode<Inner>) -> Vec<InnerRewrite<Inner>> {
+        let mut rewrites = Vec::new();
+        for rule in self.rules {
+            if let Some(rewritten) = rule.apply(subterm, self.inner_store) {
+                rewrites.push((rewritten, rule.name.clone(), RewriteDirection::Forward));
+            }
+            if let Some(rewritten) = rule.apply_reverse(subterm, self.inner_store) {
+                rewrites.push((rewritten, rule.name.clone(), RewriteDirection::Backward));
+            }
+        }
+        rewrites
+    }
+
+    /// The rewrites of `expr` obtained by replacing any subterm of any inner
+    /// term with one of the replacements `rewrite` finds for it.
+    pub fn rewrites_with<F>(&self, expr: &HashNode<Outer>, rewrite: F) -> Vec<RewriteResult<Outer>>
+    where
+        F: Fn(&HashNode<Inner>) -> Vec<InnerRewrite<Inner>>,
+    {
+        let terms = expr.value.inner_terms();
+        let mut results = Vec::new();
+
+        for (index, term) in terms.iter().enumerate() {
+            for path in positions(term) {
+                let Some(subterm) = subterm_at(term, &path) else {
+                    continue;
+                };
+                for (rewritten, rule_name, direction) in rewrite(&subterm) {
+                    let mut new_terms = terms.clone();
+                    let Some(new_term) = replace_at(term, &path, rewritten, self.inner_store) else {
+                        continue;
+                    };
+                    new_terms[index] = new_term;
+                    let Some(node) = expr.value.with_inner_terms(new_terms) else {
+                        continue;
+                    };
+
+                    let mut position = vec![index];
+                    position.extend(&path);
+                    results.push(RewriteResult {
+                        term: HashNode::from_store(node, self.outer_store),
+                        substitution: Substitution::new(),
+                        rule_name,
+                        direction,
+                        position,
+                    });
+                }
+            }
+        }
+
+        results
+    }
+}
+
+impl<Outer: SubtermRewritable<Inner>, Inner: HashNodeInner + Unifiable> StateExpander<Outer> for LayeredRewriter<'_, Outer, Inner> {
+    fn expand(&self, expr: &HashNode<Outer>) -> Vec<RewriteResult<Outer>> {
+        self.rewrites_with(expr, |subterm| self.rule_rewrites(subterm))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::Hashing;
+    use crate::proving::testing::{add, addition_rules, numeral, Term};
+    use crate::proving::{GoalChecker, Prover, SizeCostEstimator};
+    use crate::BinaryTruth;
+    use std::sync::Arc;
+
+    /// `left = right` as a node type of its own, above `Term`.
+    #[derive(Clone, PartialEq)]
+    struct Equation(HashNode<Term>, HashNode<Term>);
+
+    impl HashNodeInner for Equation {
+        fn hash(&self) -> u64 {
+            Hashing::root_hash(Hashing::opcode("equation"), &[self.0.hash(), self.1.hash()])
+        }
+
+        fn size(&self) -> u64 {
+            1 + self.0.size() + self.1.size()
+        }
+    }
+
+    impl SubtermRewritable<Term> for Equation {
+        fn inner_terms(&self) -> Vec<HashNode<Term>> {
+            vec![self.0.clone(), self.1.clone()]
+        }
+
+        fn with_inner_terms(&self, terms: Vec<HashNode<Term>>) -> Option<Self> {
+            let [left, right] = <[_; 2]>::try_from(terms).ok()?;
+            Some(Equation(left, right))
+        }
+    }
+
+    struct SidesMeet;
+
+    impl GoalChecker<Equation, BinaryTruth> for SidesMeet {
+        fn check(&self, expr: &HashNode<Equation>) -> Option<BinaryTruth> {
+            (expr.value.0 == expr.value.1).then_some(BinaryTruth::True)
+        }
+    }
+
+    #[test]
+    fn test_inner_rules_drive_the_prover() {
+        let terms = NodeStorage::new();
+        let equations = NodeStorage::new();
+        let sum = add(numeral(2, &terms), numeral(2, &terms), &terms);
+        let goal = HashNode::from_store(Equation(sum, numeral(4, &terms)), &equations);
+        let rules = addition_rules();
+        let rewriter = LayeredRewriter::new(&rules, &equations, &terms);
+
+        let prover: Prover<_, _, BinaryTruth, _> = Prover::new(100, SizeCostEstimator, SidesMeet);
+        assert!(prover.prove(&goal).is_err());
+        let result = prover.prove_with_expander(&goal, &rewriter).expect("2 + 2 = 4");
+        assert_eq!(result.steps.len(), 3);
+        assert!(result.steps.iter().all(|step| step.position.first() == Some(&0)));
+
+        // Rewritten sides are interned into the caller's term store.
+        assert!(Arc::ptr_eq(&result.final_expr.value.0.value, &goal.value.1.value));
+    }
+}
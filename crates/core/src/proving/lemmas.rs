@@ -0,0 +1,225 @@
+//! Caching of proven statements across `prove` calls.
+//!
+//! A `LemmaCache` remembers every statement a `Prover` has settled together
+//! with its certificate. Later calls on the same statement are answered
+//! without searching, and proven equations can be fed back into the search as
+//! additional rewrite rules.
+
+use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::proving::certificate::ProofCertificate;
+use crate::proving::ProofResult;
+use crate::rewriting::{Pattern, RewriteRule, Unifiable};
+use crate::truth::TruthValue;
+use std::collections::HashMap;
+
+/// Splits an equational statement into its two sides.
+///
+/// Domains provide this so lemmas can be keyed independently of orientation
+/// and turned into rewrite rules between the two sides.
+pub trait EquationSides<Node: HashNodeInner> {
+    /// Return `(lhs, rhs)` if `expr` is an equation.
+    fn sides(&self, expr: &HashNode<Node>) -> Option<(HashNode<Node>, HashNode<Node>)>;
+}
+
+/// A statement the prover has settled, with the evidence for it.
+pub struct Lemma<Node: HashNodeInner, T: TruthValue> {
+    /// Name used for the rewrite rule generated from this lemma.
+    pub name: String,
+    /// Steps from the statement to the expression accepted by the goal checker.
+    pub certificate: ProofCertificate<Node>,
+    /// The truth value the goal checker assigned.
+    pub truth: T,
+}
+
+/// Cache of proven statements keyed by their normalized side hashes.
+///
+/// Without an `EquationSides` implementation the key is the hash of the whole
+/// statement. With one, the key is the unordered pair of side hashes, so
+/// `a = b` and `b = a` share an entry.
+pub struct LemmaCache<Node: HashNodeInner, T: TruthValue> {
+    lemmas: HashMap<(u64, u64), Lemma<Node, T>>,
+    order: Vec<(u64, u64)>,
+    sides: Option<Box<dyn EquationSides<Node>>>,
+}
+
+impl<Node: HashNodeInner, T: TruthValue> LemmaCache<Node, T> {
+    /// Create a cache keyed by whole-statement hashes.
+    pub fn new() -> Self {
+        Self {
+            lemmas: HashMap::new(),
+            order: Vec::new(),
+            sides: None,
+        }
+    }
+
+    /// Create a cache that understands equations, enabling orientation-free
+    /// keys and lemma rewrite rules.
+    pub fn with_sides(sides: Box<dyn EquationSides<Node>>) -> Self {
+        Self {
+            lemmas: HashMap::new(),
+            order: Vec::new(),
+            sides: Some(sides),
+        }
+    }
+
+    /// Compute the normalized key for a statement.
+    pub fn key(&self, expr: &HashNode<Node>) -> (u64, u64) {
+        match self.sides.as_ref().and_then(|sides| sides.sides(expr)) {
+            Some((lhs, rhs)) => {
+                let (a, b) = (lhs.hash(), rhs.hash());
+                (a.min(b), a.max(b))
+            }
+            None => (expr.hash(), expr.hash()),
+        }
+    }
+
+    /// Look up a previously settled statement.
+    pub fn lookup(&self, expr: &HashNode<Node>) -> Option<&Lemma<Node, T>> {
+        self.lemmas.get(&self.key(expr))
+    }
+
+    pub fn contains(&self, expr: &HashNode<Node>) -> bool {
+        self.lemmas.contains_key(&self.key(expr))
+    }
+
+    /// Record the outcome of a successful proof of `expr`.
+    ///
+    /// An existing entry for the same key is kept, so the first certificate
+    /// found for a statement stays authoritative.
+    pub fn insert(&mut self, expr: &HashNode<Node>, result: &ProofResult<Node, T>) {
+        let key = self.key(expr);
+        if self.lemmas.contains_key(&key) {
+            return;
+        }
+
+        let mut certificate = result.certificate();
+        certificate.initial_expr = expr.clone();
+
+        let name = format!("lemma_{}", self.order.len());
+        self.order.push(key);
+        self.lemmas.insert(
+            key,
+            Lemma {
+                name,
+                certificate,
+                truth: result.truth_result.clone(),
+            },
+        );
+    }
+
+    /// All cached lemmas in insertion order.
+    pub fn lemmas(&self) -> impl Iterator<Item = &Lemma<Node, T>> {
+        self.order.iter().filter_map(|key| self.lemmas.get(key))
+    }
+
+    pub fn len(&self) -> usize {
+        self.lemmas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lemmas.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.lemmas.clear();
+        self.order.clear();
+    }
+}
+
+impl<Node: HashNodeInner + Unifiable, T: TruthValue> LemmaCache<Node, T> {
+    /// Turn every proven equation into a bidirectional rewrite rule between
+    /// its two sides.
+    ///
+    /// Lemmas that were disproved, or that are not equations according to the
+    /// cache's `EquationSides`, produce no rule.
+    pub fn lemma_rules(&self) -> Vec<RewriteRule<Node>> {
+        let Some(sides) = &self.sides else {
+            return Vec::new();
+        };
+
+        self.lemmas()
+            .filter(|lemma| lemma.truth.is_true())
+            .filter_map(|lemma| {
+                let (lhs, rhs) = sides.sides(&lemma.certificate.initial_expr)?;
+                Some(RewriteRule::bidirectional(
+                    lemma.name.clone(),
+                    Pattern::constant(lhs.value.as_ref().clone()),
+                    Pattern::constant(rhs.value.as_ref().clone()),
+                ))
+            })
+            .collect()
+    }
+}
+
+impl<Node: HashNodeInner, T: TruthValue> Default for LemmaCache<Node, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::{Prover, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    struct TermSides;
+
+    impl EquationSides<Term> for TermSides {
+        fn sides(&self, expr: &HashNode<Term>) -> Option<(HashNode<Term>, HashNode<Term>)> {
+            match expr.value.as_ref() {
+                Term::Eq(l, r) => Some((l.clone(), r.clone())),
+                _ => None,
+            }
+        }
+    }
+
+    fn prover() -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover.set_lemma_cache(LemmaCache::with_sides(Box::new(TermSides)));
+        prover
+    }
+
+    #[test]
+    fn test_key_is_orientation_free() {
+        let store = NodeStorage::new();
+        let cache: LemmaCache<Term, BinaryTruth> = LemmaCache::with_sides(Box::new(TermSides));
+        let a = numeral(1, &store);
+        let b = numeral(2, &store);
+
+        assert_eq!(cache.key(&eq(a.clone(), b.clone(), &store)), cache.key(&eq(b, a, &store)));
+    }
+
+    #[test]
+    fn test_second_prove_hits_cache() {
+        let store = NodeStorage::new();
+        let prover = prover();
+        let goal = eq(add(numeral(2, &store), numeral(2, &store), &store), numeral(4, &store), &store);
+
+        let first = prover.prove(&goal).expect("goal should be provable");
+        assert!(first.nodes_explored > 0);
+
+        let second = prover.prove(&goal).expect("cached goal should be provable");
+        assert_eq!(second.nodes_explored, 0);
+        assert_eq!(second.steps.len(), first.steps.len());
+        assert_eq!(prover.lemma_count(), 1);
+    }
+
+    #[test]
+    fn test_lemma_rules_close_swapped_goal() {
+        let store = NodeStorage::new();
+        let prover = prover();
+        let lhs = add(numeral(1, &store), numeral(1, &store), &store);
+        let rhs = numeral(2, &store);
+
+        prover.prove(&eq(lhs.clone(), rhs.clone(), &store)).expect("goal should be provable");
+
+        let swapped = prover.prove(&eq(rhs, lhs, &store)).expect("swapped goal should be provable");
+        assert!(swapped.steps.iter().any(|step| step.rule_name == "lemma_0"));
+    }
+}
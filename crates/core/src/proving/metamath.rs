@@ -0,0 +1,522 @@
+//! Metamath export of proof certificates.
+//!
+//! `to_metamath` writes a certificate and the rules it was checked against
+//! as a Metamath database, so that a small checker independent of this crate
+//! (`metamath`, `mmverify.py`) can confirm the proof. Terms are written in
+//! prefix form, `( + ?0 ( S 0 ) )`, with a syntax axiom per operator and
+//! leaf. Each rule becomes an axiom `|- pattern == replacement`, and `==` is
+//! closed under reflexivity, symmetry, transitivity and a congruence axiom
+//! per operator argument. The theorem `|- initial == final` is proven by
+//! instantiating the rule of each step with the step's substitution, lifting
+//! it to the whole expression by congruence along the step's position, and
+//! chaining the steps by transitivity.
+
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use crate::base::printing::{ExprPrinter, Notation, SymbolTable};
+use crate::proving::checker::{verify_proof, ProofCheckError};
+use crate::proving::{ProofCertificate, ProofStep};
+use crate::rewriting::{subterm_at, Pattern, RewriteDirection, RewriteRule, Unifiable};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+/// Reasons a certificate cannot be written as a Metamath database.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetamathError {
+    /// The certificate does not check against the rules.
+    Invalid(ProofCheckError),
+    /// A step's substitution leaves a variable of its rule unbound.
+    Unbound { step: usize, variable: u32 },
+    /// A rule pattern holds a wildcard, which has no Metamath counterpart.
+    Wildcard { rule: String },
+    /// A name that is not a valid Metamath label or math symbol.
+    InvalidName(String),
+    /// A name given to two different things.
+    Clash(String),
+}
+
+impl Display for MetamathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetamathError::Invalid(error) => write!(f, "{}", error),
+            MetamathError::Unbound { step, variable } => {
+                write!(f, "Step {} leaves /{} of its rule unbound", step + 1, variable)
+            }
+            MetamathError::Wildcard { rule } => write!(f, "Rule '{}' has a wildcard", rule),
+            MetamathError::InvalidName(name) => write!(f, "'{}' is not a valid Metamath name", name),
+            MetamathError::Clash(name) => write!(f, "'{}' names two different things", name),
+        }
+    }
+}
+
+impl std::error::Error for MetamathError {}
+
+/// The rewriting equality, distinct from any equality of the domain.
+const EQUALS: &str = "==";
+const RESERVED: [&str; 5] = ["|-", "term", "(", ")", EQUALS];
+
+impl<T: HashNodeInner + Unifiable> ProofCertificate<T> {
+    /// The certificate as a Metamath database proving it as theorem `name`
+    /// from `rules`, all of which are declared as axioms.
+    ///
+    /// Operators are written with their `symbols` entry, or `#<opcode>`,
+    /// and leaves with `leaf`; both must give printable ASCII names.
+    pub fn to_metamath(
+        &self,
+        name: &str,
+        rules: &[RewriteRule<T>],
+        store: &NodeStorage<T>,
+        symbols: &SymbolTable,
+        leaf: &dyn ExprPrinter<T>,
+    ) -> Result<String, MetamathError> {
+        verify_proof(self, rules, store).map_err(MetamathError::Invalid)?;
+
+        let mut database = Database {
+            symbols,
+            leaf,
+            constants: Vec::new(),
+            owners: HashMap::new(),
+            syntax: Vec::new(),
+            leaves: HashMap::new(),
+            operators: HashMap::new(),
+            variables: 3,
+        };
+        for rule in rules {
+            database.add_pattern(rule, &rule.pattern, store)?;
+            database.add_pattern(rule, &rule.replacement, store)?;
+        }
+        database.add_term(&self.initial_expr)?;
+        for step in &self.steps {
+            database.add_term(&step.new_expr)?;
+        }
+
+        let mut proof = Vec::new();
+        if self.steps.is_empty() {
+            database.syntax_proof(&self.initial_expr, &mut proof);
+            proof.push("eqid".to_string());
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            let rule = rules.iter().find(|rule| rule.name == step.rule_name).expect("checked certificates name known rules");
+            let mut step_proof = database.step_proof(index, step, rule, store)?;
+            if index > 0 {
+                let mut chained = Vec::new();
+                database.syntax_proof(&self.initial_expr, &mut chained);
+                database.syntax_proof(&step.old_expr, &mut chained);
+                database.syntax_proof(&step.new_expr, &mut chained);
+                chained.append(&mut proof);
+                chained.append(&mut step_proof);
+                chained.push("eqtr".to_string());
+                step_proof = chained;
+            }
+            proof = step_proof;
+        }
+
+        let mut labels = vec!["eqid", "eqsym", "eqsym.1", "eqtr", "eqtr.1", "eqtr.2", name].into_iter().map(String::from).collect::<Vec<_>>();
+        let mut lines = vec![
+            format!("$( Rewrite rules and a proof of {}, exported from corpus. $)", name),
+            String::new(),
+            format!("$c {} $.", RESERVED.join(" ")),
+        ];
+        if !database.constants.is_empty() {
+            lines.push(format!("$c {} $.", database.constants.join(" ")));
+        }
+        let variables: Vec<String> = (0..database.variables).map(|index| format!("?{}", index)).collect();
+        lines.push(format!("$v {} $.", variables.join(" ")));
+        for (index, variable) in variables.iter().enumerate() {
+            labels.push(format!("tv{}", index));
+            lines.push(format!("tv{} $f term {} $.", index, variable));
+        }
+        for (index, (symbol, arity)) in database.syntax.iter().enumerate() {
+            labels.push(format!("syn{}", index));
+            lines.push(match arity {
+                None => format!("syn{} $a term {} $.", index, symbol),
+                Some(arity) => format!("syn{} $a term ( {} ) $.", index, [symbol.as_str()].into_iter().chain(variables[..*arity].iter().map(String::as_str)).collect::<Vec<_>>().join(" ")),
+            });
+        }
+
+        lines.push(String::new());
+        lines.push(format!("eqid $a |- ?0 {} ?0 $.", EQUALS));
+        lines.push(format!("${{ eqsym.1 $e |- ?0 {0} ?1 $. eqsym $a |- ?1 {0} ?0 $. $}}", EQUALS));
+        lines.push(format!("${{ eqtr.1 $e |- ?0 {0} ?1 $. eqtr.2 $e |- ?1 {0} ?2 $. eqtr $a |- ?0 {0} ?2 $. $}}", EQUALS));
+        for (index, (symbol, arity)) in database.syntax.iter().enumerate() {
+            let arity = arity.unwrap_or(0);
+            for argument in 0..arity {
+                let before = std::iter::once(symbol.as_str()).chain(variables[..arity].iter().map(String::as_str));
+                let after = before.clone().enumerate().map(|(position, token)| if position == argument + 1 { variables[arity].as_str() } else { token });
+                let label = format!("syn{}.cong{}", index, argument);
+                lines.push(format!(
+                    "${{ {0}.1 $e |- {1} {2} {3} $. {0} $a |- ( {4} ) {2} ( {5} ) $. $}}",
+                    label,
+                    variables[argument],
+                    EQUALS,
+                    variables[arity],
+                    before.collect::<Vec<_>>().join(" "),
+                    after.collect::<Vec<_>>().join(" "),
+                ));
+                labels.push(format!("{}.1", label));
+                labels.push(label);
+            }
+        }
+
+        lines.push(String::new());
+        for rule in rules {
+            let label = rule_label(&rule.name)?;
+            lines.push(format!("{} $a |- {} {} {} $.", label, database.pattern_text(&rule.pattern, store), EQUALS, database.pattern_text(&rule.replacement, store)));
+            labels.push(label);
+        }
+
+        if !is_label(name) {
+            return Err(MetamathError::InvalidName(name.to_string()));
+        }
+        let mut seen = HashSet::new();
+        let clash = labels.iter().find(|label| !seen.insert(label.as_str()) || database.owners.contains_key(label.as_str()));
+        if let Some(label) = clash {
+            return Err(MetamathError::Clash(label.clone()));
+        }
+
+        lines.push(String::new());
+        lines.push(format!("{} $p |- {} {} {} $=", name, database.term_text(&self.initial_expr), EQUALS, database.term_text(&self.final_expr)));
+        let mut line = String::from(" ");
+        for token in proof {
+            if line.len() + token.len() > 78 {
+                lines.push(std::mem::replace(&mut line, String::from(" ")));
+            }
+            line.push(' ');
+            line.push_str(&token);
+        }
+        lines.push(line + " $.");
+        Ok(lines.join("\n") + "\n")
+    }
+}
+
+/// The syntax of a certificate and its rules, collected before writing.
+struct Database<'a, T: HashNodeInner> {
+    symbols: &'a SymbolTable,
+    leaf: &'a dyn ExprPrinter<T>,
+    /// Math symbols of leaves and operators, in the order they were seen.
+    constants: Vec<String>,
+    /// What each symbol names: a leaf hash or an opcode.
+    owners: HashMap<String, (bool, u64)>,
+    /// Syntax axioms `syn<i>` as symbol and arity, with no arity for leaves.
+    syntax: Vec<(String, Option<usize>)>,
+    leaves: HashMap<u64, usize>,
+    operators: HashMap<(u64, usize), usize>,
+    /// Number of variables `?0`, `?1`, … the axioms need.
+    variables: usize,
+}
+
+impl<T: HashNodeInner + Unifiable> Database<'_, T> {
+    fn declare(&mut self, symbol: &str, owner: (bool, u64)) -> Result<(), MetamathError> {
+        if symbol.is_empty() || !symbol.chars().all(|c| c.is_ascii_graphic() && c != '$') {
+            return Err(MetamathError::InvalidName(symbol.to_string()));
+        }
+        let variable = symbol.strip_prefix('?').is_some_and(|index| index.parse::<usize>().is_ok());
+        match self.owners.get(symbol) {
+            Some(existing) if *existing == owner => Ok(()),
+            Some(_) => Err(MetamathError::Clash(symbol.to_string())),
+            None if variable || RESERVED.contains(&symbol) => Err(MetamathError::Clash(symbol.to_string())),
+            None => {
+                self.owners.insert(symbol.to_string(), owner);
+                self.constants.push(symbol.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    fn add_term(&mut self, term: &HashNode<T>) -> Result<(), MetamathError> {
+        let Some((opcode, children)) = term.value.decompose() else {
+            if !self.leaves.contains_key(&term.hash()) {
+                let symbol = self.leaf.print(term.value.as_ref());
+                self.declare(&symbol, (true, term.hash()))?;
+                self.leaves.insert(term.hash(), self.syntax.len());
+                self.syntax.push((symbol, None));
+            }
+            return Ok(());
+        };
+        self.add_operator(opcode, children.len())?;
+        children.iter().try_for_each(|child| self.add_term(child))
+    }
+
+    fn add_operator(&mut self, opcode: u64, arity: usize) -> Result<(), MetamathError> {
+        if self.operators.contains_key(&(opcode, arity)) {
+            return Ok(());
+        }
+        let symbol = match self.symbols.get(opcode) {
+            Some(Notation::Infix { symbol, .. } | Notation::Prefix { symbol, .. } | Notation::Postfix { symbol, .. }) => symbol.clone(),
+            Some(Notation::Function(name)) => name.clone(),
+            None => format!("#{}", opcode),
+        };
+        self.declare(&symbol, (false, opcode))?;
+        self.operators.insert((opcode, arity), self.syntax.len());
+        self.syntax.push((symbol, Some(arity)));
+        self.variables = self.variables.max(arity + 1);
+        Ok(())
+    }
+
+    fn add_pattern(&mut self, rule: &RewriteRule<T>, pattern: &Pattern<T>, store: &NodeStorage<T>) -> Result<(), MetamathError> {
+        match pattern {
+            Pattern::Variable(index) => {
+                self.variables = self.variables.max(*index as usize + 1);
+                Ok(())
+            }
+            Pattern::Wildcard => Err(MetamathError::Wildcard { rule: rule.name.clone() }),
+            Pattern::Constant(value) => self.add_term(&HashNode::from_store(value.clone(), store)),
+            Pattern::Compound { opcode, args } => {
+                self.add_operator(*opcode, args.len())?;
+                args.iter().try_for_each(|arg| self.add_pattern(rule, arg, store))
+            }
+        }
+    }
+
+    fn term_text(&self, term: &HashNode<T>) -> String {
+        match term.value.decompose() {
+            None => self.syntax[self.leaves[&term.hash()]].0.clone(),
+            Some((opcode, children)) => {
+                let symbol = &self.syntax[self.operators[&(opcode, children.len())]].0;
+                let args: Vec<String> = children.iter().map(|child| self.term_text(child)).collect();
+                format!("( {} {} )", symbol, args.join(" "))
+            }
+        }
+    }
+
+    fn pattern_text(&self, pattern: &Pattern<T>, store: &NodeStorage<T>) -> String {
+        match pattern {
+            Pattern::Variable(index) => format!("?{}", index),
+            Pattern::Wildcard => unreachable!("rules with wildcards are rejected"),
+            Pattern::Constant(value) => self.term_text(&HashNode::from_store(value.clone(), store)),
+            Pattern::Compound { opcode, args } => {
+                let symbol = &self.syntax[self.operators[&(*opcode, args.len())]].0;
+                let args: Vec<String> = args.iter().map(|arg| self.pattern_text(arg, store)).collect();
+                format!("( {} {} )", symbol, args.join(" "))
+            }
+        }
+    }
+
+    /// Append the proof of `term $: term`, in reverse Polish notation.
+    fn syntax_proof(&self, term: &HashNode<T>, proof: &mut Vec<String>) {
+        match term.value.decompose() {
+            None => proof.push(format!("syn{}", self.leaves[&term.hash()])),
+            Some((opcode, children)) => {
+                for child in &children {
+                    self.syntax_proof(child, proof);
+                }
+                proof.push(format!("syn{}", self.operators[&(opcode, children.len())]));
+            }
+        }
+    }
+
+    /// The proof of `|- old == new` for the step at `index`.
+    fn step_proof(&self, index: usize, step: &ProofStep<T>, rule: &RewriteRule<T>, store: &NodeStorage<T>) -> Result<Vec<String>, MetamathError> {
+        let mut variables: Vec<u32> = rule.pattern.vars().into_iter().chain(rule.replacement.vars()).collect();
+        variables.sort_unstable();
+        variables.dedup();
+        let mut proof = Vec::new();
+        for variable in variables {
+            let term = step.substitution.get(variable).ok_or(MetamathError::Unbound { step: index, variable })?;
+            self.syntax_proof(term, &mut proof);
+        }
+        proof.push(rule_label(&rule.name)?);
+
+        if step.direction == RewriteDirection::Backward {
+            let instance = |pattern: &Pattern<T>| pattern.instantiate(&step.substitution, store).expect("bound rule variables instantiate");
+            let mut reversed = Vec::new();
+            self.syntax_proof(&instance(&rule.pattern), &mut reversed);
+            self.syntax_proof(&instance(&rule.replacement), &mut reversed);
+            reversed.append(&mut proof);
+            reversed.push("eqsym".to_string());
+            proof = reversed;
+        }
+
+        for depth in (0..step.position.len()).rev() {
+            let outer = subterm_at(&step.old_expr, &step.position[..depth]).expect("checked positions exist");
+            let replaced = subterm_at(&step.new_expr, &step.position[..=depth]).expect("checked positions exist");
+            let (opcode, children) = outer.value.decompose().expect("positions pass through operators");
+            let mut lifted = Vec::new();
+            for child in &children {
+                self.syntax_proof(child, &mut lifted);
+            }
+            self.syntax_proof(&replaced, &mut lifted);
+            lifted.append(&mut proof);
+            lifted.push(format!("syn{}.cong{}", self.operators[&(opcode, children.len())], step.position[depth]));
+            proof = lifted;
+        }
+        Ok(proof)
+    }
+}
+
+fn is_label(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn rule_label(name: &str) -> Result<String, MetamathError> {
+    let label = format!("ax-{}", name);
+    if is_label(&label) {
+        Ok(label)
+    } else {
+        Err(MetamathError::InvalidName(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::printing::Associativity;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, succ, Term, TermReflexivity};
+    use crate::proving::{replay_step, Prover, SizeCostEstimator};
+    use crate::rewriting::Substitution;
+
+    fn symbols() -> SymbolTable {
+        SymbolTable::new().infix("add", "+", 1, Associativity::Left).infix("eq", "=", 0, Associativity::None).function("succ", "S")
+    }
+
+    fn export(certificate: &ProofCertificate<Term>, rules: &[RewriteRule<Term>], store: &NodeStorage<Term>) -> Result<String, MetamathError> {
+        certificate.to_metamath("goal", rules, store, &symbols(), &|term: &Term| term.to_string())
+    }
+
+    /// Mandatory hypotheses, floating or not, and conclusion.
+    type Assertion = (Vec<(bool, Vec<String>)>, Vec<String>);
+
+    /// Checks the part of Metamath `to_metamath` writes (no `$d`, normal
+    /// proofs) and counts the theorems it proves.
+    fn check(database: &str) -> Result<usize, String> {
+        let mut tokens = database.split_whitespace();
+        let mut variables = HashSet::new();
+        let mut active: Vec<(String, bool, Vec<String>)> = Vec::new();
+        let mut scopes = Vec::new();
+        let mut assertions: HashMap<String, Assertion> = HashMap::new();
+        let mut label = None;
+        let mut proven = 0;
+        while let Some(token) = tokens.next() {
+            match token {
+                "$(" => tokens.by_ref().take_while(|token| *token != "$)").for_each(drop),
+                "$c" => tokens.by_ref().take_while(|token| *token != "$.").for_each(drop),
+                "$v" => variables.extend(tokens.by_ref().take_while(|token| *token != "$.").map(String::from)),
+                "${" => scopes.push(active.len()),
+                "$}" => active.truncate(scopes.pop().ok_or("unbalanced $}")?),
+                "$f" | "$e" | "$a" | "$p" => {
+                    let label: String = label.take().ok_or("unlabeled statement")?;
+                    let statement: Vec<String> = tokens.by_ref().take_while(|token| *token != "$." && *token != "$=").map(String::from).collect();
+                    if token == "$f" || token == "$e" {
+                        active.push((label, token == "$f", statement));
+                        continue;
+                    }
+                    let essential = active.iter().filter(|hypothesis| !hypothesis.1).flat_map(|hypothesis| &hypothesis.2);
+                    let used: HashSet<&String> = statement.iter().chain(essential).filter(|symbol| variables.contains(*symbol)).collect();
+                    let mandatory: Vec<(bool, Vec<String>)> = active
+                        .iter()
+                        .filter(|hypothesis| !hypothesis.1 || used.contains(&hypothesis.2[1]))
+                        .map(|hypothesis| (hypothesis.1, hypothesis.2.clone()))
+                        .collect();
+                    if token == "$p" {
+                        let mut stack: Vec<Vec<String>> = Vec::new();
+                        for step in tokens.by_ref().take_while(|token| *token != "$.") {
+                            if let Some(hypothesis) = active.iter().find(|hypothesis| hypothesis.0 == step) {
+                                stack.push(hypothesis.2.clone());
+                                continue;
+                            }
+                            let (hypotheses, conclusion) = assertions.get(step).ok_or(format!("unknown label {}", step))?;
+                            let base = stack.len().checked_sub(hypotheses.len()).ok_or("stack underflow")?;
+                            let mut substitution: HashMap<&str, &[String]> = HashMap::new();
+                            for ((floating, hypothesis), entry) in hypotheses.iter().zip(&stack[base..]) {
+                                if *floating {
+                                    if entry[0] != hypothesis[0] {
+                                        return Err(format!("{}: expected a {}", step, hypothesis[0]));
+                                    }
+                                    substitution.insert(&hypothesis[1], &entry[1..]);
+                                }
+                            }
+                            let apply = |expr: &[String]| -> Vec<String> {
+                                expr.iter().flat_map(|symbol| substitution.get(symbol.as_str()).map_or(vec![symbol.clone()], |term| term.to_vec())).collect()
+                            };
+                            for ((floating, hypothesis), entry) in hypotheses.iter().zip(&stack[base..]) {
+                                if !floating && apply(hypothesis) != *entry {
+                                    return Err(format!("{}: hypothesis {} does not hold", step, hypothesis.join(" ")));
+                                }
+                            }
+                            let result = apply(conclusion);
+                            stack.truncate(base);
+                            stack.push(result);
+                        }
+                        if stack != [statement.clone()] {
+                            return Err(format!("{} does not prove its statement", label));
+                        }
+                        proven += 1;
+                    }
+                    assertions.insert(label, (mandatory, statement));
+                }
+                other => label = Some(other.to_string()),
+            }
+        }
+        Ok(proven)
+    }
+
+    #[test]
+    fn test_exported_proofs_check() {
+        let store = NodeStorage::new();
+        let mut prover = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        let goal = eq(add(numeral(1, &store), numeral(1, &store), &store), numeral(2, &store), &store);
+        let certificate = prover.prove(&goal).unwrap().certificate();
+        let database = export(&certificate, &addition_rules(), &store).unwrap();
+        assert!(database.contains("ax-add_succ $a |- ( + ?0 ( S ?1 ) ) == ( S ( + ?0 ?1 ) ) $."));
+        assert!(database.contains("goal $p |- ( = ( + ( S 0 ) ( S 0 ) ) ( S ( S 0 ) ) ) == "));
+        assert_eq!(check(&database), Ok(1));
+
+        // Backward steps deep inside the expression, and the empty proof.
+        let mut rules = addition_rules();
+        rules[0] = RewriteRule::bidirectional("add_zero", rules[0].pattern.clone(), rules[0].replacement.clone());
+        let start = eq(numeral(1, &store), numeral(1, &store), &store);
+        let template = |direction, new_expr: &HashNode<Term>| ProofStep {
+            rule_name: "add_zero".to_string(),
+            direction,
+            position: vec![1, 0],
+            substitution: Substitution::new(),
+            old_expr: new_expr.clone(),
+            new_expr: new_expr.clone(),
+        };
+        let expanded = replay_step(&template(RewriteDirection::Backward, &start), &start, &rules, &store).unwrap();
+        assert_eq!(expanded.new_expr, eq(numeral(1, &store), succ(add(numeral(0, &store), numeral(0, &store), &store), &store), &store));
+        let reduced = replay_step(&template(RewriteDirection::Forward, &start), &expanded.new_expr, &rules, &store).unwrap();
+        let certificate = ProofCertificate::new(start.clone(), vec![expanded, reduced]);
+        assert_eq!(check(&export(&certificate, &rules, &store).unwrap()), Ok(1));
+        assert_eq!(check(&export(&ProofCertificate::new(start, Vec::new()), &rules, &store).unwrap()), Ok(1));
+
+        let tampered = database.replace("syn3.cong0 eqtr", "syn3.cong1 eqtr");
+        assert!(tampered != database && check(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_export_errors() {
+        let store = NodeStorage::new();
+        let start = eq(add(numeral(0, &store), numeral(0, &store), &store), numeral(0, &store), &store);
+        let step = ProofStep {
+            rule_name: "add_zero".to_string(),
+            direction: RewriteDirection::Forward,
+            position: vec![0],
+            substitution: Substitution::new(),
+            old_expr: start.clone(),
+            new_expr: start.clone(),
+        };
+        let mut step = replay_step(&step, &start, &addition_rules(), &store).unwrap();
+        let certificate = ProofCertificate::new(start.clone(), vec![step.clone()]);
+        assert!(export(&certificate, &addition_rules(), &store).is_ok());
+        assert!(matches!(export(&certificate, &[], &store), Err(MetamathError::Invalid(ProofCheckError::UnknownRule { .. }))));
+        assert_eq!(
+            certificate.to_metamath("goal", &addition_rules(), &store, &SymbolTable::new().function("succ", "S"), &|_: &Term| "S".to_string()),
+            Err(MetamathError::Clash("S".to_string()))
+        );
+        assert_eq!(
+            certificate.to_metamath("goal", &addition_rules(), &store, &symbols().function("add", "plus two"), &|term: &Term| term.to_string()),
+            Err(MetamathError::InvalidName("plus two".to_string()))
+        );
+        assert_eq!(
+            certificate.to_metamath("eqid", &addition_rules(), &store, &symbols(), &|term: &Term| term.to_string()),
+            Err(MetamathError::Clash("eqid".to_string()))
+        );
+
+        step.substitution = Substitution::new();
+        let unbound = ProofCertificate::new(start, vec![step]);
+        assert_eq!(export(&unbound, &addition_rules(), &store), Err(MetamathError::Unbound { step: 0, variable: 0 }));
+    }
+}
@@ -0,0 +1,212 @@
+//! Post-processing that shortens proofs found by the search.
+//!
+//! A* search often reaches the goal through detours: cycles that return to an
+//! earlier expression, steps after the goal was already reachable, or
+//! rewrites that later steps do not depend on. `minimize_proof` removes these
+//! by replaying the remaining steps and re-checking the goal, so the result is
+//! still a certificate that `verify_proof` accepts.
+
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use crate::proving::certificate::ProofCertificate;
+use crate::proving::checker::replay_step;
+use crate::proving::{GoalChecker, ProofStep};
+use crate::rewriting::{RewriteRule, Unifiable};
+use crate::truth::TruthValue;
+
+/// Shorten `certificate` while keeping it a valid proof.
+///
+/// Three reductions are applied until none makes progress:
+/// - the chain is cut at the first expression `goal_checker` accepts,
+/// - cycles (an expression reached twice) are removed,
+/// - single steps are dropped when the following steps can be replayed
+///   without them and still reach a goal.
+///
+/// If the certificate's own final expression is not accepted by
+/// `goal_checker`, it is returned unchanged.
+pub fn minimize_proof<T, V, G>(
+    certificate: &ProofCertificate<T>,
+    rules: &[RewriteRule<T>],
+    store: &NodeStorage<T>,
+    goal_checker: &G,
+) -> ProofCertificate<T>
+where
+    T: HashNodeInner + Unifiable,
+    V: TruthValue,
+    G: GoalChecker<T, V>,
+{
+    if goal_checker.check(&certificate.final_expr).is_none() {
+        return certificate.clone();
+    }
+
+    let initial = &certificate.initial_expr;
+    let mut steps = truncate_at_goal(initial, certificate.steps.clone(), goal_checker);
+
+    loop {
+        let shortened = remove_cycles(initial, &steps);
+        let shortened = drop_one_step(initial, &shortened, rules, store, goal_checker).unwrap_or(shortened);
+
+        if shortened.len() == steps.len() {
+            break;
+        }
+        steps = truncate_at_goal(initial, shortened, goal_checker);
+    }
+
+    ProofCertificate::new(initial.clone(), steps)
+}
+
+/// Keep only the steps up to the first expression accepted by the goal checker.
+fn truncate_at_goal<T, V, G>(initial: &HashNode<T>, mut steps: Vec<ProofStep<T>>, goal_checker: &G) -> Vec<ProofStep<T>>
+where
+    T: HashNodeInner,
+    V: TruthValue,
+    G: GoalChecker<T, V>,
+{
+    if goal_checker.check(initial).is_some() {
+        return Vec::new();
+    }
+
+    if let Some(index) = steps
+        .iter()
+        .position(|step| goal_checker.check(&step.new_expr).is_some())
+    {
+        steps.truncate(index + 1);
+    }
+
+    steps
+}
+
+/// Remove every segment of the chain that starts and ends at the same expression.
+fn remove_cycles<T: HashNodeInner>(initial: &HashNode<T>, steps: &[ProofStep<T>]) -> Vec<ProofStep<T>> {
+    let mut result: Vec<ProofStep<T>> = Vec::new();
+
+    for step in steps {
+        if step.new_expr == *initial {
+            result.clear();
+        } else if let Some(index) = result.iter().position(|prev| prev.new_expr == step.new_expr) {
+            result.truncate(index + 1);
+        } else {
+            result.push(step.clone());
+        }
+    }
+
+    result
+}
+
+/// Find the first step whose removal still yields a proof, and return the
+/// replayed chain without it.
+fn drop_one_step<T, V, G>(
+    initial: &HashNode<T>,
+    steps: &[ProofStep<T>],
+    rules: &[RewriteRule<T>],
+    store: &NodeStorage<T>,
+    goal_checker: &G,
+) -> Option<Vec<ProofStep<T>>>
+where
+    T: HashNodeInner + Unifiable,
+    V: TruthValue,
+    G: GoalChecker<T, V>,
+{
+    (0..steps.len()).find_map(|skip| {
+        let mut replayed = steps[..skip].to_vec();
+        let mut current = replayed.last().map_or(initial.clone(), |last| last.new_expr.clone());
+
+        for step in &steps[skip + 1..] {
+            let step = replay_step(step, &current, rules, store).ok()?;
+            current = step.new_expr.clone();
+            replayed.push(step);
+        }
+
+        goal_checker.check(&current).map(|_| replayed)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::Hashing;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::verify_proof;
+    use crate::rewriting::{Pattern, RewriteDirection, Substitution};
+
+    fn rules() -> Vec<RewriteRule<Term>> {
+        let mut rules = addition_rules();
+        rules.push(RewriteRule::new(
+            "eq_sym",
+            Pattern::compound(Hashing::opcode("eq"), vec![Pattern::var(0), Pattern::var(1)]),
+            Pattern::compound(Hashing::opcode("eq"), vec![Pattern::var(1), Pattern::var(0)]),
+            RewriteDirection::Forward,
+        ));
+        rules
+    }
+
+    /// Build a certificate by applying `(rule, position)` pairs in order.
+    fn chain(
+        initial: &HashNode<Term>,
+        applications: &[(&str, Vec<usize>)],
+        store: &NodeStorage<Term>,
+    ) -> ProofCertificate<Term> {
+        let rules = rules();
+        let mut current = initial.clone();
+        let mut steps = Vec::new();
+
+        for (rule, position) in applications {
+            let template = ProofStep {
+                rule_name: rule.to_string(),
+                direction: RewriteDirection::Forward,
+                position: position.clone(),
+                substitution: Substitution::new(),
+                old_expr: current.clone(),
+                new_expr: current.clone(),
+            };
+            let step = replay_step(&template, &current, &rules, store).expect("step should apply");
+            current = step.new_expr.clone();
+            steps.push(step);
+        }
+
+        ProofCertificate::new(initial.clone(), steps)
+    }
+
+    #[test]
+    fn test_cycle_removed() {
+        let store = NodeStorage::new();
+        let goal = eq(add(numeral(2, &store), numeral(1, &store), &store), numeral(3, &store), &store);
+        let cert = chain(
+            &goal,
+            &[("eq_sym", vec![]), ("eq_sym", vec![]), ("add_succ", vec![0]), ("add_zero", vec![0, 0])],
+            &store,
+        );
+
+        let minimized = minimize_proof(&cert, &rules(), &store, &TermReflexivity);
+        assert_eq!(minimized.rule_names(), vec!["add_succ", "add_zero"]);
+        assert_eq!(verify_proof(&minimized, &rules(), &store), Ok(()));
+    }
+
+    #[test]
+    fn test_steps_after_goal_removed() {
+        let store = NodeStorage::new();
+        let goal = eq(add(numeral(2, &store), numeral(1, &store), &store), numeral(3, &store), &store);
+        let cert = chain(
+            &goal,
+            &[("add_succ", vec![0]), ("add_zero", vec![0, 0]), ("eq_sym", vec![])],
+            &store,
+        );
+
+        let minimized = minimize_proof(&cert, &rules(), &store, &TermReflexivity);
+        assert_eq!(minimized.len(), 2);
+        assert_eq!(verify_proof(&minimized, &rules(), &store), Ok(()));
+    }
+
+    #[test]
+    fn test_needed_steps_kept() {
+        let store = NodeStorage::new();
+        let goal = eq(numeral(3, &store), add(numeral(2, &store), numeral(1, &store), &store), &store);
+        let cert = chain(
+            &goal,
+            &[("eq_sym", vec![]), ("add_succ", vec![0]), ("add_zero", vec![0, 0])],
+            &store,
+        );
+
+        let minimized = minimize_proof(&cert, &rules(), &store, &TermReflexivity);
+        assert_eq!(minimized.len(), 3);
+    }
+}
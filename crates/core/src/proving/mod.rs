@@ -3,13 +3,72 @@
 //! This module provides a generic prover that can work with any logical system
 //! by implementing the `CostEstimator` and `GoalChecker` traits.
 
+pub mod batch;
+pub mod builder;
+pub mod bidirectional;
+pub mod certificate;
+pub mod checker;
 pub mod context;
+pub mod derivation;
+pub mod distance;
+pub mod dot;
+pub mod exchange;
+pub mod failures;
+pub mod features;
+pub mod generalize;
+pub mod html;
+pub mod hypotheses;
+pub mod induction;
+pub mod interactive;
+pub mod json;
+pub mod layered;
+pub mod lemmas;
+pub mod metamath;
+pub mod minimize;
+pub mod observer;
+pub mod session;
+pub mod stats;
+pub mod steps;
+pub mod tactics;
+pub mod visited;
 
+#[cfg(test)]
+pub(crate) mod testing;
+
+use batch::SharedProgress;
+pub use bidirectional::BidirectionalProof;
+pub use builder::{ProverBuilder, SearchStrategy};
+pub use certificate::ProofCertificate;
+pub use checker::{replay_step, verify_proof, ProofCheckError};
+pub use derivation::{Derivation, DerivationLine, InferenceRule, NaturalDeduction};
+pub use distance::{SymbolMultisetDistanceEstimator, TreeEditDistanceEstimator};
+pub use dot::SearchGraph;
+pub use exchange::{CertificateError, CERTIFICATE_SCHEMA};
+pub use failures::FailureCache;
+pub use features::{StateFeatures, StructuralFeatures, WeightedCostEstimator};
+pub use generalize::{Generalization, GeneralizedProof, VariableFactory};
+pub use hypotheses::{AsRewriteRules, EquationRules};
+pub use induction::{InductionCase, InductionCertificate, InductionFailure, InductionProof, InductionSchema};
+pub use interactive::{Candidate, InteractiveSession};
+pub use json::{Json, JsonError, ToJson};
+pub use layered::{LayeredRewriter, StateExpander, SubtermRewritable};
+pub use lemmas::{EquationSides, Lemma, LemmaCache};
+pub use metamath::MetamathError;
+pub use minimize::minimize_proof;
+pub use observer::{Cancellation, NoopObserver, SearchControl, SearchObserver};
+pub use session::{SearchSession, SessionError, SessionStatus};
+pub use stats::{FailureReason, ProofFailure, SearchStats};
+pub use steps::StepList;
+pub use tactics::{ProofGoal, Tactic, TacticContext, TacticError};
+pub use visited::VisitedSet;
+
+use crate::base::clock::Stopwatch;
 use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
-use crate::rewriting::RewriteRule;
+use crate::rewriting::{Position, RewriteDirection, RewriteResult, RewriteRule, Substitution, Unifiable};
 use crate::{BinaryTruth, TruthValue};
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashSet};
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
 
 /// Trait for domain-specific cost estimation in proof search.
 ///
@@ -23,6 +82,17 @@ pub trait CostEstimator<T: HashNodeInner> {
     fn estimate_cost(&self, expr: &HashNode<T>) -> u64;
 }
 
+/// Trait for charging a cost to each rewrite step.
+///
+/// A `CostEstimator` only sees the expression a state ended up at. A
+/// `StepCost` sees how it got there, so reverse applications or rules that
+/// grow the expression can be penalized relative to simplifying ones. The
+/// costs of a state's steps are added to its estimated cost.
+pub trait StepCost<T: HashNodeInner> {
+    /// Cost of taking `step`.
+    fn step_cost(&self, step: &ProofStep<T>) -> u64;
+}
+
 /// Trait for domain-specific goal checking.
 ///
 /// Implementations define when a proof state is considered a "goal" or
@@ -62,10 +132,15 @@ impl<T: HashNodeInner> HashNode<T> {
 }
 
 /// A single transformation step in a proof.
-#[derive(Clone)]
 pub struct ProofStep<T: HashNodeInner> {
     /// Name of the rewrite rule that was applied.
     pub rule_name: String,
+    /// Direction the rule was applied in (`Forward` or `Backward`).
+    pub direction: RewriteDirection,
+    /// Path to the subterm of `old_expr` that was rewritten.
+    pub position: Position,
+    /// Bindings of the rule's variables at `position`.
+    pub substitution: Substitution<T>,
     /// The expression before the transformation.
     pub old_expr: HashNode<T>,
     /// The expression after the transformation.
@@ -77,8 +152,12 @@ pub struct ProofStep<T: HashNodeInner> {
 pub struct ProofState<T: HashNodeInner> {
     /// Expression
     pub expr: HashNode<T>,
-    /// Transformations applied to reach this state.
-    pub steps: Vec<ProofStep<T>>,
+    /// Transformations applied to reach this state, shared with the states
+    /// along the way.
+    pub steps: StepList<T>,
+    /// Total step cost of `steps`, kept so each successor adds the cost of
+    /// its own step rather than summing the whole path again.
+    pub path_cost: u64,
     /// Estimated cost to goal (for A* priority queue ordering).
     pub estimated_cost: u64,
 }
@@ -93,6 +172,45 @@ pub struct ProofResult<Node: HashNodeInner, T: TruthValue> {
     pub final_expr: HashNode<Node>,
     /// Result
     pub truth_result: T,
+    /// Statistics for the search that found this proof.
+    pub stats: SearchStats,
+}
+
+impl<Node: HashNodeInner, T: TruthValue> ProofResult<Node, T> {
+    /// Package the steps of this proof into a checkable certificate.
+    pub fn certificate(&self) -> ProofCertificate<Node> {
+        ProofCertificate::from_result(self)
+    }
+}
+
+impl<Node: HashNodeInner + Unifiable + Clone, T: TruthValue> ProofResult<Node, T> {
+    /// Re-execute this proof's steps on terms interned into `target_store`.
+    ///
+    /// The starting expression is transferred into `target_store` and every
+    /// step re-applied there with its recorded rule, direction and position.
+    /// Returns the re-derived final expression, which lives entirely in
+    /// `target_store`, or the first step that does not reproduce.
+    pub fn replay(
+        &self,
+        rules: &[RewriteRule<Node>],
+        target_store: &NodeStorage<Node>,
+    ) -> Result<HashNode<Node>, ProofCheckError> {
+        let certificate = self.certificate();
+        let mut current = certificate.initial_expr.transfer_to(target_store);
+
+        for (index, step) in certificate.steps.iter().enumerate() {
+            let replayed = checker::replay_step_at(index, step, &current, rules, target_store)?;
+            if replayed.new_expr != step.new_expr {
+                return Err(ProofCheckError::ResultMismatch { step: index });
+            }
+            current = replayed.new_expr;
+        }
+
+        if current != certificate.final_expr {
+            return Err(ProofCheckError::FinalMismatch);
+        }
+        Ok(current)
+    }
 }
 
 /// Generic prover using trait hooks for domain-specific behavior.
@@ -111,8 +229,16 @@ pub struct Prover<
     rules: Vec<RewriteRule<Node>>,
     store: NodeStorage<Node>,
     max_nodes: usize,
+    max_depth: Option<usize>,
     cost_estimator: C,
     goal_checker: G,
+    lemmas: Option<RwLock<LemmaCache<Node, T>>>,
+    failures: Option<RwLock<FailureCache<Node>>>,
+    strict: bool,
+    step_cost: Option<Box<dyn StepCost<Node>>>,
+    strategy: SearchStrategy,
+    timeout: Option<Duration>,
+    observer: Option<Mutex<Box<dyn SearchObserver<Node, T>>>>,
 
     _phantom: std::marker::PhantomData<T>,
 }
@@ -126,8 +252,16 @@ impl<Node: HashNodeInner + Clone, C: CostEstimator<Node>, T: TruthValue, G: Goal
             rules: Vec::new(),
             store: NodeStorage::new(),
             max_nodes,
+            max_depth: None,
             cost_estimator,
             goal_checker,
+            lemmas: None,
+            failures: None,
+            strict: false,
+            step_cost: None,
+            strategy: SearchStrategy::AStar,
+            timeout: None,
+            observer: None,
 
             _phantom: std::marker::PhantomData,
         }
@@ -138,69 +272,353 @@ impl<Node: HashNodeInner + Clone, C: CostEstimator<Node>, T: TruthValue, G: Goal
         self.rules.push(rule);
     }
 
+    /// Limit proofs to at most `max_depth` steps.
+    ///
+    /// States at the limit are still checked against the goal but are not
+    /// expanded, so long chains of useless rewrites cannot consume the whole
+    /// node budget.
+    pub fn set_max_depth(&mut self, max_depth: Option<usize>) {
+        self.max_depth = max_depth;
+    }
+
+    /// Compare visited states structurally instead of by hash alone.
+    ///
+    /// Strict mode guards against hash collisions pruning distinct states, at
+    /// the cost of keeping every visited expression alive.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Charge each step of a state's path in addition to its estimated cost.
+    pub fn set_step_cost(&mut self, step_cost: Box<dyn StepCost<Node>>) {
+        self.step_cost = Some(step_cost);
+    }
+
+    /// Choose how states are prioritized.
+    pub fn set_strategy(&mut self, strategy: SearchStrategy) {
+        self.strategy = strategy;
+    }
+
+    /// Give up on a search once it has run for `timeout`.
+    ///
+    /// Resumable sessions count the time of every `step` call towards it.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Observer used by `prove` and `prove_all`.
+    pub fn set_observer(&mut self, observer: Box<dyn SearchObserver<Node, T>>) {
+        self.observer = Some(Mutex::new(observer));
+    }
+
+    /// Attach a lemma cache that persists across `prove` calls.
+    ///
+    /// Statements already in the cache are answered without searching, and
+    /// proven equations are available to later searches as rewrite rules.
+    pub fn set_lemma_cache(&mut self, cache: LemmaCache<Node, T>) {
+        self.lemmas = Some(RwLock::new(cache));
+    }
+
+    /// Detach and return the lemma cache, if any.
+    pub fn take_lemma_cache(&mut self) -> Option<LemmaCache<Node, T>> {
+        self.lemmas.take().map(|cache| cache.into_inner().unwrap())
+    }
+
+    /// Number of lemmas currently cached (zero when no cache is attached).
+    pub fn lemma_count(&self) -> usize {
+        self.lemmas
+            .as_ref()
+            .map_or(0, |cache| cache.read().unwrap().len())
+    }
+
     /// Attempt to prove a statement by rewriting it until a goal is reached.
     ///
-    /// Uses A* search to explore possible rewrites. Returns `Some(ProofResult)`
-    /// if a proof is found within `max_nodes` states, otherwise `None`.
-    pub fn prove(&self, initial_expr: &HashNode<Node>) -> Option<ProofResult<Node, T>> {
-        let mut heap = BinaryHeap::new();
-        let mut visited = HashSet::new();
-        let mut nodes_explored = 0usize;
-
-        let initial_cost = self.cost_estimator.estimate_cost(initial_expr);
-        let initial_state = ProofState {
-            expr: initial_expr.clone(),
-            steps: Vec::new(),
-            estimated_cost: initial_cost,
+    /// Uses A* search to explore possible rewrites. Returns the proof if one
+    /// is found within `max_nodes` states; otherwise a `ProofFailure` saying
+    /// why the search stopped and holding the closest state it reached.
+    pub fn prove(&self, initial_expr: &HashNode<Node>) -> Result<ProofResult<Node, T>, ProofFailure<Node>> {
+        self.with_observer(|observer| self.prove_with_observer(initial_expr, observer))
+    }
+
+    /// Run `f` with the observer from `set_observer`, or a `NoopObserver`.
+    pub(crate) fn with_observer<R>(&self, f: impl FnOnce(&mut dyn SearchObserver<Node, T>) -> R) -> R {
+        match &self.observer {
+            Some(observer) => f(observer.lock().unwrap().as_mut()),
+            None => f(&mut NoopObserver),
+        }
+    }
+
+    /// Like `prove`, but reports search events to `observer`, which may also
+    /// abort the search.
+    pub fn prove_with_observer(
+        &self,
+        initial_expr: &HashNode<Node>,
+        observer: &mut dyn SearchObserver<Node, T>,
+    ) -> Result<ProofResult<Node, T>, ProofFailure<Node>> {
+        self.prove_shared(initial_expr, None, observer, None)
+    }
+
+    /// Like `prove`, but each state is also expanded by `expander`, e.g. a
+    /// `LayeredRewriter` applying rules to the terms inside the statement.
+    pub fn prove_with_expander(
+        &self,
+        initial_expr: &HashNode<Node>,
+        expander: &dyn StateExpander<Node>,
+    ) -> Result<ProofResult<Node, T>, ProofFailure<Node>> {
+        self.with_observer(|observer| self.prove_shared(initial_expr, Some(expander), observer, None))
+    }
+
+    /// `prove_with_expander` reporting search events to `observer` in place
+    /// of the one from `set_observer`.
+    pub fn prove_with_expander_and_observer(
+        &self,
+        initial_expr: &HashNode<Node>,
+        expander: &dyn StateExpander<Node>,
+        observer: &mut dyn SearchObserver<Node, T>,
+    ) -> Result<ProofResult<Node, T>, ProofFailure<Node>> {
+        self.prove_shared(initial_expr, Some(expander), observer, None)
+    }
+
+    /// Prove `initial_expr`, consulting and extending the lemma cache and,
+    /// when proving a batch, the progress shared between its goals.
+    fn prove_shared(
+        &self,
+        initial_expr: &HashNode<Node>,
+        expander: Option<&dyn StateExpander<Node>>,
+        observer: &mut dyn SearchObserver<Node, T>,
+        shared: Option<&mut SharedProgress<Node, T>>,
+    ) -> Result<ProofResult<Node, T>, ProofFailure<Node>> {
+        let Some(lemmas) = &self.lemmas else {
+            if let Some(failure) = self.cached_failure(initial_expr, self.max_nodes, 0) {
+                return Err(failure);
+            }
+            return self.search(initial_expr, &[], expander, observer, shared).inspect_err(|failure| {
+                self.record_failure(initial_expr, self.max_nodes, 0, failure.reason);
+            });
+        };
+
+        let (lemma_rules, lemma_count) = {
+            let cache = lemmas.read().unwrap();
+            if let Some(lemma) = cache.lookup(initial_expr)
+                && lemma.certificate.initial_expr == *initial_expr
+            {
+                return Ok(ProofResult {
+                    steps: lemma.certificate.steps.clone(),
+                    nodes_explored: 0,
+                    final_expr: lemma.certificate.final_expr.clone(),
+                    truth_result: lemma.truth.clone(),
+                    stats: SearchStats::new(),
+                });
+            }
+            (cache.lemma_rules(), cache.len())
+        };
+        if let Some(failure) = self.cached_failure(initial_expr, self.max_nodes, lemma_count) {
+            return Err(failure);
+        }
+
+        let result = self
+            .search(initial_expr, &lemma_rules, expander, observer, shared)
+            .inspect_err(|failure| self.record_failure(initial_expr, self.max_nodes, lemma_count, failure.reason))?;
+        lemmas.write().unwrap().insert(initial_expr, &result);
+        Ok(result)
+    }
+
+    /// Cost of taking `step`, or zero when no `StepCost` is set or the
+    /// strategy ignores path costs.
+    fn cost_of_step(&self, step: &ProofStep<Node>) -> u64 {
+        if self.strategy == SearchStrategy::BestFirst {
+            return 0;
+        }
+        self.step_cost.as_ref().map_or(0, |cost| cost.step_cost(step))
+    }
+
+    /// Start a resumable search for `initial_expr`.
+    ///
+    /// The session only uses the prover's own rules; cached lemmas are not
+    /// consulted.
+    pub fn start_session(&self, initial_expr: &HashNode<Node>) -> SearchSession<Node> {
+        SearchSession::new(initial_expr, self.cost_estimator.estimate_cost(initial_expr), self.strict)
+    }
+
+    /// Run the A* search using the prover's rules plus `extra_rules`, and
+    /// `expander` if given.
+    fn search(
+        &self,
+        initial_expr: &HashNode<Node>,
+        extra_rules: &[RewriteRule<Node>],
+        expander: Option<&dyn StateExpander<Node>>,
+        observer: &mut dyn SearchObserver<Node, T>,
+        mut shared: Option<&mut SharedProgress<Node, T>>,
+    ) -> Result<ProofResult<Node, T>, ProofFailure<Node>> {
+        let mut session = self.start_session(initial_expr);
+        let status = self.advance(&mut session, usize::MAX, extra_rules, expander, observer, shared.as_deref());
+        if let Some(shared) = shared.as_mut() {
+            // Lemma rules grow between goals, so a dead end under today's
+            // rules may not be one tomorrow.
+            let exhaustive = self.max_depth.is_none() && self.lemmas.is_none();
+            shared.record(&session, &status, exhaustive);
+        }
+
+        let reason = match status {
+            SessionStatus::Proved(result) => return Ok(result),
+            SessionStatus::Exhausted => FailureReason::Exhausted,
+            SessionStatus::Aborted => FailureReason::Aborted,
+            SessionStatus::TimedOut => FailureReason::TimedOut,
+            SessionStatus::InProgress | SessionStatus::NodeLimitReached => FailureReason::NodeLimitReached,
         };
 
-        heap.push(initial_state);
+        Err(ProofFailure {
+            reason,
+            nodes_explored: session.nodes_explored,
+            stats: Box::new(session.stats),
+            closest: session.closest,
+        })
+    }
 
-        while let Some(state) = heap.pop() {
-            nodes_explored += 1;
+    /// Explore up to `budget` states of `session`.
+    pub(crate) fn advance(
+        &self,
+        session: &mut SearchSession<Node>,
+        budget: usize,
+        extra_rules: &[RewriteRule<Node>],
+        expander: Option<&dyn StateExpander<Node>>,
+        observer: &mut dyn SearchObserver<Node, T>,
+        shared: Option<&SharedProgress<Node, T>>,
+    ) -> SessionStatus<Node, T> {
+        let started = Stopwatch::start();
+        let successors = |expr: &HashNode<Node>| {
+            self.rules
+                .iter()
+                .chain(extra_rules)
+                .flat_map(|rule| rule.rewrites_at_all_positions(expr, RewriteDirection::Forward, &self.store))
+                .chain(expander.into_iter().flat_map(|expander| expander.expand(expr)))
+                .collect()
+        };
+        let status = self.advance_inner(session, budget, &successors, observer, shared, started);
+        session.stats.elapsed += started.elapsed();
 
-            if nodes_explored > self.max_nodes {
-                return None;
+        match status {
+            SessionStatus::Proved(mut result) => {
+                result.stats = session.stats.clone();
+                SessionStatus::Proved(result)
+            }
+            status => status,
+        }
+    }
+
+    fn advance_inner(
+        &self,
+        session: &mut SearchSession<Node>,
+        budget: usize,
+        successors: &dyn Fn(&HashNode<Node>) -> Vec<RewriteResult<Node>>,
+        observer: &mut dyn SearchObserver<Node, T>,
+        shared: Option<&SharedProgress<Node, T>>,
+        started: Stopwatch,
+    ) -> SessionStatus<Node, T> {
+        for _ in 0..budget {
+            if session.nodes_explored >= self.max_nodes {
+                return SessionStatus::NodeLimitReached;
+            }
+            if self
+                .timeout
+                .is_some_and(|timeout| session.stats.elapsed + started.elapsed() >= timeout)
+            {
+                return SessionStatus::TimedOut;
             }
 
-            if let Some(truth) = self.goal_checker.check(&state.expr) {
-                return Some(ProofResult {
-                    steps: state.steps,
-                    nodes_explored,
+            let Some(state) = session.heap.pop() else {
+                return SessionStatus::Exhausted;
+            };
+            session.nodes_explored += 1;
+            session.stats.record_state(state.steps.len());
+            if session
+                .closest
+                .as_ref()
+                .is_none_or(|closest| state.estimated_cost < closest.estimated_cost)
+            {
+                session.closest = Some(state.clone());
+            }
+
+            let goal = self.goal_checker.check(&state.expr);
+            observer.on_goal_checked(&state.expr, goal.as_ref());
+
+            if let Some(truth) = goal {
+                return SessionStatus::Proved(ProofResult {
+                    steps: state.steps.to_vec(),
+                    nodes_explored: session.nodes_explored,
                     final_expr: state.expr,
                     truth_result: truth,
+                    // Filled in by `advance` once the elapsed time is known.
+                    stats: SearchStats::new(),
                 });
             }
 
-            let key = state.expr.hash();
-            if visited.contains(&key) {
+            if let Some(shared) = shared {
+                if let Some(result) = shared.complete(&state, session.nodes_explored, self.max_depth) {
+                    return SessionStatus::Proved(result);
+                }
+                if shared.is_dead(&state.expr) {
+                    session.stats.record_duplicate();
+                    continue;
+                }
+            }
+
+            if self.max_depth.is_some_and(|max_depth| state.steps.len() >= max_depth) {
                 continue;
             }
-            visited.insert(key);
-
-            for rule in self.rules.iter() {
-                for successor in state
-                    .expr
-                    .get_all_rewrites(&self.store, &|node| rule.apply(node, &self.store))
-                {
-                    heap.push(ProofState {
-                        expr: successor.clone(),
-                        steps: {
-                            let mut new_steps = state.steps.clone();
-                            new_steps.push(ProofStep {
-                                rule_name: rule.name.clone(),
-                                old_expr: state.expr.clone(),
-                                new_expr: successor.clone(),
-                            });
-                            new_steps
-                        },
-                        estimated_cost: self.cost_estimator.estimate_cost(&successor),
-                    });
-                }
+
+            // Under a depth limit, a state reached again by a shorter route
+            // has more steps left to spend, so it is expanded again.
+            let shorter = self.max_depth.is_some() && session.record_depth(&state.expr, state.steps.len());
+            if !session.visited.insert(&state.expr) && !shorter {
+                session.stats.record_duplicate();
+                continue;
+            }
+
+            if observer.on_state_expanded(&state, session.nodes_explored) == SearchControl::Abort {
+                return SessionStatus::Aborted;
+            }
+
+            for rewrite in successors(&state.expr) {
+                let step = ProofStep {
+                    rule_name: rewrite.rule_name,
+                    direction: rewrite.direction,
+                    position: rewrite.position,
+                    substitution: rewrite.substitution,
+                    old_expr: state.expr.clone(),
+                    new_expr: rewrite.term.clone(),
+                };
+                observer.on_rule_applied(&step);
+                session.stats.record_rule(&step.rule_name);
+
+                let path_cost = state.path_cost + self.cost_of_step(&step);
+                let steps = state.steps.pushed(step);
+
+                let estimated_cost = self.cost_estimator.estimate_cost(&rewrite.term) + path_cost;
+                session.heap.push(ProofState {
+                    expr: rewrite.term,
+                    steps,
+                    path_cost,
+                    estimated_cost,
+                });
             }
+            session.stats.record_frontier(session.heap.len());
         }
 
-        None
+        SessionStatus::InProgress
+    }
+}
+
+impl<T: HashNodeInner> Clone for ProofStep<T> {
+    fn clone(&self) -> Self {
+        Self {
+            rule_name: self.rule_name.clone(),
+            direction: self.direction,
+            position: self.position.clone(),
+            substitution: self.substitution.clone(),
+            old_expr: self.old_expr.clone(),
+            new_expr: self.new_expr.clone(),
+        }
     }
 }
 
@@ -232,7 +650,9 @@ impl<T: HashNodeInner> Ord for ProofState<T> {
 /// Default cost estimator: based on expression size.
 ///
 /// Lower cost = smaller expression. This encourages exploring smaller
-/// expressions first as they likely indicate simpler forms.
+/// expressions first as they likely indicate simpler forms. For equational
+/// domains prefer `TreeEditDistanceEstimator` or
+/// `SymbolMultisetDistanceEstimator`, which measure how far apart the sides are.
 pub struct SizeCostEstimator;
 
 impl<T: HashNodeInner> CostEstimator<T> for SizeCostEstimator {
@@ -241,6 +661,48 @@ impl<T: HashNodeInner> CostEstimator<T> for SizeCostEstimator {
     }
 }
 
+/// Step cost charging the same amount for every step.
+///
+/// With a cost of 1 this turns the search into A* over proof length.
+pub struct UniformStepCost(pub u64);
+
+impl<T: HashNodeInner> StepCost<T> for UniformStepCost {
+    fn step_cost(&self, _step: &ProofStep<T>) -> u64 {
+        self.0
+    }
+}
+
+/// Step cost penalizing reverse applications and expanding rewrites.
+pub struct PenalizingStepCost {
+    /// Cost of every step.
+    pub base: u64,
+    /// Extra cost for steps applied `Backward`.
+    pub reverse_penalty: u64,
+    /// Extra cost per node a step adds to the expression.
+    pub growth_penalty: u64,
+}
+
+impl Default for PenalizingStepCost {
+    fn default() -> Self {
+        Self {
+            base: 1,
+            reverse_penalty: 2,
+            growth_penalty: 1,
+        }
+    }
+}
+
+impl<T: HashNodeInner> StepCost<T> for PenalizingStepCost {
+    fn step_cost(&self, step: &ProofStep<T>) -> u64 {
+        let reverse = match step.direction {
+            RewriteDirection::Backward => self.reverse_penalty,
+            _ => 0,
+        };
+        let growth = step.new_expr.size().saturating_sub(step.old_expr.size());
+        self.base + reverse + growth * self.growth_penalty
+    }
+}
+
 /// Default goal checker: reflexive axiom check for equalities
 ///
 /// For equality expressions, checks if both sides have the same hash (i.e., they're equal),
@@ -282,6 +744,142 @@ mod tests {
         assert_eq!(cost, 1); // size of u64 is 1
     }
 
+    #[test]
+    fn test_penalizing_step_cost() {
+        use testing::{add, numeral, succ};
+
+        let store = NodeStorage::new();
+        let small = add(numeral(1, &store), numeral(0, &store), &store);
+        let large = succ(small.clone(), &store);
+        let step = |direction, old_expr: &HashNode<testing::Term>, new_expr: &HashNode<testing::Term>| ProofStep {
+            rule_name: "r".to_string(),
+            direction,
+            position: Vec::new(),
+            substitution: Substitution::new(),
+            old_expr: old_expr.clone(),
+            new_expr: new_expr.clone(),
+        };
+        let cost = PenalizingStepCost::default();
+
+        assert_eq!(cost.step_cost(&step(RewriteDirection::Forward, &large, &small)), 1);
+        assert_eq!(cost.step_cost(&step(RewriteDirection::Forward, &small, &large)), 2);
+        assert_eq!(cost.step_cost(&step(RewriteDirection::Backward, &small, &large)), 4);
+    }
+
+    #[test]
+    fn test_step_cost_keeps_proofs() {
+        use testing::{add, addition_rules, eq, numeral, TermReflexivity};
+
+        let store = NodeStorage::new();
+        let goal = eq(add(numeral(2, &store), numeral(2, &store), &store), numeral(4, &store), &store);
+        let mut prover: Prover<_, _, BinaryTruth, _> = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover.set_step_cost(Box::new(UniformStepCost(1)));
+
+        let result = prover.prove(&goal).expect("goal should be provable");
+        assert_eq!(result.steps.len(), 3);
+    }
+
+    #[test]
+    fn test_path_cost_accumulates() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+
+        /// Costs every step 2 and counts how often it is asked.
+        struct Counted(Arc<AtomicUsize>);
+
+        impl StepCost<Term> for Counted {
+            fn step_cost(&self, _step: &ProofStep<Term>) -> u64 {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                2
+            }
+        }
+
+        let store = NodeStorage::new();
+        let goal = eq(add(numeral(3, &store), numeral(3, &store), &store), numeral(7, &store), &store);
+        let mut prover: Prover<_, _, BinaryTruth, _> = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        let calls = Arc::new(AtomicUsize::new(0));
+        prover.set_step_cost(Box::new(Counted(calls.clone())));
+
+        let failure = prover.prove(&goal).err().expect("3 + 3 is not 7");
+        let closest = failure.closest.expect("states were explored");
+        assert_eq!(closest.path_cost, 2 * closest.steps.len() as u64);
+        // One call per successor, however deep it is.
+        let successors: usize = failure.stats.rule_applications.values().sum();
+        assert_eq!(calls.load(Ordering::Relaxed), successors);
+    }
+
+    #[test]
+    fn test_max_depth_limits_proofs() {
+        use testing::{add, addition_rules, eq, numeral, TermReflexivity};
+
+        let store = NodeStorage::new();
+        // 2 + 2 = 4 takes three steps: add_succ twice, then add_zero.
+        let goal = eq(add(numeral(2, &store), numeral(2, &store), &store), numeral(4, &store), &store);
+        let mut prover: Prover<_, _, BinaryTruth, _> = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+
+        prover.set_max_depth(Some(2));
+        let failure = prover.prove(&goal).err().expect("proof is too deep");
+        assert_eq!(failure.reason, FailureReason::Exhausted);
+        assert_eq!(failure.stats.max_depth(), 2);
+
+        prover.set_max_depth(Some(3));
+        assert!(prover.prove(&goal).is_ok());
+    }
+
+    #[test]
+    fn test_max_depth_reexpands_shorter_routes() {
+        use testing::{detour_rules, eq, TermReflexivity};
+
+        let store = NodeStorage::new();
+        let var = |index| HashNode::from_store(testing::Term::Var(index), &store);
+        let goal = eq(var(0), var(9), &store);
+        let mut prover: Prover<_, _, BinaryTruth, _> = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in detour_rules(&store) {
+            prover.add_rule(rule);
+        }
+
+        assert_eq!(prover.prove(&goal).expect("goal should be provable").steps.len(), 5);
+        prover.set_max_depth(Some(4));
+        assert_eq!(prover.prove(&goal).expect("the detour is short enough").steps.len(), 4);
+    }
+
+    #[test]
+    fn test_replay_onto_another_store() {
+        use std::sync::Arc;
+        use testing::{add, addition_rules, eq, numeral, TermReflexivity};
+
+        let scratch = NodeStorage::new();
+        let goal = eq(add(numeral(2, &scratch), numeral(1, &scratch), &scratch), numeral(3, &scratch), &scratch);
+        let mut prover: Prover<_, _, BinaryTruth, _> = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        let result = prover.prove(&goal).expect("goal should be provable");
+
+        let target = NodeStorage::new();
+        let final_expr = result.replay(&addition_rules(), &target).expect("proof should replay");
+        assert!(final_expr == result.final_expr);
+
+        let interned = target.get(final_expr.hash()).expect("final expression is in the target store");
+        assert!(Arc::ptr_eq(&interned.value, &final_expr.value));
+        assert!(scratch.get(final_expr.hash()).is_none_or(|node| !Arc::ptr_eq(&node.value, &final_expr.value)));
+
+        assert!(matches!(
+            result.replay(&addition_rules()[..1], &target),
+            Err(ProofCheckError::UnknownRule { .. })
+        ));
+    }
+
     #[test]
     fn test_reflexive_goal_checker() {
         let checker = ReflexiveGoalChecker::new();
@@ -0,0 +1,144 @@
+//! Hooks for watching (and stopping) a proof search while it runs.
+//!
+//! A `SearchObserver` is called by `Prover::prove_with_observer` as states are
+//! popped, checked, and expanded. Every method has a no-op default, so
+//! observers only implement the events they care about.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::proving::{ProofState, ProofStep};
+use crate::truth::TruthValue;
+
+/// Whether the search should keep going after an observer callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchControl {
+    Continue,
+    Abort,
+}
+
+/// Receives events from a running proof search.
+pub trait SearchObserver<Node: HashNodeInner, T: TruthValue> {
+    /// Called before the successors of `state` are generated.
+    ///
+    /// `nodes_explored` counts states popped so far, including this one.
+    /// Returning `SearchControl::Abort` ends the search without a result.
+    fn on_state_expanded(&mut self, _state: &ProofState<Node>, _nodes_explored: usize) -> SearchControl {
+        SearchControl::Continue
+    }
+
+    /// Called after the goal checker has examined `expr`.
+    fn on_goal_checked(&mut self, _expr: &HashNode<Node>, _result: Option<&T>) {}
+
+    /// Called for every successor produced by applying a rule.
+    fn on_rule_applied(&mut self, _step: &ProofStep<Node>) {}
+}
+
+/// Observer that ignores every event.
+pub struct NoopObserver;
+
+impl<Node: HashNodeInner, T: TruthValue> SearchObserver<Node, T> for NoopObserver {}
+
+/// Observer aborting the search once `cancel` is called on any of its
+/// clones, from another thread or from a callback between the steps of a
+/// `SearchSession`. This is the only way to stop a search on targets without
+/// a clock.
+#[derive(Debug, Clone, Default)]
+pub struct Cancellation(Arc<AtomicBool>);
+
+impl Cancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl<Node: HashNodeInner, T: TruthValue> SearchObserver<Node, T> for Cancellation {
+    fn on_state_expanded(&mut self, _state: &ProofState<Node>, _nodes_explored: usize) -> SearchControl {
+        if self.is_cancelled() { SearchControl::Abort } else { SearchControl::Continue }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::{Prover, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    #[derive(Default)]
+    struct Counter {
+        expanded: usize,
+        checked: usize,
+        applied: usize,
+        abort_after: Option<usize>,
+    }
+
+    impl SearchObserver<Term, BinaryTruth> for Counter {
+        fn on_state_expanded(&mut self, _state: &ProofState<Term>, nodes_explored: usize) -> SearchControl {
+            self.expanded += 1;
+            match self.abort_after {
+                Some(limit) if nodes_explored >= limit => SearchControl::Abort,
+                _ => SearchControl::Continue,
+            }
+        }
+
+        fn on_goal_checked(&mut self, _expr: &HashNode<Term>, _result: Option<&BinaryTruth>) {
+            self.checked += 1;
+        }
+
+        fn on_rule_applied(&mut self, _step: &ProofStep<Term>) {
+            self.applied += 1;
+        }
+    }
+
+    fn prover() -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover
+    }
+
+    #[test]
+    fn test_observer_sees_events() {
+        let store = NodeStorage::new();
+        let goal = eq(add(numeral(2, &store), numeral(2, &store), &store), numeral(4, &store), &store);
+        let mut counter = Counter::default();
+
+        let result = prover().prove_with_observer(&goal, &mut counter).expect("goal should be provable");
+
+        assert_eq!(counter.checked, result.nodes_explored);
+        assert!(counter.expanded >= result.steps.len());
+        assert!(counter.applied >= result.steps.len());
+    }
+
+    #[test]
+    fn test_observer_can_abort() {
+        let store = NodeStorage::new();
+        let goal = eq(add(numeral(2, &store), numeral(2, &store), &store), numeral(4, &store), &store);
+        let mut counter = Counter {
+            abort_after: Some(1),
+            ..Counter::default()
+        };
+
+        assert!(prover().prove_with_observer(&goal, &mut counter).is_err());
+        assert_eq!(counter.expanded, 1);
+
+        let cancellation = Cancellation::new();
+        let mut observer = cancellation.clone();
+        assert!(prover().prove_with_observer(&goal, &mut observer).is_ok());
+        cancellation.cancel();
+        let failure = prover().prove_with_observer(&goal, &mut observer).err().map(|failure| failure.reason);
+        assert_eq!(failure, Some(crate::proving::FailureReason::Aborted));
+    }
+}
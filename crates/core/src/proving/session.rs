@@ -0,0 +1,639 @@
+//! Resumable proof search.
+//!
+//! A `SearchSession` holds everything the A* loop needs between iterations:
+//! the frontier heap, the visited set with the depths states were expanded
+//! at, the node counter, the statistics and the closest state. Sessions can be
+//! advanced a bounded number of states at a time, written to a text
+//! checkpoint, and restored later (possibly in another process) to continue
+//! exactly where they stopped.
+
+use crate::base::codec::AtomCodec;
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use crate::proving::{
+    CostEstimator, GoalChecker, NoopObserver, ProofResult, ProofState, ProofStep, Prover, SearchStats, StepList,
+    VisitedSet,
+};
+use crate::rewriting::{RewriteDirection, Substitution};
+use crate::truth::TruthValue;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+const HEADER: &str = "corpus-search-session 3";
+
+/// Search state that can be advanced incrementally and checkpointed.
+pub struct SearchSession<Node: HashNodeInner> {
+    pub(crate) initial_expr: HashNode<Node>,
+    pub(crate) heap: BinaryHeap<ProofState<Node>>,
+    pub(crate) visited: VisitedSet<Node>,
+    /// Fewest steps to each expanded state, kept under a depth limit only.
+    pub(crate) depths: HashMap<u64, usize>,
+    pub(crate) nodes_explored: usize,
+    pub(crate) stats: SearchStats,
+    pub(crate) closest: Option<ProofState<Node>>,
+}
+
+/// Where a session stands after a call to `step`.
+pub enum SessionStatus<Node: HashNodeInner, T: TruthValue> {
+    /// The step budget ran out; call `step` again to continue.
+    InProgress,
+    /// A goal state was reached.
+    Proved(ProofResult<Node, T>),
+    /// Every reachable state has been explored without reaching a goal.
+    Exhausted,
+    /// The prover's `max_nodes` limit has been reached.
+    NodeLimitReached,
+    /// An observer aborted the search.
+    Aborted,
+    /// The prover's timeout has passed.
+    TimedOut,
+}
+
+/// Errors reading a serialized session.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionError {
+    /// The input is not a session checkpoint or uses an unknown version.
+    BadHeader,
+    /// A line could not be parsed.
+    Malformed { line: usize, message: String },
+    /// A node could not be encoded or rebuilt by the domain.
+    InvalidNode { line: usize },
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::BadHeader => write!(f, "Not a search session checkpoint"),
+            SessionError::Malformed { line, message } => {
+                write!(f, "Malformed session at line {}: {}", line, message)
+            }
+            SessionError::InvalidNode { line } => write!(f, "Invalid node at line {}", line),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl<Node: HashNodeInner> SearchSession<Node> {
+    /// Create a session whose frontier contains only `initial_expr`.
+    ///
+    /// With `strict` set, visited states are compared structurally (see
+    /// `VisitedSet`).
+    pub fn new(initial_expr: &HashNode<Node>, initial_cost: u64, strict: bool) -> Self {
+        let mut heap = BinaryHeap::new();
+        heap.push(ProofState {
+            expr: initial_expr.clone(),
+            steps: StepList::new(),
+            path_cost: 0,
+            estimated_cost: initial_cost,
+        });
+
+        Self {
+            initial_expr: initial_expr.clone(),
+            heap,
+            visited: VisitedSet::new(strict),
+            depths: HashMap::new(),
+            nodes_explored: 0,
+            stats: SearchStats::new(),
+            closest: None,
+        }
+    }
+
+    /// The statement this session is trying to prove.
+    pub fn initial_expr(&self) -> &HashNode<Node> {
+        &self.initial_expr
+    }
+
+    /// Number of states popped from the frontier so far.
+    pub fn nodes_explored(&self) -> usize {
+        self.nodes_explored
+    }
+
+    /// Statistics accumulated by this session's `step` calls, including
+    /// those made before a checkpoint was written.
+    pub fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    /// The popped state with the lowest estimated cost so far.
+    pub fn closest(&self) -> Option<&ProofState<Node>> {
+        self.closest.as_ref()
+    }
+
+    /// Number of states waiting in the frontier.
+    pub fn frontier_len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Record that `expr` is expanded `depth` steps from the start, returning
+    /// `false` if it already was in as few steps.
+    pub(crate) fn record_depth(&mut self, expr: &HashNode<Node>, depth: usize) -> bool {
+        let shallowest = self.depths.entry(expr.hash()).or_insert(usize::MAX);
+        if *shallowest <= depth {
+            return false;
+        }
+        *shallowest = depth;
+        true
+    }
+
+    /// Explore up to `n_nodes` more states using `prover`'s rules, cost
+    /// estimator and goal checker.
+    pub fn step<C, T, G>(&mut self, prover: &Prover<Node, C, T, G>, n_nodes: usize) -> SessionStatus<Node, T>
+    where
+        Node: Clone,
+        C: CostEstimator<Node>,
+        T: TruthValue,
+        G: GoalChecker<Node, T>,
+    {
+        prover.advance(self, n_nodes, &[], None, &mut NoopObserver, None)
+    }
+
+    /// Write the session as a line-based text checkpoint.
+    ///
+    /// Every node reachable from the session is written once, children before
+    /// parents; leaves are encoded with `codec`.
+    pub fn serialize(&self, codec: &dyn AtomCodec<Node>) -> Result<String, SessionError> {
+        let mut writer = NodeTableWriter::new(codec);
+        let initial = writer.node_id(&self.initial_expr)?;
+
+        let mut body = Vec::new();
+        body.push(format!("explored {}", self.nodes_explored));
+        match &self.visited {
+            VisitedSet::Hashes(hashes) => body.push(format!(
+                "visited {}",
+                hashes.iter().map(u64::to_string).collect::<Vec<_>>().join(" ")
+            )),
+            VisitedSet::Nodes(buckets) => {
+                let mut ids = Vec::new();
+                for node in buckets.values().flatten() {
+                    ids.push(writer.node_id(node)?.to_string());
+                }
+                body.push(format!("visited-nodes {}", ids.join(" ")));
+            }
+        }
+        let mut depths: Vec<_> = self.depths.iter().collect();
+        depths.sort();
+        body.push(format!(
+            "depths {}",
+            depths.iter().map(|(hash, depth)| format!("{}:{}", hash, depth)).collect::<Vec<_>>().join(" ")
+        ));
+        body.push(format!(
+            "stats {} {} {}",
+            self.stats.max_heap_size,
+            self.stats.duplicate_hits,
+            self.stats.elapsed.as_nanos()
+        ));
+        body.push(format!(
+            "states-per-depth {}",
+            self.stats.states_per_depth.iter().map(usize::to_string).collect::<Vec<_>>().join(" ")
+        ));
+        for (rule, count) in &self.stats.rule_applications {
+            body.push(format!("rule {} {}", count, escape(rule)));
+        }
+        body.push(format!("initial {}", initial));
+
+        let closest = self.closest.iter().map(|state| ("closest", state));
+        for (kind, state) in closest.chain(self.heap.iter().map(|state| ("state", state))) {
+            body.push(format!(
+                "{} {} {} {} {}",
+                kind,
+                state.estimated_cost,
+                state.path_cost,
+                writer.node_id(&state.expr)?,
+                state.steps.len()
+            ));
+            for step in &state.steps {
+                body.push(writer.step_line(step)?);
+            }
+        }
+
+        let mut lines = vec![HEADER.to_string()];
+        lines.extend(writer.lines);
+        lines.extend(body);
+        Ok(lines.join("\n"))
+    }
+
+    /// Restore a session written by `serialize`, interning its nodes into `store`.
+    pub fn resume(
+        text: &str,
+        codec: &dyn AtomCodec<Node>,
+        store: &NodeStorage<Node>,
+    ) -> Result<Self, SessionError> {
+        let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+        if lines.next().map(|(_, line)| line) != Some(HEADER) {
+            return Err(SessionError::BadHeader);
+        }
+
+        let mut nodes: Vec<HashNode<Node>> = Vec::new();
+        let mut initial_expr = None;
+        let mut heap = BinaryHeap::new();
+        let mut visited = VisitedSet::new(false);
+        let mut depths = HashMap::new();
+        let mut nodes_explored = 0;
+        let mut stats = SearchStats::new();
+        let mut closest = None;
+        // The state whose steps are being read, the number of steps still to
+        // come, and whether it is the closest state rather than a frontier one.
+        let mut pending: Option<(ProofState<Node>, usize, bool)> = None;
+
+        for (number, line) in lines {
+            let malformed = |message: &str| SessionError::Malformed {
+                line: number,
+                message: message.to_string(),
+            };
+            let node = |text: &str| -> Result<HashNode<Node>, SessionError> {
+                let id: usize = text.parse().map_err(|_| malformed("bad node id"))?;
+                nodes.get(id).cloned().ok_or_else(|| malformed("unknown node id"))
+            };
+            let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+
+            match kind {
+                "atom" => {
+                    let atom = codec
+                        .decode_atom(&unescape(rest), store)
+                        .ok_or(SessionError::InvalidNode { line: number })?;
+                    nodes.push(atom);
+                }
+                "compound" => {
+                    let mut fields = rest.split_whitespace();
+                    let opcode: u64 = fields
+                        .next()
+                        .and_then(|f| f.parse().ok())
+                        .ok_or_else(|| malformed("bad opcode"))?;
+                    let children = fields.map(node).collect::<Result<Vec<_>, _>>()?;
+                    let compound = Node::construct_from_parts(opcode, children, store)
+                        .ok_or(SessionError::InvalidNode { line: number })?;
+                    nodes.push(compound);
+                }
+                "explored" => nodes_explored = rest.parse().map_err(|_| malformed("bad count"))?,
+                "visited" => {
+                    let VisitedSet::Hashes(hashes) = &mut visited else {
+                        return Err(malformed("hash entries in a strict session"));
+                    };
+                    for hash in rest.split_whitespace() {
+                        hashes.insert(hash.parse().map_err(|_| malformed("bad hash"))?);
+                    }
+                }
+                "visited-nodes" => {
+                    if !visited.is_empty() && !visited.is_strict() {
+                        return Err(malformed("node entries in a non-strict session"));
+                    }
+                    if !visited.is_strict() {
+                        visited = VisitedSet::new(true);
+                    }
+                    for id in rest.split_whitespace() {
+                        visited.insert(&node(id)?);
+                    }
+                }
+                "depths" => {
+                    for entry in rest.split_whitespace() {
+                        let (hash, depth) = entry.split_once(':').ok_or_else(|| malformed("bad depth entry"))?;
+                        depths.insert(
+                            hash.parse().map_err(|_| malformed("bad hash"))?,
+                            depth.parse().map_err(|_| malformed("bad depth"))?,
+                        );
+                    }
+                }
+                "stats" => {
+                    let fields: Vec<&str> = rest.split_whitespace().collect();
+                    let [max_heap_size, duplicate_hits, elapsed] = fields.as_slice() else {
+                        return Err(malformed("expected heap size, duplicates and elapsed time"));
+                    };
+                    stats.max_heap_size = max_heap_size.parse().map_err(|_| malformed("bad heap size"))?;
+                    stats.duplicate_hits = duplicate_hits.parse().map_err(|_| malformed("bad count"))?;
+                    stats.elapsed = Duration::from_nanos(elapsed.parse().map_err(|_| malformed("bad elapsed time"))?);
+                }
+                "states-per-depth" => {
+                    stats.states_per_depth = rest
+                        .split_whitespace()
+                        .map(|count| count.parse().map_err(|_| malformed("bad count")))
+                        .collect::<Result<_, _>>()?;
+                }
+                "rule" => {
+                    let (count, rule) = rest.split_once(' ').ok_or_else(|| malformed("expected count and rule name"))?;
+                    stats
+                        .rule_applications
+                        .insert(unescape(rule), count.parse().map_err(|_| malformed("bad count"))?);
+                }
+                "initial" => initial_expr = Some(node(rest)?),
+                "state" | "closest" => {
+                    if pending.as_ref().is_some_and(|(_, remaining, _)| *remaining > 0) {
+                        return Err(malformed("state is missing steps"));
+                    }
+                    match pending.take() {
+                        Some((state, _, true)) => closest = Some(state),
+                        Some((state, _, false)) => heap.push(state),
+                        None => {}
+                    }
+                    let fields: Vec<&str> = rest.split_whitespace().collect();
+                    let [cost, path_cost, expr, count] = fields.as_slice() else {
+                        return Err(malformed("expected costs, node and step count"));
+                    };
+                    let state = ProofState {
+                        expr: node(expr)?,
+                        steps: StepList::new(),
+                        path_cost: path_cost.parse().map_err(|_| malformed("bad path cost"))?,
+                        estimated_cost: cost.parse().map_err(|_| malformed("bad cost"))?,
+                    };
+                    pending = Some((state, count.parse().map_err(|_| malformed("bad step count"))?, kind == "closest"));
+                }
+                "step" => {
+                    let Some((state, remaining, _)) = pending.as_mut().filter(|(_, remaining, _)| *remaining > 0) else {
+                        return Err(malformed("step outside of a state"));
+                    };
+                    state.steps.push(parse_step(rest, &node).ok_or_else(|| malformed("bad step"))?);
+                    *remaining -= 1;
+                }
+                _ => return Err(malformed("unknown record")),
+            }
+        }
+
+        match pending {
+            Some((_, remaining, _)) if remaining > 0 => {
+                return Err(SessionError::Malformed {
+                    line: text.lines().count(),
+                    message: "state is missing steps".to_string(),
+                });
+            }
+            Some((state, _, true)) => closest = Some(state),
+            Some((state, _, false)) => heap.push(state),
+            None => {}
+        }
+
+        Ok(Self {
+            initial_expr: initial_expr.ok_or(SessionError::Malformed {
+                line: text.lines().count(),
+                message: "missing initial expression".to_string(),
+            })?,
+            heap,
+            visited,
+            depths,
+            nodes_explored,
+            stats,
+            closest,
+        })
+    }
+}
+
+/// Parse `<dir> <position> <substitution> <old> <new> <rule name>`.
+fn parse_step<Node: HashNodeInner>(
+    text: &str,
+    node: &dyn Fn(&str) -> Result<HashNode<Node>, SessionError>,
+) -> Option<ProofStep<Node>> {
+    let mut fields = text.splitn(6, ' ');
+    let direction = match fields.next()? {
+        "F" => RewriteDirection::Forward,
+        "B" => RewriteDirection::Backward,
+        _ => return None,
+    };
+    let position = match fields.next()? {
+        "-" => Vec::new(),
+        path => path.split('.').map(|i| i.parse().ok()).collect::<Option<Vec<usize>>>()?,
+    };
+
+    let mut substitution = Substitution::new();
+    let bindings = fields.next()?;
+    if bindings != "-" {
+        for binding in bindings.split(',') {
+            let (var, id) = binding.split_once('=')?;
+            substitution.bind(var.parse().ok()?, node(id).ok()?);
+        }
+    }
+
+    let old_expr = node(fields.next()?).ok()?;
+    let new_expr = node(fields.next()?).ok()?;
+    let rule_name = unescape(fields.next()?);
+
+    Some(ProofStep {
+        rule_name,
+        direction,
+        position,
+        substitution,
+        old_expr,
+        new_expr,
+    })
+}
+
+/// Assigns table ids to nodes and records their definitions in postorder.
+struct NodeTableWriter<'a, Node: HashNodeInner> {
+    codec: &'a dyn AtomCodec<Node>,
+    ids: HashMap<u64, usize>,
+    lines: Vec<String>,
+}
+
+impl<'a, Node: HashNodeInner> NodeTableWriter<'a, Node> {
+    fn new(codec: &'a dyn AtomCodec<Node>) -> Self {
+        Self {
+            codec,
+            ids: HashMap::new(),
+            lines: Vec::new(),
+        }
+    }
+
+    fn node_id(&mut self, node: &HashNode<Node>) -> Result<usize, SessionError> {
+        if let Some(&id) = self.ids.get(&node.hash()) {
+            return Ok(id);
+        }
+
+        let line = match node.value.decompose() {
+            Some((opcode, children)) => {
+                let ids = children
+                    .iter()
+                    .map(|child| self.node_id(child).map(|id| id.to_string()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                format!("compound {} {}", opcode, ids.join(" "))
+            }
+            None => {
+                let text = self
+                    .codec
+                    .encode_atom(&node.value)
+                    .ok_or(SessionError::InvalidNode { line: self.lines.len() + 2 })?;
+                format!("atom {}", escape(&text))
+            }
+        };
+
+        let id = self.lines.len();
+        self.lines.push(line);
+        self.ids.insert(node.hash(), id);
+        Ok(id)
+    }
+
+    fn step_line(&mut self, step: &ProofStep<Node>) -> Result<String, SessionError> {
+        let direction = match step.direction {
+            RewriteDirection::Backward => "B",
+            _ => "F",
+        };
+        let position = if step.position.is_empty() {
+            "-".to_string()
+        } else {
+            step.position.iter().map(usize::to_string).collect::<Vec<_>>().join(".")
+        };
+
+        let mut bindings: Vec<(u32, usize)> = Vec::new();
+        for (var, term) in step.substitution.iter() {
+            bindings.push((*var, self.node_id(term)?));
+        }
+        bindings.sort();
+        let bindings = if bindings.is_empty() {
+            "-".to_string()
+        } else {
+            bindings
+                .iter()
+                .map(|(var, id)| format!("{}={}", var, id))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        Ok(format!(
+            "step {} {} {} {} {} {}",
+            direction,
+            position,
+            bindings,
+            self.node_id(&step.old_expr)?,
+            self.node_id(&step.new_expr)?,
+            escape(&step.rule_name)
+        ))
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (c, c == '\\') {
+            (_, true) => match chars.next() {
+                Some('n') => result.push('\n'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            },
+            (c, false) => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::SizeCostEstimator;
+    use crate::BinaryTruth;
+
+    struct TermCodec;
+
+    impl AtomCodec<Term> for TermCodec {
+        fn encode_atom(&self, atom: &Term) -> Option<String> {
+            matches!(atom, Term::Zero).then(|| "0".to_string())
+        }
+
+        fn decode_atom(&self, text: &str, store: &NodeStorage<Term>) -> Option<HashNode<Term>> {
+            (text == "0").then(|| HashNode::from_store(Term::Zero, store))
+        }
+    }
+
+    fn prover() -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover
+    }
+
+    #[test]
+    fn test_step_in_slices() {
+        let store = NodeStorage::new();
+        let prover = prover();
+        let goal = eq(add(numeral(3, &store), numeral(3, &store), &store), numeral(6, &store), &store);
+        let mut session = prover.start_session(&goal);
+
+        assert!(matches!(session.step(&prover, 1), SessionStatus::InProgress));
+        assert_eq!(session.nodes_explored(), 1);
+
+        let mut status = session.step(&prover, 1);
+        while matches!(status, SessionStatus::InProgress) {
+            status = session.step(&prover, 1);
+        }
+        assert!(matches!(status, SessionStatus::Proved(_)));
+    }
+
+    #[test]
+    fn test_serialize_and_resume() {
+        let store = NodeStorage::new();
+        let prover = prover();
+        let goal = eq(add(numeral(3, &store), numeral(3, &store), &store), numeral(6, &store), &store);
+        let mut session = prover.start_session(&goal);
+        assert!(matches!(session.step(&prover, 2), SessionStatus::InProgress));
+
+        let text = session.serialize(&TermCodec).expect("session should serialize");
+        let other_store = NodeStorage::new();
+        let mut resumed = SearchSession::resume(&text, &TermCodec, &other_store).expect("session should resume");
+
+        assert_eq!(resumed.nodes_explored(), session.nodes_explored());
+        assert_eq!(resumed.frontier_len(), session.frontier_len());
+        assert!(resumed.initial_expr() == &goal);
+
+        let SessionStatus::Proved(result) = resumed.step(&prover, 100) else {
+            panic!("resumed session should finish the proof");
+        };
+        assert_eq!(crate::proving::verify_proof(&result.certificate(), &addition_rules(), &other_store), Ok(()));
+    }
+
+    #[test]
+    fn test_resumed_depth_limited_search_matches() {
+        let store = NodeStorage::new();
+        // 2 + 2 = 3 + 2 is false, so every state within the limit is explored,
+        // many of them again by routes no shorter than the first.
+        let goal = eq(
+            add(numeral(2, &store), numeral(2, &store), &store),
+            add(numeral(3, &store), numeral(2, &store), &store),
+            &store,
+        );
+        let mut prover = prover();
+        prover.set_max_depth(Some(6));
+        let uninterrupted = prover.prove(&goal).err().expect("2 + 2 is not 3 + 2");
+
+        let mut session = prover.start_session(&goal);
+        assert!(matches!(session.step(&prover, 10), SessionStatus::InProgress));
+        let text = session.serialize(&TermCodec).expect("session should serialize");
+        let mut resumed = SearchSession::resume(&text, &TermCodec, &store).expect("session should resume");
+
+        assert_eq!(resumed.depths, session.depths);
+        assert_eq!(resumed.stats().states_per_depth, session.stats().states_per_depth);
+        assert_eq!(resumed.stats().rule_applications, session.stats().rule_applications);
+        assert!(resumed.closest().map(|state| &state.expr) == session.closest().map(|state| &state.expr));
+
+        assert!(matches!(resumed.step(&prover, 1000), SessionStatus::Exhausted));
+        assert_eq!(resumed.nodes_explored(), uninterrupted.nodes_explored);
+        assert_eq!(resumed.stats().states_per_depth, uninterrupted.stats.states_per_depth);
+        assert_eq!(resumed.stats().duplicate_hits, uninterrupted.stats.duplicate_hits);
+    }
+
+    #[test]
+    fn test_strict_session_round_trip() {
+        let store = NodeStorage::new();
+        let mut prover = prover();
+        prover.set_strict(true);
+        let goal = eq(add(numeral(2, &store), numeral(2, &store), &store), numeral(4, &store), &store);
+        let mut session = prover.start_session(&goal);
+        assert!(matches!(session.step(&prover, 2), SessionStatus::InProgress));
+
+        let text = session.serialize(&TermCodec).expect("session should serialize");
+        let resumed = SearchSession::resume(&text, &TermCodec, &store).expect("session should resume");
+
+        assert!(resumed.visited.is_strict());
+        assert_eq!(resumed.visited.len(), session.visited.len());
+    }
+
+    #[test]
+    fn test_resume_rejects_garbage() {
+        let store: NodeStorage<Term> = NodeStorage::new();
+        assert!(matches!(
+            SearchSession::resume("not a session", &TermCodec, &store),
+            Err(SessionError::BadHeader)
+        ));
+    }
+}
@@ -0,0 +1,198 @@
+//! Statistics gathered while searching for a proof.
+//!
+//! Both successful (`ProofResult`) and failed (`ProofFailure`) searches carry a
+//! `SearchStats`, so node limits and cost estimators can be tuned from data
+//! rather than guesswork.
+
+use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::proving::{ProofState, ProofStep};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// Counters describing the shape and cost of a search.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchStats {
+    /// Largest number of states waiting in the frontier at once.
+    pub max_heap_size: usize,
+    /// States popped whose expression had already been expanded.
+    pub duplicate_hits: usize,
+    /// Number of states popped at each depth (proof length so far).
+    pub states_per_depth: Vec<usize>,
+    /// Number of successors produced by each rule, keyed by rule name.
+    pub rule_applications: BTreeMap<String, usize>,
+    /// Wall-clock time spent searching.
+    pub elapsed: Duration,
+}
+
+impl SearchStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that a state at `depth` was popped from the frontier.
+    pub fn record_state(&mut self, depth: usize) {
+        if self.states_per_depth.len() <= depth {
+            self.states_per_depth.resize(depth + 1, 0);
+        }
+        self.states_per_depth[depth] += 1;
+    }
+
+    /// Record a popped state that had already been expanded.
+    pub fn record_duplicate(&mut self) {
+        self.duplicate_hits += 1;
+    }
+
+    /// Record one successor produced by the rule named `rule_name`.
+    pub fn record_rule(&mut self, rule_name: &str) {
+        *self.rule_applications.entry(rule_name.to_string()).or_insert(0) += 1;
+    }
+
+    /// Record the current frontier size.
+    pub fn record_frontier(&mut self, size: usize) {
+        self.max_heap_size = self.max_heap_size.max(size);
+    }
+
+    /// Total number of successors produced across all rules.
+    pub fn total_rule_applications(&self) -> usize {
+        self.rule_applications.values().sum()
+    }
+
+    /// Deepest level at which a state was popped.
+    pub fn max_depth(&self) -> usize {
+        self.states_per_depth.len().saturating_sub(1)
+    }
+}
+
+/// Why a search ended without a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureReason {
+    /// The prover's `max_nodes` limit was reached.
+    NodeLimitReached,
+    /// Every reachable state was explored without reaching a goal.
+    Exhausted,
+    /// An observer aborted the search.
+    Aborted,
+    /// The prover's timeout passed.
+    TimedOut,
+    /// A `FailureCache` showed the search would fail, so it was not run.
+    Cached,
+}
+
+impl Display for FailureReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailureReason::NodeLimitReached => write!(f, "node limit reached"),
+            FailureReason::Exhausted => write!(f, "search space exhausted"),
+            FailureReason::Aborted => write!(f, "aborted"),
+            FailureReason::TimedOut => write!(f, "timed out"),
+            FailureReason::Cached => write!(f, "known to fail within this budget"),
+        }
+    }
+}
+
+/// Result of an unsuccessful proof search.
+pub struct ProofFailure<Node: HashNodeInner> {
+    /// Why the search stopped.
+    pub reason: FailureReason,
+    /// Number of states explored before stopping.
+    pub nodes_explored: usize,
+    /// Statistics for the search, boxed to keep `Result`s small.
+    pub stats: Box<SearchStats>,
+    /// The explored state with the lowest estimated cost, with the steps that
+    /// reached it. `None` only if no state was explored at all.
+    pub closest: Option<ProofState<Node>>,
+}
+
+impl<Node: HashNodeInner> ProofFailure<Node> {
+    /// The expression the search got closest to a goal with.
+    pub fn closest_expr(&self) -> Option<&HashNode<Node>> {
+        self.closest.as_ref().map(|state| &state.expr)
+    }
+
+    /// The steps leading to the closest expression.
+    pub fn closest_steps(&self) -> Vec<ProofStep<Node>> {
+        self.closest.as_ref().map_or_else(Vec::new, |state| state.steps.to_vec())
+    }
+}
+
+impl<Node: HashNodeInner> std::fmt::Debug for ProofFailure<Node> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProofFailure")
+            .field("reason", &self.reason)
+            .field("nodes_explored", &self.nodes_explored)
+            .field("stats", &self.stats)
+            .field("closest_cost", &self.closest.as_ref().map(|state| state.estimated_cost))
+            .finish()
+    }
+}
+
+impl<Node: HashNodeInner> Display for ProofFailure<Node> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "No proof found ({}) after {} nodes", self.reason, self.nodes_explored)
+    }
+}
+
+impl<Node: HashNodeInner> std::error::Error for ProofFailure<Node> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, succ, Term, TermReflexivity};
+    use crate::proving::{Prover, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    fn prover(max_nodes: usize) -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(max_nodes, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover
+    }
+
+    #[test]
+    fn test_result_carries_stats() {
+        let store = NodeStorage::new();
+        let goal = eq(add(numeral(2, &store), numeral(2, &store), &store), numeral(4, &store), &store);
+
+        let result = prover(1000).prove(&goal).expect("goal should be provable");
+        let stats = &result.stats;
+
+        assert_eq!(stats.states_per_depth.iter().sum::<usize>(), result.nodes_explored);
+        assert_eq!(stats.max_depth(), result.steps.len());
+        assert_eq!(stats.rule_applications.get("add_succ"), Some(&2));
+        assert!(stats.max_heap_size >= 1);
+    }
+
+    #[test]
+    fn test_failure_reports_reason() {
+        let store = NodeStorage::new();
+        // S(0) = 0 has no rewrites at all, so the search runs dry.
+        let goal = eq(succ(numeral(0, &store), &store), numeral(0, &store), &store);
+
+        let failure = prover(1000).prove(&goal).err().expect("goal is false");
+        assert_eq!(failure.reason, FailureReason::Exhausted);
+        assert_eq!(failure.nodes_explored, 1);
+        assert_eq!(failure.stats.total_rule_applications(), 0);
+
+        let goal = eq(add(numeral(5, &store), numeral(5, &store), &store), numeral(10, &store), &store);
+        let failure = prover(2).prove(&goal).err().expect("budget is too small");
+        assert_eq!(failure.reason, FailureReason::NodeLimitReached);
+        assert_eq!(failure.nodes_explored, 2);
+    }
+
+    #[test]
+    fn test_failure_keeps_closest_state() {
+        let store = NodeStorage::new();
+        // 2 + 1 rewrites to S(2) = 3, which is not 2, so the search runs dry
+        // after reaching the fully evaluated equation.
+        let goal = eq(add(numeral(2, &store), numeral(1, &store), &store), numeral(2, &store), &store);
+
+        let failure = prover(1000).prove(&goal).err().expect("goal is false");
+        let closest = failure.closest_expr().expect("states were explored");
+        assert!(*closest == eq(numeral(3, &store), numeral(2, &store), &store));
+        assert_eq!(failure.closest_steps().len(), 2);
+        assert!(failure.closest_steps()[0].old_expr == goal);
+    }
+}
@@ -0,0 +1,155 @@
+//! Step sequences shared between search states.
+//!
+//! Every successor of a state extends the state's steps by one. A `StepList`
+//! links each step back to the one before it, so extending it takes constant
+//! time and siblings share the steps they have in common. The steps are
+//! copied out into a `Vec` only once a proof is found.
+
+use std::sync::Arc;
+
+use crate::base::nodes::HashNodeInner;
+use crate::proving::ProofStep;
+
+struct StepNode<T: HashNodeInner> {
+    step: ProofStep<T>,
+    previous: Option<Arc<StepNode<T>>>,
+}
+
+/// A persistent list of proof steps; cloning it is O(1).
+pub struct StepList<T: HashNodeInner> {
+    last: Option<Arc<StepNode<T>>>,
+    len: usize,
+}
+
+impl<T: HashNodeInner> StepList<T> {
+    pub fn new() -> Self {
+        Self { last: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn last(&self) -> Option<&ProofStep<T>> {
+        self.last.as_ref().map(|node| &node.step)
+    }
+
+    /// These steps followed by `step`, leaving `self` as it is.
+    pub fn pushed(&self, step: ProofStep<T>) -> Self {
+        Self { last: Some(Arc::new(StepNode { step, previous: self.last.clone() })), len: self.len + 1 }
+    }
+
+    pub fn push(&mut self, step: ProofStep<T>) {
+        *self = self.pushed(step);
+    }
+
+    /// The steps from last to first, without collecting them.
+    pub fn iter_rev(&self) -> impl Iterator<Item = &ProofStep<T>> {
+        std::iter::successors(self.last.as_deref(), |node| node.previous.as_deref()).map(|node| &node.step)
+    }
+
+    /// The steps from first to last.
+    pub fn iter(&self) -> std::vec::IntoIter<&ProofStep<T>> {
+        let mut steps: Vec<_> = self.iter_rev().collect();
+        steps.reverse();
+        steps.into_iter()
+    }
+
+    pub fn to_vec(&self) -> Vec<ProofStep<T>> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: HashNodeInner> Default for StepList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: HashNodeInner> Clone for StepList<T> {
+    fn clone(&self) -> Self {
+        Self { last: self.last.clone(), len: self.len }
+    }
+}
+
+impl<T: HashNodeInner> Drop for StepList<T> {
+    /// Unlink steps no other list shares one at a time, rather than
+    /// recursively, so long proofs cannot overflow the stack.
+    fn drop(&mut self) {
+        let mut next = self.last.take();
+        while let Some(node) = next {
+            next = match Arc::try_unwrap(node) {
+                Ok(mut node) => node.previous.take(),
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+impl<T: HashNodeInner> From<Vec<ProofStep<T>>> for StepList<T> {
+    fn from(steps: Vec<ProofStep<T>>) -> Self {
+        steps.into_iter().collect()
+    }
+}
+
+impl<T: HashNodeInner> FromIterator<ProofStep<T>> for StepList<T> {
+    fn from_iter<I: IntoIterator<Item = ProofStep<T>>>(steps: I) -> Self {
+        let mut list = Self::new();
+        for step in steps {
+            list.push(step);
+        }
+        list
+    }
+}
+
+impl<'a, T: HashNodeInner> IntoIterator for &'a StepList<T> {
+    type Item = &'a ProofStep<T>;
+    type IntoIter = std::vec::IntoIter<&'a ProofStep<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{numeral, Term};
+    use crate::rewriting::{RewriteDirection, Substitution};
+
+    fn step(name: &str, store: &NodeStorage<Term>) -> ProofStep<Term> {
+        ProofStep {
+            rule_name: name.to_string(),
+            direction: RewriteDirection::Forward,
+            position: Vec::new(),
+            substitution: Substitution::new(),
+            old_expr: numeral(0, store),
+            new_expr: numeral(0, store),
+        }
+    }
+
+    #[test]
+    fn test_siblings_share_prefix() {
+        let store = NodeStorage::new();
+        let parent: StepList<Term> = ["a", "b"].iter().map(|name| step(name, &store)).collect();
+        let left = parent.pushed(step("c", &store));
+        let right = parent.pushed(step("d", &store));
+
+        let names = |list: &StepList<Term>| list.iter().map(|step| step.rule_name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&parent), ["a", "b"]);
+        assert_eq!(names(&left), ["a", "b", "c"]);
+        assert_eq!(names(&right), ["a", "b", "d"]);
+        assert_eq!(right.iter_rev().map(|step| step.rule_name.as_str()).collect::<Vec<_>>(), ["d", "b", "a"]);
+        assert_eq!((left.len(), left.last().map(|step| step.rule_name.as_str())), (3, Some("c")));
+
+        drop(parent);
+        assert_eq!(left.to_vec().len(), 3);
+        let long: StepList<Term> = (0..100_000).map(|_| step("e", &store)).collect();
+        drop(long);
+    }
+}
@@ -0,0 +1,438 @@
+//! Tactics: scripted proofs with the search filling the gaps.
+//!
+//! A `Tactic` transforms a `ProofGoal` (the current expression plus the steps
+//! that led to it) or fails. Primitive tactics apply a named rule, normalize,
+//! close the goal by the goal checker, or hand the goal to the A* search;
+//! combinators sequence and branch them:
+//!
+//! ```ignore
+//! let script = then(apply_rule("add_succ"), orelse(reflexivity(), auto(500)));
+//! let goal = script.apply(&prover, &ProofGoal::new(statement))?;
+//! ```
+
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use crate::proving::certificate::ProofCertificate;
+use crate::proving::{
+    CostEstimator, FailureReason, GoalChecker, NoopObserver, ProofResult, ProofStep, Prover, SearchStats,
+    SessionStatus,
+};
+use crate::rewriting::{RewriteDirection, RewriteResult, RewriteRule};
+use crate::truth::TruthValue;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+/// Upper bound on the steps taken by `normalize` and iterations of `repeat`.
+const ITERATION_LIMIT: usize = 1000;
+
+/// A statement being proven by tactics.
+pub struct ProofGoal<Node: HashNodeInner + Clone, T: TruthValue> {
+    /// The statement the proof started from.
+    pub initial_expr: HashNode<Node>,
+    /// The expression the steps so far have reached.
+    pub current: HashNode<Node>,
+    /// Steps from `initial_expr` to `current`.
+    pub steps: Vec<ProofStep<Node>>,
+    /// Set once the goal checker has accepted `current`.
+    pub closed: Option<T>,
+}
+
+impl<Node: HashNodeInner + Clone, T: TruthValue> ProofGoal<Node, T> {
+    /// Start proving `statement`.
+    pub fn new(statement: &HashNode<Node>) -> Self {
+        Self {
+            initial_expr: statement.clone(),
+            current: statement.clone(),
+            steps: Vec::new(),
+            closed: None,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.is_some()
+    }
+
+    /// The goal after taking `step` from `current`.
+    pub(crate) fn advanced(&self, step: ProofStep<Node>) -> Self {
+        let mut steps = self.steps.clone();
+        let current = step.new_expr.clone();
+        steps.push(step);
+        Self {
+            initial_expr: self.initial_expr.clone(),
+            current,
+            steps,
+            closed: None,
+        }
+    }
+
+    /// The steps taken so far as a certificate.
+    pub fn certificate(&self) -> ProofCertificate<Node> {
+        ProofCertificate::new(self.initial_expr.clone(), self.steps.clone())
+    }
+
+    /// The finished proof, if the goal has been closed.
+    pub fn into_result(self) -> Option<ProofResult<Node, T>> {
+        Some(ProofResult {
+            truth_result: self.closed?,
+            nodes_explored: 0,
+            final_expr: self.current,
+            steps: self.steps,
+            stats: SearchStats::new(),
+        })
+    }
+}
+
+impl<Node: HashNodeInner + Clone, T: TruthValue> Clone for ProofGoal<Node, T> {
+    fn clone(&self) -> Self {
+        Self {
+            initial_expr: self.initial_expr.clone(),
+            current: self.current.clone(),
+            steps: self.steps.clone(),
+            closed: self.closed.clone(),
+        }
+    }
+}
+
+/// Why a tactic failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TacticError {
+    /// The goal was already closed.
+    GoalClosed,
+    /// No rule with this name is known to the prover.
+    UnknownRule(String),
+    /// The rule matches nowhere in the current expression.
+    RuleNotApplicable(String),
+    /// The goal checker does not accept the current expression.
+    NotAGoal,
+    /// The tactic made no progress.
+    NoProgress,
+    /// The search could not close the goal.
+    SearchFailed(FailureReason),
+}
+
+impl Display for TacticError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TacticError::GoalClosed => write!(f, "Goal is already closed"),
+            TacticError::UnknownRule(rule) => write!(f, "Unknown rule '{}'", rule),
+            TacticError::RuleNotApplicable(rule) => write!(f, "Rule '{}' does not apply", rule),
+            TacticError::NotAGoal => write!(f, "Current expression is not a goal"),
+            TacticError::NoProgress => write!(f, "Tactic made no progress"),
+            TacticError::SearchFailed(reason) => write!(f, "Search failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for TacticError {}
+
+/// What tactics need from a prover.
+pub trait TacticContext<Node: HashNodeInner + Clone, T: TruthValue> {
+    /// The prover's rewrite rules.
+    fn rules(&self) -> &[RewriteRule<Node>];
+
+    /// The store rewritten terms are interned into.
+    fn store(&self) -> &NodeStorage<Node>;
+
+    /// Run the goal checker on `expr`.
+    fn check_goal(&self, expr: &HashNode<Node>) -> Option<T>;
+
+    /// Search for a proof from `expr`, exploring at most `max_nodes` states.
+    fn search_from(&self, expr: &HashNode<Node>, max_nodes: usize) -> Result<ProofResult<Node, T>, FailureReason>;
+}
+
+impl<Node: HashNodeInner + Clone, C: CostEstimator<Node>, T: TruthValue, G: GoalChecker<Node, T>>
+    TacticContext<Node, T> for Prover<Node, C, T, G>
+{
+    fn rules(&self) -> &[RewriteRule<Node>] {
+        &self.rules
+    }
+
+    fn store(&self) -> &NodeStorage<Node> {
+        &self.store
+    }
+
+    fn check_goal(&self, expr: &HashNode<Node>) -> Option<T> {
+        self.goal_checker.check(expr)
+    }
+
+    fn search_from(&self, expr: &HashNode<Node>, max_nodes: usize) -> Result<ProofResult<Node, T>, FailureReason> {
+        let budget = max_nodes.min(self.max_nodes);
+        if let Some(failure) = self.cached_failure(expr, budget, 0) {
+            return Err(failure.reason);
+        }
+
+        let mut session = self.start_session(expr);
+        let reason = match self.advance(&mut session, max_nodes, &[], None, &mut NoopObserver, None) {
+            SessionStatus::Proved(result) => return Ok(result),
+            SessionStatus::Exhausted => FailureReason::Exhausted,
+            SessionStatus::Aborted => FailureReason::Aborted,
+            SessionStatus::TimedOut => FailureReason::TimedOut,
+            SessionStatus::InProgress | SessionStatus::NodeLimitReached => FailureReason::NodeLimitReached,
+        };
+        self.record_failure(expr, budget, 0, reason);
+        Err(reason)
+    }
+}
+
+/// A proof transformation that may fail.
+pub trait Tactic<Node: HashNodeInner + Clone, T: TruthValue> {
+    fn apply(&self, ctx: &dyn TacticContext<Node, T>, goal: &ProofGoal<Node, T>) -> Result<ProofGoal<Node, T>, TacticError>;
+}
+
+/// A boxed tactic, as taken and returned by the combinators.
+pub type BoxedTactic<Node, T> = Box<dyn Tactic<Node, T>>;
+
+fn step_from<Node: HashNodeInner + Clone>(old_expr: &HashNode<Node>, rewrite: RewriteResult<Node>) -> ProofStep<Node> {
+    ProofStep {
+        rule_name: rewrite.rule_name,
+        direction: rewrite.direction,
+        position: rewrite.position,
+        substitution: rewrite.substitution,
+        old_expr: old_expr.clone(),
+        new_expr: rewrite.term,
+    }
+}
+
+fn open_goal<Node: HashNodeInner + Clone, T: TruthValue>(goal: &ProofGoal<Node, T>) -> Result<(), TacticError> {
+    if goal.is_closed() {
+        return Err(TacticError::GoalClosed);
+    }
+    Ok(())
+}
+
+struct ApplyRule(String);
+
+impl<Node: HashNodeInner + Clone, T: TruthValue> Tactic<Node, T> for ApplyRule {
+    fn apply(&self, ctx: &dyn TacticContext<Node, T>, goal: &ProofGoal<Node, T>) -> Result<ProofGoal<Node, T>, TacticError> {
+        open_goal(goal)?;
+        let rule = ctx
+            .rules()
+            .iter()
+            .find(|rule| rule.name == self.0)
+            .ok_or_else(|| TacticError::UnknownRule(self.0.clone()))?;
+
+        [RewriteDirection::Forward, RewriteDirection::Backward]
+            .into_iter()
+            .find_map(|direction| {
+                rule.rewrites_at_all_positions(&goal.current, direction, ctx.store())
+                    .into_iter()
+                    .next()
+            })
+            .map(|rewrite| goal.advanced(step_from(&goal.current, rewrite)))
+            .ok_or_else(|| TacticError::RuleNotApplicable(self.0.clone()))
+    }
+}
+
+/// Apply the rule named `name` at the first position it matches, forwards if
+/// possible and otherwise backwards.
+pub fn apply_rule<Node: HashNodeInner + Clone, T: TruthValue>(name: impl Into<String>) -> BoxedTactic<Node, T> {
+    Box::new(ApplyRule(name.into()))
+}
+
+struct Normalize;
+
+impl<Node: HashNodeInner + Clone, T: TruthValue> Tactic<Node, T> for Normalize {
+    fn apply(&self, ctx: &dyn TacticContext<Node, T>, goal: &ProofGoal<Node, T>) -> Result<ProofGoal<Node, T>, TacticError> {
+        open_goal(goal)?;
+        let mut goal = goal.clone();
+        let mut seen = HashSet::from([goal.current.hash()]);
+
+        for _ in 0..ITERATION_LIMIT {
+            let next = ctx.rules().iter().find_map(|rule| {
+                rule.rewrites_at_all_positions(&goal.current, RewriteDirection::Forward, ctx.store())
+                    .into_iter()
+                    .find(|rewrite| !seen.contains(&rewrite.term.hash()))
+            });
+            let Some(rewrite) = next else {
+                break;
+            };
+            seen.insert(rewrite.term.hash());
+            goal = goal.advanced(step_from(&goal.current, rewrite));
+        }
+
+        Ok(goal)
+    }
+}
+
+/// Rewrite forwards with the first applicable rule until none applies
+/// without revisiting an expression. Never fails on an open goal.
+pub fn normalize<Node: HashNodeInner + Clone, T: TruthValue>() -> BoxedTactic<Node, T> {
+    Box::new(Normalize)
+}
+
+struct Reflexivity;
+
+impl<Node: HashNodeInner + Clone, T: TruthValue> Tactic<Node, T> for Reflexivity {
+    fn apply(&self, ctx: &dyn TacticContext<Node, T>, goal: &ProofGoal<Node, T>) -> Result<ProofGoal<Node, T>, TacticError> {
+        open_goal(goal)?;
+        let truth = ctx.check_goal(&goal.current).ok_or(TacticError::NotAGoal)?;
+        let mut goal = goal.clone();
+        goal.closed = Some(truth);
+        Ok(goal)
+    }
+}
+
+/// Close the goal if the goal checker accepts the current expression.
+pub fn reflexivity<Node: HashNodeInner + Clone, T: TruthValue>() -> BoxedTactic<Node, T> {
+    Box::new(Reflexivity)
+}
+
+struct Auto(usize);
+
+impl<Node: HashNodeInner + Clone, T: TruthValue> Tactic<Node, T> for Auto {
+    fn apply(&self, ctx: &dyn TacticContext<Node, T>, goal: &ProofGoal<Node, T>) -> Result<ProofGoal<Node, T>, TacticError> {
+        open_goal(goal)?;
+        let result = ctx.search_from(&goal.current, self.0).map_err(TacticError::SearchFailed)?;
+
+        let mut goal = goal.clone();
+        goal.steps.extend(result.steps);
+        goal.current = result.final_expr;
+        goal.closed = Some(result.truth_result);
+        Ok(goal)
+    }
+}
+
+/// Close the goal with the A* search, exploring at most `max_nodes` states.
+pub fn auto<Node: HashNodeInner + Clone, T: TruthValue>(max_nodes: usize) -> BoxedTactic<Node, T> {
+    Box::new(Auto(max_nodes))
+}
+
+struct Then<Node: HashNodeInner + Clone, T: TruthValue>(BoxedTactic<Node, T>, BoxedTactic<Node, T>);
+
+impl<Node: HashNodeInner + Clone, T: TruthValue> Tactic<Node, T> for Then<Node, T> {
+    fn apply(&self, ctx: &dyn TacticContext<Node, T>, goal: &ProofGoal<Node, T>) -> Result<ProofGoal<Node, T>, TacticError> {
+        let goal = self.0.apply(ctx, goal)?;
+        self.1.apply(ctx, &goal)
+    }
+}
+
+/// Apply `first`, then `second` to its result.
+pub fn then<Node: HashNodeInner + Clone + 'static, T: TruthValue + 'static>(
+    first: BoxedTactic<Node, T>,
+    second: BoxedTactic<Node, T>,
+) -> BoxedTactic<Node, T> {
+    Box::new(Then(first, second))
+}
+
+struct OrElse<Node: HashNodeInner + Clone, T: TruthValue>(BoxedTactic<Node, T>, BoxedTactic<Node, T>);
+
+impl<Node: HashNodeInner + Clone, T: TruthValue> Tactic<Node, T> for OrElse<Node, T> {
+    fn apply(&self, ctx: &dyn TacticContext<Node, T>, goal: &ProofGoal<Node, T>) -> Result<ProofGoal<Node, T>, TacticError> {
+        self.0.apply(ctx, goal).or_else(|_| self.1.apply(ctx, goal))
+    }
+}
+
+/// Apply `first`; if it fails, apply `second` to the original goal instead.
+pub fn orelse<Node: HashNodeInner + Clone + 'static, T: TruthValue + 'static>(
+    first: BoxedTactic<Node, T>,
+    second: BoxedTactic<Node, T>,
+) -> BoxedTactic<Node, T> {
+    Box::new(OrElse(first, second))
+}
+
+struct Repeat<Node: HashNodeInner + Clone, T: TruthValue>(BoxedTactic<Node, T>);
+
+impl<Node: HashNodeInner + Clone, T: TruthValue> Tactic<Node, T> for Repeat<Node, T> {
+    fn apply(&self, ctx: &dyn TacticContext<Node, T>, goal: &ProofGoal<Node, T>) -> Result<ProofGoal<Node, T>, TacticError> {
+        let mut goal = goal.clone();
+        for _ in 0..ITERATION_LIMIT {
+            match self.0.apply(ctx, &goal) {
+                Ok(next) if next.steps.len() > goal.steps.len() || next.is_closed() != goal.is_closed() => goal = next,
+                _ => break,
+            }
+        }
+        Ok(goal)
+    }
+}
+
+/// Apply `tactic` until it fails or stops making progress. Never fails.
+pub fn repeat<Node: HashNodeInner + Clone + 'static, T: TruthValue + 'static>(tactic: BoxedTactic<Node, T>) -> BoxedTactic<Node, T> {
+    Box::new(Repeat(tactic))
+}
+
+struct Try<Node: HashNodeInner + Clone, T: TruthValue>(BoxedTactic<Node, T>);
+
+impl<Node: HashNodeInner + Clone, T: TruthValue> Tactic<Node, T> for Try<Node, T> {
+    fn apply(&self, ctx: &dyn TacticContext<Node, T>, goal: &ProofGoal<Node, T>) -> Result<ProofGoal<Node, T>, TacticError> {
+        Ok(self.0.apply(ctx, goal).unwrap_or_else(|_| goal.clone()))
+    }
+}
+
+/// Apply `tactic`, leaving the goal unchanged if it fails. (`try` is a
+/// reserved word, hence the name.)
+pub fn try_tactic<Node: HashNodeInner + Clone + 'static, T: TruthValue + 'static>(
+    tactic: BoxedTactic<Node, T>,
+) -> BoxedTactic<Node, T> {
+    Box::new(Try(tactic))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proving::testing::{add, addition_rules, eq, numeral, Term, TermReflexivity};
+    use crate::proving::{verify_proof, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    type Goal = ProofGoal<Term, BinaryTruth>;
+
+    fn prover() -> Prover<Term, SizeCostEstimator, BinaryTruth, TermReflexivity> {
+        let mut prover = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        prover
+    }
+
+    #[test]
+    fn test_scripted_proof() {
+        let prover = prover();
+        let store = NodeStorage::new();
+        let statement = eq(add(numeral(2, &store), numeral(2, &store), &store), numeral(4, &store), &store);
+
+        let script = then(
+            repeat(apply_rule("add_succ")),
+            then(apply_rule("add_zero"), reflexivity()),
+        );
+        let goal = script.apply(&prover, &Goal::new(&statement)).expect("script should close the goal");
+
+        assert_eq!(goal.closed, Some(BinaryTruth::True));
+        assert_eq!(goal.steps.len(), 3);
+        assert_eq!(verify_proof(&goal.certificate(), &addition_rules(), &store), Ok(()));
+    }
+
+    #[test]
+    fn test_orelse_falls_back_to_auto() {
+        let prover = prover();
+        let store = NodeStorage::new();
+        let statement = eq(add(numeral(1, &store), numeral(2, &store), &store), numeral(3, &store), &store);
+
+        let script = orelse(reflexivity(), auto(100));
+        let goal = script.apply(&prover, &Goal::new(&statement)).expect("auto should close the goal");
+
+        let result = goal.into_result().expect("goal is closed");
+        assert!(result.final_expr == eq(numeral(3, &store), numeral(3, &store), &store));
+    }
+
+    #[test]
+    fn test_normalize_and_failures() {
+        let prover = prover();
+        let store = NodeStorage::new();
+        let statement = eq(add(numeral(1, &store), numeral(1, &store), &store), numeral(3, &store), &store);
+        let goal = Goal::new(&statement);
+
+        let normalized = normalize().apply(&prover, &goal).expect("normalize never fails");
+        assert!(normalized.current == eq(numeral(2, &store), numeral(3, &store), &store));
+
+        assert_eq!(reflexivity().apply(&prover, &normalized).err(), Some(TacticError::NotAGoal));
+        assert_eq!(
+            apply_rule("add_zero").apply(&prover, &normalized).err(),
+            Some(TacticError::RuleNotApplicable("add_zero".to_string()))
+        );
+        assert_eq!(
+            apply_rule("missing").apply(&prover, &goal).err(),
+            Some(TacticError::UnknownRule("missing".to_string()))
+        );
+
+        let unchanged = try_tactic(reflexivity()).apply(&prover, &normalized).expect("try never fails");
+        assert_eq!(unchanged.steps.len(), normalized.steps.len());
+    }
+}
@@ -0,0 +1,156 @@
+//! Small successor/addition term language used by the proving tests.
+
+use crate::base::nodes::{HashNode, HashNodeInner, Hashing, NodeStorage};
+use crate::rewriting::{Pattern, RewriteDirection, RewriteRule};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    Zero,
+    Succ(HashNode<Term>),
+    Add(HashNode<Term>, HashNode<Term>),
+    Eq(HashNode<Term>, HashNode<Term>),
+    Var(u32),
+}
+
+impl HashNodeInner for Term {
+    fn hash(&self) -> u64 {
+        match self {
+            Term::Zero => Hashing::root_hash(Hashing::opcode("zero"), &[]),
+            Term::Succ(inner) => Hashing::root_hash(Hashing::opcode("succ"), &[inner.hash()]),
+            Term::Add(l, r) => Hashing::root_hash(Hashing::opcode("add"), &[l.hash(), r.hash()]),
+            Term::Eq(l, r) => Hashing::root_hash(Hashing::opcode("eq"), &[l.hash(), r.hash()]),
+            Term::Var(index) => Hashing::root_hash(Hashing::opcode("var"), &[u64::from(*index)]),
+        }
+    }
+
+    fn size(&self) -> u64 {
+        match self {
+            Term::Zero | Term::Var(_) => 1,
+            Term::Succ(inner) => 1 + inner.size(),
+            Term::Add(l, r) | Term::Eq(l, r) => 1 + l.size() + r.size(),
+        }
+    }
+
+    fn decompose(&self) -> Option<(u64, Vec<HashNode<Self>>)> {
+        match self {
+            Term::Zero | Term::Var(_) => None,
+            Term::Succ(inner) => Some((Hashing::opcode("succ"), vec![inner.clone()])),
+            Term::Add(l, r) => Some((Hashing::opcode("add"), vec![l.clone(), r.clone()])),
+            Term::Eq(l, r) => Some((Hashing::opcode("eq"), vec![l.clone(), r.clone()])),
+        }
+    }
+
+    fn construct_from_parts(
+        opcode: u64,
+        children: Vec<HashNode<Self>>,
+        store: &NodeStorage<Self>,
+    ) -> Option<HashNode<Self>> {
+        match (opcode, children.as_slice()) {
+            (o, [inner]) if o == Hashing::opcode("succ") => {
+                Some(HashNode::from_store(Term::Succ(inner.clone()), store))
+            }
+            (o, [l, r]) if o == Hashing::opcode("add") => {
+                Some(HashNode::from_store(Term::Add(l.clone(), r.clone()), store))
+            }
+            (o, [l, r]) if o == Hashing::opcode("eq") => {
+                Some(HashNode::from_store(Term::Eq(l.clone(), r.clone()), store))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Term {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Term::Zero => write!(f, "0"),
+            Term::Succ(inner) => write!(f, "S({})", inner),
+            Term::Add(l, r) => write!(f, "({} + {})", l, r),
+            Term::Eq(l, r) => write!(f, "{} = {}", l, r),
+            Term::Var(index) => write!(f, "x{}", index),
+        }
+    }
+}
+
+pub fn zero(store: &NodeStorage<Term>) -> HashNode<Term> {
+    HashNode::from_store(Term::Zero, store)
+}
+
+pub fn succ(inner: HashNode<Term>, store: &NodeStorage<Term>) -> HashNode<Term> {
+    HashNode::from_store(Term::Succ(inner), store)
+}
+
+pub fn add(l: HashNode<Term>, r: HashNode<Term>, store: &NodeStorage<Term>) -> HashNode<Term> {
+    HashNode::from_store(Term::Add(l, r), store)
+}
+
+pub fn eq(l: HashNode<Term>, r: HashNode<Term>, store: &NodeStorage<Term>) -> HashNode<Term> {
+    HashNode::from_store(Term::Eq(l, r), store)
+}
+
+/// `n` applications of the successor to zero.
+pub fn numeral(n: usize, store: &NodeStorage<Term>) -> HashNode<Term> {
+    (0..n).fold(zero(store), |acc, _| succ(acc, store))
+}
+
+/// `x + 0 => x` and `x + S(y) => S(x + y)`.
+pub fn addition_rules() -> Vec<RewriteRule<Term>> {
+    let add_zero = RewriteRule::new(
+        "add_zero",
+        Pattern::compound(Hashing::opcode("add"), vec![Pattern::var(0), Pattern::constant(Term::Zero)]),
+        Pattern::var(0),
+        RewriteDirection::Forward,
+    );
+    let add_succ = RewriteRule::new(
+        "add_succ",
+        Pattern::compound(
+            Hashing::opcode("add"),
+            vec![
+                Pattern::var(0),
+                Pattern::compound(Hashing::opcode("succ"), vec![Pattern::var(1)]),
+            ],
+        ),
+        Pattern::compound(
+            Hashing::opcode("succ"),
+            vec![Pattern::compound(Hashing::opcode("add"), vec![Pattern::var(0), Pattern::var(1)])],
+        ),
+        RewriteDirection::Forward,
+    );
+    vec![add_zero, add_succ]
+}
+
+/// Rules rewriting `x0` to `x9` along two routes through `x3`: a cheap one
+/// through `x1` and `x2`, and a shorter one through the larger `S(S(S(x4)))`.
+/// Best-first search reaches `x3` along the cheap route first.
+pub fn detour_rules(store: &NodeStorage<Term>) -> Vec<RewriteRule<Term>> {
+    let detour = numeral_on(3, HashNode::from_store(Term::Var(4), store), store);
+    let rule = |name: &str, from: Term, to: Term| {
+        RewriteRule::new(name, Pattern::constant(from), Pattern::constant(to), RewriteDirection::Forward)
+    };
+    vec![
+        rule("x0_x1", Term::Var(0), Term::Var(1)),
+        rule("x1_x2", Term::Var(1), Term::Var(2)),
+        rule("x2_x3", Term::Var(2), Term::Var(3)),
+        rule("x0_detour", Term::Var(0), detour.value.as_ref().clone()),
+        rule("detour_x3", detour.value.as_ref().clone(), Term::Var(3)),
+        rule("x3_x5", Term::Var(3), Term::Var(5)),
+        rule("x5_x9", Term::Var(5), Term::Var(9)),
+    ]
+}
+
+/// `n` applications of the successor to `base`.
+fn numeral_on(n: usize, base: HashNode<Term>, store: &NodeStorage<Term>) -> HashNode<Term> {
+    (0..n).fold(base, |acc, _| succ(acc, store))
+}
+
+/// Goal checker accepting equalities whose sides are identical.
+pub struct TermReflexivity;
+
+impl crate::proving::GoalChecker<Term, crate::BinaryTruth> for TermReflexivity {
+    fn check(&self, expr: &HashNode<Term>) -> Option<crate::BinaryTruth> {
+        match expr.value.as_ref() {
+            Term::Eq(l, r) if l == r => Some(crate::BinaryTruth::True),
+            _ => None,
+        }
+    }
+}
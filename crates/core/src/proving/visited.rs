@@ -0,0 +1,153 @@
+//! The set of expressions a search has already expanded.
+//!
+//! By default states are identified by their 64-bit hash alone, which is fast
+//! but treats two distinct expressions with colliding hashes as the same
+//! state, silently pruning part of the search. Strict mode keeps the
+//! expressions themselves in per-hash buckets and compares them structurally,
+//! so a collision costs a comparison instead of a missed proof. `NodeStorage`
+//! keeps colliding values as distinct nodes, so both can reach the search.
+
+use crate::base::nodes::{HashNode, HashNodeInner};
+use std::collections::{HashMap, HashSet};
+
+/// Expressions already expanded by a search.
+pub enum VisitedSet<Node: HashNodeInner> {
+    /// Identify states by hash only.
+    Hashes(HashSet<u64>),
+    /// Identify states structurally, bucketed by hash.
+    Nodes(HashMap<u64, Vec<HashNode<Node>>>),
+}
+
+impl<Node: HashNodeInner> VisitedSet<Node> {
+    /// Create an empty set, comparing structurally when `strict` is set.
+    pub fn new(strict: bool) -> Self {
+        if strict {
+            VisitedSet::Nodes(HashMap::new())
+        } else {
+            VisitedSet::Hashes(HashSet::new())
+        }
+    }
+
+    /// Whether this set compares expressions structurally.
+    pub fn is_strict(&self) -> bool {
+        matches!(self, VisitedSet::Nodes(_))
+    }
+
+    /// Whether `expr` has already been recorded.
+    pub fn contains(&self, expr: &HashNode<Node>) -> bool {
+        match self {
+            VisitedSet::Hashes(hashes) => hashes.contains(&expr.hash()),
+            VisitedSet::Nodes(buckets) => buckets
+                .get(&expr.hash())
+                .is_some_and(|bucket| bucket.iter().any(|seen| seen.structurally_eq(expr))),
+        }
+    }
+
+    /// Record `expr`, returning `false` if it was already present.
+    pub fn insert(&mut self, expr: &HashNode<Node>) -> bool {
+        match self {
+            VisitedSet::Hashes(hashes) => hashes.insert(expr.hash()),
+            VisitedSet::Nodes(buckets) => {
+                let bucket = buckets.entry(expr.hash()).or_default();
+                if bucket.iter().any(|seen| seen.structurally_eq(expr)) {
+                    return false;
+                }
+                bucket.push(expr.clone());
+                true
+            }
+        }
+    }
+
+    /// Hashes of the recorded states.
+    pub fn hashes(&self) -> Vec<u64> {
+        match self {
+            VisitedSet::Hashes(hashes) => hashes.iter().copied().collect(),
+            VisitedSet::Nodes(buckets) => buckets.keys().copied().collect(),
+        }
+    }
+
+    /// Number of distinct states recorded.
+    pub fn len(&self) -> usize {
+        match self {
+            VisitedSet::Hashes(hashes) => hashes.len(),
+            VisitedSet::Nodes(buckets) => buckets.values().map(Vec::len).sum(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::{Hashing, NodeStorage};
+    use crate::proving::testing::{add, addition_rules, eq, numeral, TermReflexivity};
+    use crate::proving::{Prover, SizeCostEstimator};
+    use crate::BinaryTruth;
+
+    /// Pairs hash to the sum of their parts, so `(1, 2)` and `(2, 1)` collide.
+    #[derive(PartialEq)]
+    enum Weak {
+        Leaf(u64),
+        Pair(HashNode<Weak>, HashNode<Weak>),
+    }
+
+    impl HashNodeInner for Weak {
+        fn hash(&self) -> u64 {
+            match self {
+                Weak::Leaf(n) => *n,
+                Weak::Pair(a, b) => a.hash() + b.hash(),
+            }
+        }
+
+        fn size(&self) -> u64 {
+            1
+        }
+
+        fn decompose(&self) -> Option<(u64, Vec<HashNode<Self>>)> {
+            match self {
+                Weak::Leaf(_) => None,
+                Weak::Pair(a, b) => Some((Hashing::opcode("pair"), vec![a.clone(), b.clone()])),
+            }
+        }
+    }
+
+    #[test]
+    fn test_strict_mode_separates_collisions() {
+        let store = NodeStorage::new();
+        let (one, two) = (store.get_or_insert(Weak::Leaf(1)), store.get_or_insert(Weak::Leaf(2)));
+        let first = store.get_or_insert(Weak::Pair(one.clone(), two.clone()));
+        let second = store.get_or_insert(Weak::Pair(two, one));
+        assert_eq!(first.hash(), second.hash());
+        assert_eq!(store.len(), 4);
+
+        let mut by_hash = VisitedSet::new(false);
+        assert!(by_hash.insert(&first));
+        assert!(!by_hash.insert(&second));
+
+        let mut strict = VisitedSet::new(true);
+        assert!(strict.insert(&first));
+        assert!(strict.insert(&second));
+        assert!(!strict.insert(&first));
+        assert_eq!(strict.len(), 2);
+    }
+
+    #[test]
+    fn test_strict_prover_finds_same_proof() {
+        let store = NodeStorage::new();
+        let goal = eq(add(numeral(2, &store), numeral(3, &store), &store), numeral(5, &store), &store);
+
+        let mut prover: Prover<_, _, BinaryTruth, _> = Prover::new(1000, SizeCostEstimator, TermReflexivity);
+        for rule in addition_rules() {
+            prover.add_rule(rule);
+        }
+        let relaxed = prover.prove(&goal).expect("goal should be provable");
+
+        prover.set_strict(true);
+        let strict = prover.prove(&goal).expect("goal should be provable");
+        assert_eq!(strict.steps.len(), relaxed.steps.len());
+        assert_eq!(strict.nodes_explored, relaxed.nodes_explored);
+    }
+}
@@ -0,0 +1,165 @@
+//! Rewrite rules guarded by side conditions on their bindings.
+//!
+//! Some equations only hold under a proviso, e.g. `x · x⁻¹ = 1` for
+//! `x ≠ 0`. A `ConditionalRule` pairs a rule with `SideCondition`s over its
+//! variables. After the rule matches, each condition is instantiated with
+//! the bindings and handed to a `ConditionChecker`; the rule fires only if
+//! every condition is discharged.
+
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use crate::rewriting::{Pattern, RewriteDirection, RewriteRule, Substitution, Unifiable};
+
+/// A named predicate that must hold of an instance of `term`.
+pub struct SideCondition<Node: HashNodeInner + Clone> {
+    pub predicate: String,
+    pub term: Pattern<Node>,
+}
+
+impl<Node: HashNodeInner + Clone> Clone for SideCondition<Node> {
+    fn clone(&self) -> Self {
+        Self {
+            predicate: self.predicate.clone(),
+            term: self.term.clone(),
+        }
+    }
+}
+
+impl<Node: HashNodeInner + Clone> SideCondition<Node> {
+    pub fn new(predicate: impl Into<String>, term: Pattern<Node>) -> Self {
+        Self {
+            predicate: predicate.into(),
+            term,
+        }
+    }
+}
+
+/// Decides instantiated side conditions.
+pub trait ConditionChecker<Node: HashNodeInner> {
+    /// Whether `predicate` is known to hold of `term`. Conditions that
+    /// cannot be established count as failed.
+    fn holds(&self, predicate: &str, term: &HashNode<Node>) -> bool;
+}
+
+/// A rewrite rule that fires only where its side conditions hold.
+pub struct ConditionalRule<Node: HashNodeInner + Unifiable> {
+    pub rule: RewriteRule<Node>,
+    pub conditions: Vec<SideCondition<Node>>,
+}
+
+impl<Node: HashNodeInner + Unifiable> Clone for ConditionalRule<Node> {
+    fn clone(&self) -> Self {
+        Self {
+            rule: self.rule.clone(),
+            conditions: self.conditions.clone(),
+        }
+    }
+}
+
+impl<Node: HashNodeInner + Unifiable> ConditionalRule<Node> {
+    pub fn new(rule: RewriteRule<Node>, conditions: Vec<SideCondition<Node>>) -> Self {
+        Self { rule, conditions }
+    }
+
+    /// A rule without side conditions.
+    pub fn unconditional(rule: RewriteRule<Node>) -> Self {
+        Self::new(rule, Vec::new())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.rule.name
+    }
+
+    /// Apply the rule forward at the root of `term` if its conditions hold.
+    pub fn apply(
+        &self,
+        term: &HashNode<Node>,
+        store: &NodeStorage<Node>,
+        checker: &dyn ConditionChecker<Node>,
+    ) -> Option<HashNode<Node>> {
+        self.apply_in(RewriteDirection::Forward, term, store, checker)
+    }
+
+    /// Apply the rule backward at the root of `term` if its conditions hold.
+    ///
+    /// Conditions mentioning a variable the replacement does not bind
+    /// cannot be instantiated, so such rules never fire backward.
+    pub fn apply_reverse(
+        &self,
+        term: &HashNode<Node>,
+        store: &NodeStorage<Node>,
+        checker: &dyn ConditionChecker<Node>,
+    ) -> Option<HashNode<Node>> {
+        self.apply_in(RewriteDirection::Backward, term, store, checker)
+    }
+
+    fn apply_in(
+        &self,
+        direction: RewriteDirection,
+        term: &HashNode<Node>,
+        store: &NodeStorage<Node>,
+        checker: &dyn ConditionChecker<Node>,
+    ) -> Option<HashNode<Node>> {
+        let (substitution, target) = match direction {
+            RewriteDirection::Backward => (self.rule.try_match_reverse(term, store).ok()?, &self.rule.pattern),
+            _ => (self.rule.try_match(term, store).ok()?, &self.rule.replacement),
+        };
+        if !self.conditions_hold(&substitution, store, checker) {
+            return None;
+        }
+        target.instantiate(&substitution, store)
+    }
+
+    /// Whether every condition, instantiated with `substitution`, holds.
+    pub fn conditions_hold(
+        &self,
+        substitution: &Substitution<Node>,
+        store: &NodeStorage<Node>,
+        checker: &dyn ConditionChecker<Node>,
+    ) -> bool {
+        self.conditions.iter().all(|condition| {
+            condition
+                .term
+                .instantiate(substitution, store)
+                .is_some_and(|term| checker.holds(&condition.predicate, &term))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::Hashing;
+    use crate::proving::testing::{add, numeral, Term};
+
+    /// Knows only that successors are non-zero.
+    struct Successors;
+
+    impl ConditionChecker<Term> for Successors {
+        fn holds(&self, predicate: &str, term: &HashNode<Term>) -> bool {
+            predicate == "nonzero" && matches!(term.value.as_ref(), Term::Succ(_))
+        }
+    }
+
+    #[test]
+    fn test_conditions_guard_the_rule() {
+        // x + y => y + x, but only for non-zero x.
+        let swap = RewriteRule::new(
+            "swap",
+            Pattern::compound(Hashing::opcode("add"), vec![Pattern::var(0), Pattern::var(1)]),
+            Pattern::compound(Hashing::opcode("add"), vec![Pattern::var(1), Pattern::var(0)]),
+            RewriteDirection::Both,
+        );
+        let rule = ConditionalRule::new(swap, vec![SideCondition::new("nonzero", Pattern::var(0))]);
+
+        let store = NodeStorage::new();
+        let one_plus_zero = add(numeral(1, &store), numeral(0, &store), &store);
+        let zero_plus_one = add(numeral(0, &store), numeral(1, &store), &store);
+        assert_eq!(rule.apply(&one_plus_zero, &store, &Successors), Some(zero_plus_one.clone()));
+        assert_eq!(rule.apply(&zero_plus_one, &store, &Successors), None);
+
+        // Backward, the condition is on the bindings of the replacement.
+        assert_eq!(rule.apply_reverse(&zero_plus_one, &store, &Successors), Some(one_plus_zero));
+        assert!(ConditionalRule::unconditional(rule.rule.clone()).apply(&zero_plus_one, &store, &Successors).is_some());
+        assert_eq!(rule.name(), "swap");
+    }
+}
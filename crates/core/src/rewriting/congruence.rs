@@ -0,0 +1,85 @@
+//! Injectivity rules derived from a domain's constructors.
+//!
+//! A domain declares which of its constructors are injective by naming
+//! their opcodes in `InjectiveConstructors`. Each yields an
+//! `InjectivityRule`: an equation `f(x₁…xₙ) = f(y₁…yₙ)` whose arguments
+//! differ in one position `i` holds exactly when `xᵢ = yᵢ`, so the goal may
+//! be replaced by that equation. Equations differing in several positions
+//! are left alone, since no single equation is equivalent to them.
+
+use crate::base::nodes::{HashNode, HashNodeInner, Hashing};
+
+/// A term type some of whose constructors are injective.
+pub trait InjectiveConstructors: HashNodeInner {
+    /// Names of the injective constructors, as hashed into the opcodes that
+    /// `decompose` reports.
+    const INJECTIVE: &'static [&'static str];
+}
+
+/// `f(x…) = f(y…) ⇔ xᵢ = yᵢ` for one injective constructor `f`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InjectivityRule {
+    pub name: String,
+    pub opcode: u64,
+}
+
+impl InjectivityRule {
+    /// The rule for the constructor `constructor`, named after it.
+    pub fn new(constructor: &str) -> Self {
+        Self {
+            name: format!("{}_injectivity", constructor),
+            opcode: Hashing::opcode(constructor),
+        }
+    }
+
+    /// The sides of the equation `left = right` reduces to, if both apply
+    /// this constructor and their arguments differ in exactly one position.
+    pub fn apply<Node: HashNodeInner>(
+        &self,
+        left: &HashNode<Node>,
+        right: &HashNode<Node>,
+    ) -> Option<(HashNode<Node>, HashNode<Node>)> {
+        let (left_opcode, left_args) = left.value.decompose()?;
+        let (right_opcode, right_args) = right.value.decompose()?;
+        if left_opcode != self.opcode || right_opcode != self.opcode || left_args.len() != right_args.len() {
+            return None;
+        }
+        let mut differing = left_args.into_iter().zip(right_args).filter(|(x, y)| x != y);
+        let pair = differing.next()?;
+        differing.next().is_none().then_some(pair)
+    }
+}
+
+/// One injectivity rule per injective constructor of `Node`.
+pub fn injectivity_rules<Node: InjectiveConstructors>() -> Vec<InjectivityRule> {
+    Node::INJECTIVE.iter().map(|constructor| InjectivityRule::new(constructor)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::NodeStorage;
+    use crate::proving::testing::{add, numeral, succ, Term};
+
+    // `add` is not injective, but declaring it exercises binary constructors.
+    impl InjectiveConstructors for Term {
+        const INJECTIVE: &'static [&'static str] = &["succ", "add"];
+    }
+
+    #[test]
+    fn test_injectivity_rules() {
+        let rules = injectivity_rules::<Term>();
+        assert_eq!(rules.iter().map(|rule| rule.name.as_str()).collect::<Vec<_>>(), ["succ_injectivity", "add_injectivity"]);
+
+        let store = NodeStorage::new();
+        let (zero, one, two) = (numeral(0, &store), numeral(1, &store), numeral(2, &store));
+        assert_eq!(rules[0].apply(&succ(zero.clone(), &store), &two), Some((zero.clone(), one.clone())));
+        assert_eq!(rules[0].apply(&two, &zero), None);
+
+        // Only one argument may differ.
+        let apply = |l: HashNode<Term>, r| rules[1].apply(&l, &r);
+        assert_eq!(apply(add(zero.clone(), one.clone(), &store), add(zero.clone(), two.clone(), &store)), Some((one.clone(), two.clone())));
+        assert_eq!(apply(add(zero.clone(), one.clone(), &store), add(one.clone(), two.clone(), &store)), None);
+        assert_eq!(apply(add(zero.clone(), one.clone(), &store), add(zero, one, &store)), None);
+    }
+}
@@ -1,13 +1,22 @@
 use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
 
+pub mod conditional;
+pub mod congruence;
+pub mod parse;
 pub mod pattern;
+pub mod position;
 pub mod substitution;
+pub mod system;
 pub mod unifiable;
 
 // Re-export the main types for convenience
+pub use conditional::{ConditionChecker, ConditionalRule, SideCondition};
+pub use congruence::{injectivity_rules, InjectiveConstructors, InjectivityRule};
 pub use pattern::{Pattern, QuantifierType};
+pub use position::{Position, positions, replace_at, subterm_at};
 pub use substitution::Substitution;
-pub use unifiable::{Unifiable, UnificationError};
+pub use system::RewriteSystem;
+pub use unifiable::{unify_patterns, PatternBindings, Unifiable, UnificationError};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RewriteDirection {
@@ -28,10 +37,28 @@ pub struct RewriteRule<Node: HashNodeInner + Unifiable> {
     pub direction: RewriteDirection,
 }
 
+impl<Node: HashNodeInner + Unifiable> Clone for RewriteRule<Node> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            pattern: self.pattern.clone(),
+            replacement: self.replacement.clone(),
+            direction: self.direction,
+        }
+    }
+}
+
+/// The outcome of applying a rewrite rule at one position inside a term.
 pub struct RewriteResult<Node: HashNodeInner> {
+    /// The whole rewritten term.
     pub term: HashNode<Node>,
+    /// Bindings produced by matching the rule at `position`.
     pub substitution: Substitution<Node>,
     pub rule_name: String,
+    /// Direction the rule was applied in (`Forward` or `Backward`).
+    pub direction: RewriteDirection,
+    /// Path to the rewritten subterm.
+    pub position: Position,
 }
 
 impl<Node: HashNodeInner + Unifiable> RewriteRule<Node> {
@@ -119,6 +146,54 @@ impl<Node: HashNodeInner + Unifiable> RewriteRule<Node> {
             store,
         ))
     }
+
+    /// Apply this rule in `direction` at every position of `term` where it matches.
+    ///
+    /// `direction` must be `Forward` or `Backward`; each result records the
+    /// position and substitution so the step can be replayed independently.
+    pub fn rewrites_at_all_positions(
+        &self,
+        term: &HashNode<Node>,
+        direction: RewriteDirection,
+        store: &NodeStorage<Node>,
+    ) -> Vec<RewriteResult<Node>> {
+        let (source, target) = match direction {
+            RewriteDirection::Forward if !matches!(self.direction, RewriteDirection::Backward) => {
+                (&self.pattern, &self.replacement)
+            }
+            RewriteDirection::Backward if !matches!(self.direction, RewriteDirection::Forward) => {
+                (&self.replacement, &self.pattern)
+            }
+            _ => return Vec::new(),
+        };
+
+        let mut results = Vec::new();
+
+        for position in positions(term) {
+            let Some(subterm) = subterm_at(term, &position) else {
+                continue;
+            };
+            let Ok(substitution) = Node::unify(source, &subterm, &Substitution::new(), store) else {
+                continue;
+            };
+            let Some(rewritten) = target.instantiate(&substitution, store) else {
+                continue;
+            };
+            let Some(new_term) = replace_at(term, &position, rewritten, store) else {
+                continue;
+            };
+
+            results.push(RewriteResult {
+                term: new_term,
+                substitution,
+                rule_name: self.name.clone(),
+                direction,
+                position,
+            });
+        }
+
+        results
+    }
 }
 
 /// Apply a substitution to a pattern.
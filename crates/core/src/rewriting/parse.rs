@@ -0,0 +1,147 @@
+//! Rewrite rules and patterns written as text.
+//!
+//! A rule is a name, a colon and two patterns joined by an arrow giving its
+//! direction: `=>` forward, `<=` backward or `<=>` both ways.
+//!
+//! ```text
+//! add_zero: (add ?x 0) => ?x
+//! ```
+//!
+//! Patterns are S-expressions as read by `from_sexpr`, where `?name` is a
+//! variable and `_` a wildcard. Variables are numbered by first occurrence
+//! across both sides, so `?x` above is variable 0. Leaves are decoded with
+//! the domain's `AtomCodec`.
+
+use crate::base::codec::AtomCodec;
+use crate::base::nodes::{HashNodeInner, NodeStorage};
+use crate::base::sexpr::{read_sexprs, split_list, SExpr, SExprError};
+use crate::rewriting::{Pattern, RewriteDirection, RewriteRule, Unifiable};
+
+impl<Node: HashNodeInner + Unifiable + Clone> RewriteRule<Node> {
+    /// Read a rule written `name: pattern => replacement`.
+    pub fn parse(text: &str, codec: &dyn AtomCodec<Node>) -> Result<Self, SExprError> {
+        let Some((name, _)) = text.split_once(':').filter(|(name, _)| !name.trim().is_empty()) else {
+            return Err(SExprError::Syntax { offset: 0, message: "expected `name:`".to_string() });
+        };
+        let exprs = read_sexprs(text, name.len() + 1)?;
+        let [pattern, SExpr::Word { text: arrow, quoted: false, offset }, replacement] = exprs.as_slice() else {
+            let end = exprs.last().map_or(name.len() + 1, |_| text.len());
+            return Err(SExprError::Syntax { offset: end, message: "expected `pattern => replacement`".to_string() });
+        };
+        let direction = match arrow.as_str() {
+            "=>" => RewriteDirection::Forward,
+            "<=" => RewriteDirection::Backward,
+            "<=>" => RewriteDirection::Both,
+            _ => return Err(SExprError::Syntax { offset: *offset, message: "expected `=>`, `<=` or `<=>`".to_string() }),
+        };
+
+        let mut reader = PatternReader { codec, store: NodeStorage::new(), variables: Vec::new() };
+        let pattern = reader.pattern(pattern)?;
+        let replacement = reader.pattern(replacement)?;
+        let (left, right) = (pattern.vars(), replacement.vars());
+        let missing = |from: &[u32], to: &[u32]| to.iter().copied().find(|var| !from.contains(var));
+        let unbound = match direction {
+            RewriteDirection::Forward => missing(&left, &right),
+            RewriteDirection::Backward => missing(&right, &left),
+            RewriteDirection::Both => missing(&left, &right).or(missing(&right, &left)),
+        };
+        if let Some(var) = unbound {
+            let message = format!("?{} is not bound by the side it is rewritten from", reader.variables[var as usize]);
+            return Err(SExprError::Syntax { offset: *offset, message });
+        }
+        Ok(RewriteRule::new(name.trim(), pattern, replacement, direction))
+    }
+}
+
+impl<Node: HashNodeInner + Clone> Pattern<Node> {
+    /// Read a single pattern, numbering its variables by first occurrence.
+    pub fn parse(text: &str, codec: &dyn AtomCodec<Node>) -> Result<Self, SExprError> {
+        let exprs = read_sexprs(text, 0)?;
+        let [expr] = exprs.as_slice() else {
+            let message = format!("expected one pattern, found {}", exprs.len());
+            return Err(SExprError::Syntax { offset: 0, message });
+        };
+        PatternReader { codec, store: NodeStorage::new(), variables: Vec::new() }.pattern(expr)
+    }
+}
+
+struct PatternReader<'a, Node: HashNodeInner> {
+    codec: &'a dyn AtomCodec<Node>,
+    /// Holds decoded leaves until they are copied into constants.
+    store: NodeStorage<Node>,
+    /// Variable names, by index.
+    variables: Vec<String>,
+}
+
+impl<Node: HashNodeInner + Clone> PatternReader<'_, Node> {
+    fn pattern(&mut self, expr: &SExpr) -> Result<Pattern<Node>, SExprError> {
+        match expr {
+            SExpr::Word { text, quoted: false, .. } if text == "_" => Ok(Pattern::wildcard()),
+            SExpr::Word { text, quoted: false, .. } if text.starts_with('?') => {
+                let name = &text[1..];
+                let index = match self.variables.iter().position(|variable| variable == name) {
+                    Some(index) => index,
+                    None => {
+                        self.variables.push(name.to_string());
+                        self.variables.len() - 1
+                    }
+                };
+                Ok(Pattern::var(index as u32))
+            }
+            SExpr::Word { text, .. } => {
+                let atom = self.codec.decode_atom(text, &self.store).ok_or_else(|| SExprError::InvalidAtom(text.clone()))?;
+                Ok(Pattern::constant(atom.value.as_ref().clone()))
+            }
+            SExpr::List { items, offset } => {
+                let (opcode, children) = split_list(items, *offset)?;
+                let args = children.iter().map(|child| self.pattern(child)).collect::<Result<_, _>>()?;
+                Ok(Pattern::compound(opcode, args))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::nodes::HashNode;
+    use crate::proving::testing::{add, numeral, succ, Term};
+
+    struct TermCodec;
+
+    impl AtomCodec<Term> for TermCodec {
+        fn encode_atom(&self, atom: &Term) -> Option<String> {
+            matches!(atom, Term::Zero).then(|| "0".to_string())
+        }
+
+        fn decode_atom(&self, text: &str, store: &NodeStorage<Term>) -> Option<HashNode<Term>> {
+            (text == "0").then(|| HashNode::from_store(Term::Zero, store))
+        }
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let store = NodeStorage::new();
+        let rule = RewriteRule::<Term>::parse("add_succ: (add ?x (succ ?y)) => (succ (add ?x ?y))", &TermCodec).unwrap();
+        assert_eq!((rule.name.as_str(), rule.direction), ("add_succ", RewriteDirection::Forward));
+        let sum = add(numeral(1, &store), numeral(0, &store), &store);
+        assert_eq!(rule.apply(&add(numeral(1, &store), numeral(1, &store), &store), &store), Some(succ(sum, &store)));
+
+        let rule = RewriteRule::<Term>::parse("zero : ?x <= (add ?x 0)", &TermCodec).unwrap();
+        assert_eq!((rule.name.as_str(), rule.direction), ("zero", RewriteDirection::Backward));
+        assert_eq!(rule.apply_reverse(&add(numeral(2, &store), numeral(0, &store), &store), &store), Some(numeral(2, &store)));
+        assert!(RewriteRule::<Term>::parse("swap: (add ?x ?y) <=> (add ?y ?x)", &TermCodec).unwrap().is_bidirectional());
+        assert!(Pattern::<Term>::parse("(add _ ?z)", &TermCodec).unwrap().is_compound());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |text: &str| RewriteRule::<Term>::parse(text, &TermCodec).err().map(|e| e.to_string());
+        assert_eq!(parse("(add ?x 0) => ?x").as_deref(), Some("offset 0: expected `name:`"));
+        assert_eq!(parse("r: (add ?x 0) -> ?x").as_deref(), Some("offset 14: expected `=>`, `<=` or `<=>`"));
+        assert_eq!(parse("r: ?x => (add ?x ?y)").as_deref(), Some("offset 6: ?y is not bound by the side it is rewritten from"));
+        assert_eq!(parse("r: ?x <=> (add ?x ?y)").as_deref(), Some("offset 6: ?y is not bound by the side it is rewritten from"));
+        assert_eq!(parse("r: (add ?x 1) => ?x").as_deref(), Some("invalid atom 1"));
+        assert_eq!(parse("r: (add ?x 0)").as_deref(), Some("offset 13: expected `pattern => replacement`"));
+    }
+}
@@ -1,4 +1,6 @@
-use crate::base::nodes::{HashNode, HashNodeInner};
+use crate::base::nodes::{HashNode, HashNodeInner, Hashing, NodeStorage};
+use crate::rewriting::substitution::Substitution;
+use crate::rewriting::unifiable::PatternBindings;
 use std::fmt::{self, Debug, Display};
 
 pub enum QuantifierType {
@@ -120,6 +122,73 @@ impl<T: HashNodeInner + Clone> Pattern<T> {
             }
         }
     }
+
+    /// Build the term obtained by replacing each variable with its binding.
+    ///
+    /// Returns `None` if a variable is unbound, the pattern contains a
+    /// wildcard, or a compound opcode cannot be constructed by `T`.
+    pub fn instantiate(&self, subst: &Substitution<T>, store: &NodeStorage<T>) -> Option<HashNode<T>> {
+        match self {
+            Pattern::Variable(idx) => subst.get(*idx).cloned(),
+            Pattern::Wildcard => None,
+            Pattern::Constant(c) => Some(HashNode::from_store(c.clone(), store)),
+            Pattern::Compound { opcode, args } => {
+                let children = args
+                    .iter()
+                    .map(|arg| arg.instantiate(subst, store))
+                    .collect::<Option<Vec<_>>>()?;
+                T::construct_from_parts(*opcode, children, store)
+            }
+        }
+    }
+}
+
+impl<T: HashNodeInner + Clone> Pattern<T> {
+    /// The pattern matching exactly `term`, with `variable` choosing the
+    /// subterms to replace (typically the term's own variables).
+    pub fn from_term(term: &HashNode<T>, variable: &impl Fn(&HashNode<T>) -> Option<Pattern<T>>) -> Self {
+        if let Some(pattern) = variable(term) {
+            return pattern;
+        }
+        match term.value.decompose() {
+            Some((opcode, children)) => Pattern::Compound {
+                opcode,
+                args: children.iter().map(|child| Pattern::from_term(child, variable)).collect(),
+            },
+            None => Pattern::Constant(term.value.as_ref().clone()),
+        }
+    }
+
+    /// Replace bound variables by their bindings, following chains of
+    /// bindings until only unbound variables remain.
+    pub fn substitute(&self, bindings: &PatternBindings<T>) -> Self {
+        match self {
+            Pattern::Variable(idx) => match bindings.get(idx) {
+                Some(bound) => bound.substitute(bindings),
+                None => Pattern::Variable(*idx),
+            },
+            Pattern::Wildcard => Pattern::Wildcard,
+            Pattern::Constant(c) => Pattern::Constant(c.clone()),
+            Pattern::Compound { opcode, args } => Pattern::Compound {
+                opcode: *opcode,
+                args: args.iter().map(|arg| arg.substitute(bindings)).collect(),
+            },
+        }
+    }
+
+    /// Hash identifying the pattern up to structure: equal patterns, and
+    /// only those barring collisions, have equal hashes.
+    pub fn structural_hash(&self) -> u64 {
+        match self {
+            Pattern::Variable(idx) => Hashing::root_hash(Hashing::opcode("pattern_variable"), &[u64::from(*idx)]),
+            Pattern::Wildcard => Hashing::opcode("pattern_wildcard"),
+            Pattern::Constant(c) => c.hash(),
+            Pattern::Compound { opcode, args } => {
+                let hashes: Vec<u64> = args.iter().map(|arg| arg.structural_hash()).collect();
+                Hashing::root_hash(*opcode, &hashes)
+            }
+        }
+    }
 }
 
 impl<T: HashNodeInner + Clone> Clone for Pattern<T> {
@@ -0,0 +1,88 @@
+//! Subterm positions within hash-consed terms.
+//!
+//! A position is a path of child indices from the root of a term, following
+//! the children returned by `HashNodeInner::decompose`. The empty path denotes
+//! the root itself.
+
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+
+/// Path of child indices from the root of a term (empty = root).
+pub type Position = Vec<usize>;
+
+/// Get the subterm of `term` at `position`.
+///
+/// Returns `None` if the path leads through a node that cannot be decomposed
+/// or uses an out-of-range child index.
+pub fn subterm_at<T: HashNodeInner>(term: &HashNode<T>, position: &[usize]) -> Option<HashNode<T>> {
+    let Some((&index, rest)) = position.split_first() else {
+        return Some(term.clone());
+    };
+
+    let (_, children) = term.value.decompose()?;
+    subterm_at(children.get(index)?, rest)
+}
+
+/// Replace the subterm of `term` at `position` with `replacement`.
+///
+/// Ancestors of the replaced subterm are rebuilt through
+/// `HashNodeInner::construct_from_parts` and interned into `store`.
+pub fn replace_at<T: HashNodeInner>(
+    term: &HashNode<T>,
+    position: &[usize],
+    replacement: HashNode<T>,
+    store: &NodeStorage<T>,
+) -> Option<HashNode<T>> {
+    let Some((&index, rest)) = position.split_first() else {
+        return Some(replacement);
+    };
+
+    let (opcode, mut children) = term.value.decompose()?;
+    let child = children.get(index)?.clone();
+    children[index] = replace_at(&child, rest, replacement, store)?;
+    T::construct_from_parts(opcode, children, store)
+}
+
+/// Enumerate every position in `term` in pre-order (root first).
+pub fn positions<T: HashNodeInner>(term: &HashNode<T>) -> Vec<Position> {
+    let mut result = Vec::new();
+    collect_positions(term, &mut Vec::new(), &mut result);
+    result
+}
+
+fn collect_positions<T: HashNodeInner>(term: &HashNode<T>, current: &mut Position, result: &mut Vec<Position>) {
+    result.push(current.clone());
+
+    let Some((_, children)) = term.value.decompose() else {
+        return;
+    };
+
+    for (i, child) in children.iter().enumerate() {
+        current.push(i);
+        collect_positions(child, current, result);
+        current.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_position() {
+        let store = NodeStorage::new();
+        let term = HashNode::from_store(7u64, &store);
+        let other = HashNode::from_store(9u64, &store);
+
+        assert_eq!(subterm_at(&term, &[]), Some(term.clone()));
+        assert_eq!(replace_at(&term, &[], other.clone(), &store), Some(other));
+        assert_eq!(positions(&term), vec![Vec::<usize>::new()]);
+    }
+
+    #[test]
+    fn test_atomic_term_has_no_children() {
+        let store = NodeStorage::new();
+        let term = HashNode::from_store(7u64, &store);
+
+        assert!(subterm_at(&term, &[0]).is_none());
+    }
+}
@@ -0,0 +1,103 @@
+//! Named collections of rewrite rules.
+
+use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
+use crate::rewriting::{RewriteDirection, RewriteRule, Unifiable};
+use std::collections::HashSet;
+
+/// Most rewrite steps `RewriteSystem::normalize` takes before giving up.
+pub const NORMALIZE_LIMIT: usize = 1000;
+
+/// An ordered set of rewrite rules that can be handed to a prover as a
+/// whole or used on its own to normalize terms.
+pub struct RewriteSystem<Node: HashNodeInner + Unifiable> {
+    pub name: String,
+    rules: Vec<RewriteRule<Node>>,
+}
+
+impl<Node: HashNodeInner + Unifiable> Clone for RewriteSystem<Node> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            rules: self.rules.clone(),
+        }
+    }
+}
+
+impl<Node: HashNodeInner + Unifiable> RewriteSystem<Node> {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self::with_rules(name, Vec::new())
+    }
+
+    pub fn with_rules(name: impl Into<String>, rules: Vec<RewriteRule<Node>>) -> Self {
+        Self { name: name.into(), rules }
+    }
+
+    pub fn add_rule(&mut self, rule: RewriteRule<Node>) {
+        self.rules.push(rule);
+    }
+
+    /// Append the rules of `other`, after this system's own.
+    pub fn extend(&mut self, other: RewriteSystem<Node>) {
+        self.rules.extend(other.rules);
+    }
+
+    pub fn rules(&self) -> &[RewriteRule<Node>] {
+        &self.rules
+    }
+
+    pub fn into_rules(self) -> Vec<RewriteRule<Node>> {
+        self.rules
+    }
+
+    pub fn rule(&self, name: &str) -> Option<&RewriteRule<Node>> {
+        self.rules.iter().find(|rule| rule.name == name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Rewrite `term` forwards with the first applicable rule, earliest
+    /// position first, until no rule applies without revisiting a term or
+    /// `NORMALIZE_LIMIT` steps have been taken.
+    pub fn normalize(&self, term: &HashNode<Node>, store: &NodeStorage<Node>) -> HashNode<Node> {
+        let mut current = term.clone();
+        let mut seen = HashSet::from([current.hash()]);
+
+        for _ in 0..NORMALIZE_LIMIT {
+            let next = self.rules.iter().find_map(|rule| {
+                rule.rewrites_at_all_positions(&current, RewriteDirection::Forward, store)
+                    .into_iter()
+                    .find(|rewrite| !seen.contains(&rewrite.term.hash()))
+            });
+            let Some(rewrite) = next else {
+                break;
+            };
+            seen.insert(rewrite.term.hash());
+            current = rewrite.term;
+        }
+
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proving::testing::{add, addition_rules, numeral};
+
+    #[test]
+    fn test_normalize_rewrites_to_fixpoint() {
+        let store = NodeStorage::new();
+        let system = RewriteSystem::with_rules("addition", addition_rules());
+
+        let sum = add(numeral(2, &store), numeral(3, &store), &store);
+        assert_eq!(system.normalize(&sum, &store), numeral(5, &store));
+        assert_eq!(system.len(), 2);
+        assert!(system.rule("add_succ").is_some());
+    }
+}
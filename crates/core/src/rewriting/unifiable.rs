@@ -1,6 +1,7 @@
 use crate::base::nodes::{HashNode, HashNodeInner, NodeStorage};
 use crate::rewriting::pattern::Pattern;
 use crate::rewriting::substitution::Substitution;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum UnificationError {
@@ -88,6 +89,65 @@ impl<T: HashNodeInner + Clone> Unifiable for T {
     }
 }
 
+/// Bindings of variables to patterns, as built by `unify_patterns`.
+///
+/// Bindings may refer to other bound variables; `Pattern::substitute`
+/// resolves them fully.
+pub type PatternBindings<T> = HashMap<u32, Pattern<T>>;
+
+/// Extend `bindings` to a most general unifier of `a` and `b`, where both
+/// patterns may contain variables.
+///
+/// Constants unify when their hashes agree and wildcards unify with
+/// anything. On failure `bindings` may hold partial bindings and should be
+/// discarded.
+pub fn unify_patterns<T: HashNodeInner + Clone>(
+    a: &Pattern<T>,
+    b: &Pattern<T>,
+    bindings: &mut PatternBindings<T>,
+) -> Result<(), UnificationError> {
+    let a = resolve(a, bindings);
+    let b = resolve(b, bindings);
+    match (&a, &b) {
+        (Pattern::Wildcard, _) | (_, Pattern::Wildcard) => Ok(()),
+        (Pattern::Variable(x), Pattern::Variable(y)) if x == y => Ok(()),
+        (Pattern::Variable(x), other) | (other, Pattern::Variable(x)) => {
+            if occurs(*x, other, bindings) {
+                return Err(UnificationError::OccursCheck(*x, other.structural_hash()));
+            }
+            bindings.insert(*x, other.clone());
+            Ok(())
+        }
+        (Pattern::Constant(x), Pattern::Constant(y)) if x.hash() == y.hash() => Ok(()),
+        (Pattern::Compound { opcode: f, args: xs }, Pattern::Compound { opcode: g, args: ys }) => {
+            if f != g || xs.len() != ys.len() {
+                return Err(UnificationError::CannotUnify("Structure mismatch".into()));
+            }
+            xs.iter().zip(ys).try_for_each(|(x, y)| unify_patterns(x, y, bindings))
+        }
+        _ => Err(UnificationError::TypeMismatch),
+    }
+}
+
+/// Follow `pattern` through variable bindings until it is unbound or not a variable.
+fn resolve<T: HashNodeInner + Clone>(pattern: &Pattern<T>, bindings: &PatternBindings<T>) -> Pattern<T> {
+    let mut current = pattern;
+    while let Pattern::Variable(idx) = current
+        && let Some(bound) = bindings.get(idx)
+    {
+        current = bound;
+    }
+    current.clone()
+}
+
+fn occurs<T: HashNodeInner + Clone>(var: u32, pattern: &Pattern<T>, bindings: &PatternBindings<T>) -> bool {
+    match resolve(pattern, bindings) {
+        Pattern::Variable(idx) => idx == var,
+        Pattern::Compound { args, .. } => args.iter().any(|arg| occurs(var, arg, bindings)),
+        Pattern::Wildcard | Pattern::Constant(_) => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,4 +196,20 @@ mod tests {
         let result = u64::unify(&pattern, &term, &subst, &store);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_unify_patterns_binds_both_sides() {
+        let f = |args| Pattern::<u64>::compound(7, args);
+        // f(/0, 1) and f(2, /1) unify with /0 := 2, /1 := 1.
+        let a = f(vec![Pattern::var(0), Pattern::constant(1)]);
+        let b = f(vec![Pattern::constant(2), Pattern::var(1)]);
+        let mut bindings = PatternBindings::new();
+        unify_patterns(&a, &b, &mut bindings).unwrap();
+        assert_eq!(a.substitute(&bindings).structural_hash(), b.substitute(&bindings).structural_hash());
+
+        // /0 and f(/0) fail the occurs check.
+        let mut bindings = PatternBindings::new();
+        let cyclic = unify_patterns(&Pattern::var(0), &f(vec![Pattern::var(0)]), &mut bindings);
+        assert!(matches!(cyclic, Err(UnificationError::OccursCheck(0, _))));
+    }
 }
@@ -0,0 +1,53 @@
+//! Parse errors with the position they were found at.
+
+use std::fmt;
+
+use crate::lexer::Span;
+
+/// Why input failed to parse, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub span: Span,
+    /// 1-based line and column of `span.start`, counting characters.
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A token, or the end of input when `found` is `None`, where one of
+    /// `expected` should have been.
+    Unexpected { expected: Vec<String>, found: Option<String> },
+    /// Well-formed input that is rejected, e.g. a call to an unknown function.
+    Invalid(String),
+}
+
+impl ParseError {
+    /// An error at `span` of `input`, locating its line and column.
+    pub fn new(input: &str, span: Span, kind: ParseErrorKind) -> Self {
+        let before = &input[..span.start];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |text| text.chars().count()) + 1;
+        Self { span, line, column, kind }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseErrorKind::Unexpected { expected, found } => {
+                let found = found.as_ref().map_or("end of input".to_string(), |text| format!("`{}`", text));
+                write!(f, "expected {}, found {}", expected.join(" or "), found)
+            }
+            ParseErrorKind::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(error: ParseError) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,134 @@
+//! Binary operators parsed by precedence from a table.
+//!
+//! A syntax describes each level of its infix operators, e.g. connectives
+//! or `+` and `*` in terms, as `Infix` entries, and `parse_binary` reads a
+//! chain of operands joined by them: higher precedence binds tighter, and
+//! operators of equal precedence group by their associativity.
+
+use crate::error::ParseError;
+use crate::lexer::TokenKind;
+use crate::tokens::Tokens;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+/// A binary operator written as `token`, building `operator`.
+#[derive(Debug, Clone)]
+pub struct Infix<K, Op> {
+    pub token: K,
+    pub operator: Op,
+    pub precedence: u8,
+    pub associativity: Associativity,
+}
+
+impl<K, Op> Infix<K, Op> {
+    pub const fn left(token: K, operator: Op, precedence: u8) -> Self {
+        Self { token, operator, precedence, associativity: Associativity::Left }
+    }
+
+    pub const fn right(token: K, operator: Op, precedence: u8) -> Self {
+        Self { token, operator, precedence, associativity: Associativity::Right }
+    }
+}
+
+/// A parser reading its input through `Tokens`.
+pub trait Grammar<'a> {
+    type Token: TokenKind;
+
+    fn tokens(&mut self) -> &mut Tokens<'a, Self::Token>;
+}
+
+/// Parse operands joined by the operators of `table`, combining each
+/// operator with the operands on either side of it.
+pub fn parse_binary<'a, G, Op, T>(
+    grammar: &mut G,
+    table: &[Infix<G::Token, Op>],
+    operand: impl Fn(&mut G) -> Result<T, ParseError>,
+    combine: impl Fn(&mut G, Op, T, T) -> T,
+) -> Result<T, ParseError>
+where
+    G: Grammar<'a>,
+    Op: Clone,
+{
+    climb(grammar, table, 0, &operand, &combine)
+}
+
+fn climb<'a, G, Op, T>(
+    grammar: &mut G,
+    table: &[Infix<G::Token, Op>],
+    min_precedence: u8,
+    operand: &impl Fn(&mut G) -> Result<T, ParseError>,
+    combine: &impl Fn(&mut G, Op, T, T) -> T,
+) -> Result<T, ParseError>
+where
+    G: Grammar<'a>,
+    Op: Clone,
+{
+    let mut left = operand(grammar)?;
+    loop {
+        let Some(token) = grammar.tokens().peek() else {
+            return Ok(left);
+        };
+        let Some(infix) = table.iter().find(|infix| infix.token == *token && infix.precedence >= min_precedence) else {
+            return Ok(left);
+        };
+        grammar.tokens().advance();
+        let next = match infix.associativity {
+            Associativity::Left => infix.precedence + 1,
+            Associativity::Right => infix.precedence,
+        };
+        let right = climb(grammar, table, next, operand, combine)?;
+        left = combine(grammar, infix.operator.clone(), left, right);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Token;
+
+    /// Terms over `+`, `-`, `*` and a right associative `->`, written back
+    /// fully parenthesized.
+    struct Terms<'a> {
+        tokens: Tokens<'a, Token>,
+    }
+
+    impl<'a> Grammar<'a> for Terms<'a> {
+        type Token = Token;
+
+        fn tokens(&mut self) -> &mut Tokens<'a, Token> {
+            &mut self.tokens
+        }
+    }
+
+    const OPERATORS: &[Infix<Token, &str>] = &[
+        Infix::right(Token::Implies, "->", 1),
+        Infix::left(Token::Plus, "+", 2),
+        Infix::left(Token::Minus, "-", 2),
+        Infix::left(Token::Times, "*", 3),
+    ];
+
+    fn operand(terms: &mut Terms) -> Result<String, ParseError> {
+        match terms.tokens.advance() {
+            Some(Token::Ident(name)) => Ok(name),
+            _ => Err(terms.tokens.unexpected_previous(&["a name"])),
+        }
+    }
+
+    fn parse(input: &str) -> Result<String, ParseError> {
+        let mut terms = Terms { tokens: Tokens::new(input) };
+        let term = parse_binary(&mut terms, OPERATORS, operand, |_, op, l, r| format!("({} {} {})", l, op, r))?;
+        terms.tokens.expect_end()?;
+        Ok(term)
+    }
+
+    #[test]
+    fn test_precedence_and_associativity() {
+        assert_eq!(parse("a - b - c * d + e").unwrap(), "(((a - b) - (c * d)) + e)");
+        assert_eq!(parse("a -> b + c -> d").unwrap(), "(a -> ((b + c) -> d))");
+        assert_eq!(parse("a + * b").unwrap_err().to_string(), "1:5: expected a name, found `*`");
+    }
+}
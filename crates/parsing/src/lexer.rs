@@ -0,0 +1,164 @@
+//! Table-driven lexing into spanned tokens.
+//!
+//! A syntax lists its symbols and keywords in a `TokenKind` implementation.
+//! Symbols are matched longest first, so `<->`, `<=` and `<` can share a
+//! table; words of letters, digits and `_` are keywords when listed and
+//! identifiers otherwise; digit runs are numbers. Where the syntax accepts
+//! them, `/n` is the De Bruijn index `n`. Any other character becomes a
+//! single unknown token for the parser to reject.
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::str::Chars;
+
+/// A range of byte offsets into the parsed input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// The empty span at `offset`, e.g. the end of the input.
+    pub fn at(offset: usize) -> Self {
+        Self { start: offset, end: offset }
+    }
+}
+
+/// The tokens of one syntax, and the text they are read from.
+pub trait TokenKind: Clone + PartialEq + Debug + 'static {
+    /// Punctuation, e.g. `("->", Implies)`.
+    const SYMBOLS: &'static [(&'static str, Self)];
+    /// Reserved words, e.g. `("forall", Forall)`.
+    const KEYWORDS: &'static [(&'static str, Self)];
+
+    fn number(value: u64) -> Self;
+
+    fn identifier(name: String) -> Self;
+
+    /// A character no token starts with.
+    fn unknown(c: char) -> Self;
+
+    /// The token for `/index`, if the syntax writes De Bruijn indices.
+    fn de_bruijn(_index: u32) -> Option<Self> {
+        None
+    }
+
+    /// How the token is named in the expected set of an error.
+    fn describe(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// Splits input into tokens, each with the span it was read from.
+pub struct Lexer<'a, K> {
+    input: &'a str,
+    chars: Chars<'a>,
+    kind: PhantomData<K>,
+}
+
+impl<K> Clone for Lexer<'_, K> {
+    fn clone(&self) -> Self {
+        Self { input: self.input, chars: self.chars.clone(), kind: PhantomData }
+    }
+}
+
+impl<'a, K: TokenKind> Lexer<'a, K> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, chars: input.chars(), kind: PhantomData }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.clone().next()
+    }
+
+    /// Byte offset of the next character.
+    fn offset(&self) -> usize {
+        self.input.len() - self.chars.as_str().len()
+    }
+
+    fn read_while(&mut self, accept: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.chars.as_str();
+        while self.peek().is_some_and(&accept) {
+            self.chars.next();
+        }
+        &rest[..rest.len() - self.chars.as_str().len()]
+    }
+
+    /// `/n` as a De Bruijn index, if the syntax has them and one is next.
+    fn de_bruijn(&mut self) -> Option<K> {
+        let mut ahead = self.clone();
+        if ahead.chars.next() != Some('/') {
+            return None;
+        }
+        let token = K::de_bruijn(ahead.read_while(|c| c.is_ascii_digit()).parse().ok()?)?;
+        *self = ahead;
+        Some(token)
+    }
+
+    fn symbol(&mut self) -> Option<K> {
+        let rest = self.chars.as_str();
+        let (text, token) = K::SYMBOLS
+            .iter()
+            .filter(|(text, _)| rest.starts_with(text))
+            .max_by_key(|(text, _)| text.len())?;
+        self.chars = rest[text.len()..].chars();
+        Some(token.clone())
+    }
+}
+
+impl<K: TokenKind> Iterator for Lexer<'_, K> {
+    type Item = (K, Span);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_while(char::is_whitespace);
+        let start = self.offset();
+        let c = self.peek()?;
+        let token = if c.is_ascii_digit() {
+            let digits = self.read_while(|c| c.is_ascii_digit());
+            digits.parse().map_or(K::unknown(c), K::number)
+        } else if c.is_alphabetic() || c == '_' {
+            let word = self.read_while(|c| c.is_alphanumeric() || c == '_');
+            match K::KEYWORDS.iter().find(|(keyword, _)| *keyword == word) {
+                Some((_, token)) => token.clone(),
+                None => K::identifier(word.to_string()),
+            }
+        } else if let Some(token) = self.de_bruijn().or_else(|| self.symbol()) {
+            token
+        } else {
+            self.chars.next();
+            K::unknown(c)
+        };
+        Some((token, Span { start, end: self.offset() }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Token;
+
+    fn lex(input: &str) -> Vec<(Token, Span)> {
+        Lexer::new(input).collect()
+    }
+
+    #[test]
+    fn test_longest_symbol_and_spans() {
+        let tokens = lex("x <-> /1 <= 12 ≤ @");
+        assert_eq!(
+            tokens,
+            [
+                (Token::Ident("x".to_string()), Span { start: 0, end: 1 }),
+                (Token::Iff, Span { start: 2, end: 5 }),
+                (Token::DeBruijn(1), Span { start: 6, end: 8 }),
+                (Token::Le, Span { start: 9, end: 11 }),
+                (Token::Number(12), Span { start: 12, end: 14 }),
+                (Token::Le, Span { start: 15, end: 18 }),
+                (Token::Unknown('@'), Span { start: 19, end: 20 }),
+            ]
+        );
+        let tokens: Vec<_> = lex("forall / -").into_iter().map(|(token, _)| token).collect();
+        assert_eq!(tokens, [Token::Forall, Token::Unknown('/'), Token::Minus]);
+        assert_eq!(lex("99999999999999999999")[0].0, Token::Unknown('9'));
+    }
+}
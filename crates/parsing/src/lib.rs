@@ -0,0 +1,20 @@
+//! Shared front end for the infix syntaxes of the arithmetic tools.
+//!
+//! `lexer` splits input into spanned tokens from a table each syntax gives
+//! by implementing `TokenKind`; `tokens` is the cursor a recursive-descent
+//! parser reads them through, reporting `ParseError`s with positions; and
+//! `grammar` parses binary operators by precedence from an `Infix` table,
+//! leaving only the atoms and terms of a syntax to its own parser.
+
+pub mod error;
+pub mod grammar;
+pub mod lexer;
+pub mod tokens;
+
+#[cfg(test)]
+pub(crate) mod testing;
+
+pub use error::{ParseError, ParseErrorKind};
+pub use grammar::{parse_binary, Associativity, Grammar, Infix};
+pub use lexer::{Lexer, Span, TokenKind};
+pub use tokens::Tokens;
@@ -0,0 +1,58 @@
+//! A small token set for the tests of this crate.
+
+use crate::lexer::TokenKind;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    LParen,
+    RParen,
+    Forall,
+    Implies,
+    Iff,
+    Le,
+    Plus,
+    Minus,
+    Times,
+    Number(u64),
+    DeBruijn(u32),
+    Ident(String),
+    Unknown(char),
+}
+
+impl TokenKind for Token {
+    const SYMBOLS: &'static [(&'static str, Self)] = &[
+        ("(", Token::LParen),
+        (")", Token::RParen),
+        ("->", Token::Implies),
+        ("<->", Token::Iff),
+        ("<=", Token::Le),
+        ("≤", Token::Le),
+        ("+", Token::Plus),
+        ("-", Token::Minus),
+        ("*", Token::Times),
+    ];
+    const KEYWORDS: &'static [(&'static str, Self)] = &[("forall", Token::Forall)];
+
+    fn number(value: u64) -> Self {
+        Token::Number(value)
+    }
+
+    fn identifier(name: String) -> Self {
+        Token::Ident(name)
+    }
+
+    fn unknown(c: char) -> Self {
+        Token::Unknown(c)
+    }
+
+    fn de_bruijn(index: u32) -> Option<Self> {
+        Some(Token::DeBruijn(index))
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Token::RParen => "`)`".to_string(),
+            other => format!("{:?}", other),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! The cursor a recursive-descent parser reads tokens through.
+
+use std::collections::VecDeque;
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::lexer::{Lexer, Span, TokenKind};
+
+/// Tokens of `input` with any number of tokens of lookahead, remembering
+/// the span of the token consumed last so errors can point at it. Tokens
+/// are lexed as they are looked at; cloning saves a position to backtrack
+/// to.
+pub struct Tokens<'a, K: TokenKind> {
+    input: &'a str,
+    lexer: Lexer<'a, K>,
+    /// Tokens lexed but not yet consumed.
+    lookahead: VecDeque<(K, Span)>,
+    last: Span,
+}
+
+impl<K: TokenKind> Clone for Tokens<'_, K> {
+    fn clone(&self) -> Self {
+        Self { input: self.input, lexer: self.lexer.clone(), lookahead: self.lookahead.clone(), last: self.last }
+    }
+}
+
+impl<'a, K: TokenKind> Tokens<'a, K> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, lexer: Lexer::new(input), lookahead: VecDeque::new(), last: Span::default() }
+    }
+
+    pub fn input(&self) -> &'a str {
+        self.input
+    }
+
+    /// The token and span `n` tokens ahead, 0 being the next one.
+    fn peek_spanned(&mut self, n: usize) -> Option<&(K, Span)> {
+        while self.lookahead.len() <= n {
+            let token = self.lexer.next()?;
+            self.lookahead.push_back(token);
+        }
+        self.lookahead.get(n)
+    }
+
+    pub fn peek(&mut self) -> Option<&K> {
+        self.peek_nth(0)
+    }
+
+    /// The token `n` tokens ahead, 0 being the next one.
+    pub fn peek_nth(&mut self, n: usize) -> Option<&K> {
+        self.peek_spanned(n).map(|(token, _)| token)
+    }
+
+    pub fn advance(&mut self) -> Option<K> {
+        self.peek_spanned(0)?;
+        let (token, span) = self.lookahead.pop_front()?;
+        self.last = span;
+        Some(token)
+    }
+
+    /// Consume the next token if it is `expected`.
+    pub fn eat(&mut self, expected: &K) -> bool {
+        let matches = self.peek() == Some(expected);
+        if matches {
+            self.advance();
+        }
+        matches
+    }
+
+    /// Span of the token consumed last.
+    pub fn last(&self) -> Span {
+        self.last
+    }
+
+    /// Byte offset of the next token, or the length of the input at its end.
+    pub fn next_offset(&mut self) -> usize {
+        let end = self.input.len();
+        self.peek_spanned(0).map_or(end, |(_, span)| span.start)
+    }
+
+    /// An error at `span` for a token that is none of `expected`.
+    pub fn unexpected_at(&self, span: Span, expected: &[&str]) -> ParseError {
+        let found = (span.start < self.input.len()).then(|| self.input[span.start..span.end].to_string());
+        let expected = expected.iter().map(|text| text.to_string()).collect();
+        ParseError::new(self.input, span, ParseErrorKind::Unexpected { expected, found })
+    }
+
+    /// An error at the next token, or the end of input.
+    pub fn unexpected(&mut self, expected: &[&str]) -> ParseError {
+        let end = Span::at(self.input.len());
+        let span = self.peek_spanned(0).map_or(end, |(_, span)| *span);
+        self.unexpected_at(span, expected)
+    }
+
+    /// An error at the token consumed last.
+    pub fn unexpected_previous(&self, expected: &[&str]) -> ParseError {
+        self.unexpected_at(self.last, expected)
+    }
+
+    pub fn invalid(&self, span: Span, message: String) -> ParseError {
+        ParseError::new(self.input, span, ParseErrorKind::Invalid(message))
+    }
+
+    pub fn expect(&mut self, expected: K) -> Result<(), ParseError> {
+        if !self.eat(&expected) {
+            return Err(self.unexpected(&[&expected.describe()]));
+        }
+        Ok(())
+    }
+
+    pub fn expect_end(&mut self) -> Result<(), ParseError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.unexpected(&["end of input"])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Token;
+
+    #[test]
+    fn test_error_positions() {
+        let mut tokens = Tokens::<Token>::new("x +\n  (y");
+        tokens.advance();
+        tokens.advance();
+        assert!(tokens.eat(&Token::LParen));
+        assert_eq!(tokens.unexpected_previous(&["a term"]).to_string(), "2:3: expected a term, found `(`");
+        tokens.advance();
+        let error = tokens.expect(Token::RParen).unwrap_err();
+        assert_eq!((error.line, error.column, error.span), (2, 5, Span::at(8)));
+        assert_eq!(error.to_string(), "2:5: expected `)`, found end of input");
+    }
+
+    #[test]
+    fn test_lookahead() {
+        let mut tokens = Tokens::<Token>::new("x @ + 1");
+        assert_eq!(tokens.peek_nth(3), Some(&Token::Number(1)));
+        assert_eq!(tokens.peek_nth(4), None);
+        assert_eq!(tokens.peek(), Some(&Token::Ident("x".to_string())));
+        tokens.advance();
+        assert_eq!(tokens.peek_nth(1), Some(&Token::Plus));
+        // A stray character is reported where it is, not as the end of input.
+        assert_eq!(tokens.unexpected(&["`+`"]).to_string(), "1:3: expected `+`, found `@`");
+        assert_eq!(tokens.next_offset(), 2);
+    }
+}
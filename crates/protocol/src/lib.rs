@@ -0,0 +1,109 @@
+//! A message protocol for proving remotely.
+//!
+//! A client submits goals, and the server answers with a stream of events:
+//! progress while each goal is searched, then its outcome, and a certificate
+//! on request. Heavy searches then run in their own process while an
+//! interactive front end stays responsive.
+//!
+//! Each message is a frame: its length as a 4-byte big-endian integer, then
+//! that many bytes of UTF-8 JSON in the form documented in `message`.
+//! `ProverService` is what a domain implements to be served by `serve`.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use corpus_core::proving::{Json, JsonError, ToJson};
+
+pub mod message;
+pub mod service;
+
+pub use message::{Event, Request};
+pub use service::{serve, Client, Outcome, ProverService};
+
+/// Frames longer than this are refused rather than allocated.
+pub const MAX_FRAME_LEN: usize = 64 << 20;
+
+#[derive(Debug)]
+pub enum ProtocolError {
+    Io(io::Error),
+    /// A frame announcing more than `MAX_FRAME_LEN` bytes.
+    TooLarge(usize),
+    Utf8,
+    Json(JsonError),
+    /// Well-formed JSON that is not a message.
+    Message(String),
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::Io(e) => write!(f, "{}", e),
+            ProtocolError::TooLarge(len) => write!(f, "frame of {} bytes exceeds the limit of {}", len, MAX_FRAME_LEN),
+            ProtocolError::Utf8 => write!(f, "frame is not UTF-8"),
+            ProtocolError::Json(e) => write!(f, "{}", e),
+            ProtocolError::Message(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl From<io::Error> for ProtocolError {
+    fn from(e: io::Error) -> Self {
+        ProtocolError::Io(e)
+    }
+}
+
+/// Write one message as a frame.
+pub fn write_message(writer: &mut impl Write, message: &impl ToJson) -> Result<(), ProtocolError> {
+    let body = message.to_json().to_string();
+    if body.len() > MAX_FRAME_LEN {
+        return Err(ProtocolError::TooLarge(body.len()));
+    }
+    writer.write_all(&(body.len() as u32).to_be_bytes())?;
+    writer.write_all(body.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read the JSON of one frame, or `None` if the stream ends between frames.
+pub fn read_frame(reader: &mut impl Read) -> Result<Option<Json>, ProtocolError> {
+    let mut header = [0u8; 4];
+    let mut filled = 0;
+    while filled < header.len() {
+        match reader.read(&mut header[filled..])? {
+            0 if filled == 0 => return Ok(None),
+            0 => return Err(ProtocolError::Io(io::ErrorKind::UnexpectedEof.into())),
+            n => filled += n,
+        }
+    }
+    let len = u32::from_be_bytes(header) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(ProtocolError::TooLarge(len));
+    }
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body)?;
+    let text = String::from_utf8(body).map_err(|_| ProtocolError::Utf8)?;
+    Json::parse(&text).map(Some).map_err(ProtocolError::Json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &Request::Cancel { id: 7 }).unwrap();
+        write_message(&mut buffer, &Event::Accepted { id: 7 }).unwrap();
+        assert_eq!(&buffer[..4], &[0, 0, 0, 24]);
+
+        let mut reader = buffer.as_slice();
+        assert_eq!(Request::from_json(&read_frame(&mut reader).unwrap().unwrap()).unwrap(), Request::Cancel { id: 7 });
+        assert_eq!(Event::from_json(&read_frame(&mut reader).unwrap().unwrap()).unwrap(), Event::Accepted { id: 7 });
+        assert!(read_frame(&mut reader).unwrap().is_none());
+
+        assert!(matches!(read_frame(&mut &buffer[..10]), Err(ProtocolError::Io(_))));
+        assert!(matches!(read_frame(&mut &[0xff, 0xff, 0xff, 0xff][..]), Err(ProtocolError::TooLarge(_))));
+    }
+}
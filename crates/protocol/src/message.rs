@@ -0,0 +1,190 @@
+//! Requests and events, and their JSON form.
+//!
+//! Every message is an object whose `type` field names its variant:
+//!
+//! ```text
+//! {"type":"submit","id":1,"goal":"0 + x = x","max_nodes":10000}
+//! {"type":"progress","id":1,"nodes_explored":512}
+//! ```
+//!
+//! Fields are written in the order of the variant's declaration.
+
+use corpus_core::proving::{Json, ToJson};
+
+use crate::ProtocolError;
+
+/// Sent by a client. Ids are chosen by the client and name a goal in every
+/// later message about it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Request {
+    /// Prove `goal`, in the syntax of the service's domain, exploring at
+    /// most `max_nodes` states.
+    Submit { id: u64, goal: String, max_nodes: u64 },
+    /// Stop proving `id`, or drop it if it has not started.
+    Cancel { id: u64 },
+    /// Send the certificate of `id`, once it is proved.
+    Certificate { id: u64 },
+}
+
+/// Sent by a server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// The search for `id` has started.
+    Accepted { id: u64 },
+    Progress { id: u64, nodes_explored: u64 },
+    Proved { id: u64, steps: u64 },
+    Failed { id: u64, reason: String },
+    /// The certificate of `id`, in the schema the service writes.
+    Certificate { id: u64, certificate: Json },
+    /// A request that could not be served; `id` is absent when the request
+    /// could not be read.
+    Error { id: Option<u64>, message: String },
+}
+
+impl Event {
+    /// The goal this event is about, if any.
+    pub fn id(&self) -> Option<u64> {
+        match self {
+            Event::Accepted { id }
+            | Event::Progress { id, .. }
+            | Event::Proved { id, .. }
+            | Event::Failed { id, .. }
+            | Event::Certificate { id, .. } => Some(*id),
+            Event::Error { id, .. } => *id,
+        }
+    }
+}
+
+impl ToJson for Request {
+    fn to_json(&self) -> Json {
+        match self {
+            Request::Submit { id, goal, max_nodes } => Json::object([
+                ("type", Json::string("submit")),
+                ("id", Json::Number(*id)),
+                ("goal", Json::string(goal.as_str())),
+                ("max_nodes", Json::Number(*max_nodes)),
+            ]),
+            Request::Cancel { id } => Json::object([("type", Json::string("cancel")), ("id", Json::Number(*id))]),
+            Request::Certificate { id } => Json::object([("type", Json::string("certificate")), ("id", Json::Number(*id))]),
+        }
+    }
+}
+
+impl ToJson for Event {
+    fn to_json(&self) -> Json {
+        match self {
+            Event::Accepted { id } => Json::object([("type", Json::string("accepted")), ("id", Json::Number(*id))]),
+            Event::Progress { id, nodes_explored } => Json::object([
+                ("type", Json::string("progress")),
+                ("id", Json::Number(*id)),
+                ("nodes_explored", Json::Number(*nodes_explored)),
+            ]),
+            Event::Proved { id, steps } => {
+                Json::object([("type", Json::string("proved")), ("id", Json::Number(*id)), ("steps", Json::Number(*steps))])
+            }
+            Event::Failed { id, reason } => Json::object([
+                ("type", Json::string("failed")),
+                ("id", Json::Number(*id)),
+                ("reason", Json::string(reason.as_str())),
+            ]),
+            Event::Certificate { id, certificate } => Json::object([
+                ("type", Json::string("certificate")),
+                ("id", Json::Number(*id)),
+                ("certificate", certificate.clone()),
+            ]),
+            Event::Error { id, message } => {
+                let mut fields = vec![("type", Json::string("error"))];
+                fields.extend(id.map(|id| ("id", Json::Number(id))));
+                fields.push(("message", Json::string(message.as_str())));
+                Json::object(fields)
+            }
+        }
+    }
+}
+
+impl Request {
+    pub fn from_json(json: &Json) -> Result<Self, ProtocolError> {
+        match message_type(json)? {
+            "submit" => Ok(Request::Submit { id: number(json, "id")?, goal: string(json, "goal")?, max_nodes: number(json, "max_nodes")? }),
+            "cancel" => Ok(Request::Cancel { id: number(json, "id")? }),
+            "certificate" => Ok(Request::Certificate { id: number(json, "id")? }),
+            other => Err(ProtocolError::Message(format!("unknown request type `{}`", other))),
+        }
+    }
+}
+
+impl Event {
+    pub fn from_json(json: &Json) -> Result<Self, ProtocolError> {
+        match message_type(json)? {
+            "accepted" => Ok(Event::Accepted { id: number(json, "id")? }),
+            "progress" => Ok(Event::Progress { id: number(json, "id")?, nodes_explored: number(json, "nodes_explored")? }),
+            "proved" => Ok(Event::Proved { id: number(json, "id")?, steps: number(json, "steps")? }),
+            "failed" => Ok(Event::Failed { id: number(json, "id")?, reason: string(json, "reason")? }),
+            "certificate" => Ok(Event::Certificate { id: number(json, "id")?, certificate: field(json, "certificate")?.clone() }),
+            "error" => Ok(Event::Error {
+                id: json.get("id").map(|_| number(json, "id")).transpose()?,
+                message: string(json, "message")?,
+            }),
+            other => Err(ProtocolError::Message(format!("unknown event type `{}`", other))),
+        }
+    }
+}
+
+fn field<'a>(json: &'a Json, key: &str) -> Result<&'a Json, ProtocolError> {
+    json.get(key).ok_or_else(|| ProtocolError::Message(format!("missing field `{}`", key)))
+}
+
+fn number(json: &Json, key: &str) -> Result<u64, ProtocolError> {
+    match field(json, key)? {
+        Json::Number(value) => Ok(*value),
+        _ => Err(ProtocolError::Message(format!("field `{}` is not a number", key))),
+    }
+}
+
+fn string(json: &Json, key: &str) -> Result<String, ProtocolError> {
+    match field(json, key)? {
+        Json::String(value) => Ok(value.clone()),
+        _ => Err(ProtocolError::Message(format!("field `{}` is not a string", key))),
+    }
+}
+
+fn message_type(json: &Json) -> Result<&str, ProtocolError> {
+    match field(json, "type")? {
+        Json::String(value) => Ok(value),
+        _ => Err(ProtocolError::Message("field `type` is not a string".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_round_trip() {
+        let requests = [
+            Request::Submit { id: 1, goal: "0 + x = x".to_string(), max_nodes: 500 },
+            Request::Cancel { id: 1 },
+            Request::Certificate { id: 2 },
+        ];
+        for request in &requests {
+            assert_eq!(&Request::from_json(&request.to_json()).unwrap(), request);
+        }
+
+        let events = [
+            Event::Accepted { id: 1 },
+            Event::Progress { id: 1, nodes_explored: 256 },
+            Event::Proved { id: 1, steps: 3 },
+            Event::Failed { id: 2, reason: "cancelled".to_string() },
+            Event::Certificate { id: 1, certificate: Json::object([("steps", Json::Array(vec![]))]) },
+            Event::Error { id: None, message: "bad".to_string() },
+            Event::Error { id: Some(4), message: "unknown goal".to_string() },
+        ];
+        for event in &events {
+            assert_eq!(&Event::from_json(&event.to_json()).unwrap(), event);
+        }
+        assert_eq!(events[5].to_json().to_string(), r#"{"type":"error","message":"bad"}"#);
+
+        let error = Request::from_json(&Json::parse(r#"{"type": "submit", "id": 1}"#).unwrap()).unwrap_err();
+        assert_eq!(error.to_string(), "missing field `goal`");
+    }
+}
@@ -0,0 +1,316 @@
+//! Serving a prover over a pair of byte streams, and the client side.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{Read, Write};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use corpus_core::proving::{Json, SearchControl};
+
+use crate::{read_frame, write_message, Event, ProtocolError, Request};
+
+/// States explored between two `Event::Progress` of the same goal.
+pub const PROGRESS_INTERVAL: usize = 256;
+
+/// How a search ended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Proved { steps: usize, certificate: Json },
+    Failed(String),
+}
+
+/// A prover for goals written as text, such as one domain's prover with its
+/// axioms loaded.
+pub trait ProverService {
+    /// Search for a proof of `goal`, exploring at most `max_nodes` states.
+    ///
+    /// `progress` is called with the number of states explored so far as the
+    /// search runs; the search should stop and fail once it returns
+    /// `SearchControl::Abort`.
+    fn prove(&mut self, goal: &str, max_nodes: usize, progress: &mut dyn FnMut(usize) -> SearchControl) -> Outcome;
+}
+
+struct Goal {
+    id: u64,
+    text: String,
+    max_nodes: usize,
+}
+
+/// What `serve` keeps between requests.
+#[derive(Default)]
+struct Server {
+    pending: VecDeque<Goal>,
+    used: HashSet<u64>,
+    certificates: HashMap<u64, Json>,
+}
+
+impl Server {
+    /// Answer a request while no goal, or a goal other than those the
+    /// request names, is being proved.
+    fn handle(&mut self, request: Request, writer: &mut impl Write) -> Result<(), ProtocolError> {
+        match request {
+            Request::Submit { id, .. } if !self.used.insert(id) => {
+                write_message(writer, &Event::Error { id: Some(id), message: "id already used".to_string() })
+            }
+            Request::Submit { id, goal, max_nodes } => {
+                self.pending.push_back(Goal { id, text: goal, max_nodes: usize::try_from(max_nodes).unwrap_or(usize::MAX) });
+                Ok(())
+            }
+            Request::Cancel { id } => match self.pending.iter().position(|goal| goal.id == id) {
+                Some(index) => {
+                    self.pending.remove(index);
+                    write_message(writer, &Event::Failed { id, reason: "cancelled".to_string() })
+                }
+                None => write_message(writer, &Event::Error { id: Some(id), message: "no search to cancel".to_string() }),
+            },
+            Request::Certificate { id } => match self.certificates.get(&id) {
+                Some(certificate) => write_message(writer, &Event::Certificate { id, certificate: certificate.clone() }),
+                None => write_message(writer, &Event::Error { id: Some(id), message: "no certificate".to_string() }),
+            },
+        }
+    }
+
+    /// Prove `goal`, answering requests that arrive meanwhile.
+    fn prove<S: ProverService + ?Sized>(
+        &mut self,
+        service: &mut S,
+        goal: Goal,
+        requests: &Receiver<Result<Request, ProtocolError>>,
+        writer: &mut impl Write,
+    ) -> Result<(), ProtocolError> {
+        write_message(writer, &Event::Accepted { id: goal.id })?;
+        let mut cancelled = false;
+        let mut failure = None;
+        let mut next_report = PROGRESS_INTERVAL;
+        let mut progress = |nodes_explored: usize| {
+            let result = (|| {
+                loop {
+                    match requests.try_recv() {
+                        Ok(Ok(Request::Cancel { id })) if id == goal.id => cancelled = true,
+                        Ok(Ok(request)) => self.handle(request, writer)?,
+                        Ok(Err(ProtocolError::Message(message))) => write_message(writer, &Event::Error { id: None, message })?,
+                        Ok(Err(e)) => return Err(e),
+                        Err(TryRecvError::Empty | TryRecvError::Disconnected) => break,
+                    }
+                }
+                if nodes_explored >= next_report {
+                    next_report = nodes_explored + PROGRESS_INTERVAL;
+                    write_message(writer, &Event::Progress { id: goal.id, nodes_explored: nodes_explored as u64 })?;
+                }
+                Ok(())
+            })();
+            if let Err(e) = result {
+                failure = Some(e);
+            }
+            if cancelled || failure.is_some() { SearchControl::Abort } else { SearchControl::Continue }
+        };
+        let outcome = service.prove(&goal.text, goal.max_nodes, &mut progress);
+        if let Some(e) = failure {
+            return Err(e);
+        }
+        let event = match outcome {
+            _ if cancelled => Event::Failed { id: goal.id, reason: "cancelled".to_string() },
+            Outcome::Proved { steps, certificate } => {
+                self.certificates.insert(goal.id, certificate);
+                Event::Proved { id: goal.id, steps: steps as u64 }
+            }
+            Outcome::Failed(reason) => Event::Failed { id: goal.id, reason },
+        };
+        write_message(writer, &event)
+    }
+}
+
+/// Serve `service` to one client until its requests end.
+///
+/// Goals are proved one at a time in the order submitted. Requests are read
+/// on a thread of their own, so a cancellation reaches the search it names
+/// while it runs. Requests that are not messages are answered with
+/// `Event::Error`; an unreadable stream or a failed write ends serving.
+pub fn serve<S, R, W>(service: &mut S, reader: R, writer: &mut W) -> Result<(), ProtocolError>
+where
+    S: ProverService + ?Sized,
+    R: Read + Send + 'static,
+    W: Write,
+{
+    let (sender, requests) = mpsc::channel();
+    thread::spawn(move || {
+        let mut reader = reader;
+        loop {
+            let request = match read_frame(&mut reader) {
+                Ok(None) => break,
+                Ok(Some(json)) => Request::from_json(&json),
+                Err(e) => {
+                    let _ = sender.send(Err(e));
+                    break;
+                }
+            };
+            if sender.send(request).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut server = Server::default();
+    loop {
+        if let Some(goal) = server.pending.pop_front() {
+            server.prove(service, goal, &requests, writer)?;
+            continue;
+        }
+        match requests.recv() {
+            Ok(Ok(request)) => server.handle(request, writer)?,
+            Ok(Err(ProtocolError::Message(message))) => write_message(writer, &Event::Error { id: None, message })?,
+            Ok(Err(e)) => return Err(e),
+            Err(_) => return Ok(()),
+        }
+    }
+}
+
+/// The client end of a connection to `serve`.
+pub struct Client<R, W> {
+    reader: R,
+    writer: W,
+    next_id: u64,
+}
+
+impl<R: Read, W: Write> Client<R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        Client { reader, writer, next_id: 1 }
+    }
+
+    /// Submit `goal`, returning the id the server will report it under.
+    pub fn submit(&mut self, goal: &str, max_nodes: u64) -> Result<u64, ProtocolError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        write_message(&mut self.writer, &Request::Submit { id, goal: goal.to_string(), max_nodes })?;
+        Ok(id)
+    }
+
+    pub fn cancel(&mut self, id: u64) -> Result<(), ProtocolError> {
+        write_message(&mut self.writer, &Request::Cancel { id })
+    }
+
+    /// The next event from the server, or `None` once it has closed.
+    pub fn next_event(&mut self) -> Result<Option<Event>, ProtocolError> {
+        match read_frame(&mut self.reader)? {
+            Some(json) => Event::from_json(&json).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Wait for the outcome of `id`, an `Event::Proved`, `Event::Failed` or
+    /// `Event::Error`, passing its progress to `on_progress`.
+    ///
+    /// Events about other goals are dropped; use `next_event` to follow
+    /// several goals at once.
+    pub fn wait(&mut self, id: u64, mut on_progress: impl FnMut(u64)) -> Result<Event, ProtocolError> {
+        loop {
+            match self.expect_event()? {
+                Event::Progress { id: event_id, nodes_explored } if event_id == id => on_progress(nodes_explored),
+                event @ (Event::Proved { .. } | Event::Failed { .. } | Event::Error { .. }) if event.id() == Some(id) => return Ok(event),
+                _ => {}
+            }
+        }
+    }
+
+    /// Fetch the certificate of the proved goal `id`.
+    pub fn certificate(&mut self, id: u64) -> Result<Json, ProtocolError> {
+        write_message(&mut self.writer, &Request::Certificate { id })?;
+        loop {
+            match self.expect_event()? {
+                Event::Certificate { id: event_id, certificate } if event_id == id => return Ok(certificate),
+                Event::Error { id: Some(event_id), message } if event_id == id => return Err(ProtocolError::Message(message)),
+                _ => {}
+            }
+        }
+    }
+
+    fn expect_event(&mut self) -> Result<Event, ProtocolError> {
+        self.next_event()?.ok_or_else(|| ProtocolError::Message("server closed the connection".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::mpsc::Sender;
+
+    /// Proves a numeral `n` in `n` steps; spins on `forever` until aborted.
+    struct Counting;
+
+    impl ProverService for Counting {
+        fn prove(&mut self, goal: &str, max_nodes: usize, progress: &mut dyn FnMut(usize) -> SearchControl) -> Outcome {
+            let target = goal.parse::<usize>().unwrap_or(usize::MAX);
+            for explored in 1.. {
+                if explored > max_nodes {
+                    return Outcome::Failed("node limit reached".to_string());
+                }
+                if progress(explored) == SearchControl::Abort {
+                    return Outcome::Failed("aborted".to_string());
+                }
+                if explored == target {
+                    break;
+                }
+            }
+            Outcome::Proved { steps: target, certificate: Json::Number(target as u64) }
+        }
+    }
+
+    struct PipeWriter(Sender<Vec<u8>>);
+
+    impl Write for PipeWriter {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.send(bytes.to_vec()).map_err(|_| io::ErrorKind::BrokenPipe)?;
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct PipeReader(Receiver<Vec<u8>>, VecDeque<u8>);
+
+    impl Read for PipeReader {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            while self.1.is_empty() {
+                match self.0.recv() {
+                    Ok(bytes) => self.1.extend(bytes),
+                    Err(_) => return Ok(0),
+                }
+            }
+            self.1.read(buffer)
+        }
+    }
+
+    fn pipe() -> (PipeWriter, PipeReader) {
+        let (sender, receiver) = mpsc::channel();
+        (PipeWriter(sender), PipeReader(receiver, VecDeque::new()))
+    }
+
+    #[test]
+    fn test_serve_client() {
+        let (request_writer, request_reader) = pipe();
+        let (mut event_writer, event_reader) = pipe();
+        let server = thread::spawn(move || serve(&mut Counting, request_reader, &mut event_writer));
+        let mut client = Client::new(event_reader, request_writer);
+
+        let id = client.submit("600", 1000).unwrap();
+        let mut reports = Vec::new();
+        assert_eq!(client.wait(id, |nodes| reports.push(nodes)).unwrap(), Event::Proved { id, steps: 600 });
+        assert_eq!(reports, vec![256, 512]);
+        assert_eq!(client.certificate(id).unwrap(), Json::Number(600));
+
+        let limited = client.submit("600", 100).unwrap();
+        assert_eq!(client.wait(limited, |_| {}).unwrap(), Event::Failed { id: limited, reason: "node limit reached".to_string() });
+        assert_eq!(client.certificate(limited).unwrap_err().to_string(), "no certificate");
+
+        let forever = client.submit("forever", u64::MAX).unwrap();
+        assert_eq!(client.next_event().unwrap(), Some(Event::Accepted { id: forever }));
+        client.cancel(forever).unwrap();
+        assert_eq!(client.wait(forever, |_| {}).unwrap(), Event::Failed { id: forever, reason: "cancelled".to_string() });
+
+        drop(client);
+        assert!(server.join().unwrap().is_ok());
+    }
+}
@@ -0,0 +1,20 @@
+//! SMT-LIB 2 scripts for classical formulas.
+//!
+//! `reader` turns `declare-fun`/`assert`/`check-sat` scripts into
+//! `ClassicalLogicalExpression`s over a domain's atoms and terms, and
+//! `writer` exports goals the internal provers cannot close as scripts for
+//! an external SMT solver. Both go through the domain's `FirstOrderDomain`,
+//! with an `SmtSignature` naming its predicate and function opcodes.
+
+pub mod reader;
+pub mod sexpr;
+pub mod signature;
+pub mod writer;
+
+#[cfg(test)]
+pub(crate) mod testing;
+
+pub use reader::{parse_script, SmtScript};
+pub use sexpr::{parse_sexprs, SExpr};
+pub use signature::SmtSignature;
+pub use writer::{export_goal, format_formula};
@@ -0,0 +1,314 @@
+//! Reading SMT-LIB scripts into classical formulas.
+//!
+//! A script declares its symbols with `declare-fun` or `declare-const`,
+//! states formulas with `assert` and asks for satisfiability with
+//! `check-sat`. Each declared symbol must be named in the `SmtSignature`,
+//! which maps it to the domain's opcodes: `Bool`-valued symbols to
+//! predicates and the rest to functions or constants. Theory symbols such
+//! as `+` and numerals need no declaration, only a name in the signature.
+//!
+//! Formulas may use `not`, `and`, `or`, `=>`, `xor`, `=` between formulas
+//! or terms, `distinct`, `forall` and `exists`, and `!` annotations, which
+//! are dropped. Quantified variables become de Bruijn indices. Commands
+//! that only configure the solver are ignored; `push`, `pop`,
+//! `define-fun` and the like are rejected.
+
+use std::collections::BTreeSet;
+
+use corpus_classical_logic::first_order::FirstOrderDomain;
+use corpus_classical_logic::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner, NodeStorage};
+use corpus_core::truth::TruthValue;
+
+use crate::sexpr::{parse_sexprs, SExpr};
+use crate::signature::{is_predefined, SmtSignature};
+
+/// The assertions of a script, in order, and whether it asked `check-sat`.
+pub struct SmtScript<T, D>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+{
+    pub assertions: Vec<HashNode<ClassicalLogicalExpression<T, D>>>,
+    pub check_sat: bool,
+}
+
+/// Commands that configure the solver or ask for output, with no bearing on
+/// the formulas.
+const IGNORED_COMMANDS: &[&str] = &[
+    "set-logic",
+    "set-info",
+    "set-option",
+    "declare-sort",
+    "get-model",
+    "get-proof",
+    "get-unsat-core",
+    "get-info",
+    "exit",
+];
+
+struct Reader<'a, T, D, F>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    signature: &'a SmtSignature<F::Term>,
+    domain: &'a F,
+    store: &'a NodeStorage<ClassicalLogicalExpression<T, D>>,
+    declared: BTreeSet<String>,
+    /// Variables bound by the enclosing quantifiers, innermost last.
+    bound: Vec<String>,
+}
+
+impl<T, D, F> Reader<'_, T, D, F>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    fn command(&mut self, command: &SExpr, script: &mut SmtScript<T, D>) -> Result<(), String> {
+        let (name, args) = command.as_application().ok_or_else(|| format!("Expected a command, found {}", command))?;
+        match (name, args) {
+            (name, _) if IGNORED_COMMANDS.contains(&name) => {}
+            ("declare-fun", [symbol, SExpr::List(_), sort]) | ("declare-const", [symbol, sort]) => {
+                let symbol = symbol.as_atom().ok_or_else(|| format!("Expected a symbol, found {}", symbol))?;
+                let known = match sort.as_atom() {
+                    Some("Bool") => self.signature.predicate_opcode(symbol).is_some(),
+                    _ => self.signature.function_opcode(symbol).is_some() || self.signature.constant_term(symbol).is_some(),
+                };
+                if !known {
+                    return Err(format!("The signature has no {} of sort {}", symbol, sort));
+                }
+                self.declared.insert(symbol.to_string());
+            }
+            ("assert", [formula]) => script.assertions.push(self.formula(formula)?),
+            ("check-sat", []) => script.check_sat = true,
+            _ => return Err(format!("Unsupported command {}", command)),
+        }
+        Ok(())
+    }
+
+    fn check_declared(&self, name: &str) -> Result<(), String> {
+        match is_predefined(name) || self.declared.contains(name) {
+            true => Ok(()),
+            false => Err(format!("Undeclared symbol {}", name)),
+        }
+    }
+
+    fn formula(&mut self, expr: &SExpr) -> Result<HashNode<ClassicalLogicalExpression<T, D>>, String> {
+        let (head, args) = match expr {
+            SExpr::Atom(name) => return self.predication(name, &[]),
+            SExpr::List(_) => expr.as_application().ok_or_else(|| format!("Expected a formula, found {}", expr))?,
+        };
+        match (head, args) {
+            ("not", [inner]) => {
+                let inner = self.formula(inner)?;
+                Ok(self.compound(ClassicalOperator::Not, vec![inner]))
+            }
+            ("and" | "or", [_, ..]) => {
+                let operator = if head == "and" { ClassicalOperator::And } else { ClassicalOperator::Or };
+                let operands = args.iter().map(|arg| self.formula(arg)).collect::<Result<Vec<_>, _>>()?;
+                Ok(self.fold(operator, operands))
+            }
+            ("=>", [_, _, ..]) => {
+                let mut operands = args.iter().map(|arg| self.formula(arg)).collect::<Result<Vec<_>, _>>()?;
+                let mut formula = operands.pop().expect("at least two operands");
+                for premise in operands.into_iter().rev() {
+                    formula = self.compound(ClassicalOperator::Implies, vec![premise, formula]);
+                }
+                Ok(formula)
+            }
+            ("xor", [left, right]) => {
+                let operands = vec![self.formula(left)?, self.formula(right)?];
+                let iff = self.compound(ClassicalOperator::Iff, operands);
+                Ok(self.compound(ClassicalOperator::Not, vec![iff]))
+            }
+            ("=" | "distinct", [_, _, ..]) => self.equation(head == "distinct", args),
+            ("forall" | "exists", [SExpr::List(variables), body]) => {
+                let quantifier = if head == "forall" { ClassicalOperator::Forall } else { ClassicalOperator::Exists };
+                for variable in variables {
+                    match variable {
+                        SExpr::List(binding) if binding.len() == 2 && binding[0].as_atom().is_some() => {
+                            self.bound.push(binding[0].to_string());
+                        }
+                        _ => return Err(format!("Expected a sorted variable, found {}", variable)),
+                    }
+                }
+                let mut body = self.formula(body)?;
+                for _ in variables {
+                    self.bound.pop();
+                    body = self.compound(quantifier, vec![body]);
+                }
+                Ok(body)
+            }
+            ("!", [inner, ..]) => self.formula(inner),
+            _ => self.predication(head, args),
+        }
+    }
+
+    /// `=` or `distinct` over terms, or `=` over formulas as a biconditional.
+    fn equation(&mut self, distinct: bool, args: &[SExpr]) -> Result<HashNode<ClassicalLogicalExpression<T, D>>, String> {
+        let terms = match args.iter().map(|arg| self.term(arg)).collect::<Result<Vec<_>, _>>() {
+            Ok(terms) => terms,
+            Err(_) if !distinct => {
+                let operands = args.iter().map(|arg| self.formula(arg)).collect::<Result<Vec<_>, _>>()?;
+                let pairs = operands.windows(2).map(|pair| self.compound(ClassicalOperator::Iff, pair.to_vec())).collect();
+                return Ok(self.fold(ClassicalOperator::And, pairs));
+            }
+            Err(e) => return Err(e),
+        };
+
+        // `distinct` holds of every pair, `=` of every adjacent one.
+        let pairs: Vec<(usize, usize)> = match distinct {
+            true => (0..terms.len()).flat_map(|i| (i + 1..terms.len()).map(move |j| (i, j))).collect(),
+            false => (1..terms.len()).map(|i| (i - 1, i)).collect(),
+        };
+        let opcode = self.signature.predicate_opcode("=").ok_or("Equality is not in the signature")?;
+        let mut atoms = Vec::new();
+        for (i, j) in pairs {
+            let atom = self.relation("=", opcode, vec![terms[i].clone(), terms[j].clone()])?;
+            atoms.push(match distinct {
+                true => self.compound(ClassicalOperator::Not, vec![atom]),
+                false => atom,
+            });
+        }
+        Ok(self.fold(ClassicalOperator::And, atoms))
+    }
+
+    /// The declared predicate `name` applied to `args`.
+    fn predication(&mut self, name: &str, args: &[SExpr]) -> Result<HashNode<ClassicalLogicalExpression<T, D>>, String> {
+        self.check_declared(name)?;
+        let opcode = self.signature.predicate_opcode(name).ok_or_else(|| format!("{} is not a predicate", name))?;
+        let arguments = args.iter().map(|arg| self.term(arg)).collect::<Result<Vec<_>, _>>()?;
+        self.relation(name, opcode, arguments)
+    }
+
+    fn term(&self, expr: &SExpr) -> Result<HashNode<F::Term>, String> {
+        let (name, args) = match expr {
+            SExpr::Atom(name) => {
+                if let Some(position) = self.bound.iter().rposition(|bound| bound == name) {
+                    return Ok(self.domain.variable((self.bound.len() - 1 - position) as u32));
+                }
+                (name.as_str(), &[][..])
+            }
+            SExpr::List(_) => expr.as_application().ok_or_else(|| format!("Expected a term, found {}", expr))?,
+        };
+        self.check_declared(name)?;
+        if args.is_empty()
+            && let Some(constant) = self.signature.constant_term(name)
+        {
+            return Ok(constant.clone());
+        }
+        let opcode = self.signature.function_opcode(name).ok_or_else(|| format!("{} is not a function", name))?;
+        let arguments = args.iter().map(|arg| self.term(arg)).collect::<Result<Vec<_>, _>>()?;
+        F::Term::construct_from_parts(opcode, arguments, self.domain.terms())
+            .ok_or_else(|| format!("The domain has no term {}", expr))
+    }
+
+    fn relation(
+        &self,
+        name: &str,
+        opcode: u64,
+        arguments: Vec<HashNode<F::Term>>,
+    ) -> Result<HashNode<ClassicalLogicalExpression<T, D>>, String> {
+        let atom = self.domain.atom(opcode, arguments).ok_or_else(|| format!("The domain has no atom {}", name))?;
+        Ok(HashNode::from_store(LogicalExpression::atomic(atom), self.store))
+    }
+
+    fn compound(
+        &self,
+        operator: ClassicalOperator,
+        operands: Vec<HashNode<ClassicalLogicalExpression<T, D>>>,
+    ) -> HashNode<ClassicalLogicalExpression<T, D>> {
+        HashNode::from_store(LogicalExpression::compound(operator, operands), self.store)
+    }
+
+    /// The left-nested binary `operator` over `operands`, of which there is
+    /// at least one.
+    fn fold(
+        &self,
+        operator: ClassicalOperator,
+        operands: Vec<HashNode<ClassicalLogicalExpression<T, D>>>,
+    ) -> HashNode<ClassicalLogicalExpression<T, D>> {
+        operands
+            .into_iter()
+            .reduce(|left, right| self.compound(operator, vec![left, right]))
+            .expect("at least one operand")
+    }
+}
+
+/// Read the SMT-LIB script `text`.
+pub fn parse_script<T, D, F>(
+    text: &str,
+    signature: &SmtSignature<F::Term>,
+    domain: &F,
+    store: &NodeStorage<ClassicalLogicalExpression<T, D>>,
+) -> Result<SmtScript<T, D>, String>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    let mut reader = Reader {
+        signature,
+        domain,
+        store,
+        declared: BTreeSet::new(),
+        bound: Vec::new(),
+    };
+    let mut script = SmtScript {
+        assertions: Vec::new(),
+        check_sat: false,
+    };
+    for command in parse_sexprs(text)? {
+        reader.command(&command, &mut script)?;
+    }
+    Ok(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{signature, Formulas};
+
+    #[test]
+    fn test_parse_script() {
+        let f = Formulas::new();
+        let script = "\
+(set-logic UF)
+(declare-sort U 0)
+(declare-fun f (U) U)
+(declare-fun p (U) Bool)
+(declare-const a U)
+(assert (forall ((x U)) (=> (p x) (p (f x)))))
+(assert (! (and (p a) (distinct a (f a))) :named start))
+(check-sat)
+";
+        let parsed = parse_script(script, &signature(&f), &f.domain(), &f.store).unwrap();
+        assert!(parsed.check_sat);
+        assert_eq!(parsed.assertions.len(), 2);
+
+        // ∀x. p(x) → p(f(x))
+        let x = f.var(0);
+        let expected = f.forall(f.implies(f.p(x.clone()), f.p(f.f(x))));
+        assert_eq!(parsed.assertions[0], expected);
+        let a = f.a();
+        let distinct = f.not(f.equals(a.clone(), f.f(a.clone())));
+        assert_eq!(parsed.assertions[1], f.and(f.p(a), distinct));
+    }
+
+    #[test]
+    fn test_rejected_scripts() {
+        let f = Formulas::new();
+        let parse = |text| parse_script(text, &signature(&f), &f.domain(), &f.store).err();
+        assert_eq!(parse("(assert (p a))"), Some("Undeclared symbol p".to_string()));
+        assert_eq!(
+            parse("(declare-fun g (U) U)"),
+            Some("The signature has no g of sort U".to_string())
+        );
+        assert!(parse("(declare-const a U) (assert (p a))").is_some());
+        assert!(parse("(push 1)").is_some());
+    }
+}
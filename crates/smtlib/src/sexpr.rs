@@ -0,0 +1,108 @@
+//! S-expressions, the concrete syntax of SMT-LIB.
+
+use std::fmt;
+
+/// A symbol, numeral or keyword, or a parenthesized list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SExpr {
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+impl SExpr {
+    pub fn atom(name: impl Into<String>) -> Self {
+        SExpr::Atom(name.into())
+    }
+
+    /// The name of an atom, or `None` for a list.
+    pub fn as_atom(&self) -> Option<&str> {
+        match self {
+            SExpr::Atom(name) => Some(name),
+            SExpr::List(_) => None,
+        }
+    }
+
+    /// The head symbol and arguments of a list starting with an atom.
+    pub fn as_application(&self) -> Option<(&str, &[SExpr])> {
+        match self {
+            SExpr::List(items) => match items.split_first()? {
+                (SExpr::Atom(head), args) => Some((head, args)),
+                _ => None,
+            },
+            SExpr::Atom(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for SExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SExpr::Atom(name) => write!(f, "{}", name),
+            SExpr::List(items) => {
+                let items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+                write!(f, "({})", items.join(" "))
+            }
+        }
+    }
+}
+
+/// Read every S-expression of `text`, skipping `;` comments.
+///
+/// Quoted symbols `|…|` and string literals `"…"` are kept with their
+/// delimiters, so they print back unchanged.
+pub fn parse_sexprs(text: &str) -> Result<Vec<SExpr>, String> {
+    let mut stack: Vec<Vec<SExpr>> = vec![Vec::new()];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            ';' => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            '(' => stack.push(Vec::new()),
+            ')' => {
+                let items = stack.pop().filter(|_| !stack.is_empty()).ok_or("Unbalanced ')'")?;
+                stack.last_mut().expect("the top level stays on the stack").push(SExpr::List(items));
+            }
+            '|' | '"' => {
+                let mut atom = c.to_string();
+                loop {
+                    let next = chars.next().ok_or_else(|| format!("Unterminated {}", c))?;
+                    atom.push(next);
+                    if next == c {
+                        break;
+                    }
+                }
+                stack.last_mut().expect("the top level stays on the stack").push(SExpr::Atom(atom));
+            }
+            c => {
+                let mut atom = c.to_string();
+                while let Some(next) = chars.next_if(|&c| !c.is_whitespace() && !matches!(c, '(' | ')' | ';' | '|' | '"')) {
+                    atom.push(next);
+                }
+                stack.last_mut().expect("the top level stays on the stack").push(SExpr::Atom(atom));
+            }
+        }
+    }
+    match <[_; 1]>::try_from(stack) {
+        Ok([top]) => Ok(top),
+        Err(_) => Err("Unbalanced '('".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sexprs() {
+        let exprs = parse_sexprs("(assert (> x 0)) ; positive\n(check-sat) |a b| \"s\"").unwrap();
+        assert_eq!(exprs.len(), 4);
+        assert_eq!(exprs[0].to_string(), "(assert (> x 0))");
+        assert_eq!(exprs[1].as_application(), Some(("check-sat", &[][..])));
+        assert_eq!(exprs[2], SExpr::atom("|a b|"));
+
+        assert!(parse_sexprs("(a (b)").is_err());
+        assert!(parse_sexprs("a)").is_err());
+    }
+}
@@ -0,0 +1,110 @@
+//! Names, sorts and the logic of a domain as seen by SMT-LIB.
+
+use corpus_core::nodes::{HashNode, HashNodeInner};
+
+/// Symbols every SMT-LIB logic over `Int` or `Real` predefines; scripts
+/// use them without declaring them.
+const THEORY_SYMBOLS: &[&str] = &["=", "distinct", "+", "-", "*", "div", "mod", "abs", "/", "<", "<=", ">", ">="];
+
+/// Names for the symbols of a domain, the single sort its terms have, and
+/// the logic scripts declare.
+///
+/// Predicate and function symbols are opcodes; constants, which have no
+/// opcode, are named terms. The predicate named `=` is equality.
+pub struct SmtSignature<Term: HashNodeInner> {
+    pub(crate) logic: String,
+    pub(crate) sort: String,
+    predicates: Vec<(String, u64)>,
+    functions: Vec<(String, u64)>,
+    constants: Vec<(String, HashNode<Term>)>,
+}
+
+impl<Term: HashNodeInner> Default for SmtSignature<Term> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Term: HashNodeInner> SmtSignature<Term> {
+    /// A signature for uninterpreted functions over a declared sort `U`.
+    pub fn new() -> Self {
+        Self {
+            logic: "UF".to_string(),
+            sort: "U".to_string(),
+            predicates: Vec::new(),
+            functions: Vec::new(),
+            constants: Vec::new(),
+        }
+    }
+
+    /// The logic written by `set-logic`, e.g. `UFNIA`.
+    pub fn logic(mut self, logic: impl Into<String>) -> Self {
+        self.logic = logic.into();
+        self
+    }
+
+    /// The sort of every term, e.g. `Int`. Sorts other than `Int` and
+    /// `Real` are declared by exported scripts.
+    pub fn sort(mut self, sort: impl Into<String>) -> Self {
+        self.sort = sort.into();
+        self
+    }
+
+    pub fn predicate(mut self, name: impl Into<String>, opcode: u64) -> Self {
+        self.predicates.push((name.into(), opcode));
+        self
+    }
+
+    pub fn function(mut self, name: impl Into<String>, opcode: u64) -> Self {
+        self.functions.push((name.into(), opcode));
+        self
+    }
+
+    /// Name the constant `term`, e.g. a numeral such as `2`.
+    pub fn constant(mut self, name: impl Into<String>, term: HashNode<Term>) -> Self {
+        self.constants.push((name.into(), term));
+        self
+    }
+
+    pub(crate) fn predicate_opcode(&self, name: &str) -> Option<u64> {
+        lookup(&self.predicates, name).copied()
+    }
+
+    pub(crate) fn function_opcode(&self, name: &str) -> Option<u64> {
+        lookup(&self.functions, name).copied()
+    }
+
+    pub(crate) fn constant_term(&self, name: &str) -> Option<&HashNode<Term>> {
+        lookup(&self.constants, name)
+    }
+
+    pub(crate) fn predicate_name(&self, opcode: u64) -> Option<&str> {
+        name_of(&self.predicates, &opcode)
+    }
+
+    pub(crate) fn function_name(&self, opcode: u64) -> Option<&str> {
+        name_of(&self.functions, &opcode)
+    }
+
+    pub(crate) fn constant_name(&self, term: &HashNode<Term>) -> Option<&str> {
+        name_of(&self.constants, term)
+    }
+
+    /// Whether the sort is predefined by the arithmetic theories.
+    pub(crate) fn has_builtin_sort(&self) -> bool {
+        matches!(self.sort.as_str(), "Int" | "Real")
+    }
+}
+
+/// Whether `name` needs no declaration: a theory symbol or a numeral.
+pub(crate) fn is_predefined(name: &str) -> bool {
+    THEORY_SYMBOLS.contains(&name) || name.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+fn lookup<'a, V>(symbols: &'a [(String, V)], name: &str) -> Option<&'a V> {
+    symbols.iter().find(|(symbol, _)| symbol == name).map(|(_, value)| value)
+}
+
+fn name_of<'a, V: PartialEq>(symbols: &'a [(String, V)], value: &V) -> Option<&'a str> {
+    symbols.iter().find(|(_, symbol)| symbol == value).map(|(name, _)| name.as_str())
+}
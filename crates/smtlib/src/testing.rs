@@ -0,0 +1,185 @@
+//! A small first-order domain for the SMT-LIB tests: a constant `a`, a
+//! function `f`, a predicate `p` and equality.
+
+use corpus_classical_logic::first_order::FirstOrderDomain;
+use corpus_classical_logic::{BinaryTruth, ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::expression::DomainContent;
+use corpus_core::nodes::{HashNode, HashNodeInner, Hashing, NodeStorage};
+
+use crate::signature::SmtSignature;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    Var(u32),
+    A,
+    F(HashNode<Term>),
+}
+
+impl HashNodeInner for Term {
+    fn hash(&self) -> u64 {
+        match self {
+            Term::Var(index) => Hashing::root_hash(Hashing::opcode("var"), &[u64::from(*index)]),
+            Term::A => Hashing::root_hash(Hashing::opcode("a"), &[]),
+            Term::F(inner) => Hashing::root_hash(Hashing::opcode("f"), &[inner.hash()]),
+        }
+    }
+
+    fn size(&self) -> u64 {
+        match self {
+            Term::F(inner) => 1 + inner.size(),
+            _ => 1,
+        }
+    }
+
+    fn decompose(&self) -> Option<(u64, Vec<HashNode<Self>>)> {
+        match self {
+            Term::F(inner) => Some((Hashing::opcode("f"), vec![inner.clone()])),
+            _ => None,
+        }
+    }
+
+    fn construct_from_parts(opcode: u64, children: Vec<HashNode<Self>>, store: &NodeStorage<Self>) -> Option<HashNode<Self>> {
+        let [inner] = <[_; 1]>::try_from(children).ok()?;
+        (opcode == Hashing::opcode("f")).then(|| HashNode::from_store(Term::F(inner), store))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Atom {
+    P(HashNode<Term>),
+    Equals(HashNode<Term>, HashNode<Term>),
+}
+
+impl HashNodeInner for Atom {
+    fn hash(&self) -> u64 {
+        match self {
+            Atom::P(term) => Hashing::root_hash(Hashing::opcode("p"), &[term.hash()]),
+            Atom::Equals(left, right) => Hashing::root_hash(Hashing::opcode("="), &[left.hash(), right.hash()]),
+        }
+    }
+
+    fn size(&self) -> u64 {
+        match self {
+            Atom::P(term) => 1 + term.size(),
+            Atom::Equals(left, right) => 1 + left.size() + right.size(),
+        }
+    }
+}
+
+impl DomainContent<BinaryTruth> for Atom {
+    type Operator = ClassicalOperator;
+}
+
+pub type Formula = ClassicalLogicalExpression<BinaryTruth, Atom>;
+
+/// Storage for terms, atoms and formulas, with constructors for each.
+pub struct Formulas {
+    pub terms: NodeStorage<Term>,
+    pub atoms: NodeStorage<Atom>,
+    pub store: NodeStorage<Formula>,
+}
+
+impl Formulas {
+    pub fn new() -> Self {
+        Self {
+            terms: NodeStorage::new(),
+            atoms: NodeStorage::new(),
+            store: NodeStorage::new(),
+        }
+    }
+
+    pub fn domain(&self) -> Domain<'_> {
+        Domain(self)
+    }
+
+    pub fn var(&self, index: u32) -> HashNode<Term> {
+        HashNode::from_store(Term::Var(index), &self.terms)
+    }
+
+    pub fn a(&self) -> HashNode<Term> {
+        HashNode::from_store(Term::A, &self.terms)
+    }
+
+    pub fn f(&self, inner: HashNode<Term>) -> HashNode<Term> {
+        HashNode::from_store(Term::F(inner), &self.terms)
+    }
+
+    fn atomic(&self, atom: Atom) -> HashNode<Formula> {
+        let atom = HashNode::from_store(atom, &self.atoms);
+        HashNode::from_store(Formula::atomic(atom), &self.store)
+    }
+
+    pub fn p(&self, term: HashNode<Term>) -> HashNode<Formula> {
+        self.atomic(Atom::P(term))
+    }
+
+    pub fn equals(&self, left: HashNode<Term>, right: HashNode<Term>) -> HashNode<Formula> {
+        self.atomic(Atom::Equals(left, right))
+    }
+
+    fn compound(&self, operator: ClassicalOperator, operands: Vec<HashNode<Formula>>) -> HashNode<Formula> {
+        HashNode::from_store(Formula::compound(operator, operands), &self.store)
+    }
+
+    pub fn not(&self, inner: HashNode<Formula>) -> HashNode<Formula> {
+        self.compound(ClassicalOperator::Not, vec![inner])
+    }
+
+    pub fn and(&self, left: HashNode<Formula>, right: HashNode<Formula>) -> HashNode<Formula> {
+        self.compound(ClassicalOperator::And, vec![left, right])
+    }
+
+    pub fn implies(&self, left: HashNode<Formula>, right: HashNode<Formula>) -> HashNode<Formula> {
+        self.compound(ClassicalOperator::Implies, vec![left, right])
+    }
+
+    pub fn forall(&self, body: HashNode<Formula>) -> HashNode<Formula> {
+        self.compound(ClassicalOperator::Forall, vec![body])
+    }
+}
+
+/// The symbols of the test domain under their SMT-LIB names.
+pub fn signature(f: &Formulas) -> SmtSignature<Term> {
+    SmtSignature::new()
+        .predicate("p", Hashing::opcode("p"))
+        .predicate("=", Hashing::opcode("="))
+        .function("f", Hashing::opcode("f"))
+        .constant("a", f.a())
+}
+
+pub struct Domain<'a>(&'a Formulas);
+
+impl FirstOrderDomain<BinaryTruth, Atom> for Domain<'_> {
+    type Term = Term;
+
+    fn predicate(&self, atom: &HashNode<Atom>) -> Option<(u64, Vec<HashNode<Term>>)> {
+        match atom.value.as_ref() {
+            Atom::P(term) => Some((Hashing::opcode("p"), vec![term.clone()])),
+            Atom::Equals(left, right) => Some((Hashing::opcode("="), vec![left.clone(), right.clone()])),
+        }
+    }
+
+    fn atom(&self, predicate: u64, arguments: Vec<HashNode<Term>>) -> Option<HashNode<Atom>> {
+        let atom = match arguments.as_slice() {
+            [term] if predicate == Hashing::opcode("p") => Atom::P(term.clone()),
+            [left, right] if predicate == Hashing::opcode("=") => Atom::Equals(left.clone(), right.clone()),
+            _ => return None,
+        };
+        Some(HashNode::from_store(atom, &self.0.atoms))
+    }
+
+    fn bound_index(&self, term: &HashNode<Term>) -> Option<u32> {
+        match term.value.as_ref() {
+            Term::Var(index) => Some(*index),
+            _ => None,
+        }
+    }
+
+    fn variable(&self, index: u32) -> HashNode<Term> {
+        self.0.var(index)
+    }
+
+    fn terms(&self) -> &NodeStorage<Term> {
+        &self.0.terms
+    }
+}
@@ -0,0 +1,213 @@
+//! Exporting goals as SMT-LIB scripts.
+//!
+//! A goal `φ` under axioms `A₁ … Aₙ` becomes a script asserting the axioms
+//! and `(not φ)`, then asking `check-sat`: the goal is valid exactly when
+//! the solver answers `unsat`. Every symbol the formulas use is declared
+//! first, with the arity it is used at, except the theory symbols and
+//! numerals of the signature's logic. The variable of the quantifier at
+//! nesting depth `d` is named `x<d>`.
+
+use corpus_classical_logic::first_order::FirstOrderDomain;
+use corpus_classical_logic::{ClassicalLogicalExpression, ClassicalOperator};
+use corpus_core::expression::{DomainContent, LogicalExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner};
+use corpus_core::truth::TruthValue;
+
+use crate::signature::{is_predefined, SmtSignature};
+
+/// A symbol to declare: its name, number of arguments and whether it is a
+/// predicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Declaration {
+    name: String,
+    arity: usize,
+    predicate: bool,
+}
+
+struct Writer<'a, T, D, F>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    signature: &'a SmtSignature<F::Term>,
+    domain: &'a F,
+    /// Symbols used so far, in order of first use.
+    declarations: Vec<Declaration>,
+    _formulas: std::marker::PhantomData<fn(T, D)>,
+}
+
+impl<T, D, F> Writer<'_, T, D, F>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    fn declare(&mut self, name: &str, arity: usize, predicate: bool) {
+        if !is_predefined(name) && !self.declarations.iter().any(|declaration| declaration.name == name) {
+            self.declarations.push(Declaration { name: name.to_string(), arity, predicate });
+        }
+    }
+
+    fn formula(&mut self, expr: &HashNode<ClassicalLogicalExpression<T, D>>, depth: u32) -> Result<String, String> {
+        let (operator, operands) = match expr.value.as_ref() {
+            LogicalExpression::Atomic(atom) => return self.atom(atom, depth),
+            LogicalExpression::Compound { operator, operands, .. } => (operator, operands),
+        };
+        let (head, depth) = match operator {
+            ClassicalOperator::Forall | ClassicalOperator::Exists => {
+                let quantifier = if *operator == ClassicalOperator::Forall { "forall" } else { "exists" };
+                (format!("{} ((x{} {}))", quantifier, depth, self.signature.sort), depth + 1)
+            }
+            ClassicalOperator::Not => ("not".to_string(), depth),
+            ClassicalOperator::And => ("and".to_string(), depth),
+            ClassicalOperator::Or => ("or".to_string(), depth),
+            ClassicalOperator::Implies => ("=>".to_string(), depth),
+            ClassicalOperator::Iff | ClassicalOperator::Equals => ("=".to_string(), depth),
+        };
+        let operands = operands.iter().map(|operand| self.formula(operand, depth)).collect::<Result<Vec<_>, _>>()?;
+        Ok(format!("({} {})", head, operands.join(" ")))
+    }
+
+    fn atom(&mut self, atom: &HashNode<D>, depth: u32) -> Result<String, String> {
+        let (predicate, arguments) = self.domain.predicate(atom).ok_or("An atom has no first-order structure")?;
+        let name = self.signature.predicate_name(predicate).ok_or("Unnamed predicate symbol")?;
+        self.declare(name, arguments.len(), true);
+        self.application(name, &arguments, depth)
+    }
+
+    fn term(&mut self, term: &HashNode<F::Term>, depth: u32) -> Result<String, String> {
+        if let Some(index) = self.domain.bound_index(term) {
+            return match depth.checked_sub(index + 1) {
+                Some(binder) => Ok(format!("x{}", binder)),
+                None => Err(format!("Free variable /{}", index)),
+            };
+        }
+        if let Some(name) = self.signature.constant_name(term) {
+            self.declare(name, 0, false);
+            return Ok(name.to_string());
+        }
+        let (opcode, arguments) = term.value.decompose().ok_or("Unnamed constant")?;
+        let name = self.signature.function_name(opcode).ok_or("Unnamed function symbol")?;
+        self.declare(name, arguments.len(), false);
+        self.application(name, &arguments, depth)
+    }
+
+    fn application(&mut self, name: &str, arguments: &[HashNode<F::Term>], depth: u32) -> Result<String, String> {
+        if arguments.is_empty() {
+            return Ok(name.to_string());
+        }
+        let arguments = arguments.iter().map(|argument| self.term(argument, depth)).collect::<Result<Vec<_>, _>>()?;
+        Ok(format!("({} {})", name, arguments.join(" ")))
+    }
+
+    /// The `declare-sort` and `declare-fun` commands for the symbols used.
+    fn declarations(&self) -> Vec<String> {
+        let sort = &self.signature.sort;
+        let mut lines = Vec::new();
+        if !self.signature.has_builtin_sort() {
+            lines.push(format!("(declare-sort {} 0)", sort));
+        }
+        for declaration in &self.declarations {
+            let arguments = vec![sort.as_str(); declaration.arity].join(" ");
+            let result = if declaration.predicate { "Bool" } else { sort };
+            lines.push(format!("(declare-fun {} ({}) {})", declaration.name, arguments, result));
+        }
+        lines
+    }
+}
+
+fn writer<'a, T, D, F>(signature: &'a SmtSignature<F::Term>, domain: &'a F) -> Writer<'a, T, D, F>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    Writer {
+        signature,
+        domain,
+        declarations: Vec::new(),
+        _formulas: std::marker::PhantomData,
+    }
+}
+
+/// Write `expr` as an SMT-LIB term.
+///
+/// Fails if `expr` has a free variable, or a symbol `signature` does not name.
+pub fn format_formula<T, D, F>(
+    expr: &HashNode<ClassicalLogicalExpression<T, D>>,
+    signature: &SmtSignature<F::Term>,
+    domain: &F,
+) -> Result<String, String>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    writer(signature, domain).formula(expr, 0)
+}
+
+/// A script that is `unsat` exactly when `goal` follows from `axioms`.
+pub fn export_goal<T, D, F>(
+    axioms: &[HashNode<ClassicalLogicalExpression<T, D>>],
+    goal: &HashNode<ClassicalLogicalExpression<T, D>>,
+    signature: &SmtSignature<F::Term>,
+    domain: &F,
+) -> Result<String, String>
+where
+    T: TruthValue + HashNodeInner,
+    D: DomainContent<T>,
+    F: FirstOrderDomain<T, D>,
+{
+    let mut writer = writer(signature, domain);
+    let mut assertions = Vec::new();
+    for axiom in axioms {
+        assertions.push(format!("(assert {})", writer.formula(axiom, 0)?));
+    }
+    assertions.push(format!("(assert (not {}))", writer.formula(goal, 0)?));
+
+    let mut lines = vec![format!("(set-logic {})", signature.logic)];
+    lines.extend(writer.declarations());
+    lines.extend(assertions);
+    lines.push("(check-sat)".to_string());
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::parse_script;
+    use crate::testing::{signature, Formulas};
+
+    #[test]
+    fn test_export_goal() {
+        let f = Formulas::new();
+        let x = f.var(0);
+        let axiom = f.forall(f.implies(f.p(x.clone()), f.p(f.f(x))));
+        let goal = f.p(f.f(f.f(f.a())));
+        let start = f.p(f.a());
+
+        let script = export_goal(&[axiom.clone(), start.clone()], &goal, &signature(&f), &f.domain()).unwrap();
+        assert_eq!(
+            script,
+            "\
+(set-logic UF)
+(declare-sort U 0)
+(declare-fun p (U) Bool)
+(declare-fun f (U) U)
+(declare-fun a () U)
+(assert (forall ((x0 U)) (=> (p x0) (p (f x0)))))
+(assert (p a))
+(assert (not (p (f (f a)))))
+(check-sat)
+"
+        );
+
+        // The script reads back as the formulas it was written from.
+        let parsed = parse_script(&script, &signature(&f), &f.domain(), &f.store).unwrap();
+        assert_eq!(parsed.assertions, vec![axiom, start, f.not(goal)]);
+
+        let open = f.p(f.var(0));
+        assert_eq!(format_formula(&open, &signature(&f), &f.domain()), Err("Free variable /0".to_string()));
+    }
+}
@@ -0,0 +1,132 @@
+//! Re-checking proof certificates without the prover.
+//!
+//! Checking a certificate is syntactic: a rule applies wherever its pattern
+//! matches, whatever the terms mean. So expressions are read as `Tree`s, an
+//! opcode applied to children or an atom holding its text, and no domain
+//! crate is needed: rules and certificates a domain writes through its
+//! `AtomCodec` read back as trees with the same opcodes and atoms.
+//!
+//! A rule file holds one rule per line in the syntax of `RewriteRule::parse`;
+//! blank lines and lines starting with `;` are skipped.
+
+use std::fmt;
+
+use corpus_core::base::codec::AtomCodec;
+use corpus_core::base::sexpr::SExprError;
+use corpus_core::nodes::{HashNode, HashNodeInner, Hashing, NodeStorage};
+use corpus_core::rewriting::RewriteRule;
+
+/// An expression of any domain, as its opcodes and atom texts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tree {
+    Atom(String),
+    Apply(u64, Vec<HashNode<Tree>>),
+}
+
+impl HashNodeInner for Tree {
+    fn hash(&self) -> u64 {
+        match self {
+            Tree::Atom(text) => Hashing::root_hash(Hashing::opcode("atom"), &[Hashing::opcode(text)]),
+            Tree::Apply(opcode, children) => {
+                let hashes: Vec<u64> = children.iter().map(HashNode::hash).collect();
+                Hashing::root_hash(*opcode, &hashes)
+            }
+        }
+    }
+
+    fn size(&self) -> u64 {
+        match self {
+            Tree::Atom(_) => 1,
+            Tree::Apply(_, children) => 1 + children.iter().map(HashNode::size).sum::<u64>(),
+        }
+    }
+
+    fn decompose(&self) -> Option<(u64, Vec<HashNode<Self>>)> {
+        match self {
+            Tree::Atom(_) => None,
+            Tree::Apply(opcode, children) => Some((*opcode, children.clone())),
+        }
+    }
+
+    fn construct_from_parts(opcode: u64, children: Vec<HashNode<Self>>, store: &NodeStorage<Self>) -> Option<HashNode<Self>> {
+        Some(HashNode::from_store(Tree::Apply(opcode, children), store))
+    }
+}
+
+/// Atoms are their own text.
+pub struct TreeCodec;
+
+impl AtomCodec<Tree> for TreeCodec {
+    fn encode_atom(&self, atom: &Tree) -> Option<String> {
+        match atom {
+            Tree::Atom(text) => Some(text.clone()),
+            Tree::Apply(..) => None,
+        }
+    }
+
+    fn decode_atom(&self, text: &str, store: &NodeStorage<Tree>) -> Option<HashNode<Tree>> {
+        Some(HashNode::from_store(Tree::Atom(text.to_string()), store))
+    }
+}
+
+/// A rule file line that does not parse, at a line counting from 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleFileError {
+    pub line: usize,
+    pub error: SExprError,
+}
+
+impl fmt::Display for RuleFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for RuleFileError {}
+
+/// The rules of a rule file.
+pub fn read_rules(text: &str) -> Result<Vec<RewriteRule<Tree>>, RuleFileError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with(';'))
+        .map(|(index, line)| RewriteRule::parse(line, &TreeCodec).map_err(|error| RuleFileError { line: index + 1, error }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use corpus_core::proving::{verify_proof, ProofCertificate, ProofCheckError};
+
+    const RULES: &str = "\
+; Addition on unary numerals
+add_zero: (add ?x 0) => ?x
+add_succ: (add ?x (succ ?y)) => (succ (add ?x ?y))
+";
+
+    const CERTIFICATE: &str = r#"{"schema": "corpus-certificate/1",
+        "initial": "(eq (add 0 (succ 0)) (succ 0))", "final": "(eq (succ 0) (succ 0))",
+        "steps": [
+            {"rule": "add_succ", "direction": "forward", "position": [0], "substitution": {"0": "0", "1": "0"},
+             "from": "(eq (add 0 (succ 0)) (succ 0))", "to": "(eq (succ (add 0 0)) (succ 0))"},
+            {"rule": "add_zero", "direction": "forward", "position": [0, 0], "substitution": {"0": "0"},
+             "from": "(eq (succ (add 0 0)) (succ 0))", "to": "(eq (succ 0) (succ 0))"}],
+        "instantiation": []}"#;
+
+    #[test]
+    fn test_verify_written_certificate() {
+        let store = NodeStorage::new();
+        let rules = read_rules(RULES).unwrap();
+        let certificate = ProofCertificate::read(CERTIFICATE.as_bytes(), &TreeCodec, &store).unwrap();
+        assert_eq!(verify_proof(&certificate, &rules, &store), Ok(()));
+
+        let binary = certificate.to_binary(&TreeCodec).unwrap();
+        let read = ProofCertificate::read(&binary, &TreeCodec, &store).unwrap();
+        assert_eq!(verify_proof(&read, &rules, &store), Ok(()));
+
+        let tampered = CERTIFICATE.replace("\"position\": [0, 0]", "\"position\": [1]");
+        let certificate = ProofCertificate::read(tampered.as_bytes(), &TreeCodec, &store).unwrap();
+        assert_eq!(verify_proof(&certificate, &rules, &store), Err(ProofCheckError::RuleMismatch { step: 1, rule: "add_zero".to_string() }));
+        assert_eq!(read_rules("\nadd_zero (add ?x 0) => ?x").err().map(|e| e.to_string()).as_deref(), Some("line 2: offset 0: expected `name:`"));
+    }
+}
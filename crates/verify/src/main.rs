@@ -0,0 +1,57 @@
+use corpus_core::nodes::NodeStorage;
+use corpus_core::proving::{verify_proof, ProofCertificate};
+use corpus_verify::{read_rules, Tree, TreeCodec};
+
+const USAGE: &str = "\
+Usage: corpus-verify <rules> <certificate>...
+
+Re-checks each certificate, JSON or binary, against the rewrite rules in
+<rules>, one `name: pattern => replacement` per line. Exits with 0 when
+every certificate checks, 1 when one is rejected and 2 when an input
+cannot be read.";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| matches!(arg.as_str(), "help" | "--help" | "-h")) {
+        println!("{}", USAGE);
+        return;
+    }
+    let [rules_path, certificates @ ..] = args.as_slice() else {
+        usage();
+    };
+    if certificates.is_empty() {
+        usage();
+    }
+
+    let rules = match std::fs::read_to_string(rules_path).map_err(|e| e.to_string()).and_then(|text| read_rules(&text).map_err(|e| e.to_string())) {
+        Ok(rules) => rules,
+        Err(e) => fail(rules_path, &e),
+    };
+
+    let mut rejected = false;
+    for path in certificates {
+        let store = NodeStorage::new();
+        let bytes = std::fs::read(path).unwrap_or_else(|e| fail(path, &e.to_string()));
+        let certificate = ProofCertificate::<Tree>::read(&bytes, &TreeCodec, &store).unwrap_or_else(|e| fail(path, &e.to_string()));
+        match verify_proof(&certificate, &rules, &store) {
+            Ok(()) => println!("{}: verified, {} step{}", path, certificate.len(), if certificate.len() == 1 { "" } else { "s" }),
+            Err(e) => {
+                println!("{}: rejected: {}", path, e);
+                rejected = true;
+            }
+        }
+    }
+    if rejected {
+        std::process::exit(1);
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
+
+fn fail(path: &str, message: &str) -> ! {
+    eprintln!("Error: {}: {}", path, message);
+    std::process::exit(2);
+}
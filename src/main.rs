@@ -0,0 +1,179 @@
+use std::io::Read;
+
+use corpus_core::nodes::NodeStorage;
+use corpus_core::proving::{verify_proof, ProofCertificate};
+use corpus_verify::{read_rules, Tree, TreeCodec};
+use peano_arithmetic::batch::{parse_goals, BatchProver};
+use peano_arithmetic::parsing::Parser;
+use peano_arithmetic::printer::{Charset, Printer};
+use peano_arithmetic::rewrite::normal_form;
+use peano_arithmetic::stores::PeanoStores;
+
+const USAGE: &str = "\
+Usage: corpus <command> [<formula> | --file <path> | -]
+       corpus check-cert --rules <path> [<certificate> | -]
+
+Commands:
+  parse       Print each formula in Unicode and in the keyword (prefix) form
+  prove       Prove each formula in order, later goals using earlier ones
+  normalize   Rewrite the terms of each relation with the PA rules
+  check-cert  Re-check a certificate, JSON or binary, against the rewrite
+              rules in a rule file, as `corpus-verify` does
+
+A formula given as arguments is read as one goal. A file, or standard input
+when no formula or `-` is given, holds one goal per line; blank lines and
+everything after `#` are ignored. A certificate is likewise read from
+standard input when no path or `-` is given.";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, input)) = args.split_first() else {
+        usage();
+    };
+    if matches!(command.as_str(), "help" | "--help" | "-h") {
+        println!("{}", USAGE);
+        return;
+    }
+    if command == "check-cert" {
+        match check_cert(input) {
+            Ok(true) => return,
+            Ok(false) => std::process::exit(1),
+            Err(e) => fail(&e),
+        }
+    }
+    let goals = read_goals(input).unwrap_or_else(|e| fail(&e));
+
+    let succeeded = match command.as_str() {
+        "parse" => parse(&goals),
+        "prove" => prove(&goals),
+        "normalize" => normalize(&goals),
+        other => {
+            eprintln!("Unknown command '{}'", other);
+            usage();
+        }
+    };
+    if !succeeded {
+        std::process::exit(1);
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("Error: {}", message);
+    std::process::exit(1);
+}
+
+/// The goals named by the arguments after the command, with their lines.
+fn read_goals(input: &[String]) -> Result<Vec<(usize, String)>, String> {
+    match input {
+        [] => read_stdin(),
+        [dash] if dash == "-" => read_stdin(),
+        [flag, path] if flag == "--file" => {
+            let text = std::fs::read_to_string(path).map_err(|e| format!("reading {}: {}", path, e))?;
+            Ok(parse_goals(&text))
+        }
+        [flag] if flag == "--file" => Err("--file needs a path".to_string()),
+        words => Ok(vec![(1, words.join(" "))]),
+    }
+}
+
+fn read_stdin() -> Result<Vec<(usize, String)>, String> {
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text).map_err(|e| format!("reading standard input: {}", e))?;
+    Ok(parse_goals(&text))
+}
+
+/// Check the certificate named by the arguments, returning whether it holds.
+fn check_cert(input: &[String]) -> Result<bool, String> {
+    let [flag, rules_path, certificate @ ..] = input else {
+        return Err("check-cert needs --rules <path>".to_string());
+    };
+    if flag != "--rules" {
+        return Err("check-cert needs --rules <path>".to_string());
+    }
+    let text = std::fs::read_to_string(rules_path).map_err(|e| format!("reading {}: {}", rules_path, e))?;
+    let rules = read_rules(&text).map_err(|e| format!("{}: {}", rules_path, e))?;
+    let (name, bytes) = match certificate {
+        [] => ("standard input", read_stdin_bytes()?),
+        [dash] if dash == "-" => ("standard input", read_stdin_bytes()?),
+        [path] => (path.as_str(), std::fs::read(path).map_err(|e| format!("reading {}: {}", path, e))?),
+        _ => return Err("check-cert reads one certificate".to_string()),
+    };
+
+    let store = NodeStorage::new();
+    let certificate = ProofCertificate::<Tree>::read(&bytes, &TreeCodec, &store).map_err(|e| format!("{}: {}", name, e))?;
+    match verify_proof(&certificate, &rules, &store) {
+        Ok(()) => {
+            println!("{}: verified, {} step{}", name, certificate.len(), if certificate.len() == 1 { "" } else { "s" });
+            Ok(true)
+        }
+        Err(e) => {
+            println!("{}: rejected: {}", name, e);
+            Ok(false)
+        }
+    }
+}
+
+fn read_stdin_bytes() -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    std::io::stdin().read_to_end(&mut bytes).map_err(|e| format!("reading standard input: {}", e))?;
+    Ok(bytes)
+}
+
+fn parse(goals: &[(usize, String)]) -> bool {
+    let stores = PeanoStores::new();
+    let keyword = Printer::new().with_charset(Charset::Keyword);
+    let mut succeeded = true;
+    for (line, goal) in goals {
+        match Parser::with_stores(goal, &stores).parse_formula_recovering() {
+            Ok(formula) => {
+                println!("{}", Printer::default().expression(&formula.value));
+                println!("  {}", keyword.expression(&formula.value));
+            }
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("line {}: {}", line, error);
+                }
+                succeeded = false;
+            }
+        }
+    }
+    succeeded
+}
+
+fn prove(goals: &[(usize, String)]) -> bool {
+    let report = BatchProver::new(10000).prove_all(goals);
+    println!("{}", report);
+    report.proved() == report.goals.len()
+}
+
+fn normalize(goals: &[(usize, String)]) -> bool {
+    let stores = PeanoStores::new();
+    let mut succeeded = true;
+    for (line, goal) in goals {
+        let formula = match Parser::with_stores(goal, &stores).parse_formula() {
+            Ok(formula) => formula,
+            Err(e) => {
+                eprintln!("line {}: {}", line, e);
+                succeeded = false;
+                continue;
+            }
+        };
+        let normal = formula.value.as_domain().and_then(|content| {
+            let terms = content.value.terms().into_iter().map(|term| normal_form(term, &stores.expression_store));
+            content.value.with_terms(terms.collect())
+        });
+        match normal {
+            Some(content) => println!("{}", Printer::default().content(&content)),
+            None => {
+                eprintln!("line {}: only relations and predicates can be normalized", line);
+                succeeded = false;
+            }
+        }
+    }
+    succeeded
+}
@@ -0,0 +1,106 @@
+//! The field axioms, and derived rules normalizing negation and inverses.
+//!
+//! Each rule is written as a goal in the infix syntax. Its `x ≠ 0`
+//! hypotheses become side conditions, so `x ≠ 0 -> x · x⁻¹ = 1` only fires
+//! where the prover's `FieldFacts` show its argument non-zero.
+
+use corpus_core::nodes::HashNode;
+use corpus_core::rewriting::{ConditionalRule, Pattern, RewriteDirection, RewriteRule, SideCondition};
+
+use crate::eval::variables;
+use crate::parsing::Parser;
+use crate::syntax::FieldTerm;
+
+/// The predicate of the side conditions, `t ≠ 0`.
+pub const NONZERO: &str = "nonzero";
+
+/// `term` as a pattern whose variables match any term.
+fn pattern(term: &HashNode<FieldTerm>) -> Pattern<FieldTerm> {
+    Pattern::from_term(term, &|t: &HashNode<FieldTerm>| match t.value.as_ref() {
+        FieldTerm::DeBruijn(index) => Some(Pattern::var(*index)),
+        _ => None,
+    })
+}
+
+/// Parse the goal `text`, e.g. `x ≠ 0 -> x · x⁻¹ = 1`, into a rule named
+/// `name` conditioned on its hypotheses.
+///
+/// As with unconditional equations, a side may only be rewritten into one
+/// whose variables it binds, so the rule runs in whichever directions allow
+/// that.
+pub fn equation_rule(name: &str, text: &str) -> Result<ConditionalRule<FieldTerm>, String> {
+    let goal = Parser::new(text).parse_goal()?;
+    let (left, right) = goal.equation.value.sides();
+    let (left_vars, right_vars) = (variables(left), variables(right));
+    let direction = match (right_vars.is_subset(&left_vars), left_vars.is_subset(&right_vars)) {
+        (true, true) => RewriteDirection::Both,
+        (true, false) => RewriteDirection::Forward,
+        (false, true) => RewriteDirection::Backward,
+        (false, false) => return Err(format!("Neither side of {} binds the other's variables", text)),
+    };
+    let rule = RewriteRule::new(name, pattern(left), pattern(right), direction);
+    let conditions = goal.nonzero.iter().map(|term| SideCondition::new(NONZERO, pattern(term))).collect();
+    Ok(ConditionalRule::new(rule, conditions))
+}
+
+/// The field axioms: a commutative ring in which non-zero elements have
+/// multiplicative inverses.
+const FIELD_AXIOMS: &[(&str, &str)] = &[
+    ("add_identity", "x + 0 = x"),
+    ("add_inverse", "x + -x = 0"),
+    ("add_commutative", "x + y = y + x"),
+    ("add_associative", "(x + y) + z = x + (y + z)"),
+    ("mul_identity", "x * 1 = x"),
+    ("mul_inverse", "x ≠ 0 -> x * x⁻¹ = 1"),
+    ("mul_commutative", "x * y = y * x"),
+    ("mul_associative", "(x * y) * z = x * (y * z)"),
+    ("distributive", "x * (y + z) = x * y + x * z"),
+];
+
+/// Consequences of the axioms that push negation and inversion towards the
+/// variables.
+const DERIVED_RULES: &[(&str, &str)] = &[
+    ("double_negation", "--x = x"),
+    ("negate_add", "-(x + y) = -x + -y"),
+    ("negate_multiply", "-x * y = -(x * y)"),
+    ("mul_zero", "x * 0 = 0"),
+    ("inverse_inverse", "x ≠ 0 -> x⁻¹⁻¹ = x"),
+    ("inverse_multiply", "x ≠ 0 ∧ y ≠ 0 -> (x * y)⁻¹ = x⁻¹ * y⁻¹"),
+];
+
+fn rules(table: &[(&str, &str)]) -> Vec<ConditionalRule<FieldTerm>> {
+    table
+        .iter()
+        .map(|(name, text)| equation_rule(name, text).unwrap_or_else(|e| panic!("Failed to parse {}: {}", name, e)))
+        .collect()
+}
+
+/// The field axioms as rewrite rules.
+pub fn field_axiom_rules() -> Vec<ConditionalRule<FieldTerm>> {
+    rules(FIELD_AXIOMS)
+}
+
+/// The field axioms followed by the derived rules.
+pub fn field_rules() -> Vec<ConditionalRule<FieldTerm>> {
+    let mut all = field_axiom_rules();
+    all.extend(rules(DERIVED_RULES));
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provisos_become_conditions() {
+        let rules = field_rules();
+        let rule = |name: &str| rules.iter().find(|rule| rule.name() == name).unwrap();
+        assert!(rule("add_commutative").conditions.is_empty());
+        assert_eq!(rule("mul_inverse").conditions.len(), 1);
+        assert_eq!(rule("mul_inverse").rule.direction, RewriteDirection::Forward);
+        assert_eq!(rule("inverse_multiply").conditions.len(), 2);
+
+        assert!(equation_rule("bad", "x + y = z").is_err());
+        assert!(equation_rule("bad", "x = 0 -> x * y = 0").is_err());
+    }
+}
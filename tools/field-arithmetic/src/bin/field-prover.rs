@@ -0,0 +1,46 @@
+use corpus_core::printing::ExprPrinter;
+use field_arithmetic::parsing::Parser;
+use field_arithmetic::prove_field;
+use field_arithmetic::stores::FieldStores;
+use field_arithmetic::syntax::printer;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 {
+        println!("Usage: {} <goal>", args[0]);
+        println!();
+        println!("Example: {} \"a ≠ 0 ∧ b ≠ 0 -> (a * b)⁻¹ = b⁻¹ * a⁻¹\"", args[0]);
+        println!("         {} \"a != 0 -> (a * b) / a = b\"", args[0]);
+        println!();
+        println!("Terms: +, - (binary and unary), * or ·, /, postfix ⁻¹ or inv(..), integers and variable names");
+        println!("Goals: an equation, optionally after hypotheses t ≠ 0 (or t != 0) joined by ∧ or && and followed by ->");
+        std::process::exit(1);
+    }
+
+    let stores = FieldStores::new();
+    let goal = match Parser::with_stores(&args[1], &stores).parse_goal() {
+        Ok(goal) => goal,
+        Err(e) => {
+            eprintln!("Parse error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let printer = printer();
+    let hypotheses: Vec<_> = goal.nonzero.iter().map(|term| format!("{} ≠ 0", printer.print(term.value.as_ref()))).collect();
+    println!("Hypotheses: {}", if hypotheses.is_empty() { "none".to_string() } else { hypotheses.join(", ") });
+    println!("Goal: {}", printer.print(goal.equation.value.as_ref()));
+    println!();
+
+    println!("Searching for proof (max 10000 nodes)...");
+    match prove_field(&goal, &stores, 10000) {
+        Some(result) => {
+            let verdict = if result.truth_result.into() { "✓ Theorem proved!" } else { "✗ Statement refuted" };
+            println!("{}", verdict);
+            println!("Nodes explored: {}", result.nodes_explored);
+            for step in &result.steps {
+                println!("  {}  [{}]", printer.print(step.new_expr.value.as_ref()), step.rule_name);
+            }
+        }
+        None => println!("✗ Could not prove theorem (reached limit)"),
+    }
+}
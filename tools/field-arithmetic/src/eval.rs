@@ -0,0 +1,130 @@
+//! Ground evaluation of field terms in the rationals.
+
+use std::collections::BTreeSet;
+
+use corpus_core::nodes::{HashNode, NodeStorage};
+
+use crate::syntax::FieldTerm;
+
+/// A rational number in lowest terms, with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rational {
+    pub numerator: i64,
+    pub denominator: i64,
+}
+
+impl Rational {
+    /// `numerator / denominator` in lowest terms, or `None` for a zero
+    /// denominator or on overflow.
+    pub fn new(numerator: i64, denominator: i64) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let divisor = gcd(numerator, denominator);
+        let sign = if denominator < 0 { -1 } else { 1 };
+        Some(Self {
+            numerator: (numerator / divisor).checked_mul(sign)?,
+            denominator: (denominator / divisor).checked_mul(sign)?,
+        })
+    }
+
+    pub fn integer(n: i64) -> Self {
+        Self { numerator: n, denominator: 1 }
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numerator == 0
+    }
+
+    fn add(self, other: Self) -> Option<Self> {
+        let numerator = self
+            .numerator
+            .checked_mul(other.denominator)?
+            .checked_add(other.numerator.checked_mul(self.denominator)?)?;
+        Self::new(numerator, self.denominator.checked_mul(other.denominator)?)
+    }
+
+    fn multiply(self, other: Self) -> Option<Self> {
+        Self::new(self.numerator.checked_mul(other.numerator)?, self.denominator.checked_mul(other.denominator)?)
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1) as i64
+}
+
+/// Evaluate a variable-free term.
+///
+/// Returns `None` if `term` has a variable, inverts zero, or overflows.
+pub fn eval(term: &HashNode<FieldTerm>) -> Option<Rational> {
+    match term.value.as_ref() {
+        FieldTerm::Number(n) => Some(Rational::integer(*n)),
+        FieldTerm::DeBruijn(_) => None,
+        FieldTerm::Add(left, right) => eval(left)?.add(eval(right)?),
+        FieldTerm::Multiply(left, right) => eval(left)?.multiply(eval(right)?),
+        FieldTerm::Negate(inner) => {
+            let value = eval(inner)?;
+            Rational::new(value.numerator.checked_neg()?, value.denominator)
+        }
+        FieldTerm::Inverse(inner) => {
+            let value = eval(inner)?;
+            Rational::new(value.denominator, value.numerator)
+        }
+    }
+}
+
+/// The term denoting `value`: an integer literal, or a literal times the
+/// inverse of one.
+pub fn literal(value: Rational, store: &NodeStorage<FieldTerm>) -> HashNode<FieldTerm> {
+    let numerator = HashNode::from_store(FieldTerm::Number(value.numerator), store);
+    if value.denominator == 1 {
+        return numerator;
+    }
+    let denominator = HashNode::from_store(FieldTerm::Number(value.denominator), store);
+    let inverse = HashNode::from_store(FieldTerm::Inverse(denominator), store);
+    HashNode::from_store(FieldTerm::Multiply(numerator, inverse), store)
+}
+
+/// Replace a variable-free term by the literal it evaluates to.
+///
+/// Returns `None` if `term` is already that literal or cannot be evaluated.
+pub fn fold_constant(term: &HashNode<FieldTerm>, store: &NodeStorage<FieldTerm>) -> Option<HashNode<FieldTerm>> {
+    let folded = literal(eval(term)?, store);
+    (folded != *term).then_some(folded)
+}
+
+/// Collect the De Bruijn indices occurring in `term`.
+pub fn variables(term: &HashNode<FieldTerm>) -> BTreeSet<u32> {
+    match term.value.as_ref() {
+        FieldTerm::DeBruijn(idx) => BTreeSet::from([*idx]),
+        FieldTerm::Number(_) => BTreeSet::new(),
+        FieldTerm::Negate(inner) | FieldTerm::Inverse(inner) => variables(inner),
+        FieldTerm::Add(left, right) | FieldTerm::Multiply(left, right) => {
+            variables(left).union(&variables(right)).copied().collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    #[test]
+    fn test_rational_evaluation() {
+        let term = |text| Parser::new(text).parse_term().unwrap();
+        assert_eq!(eval(&term("1/2 + 1/3")), Rational::new(5, 6));
+        assert_eq!(eval(&term("(2 - 4) / 6")), Rational::new(-1, 3));
+        assert_eq!(eval(&term("1 / (2 - 2)")), None);
+        assert_eq!(eval(&term("x / 2")), None);
+
+        let store = NodeStorage::new();
+        let folded = fold_constant(&term("6 / 4"), &store).unwrap();
+        assert_eq!(folded.to_string(), "(3 · 2⁻¹)");
+        assert_eq!(fold_constant(&folded, &store), None);
+    }
+}
@@ -0,0 +1,17 @@
+//! Field arithmetic: the rationals, or any field, as a rewriting domain.
+//!
+//! Terms are built from integer literals, addition, multiplication,
+//! negation and the multiplicative inverse, with `x - y` and `x / y` read as
+//! `x + -y` and `x · y⁻¹`. Axioms about inverses only hold for non-zero
+//! arguments, so they are `ConditionalRule`s whose `x ≠ 0` provisos are
+//! discharged from the hypotheses of the goal, e.g.
+//! `a ≠ 0 ∧ b ≠ 0 → (a · b)⁻¹ = b⁻¹ · a⁻¹`.
+
+pub mod syntax;
+pub mod stores;
+pub mod parsing;
+pub mod eval;
+pub mod axioms;
+pub mod prover;
+
+pub use prover::{prove_field, FieldFacts};
@@ -0,0 +1,361 @@
+//! Infix syntax for field formulas, e.g. `a ≠ 0 -> a · a⁻¹ = 1`.
+//!
+//! Formulas are built from `->` (right associative), `∧` or `&&`, and `¬`
+//! or `!` over the atoms `t = u` and `t ≠ u` (or `t != u`), which reads as
+//! `¬(t = u)`. In terms, `+` and `-` bind looser than `*`, `·` and `/`,
+//! which bind looser than unary `-`; postfix `⁻¹` binds tightest, and
+//! `inv(t)` is the same inverse. A minus sign directly before a literal is
+//! part of it. Variables are names, numbered in order of first appearance
+//! and implicitly universal.
+
+use std::ops::Deref;
+
+use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
+use corpus_core::expression::LogicalExpression;
+use corpus_core::nodes::HashNode;
+use corpus_parsing::{parse_binary, Grammar, Infix, ParseError, Span, TokenKind, Tokens};
+
+use crate::stores::FieldStores;
+use crate::syntax::{FieldContent, FieldExpression, FieldTerm};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Token {
+    LParen,
+    RParen,
+    And,
+    Implies,
+    Not,
+    Eq,
+    NotEq,
+    Plus,
+    Minus,
+    Times,
+    Divide,
+    /// Postfix `⁻¹`.
+    Inverse,
+    Number(u64),
+    Ident(String),
+    /// A character no token starts with.
+    Unknown(char),
+}
+
+impl TokenKind for Token {
+    const SYMBOLS: &'static [(&'static str, Self)] = &[
+        ("(", Token::LParen),
+        (")", Token::RParen),
+        ("=", Token::Eq),
+        ("≠", Token::NotEq),
+        ("!=", Token::NotEq),
+        ("+", Token::Plus),
+        ("-", Token::Minus),
+        ("*", Token::Times),
+        ("·", Token::Times),
+        ("/", Token::Divide),
+        ("⁻¹", Token::Inverse),
+        ("∧", Token::And),
+        ("&&", Token::And),
+        ("→", Token::Implies),
+        ("->", Token::Implies),
+        ("¬", Token::Not),
+        ("!", Token::Not),
+    ];
+    const KEYWORDS: &'static [(&'static str, Self)] = &[];
+
+    fn number(value: u64) -> Self {
+        Token::Number(value)
+    }
+
+    fn identifier(name: String) -> Self {
+        Token::Ident(name)
+    }
+
+    fn unknown(c: char) -> Self {
+        Token::Unknown(c)
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Token::RParen => "`)`".to_string(),
+            other => format!("{:?}", other),
+        }
+    }
+}
+
+/// The connectives, loosest first.
+const CONNECTIVES: &[Infix<Token, ClassicalOperator>] = &[
+    Infix::right(Token::Implies, ClassicalOperator::Implies, 1),
+    Infix::left(Token::And, ClassicalOperator::And, 2),
+];
+
+/// A binary term operator; subtraction and division are sugar for adding
+/// a negation and multiplying by an inverse.
+#[derive(Clone, Copy)]
+enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// The binary term operators, loosest first.
+const OPERATIONS: &[Infix<Token, Operation>] = &[
+    Infix::left(Token::Plus, Operation::Add, 1),
+    Infix::left(Token::Minus, Operation::Subtract, 1),
+    Infix::left(Token::Times, Operation::Multiply, 2),
+    Infix::left(Token::Divide, Operation::Divide, 2),
+];
+
+/// An equation to prove under `t ≠ 0` hypotheses.
+pub struct Goal {
+    /// Terms assumed non-zero.
+    pub nonzero: Vec<HashNode<FieldTerm>>,
+    pub equation: HashNode<FieldContent>,
+}
+
+/// The stores a parser interns into: its own, or ones shared with the caller.
+enum Stores<'a> {
+    Owned(Box<FieldStores>),
+    Shared(&'a FieldStores),
+}
+
+impl Deref for Stores<'_> {
+    type Target = FieldStores;
+
+    fn deref(&self) -> &FieldStores {
+        match self {
+            Stores::Owned(stores) => stores,
+            Stores::Shared(stores) => stores,
+        }
+    }
+}
+
+pub struct Parser<'a> {
+    tokens: Tokens<'a, Token>,
+    stores: Stores<'a>,
+    /// Variable names, in order of first appearance.
+    variables: Vec<String>,
+}
+
+impl<'a> Grammar<'a> for Parser<'a> {
+    type Token = Token;
+
+    fn tokens(&mut self) -> &mut Tokens<'a, Token> {
+        &mut self.tokens
+    }
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self::with(input, Stores::Owned(Box::default()))
+    }
+
+    /// A parser interning its nodes into `stores`, so that they are shared
+    /// with the proof search that follows.
+    pub fn with_stores(input: &'a str, stores: &'a FieldStores) -> Self {
+        Self::with(input, Stores::Shared(stores))
+    }
+
+    fn with(input: &'a str, stores: Stores<'a>) -> Self {
+        Self {
+            tokens: Tokens::new(input),
+            stores,
+            variables: Vec::new(),
+        }
+    }
+
+    /// Parse a whole formula.
+    pub fn parse_formula(&mut self) -> Result<HashNode<FieldExpression>, ParseError> {
+        let formula = self.parse_connectives()?;
+        self.tokens.expect_end()?;
+        Ok(formula)
+    }
+
+    /// Parse a goal: an equation, optionally after hypotheses of the form
+    /// `t₁ ≠ 0 ∧ … ∧ tₙ ≠ 0 ->`.
+    pub fn parse_goal(&mut self) -> Result<Goal, String> {
+        let formula = self.parse_formula()?;
+        if let Some(equation) = formula.value.as_domain() {
+            return Ok(Goal { nonzero: Vec::new(), equation: equation.clone() });
+        }
+        let logical = formula.value.as_logical(&self.stores.logical_store);
+        let shape = "Expected an equation, optionally after `t ≠ 0 ∧ … ->`";
+        let (Some(ClassicalOperator::Implies), Some([hypotheses, conclusion])) =
+            (logical.value.operator(), logical.value.operands().map(Vec::as_slice))
+        else {
+            return Err(shape.to_string());
+        };
+        let LogicalExpression::Atomic(equation) = conclusion.value.as_ref() else {
+            return Err(shape.to_string());
+        };
+        let mut nonzero = Vec::new();
+        collect_nonzero(hypotheses, &mut nonzero)?;
+        Ok(Goal { nonzero, equation: equation.clone() })
+    }
+
+    fn parse_connectives(&mut self) -> Result<HashNode<FieldExpression>, ParseError> {
+        parse_binary(self, CONNECTIVES, Self::parse_unary, |parser, operator, left, right| {
+            parser.compound(operator, vec![left, right])
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<HashNode<FieldExpression>, ParseError> {
+        if self.tokens.eat(&Token::Not) {
+            let inner = self.parse_unary()?;
+            return Ok(self.compound(ClassicalOperator::Not, vec![inner]));
+        }
+
+        // A parenthesis opens either a formula or the first term of an atom.
+        if self.tokens.peek() == Some(&Token::LParen) {
+            let (start, variables) = (self.tokens.clone(), self.variables.clone());
+            self.tokens.advance();
+            if let Ok(formula) = self.parse_connectives()
+                && self.tokens.eat(&Token::RParen)
+            {
+                return Ok(formula);
+            }
+            self.tokens = start;
+            self.variables = variables;
+        }
+
+        let left = self.parse_term()?;
+        let negated = match self.tokens.peek() {
+            Some(Token::Eq) => false,
+            Some(Token::NotEq) => true,
+            _ => return Err(self.tokens.unexpected(&["`=`", "`≠`"])),
+        };
+        self.tokens.advance();
+        let right = self.parse_term()?;
+        let content = HashNode::from_store(FieldContent::Equals(left, right), &self.stores.content_store);
+        let equality = HashNode::from_store(FieldExpression::domain(content), &self.stores.expression_store);
+        Ok(if negated { self.compound(ClassicalOperator::Not, vec![equality]) } else { equality })
+    }
+
+    /// Parse a field term.
+    pub fn parse_term(&mut self) -> Result<HashNode<FieldTerm>, ParseError> {
+        parse_binary(self, OPERATIONS, Self::parse_negation, |parser, operation, left, right| {
+            let term = match operation {
+                Operation::Add => FieldTerm::Add(left, right),
+                Operation::Subtract => FieldTerm::Add(left, parser.term(FieldTerm::Negate(right))),
+                Operation::Multiply => FieldTerm::Multiply(left, right),
+                Operation::Divide => FieldTerm::Multiply(left, parser.term(FieldTerm::Inverse(right))),
+            };
+            parser.term(term)
+        })
+    }
+
+    fn parse_negation(&mut self) -> Result<HashNode<FieldTerm>, ParseError> {
+        if !self.tokens.eat(&Token::Minus) {
+            return self.parse_postfix();
+        }
+        let start = self.tokens.last().start;
+        if let Some(&Token::Number(n)) = self.tokens.peek()
+            && self.tokens.peek_nth(1) != Some(&Token::Inverse)
+        {
+            self.tokens.advance();
+            let span = Span { start, end: self.tokens.last().end };
+            let n = i64::try_from(n).map_err(|_| self.tokens.invalid(span, format!("Literal -{} is out of range", n)))?;
+            return Ok(self.term(FieldTerm::Number(-n)));
+        }
+        let inner = self.parse_negation()?;
+        Ok(self.term(FieldTerm::Negate(inner)))
+    }
+
+    fn parse_postfix(&mut self) -> Result<HashNode<FieldTerm>, ParseError> {
+        let mut term = self.parse_primary_term()?;
+        while self.tokens.eat(&Token::Inverse) {
+            term = self.term(FieldTerm::Inverse(term));
+        }
+        Ok(term)
+    }
+
+    fn parse_primary_term(&mut self) -> Result<HashNode<FieldTerm>, ParseError> {
+        let token = self.tokens.advance().ok_or_else(|| self.tokens.unexpected(&["a term"]))?;
+        match token {
+            Token::LParen => {
+                let inner = self.parse_term()?;
+                self.tokens.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Token::Number(n) => {
+                let span = self.tokens.last();
+                let n = i64::try_from(n).map_err(|_| self.tokens.invalid(span, format!("Literal {} is out of range", n)))?;
+                Ok(self.term(FieldTerm::Number(n)))
+            }
+            Token::Ident(name) if name == "inv" && self.tokens.peek() == Some(&Token::LParen) => {
+                self.tokens.advance();
+                let inner = self.parse_term()?;
+                self.tokens.expect(Token::RParen)?;
+                Ok(self.term(FieldTerm::Inverse(inner)))
+            }
+            Token::Ident(name) => {
+                let index = match self.variables.iter().position(|variable| *variable == name) {
+                    Some(position) => position,
+                    None => {
+                        self.variables.push(name);
+                        self.variables.len() - 1
+                    }
+                };
+                Ok(self.term(FieldTerm::DeBruijn(index as u32)))
+            }
+            _ => Err(self.tokens.unexpected_previous(&["a term"])),
+        }
+    }
+
+    fn term(&self, term: FieldTerm) -> HashNode<FieldTerm> {
+        HashNode::from_store(term, &self.stores.term_store)
+    }
+
+    fn compound(&self, operator: ClassicalOperator, operands: Vec<HashNode<FieldExpression>>) -> HashNode<FieldExpression> {
+        let operands = operands.iter().map(|operand| operand.value.as_logical(&self.stores.logical_store)).collect();
+        let logical_node = HashNode::from_store(LogicalExpression::compound(operator, operands), &self.stores.logical_store);
+        HashNode::from_store(FieldExpression::logical(logical_node), &self.stores.expression_store)
+    }
+}
+
+/// Collect `t` from each `t ≠ 0` of a conjunction of them.
+fn collect_nonzero(
+    hypotheses: &HashNode<LogicalExpression<BinaryTruth, FieldContent, ClassicalOperator>>,
+    nonzero: &mut Vec<HashNode<FieldTerm>>,
+) -> Result<(), String> {
+    match (hypotheses.value.operator(), hypotheses.value.operands().map(Vec::as_slice)) {
+        (Some(ClassicalOperator::And), Some(conjuncts)) => {
+            conjuncts.iter().try_for_each(|conjunct| collect_nonzero(conjunct, nonzero))
+        }
+        (Some(ClassicalOperator::Not), Some([negated])) => match negated.value.as_ref() {
+            LogicalExpression::Atomic(content)
+                if matches!(content.value.sides().1.value.as_ref(), FieldTerm::Number(0)) =>
+            {
+                nonzero.push(content.value.sides().0.clone());
+                Ok(())
+            }
+            _ => Err(format!("Hypotheses must be of the form t ≠ 0, found {}", hypotheses)),
+        },
+        _ => Err(format!("Hypotheses must be of the form t ≠ 0, found {}", hypotheses)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> String {
+        Parser::new(text).parse_formula().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_infix_formulas() {
+        assert_eq!(parse("a / b = a * inv(b)"), "(/0 · /1⁻¹) = (/0 · /1⁻¹)");
+        assert_eq!(parse("a - b = a + -b"), parse("a + -b = a + -b"));
+        assert_eq!(parse("(a·b)⁻¹ = -2⁻¹"), "(/0 · /1)⁻¹ = -2⁻¹");
+        assert_eq!(parse("a ≠ 0 -> a * a⁻¹ = 1"), parse("!(a = 0) → a · a⁻¹ = 1"));
+        assert!(Parser::new("a < b").parse_formula().is_err());
+
+        let goal = Parser::new("a ≠ 0 ∧ b - 1 ≠ 0 -> a = b").parse_goal().unwrap();
+        assert_eq!(goal.nonzero.iter().map(|term| term.to_string()).collect::<Vec<_>>(), ["/0", "(/1 + -1)"]);
+        assert_eq!(goal.equation.to_string(), "/0 = /1");
+        assert!(Parser::new("a = 0 -> a = b").parse_goal().is_err());
+        assert!(Parser::new("a ≠ b -> a = b").parse_goal().is_err());
+        let error = Parser::new("a ≠ 0 ->").parse_formula().unwrap_err();
+        assert_eq!(error.to_string(), "1:9: expected a term, found end of input");
+    }
+}
@@ -0,0 +1,139 @@
+//! Proof search for field equations on the core `Prover`.
+//!
+//! Conditional rules rewrite both sides of an equation through a
+//! `LayeredRewriter`, with `FieldFacts` deciding their `x ≠ 0` provisos, and
+//! variable-free subterms fold into rational literals.
+
+use corpus_classical_logic::BinaryTruth;
+use corpus_core::nodes::HashNode;
+use corpus_core::proving::{GoalChecker, LayeredRewriter, ProofResult, Prover, SizeCostEstimator, StateExpander};
+use corpus_core::rewriting::{ConditionChecker, ConditionalRule, RewriteDirection, RewriteResult};
+
+use crate::axioms::{field_rules, NONZERO};
+use crate::eval::{eval, fold_constant};
+use crate::parsing::Goal;
+use crate::stores::FieldStores;
+use crate::syntax::{FieldContent, FieldTerm};
+
+/// What is known to be non-zero: the hypotheses of a goal, non-zero
+/// constants, and negations, inverses and products of non-zero terms.
+pub struct FieldFacts {
+    pub nonzero: Vec<HashNode<FieldTerm>>,
+}
+
+impl FieldFacts {
+    pub fn is_nonzero(&self, term: &HashNode<FieldTerm>) -> bool {
+        if self.nonzero.contains(term) || eval(term).is_some_and(|value| !value.is_zero()) {
+            return true;
+        }
+        match term.value.as_ref() {
+            FieldTerm::Negate(inner) | FieldTerm::Inverse(inner) => self.is_nonzero(inner),
+            FieldTerm::Multiply(left, right) => self.is_nonzero(left) && self.is_nonzero(right),
+            _ => false,
+        }
+    }
+}
+
+impl ConditionChecker<FieldTerm> for FieldFacts {
+    fn holds(&self, predicate: &str, term: &HashNode<FieldTerm>) -> bool {
+        predicate == NONZERO && self.is_nonzero(term)
+    }
+}
+
+/// Search for a proof of `goal` with the field rules.
+pub fn prove_field(goal: &Goal, stores: &FieldStores, max_nodes: usize) -> Option<ProofResult<FieldContent, BinaryTruth>> {
+    prove_field_with_rules(goal, stores, max_nodes, &field_rules())
+}
+
+/// `prove_field` with the given rules in place of the field rules.
+pub fn prove_field_with_rules(
+    goal: &Goal,
+    stores: &FieldStores,
+    max_nodes: usize,
+    rules: &[ConditionalRule<FieldTerm>],
+) -> Option<ProofResult<FieldContent, BinaryTruth>> {
+    let prover: Prover<_, _, BinaryTruth, _> =
+        Prover::builder(SizeCostEstimator, FieldGoalChecker).max_nodes(max_nodes).build();
+    let expander = FieldExpander {
+        rewriter: LayeredRewriter::new(&[], &stores.content_store, &stores.term_store),
+        rules,
+        facts: FieldFacts { nonzero: goal.nonzero.clone() },
+    };
+    prover.prove_with_expander(&goal.equation, &expander).ok()
+}
+
+/// Closes equations whose sides are the same term, or evaluate to rationals.
+struct FieldGoalChecker;
+
+impl GoalChecker<FieldContent, BinaryTruth> for FieldGoalChecker {
+    fn check(&self, expr: &HashNode<FieldContent>) -> Option<BinaryTruth> {
+        let (left, right) = expr.value.sides();
+        if left == right {
+            return Some(BinaryTruth::True);
+        }
+        Some(BinaryTruth::from(eval(left)? == eval(right)?))
+    }
+}
+
+/// The conditional rules and constant folding at every subterm of either side.
+struct FieldExpander<'a> {
+    rewriter: LayeredRewriter<'a, FieldContent, FieldTerm>,
+    rules: &'a [ConditionalRule<FieldTerm>],
+    facts: FieldFacts,
+}
+
+impl StateExpander<FieldContent> for FieldExpander<'_> {
+    fn expand(&self, expr: &HashNode<FieldContent>) -> Vec<RewriteResult<FieldContent>> {
+        let store = self.rewriter.inner_store();
+        self.rewriter.rewrites_with(expr, |subterm| {
+            let mut rewrites: Vec<_> = fold_constant(subterm, store)
+                .map(|term| (term, "evaluation".to_string(), RewriteDirection::Forward))
+                .into_iter()
+                .collect();
+            for rule in self.rules {
+                if let Some(term) = rule.apply(subterm, store, &self.facts) {
+                    rewrites.push((term, rule.name().to_string(), RewriteDirection::Forward));
+                }
+                if let Some(term) = rule.apply_reverse(subterm, store, &self.facts) {
+                    rewrites.push((term, rule.name().to_string(), RewriteDirection::Backward));
+                }
+            }
+            rewrites
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    fn prove(text: &str, stores: &FieldStores) -> Option<ProofResult<FieldContent, BinaryTruth>> {
+        let goal = Parser::with_stores(text, stores).parse_goal().unwrap();
+        prove_field(&goal, stores, 5000)
+    }
+
+    #[test]
+    fn test_inverse_of_a_product() {
+        let stores = FieldStores::new();
+        let result = prove("a ≠ 0 ∧ b ≠ 0 -> (a * b)⁻¹ = b⁻¹ * a⁻¹", &stores).expect("(ab)⁻¹ = b⁻¹a⁻¹");
+        assert_eq!(result.truth_result, BinaryTruth::True);
+        assert!(result.steps.iter().any(|step| step.rule_name == "inverse_multiply"));
+
+        // Without the provisos the rule never fires.
+        assert!(prove("(a * b)⁻¹ = b⁻¹ * a⁻¹", &stores).is_none());
+    }
+
+    #[test]
+    fn test_provisos_are_discharged() {
+        let stores = FieldStores::new();
+        let result = prove("a ≠ 0 -> (a * b) / a = b", &stores).expect("(ab)/a = b");
+        assert!(result.steps.iter().any(|step| step.rule_name == "mul_inverse"));
+        assert!(prove("(a * b) / a = b", &stores).is_none());
+
+        // Non-zero constants need no hypothesis.
+        assert!(prove("(2 * b) / 2 = b", &stores).is_some());
+        let result = prove("1/2 + 1/3 = 5/6", &stores).unwrap();
+        assert_eq!((result.truth_result, result.steps.len()), (BinaryTruth::True, 0));
+    }
+}
@@ -0,0 +1,32 @@
+//! The node stores shared by parsing and proof search, as in the PA tool.
+
+use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
+use corpus_core::expression::LogicalExpression;
+use corpus_core::nodes::NodeStorage;
+
+use crate::syntax::{FieldContent, FieldExpression, FieldTerm};
+
+/// One store per node type of field formulas.
+pub struct FieldStores {
+    pub expression_store: NodeStorage<FieldExpression>,
+    pub term_store: NodeStorage<FieldTerm>,
+    pub content_store: NodeStorage<FieldContent>,
+    pub logical_store: NodeStorage<LogicalExpression<BinaryTruth, FieldContent, ClassicalOperator>>,
+}
+
+impl Default for FieldStores {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FieldStores {
+    pub fn new() -> Self {
+        Self {
+            expression_store: NodeStorage::new(),
+            term_store: NodeStorage::new(),
+            content_store: NodeStorage::new(),
+            logical_store: NodeStorage::new(),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use core::fmt;
+
+use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
+use corpus_core::expression::{DomainContent, DomainExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner, Hashing, NodeStorage};
+use corpus_core::printing::{Associativity, ExprPrinter, NotationPrinter, Parentheses, SymbolTable};
+use corpus_core::proving::SubtermRewritable;
+
+pub type FieldExpression = DomainExpression<BinaryTruth, FieldContent>;
+
+/// An atom of field formulas. Disequalities such as the `x ≠ 0` provisos
+/// are negated equalities.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum FieldContent {
+    Equals(HashNode<FieldTerm>, HashNode<FieldTerm>),
+}
+
+impl FieldContent {
+    /// The two sides of the equality.
+    pub fn sides(&self) -> (&HashNode<FieldTerm>, &HashNode<FieldTerm>) {
+        match self {
+            FieldContent::Equals(left, right) => (left, right),
+        }
+    }
+}
+
+/// The core prover rewrites both sides of an equality with term rules.
+impl SubtermRewritable<FieldTerm> for FieldContent {
+    fn inner_terms(&self) -> Vec<HashNode<FieldTerm>> {
+        let (left, right) = self.sides();
+        vec![left.clone(), right.clone()]
+    }
+
+    fn with_inner_terms(&self, terms: Vec<HashNode<FieldTerm>>) -> Option<Self> {
+        let [left, right] = <[_; 2]>::try_from(terms).ok()?;
+        Some(FieldContent::Equals(left, right))
+    }
+}
+
+impl DomainContent<BinaryTruth> for FieldContent {
+    type Operator = ClassicalOperator;
+}
+
+impl fmt::Display for FieldContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&printer().with_parentheses(Parentheses::Full).print(self))
+    }
+}
+
+impl HashNodeInner for FieldContent {
+    fn hash(&self) -> u64 {
+        let (left, right) = self.sides();
+        Hashing::root_hash(Hashing::opcode("field_equals"), &[left.hash(), right.hash()])
+    }
+
+    fn size(&self) -> u64 {
+        let (left, right) = self.sides();
+        1 + left.size() + right.size()
+    }
+
+    fn decompose(&self) -> Option<(u64, Vec<HashNode<Self>>)> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum FieldTerm {
+    Add(HashNode<FieldTerm>, HashNode<FieldTerm>),
+    Multiply(HashNode<FieldTerm>, HashNode<FieldTerm>),
+    /// Additive inverse `-x`.
+    Negate(HashNode<FieldTerm>),
+    /// Multiplicative inverse `x⁻¹`, meaningful only for `x ≠ 0`.
+    Inverse(HashNode<FieldTerm>),
+    /// An integer literal; other rationals are built with `Inverse`.
+    Number(i64),
+    DeBruijn(u32),
+}
+
+impl fmt::Display for FieldTerm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&printer().with_parentheses(Parentheses::Full).print(self))
+    }
+}
+
+/// Renders terms with `+`, `·`, `-` and `⁻¹` binding as the parser reads them;
+/// `Display` is this printer with full parentheses.
+pub fn printer() -> NotationPrinter<FieldTerm> {
+    let symbols = SymbolTable::new()
+        .infix("field_add", "+", 1, Associativity::Left)
+        .infix("field_multiply", "·", 2, Associativity::Left)
+        .prefix("field_negate", "-", 3)
+        .postfix("field_inverse", "⁻¹", 4);
+    NotationPrinter::new(symbols, |leaf| match leaf {
+        FieldTerm::Number(n) if *n < 0 => format!("({})", n),
+        FieldTerm::Number(n) => n.to_string(),
+        FieldTerm::DeBruijn(index) => format!("/{}", index),
+        compound => unreachable!("{:?} decomposes", compound),
+    })
+}
+
+/// Atoms, with their sides printed as terms.
+impl ExprPrinter<FieldContent> for NotationPrinter<FieldTerm> {
+    fn print(&self, content: &FieldContent) -> String {
+        let relation = match content {
+            FieldContent::Equals(..) => "=",
+        };
+        let (left, right) = content.sides();
+        format!("{} {} {}", self.print(left.value.as_ref()), relation, self.print(right.value.as_ref()))
+    }
+}
+
+impl HashNodeInner for FieldTerm {
+    fn hash(&self) -> u64 {
+        match self {
+            FieldTerm::Number(n) => Hashing::root_hash(Hashing::opcode("field_number"), &[*n as u64]),
+            FieldTerm::DeBruijn(idx) => Hashing::root_hash(Hashing::opcode("debruijn"), &[*idx as u64]),
+            compound => {
+                let (opcode, children) = compound.decompose().expect("compound terms decompose");
+                let hashes: Vec<_> = children.iter().map(|child| child.hash()).collect();
+                Hashing::root_hash(opcode, &hashes)
+            }
+        }
+    }
+
+    fn size(&self) -> u64 {
+        match self {
+            FieldTerm::Add(left, right) | FieldTerm::Multiply(left, right) => 1 + left.size() + right.size(),
+            FieldTerm::Negate(inner) | FieldTerm::Inverse(inner) => 1 + inner.size(),
+            FieldTerm::Number(_) | FieldTerm::DeBruijn(_) => 1,
+        }
+    }
+
+    fn decompose(&self) -> Option<(u64, Vec<HashNode<Self>>)> {
+        match self {
+            FieldTerm::Add(left, right) => Some((Hashing::opcode("field_add"), vec![left.clone(), right.clone()])),
+            FieldTerm::Multiply(left, right) => {
+                Some((Hashing::opcode("field_multiply"), vec![left.clone(), right.clone()]))
+            }
+            FieldTerm::Negate(inner) => Some((Hashing::opcode("field_negate"), vec![inner.clone()])),
+            FieldTerm::Inverse(inner) => Some((Hashing::opcode("field_inverse"), vec![inner.clone()])),
+            FieldTerm::Number(_) | FieldTerm::DeBruijn(_) => None,
+        }
+    }
+
+    fn construct_from_parts(opcode: u64, mut children: Vec<HashNode<Self>>, store: &NodeStorage<Self>) -> Option<HashNode<Self>> {
+        // Opcodes are not constants, so this cannot be a true match.
+        let term = match (opcode, children.len()) {
+            (o, 1) if o == Hashing::opcode("field_negate") => FieldTerm::Negate(children.pop()?),
+            (o, 1) if o == Hashing::opcode("field_inverse") => FieldTerm::Inverse(children.pop()?),
+            (o, 2) => {
+                let right = children.pop()?;
+                let left = children.pop()?;
+                match o {
+                    o if o == Hashing::opcode("field_add") => FieldTerm::Add(left, right),
+                    o if o == Hashing::opcode("field_multiply") => FieldTerm::Multiply(left, right),
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        Some(HashNode::from_store(term, store))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terms_rebuild_from_their_parts() {
+        let store = NodeStorage::new();
+        let x = HashNode::from_store(FieldTerm::DeBruijn(0), &store);
+        let two = HashNode::from_store(FieldTerm::Number(2), &store);
+        let half = HashNode::from_store(FieldTerm::Inverse(two), &store);
+        let term = HashNode::from_store(FieldTerm::Multiply(x, half), &store);
+        assert_eq!(term.to_string(), "(/0 · 2⁻¹)");
+
+        let (opcode, children) = term.value.decompose().unwrap();
+        assert_eq!(FieldTerm::construct_from_parts(opcode, children, &store), Some(term));
+    }
+}
@@ -0,0 +1,110 @@
+//! The axioms of ℤ as a commutative ring, and the rules normalizing
+//! subtraction and negation into them.
+//!
+//! Each rule is written as an infix equation whose variables match any
+//! term, e.g. `x + -x = 0`.
+
+use corpus_core::nodes::HashNode;
+use corpus_core::rewriting::{Pattern, RewriteDirection, RewriteRule};
+
+use crate::eval::variables;
+use crate::parsing::Parser;
+use crate::syntax::{IntegerContent, IntegerTerm};
+
+/// The equality `left = right` as a rewrite rule whose variables match any term.
+///
+/// A side may only be rewritten into one whose variables it binds, so the
+/// rule runs in whichever directions allow that; returns `None` if neither
+/// side's variables cover the other's.
+pub fn equality_rule(
+    name: impl Into<String>,
+    left: &HashNode<IntegerTerm>,
+    right: &HashNode<IntegerTerm>,
+) -> Option<RewriteRule<IntegerTerm>> {
+    let (left_vars, right_vars) = (variables(left), variables(right));
+    let direction = match (right_vars.is_subset(&left_vars), left_vars.is_subset(&right_vars)) {
+        (true, true) => RewriteDirection::Both,
+        (true, false) => RewriteDirection::Forward,
+        (false, true) => RewriteDirection::Backward,
+        (false, false) => return None,
+    };
+    let pattern = |term: &HashNode<IntegerTerm>| {
+        Pattern::from_term(term, &|t: &HashNode<IntegerTerm>| match t.value.as_ref() {
+            IntegerTerm::DeBruijn(index) => Some(Pattern::var(*index)),
+            _ => None,
+        })
+    };
+    Some(RewriteRule::new(name, pattern(left), pattern(right), direction))
+}
+
+/// Parse the infix equation `text` into a rule named `name`.
+pub fn equation_rule(name: &str, text: &str) -> Result<RewriteRule<IntegerTerm>, String> {
+    let formula = Parser::new(text).parse_formula()?;
+    let Some(IntegerContent::Equals(left, right)) = formula.value.as_domain().map(|content| content.value.as_ref()) else {
+        return Err(format!("{} is not an equation", text));
+    };
+    equality_rule(name, left, right).ok_or_else(|| format!("Neither side of {} binds the other's variables", text))
+}
+
+/// The commutative ring axioms for addition, negation and multiplication.
+const RING_AXIOMS: &[(&str, &str)] = &[
+    ("add_identity", "x + 0 = x"),
+    ("add_inverse", "x + -x = 0"),
+    ("add_commutative", "x + y = y + x"),
+    ("add_associative", "(x + y) + z = x + (y + z)"),
+    ("mul_identity", "x * 1 = x"),
+    ("mul_commutative", "x * y = y * x"),
+    ("mul_associative", "(x * y) * z = x * (y * z)"),
+    ("distributive", "x * (y + z) = x * y + x * z"),
+];
+
+/// Consequences of the ring axioms that eliminate subtraction and push
+/// negation towards the variables.
+const NORMALIZATION_RULES: &[(&str, &str)] = &[
+    ("subtraction", "x - y = x + -y"),
+    ("double_negation", "--x = x"),
+    ("negate_add", "-(x + y) = -x + -y"),
+    ("negate_multiply", "-x * y = -(x * y)"),
+    ("mul_zero", "x * 0 = 0"),
+];
+
+fn rules(table: &[(&str, &str)]) -> Vec<RewriteRule<IntegerTerm>> {
+    table
+        .iter()
+        .map(|(name, text)| equation_rule(name, text).unwrap_or_else(|e| panic!("Failed to parse {}: {}", name, e)))
+        .collect()
+}
+
+/// The ring axioms of ℤ as rewrite rules.
+pub fn integer_ring_rules() -> Vec<RewriteRule<IntegerTerm>> {
+    rules(RING_AXIOMS)
+}
+
+/// The ring axioms followed by the normalization rules.
+pub fn integer_arithmetic_rules() -> Vec<RewriteRule<IntegerTerm>> {
+    let mut all = integer_ring_rules();
+    all.extend(rules(NORMALIZATION_RULES));
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use corpus_core::nodes::NodeStorage;
+
+    #[test]
+    fn test_rules_apply_to_terms() {
+        let rules = integer_arithmetic_rules();
+        let rule = |name: &str| rules.iter().find(|rule| rule.name == name).unwrap();
+        let store = NodeStorage::new();
+        let term = |text| Parser::new(text).parse_term().unwrap();
+
+        let rewritten = rule("subtraction").apply(&term("a - 2 * b"), &store).unwrap();
+        assert_eq!(rewritten, term("a + -(2 * b)"));
+        assert_eq!(rule("add_inverse").apply(&term("(a * b) + -(a * b)"), &store).unwrap(), term("0"));
+        assert_eq!(rule("add_inverse").direction, RewriteDirection::Forward);
+
+        assert!(equation_rule("bad", "x + y = z").is_err());
+        assert!(equation_rule("bad", "x < y").is_err());
+    }
+}
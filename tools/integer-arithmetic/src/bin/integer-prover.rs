@@ -0,0 +1,57 @@
+use corpus_classical_logic::satisfies;
+use corpus_core::printing::ExprPrinter;
+use integer_arithmetic::domain::BoundedIntegers;
+use integer_arithmetic::parsing::Parser;
+use integer_arithmetic::prover::{prove_or_disprove, IntegerOutcome};
+use integer_arithmetic::stores::IntegerStores;
+use integer_arithmetic::syntax::printer;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 {
+        println!("Usage: {} <formula>", args[0]);
+        println!();
+        println!("Example: {} \"(x + y) - y = x\"", args[0]);
+        println!("         {} \"forall x. exists y. x + y = 0\"", args[0]);
+        println!();
+        println!("Terms: +, - (binary and unary), *, integers (-2, 0, 3, ...), variable names or /0, /1, ...");
+        println!("Relations: =, < and <=; formulas: ¬ or !, ∧ or &&, ∨ or ||, -> and <->, forall x. ... and exists x. ...");
+        println!("Quantified formulas are checked on -8..=8 rather than proved.");
+        std::process::exit(1);
+    }
+
+    let stores = IntegerStores::new();
+    let formula = match Parser::with_stores(&args[1], &stores).parse_formula() {
+        Ok(formula) => formula,
+        Err(e) => {
+            eprintln!("Parse error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    println!("Parsed: {}", formula);
+    println!();
+
+    let Some(relation) = formula.value.as_domain() else {
+        let logical = formula.value.as_logical(&stores.logical_store);
+        match satisfies(&BoundedIntegers { bound: 8 }, &logical) {
+            Ok(true) => println!("✓ Holds for all values in -8..=8"),
+            Ok(false) => println!("✗ Fails in -8..=8"),
+            Err(e) => eprintln!("Error: {}", e),
+        }
+        return;
+    };
+
+    println!("Searching for proof (max 10000 nodes)...");
+    match prove_or_disprove(relation, &stores, 10000, 8) {
+        IntegerOutcome::Proved(result) => {
+            let verdict = if result.truth_result.into() { "✓ Theorem proved!" } else { "✗ Statement refuted" };
+            println!("{}", verdict);
+            println!("Nodes explored: {}", result.nodes_explored);
+            for step in &result.steps {
+                println!("  {}  [{}]", printer().print(step.new_expr.value.as_ref()), step.rule_name);
+            }
+        }
+        IntegerOutcome::Disproved { witness } => println!("✗ Statement disproved at {:?}", witness),
+        IntegerOutcome::Unknown => println!("✗ Could not prove theorem (reached limit)"),
+    }
+}
@@ -0,0 +1,117 @@
+//! Integer arithmetic as a first-order domain, and a bounded model of it.
+//!
+//! Relations are binary predicates over integer terms, so the
+//! classical-logic procedures that look inside atoms (instantiation,
+//! Skolemization, evaluation in a model) work on integer formulas unchanged.
+
+use corpus_classical_logic::{Assignment, BinaryTruth, FirstOrderDomain, Interpretation};
+use corpus_core::nodes::{HashNode, NodeStorage};
+
+use crate::eval;
+use crate::syntax::{IntegerContent, IntegerTerm};
+
+/// `FirstOrderDomain` for `IntegerContent`, building atoms and terms in the
+/// given stores.
+pub struct IntegerDomain<'a> {
+    pub content_store: &'a NodeStorage<IntegerContent>,
+    pub term_store: &'a NodeStorage<IntegerTerm>,
+}
+
+impl<'a> IntegerDomain<'a> {
+    pub fn new(content_store: &'a NodeStorage<IntegerContent>, term_store: &'a NodeStorage<IntegerTerm>) -> Self {
+        Self { content_store, term_store }
+    }
+}
+
+impl FirstOrderDomain<BinaryTruth, IntegerContent> for IntegerDomain<'_> {
+    type Term = IntegerTerm;
+
+    fn predicate(&self, atom: &HashNode<IntegerContent>) -> Option<(u64, Vec<HashNode<IntegerTerm>>)> {
+        let (left, right) = atom.value.sides();
+        Some((atom.value.opcode(), vec![left.clone(), right.clone()]))
+    }
+
+    fn atom(&self, predicate: u64, arguments: Vec<HashNode<IntegerTerm>>) -> Option<HashNode<IntegerContent>> {
+        let [left, right] = <[_; 2]>::try_from(arguments).ok()?;
+        let content = [
+            IntegerContent::Equals(left.clone(), right.clone()),
+            IntegerContent::LessThan(left.clone(), right.clone()),
+            IntegerContent::LessEq(left, right),
+        ]
+        .into_iter()
+        .find(|content| content.opcode() == predicate)?;
+        Some(HashNode::from_store(content, self.content_store))
+    }
+
+    fn bound_index(&self, term: &HashNode<IntegerTerm>) -> Option<u32> {
+        match term.value.as_ref() {
+            IntegerTerm::DeBruijn(idx) => Some(*idx),
+            _ => None,
+        }
+    }
+
+    fn variable(&self, index: u32) -> HashNode<IntegerTerm> {
+        HashNode::from_store(IntegerTerm::DeBruijn(index), self.term_store)
+    }
+
+    fn terms(&self) -> &NodeStorage<IntegerTerm> {
+        self.term_store
+    }
+}
+
+/// The integers cut down to `-bound..=bound`, so quantifiers can be checked
+/// by enumeration. Sums that leave the range are still computed, so
+/// `∀x ∃y. y = x + 1` is false here even though it holds in ℤ.
+pub struct BoundedIntegers {
+    pub bound: i64,
+}
+
+impl Interpretation<BinaryTruth, IntegerContent> for BoundedIntegers {
+    type Value = i64;
+
+    fn universe(&self) -> Vec<i64> {
+        (-self.bound..=self.bound).collect()
+    }
+
+    fn holds(&self, atom: &HashNode<IntegerContent>, assignment: &Assignment<i64>) -> Option<bool> {
+        let values = assignment.by_index().copied().collect::<Vec<_>>();
+        eval::holds_with(&atom.value, &values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use crate::stores::IntegerStores;
+    use corpus_classical_logic::{instantiate, satisfies};
+    use corpus_core::expression::DomainExpression;
+
+    #[test]
+    fn test_bounded_integers() {
+        let model = BoundedIntegers { bound: 4 };
+        let holds = |text| {
+            let expr = Parser::new(text).parse_formula().unwrap();
+            let DomainExpression::Logical(expr) = expr.value.as_ref() else { panic!("expected a compound formula") };
+            satisfies(&model, expr).unwrap()
+        };
+
+        // Unlike in the naturals, every element has an additive inverse.
+        assert!(holds("forall x. exists y. x + y = 0"));
+        assert!(holds("forall x. x < 0 -> 0 < -x"));
+        assert!(!holds("forall x. 0 <= x"));
+        assert!(!holds("forall x. exists y. y = x + 1"));
+    }
+
+    #[test]
+    fn test_instantiate_with_a_negative_literal() {
+        let stores = IntegerStores::new();
+        let axiom = Parser::with_stores("forall x. x + -x = 0", &stores).parse_formula().unwrap();
+        let DomainExpression::Logical(axiom) = axiom.value.as_ref() else { panic!("expected a quantified formula") };
+        let literal = HashNode::from_store(IntegerTerm::Integer(-3), &stores.term_store);
+        let instance = instantiate(axiom, &literal, &stores.domain(), &stores.logical_store).unwrap();
+
+        let expected = Parser::new("-3 + --3 = 0").parse_formula().unwrap();
+        assert_eq!(instance.to_string(), expected.to_string());
+    }
+}
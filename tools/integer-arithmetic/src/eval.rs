@@ -0,0 +1,87 @@
+//! Ground evaluation of integer terms.
+//!
+//! De Bruijn variables are resolved through an assignment, so the same
+//! evaluator serves the bounded model of `domain`.
+
+use std::collections::BTreeSet;
+
+use corpus_core::nodes::{HashNode, NodeStorage};
+
+use crate::syntax::{IntegerContent, IntegerTerm};
+
+/// Evaluate `term`, looking up each De Bruijn index `/i` as `assignment[i]`.
+///
+/// Returns `None` if a variable has no value in the assignment or the
+/// computation overflows `i64`.
+pub fn eval_with(term: &HashNode<IntegerTerm>, assignment: &[i64]) -> Option<i64> {
+    match term.value.as_ref() {
+        IntegerTerm::Integer(n) => Some(*n),
+        IntegerTerm::DeBruijn(idx) => assignment.get(*idx as usize).copied(),
+        IntegerTerm::Add(left, right) => eval_with(left, assignment)?.checked_add(eval_with(right, assignment)?),
+        IntegerTerm::Subtract(left, right) => eval_with(left, assignment)?.checked_sub(eval_with(right, assignment)?),
+        IntegerTerm::Negate(inner) => eval_with(inner, assignment)?.checked_neg(),
+        IntegerTerm::Multiply(left, right) => eval_with(left, assignment)?.checked_mul(eval_with(right, assignment)?),
+    }
+}
+
+/// Evaluate a variable-free term.
+pub fn eval(term: &HashNode<IntegerTerm>) -> Option<i64> {
+    eval_with(term, &[])
+}
+
+/// Decide a relation, looking up variables as in `eval_with`.
+pub fn holds_with(content: &IntegerContent, assignment: &[i64]) -> Option<bool> {
+    let (left, right) = content.sides();
+    let (left, right) = (eval_with(left, assignment)?, eval_with(right, assignment)?);
+    Some(match content {
+        IntegerContent::Equals(..) => left == right,
+        IntegerContent::LessThan(..) => left < right,
+        IntegerContent::LessEq(..) => left <= right,
+    })
+}
+
+/// Decide a relation between variable-free terms.
+pub fn ground_relation(content: &IntegerContent) -> Option<bool> {
+    holds_with(content, &[])
+}
+
+/// Replace a variable-free compound term by the literal it evaluates to.
+///
+/// Returns `None` if `term` is already a literal, has a variable, or
+/// overflows.
+pub fn fold_constant(term: &HashNode<IntegerTerm>, store: &NodeStorage<IntegerTerm>) -> Option<HashNode<IntegerTerm>> {
+    if matches!(term.value.as_ref(), IntegerTerm::Integer(_)) {
+        return None;
+    }
+    Some(HashNode::from_store(IntegerTerm::Integer(eval(term)?), store))
+}
+
+/// Collect the De Bruijn indices occurring in `term`.
+pub fn variables(term: &HashNode<IntegerTerm>) -> BTreeSet<u32> {
+    match term.value.as_ref() {
+        IntegerTerm::DeBruijn(idx) => BTreeSet::from([*idx]),
+        IntegerTerm::Integer(_) => BTreeSet::new(),
+        IntegerTerm::Negate(inner) => variables(inner),
+        IntegerTerm::Add(left, right) | IntegerTerm::Subtract(left, right) | IntegerTerm::Multiply(left, right) => {
+            variables(left).union(&variables(right)).copied().collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    #[test]
+    fn test_evaluation() {
+        let term = Parser::new("-(x - 7) * 3 + -2").parse_term().unwrap();
+        assert_eq!(eval_with(&term, &[10]), Some(-11));
+        assert_eq!(eval(&term), None);
+
+        let store = NodeStorage::new();
+        let ground = Parser::new("2 - 5").parse_term().unwrap();
+        assert_eq!(fold_constant(&ground, &store).unwrap().value.as_ref(), &IntegerTerm::Integer(-3));
+        assert!(fold_constant(&fold_constant(&ground, &store).unwrap(), &store).is_none());
+    }
+}
@@ -0,0 +1,22 @@
+//! Integer arithmetic as a second domain beside Peano arithmetic.
+//!
+//! Terms are built from integer literals, negation, addition, subtraction
+//! and multiplication, and atoms compare two terms. Like the PA tool, this
+//! crate supplies only the domain: formulas are classical-logic
+//! `DomainExpression`s over `IntegerContent`, the search is the core
+//! `Prover` driven through a `LayeredRewriter`, and quantified formulas are
+//! evaluated by the classical-logic semantics over a bounded model.
+//!
+//! What still had to be written per domain, and so is a candidate for a
+//! shared abstraction: turning equations into rewrite rules, ground
+//! evaluation of terms, and goal checks for reflexive relations.
+
+pub mod syntax;
+pub mod stores;
+pub mod parsing;
+pub mod eval;
+pub mod axioms;
+pub mod domain;
+pub mod prover;
+
+pub use prover::{prove_integer, IntegerOutcome};
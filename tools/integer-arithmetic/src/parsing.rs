@@ -0,0 +1,355 @@
+//! Infix syntax for integer formulas, e.g. `forall x. x - x = 0`.
+//!
+//! From loosest to tightest: `<->`, `->` (right associative), `∨` or `||`,
+//! `∧` or `&&`, then `¬` or `!` and the quantifiers, whose bodies extend as
+//! far right as possible. Atoms are `=`, `<` and `<=` between terms. In
+//! terms, `+` and `-` bind looser than `*`, which binds looser than unary
+//! `-`; a minus sign directly before a literal is part of it.
+//!
+//! Names bound by a quantifier become De Bruijn indices counting from the
+//! innermost binder, and free names are numbered after all binders in order
+//! of first appearance, as in the PA syntax.
+
+use std::ops::Deref;
+
+use corpus_classical_logic::ClassicalOperator;
+use corpus_core::expression::LogicalExpression;
+use corpus_core::nodes::HashNode;
+use corpus_parsing::{parse_binary, Grammar, Infix, ParseError, Span, TokenKind, Tokens};
+
+use crate::stores::IntegerStores;
+use crate::syntax::{IntegerContent, IntegerExpression, IntegerTerm};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Implies,
+    Iff,
+    Not,
+    Forall,
+    Exists,
+    Dot,
+    Comma,
+    Eq,
+    Lt,
+    Le,
+    Plus,
+    Minus,
+    Times,
+    Number(u64),
+    DeBruijn(u32),
+    Ident(String),
+    /// A character no token starts with.
+    Unknown(char),
+}
+
+impl TokenKind for Token {
+    const SYMBOLS: &'static [(&'static str, Self)] = &[
+        ("(", Token::LParen),
+        (")", Token::RParen),
+        (".", Token::Dot),
+        (",", Token::Comma),
+        ("=", Token::Eq),
+        ("+", Token::Plus),
+        ("-", Token::Minus),
+        ("*", Token::Times),
+        ("<", Token::Lt),
+        ("<=", Token::Le),
+        ("≤", Token::Le),
+        ("¬", Token::Not),
+        ("!", Token::Not),
+        ("∧", Token::And),
+        ("&&", Token::And),
+        ("∨", Token::Or),
+        ("||", Token::Or),
+        ("→", Token::Implies),
+        ("->", Token::Implies),
+        ("↔", Token::Iff),
+        ("<->", Token::Iff),
+        ("∀", Token::Forall),
+        ("∃", Token::Exists),
+    ];
+    const KEYWORDS: &'static [(&'static str, Self)] = &[("forall", Token::Forall), ("exists", Token::Exists)];
+
+    fn number(value: u64) -> Self {
+        Token::Number(value)
+    }
+
+    fn identifier(name: String) -> Self {
+        Token::Ident(name)
+    }
+
+    fn unknown(c: char) -> Self {
+        Token::Unknown(c)
+    }
+
+    fn de_bruijn(index: u32) -> Option<Self> {
+        Some(Token::DeBruijn(index))
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Token::RParen => "`)`".to_string(),
+            Token::Dot => "`.`".to_string(),
+            other => format!("{:?}", other),
+        }
+    }
+}
+
+/// The connectives, loosest first.
+const CONNECTIVES: &[Infix<Token, ClassicalOperator>] = &[
+    Infix::right(Token::Iff, ClassicalOperator::Iff, 1),
+    Infix::right(Token::Implies, ClassicalOperator::Implies, 2),
+    Infix::left(Token::Or, ClassicalOperator::Or, 3),
+    Infix::left(Token::And, ClassicalOperator::And, 4),
+];
+
+type Operation = fn(HashNode<IntegerTerm>, HashNode<IntegerTerm>) -> IntegerTerm;
+
+/// The binary term operators, loosest first.
+const OPERATIONS: &[Infix<Token, Operation>] = &[
+    Infix::left(Token::Plus, IntegerTerm::Add, 1),
+    Infix::left(Token::Minus, IntegerTerm::Subtract, 1),
+    Infix::left(Token::Times, IntegerTerm::Multiply, 2),
+];
+
+/// The stores a parser interns into: its own, or ones shared with the caller.
+enum Stores<'a> {
+    Owned(Box<IntegerStores>),
+    Shared(&'a IntegerStores),
+}
+
+impl Deref for Stores<'_> {
+    type Target = IntegerStores;
+
+    fn deref(&self) -> &IntegerStores {
+        match self {
+            Stores::Owned(stores) => stores,
+            Stores::Shared(stores) => stores,
+        }
+    }
+}
+
+pub struct Parser<'a> {
+    tokens: Tokens<'a, Token>,
+    stores: Stores<'a>,
+    /// Names bound by the enclosing quantifiers, innermost last.
+    bound: Vec<String>,
+    /// Free variable names, in order of first appearance.
+    free: Vec<String>,
+}
+
+impl<'a> Grammar<'a> for Parser<'a> {
+    type Token = Token;
+
+    fn tokens(&mut self) -> &mut Tokens<'a, Token> {
+        &mut self.tokens
+    }
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self::with(input, Stores::Owned(Box::default()))
+    }
+
+    /// A parser interning its nodes into `stores`, so that they are shared
+    /// with the proof search that follows.
+    pub fn with_stores(input: &'a str, stores: &'a IntegerStores) -> Self {
+        Self::with(input, Stores::Shared(stores))
+    }
+
+    fn with(input: &'a str, stores: Stores<'a>) -> Self {
+        Self {
+            tokens: Tokens::new(input),
+            stores,
+            bound: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Parse a whole formula.
+    pub fn parse_formula(&mut self) -> Result<HashNode<IntegerExpression>, ParseError> {
+        let formula = self.parse_connectives()?;
+        self.tokens.expect_end()?;
+        Ok(formula)
+    }
+
+    fn parse_connectives(&mut self) -> Result<HashNode<IntegerExpression>, ParseError> {
+        parse_binary(self, CONNECTIVES, Self::parse_unary, |parser, operator, left, right| {
+            parser.compound(operator, vec![left, right])
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<HashNode<IntegerExpression>, ParseError> {
+        match self.tokens.peek() {
+            Some(Token::Not) => {
+                self.tokens.advance();
+                let inner = self.parse_unary()?;
+                Ok(self.compound(ClassicalOperator::Not, vec![inner]))
+            }
+            Some(Token::Forall) => {
+                self.tokens.advance();
+                self.parse_quantified(ClassicalOperator::Forall)
+            }
+            Some(Token::Exists) => {
+                self.tokens.advance();
+                self.parse_quantified(ClassicalOperator::Exists)
+            }
+            _ => self.parse_atom(),
+        }
+    }
+
+    /// Parse `x, y. body` after a quantifier, binding each name in turn.
+    fn parse_quantified(&mut self, quantifier: ClassicalOperator) -> Result<HashNode<IntegerExpression>, ParseError> {
+        let mut names = 0;
+        loop {
+            match self.tokens.peek() {
+                Some(Token::Ident(name)) => self.bound.push(name.clone()),
+                _ => return Err(self.tokens.unexpected(&["a variable name"])),
+            }
+            self.tokens.advance();
+            names += 1;
+            if !self.tokens.eat(&Token::Comma) {
+                break;
+            }
+        }
+        self.tokens.expect(Token::Dot)?;
+
+        let mut body = self.parse_connectives()?;
+        for _ in 0..names {
+            self.bound.pop();
+            body = self.compound(quantifier, vec![body]);
+        }
+        Ok(body)
+    }
+
+    fn parse_atom(&mut self) -> Result<HashNode<IntegerExpression>, ParseError> {
+        // A parenthesis opens either a formula or the first term of a relation.
+        if self.tokens.peek() == Some(&Token::LParen) {
+            let (start, bound, free) = (self.tokens.clone(), self.bound.len(), self.free.clone());
+            self.tokens.advance();
+            if let Ok(formula) = self.parse_connectives()
+                && self.tokens.eat(&Token::RParen)
+            {
+                return Ok(formula);
+            }
+            self.tokens = start;
+            self.bound.truncate(bound);
+            self.free = free;
+        }
+
+        let left = self.parse_term()?;
+        let relation = match self.tokens.peek() {
+            Some(Token::Eq) => IntegerContent::Equals,
+            Some(Token::Lt) => IntegerContent::LessThan,
+            Some(Token::Le) => IntegerContent::LessEq,
+            _ => return Err(self.tokens.unexpected(&["`=`", "`<`", "`<=`"])),
+        };
+        self.tokens.advance();
+        let content = HashNode::from_store(relation(left, self.parse_term()?), &self.stores.content_store);
+        Ok(HashNode::from_store(IntegerExpression::domain(content), &self.stores.expression_store))
+    }
+
+    /// Parse an integer term.
+    pub fn parse_term(&mut self) -> Result<HashNode<IntegerTerm>, ParseError> {
+        parse_binary(self, OPERATIONS, Self::parse_negation, |parser, operation, left, right| {
+            parser.term(operation(left, right))
+        })
+    }
+
+    fn parse_negation(&mut self) -> Result<HashNode<IntegerTerm>, ParseError> {
+        if !self.tokens.eat(&Token::Minus) {
+            return self.parse_primary_term();
+        }
+        let start = self.tokens.last().start;
+        if let Some(&Token::Number(n)) = self.tokens.peek() {
+            self.tokens.advance();
+            let span = Span { start, end: self.tokens.last().end };
+            let n = i64::try_from(n).map_err(|_| self.tokens.invalid(span, format!("Literal -{} is out of range", n)))?;
+            return Ok(self.term(IntegerTerm::Integer(-n)));
+        }
+        let inner = self.parse_negation()?;
+        Ok(self.term(IntegerTerm::Negate(inner)))
+    }
+
+    fn parse_primary_term(&mut self) -> Result<HashNode<IntegerTerm>, ParseError> {
+        let token = self.tokens.advance().ok_or_else(|| self.tokens.unexpected(&["a term"]))?;
+        match token {
+            Token::LParen => {
+                let inner = self.parse_term()?;
+                self.tokens.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Token::Number(n) => {
+                let span = self.tokens.last();
+                let n = i64::try_from(n).map_err(|_| self.tokens.invalid(span, format!("Literal {} is out of range", n)))?;
+                Ok(self.term(IntegerTerm::Integer(n)))
+            }
+            Token::DeBruijn(n) => Ok(self.term(IntegerTerm::DeBruijn(n))),
+            Token::Ident(name) => {
+                let index = self.variable_index(name);
+                Ok(self.term(IntegerTerm::DeBruijn(index)))
+            }
+            _ => Err(self.tokens.unexpected_previous(&["a term"])),
+        }
+    }
+
+    fn variable_index(&mut self, name: String) -> u32 {
+        if let Some(position) = self.bound.iter().rposition(|bound| *bound == name) {
+            return (self.bound.len() - 1 - position) as u32;
+        }
+        let free = match self.free.iter().position(|free| *free == name) {
+            Some(position) => position,
+            None => {
+                self.free.push(name);
+                self.free.len() - 1
+            }
+        };
+        (self.bound.len() + free) as u32
+    }
+
+    fn term(&self, term: IntegerTerm) -> HashNode<IntegerTerm> {
+        HashNode::from_store(term, &self.stores.term_store)
+    }
+
+    fn compound(
+        &self,
+        operator: ClassicalOperator,
+        operands: Vec<HashNode<IntegerExpression>>,
+    ) -> HashNode<IntegerExpression> {
+        let operands = operands.iter().map(|operand| operand.value.as_logical(&self.stores.logical_store)).collect();
+        let logical_node = HashNode::from_store(LogicalExpression::compound(operator, operands), &self.stores.logical_store);
+        HashNode::from_store(IntegerExpression::logical(logical_node), &self.stores.expression_store)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> String {
+        Parser::new(text).parse_formula().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_infix_formulas() {
+        assert_eq!(parse("x - -y = x + y"), parse("/0 - -/1 = /0 + /1"));
+        assert_eq!(parse("-2 * x = -(2 * x)"), "((-2) * /0) = -(2 * /0)");
+        assert_eq!(parse("x - 1 - 1 = x - 2"), "((/0 - 1) - 1) = (/0 - 2)");
+        assert_eq!(parse("forall x. exists y. x + y = 0"), parse("∀x. ∃y. /1 + /0 = 0"));
+        assert_eq!(parse("x < 0 -> 0 < -x"), parse("(/0 < 0) -> (0 < -/0)"));
+    }
+
+    #[test]
+    fn test_infix_errors() {
+        assert!(Parser::new("x + = 1").parse_formula().is_err());
+        assert!(Parser::new("x = 1 y").parse_formula().is_err());
+        assert!(Parser::new("forall . x = x").parse_formula().is_err());
+        assert!(Parser::new("x ^ 2 = 4").parse_formula().is_err());
+        let error = Parser::new("x + = 1").parse_formula().unwrap_err();
+        assert_eq!(error.to_string(), "1:5: expected a term, found `=`");
+    }
+}
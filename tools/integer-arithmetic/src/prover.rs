@@ -0,0 +1,150 @@
+//! Proof search for integer relations on the core `Prover`.
+//!
+//! The ring and normalization rules rewrite both sides of a relation through
+//! a `LayeredRewriter`, and variable-free subterms fold into literals. A
+//! state is closed once its sides are the same term, or both sides are
+//! ground and can be compared.
+
+use corpus_classical_logic::BinaryTruth;
+use corpus_core::nodes::HashNode;
+use corpus_core::proving::{GoalChecker, LayeredRewriter, ProofResult, Prover, SizeCostEstimator, StateExpander};
+use corpus_core::rewriting::{RewriteDirection, RewriteResult, RewriteRule};
+
+use crate::axioms::integer_arithmetic_rules;
+use crate::eval::{fold_constant, ground_relation, holds_with, variables};
+use crate::stores::IntegerStores;
+use crate::syntax::{IntegerContent, IntegerTerm};
+
+/// Outcome of attempting to settle an integer relation.
+pub enum IntegerOutcome {
+    /// The search reached a goal state, whose truth value says whether the
+    /// relation was proved or refuted.
+    Proved(ProofResult<IntegerContent, BinaryTruth>),
+    /// Values of the variables, by De Bruijn index, falsifying the relation.
+    Disproved { witness: Vec<i64> },
+    /// Neither a proof nor a counterexample was found within the limits.
+    Unknown,
+}
+
+/// Search for a proof of `relation` with the integer rules.
+pub fn prove_integer(
+    relation: &HashNode<IntegerContent>,
+    stores: &IntegerStores,
+    max_nodes: usize,
+) -> Option<ProofResult<IntegerContent, BinaryTruth>> {
+    prove_integer_with_rules(relation, stores, max_nodes, &integer_arithmetic_rules())
+}
+
+/// `prove_integer` with the given rules in place of the integer rules.
+pub fn prove_integer_with_rules(
+    relation: &HashNode<IntegerContent>,
+    stores: &IntegerStores,
+    max_nodes: usize,
+    rules: &[RewriteRule<IntegerTerm>],
+) -> Option<ProofResult<IntegerContent, BinaryTruth>> {
+    let prover: Prover<_, _, BinaryTruth, _> =
+        Prover::builder(SizeCostEstimator, IntegerGoalChecker).max_nodes(max_nodes).build();
+    let expander = IntegerExpander {
+        rewriter: LayeredRewriter::new(rules, &stores.content_store, &stores.term_store),
+    };
+    prover.prove_with_expander(relation, &expander).ok()
+}
+
+/// Try every assignment of `-max_witness..=max_witness` to the variables of
+/// `relation` before spending `max_nodes` on `prove_integer`.
+pub fn prove_or_disprove(
+    relation: &HashNode<IntegerContent>,
+    stores: &IntegerStores,
+    max_nodes: usize,
+    max_witness: i64,
+) -> IntegerOutcome {
+    if let Some(witness) = find_counterexample(&relation.value, max_witness) {
+        return IntegerOutcome::Disproved { witness };
+    }
+    prove_integer(relation, stores, max_nodes).map_or(IntegerOutcome::Unknown, IntegerOutcome::Proved)
+}
+
+/// An assignment of values in `-bound..=bound` under which `relation` fails.
+pub fn find_counterexample(relation: &IntegerContent, bound: i64) -> Option<Vec<i64>> {
+    let (left, right) = relation.sides();
+    let count = variables(left).union(&variables(right)).max().map_or(0, |max| *max as usize + 1);
+    let mut assignment = vec![-bound; count];
+    loop {
+        if holds_with(relation, &assignment) == Some(false) {
+            return Some(assignment);
+        }
+        // Advance the assignment like an odometer.
+        let position = assignment.iter().position(|value| *value < bound)?;
+        assignment[position] += 1;
+        assignment[..position].fill(-bound);
+    }
+}
+
+/// Closes reflexive relations and relations between ground terms.
+struct IntegerGoalChecker;
+
+impl GoalChecker<IntegerContent, BinaryTruth> for IntegerGoalChecker {
+    fn check(&self, expr: &HashNode<IntegerContent>) -> Option<BinaryTruth> {
+        let (left, right) = expr.value.sides();
+        if left == right {
+            return Some(BinaryTruth::from(!matches!(expr.value.as_ref(), IntegerContent::LessThan(..))));
+        }
+        ground_relation(&expr.value).map(BinaryTruth::from)
+    }
+}
+
+/// The rules and constant folding at every subterm of either side.
+struct IntegerExpander<'a> {
+    rewriter: LayeredRewriter<'a, IntegerContent, IntegerTerm>,
+}
+
+impl StateExpander<IntegerContent> for IntegerExpander<'_> {
+    fn expand(&self, expr: &HashNode<IntegerContent>) -> Vec<RewriteResult<IntegerContent>> {
+        let store = self.rewriter.inner_store();
+        self.rewriter.rewrites_with(expr, |subterm| {
+            let folded = fold_constant(subterm, store).map(|term| (term, "evaluation".to_string(), RewriteDirection::Forward));
+            folded.into_iter().chain(self.rewriter.rule_rewrites(subterm)).collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    fn relation(text: &str, stores: &IntegerStores) -> HashNode<IntegerContent> {
+        let formula = Parser::with_stores(text, stores).parse_formula().unwrap();
+        formula.value.as_domain().unwrap().clone()
+    }
+
+    #[test]
+    fn test_ring_identities() {
+        let stores = IntegerStores::new();
+        for (text, rule) in [
+            ("x - x = 0", "add_inverse"),
+            ("(x + y) - y = x", "add_associative"),
+            ("-(x + y) = -y + -x", "negate_add"),
+            ("x * (y + 1) = x * y + x", "distributive"),
+        ] {
+            let result = prove_integer(&relation(text, &stores), &stores, 5000).unwrap_or_else(|| panic!("{}", text));
+            assert_eq!(result.truth_result, BinaryTruth::True);
+            assert!(result.steps.iter().any(|step| step.rule_name == rule), "{} without {}", text, rule);
+        }
+    }
+
+    #[test]
+    fn test_ground_relations_and_counterexamples() {
+        let stores = IntegerStores::new();
+        let result = prove_integer(&relation("3 - 5 < -1", &stores), &stores, 10).unwrap();
+        assert_eq!(result.truth_result, BinaryTruth::True);
+        assert_eq!(result.steps.len(), 0);
+
+        // x - 1 < x holds in ℤ, but x - 1 = 0 fails at x = -1.
+        assert!(matches!(prove_or_disprove(&relation("x - 1 < x", &stores), &stores, 10, 3), IntegerOutcome::Unknown));
+        let IntegerOutcome::Disproved { witness } = prove_or_disprove(&relation("x - 1 = 0", &stores), &stores, 10, 3) else {
+            panic!("x - 1 = 0 should fail");
+        };
+        assert_eq!(witness, vec![-3]);
+    }
+}
@@ -0,0 +1,38 @@
+//! The node stores shared by parsing and proof search, as in the PA tool.
+
+use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
+use corpus_core::expression::LogicalExpression;
+use corpus_core::nodes::NodeStorage;
+
+use crate::domain::IntegerDomain;
+use crate::syntax::{IntegerContent, IntegerExpression, IntegerTerm};
+
+/// One store per node type of integer formulas.
+pub struct IntegerStores {
+    pub expression_store: NodeStorage<IntegerExpression>,
+    pub term_store: NodeStorage<IntegerTerm>,
+    pub content_store: NodeStorage<IntegerContent>,
+    pub logical_store: NodeStorage<LogicalExpression<BinaryTruth, IntegerContent, ClassicalOperator>>,
+}
+
+impl Default for IntegerStores {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntegerStores {
+    pub fn new() -> Self {
+        Self {
+            expression_store: NodeStorage::new(),
+            term_store: NodeStorage::new(),
+            content_store: NodeStorage::new(),
+            logical_store: NodeStorage::new(),
+        }
+    }
+
+    /// The first-order view of integer formulas over these stores.
+    pub fn domain(&self) -> IntegerDomain<'_> {
+        IntegerDomain::new(&self.content_store, &self.term_store)
+    }
+}
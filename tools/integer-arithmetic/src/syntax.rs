@@ -0,0 +1,213 @@
+use core::fmt;
+
+use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
+use corpus_core::expression::{DomainContent, DomainExpression};
+use corpus_core::nodes::{HashNode, HashNodeInner, Hashing, NodeStorage};
+use corpus_core::printing::{Associativity, ExprPrinter, NotationPrinter, Parentheses, SymbolTable};
+use corpus_core::proving::SubtermRewritable;
+
+pub type IntegerExpression = DomainExpression<BinaryTruth, IntegerContent>;
+
+/// An atom comparing two integer terms.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum IntegerContent {
+    Equals(HashNode<IntegerTerm>, HashNode<IntegerTerm>),
+    /// Strict order: the left side is less than the right.
+    LessThan(HashNode<IntegerTerm>, HashNode<IntegerTerm>),
+    /// Non-strict order: the left side is at most the right.
+    LessEq(HashNode<IntegerTerm>, HashNode<IntegerTerm>),
+}
+
+impl IntegerContent {
+    /// The two sides of the relation.
+    pub fn sides(&self) -> (&HashNode<IntegerTerm>, &HashNode<IntegerTerm>) {
+        match self {
+            IntegerContent::Equals(left, right)
+            | IntegerContent::LessThan(left, right)
+            | IntegerContent::LessEq(left, right) => (left, right),
+        }
+    }
+
+    /// The same relation between new sides.
+    pub fn with_sides(&self, left: HashNode<IntegerTerm>, right: HashNode<IntegerTerm>) -> Self {
+        match self {
+            IntegerContent::Equals(..) => IntegerContent::Equals(left, right),
+            IntegerContent::LessThan(..) => IntegerContent::LessThan(left, right),
+            IntegerContent::LessEq(..) => IntegerContent::LessEq(left, right),
+        }
+    }
+
+    /// The opcode naming the relation.
+    pub fn opcode(&self) -> u64 {
+        match self {
+            IntegerContent::Equals(..) => Hashing::opcode("int_equals"),
+            IntegerContent::LessThan(..) => Hashing::opcode("int_less_than"),
+            IntegerContent::LessEq(..) => Hashing::opcode("int_less_eq"),
+        }
+    }
+}
+
+/// The core prover rewrites both sides of a relation with term rules.
+impl SubtermRewritable<IntegerTerm> for IntegerContent {
+    fn inner_terms(&self) -> Vec<HashNode<IntegerTerm>> {
+        let (left, right) = self.sides();
+        vec![left.clone(), right.clone()]
+    }
+
+    fn with_inner_terms(&self, terms: Vec<HashNode<IntegerTerm>>) -> Option<Self> {
+        let [left, right] = <[_; 2]>::try_from(terms).ok()?;
+        Some(self.with_sides(left, right))
+    }
+}
+
+impl DomainContent<BinaryTruth> for IntegerContent {
+    type Operator = ClassicalOperator;
+}
+
+impl fmt::Display for IntegerContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&printer().with_parentheses(Parentheses::Full).print(self))
+    }
+}
+
+impl HashNodeInner for IntegerContent {
+    fn hash(&self) -> u64 {
+        let (left, right) = self.sides();
+        Hashing::root_hash(self.opcode(), &[left.hash(), right.hash()])
+    }
+
+    fn size(&self) -> u64 {
+        let (left, right) = self.sides();
+        1 + left.size() + right.size()
+    }
+
+    fn decompose(&self) -> Option<(u64, Vec<HashNode<Self>>)> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum IntegerTerm {
+    Add(HashNode<IntegerTerm>, HashNode<IntegerTerm>),
+    Subtract(HashNode<IntegerTerm>, HashNode<IntegerTerm>),
+    /// Additive inverse `-x`.
+    Negate(HashNode<IntegerTerm>),
+    Multiply(HashNode<IntegerTerm>, HashNode<IntegerTerm>),
+    /// An integer literal, negative ones included.
+    Integer(i64),
+    DeBruijn(u32),
+}
+
+impl fmt::Display for IntegerTerm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&printer().with_parentheses(Parentheses::Full).print(self))
+    }
+}
+
+/// Renders terms with `+`, `-` and `*` binding as the parser reads them;
+/// `Display` is this printer with full parentheses.
+pub fn printer() -> NotationPrinter<IntegerTerm> {
+    let symbols = SymbolTable::new()
+        .infix("int_add", "+", 1, Associativity::Left)
+        .infix("int_subtract", "-", 1, Associativity::Left)
+        .infix("int_multiply", "*", 2, Associativity::Left)
+        .prefix("int_negate", "-", 3);
+    NotationPrinter::new(symbols, |leaf| match leaf {
+        IntegerTerm::Integer(n) if *n < 0 => format!("({})", n),
+        IntegerTerm::Integer(n) => n.to_string(),
+        IntegerTerm::DeBruijn(index) => format!("/{}", index),
+        compound => unreachable!("{:?} decomposes", compound),
+    })
+}
+
+/// Atoms, with their sides printed as terms.
+impl ExprPrinter<IntegerContent> for NotationPrinter<IntegerTerm> {
+    fn print(&self, content: &IntegerContent) -> String {
+        let relation = match content {
+            IntegerContent::Equals(..) => "=",
+            IntegerContent::LessThan(..) => "<",
+            IntegerContent::LessEq(..) => "≤",
+        };
+        let (left, right) = content.sides();
+        format!("{} {} {}", self.print(left.value.as_ref()), relation, self.print(right.value.as_ref()))
+    }
+}
+
+impl HashNodeInner for IntegerTerm {
+    fn hash(&self) -> u64 {
+        match self {
+            IntegerTerm::Integer(n) => Hashing::root_hash(Hashing::opcode("integer"), &[*n as u64]),
+            IntegerTerm::DeBruijn(idx) => Hashing::root_hash(Hashing::opcode("debruijn"), &[*idx as u64]),
+            compound => {
+                let (opcode, children) = compound.decompose().expect("compound terms decompose");
+                let hashes: Vec<_> = children.iter().map(|child| child.hash()).collect();
+                Hashing::root_hash(opcode, &hashes)
+            }
+        }
+    }
+
+    fn size(&self) -> u64 {
+        match self {
+            IntegerTerm::Add(left, right) | IntegerTerm::Subtract(left, right) | IntegerTerm::Multiply(left, right) => {
+                1 + left.size() + right.size()
+            }
+            IntegerTerm::Negate(inner) => 1 + inner.size(),
+            IntegerTerm::Integer(_) | IntegerTerm::DeBruijn(_) => 1,
+        }
+    }
+
+    fn decompose(&self) -> Option<(u64, Vec<HashNode<Self>>)> {
+        match self {
+            IntegerTerm::Add(left, right) => Some((Hashing::opcode("int_add"), vec![left.clone(), right.clone()])),
+            IntegerTerm::Subtract(left, right) => {
+                Some((Hashing::opcode("int_subtract"), vec![left.clone(), right.clone()]))
+            }
+            IntegerTerm::Negate(inner) => Some((Hashing::opcode("int_negate"), vec![inner.clone()])),
+            IntegerTerm::Multiply(left, right) => {
+                Some((Hashing::opcode("int_multiply"), vec![left.clone(), right.clone()]))
+            }
+            IntegerTerm::Integer(_) | IntegerTerm::DeBruijn(_) => None,
+        }
+    }
+
+    fn construct_from_parts(opcode: u64, mut children: Vec<HashNode<Self>>, store: &NodeStorage<Self>) -> Option<HashNode<Self>> {
+        // Opcodes are not constants, so this cannot be a true match.
+        let term = match (opcode, children.len()) {
+            (o, 1) if o == Hashing::opcode("int_negate") => IntegerTerm::Negate(children.pop()?),
+            (o, 2) => {
+                let right = children.pop()?;
+                let left = children.pop()?;
+                match o {
+                    o if o == Hashing::opcode("int_add") => IntegerTerm::Add(left, right),
+                    o if o == Hashing::opcode("int_subtract") => IntegerTerm::Subtract(left, right),
+                    o if o == Hashing::opcode("int_multiply") => IntegerTerm::Multiply(left, right),
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        Some(HashNode::from_store(term, store))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terms_rebuild_from_their_parts() {
+        let store = NodeStorage::new();
+        let x = HashNode::from_store(IntegerTerm::DeBruijn(0), &store);
+        let three = HashNode::from_store(IntegerTerm::Integer(-3), &store);
+        let negated = HashNode::from_store(IntegerTerm::Negate(x.clone()), &store);
+        let difference = HashNode::from_store(IntegerTerm::Subtract(negated, three), &store);
+        assert_eq!(difference.to_string(), "(-/0 - (-3))");
+
+        let (opcode, children) = difference.value.decompose().unwrap();
+        let rebuilt = IntegerTerm::construct_from_parts(opcode, children, &store).unwrap();
+        assert_eq!(rebuilt, difference);
+        assert_ne!(opcode, Hashing::opcode("int_add"));
+    }
+}
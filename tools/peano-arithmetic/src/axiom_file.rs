@@ -0,0 +1,182 @@
+//! Axiom files: theories written as text instead of Rust.
+//!
+//! ```text
+//! # Comments start with '#'.
+//! include ordering.axioms
+//! axiom axiom3_additive_identity: EQ (PLUS (/0) (0)) (/0)
+//! axiom add_comm: x + y = y + x
+//! ```
+//!
+//! Each `axiom` line names a formula in the syntax of `parse_axiom`, with its
+//! variables implicitly universal. `include <path>` reads another file in
+//! place, its path relative to the including file; a file already read is
+//! skipped, so includes may repeat or form cycles. Axiom names must be unique
+//! across all the files read.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
+use corpus_core::base::axioms::NamedAxiom;
+
+use crate::parsing::parse_axiom;
+use crate::stores::PeanoStores;
+use crate::syntax::PeanoContent;
+
+/// An axiom as written in a file, before its formula is parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AxiomDeclaration {
+    pub name: String,
+    pub formula: String,
+    /// The file declaring it, as named by the loader or the include.
+    pub path: PathBuf,
+    /// Line of the declaration, counting from 1.
+    pub line: usize,
+}
+
+impl AxiomDeclaration {
+    /// Parse the formula into a `NamedAxiom`.
+    pub fn parse(&self, stores: &PeanoStores) -> Result<NamedAxiom<BinaryTruth, PeanoContent, ClassicalOperator>, AxiomFileError> {
+        parse_axiom(&self.formula, &self.name, stores).map_err(|e| AxiomFileError::Format {
+            path: self.path.clone(),
+            line: self.line,
+            message: e.to_string(),
+        })
+    }
+}
+
+/// Failure to read an axiom file.
+#[derive(Debug)]
+pub enum AxiomFileError {
+    Io { path: PathBuf, error: io::Error },
+    /// A malformed line or formula, at a line counting from 1.
+    Format { path: PathBuf, line: usize, message: String },
+}
+
+impl fmt::Display for AxiomFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AxiomFileError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            AxiomFileError::Format { path, line, message } => write!(f, "{}:{}: {}", path.display(), line, message),
+        }
+    }
+}
+
+impl std::error::Error for AxiomFileError {}
+
+/// The axioms of the file at `path` and of the files it includes.
+pub fn load_axioms(
+    path: impl AsRef<Path>,
+    stores: &PeanoStores,
+) -> Result<Vec<NamedAxiom<BinaryTruth, PeanoContent, ClassicalOperator>>, AxiomFileError> {
+    read_axiom_file(path)?.iter().map(|declaration| declaration.parse(stores)).collect()
+}
+
+/// The declarations of the file at `path`, with those of its includes in
+/// place of each `include` line.
+pub fn read_axiom_file(path: impl AsRef<Path>) -> Result<Vec<AxiomDeclaration>, AxiomFileError> {
+    let mut loader = Loader::default();
+    loader.read(path.as_ref())?;
+    Ok(loader.declarations)
+}
+
+/// The declarations of `text`, read as the contents of `path`; includes are
+/// resolved relative to it.
+pub fn parse_axiom_file(text: &str, path: impl AsRef<Path>) -> Result<Vec<AxiomDeclaration>, AxiomFileError> {
+    let mut loader = Loader::default();
+    loader.parse(text, path.as_ref())?;
+    Ok(loader.declarations)
+}
+
+#[derive(Default)]
+struct Loader {
+    /// Files read so far, canonicalized.
+    seen: HashSet<PathBuf>,
+    declarations: Vec<AxiomDeclaration>,
+}
+
+impl Loader {
+    fn read(&mut self, path: &Path) -> Result<(), AxiomFileError> {
+        let io_error = |error| AxiomFileError::Io { path: path.to_path_buf(), error };
+        if !self.seen.insert(path.canonicalize().map_err(io_error)?) {
+            return Ok(());
+        }
+        let text = std::fs::read_to_string(path).map_err(io_error)?;
+        self.parse(&text, path)
+    }
+
+    fn parse(&mut self, text: &str, path: &Path) -> Result<(), AxiomFileError> {
+        if let Ok(canonical) = path.canonicalize() {
+            self.seen.insert(canonical);
+        }
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let error = |message: String| AxiomFileError::Format { path: path.to_path_buf(), line, message };
+            let content = raw.split('#').next().unwrap_or("").trim();
+            if content.is_empty() {
+                continue;
+            }
+
+            if let Some(included) = content.strip_prefix("include ") {
+                let included = included.trim().trim_matches('"');
+                self.read(&path.parent().unwrap_or(Path::new("")).join(included))?;
+                continue;
+            }
+
+            let rest = content.strip_prefix("axiom ").ok_or_else(|| error(format!("unexpected line '{}'", content)))?;
+            let (name, formula) = rest.split_once(':').ok_or_else(|| error("expected 'axiom <name>: <formula>'".into()))?;
+            let name = name.trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(error(format!("invalid axiom name '{}'", name)));
+            }
+            if let Some(earlier) = self.declarations.iter().find(|declaration| declaration.name == name) {
+                let message = format!("axiom '{}' is already declared at {}:{}", name, earlier.path.display(), earlier.line);
+                return Err(error(message));
+            }
+            self.declarations.push(AxiomDeclaration {
+                name: name.to_string(),
+                formula: formula.trim().to_string(),
+                path: path.to_path_buf(),
+                line,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use corpus_core::base::axioms::Axiom;
+
+    #[test]
+    fn test_includes() {
+        let dir = std::env::temp_dir().join(format!("axiom-file-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("lib")).unwrap();
+        std::fs::write(dir.join("lib/order.axioms"), "include ../main.axioms\naxiom zero_least: 0 <= x\n").unwrap();
+        std::fs::write(dir.join("main.axioms"), "# Arithmetic\ninclude lib/order.axioms\n\naxiom add_zero: x + 0 = x  # identity\n").unwrap();
+
+        let axioms = load_axioms(dir.join("main.axioms"), &PeanoStores::new()).unwrap();
+        let names: Vec<_> = axioms.iter().map(|axiom| axiom.name()).collect();
+        assert_eq!(names, ["zero_least", "add_zero"]);
+
+        std::fs::write(dir.join("twice.axioms"), "include main.axioms\naxiom add_zero: 0 + x = x\n").unwrap();
+        let error = read_axiom_file(dir.join("twice.axioms")).unwrap_err().to_string();
+        let earlier = format!("{}:4", dir.join("main.axioms").display());
+        assert!(error.ends_with(&format!("twice.axioms:2: axiom 'add_zero' is already declared at {}", earlier)), "{}", error);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_errors() {
+        let parse = |text| parse_axiom_file(text, "t.axioms").and_then(|declarations| {
+            declarations.iter().try_for_each(|declaration| declaration.parse(&PeanoStores::new()).map(drop))
+        });
+        assert_eq!(parse("theorem a: x = x").unwrap_err().to_string(), "t.axioms:1: unexpected line 'theorem a: x = x'");
+        assert_eq!(parse("axiom x = x").unwrap_err().to_string(), "t.axioms:1: expected 'axiom <name>: <formula>'");
+        assert!(parse("\naxiom bad: x +").unwrap_err().to_string().starts_with("t.axioms:2: "));
+        assert!(matches!(parse("include missing.axioms").unwrap_err(), AxiomFileError::Io { .. }));
+    }
+}
@@ -3,16 +3,20 @@
 //! This module defines PA axioms as concise string declarations that are
 //! parsed into first-class `NamedAxiom` instances.
 
-use corpus_core::base::axioms::NamedAxiom;
-use corpus_core::nodes::Hashing;
+use corpus_core::base::axioms::{AxiomError, AxiomSchema, NamedAxiom};
+use corpus_core::expression::LogicalExpression;
+use corpus_core::nodes::{HashNode, Hashing, NodeStorage};
 use corpus_core::rewriting::{Pattern, RewriteDirection, RewriteRule};
-use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
-use crate::parsing::{parse_axiom, AxiomStores};
+use corpus_classical_logic::{free_variables, instantiate, BinaryTruth, ClassicalOperator};
+use crate::axiom_file::parse_axiom_file;
+use crate::domain::PeanoDomain;
+use crate::stores::PeanoStores;
 use crate::syntax::{ArithmeticExpression, PeanoContent};
 
 /// PA axioms as first-class NamedAxiom instances.
 ///
-/// Uses string-based parsing for clean, readable axiom declarations.
+/// They are declared in `axioms/peano.axioms`, in the format of
+/// `axiom_file`, with formulas in the prefix syntax of `parse_axiom`:
 ///
 /// # Syntax
 /// - `EQ (<left>) (<right>)` - equality
@@ -22,15 +26,12 @@ use crate::syntax::{ArithmeticExpression, PeanoContent};
 /// - `/0`, `/1`, `/2` - De Bruijn indices for variables
 ///
 /// # Examples
-/// ```ignore
-/// // Successor injectivity: S(x) = S(y) -> x = y
-/// "-> (EQ (S (/0)) (S (/1))) (EQ (/0) (/1))"
+/// ```text
+/// # Successor injectivity: S(x) = S(y) -> x = y
+/// axiom axiom2_successor_injectivity: -> (EQ (S (/0)) (S (/1))) (EQ (/0) (/1))
 ///
-/// // Additive identity: x + 0 = x
-/// "EQ (PLUS (/0) (0)) (/0)"
-///
-/// // Additive successor: x + S(y) = S(x + y)
-/// "EQ (PLUS (/0) (S (/1))) (S (PLUS (/0) (/1)))"
+/// # Additive identity: x + 0 = x
+/// axiom axiom3_additive_identity: EQ (PLUS (/0) (0)) (/0)
 /// ```
 ///
 /// Note:
@@ -40,36 +41,26 @@ use crate::syntax::{ArithmeticExpression, PeanoContent};
 /// - Quantifiers are not needed in axiom strings since rewrite rules
 ///   implicitly apply universally
 pub fn peano_arithmetic_axioms() -> Vec<NamedAxiom<BinaryTruth, PeanoContent, ClassicalOperator>> {
-    let stores = AxiomStores::new();
+    builtin_axioms(PEANO_AXIOMS, "axioms/peano.axioms")
+}
 
-    vec![
-        // Axiom 2: Successor injectivity
-        // S(x) = S(y) -> x = y
-        parse_axiom(
-            "-> (EQ (S (/0)) (S (/1))) (EQ (/0) (/1))",
-            "axiom2_successor_injectivity",
-            &stores,
-        )
-        .expect("Failed to parse axiom2_successor_injectivity"),
+/// Axioms for `<` and `≤`, implicitly universal like `peano_arithmetic_axioms`,
+/// declared in `axioms/ordering.axioms`.
+///
+/// `x < y` is characterised through `S(x) ≤ y`, and `≤` is closed under
+/// successor on the right with `0` as its least element.
+pub fn peano_ordering_axioms() -> Vec<NamedAxiom<BinaryTruth, PeanoContent, ClassicalOperator>> {
+    builtin_axioms(ORDERING_AXIOMS, "axioms/ordering.axioms")
+}
 
-        // Axiom 3: Additive identity
-        // x + 0 = x
-        parse_axiom(
-            "EQ (PLUS (/0) (0)) (/0)",
-            "axiom3_additive_identity",
-            &stores,
-        )
-        .expect("Failed to parse axiom3_additive_identity"),
+const PEANO_AXIOMS: &str = include_str!("../axioms/peano.axioms");
+const ORDERING_AXIOMS: &str = include_str!("../axioms/ordering.axioms");
 
-        // Axiom 4: Additive successor
-        // x + S(y) = S(x + y)
-        parse_axiom(
-            "EQ (PLUS (/0) (S (/1))) (S (PLUS (/0) (/1)))",
-            "axiom4_additive_successor",
-            &stores,
-        )
-        .expect("Failed to parse axiom4_additive_successor"),
-    ]
+fn builtin_axioms(text: &str, path: &str) -> Vec<NamedAxiom<BinaryTruth, PeanoContent, ClassicalOperator>> {
+    let stores = PeanoStores::new();
+    parse_axiom_file(text, path)
+        .and_then(|declarations| declarations.iter().map(|declaration| declaration.parse(&stores)).collect())
+        .unwrap_or_else(|e| panic!("Failed to parse {}", e))
 }
 
 /// Generate arithmetic rewrite rules from PA axioms.
@@ -82,6 +73,15 @@ pub fn peano_arithmetic_axioms() -> Vec<NamedAxiom<BinaryTruth, PeanoContent, Cl
 /// - Axiom 2: S(x) = S(y) -> x = y (successor injectivity)
 /// - Axiom 3: x + 0 = x (additive identity)
 /// - Axiom 4: x + S(y) = S(x + y) (additive successor)
+///
+/// followed by the defining equations of predecessor and monus:
+/// `P(0) = 0`, `P(S(x)) = x`, `x ∸ 0 = x` and `x ∸ S(y) = P(x ∸ y)`, plus
+/// their consequence `S(x) ∸ S(y) = x ∸ y`, without which induction cannot
+/// cancel a successor on both sides of `∸`.
+///
+/// Multiplication and exponentiation are defined by primitive recursion on
+/// the right argument: `x * 0 = 0`, `x * S(y) = x * y + x`, `x ^ 0 = S(0)`
+/// and `x ^ S(y) = x ^ y * x`.
 pub fn peano_arithmetic_rules() -> Vec<RewriteRule<ArithmeticExpression>> {
     vec![
         // Axiom 2: S(x) = S(y) -> x = y (bidirectional)
@@ -118,12 +118,129 @@ pub fn peano_arithmetic_rules() -> Vec<RewriteRule<ArithmeticExpression>> {
 
             RewriteRule::new("axiom4_additive_successor", pattern, replacement, RewriteDirection::Forward)
         },
+        // P(0) = 0 (forward)
+        {
+            let zero = Pattern::constant(ArithmeticExpression::Number(0));
+            let pattern = Pattern::compound(Hashing::opcode("predecessor"), vec![zero.clone()]);
+
+            RewriteRule::new("pred_zero", pattern, zero, RewriteDirection::Forward)
+        },
+        // P(S(x)) = x (forward)
+        {
+            let x = Pattern::var(0);
+            let sx = Pattern::compound(Hashing::opcode("successor"), vec![x.clone()]);
+            let pattern = Pattern::compound(Hashing::opcode("predecessor"), vec![sx]);
+
+            RewriteRule::new("pred_successor", pattern, x, RewriteDirection::Forward)
+        },
+        // x ∸ 0 = x (forward)
+        {
+            let x = Pattern::var(0);
+            let zero = Pattern::constant(ArithmeticExpression::Number(0));
+            let pattern = Pattern::compound(Hashing::opcode("monus"), vec![x.clone(), zero]);
+
+            RewriteRule::new("monus_zero", pattern, x, RewriteDirection::Forward)
+        },
+        // x ∸ S(y) = P(x ∸ y) (forward)
+        {
+            let x = Pattern::var(0);
+            let y = Pattern::var(1);
+            let sy = Pattern::compound(Hashing::opcode("successor"), vec![y.clone()]);
+            let pattern = Pattern::compound(Hashing::opcode("monus"), vec![x.clone(), sy]);
+
+            let x_monus_y = Pattern::compound(Hashing::opcode("monus"), vec![x, y]);
+            let replacement = Pattern::compound(Hashing::opcode("predecessor"), vec![x_monus_y]);
+
+            RewriteRule::new("monus_successor", pattern, replacement, RewriteDirection::Forward)
+        },
+        // S(x) ∸ S(y) = x ∸ y (forward), a lemma of the two above
+        {
+            let sx = Pattern::compound(Hashing::opcode("successor"), vec![Pattern::var(0)]);
+            let sy = Pattern::compound(Hashing::opcode("successor"), vec![Pattern::var(1)]);
+            let pattern = Pattern::compound(Hashing::opcode("monus"), vec![sx, sy]);
+
+            let replacement = Pattern::compound(Hashing::opcode("monus"), vec![Pattern::var(0), Pattern::var(1)]);
+
+            RewriteRule::new("monus_successor_both", pattern, replacement, RewriteDirection::Forward)
+        },
+        // x * 0 = 0 (forward)
+        {
+            let zero = Pattern::constant(ArithmeticExpression::Number(0));
+            let pattern = Pattern::compound(Hashing::opcode("multiply"), vec![Pattern::var(0), zero.clone()]);
+
+            RewriteRule::new("multiply_zero", pattern, zero, RewriteDirection::Forward)
+        },
+        // x * S(y) = x * y + x (forward)
+        {
+            let x = Pattern::var(0);
+            let y = Pattern::var(1);
+            let sy = Pattern::compound(Hashing::opcode("successor"), vec![y.clone()]);
+            let pattern = Pattern::compound(Hashing::opcode("multiply"), vec![x.clone(), sy]);
+
+            let x_times_y = Pattern::compound(Hashing::opcode("multiply"), vec![x.clone(), y]);
+            let replacement = Pattern::compound(Hashing::opcode("add"), vec![x_times_y, x]);
+
+            RewriteRule::new("multiply_successor", pattern, replacement, RewriteDirection::Forward)
+        },
+        // x ^ 0 = S(0) (forward)
+        {
+            let zero = Pattern::constant(ArithmeticExpression::Number(0));
+            let pattern = Pattern::compound(Hashing::opcode("power"), vec![Pattern::var(0), zero.clone()]);
+
+            let replacement = Pattern::compound(Hashing::opcode("successor"), vec![zero]);
+
+            RewriteRule::new("power_zero", pattern, replacement, RewriteDirection::Forward)
+        },
+        // x ^ S(y) = x ^ y * x (forward)
+        {
+            let x = Pattern::var(0);
+            let y = Pattern::var(1);
+            let sy = Pattern::compound(Hashing::opcode("successor"), vec![y.clone()]);
+            let pattern = Pattern::compound(Hashing::opcode("power"), vec![x.clone(), sy]);
+
+            let x_power_y = Pattern::compound(Hashing::opcode("power"), vec![x.clone(), y]);
+            let replacement = Pattern::compound(Hashing::opcode("multiply"), vec![x_power_y, x]);
+
+            RewriteRule::new("power_successor", pattern, replacement, RewriteDirection::Forward)
+        },
     ]
 }
 
+/// The induction schema `P(0) ∧ ∀x (P(x) → P(S(x))) → ∀x P(x)`.
+///
+/// Its one argument is the formula `P`, with `x` as `/0` and no other free
+/// variables; instances with other free variables are rejected as unbound.
+pub fn induction_schema() -> AxiomSchema<BinaryTruth, PeanoContent, ClassicalOperator> {
+    let logical_store = NodeStorage::new();
+    let content_store = NodeStorage::new();
+    let expression_store = NodeStorage::new();
+
+    AxiomSchema::new("induction", 1, move |arguments| {
+        let domain = PeanoDomain::new(&content_store, &expression_store);
+        let compound = |operator, operands| HashNode::from_store(LogicalExpression::compound(operator, operands), &logical_store);
+
+        let predicate = &arguments[0];
+        let closure = compound(ClassicalOperator::Forall, vec![predicate.clone()]);
+        if let Some(index) = free_variables(&closure, &domain).into_iter().max() {
+            return Err(AxiomError::UnboundVariable { index: index + 1 });
+        }
+
+        let zero = HashNode::from_store(ArithmeticExpression::Number(0), &expression_store);
+        let x = HashNode::from_store(ArithmeticExpression::DeBruijn(0), &expression_store);
+        let successor = HashNode::from_store(ArithmeticExpression::Successor(x), &expression_store);
+        let base = instantiate(&closure, &zero, &domain, &logical_store).ok_or(AxiomError::NotAnAxiom)?;
+        let next = instantiate(&closure, &successor, &domain, &logical_store).ok_or(AxiomError::NotAnAxiom)?;
+
+        let step = compound(ClassicalOperator::Forall, vec![compound(ClassicalOperator::Implies, vec![predicate.clone(), next])]);
+        let premise = compound(ClassicalOperator::And, vec![base, step]);
+        Ok(compound(ClassicalOperator::Implies, vec![premise, closure]))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parsing::parse_axiom;
     use corpus_core::base::axioms::Axiom;
 
     #[test]
@@ -161,7 +278,7 @@ mod tests {
 
     #[test]
     fn test_axiom2_successor_injectivity() {
-        let stores = AxiomStores::new();
+        let stores = PeanoStores::new();
         let axiom = parse_axiom(
             "-> (EQ (S (/0)) (S (/1))) (EQ (/0) (/1))",
             "test_axiom2",
@@ -178,7 +295,7 @@ mod tests {
 
     #[test]
     fn test_axiom3_additive_identity() {
-        let stores = AxiomStores::new();
+        let stores = PeanoStores::new();
         let axiom = parse_axiom(
             "EQ (PLUS (/0) (0)) (/0)",
             "test_axiom3",
@@ -195,7 +312,7 @@ mod tests {
 
     #[test]
     fn test_axiom4_additive_successor() {
-        let stores = AxiomStores::new();
+        let stores = PeanoStores::new();
         let axiom = parse_axiom(
             "EQ (PLUS (/0) (S (/1))) (S (PLUS (/0) (/1)))",
             "test_axiom4",
@@ -212,8 +329,47 @@ mod tests {
 
     #[test]
     fn test_parse_error_invalid_syntax() {
-        let stores = AxiomStores::new();
+        let stores = PeanoStores::new();
         let result = parse_axiom("invalid syntax", "test", &stores);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_ordering_axioms_convert_to_rules() {
+        let axioms = peano_ordering_axioms();
+        assert_eq!(axioms.len(), 4);
+        for axiom in &axioms {
+            assert_eq!(axiom.operator(), Some(&ClassicalOperator::Implies), "{}", axiom.name());
+            assert!(!axiom.to_rewrite_rules().is_empty(), "{}", axiom.name());
+        }
+    }
+
+    #[test]
+    fn test_induction_schema_instance() {
+        let stores = PeanoStores::new();
+        let parse = |text| parse_axiom(text, "p", &stores).unwrap().expression;
+        let schema = induction_schema();
+
+        let predicate = parse("EQ (PLUS (/0) (0)) (/0)");
+        let instance = schema.instantiate(std::slice::from_ref(&predicate)).unwrap();
+        assert!(instance.name.starts_with("induction["));
+        assert_eq!(schema.instantiate(&[parse("EQ (PLUS (/0) (0)) (/0)")]).unwrap().name, instance.name);
+
+        let LogicalExpression::Compound { operator: ClassicalOperator::Implies, operands, .. } = instance.expression.value.as_ref() else {
+            panic!("expected an implication");
+        };
+        let LogicalExpression::Compound { operator: ClassicalOperator::And, operands: premises, .. } = operands[0].value.as_ref() else {
+            panic!("expected a conjunction of base and step");
+        };
+        assert_eq!(premises[0], parse("EQ (PLUS (0) (0)) (0)"));
+        let store = NodeStorage::new();
+        let compound = |operator, operands| HashNode::from_store(LogicalExpression::compound(operator, operands), &store);
+        let next = parse("EQ (PLUS (S (/0)) (0)) (S (/0))");
+        let step = compound(ClassicalOperator::Forall, vec![compound(ClassicalOperator::Implies, vec![predicate.clone(), next])]);
+        assert_eq!(premises[1], step);
+
+        assert_eq!(schema.instantiate(&[]).unwrap_err(), AxiomError::MalformedAxiom { expected: 1, found: 0 });
+        let open = parse("EQ (PLUS (/0) (/1)) (/0)");
+        assert_eq!(schema.instantiate(&[open]).unwrap_err(), AxiomError::UnboundVariable { index: 1 });
+    }
 }
@@ -0,0 +1,424 @@
+//! Proving a file of goals with shared state.
+//!
+//! A goal file holds one formula per line; blank lines and everything after
+//! `#` are ignored. Unlike a proof script, goals carry no names or hints.
+//! They are proven in order by one `BatchProver`, which keeps a single
+//! content store, rule set and lemma cache across them: a goal already
+//! settled by search is answered from the cache, and every proven equality
+//! becomes a rewrite rule for the goals after it.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::time::Duration;
+
+use corpus_classical_logic::BinaryTruth;
+use corpus_core::clock::Stopwatch;
+use corpus_core::nodes::HashNode;
+use corpus_core::proving::{FailureReason, LemmaCache, ProofStep, SearchStats};
+use corpus_core::rewriting::{RewriteDirection, RewriteRule};
+
+use crate::axioms::peano_arithmetic_rules;
+use crate::counterexample::{find_counterexample, search_formula_counterexample, Refutation, MAX_INSTANCES};
+use crate::eval::variables;
+use crate::induction::search_by_induction_with_rules;
+use crate::parsing::Parser;
+use crate::presburger::decide;
+use crate::printer::Printer;
+use crate::prover::{search_pa_with_goals, NoGoals};
+use crate::rewrite::equality_rule;
+use crate::stores::PeanoStores;
+use crate::syntax::{ArithmeticExpression, PeanoContent, PeanoExpression};
+
+/// How a goal fared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoalStatus {
+    Proved,
+    ProvedByInduction,
+    /// Decided valid by Presburger arithmetic.
+    Decided,
+    /// Answered from the lemma cache.
+    Cached,
+    /// Refuted by a counterexample, a contradiction or Presburger arithmetic.
+    Disproved,
+    /// Neither proven nor refuted within the node budget.
+    Timeout,
+    /// Neither proven nor refuted, the search having run out of states.
+    Exhausted,
+    /// The goal could not be parsed or lies outside every procedure.
+    Error(String),
+}
+
+impl GoalStatus {
+    pub fn proved(&self) -> bool {
+        matches!(
+            self,
+            GoalStatus::Proved | GoalStatus::ProvedByInduction | GoalStatus::Decided | GoalStatus::Cached
+        )
+    }
+}
+
+impl fmt::Display for GoalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoalStatus::Proved => write!(f, "proved"),
+            GoalStatus::ProvedByInduction => write!(f, "proved (induction)"),
+            GoalStatus::Decided => write!(f, "proved (Presburger)"),
+            GoalStatus::Cached => write!(f, "proved (cached)"),
+            GoalStatus::Disproved => write!(f, "disproved"),
+            GoalStatus::Timeout => write!(f, "timeout"),
+            GoalStatus::Exhausted => write!(f, "exhausted"),
+            GoalStatus::Error(reason) => write!(f, "error: {}", reason),
+        }
+    }
+}
+
+/// The result of one goal.
+#[derive(Debug, Clone)]
+pub struct GoalReport {
+    /// Line of the goal, counting from 1.
+    pub line: usize,
+    pub goal: String,
+    pub status: GoalStatus,
+    /// States explored by the search that settled the goal, or for goals
+    /// left open by every search tried.
+    pub nodes_explored: usize,
+    pub elapsed: Duration,
+    /// The rewrite proofs that settled the goal: one for a direct proof, the
+    /// base case and inductive step for a proof by induction.
+    pub proofs: Vec<ProofSteps>,
+    /// Statistics of the last search run for the goal, if any.
+    pub stats: Option<SearchStats>,
+    /// Why the search gave up, for goals left open.
+    pub failure: Option<FailureSummary>,
+}
+
+/// The steps of one rewrite proof, with the case of an induction it proves.
+#[derive(Debug, Clone)]
+pub struct ProofSteps {
+    pub case: Option<&'static str>,
+    pub steps: Vec<StepSummary>,
+}
+
+/// A rewrite step, with the formula it produced printed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepSummary {
+    pub rule: String,
+    pub direction: RewriteDirection,
+    pub formula: String,
+}
+
+impl StepSummary {
+    fn new(step: &ProofStep<PeanoContent>) -> Self {
+        Self {
+            rule: step.rule_name.clone(),
+            direction: step.direction,
+            formula: Printer::default().content(&step.new_expr.value),
+        }
+    }
+}
+
+/// How far a failed search got.
+#[derive(Debug, Clone)]
+pub struct FailureSummary {
+    pub reason: FailureReason,
+    /// The formula the search got closest to a goal with, and the number of
+    /// steps that reached it.
+    pub closest: Option<(String, usize)>,
+}
+
+/// What settling a goal produced, before it is timed.
+struct Settlement {
+    status: GoalStatus,
+    nodes_explored: usize,
+    proofs: Vec<ProofSteps>,
+    stats: Option<SearchStats>,
+    failure: Option<FailureSummary>,
+}
+
+impl Settlement {
+    fn new(status: GoalStatus, nodes_explored: usize) -> Self {
+        Self { status, nodes_explored, proofs: Vec::new(), stats: None, failure: None }
+    }
+}
+
+/// Reports for every goal of a file, in order.
+#[derive(Debug, Clone, Default)]
+pub struct BatchReport {
+    pub goals: Vec<GoalReport>,
+}
+
+impl BatchReport {
+    pub fn proved(&self) -> usize {
+        self.count(GoalStatus::proved)
+    }
+
+    pub fn disproved(&self) -> usize {
+        self.count(|status| *status == GoalStatus::Disproved)
+    }
+
+    pub fn timeouts(&self) -> usize {
+        self.count(|status| *status == GoalStatus::Timeout)
+    }
+
+    pub fn exhausted(&self) -> usize {
+        self.count(|status| *status == GoalStatus::Exhausted)
+    }
+
+    pub fn errors(&self) -> usize {
+        self.count(|status| matches!(status, GoalStatus::Error(_)))
+    }
+
+    fn count(&self, predicate: impl Fn(&GoalStatus) -> bool) -> usize {
+        self.goals.iter().filter(|report| predicate(&report.status)).count()
+    }
+}
+
+impl fmt::Display for BatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>5}  {:<20} {:>8} {:>10}  goal", "line", "status", "nodes", "time")?;
+        for report in &self.goals {
+            writeln!(
+                f,
+                "{:>5}  {:<20} {:>8} {:>10}  {}",
+                report.line,
+                report.status.to_string(),
+                report.nodes_explored,
+                format!("{:.2?}", report.elapsed),
+                report.goal
+            )?;
+        }
+        write!(
+            f,
+            "{} goals: {} proved, {} disproved, {} exhausted, {} timeout, {} errors",
+            self.goals.len(),
+            self.proved(),
+            self.disproved(),
+            self.exhausted(),
+            self.timeouts(),
+            self.errors()
+        )
+    }
+}
+
+/// The goals of a goal file with their line numbers.
+pub fn parse_goals(text: &str) -> Vec<(usize, String)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, raw)| (index + 1, raw.split('#').next().unwrap_or("").trim().to_string()))
+        .filter(|(_, goal)| !goal.is_empty())
+        .collect()
+}
+
+/// Proves goals one after another, sharing what earlier goals established.
+pub struct BatchProver {
+    stores: PeanoStores,
+    rules: Vec<RewriteRule<ArithmeticExpression>>,
+    lemmas: LemmaCache<PeanoContent, BinaryTruth>,
+    learned: usize,
+    max_nodes: usize,
+    max_witness: u64,
+}
+
+impl BatchProver {
+    /// A prover searching at most `max_nodes` states per goal, starting from
+    /// the PA rules.
+    pub fn new(max_nodes: usize) -> Self {
+        Self {
+            stores: PeanoStores::new(),
+            rules: peano_arithmetic_rules(),
+            lemmas: LemmaCache::new(),
+            learned: 0,
+            max_nodes,
+            max_witness: 8,
+        }
+    }
+
+    /// The rules in force: the PA rules followed by one per proven equality.
+    pub fn rules(&self) -> &[RewriteRule<ArithmeticExpression>] {
+        &self.rules
+    }
+
+    /// The stores goals are interned into. Formulas built over them, e.g. by
+    /// an importer, can be handed to `settle_proposition`.
+    pub fn stores(&self) -> &PeanoStores {
+        &self.stores
+    }
+
+    /// Add `rule` to the rules for later goals, e.g. an axiom of an imported
+    /// problem.
+    pub fn assume(&mut self, rule: RewriteRule<ArithmeticExpression>) {
+        self.rules.push(rule);
+    }
+
+    /// Prove every goal of `goals` in order.
+    pub fn prove_all(&mut self, goals: &[(usize, String)]) -> BatchReport {
+        BatchReport {
+            goals: goals.iter().map(|(line, goal)| self.prove(*line, goal)).collect(),
+        }
+    }
+
+    /// Prove a single goal, learning from it if it is a proven equality.
+    pub fn prove(&mut self, line: usize, goal: &str) -> GoalReport {
+        let started = Stopwatch::start();
+        let settlement = match Parser::with_stores(goal, &self.stores).parse_formula() {
+            Ok(proposition) => self.settle(&proposition),
+            Err(e) => Settlement::new(GoalStatus::Error(format!("parse error: {}", e)), 0),
+        };
+        GoalReport {
+            line,
+            goal: goal.to_string(),
+            status: settlement.status,
+            nodes_explored: settlement.nodes_explored,
+            elapsed: started.elapsed(),
+            proofs: settlement.proofs,
+            stats: settlement.stats,
+            failure: settlement.failure,
+        }
+    }
+
+    /// Settle a parsed goal, returning its status and the states explored.
+    pub fn settle_proposition(&mut self, proposition: &HashNode<PeanoExpression>) -> (GoalStatus, usize) {
+        let settlement = self.settle(proposition);
+        (settlement.status, settlement.nodes_explored)
+    }
+
+    fn settle(&mut self, proposition: &HashNode<PeanoExpression>) -> Settlement {
+        let Some(content) = proposition.value.as_domain() else {
+            let status = match decide(&proposition.value) {
+                Some(true) => GoalStatus::Decided,
+                Some(false) => GoalStatus::Disproved,
+                None => match search_formula_counterexample(&proposition.value, self.max_witness, MAX_INSTANCES) {
+                    Refutation::Found(_) => GoalStatus::Disproved,
+                    Refutation::NotFound => GoalStatus::Error("outside Presburger arithmetic".to_string()),
+                    Refutation::Truncated { instances } => GoalStatus::Error(format!(
+                        "outside Presburger arithmetic, and no counterexample among the first {} instances",
+                        instances
+                    )),
+                },
+            };
+            return Settlement::new(status, 0);
+        };
+        let content = content.clone();
+
+        if self.lemmas.contains(&content) {
+            return Settlement::new(GoalStatus::Cached, 0);
+        }
+        if find_counterexample(&content, self.max_witness).is_some() {
+            return Settlement::new(GoalStatus::Disproved, 0);
+        }
+
+        let failure = match search_pa_with_goals(&content, &self.stores, self.max_nodes, &self.rules, &NoGoals) {
+            Ok(result) => {
+                let status = if result.truth_result == BinaryTruth::True {
+                    self.lemmas.insert(&content, &result);
+                    self.learn(&content);
+                    GoalStatus::Proved
+                } else {
+                    GoalStatus::Disproved
+                };
+                return Settlement {
+                    proofs: vec![ProofSteps { case: None, steps: result.steps.iter().map(StepSummary::new).collect() }],
+                    stats: Some(result.stats),
+                    ..Settlement::new(status, result.nodes_explored)
+                };
+            }
+            Err(failure) => failure,
+        };
+
+        let mut nodes_explored = failure.nodes_explored;
+        let variables: BTreeSet<u32> = content.value.terms().into_iter().flat_map(variables).collect();
+        for variable in variables {
+            let (attempt, attempt_nodes) =
+                search_by_induction_with_rules(&content, variable, &self.stores, self.max_nodes, &self.rules);
+            nodes_explored += attempt_nodes;
+            if let Ok(proof) = attempt {
+                self.learn(&content);
+                let case = |case, result: &crate::prover::ProofResult<PeanoContent, BinaryTruth>| ProofSteps {
+                    case: Some(case),
+                    steps: result.steps.iter().map(StepSummary::new).collect(),
+                };
+                return Settlement {
+                    proofs: vec![case("base case", &proof.base), case("inductive step", &proof.step)],
+                    stats: Some(proof.step.stats.clone()),
+                    ..Settlement::new(GoalStatus::ProvedByInduction, proof.base.nodes_explored + proof.step.nodes_explored)
+                };
+            }
+        }
+        let closest = failure
+            .closest
+            .as_ref()
+            .map(|state| (Printer::default().content(&state.expr.value), state.steps.len()));
+        let status = match failure.reason {
+            FailureReason::Exhausted => GoalStatus::Exhausted,
+            _ => GoalStatus::Timeout,
+        };
+        Settlement {
+            stats: Some(*failure.stats),
+            failure: Some(FailureSummary { reason: failure.reason, closest }),
+            ..Settlement::new(status, nodes_explored)
+        }
+    }
+
+    /// Add a proven equality to the rules for later goals.
+    fn learn(&mut self, content: &HashNode<PeanoContent>) {
+        let PeanoContent::Equals(left, right) = content.value.as_ref() else {
+            return;
+        };
+        if let Some(rule) = equality_rule(format!("lemma_{}", self.learned + 1), left, right) {
+            self.learned += 1;
+            self.rules.push(rule);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOALS: &str = "\
+# Settled goals are remembered.
+1 + 1 = 2
+EQ (PLUS (1) (1)) (2)
+
+0 + x = x          # needs induction
+2 + x = x + 2
+x + 1 = x
+forall x. exists y. x < y
+x + = 1
+";
+
+    #[test]
+    fn test_prove_all() {
+        let goals = parse_goals(GOALS);
+        assert_eq!(goals.len(), 7);
+        assert_eq!(goals[2], (5, "0 + x = x".to_string()));
+
+        let mut prover = BatchProver::new(2000);
+        let report = prover.prove_all(&goals);
+        let statuses: Vec<_> = report.goals.iter().map(|goal| goal.status.clone()).collect();
+        assert_eq!(statuses[..6], [
+            GoalStatus::Proved,
+            GoalStatus::Cached,
+            GoalStatus::ProvedByInduction,
+            GoalStatus::ProvedByInduction,
+            GoalStatus::Disproved,
+            GoalStatus::Decided,
+        ]);
+        assert!(matches!(statuses[6], GoalStatus::Error(_)));
+        assert_eq!(report.goals[1].nodes_explored, 0);
+        assert_eq!((report.proved(), report.disproved(), report.timeouts(), report.errors()), (5, 1, 0, 1));
+
+        // The direct search and both induction attempts count towards an open goal.
+        let goal = "x * (y * z) = (x * y) * z";
+        let open = BatchProver::new(5).prove(1, goal);
+        assert_eq!(open.status, GoalStatus::Exhausted);
+        let stores = PeanoStores::new();
+        let content = Parser::with_stores(goal, &stores).parse_formula().unwrap().value.as_domain().unwrap().clone();
+        let direct = search_pa_with_goals(&content, &stores, 5, &peano_arithmetic_rules(), &NoGoals).err().unwrap();
+        assert!(direct.nodes_explored > 0 && open.nodes_explored > direct.nodes_explored);
+
+        // Every proven equality is a rule now.
+        assert_eq!(prover.rules().len(), peano_arithmetic_rules().len() + 3);
+        assert!(report.to_string().ends_with("7 goals: 5 proved, 1 disproved, 0 exhausted, 0 timeout, 1 errors"));
+    }
+}
@@ -0,0 +1,106 @@
+use std::io::{self, BufRead, Write};
+
+use peano_arithmetic::batch::{parse_goals, BatchProver};
+use peano_arithmetic::database::TheoremDatabase;
+use peano_arithmetic::markdown::markdown_report;
+use peano_arithmetic::prover::{ProofOutcome, ProofResultExt};
+use peano_arithmetic::session::{Reply, Session, HELP};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let database = match args.as_slice() {
+        [_] => TheoremDatabase::new(),
+        [_, command, path] if command == "prove-file" => {
+            prove_file(path, false);
+            return;
+        }
+        [_, command, path, flag] if command == "prove-file" && flag == "--markdown" => {
+            prove_file(path, true);
+            return;
+        }
+        [_, flag, path] if flag == "--db" => match TheoremDatabase::open(path) {
+            Ok(database) => database,
+            Err(e) => {
+                eprintln!("Error loading {}: {}", path, e);
+                std::process::exit(1);
+            }
+        },
+        _ => {
+            println!("Usage: {} [--db <theorems> | prove-file <goals> [--markdown]]", args[0]);
+            std::process::exit(1);
+        }
+    };
+
+    let mut session = Session::with_database(database);
+    println!("Peano arithmetic prover. {}", HELP);
+
+    let stdin = io::stdin();
+    loop {
+        print!("peano> ");
+        io::stdout().flush().expect("stdout is writable");
+
+        let mut line = String::new();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("Error reading input: {}", e);
+                break;
+            }
+        }
+
+        match session.execute(&line) {
+            Ok(Reply::Outcome(outcome)) => print_outcome(&outcome, session.trace()),
+            Ok(Reply::Stored(theorem)) if session.trace() => println!("✓ Stored as {}:\n{}", theorem.name, theorem.certificate),
+            Ok(Reply::Stored(theorem)) => println!("✓ Proved (stored as {})", theorem.name),
+            Ok(Reply::Decided(Some(true))) => println!("✓ Valid (decided by Presburger arithmetic)"),
+            Ok(Reply::Decided(Some(false))) => println!("✗ Not valid (decided by Presburger arithmetic)"),
+            Ok(Reply::Refuted(witness)) => println!("✗ Not valid: {}", witness),
+            Ok(Reply::Decided(None)) => println!("? Quantified statements must be linear (no products of variables)"),
+            Ok(Reply::Message(message)) if message.is_empty() => {}
+            Ok(Reply::Message(message)) => println!("{}", message),
+            Ok(Reply::Quit) => break,
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+}
+
+/// Prove every goal of a goal file and print the summary table, or with
+/// `markdown` the full Markdown report.
+fn prove_file(path: &str, markdown: bool) {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("Error reading {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+
+    let report = BatchProver::new(10000).prove_all(&parse_goals(&text));
+    if markdown {
+        print!("{}", markdown_report(&report, path));
+    } else {
+        println!("{}", report);
+    }
+    if report.proved() < report.goals.len() {
+        std::process::exit(1);
+    }
+}
+
+fn print_outcome(outcome: &ProofOutcome, trace: bool) {
+    match outcome {
+        ProofOutcome::Proved(result) if trace => result.print(),
+        ProofOutcome::Proved(result) => {
+            let verdict = if result.truth_result == corpus_classical_logic::BinaryTruth::False {
+                "✗ Disproved (contradiction)"
+            } else {
+                "✓ Proved"
+            };
+            println!("{} in {} steps ({} nodes explored)", verdict, result.steps.len(), result.nodes_explored);
+        }
+        ProofOutcome::ProvedByInduction(proof) if trace => proof.print(),
+        ProofOutcome::ProvedByInduction(proof) => println!("✓ Proved by induction on /{}", proof.variable),
+        ProofOutcome::Disproved { witness } => println!("✗ Disproved: {}", witness),
+        ProofOutcome::Unknown => println!("? Could not prove (reached limit)"),
+    }
+}
@@ -0,0 +1,45 @@
+use peano_arithmetic::script::{parse_script, run_script};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let certificates = args.iter().any(|arg| arg == "--certificates");
+    let paths: Vec<&String> = args.iter().skip(1).filter(|arg| !arg.starts_with("--")).collect();
+
+    let [path] = paths.as_slice() else {
+        println!("Usage: {} [--certificates] <script>", args[0]);
+        println!();
+        println!("Each theorem is written 'theorem <name>: <formula>', optionally followed by");
+        println!("indented 'by induction on /i', 'hint use <name>' or 'hint max_nodes <n>' lines.");
+        std::process::exit(1);
+    };
+
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("Error reading {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+    let entries = match parse_script(&text) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error in {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+
+    let report = run_script(&entries, 10000, certificates);
+    if certificates {
+        for entry in &report.entries {
+            if let Some(certificate) = &entry.certificate {
+                println!("== {} ==", entry.name);
+                println!("{}", certificate);
+            }
+        }
+    }
+    println!("{}", report);
+
+    if report.failed() > 0 {
+        std::process::exit(1);
+    }
+}
@@ -1,33 +1,114 @@
-use corpus_core::base::nodes::{HashNode, NodeStorage};
+use corpus_core::base::nodes::HashNode;
+use peano_arithmetic::counterexample::{search_formula_counterexample, Refutation, MAX_INSTANCES};
+use peano_arithmetic::latex::outcome_latex;
 use peano_arithmetic::parsing::Parser;
-use peano_arithmetic::prover::{prove_pa, ProofResultExt};
-use peano_arithmetic::syntax::{PeanoContent, PeanoExpression};
-use peano_arithmetic::axioms::peano_arithmetic_rules;
+use peano_arithmetic::presburger::decide;
+use peano_arithmetic::printer::Printer;
+use peano_arithmetic::profiles::{prove_with_profile, AxiomProfile, PROFILE_NAMES};
+use peano_arithmetic::prover::{ProofOutcome, ProofResultExt};
+use peano_arithmetic::stores::PeanoStores;
+use peano_arithmetic::syntax::{NumeralForm, PeanoContent, PeanoExpression};
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
+    let mut args: Vec<String> = std::env::args().collect();
+    let numerals = match args.iter().position(|arg| arg.starts_with("--numerals=")) {
+        Some(index) => match args.remove(index).trim_start_matches("--numerals=") {
+            "decimal" => Some(NumeralForm::Decimal),
+            "successor" => Some(NumeralForm::Successor),
+            other => {
+                eprintln!("Unknown numeral form '{}' (expected decimal or successor)", other);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let profile = match args.iter().position(|arg| arg.starts_with("--profile=")) {
+        Some(index) => {
+            let name = args.remove(index).trim_start_matches("--profile=").to_string();
+            AxiomProfile::named(&name).unwrap_or_else(|| {
+                eprintln!("Unknown profile '{}' (expected one of {})", name, PROFILE_NAMES.join(", "));
+                std::process::exit(1);
+            })
+        }
+        None => AxiomProfile::peano(),
+    };
+    let profile = match args.iter().position(|arg| arg.starts_with("--axioms=")) {
+        Some(index) => {
+            let path = args.remove(index).trim_start_matches("--axioms=").to_string();
+            profile.with_axiom_file(&path).unwrap_or_else(|e| {
+                eprintln!("Error loading axioms: {}", e);
+                std::process::exit(1);
+            })
+        }
+        None => profile,
+    };
+    let latex = match args.iter().position(|arg| arg == "--latex") {
+        Some(index) => {
+            args.remove(index);
+            true
+        }
+        None => false,
+    };
+    let html = args
+        .iter()
+        .position(|arg| arg.starts_with("--html="))
+        .map(|index| args.remove(index).trim_start_matches("--html=").to_string());
 
     if args.len() < 2 {
-        println!("Usage: {} <theorem>", args[0]);
+        println!("Usage: {} [--numerals=decimal|successor] [--profile=pa|pa-no-induction|q] [--axioms=<file>] [--latex] [--html=<file>] <theorem>", args[0]);
         println!();
-        println!("Example: {} \"EQ (PLUS (S(0)) (0)) (S(0))\"", args[0]);
+        println!("Example: {} \"S(0) + 0 = S(0)\"", args[0]);
+        println!("         {} \"EQ (PLUS (S(0)) (0)) (S(0))\"", args[0]);
         println!();
-        println!("Theorem format: EQ (left) (right)");
-        println!("  left, right: Peano arithmetic expressions");
-        println!("  Operators: PLUS, S (successor), numbers (0, 1, 2, ...)");
-        println!("  Variables: /0, /1, /2, ... (De Bruijn indices)");
+        println!("Infix format: left = right, left < right, left <= right, left | right, Even(term) or Odd(term)");
+        println!("  Terms: +, - (truncated), *, ^, S(..) (successor), P(..) (predecessor), numbers (0, 1, 2, ...)");
+        println!("  Formulas: ¬ or !, ∧ or &, ∨, -> and <->, forall x. ... and exists x. ... (bounded: forall x < n. ...)");
+        println!("  Variables: names, or /0, /1, /2, ... (De Bruijn indices)");
+        println!("Prefix format: EQ, LT, LE or DIVIDES followed by (left) (right), or EVEN/ODD (term)");
+        println!("  Operators: PLUS, TIMES, POW, S, P, MONUS, numbers, declared functions");
+        println!("  Connectives: AND, OR, IMPLIES and IFF (left) (right), NOT, FORALL and EXISTS (body)");
+        println!("  --numerals: write numerals as 2 or as S(S(0)) (default: as given)");
+        println!("  --profile: prove in PA (default), PA without induction, or Robinson arithmetic Q");
+        println!("  --axioms: extend the profile with the axioms of a .axioms file");
+        println!("  --latex: also print the proof as LaTeX (amsmath and bussproofs)");
+        println!("  --html: also write a rewrite proof as a standalone HTML page to <file>");
         std::process::exit(1);
     }
 
     let theorem = &args[1];
     println!("Parsing theorem: {}", theorem);
 
-    let mut parser = Parser::new(theorem);
-    match parser.parse_proposition() {
+    let stores = PeanoStores::new();
+    let mut parser = Parser::with_stores(theorem, &stores);
+    if let Some(form) = numerals {
+        parser = parser.with_numerals(form);
+    }
+    match parser.parse_formula_recovering() {
         Ok(proposition) => {
-            println!("Parsed: {}", proposition);
+            println!("Parsed: {}", Printer::default().expression(&proposition.value));
             println!();
 
+            // Quantified statements go to the Presburger decision procedure
+            if proposition.value.is_logical() {
+                let decided = decide(&proposition.value);
+                if decided == Some(true) {
+                    println!("✓ Valid (decided by Presburger arithmetic)");
+                    return;
+                }
+                match search_formula_counterexample(&proposition.value, 8, MAX_INSTANCES) {
+                    Refutation::Found(witness) => println!("✗ Not valid: {}", witness),
+                    _ if decided == Some(false) => println!("✗ Not valid (decided by Presburger arithmetic)"),
+                    refutation => {
+                        if let Refutation::Truncated { instances } = refutation {
+                            eprintln!("No counterexample among the first {} instances.", instances);
+                        }
+                        eprintln!("Error: quantified statements must be linear (no products of variables).");
+                        std::process::exit(1);
+                    }
+                }
+                return;
+            }
+
             // Extract the PeanoContent (equality expression) from the DomainExpression
             let peano_content = match extract_equality_content(proposition) {
                 Ok(content) => content,
@@ -37,33 +118,63 @@ fn main() {
                 }
             };
 
-            println!("Theorem: {}", peano_content);
+            println!("Theorem: {}", Printer::default().content(&peano_content.value));
             println!();
 
-            println!("Loading Peano axioms...");
-            let arithmetic_rules = peano_arithmetic_rules();
-            for rule in &arithmetic_rules {
+            println!("Loading axiom profile {}...", profile.name);
+            for rule in &profile.rules {
                 println!("  - {}", rule.name);
             }
             println!();
 
-            // Create a NodeStorage for PeanoContent
-            let store = NodeStorage::new();
-
             println!("Searching for proof (max 10000 nodes)...");
-            match prove_pa(&peano_content, &store, 10000) {
-                Some(result) => {
+            let outcome = prove_with_profile(&peano_content, &stores, 10000, 8, &profile);
+            match &outcome {
+                ProofOutcome::Proved(result) => {
                     println!();
                     result.print();
                 }
-                None => {
+                ProofOutcome::ProvedByInduction(proof) => {
+                    println!();
+                    proof.print();
+                }
+                ProofOutcome::Disproved { witness } => {
+                    println!();
+                    println!("✗ Statement disproved: {}", witness);
+                }
+                ProofOutcome::Unknown => {
                     println!();
                     println!("✗ Could not prove theorem (reached limit)");
                 }
             }
+            if latex && let Some(text) = outcome_latex(&outcome) {
+                println!();
+                print!("{}", text);
+            }
+            if let Some(path) = html {
+                write_html(&outcome, theorem, &path);
+            }
         }
+        Err(errors) => {
+            for error in errors {
+                eprintln!("Parse error: {}", error);
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Write the page stepping through a rewrite proof to `path`.
+fn write_html(outcome: &ProofOutcome, theorem: &str, path: &str) {
+    let ProofOutcome::Proved(result) = outcome else {
+        eprintln!("No HTML written: only rewrite proofs can be exported");
+        return;
+    };
+    let page = result.certificate().to_html_with(theorem, &Printer::default());
+    match std::fs::write(path, page) {
+        Ok(()) => println!("Wrote {}", path),
         Err(e) => {
-            eprintln!("Parse error: {}", e);
+            eprintln!("Error writing {}: {}", path, e);
             std::process::exit(1);
         }
     }
@@ -74,6 +185,6 @@ fn extract_equality_content(
 ) -> Result<HashNode<PeanoContent>, String> {
     match proposition.value.as_domain() {
         Some(content) => Ok(content.clone()),
-        None => Err("Theorem must be an equality (EQ ...).".to_string()),
+        None => Err("Theorem must be an equality, comparison or divisibility (EQ, LT, LE, DIVIDES, EVEN or ODD ...).".to_string()),
     }
 }
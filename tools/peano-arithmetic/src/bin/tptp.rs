@@ -0,0 +1,25 @@
+use std::path::Path;
+
+use peano_arithmetic::tptp::{run_problem, szs_line, SzsStatus};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 {
+        println!("Usage: {} <problem.p>...", args[0]);
+        println!();
+        println!("Prints an SZS status line for each TPTP problem in the arithmetic fragment.");
+        std::process::exit(1);
+    }
+
+    for path in &args[1..] {
+        let name = Path::new(path).file_stem().map_or(path.as_str(), |stem| stem.to_str().unwrap_or(path));
+        let status = match std::fs::read_to_string(path) {
+            Ok(text) => run_problem(&text, 10000),
+            Err(e) => {
+                eprintln!("Error reading {}: {}", path, e);
+                SzsStatus::GaveUp
+            }
+        };
+        println!("{}", szs_line(status, name));
+    }
+}
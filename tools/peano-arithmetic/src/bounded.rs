@@ -0,0 +1,165 @@
+//! Expansion of bounded quantifiers.
+//!
+//! The parser reads `∀x < n. φ` as `∀x. x < n → φ` and `∃x < n. φ` as
+//! `∃x. x < n ∧ φ` (likewise with `≤`). When the bound is a ground term, the
+//! quantifier ranges over finitely many numerals and is replaced by the
+//! conjunction `φ(0) ∧ … ∧ φ(n - 1)`, or the disjunction under `∃`. A closed
+//! formula whose quantifiers are all bounded this way expands to ground
+//! atoms, which evaluation decides even when they multiply variables, e.g.
+//! `∀x < 10. x * x < 100`.
+
+use corpus_classical_logic::{instantiate, BinaryTruth, ClassicalOperator};
+use corpus_core::expression::{DomainExpression, LogicalExpression};
+use corpus_core::nodes::{HashNode, NodeStorage};
+
+use crate::domain::PeanoDomain;
+use crate::eval::eval;
+use crate::syntax::{ArithmeticExpression, PeanoContent, PeanoExpression};
+
+type PeanoLogicalExpression = LogicalExpression<BinaryTruth, PeanoContent, ClassicalOperator>;
+
+/// Largest number of cases a quantifier is expanded into; wider bounds keep
+/// their quantifier.
+pub const MAX_EXPANSION: u64 = 1000;
+
+/// `expr` with every bounded quantifier over a ground bound expanded.
+pub fn expand_bounded(expr: &PeanoExpression) -> PeanoExpression {
+    let DomainExpression::Logical(logical) = expr else {
+        return expr.clone();
+    };
+    let content_store = NodeStorage::new();
+    let expression_store = NodeStorage::new();
+    let expander = Expander {
+        domain: PeanoDomain::new(&content_store, &expression_store),
+        store: NodeStorage::new(),
+    };
+    let expanded = expander.expand(logical);
+    match expanded.value.as_ref() {
+        LogicalExpression::Atomic(content) => PeanoExpression::domain(content.clone()),
+        LogicalExpression::Compound { .. } => PeanoExpression::logical(expanded),
+    }
+}
+
+struct Expander<'a> {
+    domain: PeanoDomain<'a>,
+    store: NodeStorage<PeanoLogicalExpression>,
+}
+
+impl Expander<'_> {
+    fn expand(&self, expr: &HashNode<PeanoLogicalExpression>) -> HashNode<PeanoLogicalExpression> {
+        let LogicalExpression::Compound { operator, operands, .. } = expr.value.as_ref() else {
+            return expr.clone();
+        };
+        // Outer quantifiers first, so that inner bounds mentioning their
+        // variables become ground.
+        if let [body] = operands.as_slice()
+            && let Some(cases) = self.cases(*operator, body)
+        {
+            return self.expand(&cases);
+        }
+        let operands = operands.iter().map(|operand| self.expand(operand)).collect();
+        HashNode::from_store(LogicalExpression::compound(*operator, operands), &self.store)
+    }
+
+    /// The finite conjunction or disjunction replacing `quantifier body`, if
+    /// the body is guarded by a ground bound.
+    fn cases(
+        &self,
+        quantifier: ClassicalOperator,
+        body: &HashNode<PeanoLogicalExpression>,
+    ) -> Option<HashNode<PeanoLogicalExpression>> {
+        let (connective, junction) = match quantifier {
+            ClassicalOperator::Forall => (ClassicalOperator::Implies, ClassicalOperator::And),
+            ClassicalOperator::Exists => (ClassicalOperator::And, ClassicalOperator::Or),
+            _ => return None,
+        };
+        let LogicalExpression::Compound { operator, operands, .. } = body.value.as_ref() else {
+            return None;
+        };
+        let [guard, _] = operands.as_slice() else {
+            return None;
+        };
+        let LogicalExpression::Atomic(guard) = guard.value.as_ref() else {
+            return None;
+        };
+        let count = match guard.value.as_ref() {
+            PeanoContent::LessThan(variable, limit) if is_innermost(variable) => eval(limit)?,
+            PeanoContent::LessEq(variable, limit) if is_innermost(variable) => eval(limit)?.checked_add(1)?,
+            _ => return None,
+        };
+        if *operator != connective || count > MAX_EXPANSION {
+            return None;
+        }
+
+        // Instantiating `∀x. x < n → φ` at `k` gives `k < n → φ(k)`, and the
+        // guard holds for every `k` below the bound.
+        let universal = HashNode::from_store(LogicalExpression::compound(ClassicalOperator::Forall, vec![body.clone()]), &self.store);
+        let instances = (0..count)
+            .map(|k| {
+                let numeral = HashNode::from_store(ArithmeticExpression::Number(k), self.domain.expression_store);
+                let instance = instantiate(&universal, &numeral, &self.domain, &self.store)?;
+                match instance.value.as_ref() {
+                    LogicalExpression::Compound { operands, .. } => operands.get(1).cloned(),
+                    LogicalExpression::Atomic(_) => None,
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let cases = instances.into_iter().reduce(|left, right| {
+            HashNode::from_store(LogicalExpression::compound(junction, vec![left, right]), &self.store)
+        });
+        Some(cases.unwrap_or_else(|| self.constant(quantifier == ClassicalOperator::Forall)))
+    }
+
+    /// `0 = 0` or `0 < 0`, for a quantifier over no values.
+    fn constant(&self, value: bool) -> HashNode<PeanoLogicalExpression> {
+        let zero = HashNode::from_store(ArithmeticExpression::Number(0), self.domain.expression_store);
+        let content = if value {
+            PeanoContent::Equals(zero.clone(), zero)
+        } else {
+            PeanoContent::LessThan(zero.clone(), zero)
+        };
+        let atom = HashNode::from_store(content, self.domain.content_store);
+        HashNode::from_store(LogicalExpression::atomic(atom), &self.store)
+    }
+}
+
+fn is_innermost(term: &HashNode<ArithmeticExpression>) -> bool {
+    matches!(term.value.as_ref(), ArithmeticExpression::DeBruijn(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use crate::presburger::decide;
+
+    fn parse(text: &str) -> PeanoExpression {
+        Parser::new(text).parse_formula().unwrap().value.as_ref().clone()
+    }
+
+    #[test]
+    fn test_bounded_quantifiers_expand() {
+        assert_eq!(
+            expand_bounded(&parse("forall x < 2. Even(x * x) || Odd(x)")),
+            parse("(Even(0 * 0) || Odd(0)) && (Even(1 * 1) || Odd(1))")
+        );
+        assert_eq!(parse("exists x <= 1. x = /0"), parse("exists x. x <= 1 && x = /0"));
+        assert_eq!(expand_bounded(&parse("exists x <= 1. x = y")), parse("0 = y || 1 = y"));
+        assert_eq!(expand_bounded(&parse("forall x < 0. x = 1")), parse("0 = 0"));
+        // Inner bounds may mention outer variables.
+        assert_eq!(expand_bounded(&parse("forall x < 2. exists y < x. y = 0")), parse("0 < 0 && 0 = 0"));
+        // Open bounds are left to Presburger arithmetic.
+        let open = parse("forall x < y. x < y + 1");
+        assert_eq!(expand_bounded(&open), open);
+    }
+
+    #[test]
+    fn test_bounded_formulas_are_decided() {
+        assert_eq!(decide(&parse("forall x < 10. x * x < 100")), Some(true));
+        assert_eq!(decide(&parse("forall x <= 10. x * x < 100")), Some(false));
+        assert_eq!(decide(&parse("exists x < 10, y < x. x * y = 12")), Some(true));
+        assert_eq!(decide(&parse("forall x < y. x < y + 1")), Some(true));
+        assert!(Parser::new("forall x < x. x = 0").parse_formula().is_err());
+    }
+}
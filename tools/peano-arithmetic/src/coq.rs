@@ -0,0 +1,111 @@
+//! Coq backend for proof export.
+//!
+//! Writes `.v` files over Coq's `nat`, in which PA's truncated subtraction
+//! and predecessor are `-` and `pred`. Rule lemmas are proven by `simpl`,
+//! `ring` and `lia`; the goal's proof is a sequence of `rewrite … at n`.
+
+use corpus_classical_logic::BinaryTruth;
+use corpus_core::rewriting::RewriteRule;
+
+use crate::export::{export_proof, ExportError, ProofExporter};
+use crate::prover::ProofResult;
+use crate::stores::PeanoStores;
+use crate::syntax::{ArithmeticExpression, PeanoContent};
+
+/// The Coq syntax of exported proofs.
+pub struct Coq;
+
+impl ProofExporter for Coq {
+    const EXTENSION: &'static str = "v";
+
+    fn preamble(&self) -> Vec<String> {
+        vec!["From Coq Require Import Arith Lia Setoid.".to_string()]
+    }
+
+    fn successor(&self) -> &'static str {
+        "S"
+    }
+
+    fn predecessor(&self) -> &'static str {
+        "pred"
+    }
+
+    fn lemma(&self, name: &str, variables: &[String], statement: &str, tactics: &[String]) -> String {
+        let mut lines = Vec::new();
+        match variables {
+            [] => lines.push(format!("Lemma {} : {}.", name, statement)),
+            _ => lines.push(format!("Lemma {} : forall {} : nat, {}.", name, variables.join(" "), statement)),
+        }
+        lines.push("Proof.".to_string());
+        if !variables.is_empty() {
+            lines.push(format!("  intros {}.", variables.join(" ")));
+        }
+        lines.extend(tactics.iter().map(|tactic| format!("  {}", tactic)));
+        lines.push("Qed.".to_string());
+        lines.join("\n")
+    }
+
+    fn rule_tactics(&self) -> Vec<String> {
+        vec!["simpl; try ring; lia.".to_string()]
+    }
+
+    fn rewrite(&self, lemma: &str, arguments: &[String], backward: bool, occurrence: usize) -> String {
+        let arrow = if backward { "<- " } else { "" };
+        match arguments {
+            [] => format!("rewrite {}{} at {}.", arrow, lemma, occurrence),
+            _ => format!("rewrite {}({} {}) at {}.", arrow, lemma, arguments.join(" "), occurrence),
+        }
+    }
+
+    fn injectivity(&self) -> String {
+        "f_equal.".to_string()
+    }
+
+    fn reflexivity(&self) -> String {
+        "reflexivity.".to_string()
+    }
+
+    fn arithmetic(&self) -> String {
+        "lia.".to_string()
+    }
+}
+
+/// `result` as a Coq file proving the lemma `name`.
+pub fn coq_proof(
+    name: &str,
+    result: &ProofResult<PeanoContent, BinaryTruth>,
+    rules: &[RewriteRule<ArithmeticExpression>],
+    stores: &PeanoStores,
+) -> Result<String, ExportError> {
+    export_proof(&Coq, name, result, rules, stores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axioms::peano_arithmetic_rules;
+    use crate::parsing::Parser;
+    use crate::prover::prove_pa;
+
+    fn export(text: &str) -> Result<String, ExportError> {
+        let stores = PeanoStores::new();
+        let goal = Parser::with_stores(text, &stores).parse_formula().unwrap();
+        let goal = goal.value.as_domain().unwrap();
+        let result = prove_pa(goal, &stores, 5000).expect("the goal should be settled");
+        coq_proof("goal", &result, &peano_arithmetic_rules(), &stores)
+    }
+
+    #[test]
+    fn test_coq_export() {
+        let file = export("x + 1 = S(x)").unwrap();
+        assert!(file.starts_with("From Coq Require Import Arith Lia Setoid.\n\n"));
+        assert!(file.contains(
+            "Lemma axiom4_additive_successor : forall v0 v1 : nat, v0 + S v1 = S (v0 + v1).\nProof.\n  intros v0 v1.\n  simpl; try ring; lia.\nQed."
+        ));
+        assert!(file.contains("Lemma goal : forall v0 : nat, v0 + 1 = S v0.\nProof.\n  intros v0.\n"));
+        assert!(file.contains("  rewrite (axiom4_additive_successor v0 0) at 1.\n"));
+        assert!(file.ends_with("  reflexivity.\nQed.\n"));
+
+        assert_eq!(export("1 = 0"), Err(ExportError::Disproved));
+    }
+}
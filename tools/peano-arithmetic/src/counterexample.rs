@@ -0,0 +1,246 @@
+//! Disproof of universally quantified equalities by small instances.
+//!
+//! PA goals with De Bruijn variables are implicitly universal. Before spending
+//! the node budget on a search, the goal can be instantiated with small
+//! numerals and both sides evaluated; any instance where they differ is a
+//! concrete counterexample. With many variables the instances grow
+//! exponentially, so a search tries at most a fixed number of them and says
+//! when it stopped short of the whole range.
+
+use std::fmt;
+
+use corpus_classical_logic::ClassicalOperator;
+use corpus_core::expression::{DomainExpression, LogicalExpression};
+use corpus_core::nodes::HashNode;
+
+use crate::eval::{eval_with, holds_with, variables};
+use crate::printer::bound_name;
+use crate::syntax::{PeanoContent, PeanoExpression};
+
+/// Assignments `find_counterexample` tries before giving up.
+pub const MAX_INSTANCES: usize = 100_000;
+
+/// How a search for a counterexample ended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Refutation {
+    Found(Counterexample),
+    /// Every instance in range holds or cannot be evaluated, or the goal is
+    /// not a relation the search applies to.
+    NotFound,
+    /// The budget of instances ran out before the range was covered.
+    Truncated { instances: usize },
+}
+
+impl Refutation {
+    pub fn witness(self) -> Option<Counterexample> {
+        match self {
+            Refutation::Found(witness) => Some(witness),
+            Refutation::NotFound | Refutation::Truncated { .. } => None,
+        }
+    }
+}
+
+/// An assignment of numerals to variables under which a relation fails.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Counterexample {
+    /// Value given to each De Bruijn index, ordered by index.
+    pub assignment: Vec<(u32, u64)>,
+    /// Number of `∀` binders around the relation. Indices below it are the
+    /// bound variables, named as by `Printer`; the rest are free.
+    pub bound: u32,
+    /// Value of the left side under the assignment.
+    pub left_value: u64,
+    /// Value of the right side under the assignment.
+    pub right_value: u64,
+    /// The negated relation between the values, e.g. `≠` for an equality.
+    pub failure: &'static str,
+}
+
+impl Counterexample {
+    /// The name of the variable with De Bruijn index `index`.
+    pub fn variable_name(&self, index: u32) -> String {
+        if index < self.bound {
+            bound_name((self.bound - 1 - index) as usize)
+        } else {
+            format!("/{}", index - self.bound)
+        }
+    }
+}
+
+impl fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.assignment.is_empty() {
+            write!(f, "fails")?;
+        } else {
+            // Bound variables first, outermost binder first, then free ones.
+            let (bound, free): (Vec<_>, Vec<_>) = self.assignment.iter().partition(|(idx, _)| *idx < self.bound);
+            let bindings = bound
+                .iter()
+                .rev()
+                .chain(&free)
+                .map(|(idx, value)| format!("{}={}", self.variable_name(*idx), value))
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, "fails at {}", bindings)?;
+        }
+        write!(f, ": {} {} {}", self.left_value, self.failure, self.right_value)
+    }
+}
+
+/// Search for a counterexample to a relation with every variable ranging over `0..=max_value`.
+///
+/// Instances that cannot be evaluated, e.g. because a value overflows, are
+/// skipped. Returns `None` if the goal is not a relation between two terms
+/// or if no other instance in range fails.
+///
+/// At most `MAX_INSTANCES` assignments are tried; see `search_counterexample`
+/// to tell a truncated search from a complete one.
+pub fn find_counterexample(goal: &HashNode<PeanoContent>, max_value: u64) -> Option<Counterexample> {
+    search_counterexample(goal, max_value, MAX_INSTANCES).witness()
+}
+
+/// `find_counterexample` trying at most `max_instances` assignments.
+pub fn search_counterexample(goal: &HashNode<PeanoContent>, max_value: u64, max_instances: usize) -> Refutation {
+    search(&goal.value, 0, max_value, max_instances)
+}
+
+/// Search for a counterexample to `∀x₁…∀xₖ. R`, a relation under universal
+/// quantifiers, instantiating bound and free variables alike.
+///
+/// Returns `None` for any other formula, and as `find_counterexample` does.
+pub fn find_formula_counterexample(expr: &PeanoExpression, max_value: u64) -> Option<Counterexample> {
+    search_formula_counterexample(expr, max_value, MAX_INSTANCES).witness()
+}
+
+/// `find_formula_counterexample` trying at most `max_instances` assignments.
+pub fn search_formula_counterexample(expr: &PeanoExpression, max_value: u64, max_instances: usize) -> Refutation {
+    let mut logical = match expr {
+        DomainExpression::Domain(content) => return search_counterexample(content, max_value, max_instances),
+        DomainExpression::Logical(logical) => logical.clone(),
+    };
+    let mut bound = 0;
+    loop {
+        match logical.value.as_ref() {
+            LogicalExpression::Atomic(content) => return search(&content.value, bound, max_value, max_instances),
+            LogicalExpression::Compound { operator: ClassicalOperator::Forall, operands, .. } if operands.len() == 1 => {
+                logical = operands[0].clone();
+                bound += 1;
+            }
+            LogicalExpression::Compound { .. } => return Refutation::NotFound,
+        }
+    }
+}
+
+fn search(content: &PeanoContent, bound: u32, max_value: u64, max_instances: usize) -> Refutation {
+    let Some((left, right)) = content.sides() else {
+        return Refutation::NotFound;
+    };
+    let failure = match content {
+        PeanoContent::Equals(..) => "≠",
+        PeanoContent::LessThan(..) => "≮",
+        PeanoContent::LessEq(..) => "≰",
+        PeanoContent::Divides(..) => "∤",
+        _ => return Refutation::NotFound,
+    };
+
+    let vars: Vec<u32> = variables(left).union(&variables(right)).copied().collect();
+    let slots = vars.last().map_or(0, |&max| max as usize + 1);
+    let mut assignment = vec![0u64; slots];
+
+    for instances in 0.. {
+        if instances == max_instances {
+            return Refutation::Truncated { instances };
+        }
+        if holds_with(content, &assignment) == Some(false)
+            && let (Some(left_value), Some(right_value)) = (eval_with(left, &assignment), eval_with(right, &assignment))
+        {
+            return Refutation::Found(Counterexample {
+                assignment: vars.iter().map(|&idx| (idx, assignment[idx as usize])).collect(),
+                bound,
+                left_value,
+                right_value,
+                failure,
+            });
+        }
+
+        if !advance(&mut assignment, &vars, max_value) {
+            break;
+        }
+    }
+    Refutation::NotFound
+}
+
+/// Step to the next assignment of `vars` in odometer order. Returns `false`
+/// once every combination has been visited.
+fn advance(assignment: &mut [u64], vars: &[u32], max_value: u64) -> bool {
+    for &idx in vars {
+        let slot = &mut assignment[idx as usize];
+        if *slot < max_value {
+            *slot += 1;
+            return true;
+        }
+        *slot = 0;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    fn equality(input: &str) -> HashNode<PeanoContent> {
+        let mut parser = Parser::new(input);
+        let proposition = parser.parse_proposition().expect("should parse");
+        proposition.value.as_domain().expect("should be an equality").clone()
+    }
+
+    #[test]
+    fn test_false_conjecture_has_witness() {
+        // x + 1 = x fails for every x, starting at 0
+        let witness = find_counterexample(&equality("EQ (PLUS (/0) (1)) (/0)"), 5).expect("should be refuted");
+        assert_eq!(witness.assignment, vec![(0, 0)]);
+        assert_eq!((witness.left_value, witness.right_value), (1, 0));
+    }
+
+    #[test]
+    fn test_two_variable_witness() {
+        // x + y = x fails first at x=0, y=1
+        let witness = find_counterexample(&equality("EQ (PLUS (/0) (/1)) (/0)"), 3).expect("should be refuted");
+        assert_eq!(witness.assignment, vec![(0, 0), (1, 1)]);
+        assert_eq!(witness.to_string(), "fails at /0=0, /1=1: 1 ≠ 0");
+    }
+
+    #[test]
+    fn test_true_statement_has_no_witness() {
+        assert!(find_counterexample(&equality("EQ (PLUS (/0) (S (/1))) (S (PLUS (/0) (/1)))"), 4).is_none());
+        assert!(find_counterexample(&equality("EQ (PLUS (1) (1)) (2)"), 4).is_none());
+    }
+
+    #[test]
+    fn test_overflowing_instances_are_skipped() {
+        // x ^ 70 overflows for every x from 2, before y = 1 is reached.
+        let goal = Parser::new("x ^ 70 = x + y").parse_formula().unwrap();
+        let witness = find_counterexample(goal.value.as_domain().unwrap(), 4).expect("0 ^ 70 ≠ 0 + 1");
+        assert_eq!(witness.to_string(), "fails at /0=0, /1=1: 0 ≠ 1");
+    }
+
+    #[test]
+    fn test_search_is_truncated() {
+        // 5^6 instances, all of which hold.
+        let goal = equality("EQ (PLUS (PLUS (PLUS (/0) (/1)) (PLUS (/2) (/3))) (PLUS (/4) (/5))) (PLUS (PLUS (PLUS (/5) (/4)) (PLUS (/3) (/2))) (PLUS (/1) (/0)))");
+        assert_eq!(search_counterexample(&goal, 4, 1000), Refutation::Truncated { instances: 1000 });
+        assert_eq!(search_counterexample(&goal, 4, 20_000), Refutation::NotFound);
+    }
+
+    #[test]
+    fn test_quantified_witness() {
+        let refute = |text| find_formula_counterexample(&Parser::new(text).parse_formula().unwrap().value, 4);
+
+        let witness = refute("forall x, y. x * y <= x + y").expect("2 * 3 > 2 + 3");
+        assert_eq!(witness.to_string(), "fails at x=2, y=3: 6 ≰ 5");
+        assert_eq!(refute("forall x. x | z").unwrap().to_string(), "fails at x=0, /0=1: 0 ∤ 1");
+        assert!(refute("forall x. x <= x * x").is_none());
+        assert!(refute("forall x. exists y. x = y + y").is_none());
+    }
+}
@@ -0,0 +1,240 @@
+//! A file-backed database of proven theorems.
+//!
+//! Each theorem is stored under the hash of its statement, with numerals
+//! normalized so that `2` and `S(S(0))` share an entry, together with the
+//! derivation that proved it:
+//!
+//! ```text
+//! theorem zero_add 5c1e0a93b2f7d604: 0 + /0 = /0
+//!   | Base case:
+//!   | 1 │ 0 = 0       axiom
+//!   | ...
+//! ```
+//!
+//! Formulas are written by the ASCII `Printer` and read back by
+//! `Parser::parse_formula`; a hash that no longer matches its formula is
+//! rejected. Goals found in the database need no search, and stored
+//! equalities can be turned back into rewrite rules.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use corpus_core::nodes::{HashNode, NodeStorage};
+use corpus_core::rewriting::RewriteRule;
+
+use crate::parsing::Parser;
+use crate::printer::{Charset, Printer};
+use crate::rewrite::equality_rule;
+use crate::syntax::{normalize_numerals, ArithmeticExpression, NumeralForm, PeanoContent};
+
+/// A proven statement and its certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredTheorem {
+    pub name: String,
+    /// The statement, as printed by the ASCII `Printer`.
+    pub formula: String,
+    /// The rendered derivation.
+    pub certificate: String,
+}
+
+/// Failure to load or save a database.
+#[derive(Debug)]
+pub enum DatabaseError {
+    Io(io::Error),
+    /// A malformed entry, at a line counting from 1.
+    Format { line: usize, message: String },
+}
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatabaseError::Io(e) => write!(f, "{}", e),
+            DatabaseError::Format { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {}
+
+impl From<io::Error> for DatabaseError {
+    fn from(e: io::Error) -> Self {
+        DatabaseError::Io(e)
+    }
+}
+
+/// Proven theorems keyed by the hash of their statement.
+#[derive(Debug, Clone, Default)]
+pub struct TheoremDatabase {
+    path: Option<PathBuf>,
+    theorems: BTreeMap<u64, StoredTheorem>,
+}
+
+impl TheoremDatabase {
+    /// An empty database kept in memory only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the database at `path`, or start an empty one if the file does
+    /// not exist yet. `record` writes changes back to the same file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DatabaseError> {
+        let path = path.as_ref().to_path_buf();
+        let mut database = match std::fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::new(),
+            Err(e) => return Err(e.into()),
+        };
+        database.path = Some(path);
+        Ok(database)
+    }
+
+    /// Read a database from its text form.
+    pub fn parse(text: &str) -> Result<Self, DatabaseError> {
+        let mut database = Self::new();
+        let mut current: Option<u64> = None;
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let error = |message: String| DatabaseError::Format { line, message };
+            if raw.trim().is_empty() || raw.starts_with('#') {
+                continue;
+            }
+
+            if let Some(certificate) = raw.trim_start().strip_prefix('|') {
+                let theorem = current
+                    .and_then(|key| database.theorems.get_mut(&key))
+                    .ok_or_else(|| error("certificate line before any theorem".into()))?;
+                theorem.certificate.push_str(certificate.strip_prefix(' ').unwrap_or(certificate));
+                theorem.certificate.push('\n');
+                continue;
+            }
+
+            let header = raw.strip_prefix("theorem ").ok_or_else(|| error(format!("unexpected line '{}'", raw)))?;
+            let (head, formula) = header.split_once(':').ok_or_else(|| error("expected 'theorem <name> <hash>: <formula>'".into()))?;
+            let (name, hash) = head.trim().split_once(' ').ok_or_else(|| error("expected a name and a hash".into()))?;
+            let hash = u64::from_str_radix(hash.trim(), 16).map_err(|_| error(format!("not a hash: {}", hash.trim())))?;
+
+            let proposition = Parser::new(formula.trim()).parse_formula().map_err(|e| error(e.to_string()))?;
+            let content = proposition.value.as_domain().ok_or_else(|| error("only atoms can be stored".into()))?;
+            if Self::key(&content.value) != hash {
+                return Err(error(format!("hash {:016x} does not match its formula", hash)));
+            }
+            database.theorems.insert(hash, StoredTheorem {
+                name: name.to_string(),
+                formula: formula.trim().to_string(),
+                certificate: String::new(),
+            });
+            current = Some(hash);
+        }
+        Ok(database)
+    }
+
+    /// The key of a statement: its hash with numerals in decimal.
+    pub fn key(content: &PeanoContent) -> u64 {
+        let store = NodeStorage::new();
+        let terms = content
+            .terms()
+            .into_iter()
+            .map(|term| normalize_numerals(term, NumeralForm::Decimal, &store))
+            .collect();
+        match content.with_terms(terms) {
+            Some(normalized) => HashNode::from_store(normalized, &NodeStorage::new()).hash(),
+            None => HashNode::from_store(content.clone(), &NodeStorage::new()).hash(),
+        }
+    }
+
+    pub fn lookup(&self, content: &PeanoContent) -> Option<&StoredTheorem> {
+        self.theorems.get(&Self::key(content))
+    }
+
+    /// The theorem named `name`, if stored.
+    pub fn named(&self, name: &str) -> Option<&StoredTheorem> {
+        self.theorems.values().find(|theorem| theorem.name == name)
+    }
+
+    /// Store a proven statement, replacing any earlier entry for it, and
+    /// write the database back if it was opened from a file.
+    pub fn record(&mut self, name: &str, content: &PeanoContent, certificate: &str) -> Result<(), DatabaseError> {
+        let formula = Printer::new().with_charset(Charset::Ascii).content(content);
+        self.theorems.insert(Self::key(content), StoredTheorem {
+            name: name.to_string(),
+            formula,
+            certificate: certificate.to_string(),
+        });
+        match &self.path {
+            Some(path) => Ok(std::fs::write(path, self.to_string())?),
+            None => Ok(()),
+        }
+    }
+
+    /// The stored equalities as rewrite rules named after their theorems.
+    pub fn rules(&self) -> Vec<RewriteRule<ArithmeticExpression>> {
+        self.theorems.values().filter_map(theorem_rule).collect()
+    }
+
+    pub fn theorems(&self) -> impl Iterator<Item = &StoredTheorem> {
+        self.theorems.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.theorems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.theorems.is_empty()
+    }
+}
+
+/// A stored equality as a rule, or `None` for other statements.
+pub fn theorem_rule(theorem: &StoredTheorem) -> Option<RewriteRule<ArithmeticExpression>> {
+    let proposition = Parser::new(&theorem.formula).parse_formula().ok()?;
+    let PeanoContent::Equals(left, right) = proposition.value.as_domain()?.value.as_ref() else {
+        return None;
+    };
+    equality_rule(theorem.name.clone(), left, right)
+}
+
+impl fmt::Display for TheoremDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (hash, theorem) in &self.theorems {
+            writeln!(f, "theorem {} {:016x}: {}", theorem.name, hash, theorem.formula)?;
+            for line in theorem.certificate.lines() {
+                writeln!(f, "  | {}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content(text: &str) -> PeanoContent {
+        Parser::new(text).parse_formula().unwrap().value.as_domain().unwrap().value.as_ref().clone()
+    }
+
+    #[test]
+    fn test_round_trip_and_lookup() {
+        let mut database = TheoremDatabase::new();
+        database.record("zero_add", &content("0 + x = x"), "1 │ 0 = 0   axiom\n").unwrap();
+        database.record("two", &content("S(S(0)) <= 2 + 1"), "").unwrap();
+
+        let loaded = TheoremDatabase::parse(&database.to_string()).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.named("zero_add").unwrap().certificate, "1 │ 0 = 0   axiom\n");
+        // Numerals are normalized, so either spelling finds the entry.
+        assert_eq!(loaded.lookup(&content("2 <= S(S(0)) + 1")).unwrap().name, "two");
+        assert!(loaded.lookup(&content("x + 0 = x")).is_none());
+        assert_eq!(loaded.rules().len(), 1);
+    }
+
+    #[test]
+    fn test_malformed_entries_rejected() {
+        let error = TheoremDatabase::parse("theorem a 0000000000000001: 0 = 0").unwrap_err();
+        assert!(matches!(error, DatabaseError::Format { line: 1, .. }));
+        assert!(TheoremDatabase::parse("  | orphan").is_err());
+        assert!(TheoremDatabase::parse("lemma a 1: 0 = 0").is_err());
+    }
+}
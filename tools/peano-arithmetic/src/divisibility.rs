@@ -0,0 +1,82 @@
+//! Definitional expansion of divisibility.
+//!
+//! `a ∣ b` abbreviates `∃k. b = a * k`. Unfolding puts the new `∃` directly
+//! around the atom, so every variable of `a` and `b` moves up by one to skip
+//! it, and the witness `k` is `/0`.
+
+use corpus_classical_logic::{BinaryTruth, ClassicalLogicalExpression, ClassicalOperator, FirstOrderDomain};
+use corpus_core::expression::LogicalExpression;
+use corpus_core::nodes::{HashNode, NodeStorage};
+
+use crate::domain::PeanoDomain;
+use crate::syntax::{ArithmeticExpression, PeanoContent};
+
+pub(crate) type PeanoLogicalExpression = ClassicalLogicalExpression<BinaryTruth, PeanoContent>;
+
+/// `∃k. b = a * k` for the atom `a ∣ b`, or `None` for any other atom.
+pub fn unfold_divides(
+    atom: &HashNode<PeanoContent>,
+    domain: &PeanoDomain,
+    store: &NodeStorage<PeanoLogicalExpression>,
+) -> Option<HashNode<PeanoLogicalExpression>> {
+    let PeanoContent::Divides(divisor, multiple) = atom.value.as_ref() else {
+        return None;
+    };
+    let shift = |term| domain.map_variables(term, &|index| Some(domain.variable(index + 1)));
+    let product = ArithmeticExpression::Multiply(shift(divisor)?, domain.variable(0));
+    let product = HashNode::from_store(product, domain.expression_store);
+    let equality = HashNode::from_store(PeanoContent::Equals(shift(multiple)?, product), domain.content_store);
+
+    let body = HashNode::from_store(PeanoLogicalExpression::atomic(equality), store);
+    Some(HashNode::from_store(
+        PeanoLogicalExpression::compound(ClassicalOperator::Exists, vec![body]),
+        store,
+    ))
+}
+
+/// `expr` with every divisibility atom replaced by its definition.
+pub fn unfold_divisibility(
+    expr: &HashNode<PeanoLogicalExpression>,
+    domain: &PeanoDomain,
+    store: &NodeStorage<PeanoLogicalExpression>,
+) -> HashNode<PeanoLogicalExpression> {
+    unfold_atoms(expr, store, &|atom| unfold_divides(atom, domain, store))
+}
+
+/// `expr` with every atom for which `unfold` returns a definition replaced
+/// by it. Definitions are expanded in place, so `unfold` must already account
+/// for any binders it introduces.
+pub fn unfold_atoms(
+    expr: &HashNode<PeanoLogicalExpression>,
+    store: &NodeStorage<PeanoLogicalExpression>,
+    unfold: &dyn Fn(&HashNode<PeanoContent>) -> Option<HashNode<PeanoLogicalExpression>>,
+) -> HashNode<PeanoLogicalExpression> {
+    match expr.value.as_ref() {
+        LogicalExpression::Atomic(atom) => unfold(atom).unwrap_or_else(|| expr.clone()),
+        LogicalExpression::Compound { operator, operands, .. } => {
+            let operands = operands.iter().map(|operand| unfold_atoms(operand, store, unfold)).collect();
+            HashNode::from_store(PeanoLogicalExpression::compound(*operator, operands), store)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    #[test]
+    fn test_unfold_under_quantifier() {
+        let content_store = NodeStorage::new();
+        let expression_store = NodeStorage::new();
+        let domain = PeanoDomain::new(&content_store, &expression_store);
+        let store = NodeStorage::new();
+
+        // ∀x. 2 ∣ x + x
+        let proposition = Parser::new("FORALL (DIVIDES (2) (PLUS (/0) (/0)))").parse_proposition().unwrap();
+        let expr = proposition.value.as_logical(&store);
+
+        let unfolded = unfold_divisibility(&expr, &domain, &store);
+        assert_eq!(unfolded.to_string(), "(∀ (∃ (/1 + /1) = (2 * /0)))");
+    }
+}
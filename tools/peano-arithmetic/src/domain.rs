@@ -0,0 +1,210 @@
+//! Peano arithmetic as a first-order domain.
+//!
+//! Equalities are binary predicates over arithmetic terms, and a bare
+//! arithmetic wrapper is a unary one. This lets the classical-logic
+//! procedures that look inside atoms (variable analysis, Skolemization,
+//! instantiation) work on Peano formulas.
+
+use std::collections::HashSet;
+
+use corpus_classical_logic::{variables, Assignment, BinaryTruth, FirstOrderDomain, Interpretation};
+use corpus_core::expression::DomainExpression;
+use corpus_core::nodes::{HashNode, Hashing, NodeStorage};
+
+use crate::eval;
+use crate::syntax::{ArithmeticExpression, PeanoContent, PeanoExpression};
+
+/// `FirstOrderDomain` for `PeanoContent`, building atoms and terms in the
+/// given stores.
+pub struct PeanoDomain<'a> {
+    pub content_store: &'a NodeStorage<PeanoContent>,
+    pub expression_store: &'a NodeStorage<ArithmeticExpression>,
+}
+
+impl<'a> PeanoDomain<'a> {
+    pub fn new(
+        content_store: &'a NodeStorage<PeanoContent>,
+        expression_store: &'a NodeStorage<ArithmeticExpression>,
+    ) -> Self {
+        Self { content_store, expression_store }
+    }
+}
+
+impl FirstOrderDomain<BinaryTruth, PeanoContent> for PeanoDomain<'_> {
+    type Term = ArithmeticExpression;
+
+    fn predicate(&self, atom: &HashNode<PeanoContent>) -> Option<(u64, Vec<HashNode<ArithmeticExpression>>)> {
+        match atom.value.as_ref() {
+            PeanoContent::Equals(left, right) => Some((Hashing::opcode("equals"), vec![left.clone(), right.clone()])),
+            PeanoContent::LessThan(left, right) => Some((Hashing::opcode("less_than"), vec![left.clone(), right.clone()])),
+            PeanoContent::LessEq(left, right) => Some((Hashing::opcode("less_eq"), vec![left.clone(), right.clone()])),
+            PeanoContent::Divides(left, right) => Some((Hashing::opcode("divides"), vec![left.clone(), right.clone()])),
+            PeanoContent::Even(term) => Some((Hashing::opcode("even"), vec![term.clone()])),
+            PeanoContent::Odd(term) => Some((Hashing::opcode("odd"), vec![term.clone()])),
+            PeanoContent::Arithmetic(expr) => Some((Hashing::opcode("arithmetic_wrapper"), vec![expr.clone()])),
+        }
+    }
+
+    fn atom(&self, predicate: u64, mut arguments: Vec<HashNode<ArithmeticExpression>>) -> Option<HashNode<PeanoContent>> {
+        let content = match predicate {
+            p if p == Hashing::opcode("equals") && arguments.len() == 2 => {
+                let right = arguments.pop()?;
+                PeanoContent::Equals(arguments.pop()?, right)
+            }
+            p if p == Hashing::opcode("less_than") && arguments.len() == 2 => {
+                let right = arguments.pop()?;
+                PeanoContent::LessThan(arguments.pop()?, right)
+            }
+            p if p == Hashing::opcode("less_eq") && arguments.len() == 2 => {
+                let right = arguments.pop()?;
+                PeanoContent::LessEq(arguments.pop()?, right)
+            }
+            p if p == Hashing::opcode("divides") && arguments.len() == 2 => {
+                let right = arguments.pop()?;
+                PeanoContent::Divides(arguments.pop()?, right)
+            }
+            p if p == Hashing::opcode("even") && arguments.len() == 1 => PeanoContent::Even(arguments.pop()?),
+            p if p == Hashing::opcode("odd") && arguments.len() == 1 => PeanoContent::Odd(arguments.pop()?),
+            p if p == Hashing::opcode("arithmetic_wrapper") && arguments.len() == 1 => {
+                PeanoContent::Arithmetic(arguments.pop()?)
+            }
+            _ => return None,
+        };
+        Some(HashNode::from_store(content, self.content_store))
+    }
+
+    fn bound_index(&self, term: &HashNode<ArithmeticExpression>) -> Option<u32> {
+        match term.value.as_ref() {
+            ArithmeticExpression::DeBruijn(idx) => Some(*idx),
+            _ => None,
+        }
+    }
+
+    fn variable(&self, index: u32) -> HashNode<ArithmeticExpression> {
+        HashNode::from_store(ArithmeticExpression::DeBruijn(index), self.expression_store)
+    }
+
+    fn terms(&self) -> &NodeStorage<ArithmeticExpression> {
+        self.expression_store
+    }
+}
+
+/// The free De Bruijn indices of `expr`, relative to its top level.
+///
+/// A bare domain expression has no binders, so all of its variables are free.
+pub fn free_variables(expr: &PeanoExpression, domain: &PeanoDomain) -> HashSet<u32> {
+    match expr {
+        DomainExpression::Domain(content) => content_variables(content).collect(),
+        DomainExpression::Logical(logical) => variables::free_variables(logical, domain),
+    }
+}
+
+/// The largest De Bruijn index occurring in `expr`, bound or free.
+pub fn max_de_bruijn_index(expr: &PeanoExpression, domain: &PeanoDomain) -> Option<u32> {
+    match expr {
+        DomainExpression::Domain(content) => content_variables(content).max(),
+        DomainExpression::Logical(logical) => variables::max_de_bruijn_index(logical, domain),
+    }
+}
+
+/// Whether `expr` has no free variables.
+pub fn is_closed(expr: &PeanoExpression, domain: &PeanoDomain) -> bool {
+    free_variables(expr, domain).is_empty()
+}
+
+/// The standard model cut down to `0..size`, so quantifiers can be checked by
+/// enumeration. Sums that leave the range are still computed, so `∀x ∃y. y = S(x)`
+/// is false here even though it holds in ℕ.
+pub struct BoundedNaturals {
+    pub size: u64,
+}
+
+impl Interpretation<BinaryTruth, PeanoContent> for BoundedNaturals {
+    type Value = u64;
+
+    fn universe(&self) -> Vec<u64> {
+        (0..self.size).collect()
+    }
+
+    fn holds(&self, atom: &HashNode<PeanoContent>, assignment: &Assignment<u64>) -> Option<bool> {
+        let values = assignment.by_index().copied().collect::<Vec<_>>();
+        eval::holds_with(&atom.value, &values)
+    }
+}
+
+fn content_variables(content: &HashNode<PeanoContent>) -> impl Iterator<Item = u32> {
+    let terms = match content.value.as_ref() {
+        PeanoContent::Arithmetic(expr) => vec![expr.clone()],
+        relation => relation.terms().into_iter().cloned().collect(),
+    };
+    terms.into_iter().flat_map(|term| eval::variables(&term))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    #[test]
+    fn test_free_variables_respect_quantifiers() {
+        let content_store = NodeStorage::new();
+        let expression_store = NodeStorage::new();
+        let domain = PeanoDomain::new(&content_store, &expression_store);
+        let parse = |text| Parser::new(text).parse_proposition().unwrap();
+
+        let closed = parse("FORALL (FORALL (EQ (PLUS (/0) (S (/1))) (S (PLUS (/0) (/1)))))");
+        assert!(is_closed(&closed.value, &domain));
+        assert_eq!(max_de_bruijn_index(&closed.value, &domain), Some(1));
+
+        // Under one binder, /2 is the free variable /1.
+        let open = parse("AND (FORALL (EQ (/0) (/2))) (EQ (/0) (0))");
+        assert_eq!(free_variables(&open.value, &domain), HashSet::from([0, 1]));
+        assert!(!is_closed(&open.value, &domain));
+
+        let bare = parse("EQ (PLUS (/3) (0)) (/3)");
+        assert_eq!(free_variables(&bare.value, &domain), HashSet::from([3]));
+    }
+
+    #[test]
+    fn test_instantiate_axiom_with_numeral() {
+        use corpus_classical_logic::instantiate;
+
+        let content_store = NodeStorage::new();
+        let expression_store = NodeStorage::new();
+        let logical_store = NodeStorage::new();
+        let domain = PeanoDomain::new(&content_store, &expression_store);
+        let parse = |text| Parser::new(text).parse_proposition().unwrap();
+
+        let axiom = parse("FORALL (EQ (PLUS (/0) (0)) (/0))");
+        let DomainExpression::Logical(axiom) = axiom.value.as_ref() else { panic!("expected a quantified axiom") };
+        let three = HashNode::from_store(ArithmeticExpression::Number(3), &expression_store);
+        let instance = instantiate(axiom, &three, &domain, &logical_store).unwrap();
+
+        let expected = parse("EQ (PLUS (3) (0)) (3)");
+        assert_eq!(instance.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_bounded_naturals() {
+        use corpus_classical_logic::{evaluate, satisfies};
+
+        let model = BoundedNaturals { size: 5 };
+        let holds = |text| {
+            let expr = Parser::new(text).parse_proposition().unwrap();
+            let DomainExpression::Logical(expr) = expr.value.as_ref() else { panic!("expected a compound formula") };
+            satisfies(&model, expr).unwrap()
+        };
+
+        assert!(holds("FORALL (EQ (PLUS (/0) (0)) (/0))"));
+        assert!(holds("EXISTS (EQ (PLUS (/0) (/0)) (4))"));
+        assert!(!holds("EXISTS (EQ (PLUS (/0) (/0)) (3))"));
+        assert!(!holds("FORALL (EXISTS (EQ (/0) (S (/1))))"));
+        assert!(holds("FORALL (LT (/0) (S (/0)))"));
+        assert!(holds("FORALL (EXISTS (AND (LE (/0) (/1)) (NOT (LT (/0) (/1)))))"));
+
+        let open = Parser::new("NOT (EQ (/0) (2))").parse_proposition().unwrap();
+        let DomainExpression::Logical(open) = open.value.as_ref() else { panic!("expected a compound formula") };
+        assert_eq!(evaluate(open, &model, &Assignment::from_indices(vec![2])), Ok(BinaryTruth::False));
+        assert_eq!(evaluate(open, &model, &Assignment::from_indices(vec![3])), Ok(BinaryTruth::True));
+    }
+}
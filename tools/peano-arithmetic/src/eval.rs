@@ -0,0 +1,135 @@
+//! Ground evaluation of arithmetic terms.
+//!
+//! Terms built from numerals, successor and addition denote natural numbers
+//! and can be computed directly. De Bruijn variables are resolved through an
+//! assignment so the same evaluator serves counterexample search.
+
+use std::collections::BTreeSet;
+
+use corpus_core::nodes::HashNode;
+
+use crate::syntax::{ArithmeticExpression, PeanoContent};
+
+/// Evaluate `term`, looking up each De Bruijn index `/i` as `assignment[i]`.
+///
+/// Returns `None` if a variable has no value in the assignment, the term
+/// applies a defined function, or the computation overflows `u64`.
+pub fn eval_with(term: &HashNode<ArithmeticExpression>, assignment: &[u64]) -> Option<u64> {
+    match term.value.as_ref() {
+        ArithmeticExpression::Number(n) => Some(*n),
+        ArithmeticExpression::DeBruijn(idx) => assignment.get(*idx as usize).copied(),
+        ArithmeticExpression::Successor(inner) => eval_with(inner, assignment)?.checked_add(1),
+        ArithmeticExpression::Predecessor(inner) => Some(eval_with(inner, assignment)?.saturating_sub(1)),
+        ArithmeticExpression::Monus(left, right) => {
+            Some(eval_with(left, assignment)?.saturating_sub(eval_with(right, assignment)?))
+        }
+        ArithmeticExpression::Multiply(left, right) => {
+            eval_with(left, assignment)?.checked_mul(eval_with(right, assignment)?)
+        }
+        ArithmeticExpression::Power(left, right) => {
+            let exponent = u32::try_from(eval_with(right, assignment)?).ok()?;
+            eval_with(left, assignment)?.checked_pow(exponent)
+        }
+        ArithmeticExpression::Add(left, right) => {
+            eval_with(left, assignment)?.checked_add(eval_with(right, assignment)?)
+        }
+        // Defined functions are unfolded by their rules, not evaluated.
+        ArithmeticExpression::Function(..) => None,
+    }
+}
+
+/// Evaluate a variable-free term.
+///
+/// Returns `None` if `term` has a variable or the computation overflows.
+pub fn eval(term: &HashNode<ArithmeticExpression>) -> Option<u64> {
+    eval_with(term, &[])
+}
+
+/// Decide an equality between variable-free terms by comparing their values.
+pub fn ground_equality(content: &PeanoContent) -> Option<bool> {
+    let PeanoContent::Equals(left, right) = content else {
+        return None;
+    };
+    Some(eval(left)? == eval(right)?)
+}
+
+/// Decide an equality, comparison, divisibility or parity, looking up
+/// variables as in `eval_with`.
+///
+/// Returns `None` for a bare arithmetic expression or if a term cannot be
+/// evaluated.
+pub fn holds_with(content: &PeanoContent, assignment: &[u64]) -> Option<bool> {
+    match content {
+        PeanoContent::Even(term) => return Some(eval_with(term, assignment)? % 2 == 0),
+        PeanoContent::Odd(term) => return Some(eval_with(term, assignment)? % 2 == 1),
+        _ => {}
+    }
+    let (left, right) = content.sides()?;
+    let (left, right) = (eval_with(left, assignment)?, eval_with(right, assignment)?);
+    match content {
+        PeanoContent::Equals(..) => Some(left == right),
+        PeanoContent::LessThan(..) => Some(left < right),
+        PeanoContent::LessEq(..) => Some(left <= right),
+        PeanoContent::Divides(..) => Some(right.checked_rem(left).map_or(right == 0, |rest| rest == 0)),
+        _ => None,
+    }
+}
+
+/// Collect the De Bruijn indices occurring in `term`.
+pub fn variables(term: &HashNode<ArithmeticExpression>) -> BTreeSet<u32> {
+    let mut vars = BTreeSet::new();
+    collect_variables(term, &mut vars);
+    vars
+}
+
+fn collect_variables(term: &HashNode<ArithmeticExpression>, vars: &mut BTreeSet<u32>) {
+    match term.value.as_ref() {
+        ArithmeticExpression::DeBruijn(idx) => {
+            vars.insert(*idx);
+        }
+        ArithmeticExpression::Successor(inner) | ArithmeticExpression::Predecessor(inner) => {
+            collect_variables(inner, vars)
+        }
+        ArithmeticExpression::Add(left, right)
+        | ArithmeticExpression::Monus(left, right)
+        | ArithmeticExpression::Multiply(left, right)
+        | ArithmeticExpression::Power(left, right) => {
+            collect_variables(left, vars);
+            collect_variables(right, vars);
+        }
+        ArithmeticExpression::Function(_, args) => args.iter().for_each(|arg| collect_variables(arg, vars)),
+        ArithmeticExpression::Number(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use corpus_core::nodes::NodeStorage;
+
+    #[test]
+    fn test_eval_with_assignment() {
+        let store = NodeStorage::new();
+        let x = HashNode::from_store(ArithmeticExpression::DeBruijn(0), &store);
+        let two = HashNode::from_store(ArithmeticExpression::Number(2), &store);
+        let sum = HashNode::from_store(ArithmeticExpression::Add(x.clone(), two), &store);
+        let term = HashNode::from_store(ArithmeticExpression::Successor(sum), &store);
+
+        assert_eq!(eval_with(&term, &[4]), Some(7));
+        assert_eq!(eval_with(&term, &[]), None);
+        assert_eq!(eval(&term), None);
+        assert_eq!(variables(&term).into_iter().collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn test_monus_truncates() {
+        let proposition = crate::parsing::Parser::new("EQ (∸ (2) (P (/0))) (0)").parse_proposition().unwrap();
+        let equality = proposition.value.as_domain().unwrap();
+        let crate::syntax::PeanoContent::Equals(left, _) = equality.value.as_ref() else {
+            panic!("expected an equality");
+        };
+        assert_eq!(left.to_string(), "(2 ∸ P(/0))");
+        assert_eq!(eval_with(left, &[0]), Some(2));
+        assert_eq!(eval_with(left, &[5]), Some(0));
+    }
+}
@@ -0,0 +1,289 @@
+//! Export of PA rewrite proofs to interactive proof assistants.
+//!
+//! A proof found by `prove_pa` becomes a file with one lemma per arithmetic
+//! rule the proof uses, each proven outright from the definitions of the
+//! operators, followed by a lemma for the goal whose proof replays the
+//! certificate: every rule application is a `rewrite` with the rule's lemma
+//! at the instance and occurrence the step rewrote, successor injectivity
+//! peels `S` off both sides, and the final relation is closed by reflexivity
+//! or arithmetic. Numeral steps are not replayed, since `2` and `S(S(0))` are
+//! the same natural number to a proof assistant.
+//!
+//! The translation is shared; a `ProofExporter` only supplies the concrete
+//! syntax of one proof assistant. Free variables `/i` are named `v<i>` and
+//! universally quantified.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use corpus_classical_logic::BinaryTruth;
+use corpus_core::nodes::HashNode;
+use corpus_core::rewriting::{positions, subterm_at, Pattern, RewriteDirection, RewriteRule, Substitution, Unifiable};
+
+use crate::eval::variables;
+use crate::patterns::apply_substitution;
+use crate::prover::ProofResult;
+use crate::stores::PeanoStores;
+use crate::syntax::{ArithmeticExpression, PeanoContent};
+
+/// The concrete syntax of one proof assistant.
+pub trait ProofExporter {
+    /// Extension of the files this backend writes, without the dot.
+    const EXTENSION: &'static str;
+
+    /// Lines opening every file, e.g. imports.
+    fn preamble(&self) -> Vec<String>;
+
+    /// The names the successor and predecessor functions are applied by.
+    fn successor(&self) -> &'static str;
+    fn predecessor(&self) -> &'static str;
+
+    /// A lemma `name` stating `statement` for all natural numbers
+    /// `variables`, which its proof introduces before running `tactics`.
+    fn lemma(&self, name: &str, variables: &[String], statement: &str, tactics: &[String]) -> String;
+
+    /// Tactics proving a rule's equation from the operators' definitions.
+    fn rule_tactics(&self) -> Vec<String>;
+
+    /// Rewrite occurrence `occurrence` (counting from 1, left to right) of
+    /// the instance of `lemma` at `arguments`, right to left if `backward`.
+    fn rewrite(&self, lemma: &str, arguments: &[String], backward: bool, occurrence: usize) -> String;
+
+    /// Reduce a goal `S(a) = S(b)` to `a = b`.
+    fn injectivity(&self) -> String;
+
+    /// Close an equation whose sides compute to the same numeral.
+    fn reflexivity(&self) -> String;
+
+    /// Close a true comparison of linear terms.
+    fn arithmetic(&self) -> String;
+}
+
+/// Why a proof could not be exported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportError {
+    /// The proof shows the statement false.
+    Disproved,
+    /// A step uses a rule that was not given to the exporter.
+    UnknownRule(String),
+    /// The proof uses a construct the exporter cannot translate.
+    Unsupported(String),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Disproved => write!(f, "the proof refutes its statement"),
+            ExportError::UnknownRule(name) => write!(f, "unknown rule {}", name),
+            ExportError::Unsupported(reason) => write!(f, "unsupported: {}", reason),
+        }
+    }
+}
+
+fn unsupported<T>(reason: impl Into<String>) -> Result<T, ExportError> {
+    Err(ExportError::Unsupported(reason.into()))
+}
+
+/// The name of the free variable `/index`.
+pub fn variable_name(index: u32) -> String {
+    format!("v{}", index)
+}
+
+/// Export `result`, a proof of the goal it starts from, as a lemma `name`
+/// using `rules`, the arithmetic rules it was found with.
+pub fn export_proof<E: ProofExporter>(
+    exporter: &E,
+    name: &str,
+    result: &ProofResult<PeanoContent, BinaryTruth>,
+    rules: &[RewriteRule<ArithmeticExpression>],
+    stores: &PeanoStores,
+) -> Result<String, ExportError> {
+    if result.truth_result == BinaryTruth::False {
+        return Err(ExportError::Disproved);
+    }
+    let certificate = result.certificate();
+    let mut used: Vec<&RewriteRule<ArithmeticExpression>> = Vec::new();
+    let mut tactics = Vec::new();
+    for step in &certificate.steps {
+        if step.rule_name.starts_with("numeral_") {
+            continue;
+        }
+        if step.rule_name == "successor_injectivity" {
+            tactics.push(exporter.injectivity());
+            continue;
+        }
+        let Some((&side, path)) = step.position.split_first() else {
+            return unsupported(format!("the relation rewrite {}", step.rule_name));
+        };
+        let backward = step.direction == RewriteDirection::Backward;
+        let rule_name = match step.rule_name.strip_suffix("_reverse") {
+            Some(name) if backward => name,
+            _ => &step.rule_name,
+        };
+        let rule = rules
+            .iter()
+            .find(|rule| rule.name == rule_name)
+            .ok_or_else(|| ExportError::UnknownRule(step.rule_name.clone()))?;
+        if !used.iter().any(|other| other.name == rule.name) {
+            used.push(rule);
+        }
+
+        // Rule rewrites inside relations keep no substitution, so the rule
+        // is matched again against the subterm it rewrote.
+        let missing = || ExportError::Unsupported(format!("{} at a missing position", rule.name));
+        let target = step.old_expr.value.terms().get(side).and_then(|term| subterm_at(term, path)).ok_or_else(missing)?;
+        let side_pattern = if backward { &rule.replacement } else { &rule.pattern };
+        let substitution = ArithmeticExpression::unify(side_pattern, &target, &Substitution::new(), &stores.expression_store)
+            .map_err(|_| ExportError::Unsupported(format!("{} does not match {}", rule.name, target)))?;
+        let arguments = rule_variables(rule)
+            .into_iter()
+            .map(|index| match substitution.get(index) {
+                Some(term) => term_text(exporter, term, Context::Argument),
+                None => unsupported(format!("{} leaves /{} unbound", rule.name, index)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let occurrence = occurrence(&step.old_expr.value, side, path).ok_or_else(missing)?;
+        tactics.push(exporter.rewrite(&rule.name, &arguments, backward, occurrence));
+    }
+    tactics.push(match certificate.final_expr.value.as_ref() {
+        PeanoContent::Equals(..) => exporter.reflexivity(),
+        PeanoContent::LessThan(..) | PeanoContent::LessEq(..) => exporter.arithmetic(),
+        other => return unsupported(format!("closing {}", other)),
+    });
+
+    let mut sections = vec![exporter.preamble().join("\n")];
+    for rule in used {
+        sections.push(rule_lemma(exporter, rule, stores)?);
+    }
+    let goal = &certificate.initial_expr.value;
+    let goal_variables: BTreeSet<u32> = goal.terms().into_iter().flat_map(variables).collect();
+    let goal_variables: Vec<String> = goal_variables.into_iter().map(variable_name).collect();
+    sections.push(exporter.lemma(name, &goal_variables, &relation_text(exporter, goal)?, &tactics));
+    Ok(sections.join("\n\n") + "\n")
+}
+
+/// The variables of `rule`, in index order.
+fn rule_variables(rule: &RewriteRule<ArithmeticExpression>) -> Vec<u32> {
+    let variables: BTreeSet<u32> = rule.pattern.vars().into_iter().chain(rule.replacement.vars()).collect();
+    variables.into_iter().collect()
+}
+
+/// The lemma stating `rule` as an equation over its variables.
+fn rule_lemma<E: ProofExporter>(
+    exporter: &E,
+    rule: &RewriteRule<ArithmeticExpression>,
+    stores: &PeanoStores,
+) -> Result<String, ExportError> {
+    let indices = rule_variables(rule);
+    let mut generic = Substitution::new();
+    for &index in &indices {
+        generic.bind(index, HashNode::from_store(ArithmeticExpression::DeBruijn(index), &stores.expression_store));
+    }
+    let side = |pattern: &Pattern<ArithmeticExpression>| {
+        term_text(exporter, &apply_substitution(pattern, &generic, &stores.expression_store), Context::Side)
+    };
+    let statement = format!("{} = {}", side(&rule.pattern)?, side(&rule.replacement)?);
+    let names: Vec<String> = indices.into_iter().map(variable_name).collect();
+    Ok(exporter.lemma(&rule.name, &names, &statement, &exporter.rule_tactics()))
+}
+
+fn relation_text<E: ProofExporter>(exporter: &E, content: &PeanoContent) -> Result<String, ExportError> {
+    let symbol = match content {
+        PeanoContent::Equals(..) => "=",
+        PeanoContent::LessThan(..) => "<",
+        PeanoContent::LessEq(..) => "<=",
+        other => return unsupported(format!("the relation {}", other)),
+    };
+    let (left, right) = content.sides().expect("relations have two sides");
+    Ok(format!("{} {} {}", term_text(exporter, left, Context::Side)?, symbol, term_text(exporter, right, Context::Side)?))
+}
+
+/// How tightly the context of a term binds: the whole of a side, an
+/// operand of an infix operator, or an argument of a function application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Context {
+    Side,
+    Operand,
+    Argument,
+}
+
+/// Render `term`, parenthesized as its context requires.
+fn term_text<E: ProofExporter>(
+    exporter: &E,
+    term: &HashNode<ArithmeticExpression>,
+    context: Context,
+) -> Result<String, ExportError> {
+    let (text, own) = match term.value.as_ref() {
+        ArithmeticExpression::Number(n) => return Ok(n.to_string()),
+        ArithmeticExpression::DeBruijn(index) => return Ok(variable_name(*index)),
+        ArithmeticExpression::Successor(inner) => {
+            let inner = term_text(exporter, inner, Context::Argument)?;
+            (format!("{} {}", exporter.successor(), inner), Context::Argument)
+        }
+        ArithmeticExpression::Predecessor(inner) => {
+            let inner = term_text(exporter, inner, Context::Argument)?;
+            (format!("{} {}", exporter.predecessor(), inner), Context::Argument)
+        }
+        ArithmeticExpression::Add(left, right) => (binary(exporter, left, "+", right)?, Context::Operand),
+        ArithmeticExpression::Monus(left, right) => (binary(exporter, left, "-", right)?, Context::Operand),
+        ArithmeticExpression::Multiply(left, right) => (binary(exporter, left, "*", right)?, Context::Operand),
+        ArithmeticExpression::Power(left, right) => (binary(exporter, left, "^", right)?, Context::Operand),
+        other => return unsupported(format!("the term {}", other)),
+    };
+    Ok(if context >= own { format!("({})", text) } else { text })
+}
+
+fn binary<E: ProofExporter>(
+    exporter: &E,
+    left: &HashNode<ArithmeticExpression>,
+    symbol: &str,
+    right: &HashNode<ArithmeticExpression>,
+) -> Result<String, ExportError> {
+    let left = term_text(exporter, left, Context::Operand)?;
+    let right = term_text(exporter, right, Context::Operand)?;
+    Ok(format!("{} {} {}", left, symbol, right))
+}
+
+/// Which occurrence, counting from 1 in left-to-right order, the subterm at
+/// `path` in side `side` of `relation` is among the subterms equal to it.
+fn occurrence(relation: &PeanoContent, side: usize, path: &[usize]) -> Option<usize> {
+    let terms = relation.terms();
+    let target = subterm_at(terms.get(side)?, path)?;
+    let mut count = 0;
+    for (index, term) in terms.iter().enumerate() {
+        for position in positions(term) {
+            let subterm = subterm_at(term, &position)?;
+            if same_number(&subterm, &target) {
+                count += 1;
+            }
+            if index == side && position == path {
+                return Some(count);
+            }
+        }
+    }
+    None
+}
+
+/// Whether two terms denote the same natural number syntactically, with
+/// `2` and `S(S(0))` identified.
+fn same_number(left: &HashNode<ArithmeticExpression>, right: &HashNode<ArithmeticExpression>) -> bool {
+    use ArithmeticExpression::*;
+    if let (Some(left), Some(right)) = (numeral_value(left), numeral_value(right)) {
+        return left == right;
+    }
+    match (left.value.as_ref(), right.value.as_ref()) {
+        (Successor(a), Successor(b)) | (Predecessor(a), Predecessor(b)) => same_number(a, b),
+        (Add(a, b), Add(c, d)) | (Monus(a, b), Monus(c, d)) | (Multiply(a, b), Multiply(c, d)) | (Power(a, b), Power(c, d)) => {
+            same_number(a, c) && same_number(b, d)
+        }
+        _ => left == right,
+    }
+}
+
+fn numeral_value(term: &HashNode<ArithmeticExpression>) -> Option<u64> {
+    match term.value.as_ref() {
+        ArithmeticExpression::Number(n) => Some(*n),
+        ArithmeticExpression::Successor(inner) => numeral_value(inner)?.checked_add(1),
+        _ => None,
+    }
+}
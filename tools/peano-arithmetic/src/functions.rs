@@ -0,0 +1,133 @@
+//! User-defined function symbols.
+//!
+//! `def double(x) := x + x` declares `double` as a function of one argument
+//! and defines it by its body. Declaring a symbol registers a fresh opcode,
+//! under which its applications are hashed, decomposed and rebuilt like the
+//! built-in operations. Nodes are rebuilt from their opcode alone, so the
+//! table of symbols is shared by the whole process.
+//!
+//! A definition becomes the rule `double(/0) → /0 + /0`, named
+//! `double_def`, which unfolds applications during proof search.
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use corpus_core::nodes::{HashNode, Hashing, NodeStorage};
+use corpus_core::rewriting::RewriteRule;
+
+use crate::rewrite::equality_rule;
+use crate::syntax::ArithmeticExpression;
+
+/// The declared symbols by opcode.
+static SYMBOLS: RwLock<BTreeMap<u64, FunctionSymbol>> = RwLock::new(BTreeMap::new());
+
+/// A declared function symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSymbol {
+    pub name: String,
+    pub arity: usize,
+}
+
+/// The opcode of the symbol `name`, which never clashes with those of the
+/// built-in operations.
+pub fn opcode(name: &str) -> u64 {
+    Hashing::opcode(&format!("function {}", name))
+}
+
+/// Declare `name` as a function of `arity` arguments and return its opcode.
+///
+/// Declaring a symbol again with the same arity is allowed, e.g. to
+/// redefine it; a different arity is an error.
+pub fn declare(name: &str, arity: usize) -> Result<u64, String> {
+    let opcode = opcode(name);
+    let mut symbols = SYMBOLS.write().unwrap();
+    match symbols.get(&opcode) {
+        Some(symbol) if symbol.name != name => {
+            Err(format!("Function '{}' clashes with '{}'", name, symbol.name))
+        }
+        Some(symbol) if symbol.arity != arity => Err(format!(
+            "Function '{}' is already declared with {} arguments",
+            name, symbol.arity
+        )),
+        _ => {
+            symbols.insert(opcode, FunctionSymbol { name: name.to_string(), arity });
+            Ok(opcode)
+        }
+    }
+}
+
+/// The symbol registered under `opcode`, if any.
+pub fn symbol(opcode: u64) -> Option<FunctionSymbol> {
+    SYMBOLS.read().unwrap().get(&opcode).cloned()
+}
+
+/// The name of the symbol `opcode`, or `f<opcode>` if it was never declared.
+pub fn name(opcode: u64) -> String {
+    symbol(opcode).map_or_else(|| format!("f{}", opcode), |symbol| symbol.name)
+}
+
+/// The opcode and arity of the declared symbol `name`.
+pub fn lookup(name: &str) -> Option<(u64, usize)> {
+    let opcode = opcode(name);
+    symbol(opcode).filter(|symbol| symbol.name == name).map(|symbol| (opcode, symbol.arity))
+}
+
+/// A function defined by a term over its parameters `/0`, `/1`, ….
+pub struct FunctionDefinition {
+    pub name: String,
+    pub opcode: u64,
+    pub arity: usize,
+    pub body: HashNode<ArithmeticExpression>,
+}
+
+impl FunctionDefinition {
+    /// The name of the rule unfolding this function.
+    pub fn rule_name(&self) -> String {
+        format!("{}_def", self.name)
+    }
+
+    /// The rule rewriting an application into the body.
+    pub fn unfolding_rule(&self) -> RewriteRule<ArithmeticExpression> {
+        let store = NodeStorage::new();
+        let parameters = (0..self.arity as u32)
+            .map(|index| HashNode::from_store(ArithmeticExpression::DeBruijn(index), &store))
+            .collect();
+        let application = HashNode::from_store(ArithmeticExpression::Function(self.opcode, parameters), &store);
+        equality_rule(self.rule_name(), &application, &self.body).expect("the body only mentions parameters")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    #[test]
+    fn test_declarations() {
+        let opcode = declare("triple_decl", 1).unwrap();
+        assert_eq!(declare("triple_decl", 1), Ok(opcode));
+        assert!(declare("triple_decl", 2).is_err());
+        assert_eq!(lookup("triple_decl"), Some((opcode, 1)));
+        assert_eq!(lookup("undeclared"), None);
+        assert_ne!(opcode, Hashing::opcode("add"));
+    }
+
+    #[test]
+    fn test_definitions_unfold() {
+        let definition = Parser::new("def double(x) := x + x").parse_definition().unwrap();
+        assert_eq!((definition.name.as_str(), definition.arity), ("double", 1));
+        let rule = definition.unfolding_rule();
+        assert_eq!(rule.name, "double_def");
+
+        let store = NodeStorage::new();
+        let term = Parser::new("double(y * 2)").parse_term().unwrap();
+        let unfolded = rule.apply(&term, &store).unwrap();
+        assert_eq!(unfolded, Parser::new("y * 2 + y * 2").parse_term().unwrap());
+        assert_eq!(term.to_string(), "double((/0 * 2))");
+
+        assert!(Parser::new("def bad(x) := x + y").parse_definition().is_err());
+        assert!(Parser::new("def loop(x) := loop(x)").parse_definition().is_err());
+        assert!(Parser::new("double(1, 2) = 0").parse_formula().is_err());
+        assert!(Parser::new("undefined(1) = 0").parse_formula().is_err());
+    }
+}
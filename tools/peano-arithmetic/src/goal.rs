@@ -2,11 +2,12 @@
 //!
 //! This module provides goal checking implementations for the PA prover,
 //! specifically for checking when an equality is reflexive (x = x) or
-//! contradictory (n = S(n)).
+//! contradictory (n = S(n)), and for deciding trivial comparisons.
 
 use corpus_classical_logic::BinaryTruth;
 use corpus_core::proving::GoalChecker;
 use corpus_core::base::nodes::HashNode;
+use crate::eval::holds_with;
 use crate::syntax::{PeanoContent, ArithmeticExpression};
 
 /// Goal checker for Peano Arithmetic equalities.
@@ -16,6 +17,12 @@ use crate::syntax::{PeanoContent, ArithmeticExpression};
 ///   have the same hash, indicating a tautology.
 /// - **Contradictions** (n = S(n)): Returns `Some(False)` when a provable
 ///   contradiction is detected, such as 0 = S(0).
+/// - **Trivial comparisons**: `x ≤ x` and `x < x`, and comparisons of
+///   ground terms, which are evaluated.
+/// - **Trivial divisibility**: `x ∣ x`, `x ∣ 0`, and divisibility of ground
+///   terms, which is evaluated.
+/// - **Parity introduction**: `Even(x + x)` and `Odd(S(x + x))` hold, their
+///   opposites fail, and the parity of ground terms is evaluated.
 ///
 /// Note: The PA axioms (additive identity, additive successor) are used as
 /// **rewrite rules** for transforming expressions, not as goal patterns.
@@ -40,7 +47,12 @@ impl GoalChecker<PeanoContent, BinaryTruth> for AxiomPatternChecker {
             return Some(result);
         }
         // Then check for reflexive equality (x = x)
-        check_reflexive_equality(expr)
+        if let Some(result) = check_reflexive_equality(expr) {
+            return Some(result);
+        }
+        check_comparison(expr)
+            .or_else(|| check_divisibility(expr))
+            .or_else(|| check_parity(expr))
     }
 }
 
@@ -60,6 +72,50 @@ fn check_reflexive_equality(expr: &HashNode<PeanoContent>) -> Option<BinaryTruth
     None
 }
 
+/// Decide a comparison of a term with itself, or of two ground terms.
+fn check_comparison(expr: &HashNode<PeanoContent>) -> Option<BinaryTruth> {
+    let (PeanoContent::LessThan(left, right) | PeanoContent::LessEq(left, right)) = expr.value.as_ref() else {
+        return None;
+    };
+    if left.hash() == right.hash() {
+        return Some(BinaryTruth::from(matches!(expr.value.as_ref(), PeanoContent::LessEq(..))));
+    }
+    holds_with(&expr.value, &[]).map(BinaryTruth::from)
+}
+
+/// Decide `x ∣ x`, `x ∣ 0` and divisibility of ground terms.
+fn check_divisibility(expr: &HashNode<PeanoContent>) -> Option<BinaryTruth> {
+    let PeanoContent::Divides(left, right) = expr.value.as_ref() else {
+        return None;
+    };
+    if left.hash() == right.hash() || matches!(right.value.as_ref(), ArithmeticExpression::Number(0)) {
+        return Some(BinaryTruth::True);
+    }
+    holds_with(&expr.value, &[]).map(BinaryTruth::from)
+}
+
+/// Decide parity of doubles `x + x` and their successors, and of ground terms.
+fn check_parity(expr: &HashNode<PeanoContent>) -> Option<BinaryTruth> {
+    let (PeanoContent::Even(term) | PeanoContent::Odd(term)) = expr.value.as_ref() else {
+        return None;
+    };
+    let even = matches!(expr.value.as_ref(), PeanoContent::Even(_));
+    if is_double(term) {
+        return Some(BinaryTruth::from(even));
+    }
+    if let ArithmeticExpression::Successor(inner) = term.value.as_ref()
+        && is_double(inner)
+    {
+        return Some(BinaryTruth::from(!even));
+    }
+    holds_with(&expr.value, &[]).map(BinaryTruth::from)
+}
+
+/// Whether `term` is syntactically `x + x`.
+fn is_double(term: &HashNode<ArithmeticExpression>) -> bool {
+    matches!(term.value.as_ref(), ArithmeticExpression::Add(left, right) if left.hash() == right.hash())
+}
+
 /// Check if the equality represents a contradiction.
 ///
 /// A contradiction in Peano Arithmetic occurs when we can prove that an
@@ -247,4 +303,39 @@ mod tests {
         // Should return None, not Some(False)
         assert_eq!(checker.check(&expr), None);
     }
+
+    #[test]
+    fn test_trivial_comparisons_decided() {
+        use crate::parsing::Parser;
+
+        let checker = AxiomPatternChecker::new();
+        let check = |text| {
+            let proposition = Parser::new(text).parse_proposition().unwrap();
+            checker.check(proposition.value.as_domain().unwrap())
+        };
+
+        assert_eq!(check("LT (PLUS (1) (1)) (S (2))"), Some(BinaryTruth::True));
+        assert_eq!(check("<= (3) (S (1))"), Some(BinaryTruth::False));
+        assert_eq!(check("LE (PLUS (/0) (/1)) (PLUS (/0) (/1))"), Some(BinaryTruth::True));
+        assert_eq!(check("< (/0) (/0)"), Some(BinaryTruth::False));
+        assert_eq!(check("LT (/0) (S (/0))"), None);
+    }
+
+    #[test]
+    fn test_ground_divisibility_decided() {
+        use crate::parsing::Parser;
+
+        let checker = AxiomPatternChecker::new();
+        let check = |text| {
+            let proposition = Parser::new(text).parse_proposition().unwrap();
+            checker.check(proposition.value.as_domain().unwrap())
+        };
+
+        assert_eq!(check("DIVIDES (3) (PLUS (4) (2))"), Some(BinaryTruth::True));
+        assert_eq!(check("∣ (4) (6)"), Some(BinaryTruth::False));
+        assert_eq!(check("DIVIDES (0) (0)"), Some(BinaryTruth::True));
+        assert_eq!(check("DIVIDES (0) (2)"), Some(BinaryTruth::False));
+        assert_eq!(check("DIVIDES (/0) (0)"), Some(BinaryTruth::True));
+        assert_eq!(check("DIVIDES (2) (PLUS (/0) (/0))"), None);
+    }
 }
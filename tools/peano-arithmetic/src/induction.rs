@@ -0,0 +1,188 @@
+//! Proof by induction for PA equalities.
+//!
+//! The rewrite rules alone cannot prove `0 + x = x`, since no rule applies to
+//! the variable `x`. Induction on `x` instead proves the base case `P(0)` and
+//! the step case `P(S(x))`, the latter with the hypothesis `P(x)` available
+//! as the extra rewrite rule `hyp_0`. Other variables stay fixed throughout.
+
+use corpus_classical_logic::BinaryTruth;
+use corpus_core::nodes::HashNode;
+use corpus_core::proving::generalize::replace_subterm;
+use corpus_core::proving::{InductionCase, ProofResult};
+use corpus_core::rewriting::{Pattern, RewriteRule};
+
+use crate::axioms::peano_arithmetic_rules;
+use crate::printer::Printer;
+use crate::prover::{search_pa_with_goals, NoGoals, ProofResultExt};
+use crate::stores::PeanoStores;
+use crate::syntax::{ArithmeticExpression, PeanoContent};
+
+/// Name of the induction hypothesis rule in step-case proofs.
+pub const HYPOTHESIS: &str = "hyp_0";
+
+/// A proof of an equality for every value of one variable.
+pub struct InductionProof {
+    /// The statement `P(x)`.
+    pub statement: HashNode<PeanoContent>,
+    /// De Bruijn index of the induction variable `x`.
+    pub variable: u32,
+    /// Proof of `P(0)`.
+    pub base: ProofResult<PeanoContent, BinaryTruth>,
+    /// Proof of `P(S(x))` using `P(x)` as the rule `hyp_0`.
+    pub step: ProofResult<PeanoContent, BinaryTruth>,
+}
+
+impl InductionProof {
+    /// Print both cases in the format of `ProofResultExt::print`.
+    pub fn print(&self) {
+        println!("✓ Theorem proved by induction on /{}!", self.variable);
+        println!();
+        println!("Base case:");
+        self.base.print();
+        println!();
+        println!("Step case (hypothesis {}: {}):", HYPOTHESIS, Printer::default().content(&self.statement.value));
+        self.step.print();
+    }
+}
+
+/// The base and step goals `P(0)` and `P(S(x))` for induction on `/variable`.
+///
+/// Returns `None` if `statement` is not an equality.
+pub fn induction_cases(
+    statement: &HashNode<PeanoContent>,
+    variable: u32,
+    stores: &PeanoStores,
+) -> Option<(HashNode<PeanoContent>, HashNode<PeanoContent>)> {
+    let PeanoContent::Equals(left, right) = statement.value.as_ref() else {
+        return None;
+    };
+    let terms = &stores.expression_store;
+    let x = HashNode::from_store(ArithmeticExpression::DeBruijn(variable), terms);
+    let zero = HashNode::from_store(ArithmeticExpression::Number(0), terms);
+    let successor = HashNode::from_store(ArithmeticExpression::Successor(x.clone()), terms);
+
+    let case = |value: &HashNode<ArithmeticExpression>| {
+        let left = replace_subterm(left, &x, value, terms);
+        let right = replace_subterm(right, &x, value, terms);
+        HashNode::from_store(PeanoContent::Equals(left, right), &stores.content_store)
+    };
+    Some((case(&zero), case(&successor)))
+}
+
+/// Prove `statement` for every value of `/variable`, searching each case
+/// with at most `max_nodes` states.
+///
+/// On failure, returns the case that could not be proven.
+pub fn prove_by_induction(
+    statement: &HashNode<PeanoContent>,
+    variable: u32,
+    stores: &PeanoStores,
+    max_nodes: usize,
+) -> Result<InductionProof, InductionCase> {
+    prove_by_induction_with_rules(statement, variable, stores, max_nodes, &peano_arithmetic_rules())
+}
+
+/// `prove_by_induction` with the given arithmetic rules in place of the PA
+/// axioms, e.g. with earlier theorems added.
+pub fn prove_by_induction_with_rules(
+    statement: &HashNode<PeanoContent>,
+    variable: u32,
+    stores: &PeanoStores,
+    max_nodes: usize,
+    rules: &[RewriteRule<ArithmeticExpression>],
+) -> Result<InductionProof, InductionCase> {
+    search_by_induction_with_rules(statement, variable, stores, max_nodes, rules).0
+}
+
+/// `prove_by_induction_with_rules`, also counting the states explored by
+/// the cases it searched, whether they were proven or not.
+pub fn search_by_induction_with_rules(
+    statement: &HashNode<PeanoContent>,
+    variable: u32,
+    stores: &PeanoStores,
+    max_nodes: usize,
+    rules: &[RewriteRule<ArithmeticExpression>],
+) -> (Result<InductionProof, InductionCase>, usize) {
+    let Some((base_goal, step_goal)) = induction_cases(statement, variable, stores) else {
+        return (Err(InductionCase::Base), 0);
+    };
+    let mut rules = rules.to_vec();
+    let mut nodes_explored = 0;
+    let mut search = |goal: &HashNode<PeanoContent>, rules: &[RewriteRule<ArithmeticExpression>]| {
+        match search_pa_with_goals(goal, stores, max_nodes, rules, &NoGoals) {
+            Ok(result) => {
+                nodes_explored += result.nodes_explored;
+                Some(result).filter(|result| result.truth_result == BinaryTruth::True)
+            }
+            Err(failure) => {
+                nodes_explored += failure.nodes_explored;
+                None
+            }
+        }
+    };
+
+    let Some(base) = search(&base_goal, &rules) else {
+        return (Err(InductionCase::Base), nodes_explored);
+    };
+    rules.extend(hypothesis_rule(statement));
+    let Some(step) = search(&step_goal, &rules) else {
+        return (Err(InductionCase::Step), nodes_explored);
+    };
+
+    let proof = InductionProof {
+        statement: statement.clone(),
+        variable,
+        base,
+        step,
+    };
+    (Ok(proof), nodes_explored)
+}
+
+/// The hypothesis `l = r` as a rule rewriting exactly `l` to `r` and back.
+fn hypothesis_rule(statement: &HashNode<PeanoContent>) -> Option<RewriteRule<ArithmeticExpression>> {
+    let PeanoContent::Equals(left, right) = statement.value.as_ref() else {
+        return None;
+    };
+    let exact = |term| Pattern::from_term(term, &|_| None);
+    Some(RewriteRule::bidirectional(HYPOTHESIS, exact(left), exact(right)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    fn equality(text: &str) -> HashNode<PeanoContent> {
+        let proposition = Parser::new(text).parse_proposition().unwrap();
+        proposition.value.as_domain().unwrap().clone()
+    }
+
+    #[test]
+    fn test_left_identity_by_induction() {
+        let stores = PeanoStores::new();
+        let statement = equality("EQ (PLUS (0) (/0)) (/0)");
+
+        let (base, step) = induction_cases(&statement, 0, &stores).unwrap();
+        assert_eq!(base, equality("EQ (PLUS (0) (0)) (0)"));
+        assert_eq!(step, equality("EQ (PLUS (0) (S (/0))) (S (/0))"));
+
+        let proof = prove_by_induction(&statement, 0, &stores, 1000).expect("0 + x = x by induction");
+        assert!(proof.step.steps.iter().any(|step| step.rule_name.starts_with(HYPOTHESIS)));
+    }
+
+    #[test]
+    fn test_monus_cancels_addition() {
+        let stores = PeanoStores::new();
+        // (x + y) ∸ y = x by induction on y.
+        let statement = equality("EQ (MONUS (PLUS (/0) (/1)) (/1)) (/0)");
+        assert!(prove_by_induction(&statement, 1, &stores, 2000).is_ok());
+    }
+
+    #[test]
+    fn test_failing_base_case() {
+        let stores = PeanoStores::new();
+        // x = S(x) already fails at 0.
+        let statement = equality("EQ (/0) (S (/0))");
+        assert_eq!(prove_by_induction(&statement, 0, &stores, 200).err(), Some(InductionCase::Base));
+    }
+}
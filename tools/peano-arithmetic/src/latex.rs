@@ -0,0 +1,136 @@
+//! LaTeX rendering of PA proofs.
+//!
+//! A rewrite proof becomes an `align*` chain from the goal down to the
+//! expression that closed it, each line annotated with the rule that
+//! produced it. Every PA rewrite replaces equals by equals, so consecutive
+//! lines are equivalent. An induction proof adds a `bussproofs` tree joining
+//! the base and step cases, followed by the chain of each case:
+//!
+//! ```latex
+//! \begin{prooftree}
+//!   \AxiomC{$0 + 0 = 0$}
+//!   \AxiomC{$0 + v_{0} = v_{0} \rightarrow 0 + S(v_{0}) = S(v_{0})$}
+//!   \RightLabel{\scriptsize induction on $v_{0}$}
+//!   \BinaryInfC{$\forall x.\, 0 + x = x$}
+//! \end{prooftree}
+//! ```
+//!
+//! The output needs `amsmath` and `bussproofs`.
+
+use corpus_classical_logic::BinaryTruth;
+
+use crate::induction::InductionProof;
+use crate::printer::{bound_name, Charset, Printer};
+use crate::prover::{ProofOutcome, ProofResult};
+use crate::syntax::PeanoContent;
+
+/// The rewrite chain of `result` as an `align*` environment.
+pub fn derivation_latex(result: &ProofResult<PeanoContent, BinaryTruth>) -> String {
+    let printer = latex_printer();
+    let initial = result.steps.first().map_or(&result.final_expr, |step| &step.old_expr);
+
+    let mut lines = vec![format!("  & {}", printer.content(&initial.value))];
+    for step in &result.steps {
+        lines.push(format!(
+            "  \\iff{{}} & {} && \\text{{by {}}}",
+            printer.content(&step.new_expr.value),
+            rule_text(&step.rule_name)
+        ));
+    }
+    let closing = match result.truth_result {
+        BinaryTruth::False => "contradiction",
+        BinaryTruth::True => "axiom",
+    };
+    let last = lines.last_mut().expect("the chain starts at the goal");
+    last.push_str(&format!(" && \\text{{({})}}", closing));
+
+    format!("\\begin{{align*}}\n{}\n\\end{{align*}}\n", lines.join(" \\\\\n"))
+}
+
+/// The induction rule applied to `proof` as a `prooftree`, followed by the
+/// chains of both cases.
+pub fn induction_latex(proof: &InductionProof) -> String {
+    let printer = latex_printer();
+    let statement = printer.content(&proof.statement.value);
+    let (base, step) = (proof.base.steps.first(), proof.step.steps.first());
+    let base_goal = base.map_or(&proof.base.final_expr, |step| &step.old_expr);
+    let step_goal = step.map_or(&proof.step.final_expr, |step| &step.old_expr);
+    let variable = free_variable(proof.variable);
+
+    format!(
+        "\\begin{{prooftree}}\n  \\AxiomC{{${}$}}\n  \\AxiomC{{${} \\rightarrow {}$}}\n  \\RightLabel{{\\scriptsize induction on ${}$}}\n  \\BinaryInfC{{$\\forall {}.\\, {}$}}\n\\end{{prooftree}}\n\nBase case:\n{}\nStep case:\n{}",
+        printer.content(&base_goal.value),
+        statement,
+        printer.content(&step_goal.value),
+        variable,
+        bound_name(0),
+        generalized(&proof.statement.value, proof.variable, &printer),
+        derivation_latex(&proof.base),
+        derivation_latex(&proof.step)
+    )
+}
+
+/// The LaTeX of a proof outcome, or `None` if nothing was proven.
+pub fn outcome_latex(outcome: &ProofOutcome) -> Option<String> {
+    match outcome {
+        ProofOutcome::Proved(result) => Some(derivation_latex(result)),
+        ProofOutcome::ProvedByInduction(proof) => Some(induction_latex(proof)),
+        ProofOutcome::Disproved { .. } | ProofOutcome::Unknown => None,
+    }
+}
+
+fn latex_printer() -> Printer {
+    Printer::new().with_charset(Charset::Latex)
+}
+
+/// `statement` with `/variable` written as the bound variable `x`.
+fn generalized(statement: &PeanoContent, variable: u32, printer: &Printer) -> String {
+    printer.content(statement).replace(&free_variable(variable), &bound_name(0))
+}
+
+/// How the LaTeX printer writes the free variable `/index`.
+fn free_variable(index: u32) -> String {
+    format!("v_{{{}}}", index)
+}
+
+/// A rule name in typewriter type, with underscores escaped.
+fn rule_text(name: &str) -> String {
+    format!("\\texttt{{{}}}", name.replace('_', "\\_"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use crate::prover::prove_or_disprove;
+    use crate::stores::PeanoStores;
+
+    fn outcome(text: &str) -> ProofOutcome {
+        let stores = PeanoStores::new();
+        let proposition = Parser::with_stores(text, &stores).parse_formula().unwrap();
+        prove_or_disprove(proposition.value.as_domain().unwrap(), &stores, 5000, 8)
+    }
+
+    #[test]
+    fn test_formula_symbols() {
+        let printer = latex_printer();
+        let formula = Parser::new("forall x. S(x) * 2 ^ (x + 1) <= /1 -> !(x = 0)").parse_formula().unwrap();
+        assert_eq!(
+            printer.expression(&formula.value),
+            "\\forall x.\\, S(x) \\cdot 2^{x + 1} \\leq v_{1} \\rightarrow \\neg (x = 0)"
+        );
+    }
+
+    #[test]
+    fn test_proofs_render() {
+        let chain = outcome_latex(&outcome("x + 1 = S(x)")).unwrap();
+        assert!(chain.starts_with("\\begin{align*}\n  & v_{0} + 1 = S(v_{0}) \\\\\n  \\iff{} & "));
+        assert!(chain.contains("\\texttt{numeral\\_successor}"));
+        assert!(chain.ends_with("\\text{(axiom)}\n\\end{align*}\n"));
+
+        let induction = outcome_latex(&outcome("0 + x = x")).unwrap();
+        assert!(induction.contains("\\BinaryInfC{$\\forall x.\\, 0 + x = x$}"));
+        assert_eq!(induction.matches("\\begin{align*}").count(), 2);
+        assert!(outcome_latex(&outcome("x + 1 = x")).is_none());
+    }
+}
@@ -0,0 +1,114 @@
+//! A library of proven addition lemmas.
+//!
+//! The PA rules define addition by recursion on the right argument, so even
+//! `0 + x = x` needs induction and commutativity is out of reach for a plain
+//! search. The lemmas here are proven once, in order, each by induction from
+//! the PA rules and the lemmas before it. Every lemma comes with its proof,
+//! as a `NamedAxiom` for the axiom machinery and as a rewrite rule whose
+//! variables match any term.
+
+use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
+use corpus_core::base::axioms::NamedAxiom;
+use corpus_core::nodes::HashNode;
+use corpus_core::rewriting::RewriteRule;
+
+use crate::axioms::peano_arithmetic_rules;
+use crate::induction::{prove_by_induction_with_rules, InductionProof};
+use crate::parsing::{parse_axiom, Parser};
+use crate::stores::PeanoStores;
+use crate::rewrite::equality_rule;
+use crate::syntax::{ArithmeticExpression, PeanoContent};
+
+/// Search budget for each case of each lemma.
+const MAX_NODES: usize = 5000;
+
+/// A lemma of the library with its certificate.
+pub struct LibraryLemma {
+    pub name: &'static str,
+    pub statement: HashNode<PeanoContent>,
+    pub axiom: NamedAxiom<BinaryTruth, PeanoContent, ClassicalOperator>,
+    pub rule: RewriteRule<ArithmeticExpression>,
+    /// The induction proof from the PA rules and the earlier lemmas.
+    pub proof: InductionProof,
+}
+
+/// The addition lemmas: name, statement and induction variable.
+const ADDITION_LEMMAS: [(&str, &str, u32); 4] = [
+    // 0 + x = x
+    ("add_zero_left", "EQ (PLUS (0) (/0)) (/0)", 0),
+    // S(x) + y = S(x + y)
+    ("add_successor_left", "EQ (PLUS (S (/0)) (/1)) (S (PLUS (/0) (/1)))", 1),
+    // x + y = y + x
+    ("add_commutative", "EQ (PLUS (/0) (/1)) (PLUS (/1) (/0))", 1),
+    // (x + y) + z = x + (y + z)
+    ("add_associative", "EQ (PLUS (PLUS (/0) (/1)) (/2)) (PLUS (/0) (PLUS (/1) (/2)))", 2),
+];
+
+/// Prove the addition lemmas.
+///
+/// Panics if a lemma cannot be proven, which would be a regression in the
+/// rules or the search rather than a property of the input.
+pub fn addition_lemmas() -> Vec<LibraryLemma> {
+    let stores = PeanoStores::new();
+    let mut rules = peano_arithmetic_rules();
+    let mut lemmas = Vec::new();
+
+    for (name, text, variable) in ADDITION_LEMMAS {
+        let proposition = Parser::with_stores(text, &stores).parse_proposition().expect("library lemmas parse");
+        let statement = proposition.value.as_domain().expect("library lemmas are atoms").clone();
+        let proof = prove_by_induction_with_rules(&statement, variable, &stores, MAX_NODES, &rules)
+            .unwrap_or_else(|case| panic!("{} case of {} not proven", case, name));
+
+        let PeanoContent::Equals(left, right) = statement.value.as_ref() else {
+            unreachable!("library lemmas are equalities");
+        };
+        let rule = equality_rule(name, left, right).expect("both sides share their variables");
+        rules.push(rule.clone());
+
+        lemmas.push(LibraryLemma {
+            name,
+            axiom: parse_axiom(text, name, &stores).unwrap_or_else(|e| panic!("Failed to parse {}: {}", name, e)),
+            statement,
+            rule,
+            proof,
+        });
+    }
+    lemmas
+}
+
+/// The PA rules followed by the rules of the addition lemmas.
+pub fn peano_arithmetic_rules_with_lemmas() -> Vec<RewriteRule<ArithmeticExpression>> {
+    let mut rules = peano_arithmetic_rules();
+    rules.extend(addition_lemmas().into_iter().map(|lemma| lemma.rule));
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::prove_pa_with_rules;
+
+    #[test]
+    fn test_lemmas_are_proven() {
+        let lemmas = addition_lemmas();
+        let names: Vec<_> = lemmas.iter().map(|lemma| lemma.name).collect();
+        assert_eq!(names, ["add_zero_left", "add_successor_left", "add_commutative", "add_associative"]);
+        for lemma in &lemmas {
+            assert_eq!(lemma.axiom.name, lemma.name);
+            assert_eq!(lemma.proof.statement, lemma.statement);
+            assert_eq!(lemma.proof.base.truth_result, BinaryTruth::True);
+            assert_eq!(lemma.proof.step.truth_result, BinaryTruth::True);
+        }
+    }
+
+    #[test]
+    fn test_lemmas_enable_proofs() {
+        let stores = PeanoStores::new();
+        let goal = Parser::with_stores("x + (y + 1) = (1 + y) + x", &stores).parse_formula().unwrap();
+        let goal = goal.value.as_domain().unwrap();
+
+        assert!(prove_pa_with_rules(goal, &stores, 300, &peano_arithmetic_rules()).is_none());
+        let result = prove_pa_with_rules(goal, &stores, 300, &peano_arithmetic_rules_with_lemmas()).expect("proof with lemmas");
+        assert_eq!(result.truth_result, BinaryTruth::True);
+    }
+}
@@ -1,9 +1,33 @@
 pub mod parsing;
 pub mod syntax;
 pub mod axioms;
+pub mod axiom_file;
 pub mod patterns;
 pub mod prover;
 pub mod rewrite;
 pub mod goal;
+pub mod induction;
+pub mod eval;
+pub mod counterexample;
+pub mod domain;
+pub mod divisibility;
+pub mod presburger;
+pub mod bounded;
+pub mod parity;
+pub mod session;
+pub mod script;
+pub mod printer;
+pub mod batch;
+pub mod lemmas;
+pub mod database;
+pub mod profiles;
+pub mod latex;
+pub mod markdown;
+pub mod export;
+pub mod coq;
+pub mod stores;
+pub mod functions;
+pub mod tptp;
+pub mod web;
 
-pub use prover::{PeanoProver, create_prover, ProofResult, ProofState, ProofStep, ProofResultExt};
\ No newline at end of file
+pub use prover::{PeanoProver, create_prover, ProofOutcome, ProofResult, ProofState, ProofStep, ProofResultExt};
\ No newline at end of file
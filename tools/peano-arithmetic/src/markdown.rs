@@ -0,0 +1,146 @@
+//! Markdown reports of batch runs.
+//!
+//! A report opens with a table of every goal and its status, followed by a
+//! section per goal: the steps of each rewrite proof that settled it, the
+//! statistics of its search and, for goals left open, why the search gave
+//! up and the closest formula it reached. Formulas are printed in Unicode
+//! as code spans.
+
+use std::fmt::Write;
+
+use corpus_core::proving::SearchStats;
+use corpus_core::rewriting::RewriteDirection;
+
+use crate::batch::{BatchReport, GoalReport, GoalStatus, ProofSteps};
+
+/// `report` as a Markdown document headed `title`.
+pub fn markdown_report(report: &BatchReport, title: &str) -> String {
+    let mut text = format!("# {}\n\n", title);
+    text.push_str("| Line | Goal | Status | Nodes | Time |\n|---:|---|---|---:|---:|\n");
+    for goal in &report.goals {
+        let _ = writeln!(
+            text,
+            "| {} | {} | {} {} | {} | {:.2?} |",
+            goal.line,
+            code(&goal.goal),
+            if goal.status.proved() { "✓" } else { "✗" },
+            cell(&goal.status.to_string()),
+            goal.nodes_explored,
+            goal.elapsed
+        );
+    }
+    let _ = writeln!(
+        text,
+        "\n**{} goals**: {} proved, {} disproved, {} exhausted, {} timeout, {} errors",
+        report.goals.len(),
+        report.proved(),
+        report.disproved(),
+        report.exhausted(),
+        report.timeouts(),
+        report.errors()
+    );
+    for goal in &report.goals {
+        text.push('\n');
+        goal_section(goal, &mut text);
+    }
+    text
+}
+
+fn goal_section(goal: &GoalReport, text: &mut String) {
+    let _ = writeln!(text, "## Line {}: {}\n\n**Status**: {}", goal.line, code(&goal.goal), goal.status);
+    for proof in &goal.proofs {
+        steps_table(proof, text);
+    }
+    if let Some(stats) = &goal.stats {
+        stats_list(stats, text);
+    }
+    if let Some(failure) = &goal.failure {
+        let _ = writeln!(text, "\n### Diagnostics\n\n- Reason: {}", failure.reason);
+        if let Some((formula, steps)) = &failure.closest {
+            let _ = writeln!(text, "- Closest formula: {} after {} steps", code(formula), steps);
+        }
+    }
+    if let GoalStatus::Error(reason) = &goal.status {
+        let _ = writeln!(text, "\n### Diagnostics\n\n- {}", reason);
+    }
+}
+
+fn steps_table(proof: &ProofSteps, text: &mut String) {
+    let heading = proof.case.map_or("Proof".to_string(), |case| format!("Proof: {}", case));
+    let _ = writeln!(text, "\n### {}\n", heading);
+    if proof.steps.is_empty() {
+        text.push_str("Closed without rewriting.\n");
+        return;
+    }
+    text.push_str("| # | Rule | Direction | Result |\n|---:|---|---|---|\n");
+    for (index, step) in proof.steps.iter().enumerate() {
+        let direction = match step.direction {
+            RewriteDirection::Backward => "←",
+            _ => "→",
+        };
+        let _ = writeln!(text, "| {} | {} | {} | {} |", index + 1, cell(&step.rule), direction, code(&step.formula));
+    }
+}
+
+fn stats_list(stats: &SearchStats, text: &mut String) {
+    let _ = writeln!(
+        text,
+        "\n### Statistics\n\n- Largest frontier: {}\n- Duplicate states: {}\n- Deepest level: {}\n- Time searching: {:.2?}",
+        stats.max_heap_size,
+        stats.duplicate_hits,
+        stats.max_depth(),
+        stats.elapsed
+    );
+    let mut rules: Vec<_> = stats.rule_applications.iter().collect();
+    rules.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    if !rules.is_empty() {
+        let applications: Vec<_> = rules.iter().map(|(rule, count)| format!("{} ×{}", cell(rule), count)).collect();
+        let _ = writeln!(text, "- Rule applications: {}", applications.join(", "));
+    }
+}
+
+/// `text` as a code span that is safe inside a table cell.
+fn code(text: &str) -> String {
+    if text.contains('`') {
+        format!("`` {} ``", cell(text))
+    } else {
+        format!("`{}`", cell(text))
+    }
+}
+
+/// `text` with the pipes that would end a table cell escaped.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::{parse_goals, BatchProver};
+
+    #[test]
+    fn test_report_sections() {
+        let goals = parse_goals("1 + 1 = 2\n0 + x = x\nx * x = x + 1\nx + = 1");
+        let report = BatchProver::new(300).prove_all(&goals);
+        let text = markdown_report(&report, "Arithmetic");
+
+        assert!(text.starts_with("# Arithmetic\n\n| Line | Goal | Status | Nodes | Time |\n"));
+        assert!(text.contains("\n| 2 | `0 + x = x` | ✓ proved (induction) | "));
+        assert!(text.contains("**4 goals**: 2 proved, 1 disproved, 0 exhausted, 0 timeout, 1 errors"));
+        assert!(text.contains("## Line 1: `1 + 1 = 2`\n\n**Status**: proved\n\n### Proof\n\nClosed without rewriting.\n"));
+        assert!(text.contains("### Proof: inductive step\n\n| # | Rule | Direction | Result |\n|---:|---|---|---|\n| 1 | axiom4_additive_successor | → | `S(0 + /0) = S(/0)` |"));
+        assert!(text.contains("### Statistics\n\n- Largest frontier: "));
+        assert!(text.contains("## Line 4: `x + = 1`\n\n**Status**: error: parse error: "));
+    }
+
+    #[test]
+    fn test_timeout_diagnostics() {
+        // Nothing rewrites products of variables.
+        let report = BatchProver::new(5).prove_all(&[(1, "x * (y * z) = (x * y) * z".to_string())]);
+        let text = markdown_report(&report, "Open");
+        assert_eq!(report.exhausted(), 1);
+        assert!(report.goals[0].nodes_explored > 0);
+        assert!(text.contains("### Diagnostics\n\n- Reason: search space exhausted\n- Closest formula: `/0 * (/1 * /2) = /0 * /1 * /2` after 0 steps"));
+        assert_eq!(code("a | b"), "`a \\| b`");
+    }
+}
@@ -0,0 +1,154 @@
+//! Parity predicates, as a worked example of predicates layered on PA.
+//!
+//! `Even(x)` abbreviates `∃k. x = k + k` and `Odd(x)` abbreviates
+//! `∃k. x = S(k + k)`. The goal checker introduces them on doubles, the
+//! rules below move them through successors, `unfold_parity` eliminates them
+//! into their definitions, and `parity_lemmas` collects the usual facts
+//! about sums for use as axioms.
+
+use corpus_classical_logic::{AxiomGoalChecker, BinaryTruth, ClassicalOperator, FirstOrderDomain};
+use corpus_core::base::axioms::NamedAxiom;
+use corpus_core::nodes::{HashNode, NodeStorage};
+
+use crate::divisibility::{unfold_atoms, PeanoLogicalExpression};
+use crate::domain::{free_variables, PeanoDomain};
+use crate::parsing::parse_axiom;
+use crate::stores::PeanoStores;
+use crate::syntax::{ArithmeticExpression, PeanoContent, PeanoExpression};
+
+/// Lemmas about parity, implicitly universal like `peano_arithmetic_axioms`.
+pub fn parity_lemmas() -> Vec<NamedAxiom<BinaryTruth, PeanoContent, ClassicalOperator>> {
+    let stores = PeanoStores::new();
+    let lemmas = [
+        ("OR (EVEN (/0)) (ODD (/0))", "parity_even_or_odd"),
+        ("NOT (AND (EVEN (/0)) (ODD (/0)))", "parity_not_even_and_odd"),
+        ("-> (AND (EVEN (/0)) (EVEN (/1))) (EVEN (PLUS (/0) (/1)))", "parity_even_plus_even"),
+        ("-> (AND (ODD (/0)) (ODD (/1))) (EVEN (PLUS (/0) (/1)))", "parity_odd_plus_odd"),
+        ("-> (AND (EVEN (/0)) (ODD (/1))) (ODD (PLUS (/0) (/1)))", "parity_even_plus_odd"),
+        ("-> (AND (ODD (/0)) (EVEN (/1))) (ODD (PLUS (/0) (/1)))", "parity_odd_plus_even"),
+    ];
+    lemmas
+        .iter()
+        .map(|(text, name)| parse_axiom(text, name, &stores).unwrap_or_else(|e| panic!("Failed to parse {}: {}", name, e)))
+        .collect()
+}
+
+/// An `AxiomGoalChecker` holding the universal closure of each parity lemma,
+/// so that goals instantiating a lemma's variables are closed by it.
+pub fn parity_lemma_checker<'a>(domain: PeanoDomain<'a>) -> AxiomGoalChecker<PeanoContent, PeanoDomain<'a>> {
+    let store = NodeStorage::new();
+    let closures: Vec<_> = parity_lemmas()
+        .into_iter()
+        .map(|lemma| {
+            let arity = free_variables(&PeanoExpression::logical(lemma.expression.clone()), &domain).len();
+            let closure = (0..arity).fold(lemma.expression, |body, _| {
+                HashNode::from_store(PeanoLogicalExpression::compound(ClassicalOperator::Forall, vec![body]), &store)
+            });
+            (lemma.name, closure)
+        })
+        .collect();
+
+    let mut checker = AxiomGoalChecker::new(domain);
+    for (name, closure) in closures {
+        checker.add_axiom(name, closure);
+    }
+    checker
+}
+
+/// Parity through a successor: `Even(S(x))` becomes `Odd(x)` and `Odd(S(x))`
+/// becomes `Even(x)`, each with the name of the rule used.
+pub fn apply_parity_rules(
+    content: &HashNode<PeanoContent>,
+    store: &NodeStorage<PeanoContent>,
+) -> Vec<(HashNode<PeanoContent>, &'static str)> {
+    let rewritten = match content.value.as_ref() {
+        PeanoContent::Even(term) => match term.value.as_ref() {
+            ArithmeticExpression::Successor(inner) => (PeanoContent::Odd(inner.clone()), "even_successor"),
+            _ => return Vec::new(),
+        },
+        PeanoContent::Odd(term) => match term.value.as_ref() {
+            ArithmeticExpression::Successor(inner) => (PeanoContent::Even(inner.clone()), "odd_successor"),
+            _ => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+    vec![(HashNode::from_store(rewritten.0, store), rewritten.1)]
+}
+
+/// The definition of a parity atom: `∃k. x = k + k` for `Even(x)` and
+/// `∃k. x = S(k + k)` for `Odd(x)`, or `None` for any other atom.
+pub fn unfold_parity(
+    atom: &HashNode<PeanoContent>,
+    domain: &PeanoDomain,
+    store: &NodeStorage<PeanoLogicalExpression>,
+) -> Option<HashNode<PeanoLogicalExpression>> {
+    let (term, odd) = match atom.value.as_ref() {
+        PeanoContent::Even(term) => (term, false),
+        PeanoContent::Odd(term) => (term, true),
+        _ => return None,
+    };
+    let term = domain.map_variables(term, &|index| Some(domain.variable(index + 1)))?;
+    let witness = domain.variable(0);
+    let mut double = HashNode::from_store(ArithmeticExpression::Add(witness.clone(), witness), domain.expression_store);
+    if odd {
+        double = HashNode::from_store(ArithmeticExpression::Successor(double), domain.expression_store);
+    }
+    let equality = HashNode::from_store(PeanoContent::Equals(term, double), domain.content_store);
+
+    let body = HashNode::from_store(PeanoLogicalExpression::atomic(equality), store);
+    Some(HashNode::from_store(
+        PeanoLogicalExpression::compound(ClassicalOperator::Exists, vec![body]),
+        store,
+    ))
+}
+
+/// `expr` with every parity atom replaced by its definition.
+pub fn unfold_parity_predicates(
+    expr: &HashNode<PeanoLogicalExpression>,
+    domain: &PeanoDomain,
+    store: &NodeStorage<PeanoLogicalExpression>,
+) -> HashNode<PeanoLogicalExpression> {
+    unfold_atoms(expr, store, &|atom| unfold_parity(atom, domain, store))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use crate::presburger::decide;
+
+    #[test]
+    fn test_lemmas_are_valid_and_close_instances() {
+        for lemma in parity_lemmas() {
+            let statement = PeanoExpression::logical(lemma.expression.clone());
+            assert_eq!(decide(&statement), Some(true), "{}", lemma.name);
+        }
+
+        let content_store = NodeStorage::new();
+        let expression_store = NodeStorage::new();
+        let checker = parity_lemma_checker(PeanoDomain::new(&content_store, &expression_store));
+        let goal = Parser::new("-> (AND (EVEN (2)) (ODD (S (/0)))) (ODD (PLUS (2) (S (/0))))")
+            .parse_proposition()
+            .unwrap();
+        let goal = goal.value.as_logical(&NodeStorage::new());
+        assert_eq!(checker.matching_axiom(&goal), Some("parity_even_plus_odd"));
+    }
+
+    #[test]
+    fn test_unfold_and_successor_rules() {
+        let content_store = NodeStorage::new();
+        let expression_store = NodeStorage::new();
+        let domain = PeanoDomain::new(&content_store, &expression_store);
+        let store = NodeStorage::new();
+
+        let proposition = Parser::new("ODD (PLUS (/0) (1))").parse_proposition().unwrap();
+        let expr = proposition.value.as_logical(&store);
+        let unfolded = unfold_parity_predicates(&expr, &domain, &store);
+        assert_eq!(unfolded.to_string(), "(∃ (/1 + 1) = S((/0 + /0)))");
+
+        let proposition = Parser::new("EVEN (S (/0))").parse_proposition().unwrap();
+        let rewritten = apply_parity_rules(proposition.value.as_domain().unwrap(), &content_store);
+        assert_eq!(rewritten.len(), 1);
+        assert_eq!((rewritten[0].0.to_string().as_str(), rewritten[0].1), ("Odd(/0)", "even_successor"));
+    }
+}
@@ -1,10 +1,15 @@
-use std::{iter::Peekable, str::Chars};
+use std::ops::Deref;
 
 use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
 use corpus_core::expression::LogicalExpression;
-use corpus_core::nodes::{HashNode, NodeStorage};
+use corpus_core::nodes::{HashNode, HashNodeInner, NodeStorage};
+use corpus_parsing::{parse_binary, Grammar, Infix, Lexer, TokenKind, Tokens};
+pub use corpus_parsing::{ParseError, ParseErrorKind, Span};
 
-use crate::syntax::{ArithmeticExpression, PeanoContent, PeanoExpression};
+use crate::eval::variables;
+use crate::functions::{self, FunctionDefinition};
+use crate::stores::PeanoStores;
+use crate::syntax::{normalize_numerals, ArithmeticExpression, NumeralForm, PeanoContent, PeanoExpression};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
@@ -17,355 +22,760 @@ pub enum Token {
     Forall,
     Exists,
     Eq,
+    Lt,
+    Le,
     Plus,
     Successor,
+    Predecessor,
+    Monus,
+    Times,
+    Power,
+    Divides,
+    Even,
+    Odd,
+    Iff,
+    Dot,
+    Comma,
+    /// `def`, starting a function definition.
+    Def,
+    /// `:=` between a defined function and its body.
+    Define,
     Number(u64),
     DeBruijn(u32),
+    /// A variable name of the infix syntax.
+    Ident(String),
+    /// A character no token starts with.
+    Unknown(char),
 }
 
-pub struct Lexer<'a> {
-    chars: Peekable<Chars<'a>>,
-}
-
-impl<'a> Lexer<'a> {
-    pub fn new(input: &'a str) -> Self {
-        Self {
-            chars: input.chars().peekable(),
-        }
+impl TokenKind for Token {
+    const SYMBOLS: &'static [(&'static str, Self)] = &[
+        ("(", Token::LParen),
+        (")", Token::RParen),
+        ("∧", Token::And),
+        ("&", Token::And),
+        ("&&", Token::And),
+        ("∨", Token::Or),
+        ("||", Token::Or),
+        ("→", Token::Implies),
+        ("->", Token::Implies),
+        ("↔", Token::Iff),
+        ("<->", Token::Iff),
+        ("¬", Token::Not),
+        ("!", Token::Not),
+        ("~", Token::Not),
+        ("∀", Token::Forall),
+        ("∃", Token::Exists),
+        ("=", Token::Eq),
+        ("<", Token::Lt),
+        ("<=", Token::Le),
+        ("≤", Token::Le),
+        ("+", Token::Plus),
+        ("-", Token::Monus),
+        ("∸", Token::Monus),
+        ("*", Token::Times),
+        ("^", Token::Power),
+        ("∣", Token::Divides),
+        ("|", Token::Divides),
+        (".", Token::Dot),
+        (",", Token::Comma),
+        (":=", Token::Define),
+    ];
+    const KEYWORDS: &'static [(&'static str, Self)] = &[
+        ("AND", Token::And),
+        ("OR", Token::Or),
+        ("IMPLIES", Token::Implies),
+        ("IFF", Token::Iff),
+        ("NOT", Token::Not),
+        ("FORALL", Token::Forall),
+        ("forall", Token::Forall),
+        ("EXISTS", Token::Exists),
+        ("exists", Token::Exists),
+        ("EQ", Token::Eq),
+        ("LT", Token::Lt),
+        ("LE", Token::Le),
+        ("PLUS", Token::Plus),
+        ("S", Token::Successor),
+        ("P", Token::Predecessor),
+        ("PRED", Token::Predecessor),
+        ("MONUS", Token::Monus),
+        ("TIMES", Token::Times),
+        ("MUL", Token::Times),
+        ("POW", Token::Power),
+        ("DIVIDES", Token::Divides),
+        ("EVEN", Token::Even),
+        ("Even", Token::Even),
+        ("ODD", Token::Odd),
+        ("Odd", Token::Odd),
+        ("def", Token::Def),
+    ];
+
+    fn number(value: u64) -> Self {
+        Token::Number(value)
     }
 
-    fn skip_whitespace(&mut self) {
-        while let Some(&c) = self.chars.peek() {
-            if c.is_whitespace() {
-                self.chars.next();
-            } else {
-                break;
-            }
-        }
+    fn identifier(name: String) -> Self {
+        Token::Ident(name)
     }
 
-    fn parse_number_or_debruijn(&mut self) -> Option<Token> {
-        let mut s = String::new();
-        let is_debruijn = if let Some(&'/') = self.chars.peek() {
-            self.chars.next(); // consume '/'
-            true
-        } else {
-            false
-        };
-
-        while let Some(&c) = self.chars.peek() {
-            if c.is_ascii_digit() {
-                s.push(self.chars.next().unwrap());
-            } else {
-                break;
-            }
-        }
-
-        if s.is_empty() {
-            return None; // Should not happen if called correctly
-        }
-
-        if is_debruijn {
-            Some(Token::DeBruijn(s.parse().ok()?))
-        } else {
-            Some(Token::Number(s.parse().ok()?))
-        }
+    fn unknown(c: char) -> Self {
+        Token::Unknown(c)
     }
 
-    fn parse_keyword_or_symbol(&mut self) -> Option<Token> {
-        let c = self.chars.peek()?;
-        if *c == '(' {
-            self.chars.next();
-            return Some(Token::LParen);
-        }
-        if *c == ')' {
-            self.chars.next();
-            return Some(Token::RParen);
-        }
-
-        // Symbols
-        match *c {
-            '∧' => {
-                self.chars.next();
-                return Some(Token::And);
-            }
-            '∨' => {
-                self.chars.next();
-                return Some(Token::Or);
-            }
-            '→' => {
-                self.chars.next();
-                return Some(Token::Implies);
-            }
-            '¬' => {
-                self.chars.next();
-                return Some(Token::Not);
-            }
-            '∀' => {
-                self.chars.next();
-                return Some(Token::Forall);
-            }
-            '∃' => {
-                self.chars.next();
-                return Some(Token::Exists);
-            }
-            '=' => {
-                self.chars.next();
-                return Some(Token::Eq);
-            }
-            '+' => {
-                self.chars.next();
-                return Some(Token::Plus);
-            }
-            _ => {}
-        }
-
-        // Multi-char symbols or keywords
-        // Simple heuristic: read alphanumeric chars
-        let mut s = String::new();
-        while let Some(&peep) = self.chars.peek() {
-            if peep.is_alphanumeric() || peep == '-' || peep == '>' {
-                s.push(self.chars.next().unwrap());
-            } else {
-                break;
-            }
-        }
+    fn de_bruijn(index: u32) -> Option<Self> {
+        Some(Token::DeBruijn(index))
+    }
 
-        match s.as_str() {
-            "AND" => Some(Token::And),
-            "OR" => Some(Token::Or),
-            "IMPLIES" | "->" => Some(Token::Implies),
-            "NOT" => Some(Token::Not),
-            "FORALL" => Some(Token::Forall),
-            "EXISTS" => Some(Token::Exists),
-            "EQ" => Some(Token::Eq),
-            "PLUS" => Some(Token::Plus),
-            "S" => Some(Token::Successor), // 'S' is a keyword for Successor
-            _ => None,                     // parsing error or empty
-        }
+    fn describe(&self) -> String {
+        let text = match self {
+            Token::LParen => "(",
+            Token::RParen => ")",
+            Token::Dot => ".",
+            Token::Comma => ",",
+            Token::Def => "def",
+            Token::Define => ":=",
+            Token::Eq => "=",
+            Token::Lt => "<",
+            Token::Le => "<=",
+            Token::Divides => "|",
+            other => return format!("{:?}", other),
+        };
+        format!("`{}`", text)
     }
 }
 
-impl<'a> Iterator for Lexer<'a> {
-    type Item = Token;
+/// The connectives of the infix syntax, loosest first.
+const CONNECTIVES: &[Infix<Token, ClassicalOperator>] = &[
+    Infix::right(Token::Iff, ClassicalOperator::Iff, 1),
+    Infix::right(Token::Implies, ClassicalOperator::Implies, 2),
+    Infix::left(Token::Or, ClassicalOperator::Or, 3),
+    Infix::left(Token::And, ClassicalOperator::And, 4),
+];
+
+type Operation = fn(HashNode<ArithmeticExpression>, HashNode<ArithmeticExpression>) -> ArithmeticExpression;
+
+/// The binary operators of infix terms, loosest first.
+const OPERATIONS: &[Infix<Token, Operation>] = &[
+    Infix::left(Token::Plus, ArithmeticExpression::Add, 1),
+    Infix::left(Token::Monus, ArithmeticExpression::Monus, 1),
+    Infix::left(Token::Times, ArithmeticExpression::Multiply, 2),
+    Infix::right(Token::Power, ArithmeticExpression::Power, 3),
+];
+
+/// The stores a parser interns into: its own, or ones shared with the caller.
+enum Stores<'a> {
+    Owned(Box<PeanoStores>),
+    Shared(&'a PeanoStores),
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.skip_whitespace();
-        if let Some(&c) = self.chars.peek() {
-            if c.is_ascii_digit() || c == '/' {
-                return self.parse_number_or_debruijn();
-            }
-            return self.parse_keyword_or_symbol();
+impl Deref for Stores<'_> {
+    type Target = PeanoStores;
+
+    fn deref(&self) -> &PeanoStores {
+        match self {
+            Stores::Owned(stores) => stores,
+            Stores::Shared(stores) => stores,
         }
-        None
     }
 }
 
 pub struct Parser<'a> {
-    tokens: Peekable<Lexer<'a>>,
-    peano_store: NodeStorage<PeanoExpression>,
-    expression_store: NodeStorage<ArithmeticExpression>,
-    content_store: NodeStorage<PeanoContent>,
-    logical_store: NodeStorage<LogicalExpression<BinaryTruth, PeanoContent, ClassicalOperator>>,
+    tokens: Tokens<'a, Token>,
+    /// Whether infix atoms that fail to parse are recorded in `errors` and
+    /// skipped, rather than ending the parse.
+    recovering: bool,
+    errors: Vec<ParseError>,
+    stores: Stores<'a>,
+    numerals: Option<NumeralForm>,
+    /// Names bound by the enclosing infix quantifiers, innermost last.
+    bound: Vec<String>,
+    /// Free infix variable names, in order of first appearance.
+    free: Vec<String>,
+}
+
+impl<'a> Grammar<'a> for Parser<'a> {
+    type Token = Token;
+
+    fn tokens(&mut self) -> &mut Tokens<'a, Token> {
+        &mut self.tokens
+    }
 }
 
 impl<'a> Parser<'a> {
     pub fn new(input: &'a str) -> Self {
+        Self::with(input, Stores::Owned(Box::default()))
+    }
+
+    /// A parser interning its nodes into `stores`, so that they are shared
+    /// with the proof search that follows.
+    pub fn with_stores(input: &'a str, stores: &'a PeanoStores) -> Self {
+        Self::with(input, Stores::Shared(stores))
+    }
+
+    fn with(input: &'a str, stores: Stores<'a>) -> Self {
         Self {
-            tokens: Lexer::new(input).peekable(),
-            peano_store: NodeStorage::new(),
-            expression_store: NodeStorage::new(),
-            content_store: NodeStorage::new(),
-            logical_store: NodeStorage::new(),
+            tokens: Tokens::new(input),
+            recovering: false,
+            errors: Vec::new(),
+            stores,
+            numerals: None,
+            bound: Vec::new(),
+            free: Vec::new(),
         }
     }
 
-    fn expect(&mut self, expected: Token) -> Result<(), String> {
-        match self.tokens.next() {
-            Some(t) if t == expected => Ok(()),
-            Some(t) => Err(format!("Expected {:?}, found {:?}", expected, t)),
-            None => Err(format!("Expected {:?}, found EOF", expected)),
+    /// Normalize every parsed numeral into `form`, instead of keeping `2`
+    /// and `S(S(0))` as written.
+    pub fn with_numerals(mut self, form: NumeralForm) -> Self {
+        self.numerals = Some(form);
+        self
+    }
+
+    fn numeral(&self, expr: ArithmeticExpression) -> HashNode<ArithmeticExpression> {
+        let node = HashNode::from_store(expr, &self.stores.expression_store);
+        match self.numerals {
+            Some(form) => normalize_numerals(&node, form, &self.stores.expression_store),
+            None => node,
         }
     }
 
     // Helper to consume optional surrounding parentheses for an argument
     // The grammar says: <op> (<arg>) (<arg>)
     // So we basically expect a LParen, parse, then RParen.
-    fn parse_parenthesized<F, T>(&mut self, parser: F) -> Result<T, String>
+    fn parse_parenthesized<F, T>(&mut self, parser: F) -> Result<T, ParseError>
     where
-        F: FnOnce(&mut Self) -> Result<T, String>,
+        F: FnOnce(&mut Self) -> Result<T, ParseError>,
     {
-        self.expect(Token::LParen)?;
+        self.tokens.expect(Token::LParen)?;
         let result = parser(self)?;
-        self.expect(Token::RParen)?;
+        self.tokens.expect(Token::RParen)?;
         Ok(result)
     }
 
-    pub fn parse_proposition(&mut self) -> Result<HashNode<PeanoExpression>, String> {
-        let token = self
-            .tokens
-            .next()
-            .ok_or("Unexpected EOF expecting Proposition")?;
+    pub fn parse_proposition(&mut self) -> Result<HashNode<PeanoExpression>, ParseError> {
+        let token = self.tokens.advance().ok_or_else(|| self.tokens.unexpected(&["a proposition"]))?;
         match token {
-            Token::And => {
+            Token::And | Token::Or | Token::Implies | Token::Iff => {
+                let operator = match token {
+                    Token::And => ClassicalOperator::And,
+                    Token::Or => ClassicalOperator::Or,
+                    Token::Implies => ClassicalOperator::Implies,
+                    _ => ClassicalOperator::Iff,
+                };
                 let left = self.parse_parenthesized(Self::parse_proposition)?;
                 let right = self.parse_parenthesized(Self::parse_proposition)?;
                 let logical_expr = LogicalExpression::compound(
-                    ClassicalOperator::And,
+                    operator,
                     vec![
-                        left.value.as_logical(&self.logical_store),
-                        right.value.as_logical(&self.logical_store),
+                        left.value.as_logical(&self.stores.logical_store),
+                        right.value.as_logical(&self.stores.logical_store),
                     ],
                 );
-                let logical_node = HashNode::from_store(logical_expr, &self.logical_store);
+                let logical_node = HashNode::from_store(logical_expr, &self.stores.logical_store);
                 let peano_expr = PeanoExpression::logical(logical_node);
-                Ok(HashNode::from_store(peano_expr, &self.peano_store))
-            }
-            Token::Or => {
-                let left = self.parse_parenthesized(Self::parse_proposition)?;
-                let right = self.parse_parenthesized(Self::parse_proposition)?;
-                let logical_expr = LogicalExpression::compound(
-                    ClassicalOperator::Or,
-                    vec![
-                        left.value.as_logical(&self.logical_store),
-                        right.value.as_logical(&self.logical_store),
-                    ],
-                );
-                let logical_node = HashNode::from_store(logical_expr, &self.logical_store);
-                let peano_expr = PeanoExpression::logical(logical_node);
-                Ok(HashNode::from_store(peano_expr, &self.peano_store))
-            }
-            Token::Implies => {
-                let left = self.parse_parenthesized(Self::parse_proposition)?;
-                let right = self.parse_parenthesized(Self::parse_proposition)?;
-                let logical_expr = LogicalExpression::compound(
-                    ClassicalOperator::Implies,
-                    vec![
-                        left.value.as_logical(&self.logical_store),
-                        right.value.as_logical(&self.logical_store),
-                    ],
-                );
-                let logical_node = HashNode::from_store(logical_expr, &self.logical_store);
-                let peano_expr = PeanoExpression::logical(logical_node);
-                Ok(HashNode::from_store(peano_expr, &self.peano_store))
+                Ok(HashNode::from_store(peano_expr, &self.stores.peano_store))
             }
             Token::Not => {
                 let inner = self.parse_parenthesized(Self::parse_proposition)?;
                 let logical_expr = LogicalExpression::compound(
                     ClassicalOperator::Not,
-                    vec![inner.value.as_logical(&self.logical_store)],
+                    vec![inner.value.as_logical(&self.stores.logical_store)],
                 );
-                let logical_node = HashNode::from_store(logical_expr, &self.logical_store);
+                let logical_node = HashNode::from_store(logical_expr, &self.stores.logical_store);
                 let peano_expr = PeanoExpression::logical(logical_node);
-                Ok(HashNode::from_store(peano_expr, &self.peano_store))
+                Ok(HashNode::from_store(peano_expr, &self.stores.peano_store))
             }
             Token::Forall => {
                 let inner = self.parse_parenthesized(Self::parse_proposition)?;
                 let logical_expr = LogicalExpression::compound(
                     ClassicalOperator::Forall,
-                    vec![inner.value.as_logical(&self.logical_store)],
+                    vec![inner.value.as_logical(&self.stores.logical_store)],
                 );
-                let logical_node = HashNode::from_store(logical_expr, &self.logical_store);
+                let logical_node = HashNode::from_store(logical_expr, &self.stores.logical_store);
                 let peano_expr = PeanoExpression::logical(logical_node);
-                Ok(HashNode::from_store(peano_expr, &self.peano_store))
+                Ok(HashNode::from_store(peano_expr, &self.stores.peano_store))
             }
             Token::Exists => {
                 let inner = self.parse_parenthesized(Self::parse_proposition)?;
                 let logical_expr = LogicalExpression::compound(
                     ClassicalOperator::Exists,
-                    vec![inner.value.as_logical(&self.logical_store)]
+                    vec![inner.value.as_logical(&self.stores.logical_store)]
                 );
-                let logical_node = HashNode::from_store(logical_expr, &self.logical_store);
+                let logical_node = HashNode::from_store(logical_expr, &self.stores.logical_store);
                 let peano_expr = PeanoExpression::logical(logical_node);
-                Ok(HashNode::from_store(peano_expr, &self.peano_store))
+                Ok(HashNode::from_store(peano_expr, &self.stores.peano_store))
             }
             Token::Eq => {
                 let left = self.parse_parenthesized(Self::parse_expression)?;
                 let right = self.parse_parenthesized(Self::parse_expression)?;
-                let content_node = HashNode::from_store(PeanoContent::Equals(left, right), &self.content_store);
+                let content_node = HashNode::from_store(PeanoContent::Equals(left, right), &self.stores.content_store);
+                let peano_expr = PeanoExpression::domain(content_node);
+                Ok(HashNode::from_store(peano_expr, &self.stores.peano_store))
+            }
+            Token::Lt | Token::Le | Token::Divides => {
+                let left = self.parse_parenthesized(Self::parse_expression)?;
+                let right = self.parse_parenthesized(Self::parse_expression)?;
+                let content = match token {
+                    Token::Lt => PeanoContent::LessThan(left, right),
+                    Token::Le => PeanoContent::LessEq(left, right),
+                    _ => PeanoContent::Divides(left, right),
+                };
+                let content_node = HashNode::from_store(content, &self.stores.content_store);
+                let peano_expr = PeanoExpression::domain(content_node);
+                Ok(HashNode::from_store(peano_expr, &self.stores.peano_store))
+            }
+            Token::Even | Token::Odd => {
+                let term = self.parse_parenthesized(Self::parse_expression)?;
+                let content = if token == Token::Even {
+                    PeanoContent::Even(term)
+                } else {
+                    PeanoContent::Odd(term)
+                };
+                let content_node = HashNode::from_store(content, &self.stores.content_store);
                 let peano_expr = PeanoExpression::domain(content_node);
-                Ok(HashNode::from_store(peano_expr, &self.peano_store))
+                Ok(HashNode::from_store(peano_expr, &self.stores.peano_store))
             }
-            _ => Err(format!(
-                "Unexpected token {:?} for start of Proposition",
-                token
-            )),
+            _ => Err(self.tokens.unexpected_previous(&["a proposition"])),
         }
     }
 
-    pub fn parse_expression(&mut self) -> Result<HashNode<ArithmeticExpression>, String> {
-        let token = self
-            .tokens
-            .peek()
-            .cloned()
-            .ok_or("Unexpected EOF expecting Expression")?;
+    pub fn parse_expression(&mut self) -> Result<HashNode<ArithmeticExpression>, ParseError> {
+        let token = self.tokens.peek().cloned().ok_or_else(|| self.tokens.unexpected(&["an expression"]))?;
 
         match token {
             Token::Plus => {
-                self.tokens.next();
+                self.tokens.advance();
                 let left = self.parse_parenthesized(Self::parse_expression)?;
                 let right = self.parse_parenthesized(Self::parse_expression)?;
                 let expr = ArithmeticExpression::Add(left, right);
-                Ok(HashNode::from_store(expr, &self.expression_store))
+                Ok(HashNode::from_store(expr, &self.stores.expression_store))
             }
             Token::Successor => {
-                self.tokens.next();
+                self.tokens.advance();
+                let inner = self.parse_parenthesized(Self::parse_expression)?;
+                Ok(self.numeral(ArithmeticExpression::Successor(inner)))
+            }
+            Token::Predecessor => {
+                self.tokens.advance();
                 let inner = self.parse_parenthesized(Self::parse_expression)?;
-                let expr = ArithmeticExpression::Successor(inner);
-                Ok(HashNode::from_store(expr, &self.expression_store))
+                let expr = ArithmeticExpression::Predecessor(inner);
+                Ok(HashNode::from_store(expr, &self.stores.expression_store))
+            }
+            Token::Monus => {
+                self.tokens.advance();
+                let left = self.parse_parenthesized(Self::parse_expression)?;
+                let right = self.parse_parenthesized(Self::parse_expression)?;
+                let expr = ArithmeticExpression::Monus(left, right);
+                Ok(HashNode::from_store(expr, &self.stores.expression_store))
+            }
+            Token::Times => {
+                self.tokens.advance();
+                let left = self.parse_parenthesized(Self::parse_expression)?;
+                let right = self.parse_parenthesized(Self::parse_expression)?;
+                let expr = ArithmeticExpression::Multiply(left, right);
+                Ok(HashNode::from_store(expr, &self.stores.expression_store))
+            }
+            Token::Power => {
+                self.tokens.advance();
+                let left = self.parse_parenthesized(Self::parse_expression)?;
+                let right = self.parse_parenthesized(Self::parse_expression)?;
+                let expr = ArithmeticExpression::Power(left, right);
+                Ok(HashNode::from_store(expr, &self.stores.expression_store))
             }
             Token::Number(n) => {
-                self.tokens.next();
-                let expr = ArithmeticExpression::Number(n);
-                Ok(HashNode::from_store(expr, &self.expression_store))
+                self.tokens.advance();
+                Ok(self.numeral(ArithmeticExpression::Number(n)))
             }
             Token::DeBruijn(n) => {
-                self.tokens.next();
+                self.tokens.advance();
                 let expr = ArithmeticExpression::DeBruijn(n);
-                Ok(HashNode::from_store(expr, &self.expression_store))
+                Ok(HashNode::from_store(expr, &self.stores.expression_store))
+            }
+            // A declared function, applied to one parenthesized argument
+            // per parameter: `name (a) (b)`.
+            Token::Ident(name) => {
+                self.tokens.advance();
+                let name_span = self.tokens.last();
+                let (opcode, arity) = functions::lookup(&name)
+                    .ok_or_else(|| self.tokens.invalid(name_span, format!("Unknown function '{}'", name)))?;
+                let args = (0..arity)
+                    .map(|_| self.parse_parenthesized(Self::parse_expression))
+                    .collect::<Result<_, _>>()?;
+                Ok(HashNode::from_store(ArithmeticExpression::Function(opcode, args), &self.stores.expression_store))
+            }
+            _ => Err(self.tokens.unexpected(&["an expression"])),
+        }
+    }
+
+    /// Parse a whole formula in either syntax.
+    ///
+    /// Input that parses completely as the prefix form is read that way;
+    /// anything else is read as infix. Formulas that can only be prefix,
+    /// such as those starting with `EQ`, report the prefix error.
+    pub fn parse_formula(&mut self) -> Result<HashNode<PeanoExpression>, ParseError> {
+        let start = self.tokens.clone();
+        let prefix_only = match self.tokens.peek() {
+            Some(Token::Ident(_) | Token::Number(_) | Token::DeBruijn(_) | Token::LParen)
+            | Some(Token::Successor | Token::Predecessor) => None,
+            Some(Token::Not | Token::Forall | Token::Exists | Token::Even | Token::Odd) => Some(false),
+            _ => Some(true),
+        };
+
+        if let Some(prefix_only) = prefix_only {
+            let prefix = self.parse_proposition().and_then(|proposition| self.tokens.expect_end().map(|_| proposition));
+            if prefix.is_ok() || prefix_only {
+                return prefix;
+            }
+            self.tokens = start;
+        }
+
+        let formula = self.parse_infix()?;
+        self.tokens.expect_end()?;
+        Ok(formula)
+    }
+
+    /// Parse a whole formula like `parse_formula`, but report every
+    /// malformed atom of an infix formula instead of only the first.
+    ///
+    /// An atom that fails to parse is skipped up to the next connective at
+    /// its nesting level, a parenthesis closing it, or the end of input, and
+    /// parsing resumes from there.
+    pub fn parse_formula_recovering(&mut self) -> Result<HashNode<PeanoExpression>, Vec<ParseError>> {
+        self.recovering = true;
+        let result = self.parse_formula();
+        self.recovering = false;
+        let mut errors = std::mem::take(&mut self.errors);
+        match result {
+            Ok(formula) if errors.is_empty() => Ok(formula),
+            Ok(_) => Err(errors),
+            Err(error) => {
+                errors.push(error);
+                Err(errors)
+            }
+        }
+    }
+
+    /// Parse a formula in infix syntax, e.g. `forall x. x + 0 = x`.
+    ///
+    /// From loosest to tightest: `<->`, `->` (right associative), `∨` or
+    /// `||`, `∧` or `&&`, then `¬` and the quantifiers, whose bodies extend as far right
+    /// as possible. Atoms are `=`, `<`, `<=` and `|` between terms, or
+    /// `Even(t)` and `Odd(t)`. In terms, `+` and `-` (truncated) bind
+    /// looser than `*`, which binds looser than the right associative `^`.
+    ///
+    /// Names bound by a quantifier become De Bruijn indices counting from
+    /// the innermost binder. Free names are numbered after all binders in
+    /// order of first appearance, so `x + y = y + x` reads as
+    /// `/0 + /1 = /1 + /0`. Indices such as `/0` may also be written
+    /// directly.
+    pub fn parse_infix(&mut self) -> Result<HashNode<PeanoExpression>, ParseError> {
+        parse_binary(self, CONNECTIVES, Self::parse_unary, |parser, operator, left, right| {
+            parser.compound(operator, vec![left, right])
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<HashNode<PeanoExpression>, ParseError> {
+        match self.tokens.peek() {
+            Some(Token::Not) => {
+                self.tokens.advance();
+                let inner = self.parse_unary()?;
+                Ok(self.compound(ClassicalOperator::Not, vec![inner]))
+            }
+            Some(Token::Forall) => {
+                self.tokens.advance();
+                self.parse_quantified(ClassicalOperator::Forall)
+            }
+            Some(Token::Exists) => {
+                self.tokens.advance();
+                self.parse_quantified(ClassicalOperator::Exists)
+            }
+            _ => self.parse_atom_or_recover(),
+        }
+    }
+
+    /// Parse an atom, or when recovering, record why it is malformed, skip
+    /// it, and stand in `0 = 0` for it.
+    fn parse_atom_or_recover(&mut self) -> Result<HashNode<PeanoExpression>, ParseError> {
+        let start = self.tokens.next_offset();
+        let error = match self.parse_atom() {
+            Ok(atom) => return Ok(atom),
+            Err(error) if !self.recovering => return Err(error),
+            Err(error) => error,
+        };
+        self.errors.push(error);
+
+        // Parentheses the atom opened before failing are closed by the skip.
+        let mut depth = Lexer::<Token>::new(&self.tokens.input()[start..self.tokens.next_offset()]).fold(0, |depth, (token, _)| match token {
+            Token::LParen => depth + 1,
+            Token::RParen => depth - 1,
+            _ => depth,
+        });
+        while let Some(token) = self.tokens.peek() {
+            match token {
+                Token::And | Token::Or | Token::Implies | Token::Iff if depth <= 0 => break,
+                Token::RParen if depth <= 0 => break,
+                Token::RParen => depth -= 1,
+                Token::LParen => depth += 1,
+                _ => {}
+            }
+            self.tokens.advance();
+        }
+        let zero = self.numeral(ArithmeticExpression::Number(0));
+        Ok(self.relation(PeanoContent::Equals(zero.clone(), zero)))
+    }
+
+    /// Parse `x, y. body` after a quantifier, binding each name in turn.
+    /// A quantifier over one or more names, each optionally bounded as in
+    /// `∀x < n, y ≤ x. φ`. A bound desugars to `∀x. x < n → φ`, or to
+    /// `∃x. x < n ∧ φ` under `∃`.
+    fn parse_quantified(&mut self, quantifier: ClassicalOperator) -> Result<HashNode<PeanoExpression>, ParseError> {
+        let mut bounds = Vec::new();
+        loop {
+            let name = match self.tokens.peek() {
+                Some(Token::Ident(name)) => name.clone(),
+                _ => return Err(self.tokens.unexpected(&["a variable name"])),
+            };
+            self.tokens.advance();
+            self.bound.push(name.clone());
+
+            // The bound is read inside the binder, where outer names keep
+            // their indices, and must not mention the name it bounds.
+            let bound = match self.tokens.peek() {
+                Some(Token::Lt | Token::Le) => {
+                    let strict = self.tokens.advance() == Some(Token::Lt);
+                    let start = self.tokens.next_offset();
+                    let limit = self.parse_term()?;
+                    if variables(&limit).contains(&0) {
+                        let span = Span { start, end: self.tokens.last().end };
+                        return Err(self.tokens.invalid(span, format!("The bound of {} mentions {}", name, name)));
+                    }
+                    let variable = HashNode::from_store(ArithmeticExpression::DeBruijn(0), &self.stores.expression_store);
+                    Some(if strict {
+                        PeanoContent::LessThan(variable, limit)
+                    } else {
+                        PeanoContent::LessEq(variable, limit)
+                    })
+                }
+                _ => None,
+            };
+            bounds.push(bound);
+            if self.tokens.peek() != Some(&Token::Comma) {
+                break;
+            }
+            self.tokens.advance();
+        }
+        self.tokens.expect(Token::Dot)?;
+
+        let mut body = self.parse_infix()?;
+        for bound in bounds.into_iter().rev() {
+            self.bound.pop();
+            if let Some(bound) = bound {
+                let connective = match quantifier {
+                    ClassicalOperator::Exists => ClassicalOperator::And,
+                    _ => ClassicalOperator::Implies,
+                };
+                body = self.compound(connective, vec![self.relation(bound), body]);
             }
-            _ => Err(format!(
-                "Unexpected token {:?} for start of Expression",
-                token
-            )),
+            body = self.compound(quantifier, vec![body]);
         }
+        Ok(body)
+    }
+
+    fn parse_atom(&mut self) -> Result<HashNode<PeanoExpression>, ParseError> {
+        // A parenthesis opens either a formula or the first term of a relation.
+        if self.tokens.peek() == Some(&Token::LParen) {
+            let (start, bound, free) = (self.tokens.clone(), self.bound.len(), self.free.clone());
+            // Errors in this attempt only mean the parenthesis opens a term.
+            let recovering = std::mem::replace(&mut self.recovering, false);
+            let formula = self.parse_parenthesized(Self::parse_infix);
+            self.recovering = recovering;
+            if let Ok(formula) = formula {
+                return Ok(formula);
+            }
+            self.tokens = start;
+            self.bound.truncate(bound);
+            self.free = free;
+        }
+
+        if let Some(Token::Even | Token::Odd) = self.tokens.peek() {
+            let token = self.tokens.advance();
+            let term = self.parse_parenthesized(Self::parse_term)?;
+            let content = if token == Some(Token::Even) {
+                PeanoContent::Even(term)
+            } else {
+                PeanoContent::Odd(term)
+            };
+            return Ok(self.relation(content));
+        }
+
+        let left = self.parse_term()?;
+        let relation = match self.tokens.peek() {
+            Some(Token::Eq) => PeanoContent::Equals,
+            Some(Token::Lt) => PeanoContent::LessThan,
+            Some(Token::Le) => PeanoContent::LessEq,
+            Some(Token::Divides) => PeanoContent::Divides,
+            _ => return Err(self.tokens.unexpected(&["`=`", "`<`", "`<=`", "`|`"])),
+        };
+        self.tokens.advance();
+        let content = relation(left, self.parse_term()?);
+        Ok(self.relation(content))
+    }
+
+    /// Parse an arithmetic term in infix syntax.
+    pub fn parse_term(&mut self) -> Result<HashNode<ArithmeticExpression>, ParseError> {
+        parse_binary(self, OPERATIONS, Self::parse_primary_term, |parser, operation, left, right| {
+            HashNode::from_store(operation(left, right), &parser.stores.expression_store)
+        })
+    }
+
+    fn parse_primary_term(&mut self) -> Result<HashNode<ArithmeticExpression>, ParseError> {
+        let token = self.tokens.advance().ok_or_else(|| self.tokens.unexpected(&["a term"]))?;
+        match token {
+            Token::LParen => {
+                let inner = self.parse_term()?;
+                self.tokens.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Token::Successor => {
+                let inner = self.parse_parenthesized(Self::parse_term)?;
+                Ok(self.numeral(ArithmeticExpression::Successor(inner)))
+            }
+            Token::Predecessor => {
+                let inner = self.parse_parenthesized(Self::parse_term)?;
+                Ok(HashNode::from_store(ArithmeticExpression::Predecessor(inner), &self.stores.expression_store))
+            }
+            Token::Number(n) => Ok(self.numeral(ArithmeticExpression::Number(n))),
+            Token::DeBruijn(n) => Ok(HashNode::from_store(ArithmeticExpression::DeBruijn(n), &self.stores.expression_store)),
+            Token::Ident(name) if self.tokens.peek() == Some(&Token::LParen) => self.parse_application(&name),
+            Token::Ident(name) => {
+                let index = self.variable_index(name);
+                Ok(HashNode::from_store(ArithmeticExpression::DeBruijn(index), &self.stores.expression_store))
+            }
+            _ => Err(self.tokens.unexpected_previous(&["a term"])),
+        }
+    }
+
+    /// Parse the arguments of the declared function `name`, e.g. `(x, 2)`.
+    fn parse_application(&mut self, name: &str) -> Result<HashNode<ArithmeticExpression>, ParseError> {
+        let name_span = self.tokens.last();
+        let (opcode, arity) =
+            functions::lookup(name).ok_or_else(|| self.tokens.invalid(name_span, format!("Unknown function '{}'", name)))?;
+        self.tokens.expect(Token::LParen)?;
+        let mut args = Vec::new();
+        if self.tokens.peek() != Some(&Token::RParen) {
+            args.push(self.parse_term()?);
+            while self.tokens.peek() == Some(&Token::Comma) {
+                self.tokens.advance();
+                args.push(self.parse_term()?);
+            }
+        }
+        self.tokens.expect(Token::RParen)?;
+        if args.len() != arity {
+            let span = Span { start: name_span.start, end: self.tokens.last().end };
+            return Err(self.tokens.invalid(span, format!("Function '{}' takes {} arguments, found {}", name, arity, args.len())));
+        }
+        Ok(HashNode::from_store(ArithmeticExpression::Function(opcode, args), &self.stores.expression_store))
+    }
+
+    /// Parse a function definition `def name(x, y) := body` and declare
+    /// `name`.
+    ///
+    /// The body is an infix term over the parameters, which become `/0`,
+    /// `/1`, … in order. It may apply functions defined before, but not the
+    /// one being defined.
+    pub fn parse_definition(&mut self) -> Result<FunctionDefinition, ParseError> {
+        self.tokens.expect(Token::Def)?;
+        let name = match self.tokens.peek() {
+            Some(Token::Ident(name)) => name.clone(),
+            _ => return Err(self.tokens.unexpected(&["a function name"])),
+        };
+        self.tokens.advance();
+        let name_span = self.tokens.last();
+        self.tokens.expect(Token::LParen)?;
+        let mut parameters = Vec::new();
+        while let Some(Token::Ident(parameter)) = self.tokens.peek().cloned() {
+            self.tokens.advance();
+            if parameters.contains(&parameter) {
+                return Err(self.tokens.invalid(self.tokens.last(), format!("Parameter {} of {} appears twice", parameter, name)));
+            }
+            parameters.push(parameter);
+            if self.tokens.peek() != Some(&Token::Comma) {
+                break;
+            }
+            self.tokens.advance();
+        }
+        self.tokens.expect(Token::RParen)?;
+        self.tokens.expect(Token::Define)?;
+
+        let opcode = functions::opcode(&name);
+        let arity = parameters.len();
+        self.free = parameters;
+        let body_start = self.tokens.next_offset();
+        let body = self.parse_term()?;
+        self.tokens.expect_end()?;
+        let body_span = Span { start: body_start, end: self.tokens.last().end };
+        if self.free.len() > arity {
+            let message = format!("The body of {} mentions {}, which is not a parameter", name, self.free[arity]);
+            return Err(self.tokens.invalid(body_span, message));
+        }
+        if mentions(&body, opcode) {
+            return Err(self.tokens.invalid(body_span, format!("{} cannot be defined in terms of itself", name)));
+        }
+
+        functions::declare(&name, arity).map_err(|message| self.tokens.invalid(name_span, message))?;
+        Ok(FunctionDefinition { name, opcode, arity, body })
+    }
+
+    fn variable_index(&mut self, name: String) -> u32 {
+        if let Some(position) = self.bound.iter().rposition(|bound| *bound == name) {
+            return (self.bound.len() - 1 - position) as u32;
+        }
+        let free = match self.free.iter().position(|free| *free == name) {
+            Some(position) => position,
+            None => {
+                self.free.push(name);
+                self.free.len() - 1
+            }
+        };
+        (self.bound.len() + free) as u32
+    }
+
+    fn compound(
+        &self,
+        operator: ClassicalOperator,
+        operands: Vec<HashNode<PeanoExpression>>,
+    ) -> HashNode<PeanoExpression> {
+        let operands = operands.iter().map(|operand| operand.value.as_logical(&self.stores.logical_store)).collect();
+        let logical_node = HashNode::from_store(LogicalExpression::compound(operator, operands), &self.stores.logical_store);
+        HashNode::from_store(PeanoExpression::logical(logical_node), &self.stores.peano_store)
+    }
+
+    fn relation(&self, content: PeanoContent) -> HashNode<PeanoExpression> {
+        let content_node = HashNode::from_store(content, &self.stores.content_store);
+        HashNode::from_store(PeanoExpression::domain(content_node), &self.stores.peano_store)
     }
 
     pub fn store_stats(&self) -> (usize, usize, usize) {
         (
-            self.peano_store.len(),
-            self.expression_store.len(),
-            self.logical_store.len(),
+            self.stores.peano_store.len(),
+            self.stores.expression_store.len(),
+            self.stores.logical_store.len(),
         )
     }
 }
 
+/// Whether `term` applies the function symbol `opcode`.
+fn mentions(term: &HashNode<ArithmeticExpression>, opcode: u64) -> bool {
+    match term.value.decompose() {
+        Some((own, args)) => own == opcode || args.iter().any(|arg| mentions(arg, opcode)),
+        None => false,
+    }
+}
+
 // ============================================================================
 // Axiom Parsing Support
 // ============================================================================
 
-/// Storage instances for axiom parsing.
-///
-/// This struct holds the various NodeStorage instances needed during
-/// axiom parsing, allowing external management of storage lifetime.
-pub struct AxiomStores {
-    pub peano_store: NodeStorage<PeanoExpression>,
-    pub expression_store: NodeStorage<ArithmeticExpression>,
-    pub content_store: NodeStorage<PeanoContent>,
-    pub logical_store: NodeStorage<LogicalExpression<BinaryTruth, PeanoContent, ClassicalOperator>>,
-}
-
-impl AxiomStores {
-    pub fn new() -> Self {
-        Self {
-            peano_store: NodeStorage::new(),
-            expression_store: NodeStorage::new(),
-            content_store: NodeStorage::new(),
-            logical_store: NodeStorage::new(),
-        }
-    }
-}
-
 /// Parse an axiom from a string with explicit quantifiers.
 ///
 /// # Syntax
@@ -396,7 +806,7 @@ impl AxiomStores {
 pub fn parse_axiom(
     input: &str,
     name: &str,
-    _stores: &AxiomStores,
+    stores: &PeanoStores,
 ) -> Result<
     corpus_core::base::axioms::NamedAxiom<
         BinaryTruth,
@@ -409,12 +819,12 @@ pub fn parse_axiom(
     use corpus_core::expression::DomainExpression;
 
     // Parse the input using the existing parser infrastructure
-    let mut parser = Parser::new(input);
+    let mut parser = Parser::with_stores(input, stores);
 
-    // Try to parse as a proposition (logical expression)
-    let peano_expr = parser.parse_proposition().map_err(|e| AxiomError::ParseError {
-        message: e,
-        position: None,
+    // Parse a formula, infix or prefix
+    let peano_expr = parser.parse_formula().map_err(|e| AxiomError::ParseError {
+        position: Some(e.span.start),
+        message: e.to_string(),
     })?;
 
     // Extract the LogicalExpression from the PeanoExpression (DomainExpression)
@@ -424,7 +834,7 @@ pub fn parse_axiom(
         DomainExpression::Domain(domain_node) => {
             // Convert domain expression to logical expression
             // For axioms, we expect domain content to be equality statements
-            convert_domain_to_logical(domain_node, &parser.logical_store, &parser.content_store)?
+            convert_domain_to_logical(domain_node, &parser.stores.logical_store, &parser.stores.content_store)?
         }
     };
 
@@ -441,8 +851,8 @@ pub fn parse_axiom(
 /// Domain-level equality (PeanoContent::Equals) is converted to logical-level
 /// equality (LogicalExpression::Compound with ClassicalOperator::Equals).
 ///
-/// Domain-level arithmetic expressions are wrapped in PeanoContent::Arithmetic
-/// and then LogicalExpression::Atomic.
+/// Domain-level arithmetic expressions and comparisons are wrapped directly
+/// in LogicalExpression::Atomic.
 fn convert_domain_to_logical(
     domain_node: &HashNode<PeanoContent>,
     logical_store: &NodeStorage<LogicalExpression<BinaryTruth, PeanoContent, ClassicalOperator>>,
@@ -471,8 +881,13 @@ fn convert_domain_to_logical(
 
             Ok(HashNode::from_store(equals_expr, logical_store))
         }
-        PeanoContent::Arithmetic(_) => {
-            // Wrap arithmetic expression in Atomic logical expression
+        PeanoContent::Arithmetic(_)
+        | PeanoContent::LessThan(..)
+        | PeanoContent::LessEq(..)
+        | PeanoContent::Divides(..)
+        | PeanoContent::Even(_)
+        | PeanoContent::Odd(_) => {
+            // Wrap the content in an Atomic logical expression
             let atomic = LogicalExpression::atomic(HashNode::from_store(
                 domain_node.value.as_ref().clone(),
                 content_store,
@@ -481,3 +896,57 @@ fn convert_domain_to_logical(
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> HashNode<PeanoExpression> {
+        Parser::new(text).parse_formula().unwrap()
+    }
+
+    #[test]
+    fn test_infix_matches_prefix() {
+        assert_eq!(parse("S(0) + S(0) = S(S(0))"), parse("EQ (PLUS (S (0)) (S (0))) (S (S (0)))"));
+        assert_eq!(parse("forall x. x + 0 = x"), parse("FORALL (EQ (PLUS (/0) (0)) (/0))"));
+        assert_eq!(
+            parse("∀x. ∃y. x < y ∧ ¬(y = 0)"),
+            parse("FORALL (EXISTS (AND (LT (/1) (/0)) (NOT (EQ (/0) (0)))))")
+        );
+        assert_eq!(parse("x + y * 2 ^ 3 = y + x"), parse("EQ (PLUS (/0) (TIMES (/1) (POW (2) (3)))) (PLUS (/1) (/0))"));
+        assert_eq!(parse("(x + 1) - 1 <= x"), parse("LE (MONUS (PLUS (/0) (1)) (1)) (/0)"));
+        assert_eq!(
+            parse("Even(x) -> Odd(S(x)) ∨ x | 4"),
+            parse("-> (EVEN (/0)) (OR (ODD (S (/0))) (DIVIDES (/0) (4)))")
+        );
+        assert_eq!(parse("forall x, y. y = z"), parse("FORALL (FORALL (EQ (/0) (/2)))"));
+    }
+
+    #[test]
+    fn test_infix_errors() {
+        assert!(Parser::new("x + = 1").parse_formula().is_err());
+        assert!(Parser::new("x = 1 y").parse_formula().is_err());
+        assert!(Parser::new("forall . x = x").parse_formula().is_err());
+        assert!(Parser::new("EQ (x) (1)").parse_formula().is_err());
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let error = Parser::new("forall x.\n  x + = 1").parse_formula().unwrap_err();
+        assert_eq!((error.line, error.column, error.span), (2, 7, Span { start: 16, end: 17 }));
+        assert_eq!(error.to_string(), "2:7: expected a term, found `=`");
+
+        let error = Parser::new("f(x) = 1").parse_formula().unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::Invalid("Unknown function 'f'".to_string()));
+        assert_eq!(Parser::new("x = ").parse_formula().unwrap_err().to_string(), "1:5: expected a term, found end of input");
+    }
+
+    #[test]
+    fn test_recovery_reports_every_atom() {
+        let errors = Parser::new("(x + = 1) && y < && z = z -> w ^ 2").parse_formula_recovering().unwrap_err();
+        let positions: Vec<_> = errors.iter().map(|error| error.column).collect();
+        assert_eq!(positions, [6, 18, 35]);
+        assert!(Parser::new("x + 1 = S(x)").parse_formula_recovering().is_ok());
+    }
+}
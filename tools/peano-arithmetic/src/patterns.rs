@@ -1,7 +1,9 @@
+use corpus_classical_logic::FirstOrderDomain;
 use corpus_core::nodes::{HashNode, NodeStorage, Hashing};
 use corpus_core::rewriting::{Pattern, Substitution};
 
-use crate::syntax::ArithmeticExpression;
+use crate::domain::PeanoDomain;
+use crate::syntax::{ArithmeticExpression, PeanoContent};
 
 pub fn apply_substitution(
     pattern: &Pattern<ArithmeticExpression>,
@@ -33,8 +35,20 @@ pub fn apply_substitution(
                     let term = ArithmeticExpression::Successor(applied_args[0].clone());
                     HashNode::from_store(term, store)
                 }
-                o if o == Hashing::opcode("equals") && applied_args.len() == 2 => {
-                    let term = ArithmeticExpression::Add(applied_args[0].clone(), applied_args[1].clone());
+                o if o == Hashing::opcode("predecessor") && applied_args.len() == 1 => {
+                    let term = ArithmeticExpression::Predecessor(applied_args[0].clone());
+                    HashNode::from_store(term, store)
+                }
+                o if o == Hashing::opcode("monus") && applied_args.len() == 2 => {
+                    let term = ArithmeticExpression::Monus(applied_args[0].clone(), applied_args[1].clone());
+                    HashNode::from_store(term, store)
+                }
+                o if o == Hashing::opcode("multiply") && applied_args.len() == 2 => {
+                    let term = ArithmeticExpression::Multiply(applied_args[0].clone(), applied_args[1].clone());
+                    HashNode::from_store(term, store)
+                }
+                o if o == Hashing::opcode("power") && applied_args.len() == 2 => {
+                    let term = ArithmeticExpression::Power(applied_args[0].clone(), applied_args[1].clone());
                     HashNode::from_store(term, store)
                 }
                 _ => panic!("Unexpected opcode: {}", opcode),
@@ -42,3 +56,54 @@ pub fn apply_substitution(
         }
     }
 }
+
+/// Instantiate an atom pattern such as `equals(x, y)`, whose arguments are
+/// term patterns, building the atom through `FirstOrderDomain::atom`.
+///
+/// Equality is a predicate rather than a term, so `apply_substitution` cannot
+/// produce it. Returns `None` for an unknown predicate or wrong arity.
+pub fn apply_atom_substitution(
+    pattern: &Pattern<ArithmeticExpression>,
+    subst: &Substitution<ArithmeticExpression>,
+    domain: &PeanoDomain,
+) -> Option<HashNode<PeanoContent>> {
+    let Pattern::Compound { opcode, args } = pattern else {
+        return None;
+    };
+    let arguments = args
+        .iter()
+        .map(|arg| apply_substitution(arg, subst, domain.expression_store))
+        .collect();
+    domain.atom(*opcode, arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equality_pattern_builds_equals_atom() {
+        let content_store = NodeStorage::new();
+        let expression_store = NodeStorage::new();
+        let domain = PeanoDomain::new(&content_store, &expression_store);
+
+        let pattern = Pattern::compound(
+            Hashing::opcode("equals"),
+            vec![
+                Pattern::compound(Hashing::opcode("add"), vec![Pattern::var(0), Pattern::constant(ArithmeticExpression::Number(0))]),
+                Pattern::var(0),
+            ],
+        );
+        let mut subst = Substitution::new();
+        let two = HashNode::from_store(ArithmeticExpression::Number(2), &expression_store);
+        subst.bind(0, two.clone());
+
+        let atom = apply_atom_substitution(&pattern, &subst, &domain).unwrap();
+        let zero = HashNode::from_store(ArithmeticExpression::Number(0), &expression_store);
+        let sum = HashNode::from_store(ArithmeticExpression::Add(two.clone(), zero), &expression_store);
+        assert_eq!(atom.value.as_ref(), &PeanoContent::Equals(sum, two));
+
+        let wrong_arity = Pattern::compound(Hashing::opcode("equals"), vec![Pattern::var(0)]);
+        assert!(apply_atom_substitution(&wrong_arity, &subst, &domain).is_none());
+    }
+}
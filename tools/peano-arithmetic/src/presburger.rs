@@ -0,0 +1,481 @@
+//! Decision procedure for Presburger arithmetic.
+//!
+//! Formulas over `=`, `<`, `≤`, parity and divisibility by a numeral, whose terms use
+//! only numerals, variables, successor, addition and multiplication by a
+//! numeral, are decided by Cooper's quantifier elimination. Quantifiers range
+//! over ℕ, so every eliminated variable carries the constraint `x ≥ 0`, and
+//! free variables are read universally. Ground subterms are evaluated, so
+//! e.g. `x * (2 ^ 3)` is still linear, and bounded quantifiers over ground
+//! bounds are expanded first, so their bodies may be nonlinear.
+
+use std::collections::BTreeMap;
+
+use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
+use corpus_core::expression::{DomainExpression, LogicalExpression};
+use corpus_core::nodes::HashNode;
+use corpus_core::proving::GoalChecker;
+
+use crate::bounded::expand_bounded;
+use crate::eval::{eval_with, variables};
+use crate::syntax::{ArithmeticExpression, PeanoContent, PeanoExpression};
+
+type PeanoLogicalExpression = LogicalExpression<BinaryTruth, PeanoContent, ClassicalOperator>;
+
+/// Whether `expr` holds for every value of its free variables.
+///
+/// Returns `None` if `expr` lies outside the linear fragment, e.g. when it
+/// multiplies two variables.
+pub fn decide(expr: &PeanoExpression) -> Option<bool> {
+    let mut translator = Translator::default();
+    let formula = match &expand_bounded(expr) {
+        DomainExpression::Domain(content) => translator.atom(content)?,
+        DomainExpression::Logical(logical) => translator.formula(logical)?,
+    };
+    let closed = translator.free.into_values().fold(formula, |formula, id| forall(id, formula));
+    match closed {
+        Formula::True => Some(true),
+        Formula::False => Some(false),
+        _ => None,
+    }
+}
+
+/// Goal checker deciding atoms of the linear fragment outright.
+///
+/// Returns `Some(True)` if the atom holds for all values of its variables and
+/// `Some(False)` if some instance fails; atoms outside the fragment are left
+/// to the search.
+pub struct PresburgerChecker;
+
+impl GoalChecker<PeanoContent, BinaryTruth> for PresburgerChecker {
+    fn check(&self, expr: &HashNode<PeanoContent>) -> Option<BinaryTruth> {
+        decide(&PeanoExpression::domain(expr.clone())).map(BinaryTruth::from)
+    }
+}
+
+/// A linear term `c₁·x₁ + … + cₙ·xₙ + k` over integer variables.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct Linear {
+    coefficients: BTreeMap<u32, i64>,
+    constant: i64,
+}
+
+impl Linear {
+    fn constant(constant: i64) -> Self {
+        Self { coefficients: BTreeMap::new(), constant }
+    }
+
+    fn variable(id: u32) -> Self {
+        Self { coefficients: BTreeMap::from([(id, 1)]), constant: 0 }
+    }
+
+    fn coefficient(&self, id: u32) -> i64 {
+        self.coefficients.get(&id).copied().unwrap_or(0)
+    }
+
+    fn add(&self, other: &Linear) -> Linear {
+        let mut sum = self.clone();
+        sum.constant += other.constant;
+        for (&id, &c) in &other.coefficients {
+            let entry = sum.coefficients.entry(id).or_insert(0);
+            *entry += c;
+            if *entry == 0 {
+                sum.coefficients.remove(&id);
+            }
+        }
+        sum
+    }
+
+    fn scale(&self, factor: i64) -> Linear {
+        if factor == 0 {
+            return Linear::constant(0);
+        }
+        Linear {
+            coefficients: self.coefficients.iter().map(|(&id, &c)| (id, c * factor)).collect(),
+            constant: self.constant * factor,
+        }
+    }
+
+    fn offset(&self, amount: i64) -> Linear {
+        self.add(&Linear::constant(amount))
+    }
+
+    /// `self` with `x` set to `0`.
+    fn without(&self, id: u32) -> Linear {
+        let mut rest = self.clone();
+        rest.coefficients.remove(&id);
+        rest
+    }
+
+    /// `self` with `x` replaced by `value`.
+    fn substitute(&self, id: u32, value: &Linear) -> Linear {
+        self.without(id).add(&value.scale(self.coefficient(id)))
+    }
+
+    /// `self` with the coefficient of `x` set to `coefficient`.
+    fn with_coefficient(&self, id: u32, coefficient: i64) -> Linear {
+        self.without(id).add(&Linear::variable(id).scale(coefficient))
+    }
+
+    fn ground(&self) -> Option<i64> {
+        self.coefficients.is_empty().then_some(self.constant)
+    }
+}
+
+/// A quantifier-free formula in negation normal form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Formula {
+    True,
+    False,
+    /// `0 < t`.
+    Positive(Linear),
+    /// `d ∣ t`, with `d > 0`.
+    Divisible(i64, Linear),
+    /// `¬(d ∣ t)`, with `d > 0`.
+    NotDivisible(i64, Linear),
+    And(Vec<Formula>),
+    Or(Vec<Formula>),
+}
+
+impl Formula {
+    fn positive(term: Linear) -> Formula {
+        match term.ground() {
+            Some(k) => Formula::from(k > 0),
+            None => Formula::Positive(term),
+        }
+    }
+
+    fn divisible(divisor: i64, term: Linear, holds: bool) -> Formula {
+        match term.ground() {
+            Some(k) => Formula::from((k.rem_euclid(divisor) == 0) == holds),
+            None if divisor == 1 => Formula::from(holds),
+            None if holds => Formula::Divisible(divisor, term),
+            None => Formula::NotDivisible(divisor, term),
+        }
+    }
+
+    fn negate(self) -> Formula {
+        match self {
+            Formula::True => Formula::False,
+            Formula::False => Formula::True,
+            // ¬(0 < t) is t ≤ 0, i.e. 0 < 1 - t.
+            Formula::Positive(term) => Formula::Positive(term.scale(-1).offset(1)),
+            Formula::Divisible(d, term) => Formula::NotDivisible(d, term),
+            Formula::NotDivisible(d, term) => Formula::Divisible(d, term),
+            Formula::And(parts) => or(parts.into_iter().map(Formula::negate).collect()),
+            Formula::Or(parts) => and(parts.into_iter().map(Formula::negate).collect()),
+        }
+    }
+
+    /// Rebuild the formula with each atom replaced by `map(atom)`.
+    fn map_atoms(&self, map: &dyn Fn(&Formula) -> Formula) -> Formula {
+        match self {
+            Formula::And(parts) => and(parts.iter().map(|part| part.map_atoms(map)).collect()),
+            Formula::Or(parts) => or(parts.iter().map(|part| part.map_atoms(map)).collect()),
+            atom => map(atom),
+        }
+    }
+
+    fn substitute(&self, id: u32, value: &Linear) -> Formula {
+        self.map_atoms(&|atom| match atom {
+            Formula::Positive(term) => Formula::positive(term.substitute(id, value)),
+            Formula::Divisible(d, term) => Formula::divisible(*d, term.substitute(id, value), true),
+            Formula::NotDivisible(d, term) => Formula::divisible(*d, term.substitute(id, value), false),
+            other => other.clone(),
+        })
+    }
+
+    fn atoms(&self) -> Vec<&Formula> {
+        match self {
+            Formula::And(parts) | Formula::Or(parts) => parts.iter().flat_map(Formula::atoms).collect(),
+            atom => vec![atom],
+        }
+    }
+}
+
+impl From<bool> for Formula {
+    fn from(value: bool) -> Self {
+        if value { Formula::True } else { Formula::False }
+    }
+}
+
+fn and(parts: Vec<Formula>) -> Formula {
+    let mut result = Vec::new();
+    for part in parts {
+        match part {
+            Formula::True => {}
+            Formula::False => return Formula::False,
+            Formula::And(inner) => result.extend(inner),
+            other if !result.contains(&other) => result.push(other),
+            _ => {}
+        }
+    }
+    match result.len() {
+        0 => Formula::True,
+        1 => result.remove(0),
+        _ => Formula::And(result),
+    }
+}
+
+fn or(parts: Vec<Formula>) -> Formula {
+    let mut result = Vec::new();
+    for part in parts {
+        match part {
+            Formula::False => {}
+            Formula::True => return Formula::True,
+            Formula::Or(inner) => result.extend(inner),
+            other if !result.contains(&other) => result.push(other),
+            _ => {}
+        }
+    }
+    match result.len() {
+        0 => Formula::False,
+        1 => result.remove(0),
+        _ => Formula::Or(result),
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+fn lcm(a: i64, b: i64) -> i64 {
+    a / gcd(a, b) * b
+}
+
+/// `∀x ≥ 0. body`, as `¬∃x ≥ 0. ¬body`.
+fn forall(id: u32, body: Formula) -> Formula {
+    exists(id, body.negate()).negate()
+}
+
+/// `∃x ≥ 0. body` with `x` eliminated by Cooper's method.
+fn exists(id: u32, body: Formula) -> Formula {
+    let body = and(vec![Formula::Positive(Linear::variable(id).offset(1)), body]);
+
+    // Scale every atom so that x has the same coefficient `scale`, then
+    // substitute x for `scale·x`, remembering that x is a multiple of `scale`.
+    let scale = body
+        .atoms()
+        .iter()
+        .filter_map(|atom| match atom {
+            Formula::Positive(term) | Formula::Divisible(_, term) | Formula::NotDivisible(_, term) => Some(term.coefficient(id)),
+            _ => None,
+        })
+        .filter(|&c| c != 0)
+        .fold(1, |acc, c| lcm(acc, c.abs()));
+    let unit = body.map_atoms(&|atom| {
+        let normalize = |term: &Linear| {
+            let c = term.coefficient(id);
+            let m = if c == 0 { 1 } else { scale / c.abs() };
+            (m, term.scale(m).with_coefficient(id, c.signum()))
+        };
+        match atom {
+            Formula::Positive(term) => Formula::positive(normalize(term).1),
+            Formula::Divisible(d, term) => {
+                let (m, term) = normalize(term);
+                Formula::divisible(d * m, term, true)
+            }
+            Formula::NotDivisible(d, term) => {
+                let (m, term) = normalize(term);
+                Formula::divisible(d * m, term, false)
+            }
+            other => other.clone(),
+        }
+    });
+    let unit = and(vec![unit, Formula::divisible(scale, Linear::variable(id), true)]);
+
+    let atoms = unit.atoms();
+    let period = atoms
+        .iter()
+        .filter_map(|atom| match atom {
+            Formula::Divisible(d, term) | Formula::NotDivisible(d, term) if term.coefficient(id) != 0 => Some(*d),
+            _ => None,
+        })
+        .fold(1, lcm);
+    // Each lower bound `0 < x + a` says `x > -a`.
+    let lower_bounds: Vec<Linear> = atoms
+        .iter()
+        .filter_map(|atom| match atom {
+            Formula::Positive(term) if term.coefficient(id) == 1 => Some(term.without(id).scale(-1)),
+            _ => None,
+        })
+        .collect();
+
+    // With x ≥ 0 among the conjuncts, x has a lower bound, so the x → -∞
+    // projection is unsatisfiable and only the bounded witnesses remain.
+    let witnesses = lower_bounds
+        .iter()
+        .flat_map(|bound| (1..=period).map(move |j| bound.offset(j)))
+        .map(|witness| unit.substitute(id, &witness))
+        .collect();
+    or(witnesses)
+}
+
+/// Translation of PA formulas into linear constraints over fresh variable ids.
+#[derive(Default)]
+struct Translator {
+    /// Ids of the enclosing binders, innermost last.
+    scope: Vec<u32>,
+    /// Ids of free variables by their index above the top level.
+    free: BTreeMap<u32, u32>,
+    next: u32,
+}
+
+impl Translator {
+    fn fresh(&mut self) -> u32 {
+        self.next += 1;
+        self.next
+    }
+
+    fn formula(&mut self, expr: &HashNode<PeanoLogicalExpression>) -> Option<Formula> {
+        let (operator, operands) = match expr.value.as_ref() {
+            LogicalExpression::Atomic(content) => return self.atom(content),
+            LogicalExpression::Compound { operator, operands, .. } => (operator, operands.as_slice()),
+        };
+        match (operator, operands) {
+            (ClassicalOperator::Forall | ClassicalOperator::Exists, [body]) => {
+                let id = self.fresh();
+                self.scope.push(id);
+                let body = self.formula(body);
+                self.scope.pop();
+                match operator {
+                    ClassicalOperator::Forall => Some(forall(id, body?)),
+                    _ => Some(exists(id, body?)),
+                }
+            }
+            (ClassicalOperator::Not, [operand]) => Some(self.formula(operand)?.negate()),
+            (ClassicalOperator::And, parts) => Some(and(parts.iter().map(|p| self.formula(p)).collect::<Option<_>>()?)),
+            (ClassicalOperator::Or, parts) => Some(or(parts.iter().map(|p| self.formula(p)).collect::<Option<_>>()?)),
+            (ClassicalOperator::Implies, [left, right]) => {
+                Some(or(vec![self.formula(left)?.negate(), self.formula(right)?]))
+            }
+            (ClassicalOperator::Equals, [left, right]) if self.arithmetic(left).is_some() => {
+                let (left, right) = (self.arithmetic(left)?, self.arithmetic(right)?);
+                self.equation(&left, &right)
+            }
+            (ClassicalOperator::Iff | ClassicalOperator::Equals, [left, right]) => {
+                let (left, right) = (self.formula(left)?, self.formula(right)?);
+                Some(or(vec![
+                    and(vec![left.clone(), right.clone()]),
+                    and(vec![left.negate(), right.negate()]),
+                ]))
+            }
+            _ => None,
+        }
+    }
+
+    /// The term of a wrapped arithmetic atom, as produced for logical equality.
+    fn arithmetic(&self, expr: &HashNode<PeanoLogicalExpression>) -> Option<HashNode<ArithmeticExpression>> {
+        match expr.value.as_ref() {
+            LogicalExpression::Atomic(content) => match content.value.as_ref() {
+                PeanoContent::Arithmetic(term) => Some(term.clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn equation(&mut self, left: &HashNode<ArithmeticExpression>, right: &HashNode<ArithmeticExpression>) -> Option<Formula> {
+        let difference = self.term(right)?.add(&self.term(left)?.scale(-1));
+        Some(and(vec![
+            Formula::positive(difference.offset(1)),
+            Formula::positive(difference.scale(-1).offset(1)),
+        ]))
+    }
+
+    fn atom(&mut self, content: &HashNode<PeanoContent>) -> Option<Formula> {
+        match content.value.as_ref() {
+            PeanoContent::Equals(left, right) => self.equation(left, right),
+            PeanoContent::LessThan(left, right) => {
+                Some(Formula::positive(self.term(right)?.add(&self.term(left)?.scale(-1))))
+            }
+            PeanoContent::LessEq(left, right) => {
+                Some(Formula::positive(self.term(right)?.add(&self.term(left)?.scale(-1)).offset(1)))
+            }
+            PeanoContent::Divides(divisor, multiple) => {
+                let divisor = self.term(divisor)?.ground()?;
+                let multiple = self.term(multiple)?;
+                if divisor == 0 {
+                    Some(and(vec![
+                        Formula::positive(multiple.offset(1)),
+                        Formula::positive(multiple.scale(-1).offset(1)),
+                    ]))
+                } else {
+                    Some(Formula::divisible(divisor, multiple, true))
+                }
+            }
+            PeanoContent::Even(term) => Some(Formula::divisible(2, self.term(term)?, true)),
+            PeanoContent::Odd(term) => Some(Formula::divisible(2, self.term(term)?, false)),
+            _ => None,
+        }
+    }
+
+    fn term(&mut self, term: &HashNode<ArithmeticExpression>) -> Option<Linear> {
+        if variables(term).is_empty() {
+            return Some(Linear::constant(i64::try_from(eval_with(term, &[])?).ok()?));
+        }
+        match term.value.as_ref() {
+            ArithmeticExpression::DeBruijn(index) => Some(Linear::variable(self.variable(*index))),
+            ArithmeticExpression::Successor(inner) => Some(self.term(inner)?.offset(1)),
+            ArithmeticExpression::Add(left, right) => Some(self.term(left)?.add(&self.term(right)?)),
+            ArithmeticExpression::Multiply(left, right) => {
+                let (left, right) = (self.term(left)?, self.term(right)?);
+                match (left.ground(), right.ground()) {
+                    (Some(k), _) => Some(right.scale(k)),
+                    (_, Some(k)) => Some(left.scale(k)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn variable(&mut self, index: u32) -> u32 {
+        let depth = self.scope.len() as u32;
+        if index < depth {
+            return self.scope[(depth - 1 - index) as usize];
+        }
+        if let Some(&id) = self.free.get(&(index - depth)) {
+            return id;
+        }
+        let id = self.fresh();
+        self.free.insert(index - depth, id);
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    fn decide_text(text: &str) -> Option<bool> {
+        let proposition = Parser::new(text).parse_proposition().unwrap();
+        decide(&proposition.value)
+    }
+
+    #[test]
+    fn test_quantifier_alternation() {
+        // ∀x ∃y. x < y
+        assert_eq!(decide_text("FORALL (EXISTS (LT (/1) (/0)))"), Some(true));
+        // ∃y ∀x. x < y
+        assert_eq!(decide_text("EXISTS (FORALL (LT (/0) (/1)))"), Some(false));
+        // ∀x ∃y. x = y + y ∨ x = S(y + y)
+        assert_eq!(
+            decide_text("FORALL (EXISTS (OR (EQ (/1) (PLUS (/0) (/0))) (EQ (/1) (S (PLUS (/0) (/0))))))"),
+            Some(true)
+        );
+        // ∀x. 2 ∣ x + x, and ∃x. x + x = 3 fails
+        assert_eq!(decide_text("FORALL (DIVIDES (2) (PLUS (/0) (/0)))"), Some(true));
+        assert_eq!(decide_text("EXISTS (EQ (PLUS (/0) (/0)) (3))"), Some(false));
+    }
+
+    #[test]
+    fn test_free_variables_and_fragment() {
+        // Free variables are universal: x ≤ x + y holds, x < x + y does not.
+        assert_eq!(decide_text("LE (/0) (PLUS (/0) (/1))"), Some(true));
+        assert_eq!(decide_text("LT (/0) (PLUS (/0) (/1))"), Some(false));
+        // Multiplication by a ground term is linear; by a variable it is not.
+        assert_eq!(decide_text("EQ (* (/0) (PLUS (1) (1))) (PLUS (/0) (/0))"), Some(true));
+        assert_eq!(decide_text("EQ (* (/0) (/0)) (/0)"), None);
+    }
+}
@@ -0,0 +1,516 @@
+//! Readable rendering of PA terms and formulas.
+//!
+//! `Display` on the syntax types brackets every operation and keeps numerals
+//! as written, so `S(S(0)) + /0` shows as `(S(S(0)) + /0)`. A `Printer`
+//! instead folds successor towers into numerals, drops the parentheses that
+//! precedence makes redundant, and names bound variables. Its Unicode, ASCII
+//! and keyword output parses back with `Parser::parse_formula` to the same
+//! formula; its LaTeX output is meant for math mode. As an `ExprPrinter`
+//! it renders atoms and formulas wherever core output takes one.
+
+use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
+use corpus_core::expression::{DomainExpression, LogicalExpression};
+use corpus_core::nodes::HashNode;
+use corpus_core::printing::ExprPrinter;
+
+use crate::eval::variables;
+use crate::functions;
+use crate::syntax::{ArithmeticExpression, NumeralForm, PeanoContent, PeanoExpression};
+
+type PeanoLogicalExpression = LogicalExpression<BinaryTruth, PeanoContent, ClassicalOperator>;
+
+/// Names given to bound variables, outermost binder first.
+const BOUND_NAMES: [&str; 6] = ["x", "y", "z", "u", "v", "w"];
+
+/// Which symbols the printer uses for operators and quantifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Charset {
+    /// `∀x. x ≤ x ∧ ¬(x < 0)`
+    #[default]
+    Unicode,
+    /// `forall x. x <= x && !(x < 0)`
+    Ascii,
+    /// `\forall x.\, x \leq x \land \neg(x < 0)`, for math mode.
+    Latex,
+    /// `FORALL (AND (LE (/0) (/0)) (NOT (LT (/0) (0))))`, the prefix form.
+    Keyword,
+}
+
+/// Configurable printer for PA terms, atoms and formulas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Printer {
+    charset: Charset,
+    numerals: Option<NumeralForm>,
+}
+
+impl Default for Printer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Printer {
+    /// A Unicode printer writing numerals in decimal.
+    pub fn new() -> Self {
+        Self {
+            charset: Charset::Unicode,
+            numerals: Some(NumeralForm::Decimal),
+        }
+    }
+
+    pub fn with_charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Write every numeral in `form`.
+    pub fn with_numerals(mut self, form: NumeralForm) -> Self {
+        self.numerals = Some(form);
+        self
+    }
+
+    /// Write numerals as they appear in the term.
+    pub fn with_numerals_as_written(mut self) -> Self {
+        self.numerals = None;
+        self
+    }
+
+    /// Render a term.
+    pub fn term(&self, term: &HashNode<ArithmeticExpression>) -> String {
+        match self.charset {
+            Charset::Keyword => self.keyword_term(term),
+            _ => self.term_at(term, 0, 0),
+        }
+    }
+
+    /// Render an atom: a relation between terms or a parity predicate.
+    pub fn content(&self, content: &PeanoContent) -> String {
+        match self.charset {
+            Charset::Keyword => self.keyword_content(content),
+            _ => self.content_at(content, 0),
+        }
+    }
+
+    /// Render a formula of either level.
+    pub fn expression(&self, expr: &PeanoExpression) -> String {
+        match (expr, self.charset) {
+            (DomainExpression::Domain(content), _) => self.content(&content.value),
+            (DomainExpression::Logical(logical), Charset::Keyword) => self.keyword_formula(logical),
+            (DomainExpression::Logical(logical), _) => self.formula_at(logical, 0, 0),
+        }
+    }
+
+    fn symbol(&self, unicode: &'static str, ascii: &'static str, latex: &'static str) -> &'static str {
+        match self.charset {
+            Charset::Unicode => unicode,
+            Charset::Ascii | Charset::Keyword => ascii,
+            Charset::Latex => latex,
+        }
+    }
+
+    /// `name (a) (b) …`, the prefix application of `name`.
+    fn keyword_application(name: &str, args: impl IntoIterator<Item = String>) -> String {
+        let args: Vec<_> = args.into_iter().map(|arg| format!("({})", arg)).collect();
+        format!("{} {}", name, args.join(" "))
+    }
+
+    fn keyword_term(&self, term: &HashNode<ArithmeticExpression>) -> String {
+        if let Some(text) = self.numeral(term) {
+            return text;
+        }
+        let (name, args) = match term.value.as_ref() {
+            ArithmeticExpression::Add(left, right) => ("PLUS".to_string(), vec![left, right]),
+            ArithmeticExpression::Monus(left, right) => ("MONUS".to_string(), vec![left, right]),
+            ArithmeticExpression::Multiply(left, right) => ("TIMES".to_string(), vec![left, right]),
+            ArithmeticExpression::Power(left, right) => ("POW".to_string(), vec![left, right]),
+            ArithmeticExpression::Successor(inner) => ("S".to_string(), vec![inner]),
+            ArithmeticExpression::Predecessor(inner) => ("P".to_string(), vec![inner]),
+            ArithmeticExpression::Number(n) => return n.to_string(),
+            ArithmeticExpression::DeBruijn(index) => return format!("/{}", index),
+            ArithmeticExpression::Function(opcode, args) => (functions::name(*opcode), args.iter().collect()),
+        };
+        Self::keyword_application(&name, args.into_iter().map(|arg| self.keyword_term(arg)))
+    }
+
+    fn keyword_content(&self, content: &PeanoContent) -> String {
+        let (name, terms) = match content {
+            PeanoContent::Arithmetic(term) => return self.keyword_term(term),
+            PeanoContent::Equals(left, right) => ("EQ", vec![left, right]),
+            PeanoContent::LessThan(left, right) => ("LT", vec![left, right]),
+            PeanoContent::LessEq(left, right) => ("LE", vec![left, right]),
+            PeanoContent::Divides(left, right) => ("DIVIDES", vec![left, right]),
+            PeanoContent::Even(term) => ("EVEN", vec![term]),
+            PeanoContent::Odd(term) => ("ODD", vec![term]),
+        };
+        Self::keyword_application(name, terms.into_iter().map(|term| self.keyword_term(term)))
+    }
+
+    /// Render `expr` in the prefix form, where bound variables stay De
+    /// Bruijn indices.
+    fn keyword_formula(&self, expr: &HashNode<PeanoLogicalExpression>) -> String {
+        let (operator, operands) = match expr.value.as_ref() {
+            LogicalExpression::Atomic(content) => return self.keyword_content(&content.value),
+            LogicalExpression::Compound { operator, operands, .. } => (*operator, operands),
+        };
+        let name = match operator {
+            ClassicalOperator::And => "AND",
+            ClassicalOperator::Or => "OR",
+            ClassicalOperator::Implies => "IMPLIES",
+            ClassicalOperator::Iff => "IFF",
+            ClassicalOperator::Not => "NOT",
+            ClassicalOperator::Forall => "FORALL",
+            ClassicalOperator::Exists => "EXISTS",
+            _ => return self.formula_at(expr, 0, 0),
+        };
+        Self::keyword_application(name, operands.iter().map(|operand| self.keyword_formula(operand)))
+    }
+
+    fn content_at(&self, content: &PeanoContent, depth: usize) -> String {
+        let relation = match content {
+            PeanoContent::Arithmetic(term) => return self.term_at(term, 0, depth),
+            PeanoContent::Even(term) => {
+                return format!("{}({})", self.symbol("Even", "Even", "\\mathrm{Even}"), self.term_at(term, 0, depth));
+            }
+            PeanoContent::Odd(term) => {
+                return format!("{}({})", self.symbol("Odd", "Odd", "\\mathrm{Odd}"), self.term_at(term, 0, depth));
+            }
+            PeanoContent::Equals(..) => "=",
+            PeanoContent::LessThan(..) => "<",
+            PeanoContent::LessEq(..) => self.symbol("≤", "<=", "\\leq"),
+            PeanoContent::Divides(..) => self.symbol("∣", "|", "\\mid"),
+        };
+        let (left, right) = content.sides().expect("relations have two sides");
+        format!("{} {} {}", self.term_at(left, 0, depth), relation, self.term_at(right, 0, depth))
+    }
+
+    /// Render `term`, parenthesized if it binds looser than `precedence`.
+    ///
+    /// `depth` counts the binders around the term, so that `/i` below `depth`
+    /// is printed by name.
+    fn term_at(&self, term: &HashNode<ArithmeticExpression>, precedence: u8, depth: usize) -> String {
+        if let Some(text) = self.numeral(term) {
+            return text;
+        }
+        let (own, text) = match term.value.as_ref() {
+            ArithmeticExpression::Add(left, right) => {
+                (1, format!("{} + {}", self.term_at(left, 1, depth), self.term_at(right, 2, depth)))
+            }
+            ArithmeticExpression::Monus(left, right) => (
+                1,
+                format!("{} {} {}", self.term_at(left, 1, depth), self.symbol("∸", "-", "\\mathbin{\\dot-}"), self.term_at(right, 2, depth)),
+            ),
+            ArithmeticExpression::Multiply(left, right) => (
+                2,
+                format!("{} {} {}", self.term_at(left, 2, depth), self.symbol("*", "*", "\\cdot"), self.term_at(right, 3, depth)),
+            ),
+            // Braces group the exponent, so it needs no parentheses.
+            ArithmeticExpression::Power(left, right) if self.charset == Charset::Latex => {
+                (3, format!("{}^{{{}}}", self.term_at(left, 4, depth), self.term_at(right, 0, depth)))
+            }
+            ArithmeticExpression::Power(left, right) => {
+                (3, format!("{} ^ {}", self.term_at(left, 4, depth), self.term_at(right, 3, depth)))
+            }
+            ArithmeticExpression::Successor(inner) => (4, format!("S({})", self.term_at(inner, 0, depth))),
+            ArithmeticExpression::Predecessor(inner) => (4, format!("P({})", self.term_at(inner, 0, depth))),
+            ArithmeticExpression::Number(n) => (4, n.to_string()),
+            ArithmeticExpression::DeBruijn(index) => (4, self.variable(*index, depth)),
+            ArithmeticExpression::Function(opcode, args) => {
+                let name = functions::name(*opcode);
+                let name = match self.charset {
+                    Charset::Latex => format!("\\mathrm{{{}}}", name.replace('_', "\\_")),
+                    _ => name,
+                };
+                let args: Vec<_> = args.iter().map(|arg| self.term_at(arg, 0, depth)).collect();
+                (4, format!("{}({})", name, args.join(", ")))
+            }
+        };
+        parenthesize(text, own < precedence)
+    }
+
+    /// The numeral `term` spells, in the configured form.
+    fn numeral(&self, term: &HashNode<ArithmeticExpression>) -> Option<String> {
+        match self.numerals? {
+            NumeralForm::Decimal => match term.value.as_ref() {
+                ArithmeticExpression::Successor(_) => numeral_value(term).map(|n| n.to_string()),
+                _ => None,
+            },
+            NumeralForm::Successor => match term.value.as_ref() {
+                ArithmeticExpression::Number(n) => {
+                    let n = *n as usize;
+                    Some(format!("{}0{}", "S(".repeat(n), ")".repeat(n)))
+                }
+                _ => None,
+            },
+        }
+    }
+
+    fn variable(&self, index: u32, depth: usize) -> String {
+        let index = index as usize;
+        if index >= depth {
+            return match self.charset {
+                Charset::Latex => format!("v_{{{}}}", index),
+                _ => format!("/{}", index),
+            };
+        }
+        bound_name(depth - 1 - index)
+    }
+
+    /// Render `expr`, parenthesized if it binds looser than `precedence`.
+    ///
+    /// From loosest to tightest: quantifiers (0, as their bodies extend to
+    /// the right), `↔` (1), `→` (2), `∨` (3), `∧` (4), `¬` (5), atoms (6).
+    /// Negated relations are parenthesized all the same.
+    fn formula_at(&self, expr: &HashNode<PeanoLogicalExpression>, precedence: u8, depth: usize) -> String {
+        let (operator, operands) = match expr.value.as_ref() {
+            LogicalExpression::Atomic(content) => return self.content_at(&content.value, depth),
+            LogicalExpression::Compound { operator, operands, .. } => (*operator, operands.as_slice()),
+        };
+        let (own, text) = match (operator, operands) {
+            (ClassicalOperator::Forall | ClassicalOperator::Exists, [body]) => {
+                let quantifier = if operator == ClassicalOperator::Forall {
+                    self.symbol("∀", "forall ", "\\forall ")
+                } else {
+                    self.symbol("∃", "exists ", "\\exists ")
+                };
+                let separator = self.symbol(". ", ". ", ".\\, ");
+                let (bound, body) = match bounded(operator, body) {
+                    Some((relation, limit, body)) => {
+                        let relation = match relation {
+                            PeanoContent::LessThan(..) => "<",
+                            _ => self.symbol("≤", "<=", "\\leq"),
+                        };
+                        (format!(" {} {}", relation, self.term_at(limit, 0, depth + 1)), body)
+                    }
+                    None => (String::new(), body),
+                };
+                let body = self.formula_at(body, 0, depth + 1);
+                (0, format!("{}{}{}{}{}", quantifier, bound_name(depth), bound, separator, body))
+            }
+            (ClassicalOperator::Not, [operand]) => {
+                // `¬x = 0` would parse, but reads as `(¬x) = 0`.
+                let relation = match operand.value.as_ref() {
+                    LogicalExpression::Atomic(content) => content.value.sides().is_some(),
+                    LogicalExpression::Compound { operator, .. } => *operator == ClassicalOperator::Equals,
+                };
+                let operand = self.formula_at(operand, 5, depth);
+                (5, format!("{}{}", self.symbol("¬", "!", "\\neg "), parenthesize(operand, relation)))
+            }
+            (ClassicalOperator::Iff, [left, right]) => {
+                (1, self.binary(left, self.symbol("↔", "<->", "\\leftrightarrow"), right, (2, 1), depth))
+            }
+            (ClassicalOperator::Implies, [left, right]) => {
+                (2, self.binary(left, self.symbol("→", "->", "\\rightarrow"), right, (3, 2), depth))
+            }
+            (ClassicalOperator::Or, [left, right]) => (3, self.binary(left, self.symbol("∨", "||", "\\lor"), right, (3, 4), depth)),
+            (ClassicalOperator::And, [left, right]) => (4, self.binary(left, self.symbol("∧", "&&", "\\land"), right, (4, 5), depth)),
+            (ClassicalOperator::Equals, [left, right]) => (6, self.binary(left, "=", right, (7, 7), depth)),
+            _ => {
+                let operands: Vec<String> = operands.iter().map(|operand| self.formula_at(operand, 0, depth)).collect();
+                (6, format!("{}({})", operator, operands.join(", ")))
+            }
+        };
+        parenthesize(text, own < precedence)
+    }
+
+    fn binary(
+        &self,
+        left: &HashNode<PeanoLogicalExpression>,
+        symbol: &str,
+        right: &HashNode<PeanoLogicalExpression>,
+        (left_precedence, right_precedence): (u8, u8),
+        depth: usize,
+    ) -> String {
+        format!(
+            "{} {} {}",
+            self.formula_at(left, left_precedence, depth),
+            symbol,
+            self.formula_at(right, right_precedence, depth)
+        )
+    }
+}
+
+impl ExprPrinter<PeanoContent> for Printer {
+    fn print(&self, content: &PeanoContent) -> String {
+        self.content(content)
+    }
+}
+
+impl ExprPrinter<PeanoExpression> for Printer {
+    fn print(&self, expr: &PeanoExpression) -> String {
+        self.expression(expr)
+    }
+}
+
+/// The guard and the rest of a bounded quantifier's body: `x < n → φ` under
+/// `∀` or `x < n ∧ φ` under `∃`, where `n` does not mention `x`.
+fn bounded(
+    quantifier: ClassicalOperator,
+    body: &HashNode<PeanoLogicalExpression>,
+) -> Option<(&PeanoContent, &HashNode<ArithmeticExpression>, &HashNode<PeanoLogicalExpression>)> {
+    let connective = match quantifier {
+        ClassicalOperator::Forall => ClassicalOperator::Implies,
+        _ => ClassicalOperator::And,
+    };
+    let LogicalExpression::Compound { operator, operands, .. } = body.value.as_ref() else {
+        return None;
+    };
+    let [guard, rest] = operands.as_slice() else {
+        return None;
+    };
+    let LogicalExpression::Atomic(guard) = guard.value.as_ref() else {
+        return None;
+    };
+    let (PeanoContent::LessThan(variable, limit) | PeanoContent::LessEq(variable, limit)) = guard.value.as_ref() else {
+        return None;
+    };
+    let innermost = matches!(variable.value.as_ref(), ArithmeticExpression::DeBruijn(0));
+    (*operator == connective && innermost && !variables(limit).contains(&0)).then_some((guard.value.as_ref(), limit, rest))
+}
+
+/// The value of a tower of successors over a numeral.
+fn numeral_value(term: &HashNode<ArithmeticExpression>) -> Option<u64> {
+    match term.value.as_ref() {
+        ArithmeticExpression::Number(n) => Some(*n),
+        ArithmeticExpression::Successor(inner) => numeral_value(inner)?.checked_add(1),
+        _ => None,
+    }
+}
+
+/// The name of the binder at `level`, counting from the outermost.
+pub(crate) fn bound_name(level: usize) -> String {
+    match BOUND_NAMES.get(level) {
+        Some(name) => name.to_string(),
+        None => format!("x{}", level),
+    }
+}
+
+fn parenthesize(text: String, needed: bool) -> String {
+    if needed {
+        format!("({})", text)
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    fn print(text: &str, printer: Printer) -> String {
+        printer.expression(&Parser::new(text).parse_formula().unwrap().value)
+    }
+
+    #[test]
+    fn test_minimal_parentheses_and_numerals() {
+        let printer = Printer::new();
+        assert_eq!(print("EQ (PLUS (S (S (0))) (TIMES (/0) (PLUS (/1) (1)))) (S (/0))", printer), "2 + /0 * (/1 + 1) = S(/0)");
+        assert_eq!(print("(x - y) - z = x - (y - z)", printer), "/0 ∸ /1 ∸ /2 = /0 ∸ (/1 ∸ /2)");
+        assert_eq!(print("2 ^ 3 ^ x = (2 ^ 3) ^ x", printer), "2 ^ 3 ^ /0 = (2 ^ 3) ^ /0");
+        assert_eq!(print("EQ (2) (S (/0))", printer.with_numerals(NumeralForm::Successor)), "S(S(0)) = S(/0)");
+    }
+
+    #[test]
+    fn test_formulas_round_trip() {
+        functions::declare("printer_pair", 2).unwrap();
+        let cases = [
+            "forall x. exists y. x < y && !(y = 0)",
+            "(forall x. x = x) -> Even(/0) || /0 | 4",
+            "∀x. x ≤ /1 ∧ (Odd(x) ∨ x ∸ 1 = 0) ↔ ¬∃y. y < x",
+            "forall x < 10, y <= x. exists z < y. x = y * z",
+            "printer_pair(x, 2) = printer_pair(1, x) <-> x = 1",
+        ];
+        for text in cases {
+            let parsed = Parser::new(text).parse_formula().unwrap();
+            for charset in [Charset::Unicode, Charset::Ascii, Charset::Keyword] {
+                let printed = Printer::new().with_charset(charset).expression(&parsed.value);
+                assert_eq!(Parser::new(&printed).parse_formula().unwrap(), parsed, "{} printed as {}", text, printed);
+            }
+        }
+        assert_eq!(
+            print("forall x. exists y. x < y && !(y = 0)", Printer::new().with_charset(Charset::Ascii)),
+            "forall x. exists y. x < y && !(y = 0)"
+        );
+        assert_eq!(print("(forall x. x = x) -> Even(/0)", Printer::new()), "(∀x. x = x) → Even(/0)");
+        assert_eq!(print("forall x. x < 3 -> exists y. y < x && y = 1", Printer::new()), "∀x < 3. ∃y < x. y = 1");
+        assert_eq!(
+            print("forall x. x + 1 <= 2 * x <-> !Odd(x)", Printer::new().with_charset(Charset::Keyword)),
+            "FORALL (IFF (LE (PLUS (/0) (1)) (TIMES (2) (/0))) (NOT (ODD (/0))))"
+        );
+    }
+
+    /// Random terms and formulas of the parser's grammar, from an xorshift
+    /// generator with a fixed seed.
+    struct Generator {
+        state: u64,
+        stores: crate::stores::PeanoStores,
+    }
+
+    impl Generator {
+        fn below(&mut self, n: u64) -> u64 {
+            self.state ^= self.state << 13;
+            self.state ^= self.state >> 7;
+            self.state ^= self.state << 17;
+            self.state % n
+        }
+
+        fn term(&mut self, size: u32, depth: u32) -> HashNode<ArithmeticExpression> {
+            let choice = if size == 0 { self.below(2) } else { self.below(8) };
+            let sub = |generator: &mut Self| generator.term(size - 1, depth);
+            let expr = match choice {
+                0 => ArithmeticExpression::Number(self.below(4)),
+                1 => ArithmeticExpression::DeBruijn(self.below(depth as u64 + 2) as u32),
+                2 => ArithmeticExpression::Successor(sub(self)),
+                3 => ArithmeticExpression::Predecessor(sub(self)),
+                4 => ArithmeticExpression::Add(sub(self), sub(self)),
+                5 => ArithmeticExpression::Monus(sub(self), sub(self)),
+                6 => ArithmeticExpression::Multiply(sub(self), sub(self)),
+                _ => ArithmeticExpression::Power(sub(self), sub(self)),
+            };
+            HashNode::from_store(expr, &self.stores.expression_store)
+        }
+
+        fn formula(&mut self, size: u32, depth: u32) -> HashNode<PeanoLogicalExpression> {
+            if size == 0 || self.below(3) == 0 {
+                let (left, right) = (self.term(2, depth), self.term(2, depth));
+                let content = match self.below(6) {
+                    0 => PeanoContent::Equals(left, right),
+                    1 => PeanoContent::LessThan(left, right),
+                    2 => PeanoContent::LessEq(left, right),
+                    3 => PeanoContent::Divides(left, right),
+                    4 => PeanoContent::Even(left),
+                    _ => PeanoContent::Odd(left),
+                };
+                let content = HashNode::from_store(content, &self.stores.content_store);
+                return HashNode::from_store(LogicalExpression::Atomic(content), &self.stores.logical_store);
+            }
+            let (operator, operands) = match self.below(7) {
+                0 => (ClassicalOperator::Not, vec![self.formula(size - 1, depth)]),
+                1 => (ClassicalOperator::Forall, vec![self.formula(size - 1, depth + 1)]),
+                2 => (ClassicalOperator::Exists, vec![self.formula(size - 1, depth + 1)]),
+                n => {
+                    let connectives = [ClassicalOperator::And, ClassicalOperator::Or, ClassicalOperator::Implies, ClassicalOperator::Iff];
+                    let operator = connectives[n as usize - 3];
+                    (operator, vec![self.formula(size - 1, depth), self.formula(size - 1, depth)])
+                }
+            };
+            HashNode::from_store(LogicalExpression::compound(operator, operands), &self.stores.logical_store)
+        }
+    }
+
+    #[test]
+    fn test_random_formulas_round_trip() {
+        let mut generator = Generator { state: 0x9e37_79b9_7f4a_7c15, stores: crate::stores::PeanoStores::new() };
+        for _ in 0..300 {
+            let formula = generator.formula(4, 0);
+            let expr = PeanoExpression::logical(formula.clone());
+            for charset in [Charset::Unicode, Charset::Ascii, Charset::Keyword] {
+                let printed = Printer::new().with_numerals_as_written().with_charset(charset).expression(&expr);
+                let parsed = Parser::with_stores(&printed, &generator.stores).parse_formula();
+                let parsed = parsed.unwrap_or_else(|error| panic!("{} does not parse: {}", printed, error));
+                // Atoms parse at the domain level; compare as logical formulas.
+                assert_eq!(parsed.value.as_logical(&generator.stores.logical_store), formula, "{}", printed);
+            }
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! Axiom profiles: the theories the PA tool can prove in.
+//!
+//! A profile bundles the axioms of a theory as `NamedAxiom`s, the rewrite
+//! rules the search uses for its equations, and goal axioms, which close any
+//! goal that is an instance of them and refute any goal whose negation is.
+//! The built-in profiles are selected by name:
+//!
+//! - `pa`: Peano arithmetic, with the full rule set and induction
+//! - `pa-no-induction`: the same axioms and rules without induction
+//! - `q`: Robinson arithmetic, whose rules cover only successor, addition
+//!   and multiplication, and which has no induction
+//!
+//! User axioms extend any profile with `with_axiom`, or `with_axiom_file`
+//! for a file of them.
+
+use corpus_classical_logic::{AxiomGoalChecker, BinaryTruth, ClassicalAxiomConverter, ClassicalOperator};
+use corpus_core::base::axioms::NamedAxiom;
+use corpus_core::nodes::{HashNode, NodeStorage};
+use corpus_core::proving::GoalChecker;
+use corpus_core::rewriting::RewriteRule;
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::axiom_file::read_axiom_file;
+use crate::axioms::{peano_arithmetic_axioms, peano_arithmetic_rules, peano_ordering_axioms};
+use crate::counterexample::find_counterexample;
+use crate::divisibility::PeanoLogicalExpression;
+use crate::domain::{free_variables, PeanoDomain};
+use crate::eval::variables;
+use crate::induction::prove_by_induction_with_rules;
+use crate::parsing::{parse_axiom, Parser};
+use crate::stores::PeanoStores;
+use crate::prover::{prove_pa_with_goals, ProofOutcome};
+use crate::rewrite::equality_rule;
+use crate::syntax::{ArithmeticExpression, PeanoContent, PeanoExpression};
+
+type PeanoAxiom = NamedAxiom<BinaryTruth, PeanoContent, ClassicalOperator>;
+
+/// Names accepted by `AxiomProfile::named`.
+pub const PROFILE_NAMES: [&str; 3] = ["pa", "pa-no-induction", "q"];
+
+/// Robinson's axioms beyond those shared with `peano_arithmetic_axioms`.
+const ROBINSON_AXIOMS: [(&str, &str); 4] = [
+    // S(x) ≠ 0
+    ("NOT (EQ (S (/0)) (0))", "q1_successor_nonzero"),
+    // x = 0 ∨ ∃y. x = S(y)
+    ("OR (EQ (/0) (0)) (EXISTS (EQ (/1) (S (/0))))", "q3_zero_or_successor"),
+    // x * 0 = 0
+    ("EQ (TIMES (/0) (0)) (0)", "q6_multiplicative_zero"),
+    // x * S(y) = x * y + x
+    ("EQ (TIMES (/0) (S (/1))) (PLUS (TIMES (/0) (/1)) (/0))", "q7_multiplicative_successor"),
+];
+
+/// The rules of `peano_arithmetic_rules` that Robinson's axioms justify.
+const ROBINSON_RULES: [&str; 5] = [
+    "axiom2_successor_injectivity",
+    "axiom3_additive_identity",
+    "axiom4_additive_successor",
+    "multiply_zero",
+    "multiply_successor",
+];
+
+/// A theory to prove in.
+pub struct AxiomProfile {
+    pub name: String,
+    pub axioms: Vec<PeanoAxiom>,
+    pub rules: Vec<RewriteRule<ArithmeticExpression>>,
+    /// Axioms closing goals that instantiate them.
+    pub goal_axioms: Vec<PeanoAxiom>,
+    /// Whether goals may be proven by induction.
+    pub induction: bool,
+}
+
+impl AxiomProfile {
+    /// The built-in profile called `name`, one of `PROFILE_NAMES`.
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "pa" => Some(Self::peano()),
+            "pa-no-induction" => Some(Self::peano_without_induction()),
+            "q" => Some(Self::robinson()),
+            _ => None,
+        }
+    }
+
+    /// Peano arithmetic with the ordering axioms.
+    pub fn peano() -> Self {
+        let mut axioms = peano_arithmetic_axioms();
+        axioms.extend(peano_ordering_axioms());
+        Self {
+            name: "pa".to_string(),
+            axioms,
+            rules: peano_arithmetic_rules(),
+            goal_axioms: Vec::new(),
+            induction: true,
+        }
+    }
+
+    /// `peano` without the induction schema.
+    pub fn peano_without_induction() -> Self {
+        Self {
+            name: "pa-no-induction".to_string(),
+            induction: false,
+            ..Self::peano()
+        }
+    }
+
+    /// Robinson arithmetic Q: PA's successor, addition and multiplication
+    /// axioms, `S(x) ≠ 0` and `x = 0 ∨ ∃y. x = S(y)`, but no induction.
+    pub fn robinson() -> Self {
+        let stores = PeanoStores::new();
+        let mut axioms = peano_arithmetic_axioms();
+        axioms.extend(
+            ROBINSON_AXIOMS
+                .iter()
+                .map(|(text, name)| parse_axiom(text, name, &stores).unwrap_or_else(|e| panic!("Failed to parse {}: {}", name, e))),
+        );
+        let nonzero = parse_axiom(ROBINSON_AXIOMS[0].0, ROBINSON_AXIOMS[0].1, &stores).expect("q1 parses");
+        Self {
+            name: "q".to_string(),
+            axioms,
+            rules: peano_arithmetic_rules().into_iter().filter(|rule| ROBINSON_RULES.contains(&rule.name.as_str())).collect(),
+            goal_axioms: vec![nonzero],
+            induction: false,
+        }
+    }
+
+    /// Add a user axiom, written infix or in prefix form with its variables
+    /// implicitly universal. It closes the goals that instantiate it, and an
+    /// equality also becomes a rewrite rule.
+    pub fn with_axiom(mut self, name: &str, text: &str) -> Result<Self, String> {
+        let axiom = parse_axiom(text, name, &PeanoStores::new()).map_err(|e| e.to_string())?;
+        // `parse_axiom` lifts a bare equality to an equation between terms;
+        // goals are matched against the atom itself.
+        let goal_axiom = match Parser::new(text).parse_formula()?.value.as_domain() {
+            Some(content) => {
+                if let PeanoContent::Equals(left, right) = content.value.as_ref()
+                    && let Some(rule) = equality_rule(name, left, right)
+                {
+                    self.rules.push(rule);
+                }
+                let atom = HashNode::from_store(PeanoLogicalExpression::atomic(content.clone()), &NodeStorage::new());
+                NamedAxiom::new_with_converter(name, atom, Box::new(ClassicalAxiomConverter))
+            }
+            None => axiom.clone(),
+        };
+        self.axioms.push(axiom);
+        self.goal_axioms.push(goal_axiom);
+        Ok(self)
+    }
+
+    /// Add every axiom of an axiom file and its includes, as `with_axiom`.
+    pub fn with_axiom_file(self, path: impl AsRef<Path>) -> Result<Self, String> {
+        read_axiom_file(path).map_err(|e| e.to_string())?.into_iter().try_fold(self, |profile, declaration| {
+            profile
+                .with_axiom(&declaration.name, &declaration.formula)
+                .map_err(|e| format!("{}:{}: {}", declaration.path.display(), declaration.line, e))
+        })
+    }
+
+    /// A goal checker for the universal closures of the goal axioms.
+    pub fn goal_checker<'a>(&self, domain: PeanoDomain<'a>) -> GoalAxiomChecker<'a> {
+        let store = NodeStorage::new();
+        let closures: Vec<_> = self
+            .goal_axioms
+            .iter()
+            .map(|axiom| {
+                let arity = free_variables(&PeanoExpression::logical(axiom.expression.clone()), &domain).len();
+                let closure = (0..arity).fold(axiom.expression.clone(), |body, _| {
+                    HashNode::from_store(PeanoLogicalExpression::compound(ClassicalOperator::Forall, vec![body]), &store)
+                });
+                (axiom.name.clone(), closure)
+            })
+            .collect();
+
+        let mut checker = AxiomGoalChecker::new(domain);
+        for (name, closure) in closures {
+            checker.add_axiom(name, closure);
+        }
+        GoalAxiomChecker { checker, store }
+    }
+}
+
+/// Closes atoms that instantiate a goal axiom, and refutes those whose
+/// negation does.
+pub struct GoalAxiomChecker<'a> {
+    checker: AxiomGoalChecker<PeanoContent, PeanoDomain<'a>>,
+    store: NodeStorage<PeanoLogicalExpression>,
+}
+
+impl GoalChecker<PeanoContent, BinaryTruth> for GoalAxiomChecker<'_> {
+    fn check(&self, expr: &HashNode<PeanoContent>) -> Option<BinaryTruth> {
+        let atom = HashNode::from_store(PeanoLogicalExpression::atomic(expr.clone()), &self.store);
+        if self.checker.matching_axiom(&atom).is_some() {
+            return Some(BinaryTruth::True);
+        }
+        let negation = HashNode::from_store(PeanoLogicalExpression::compound(ClassicalOperator::Not, vec![atom]), &self.store);
+        self.checker.matching_axiom(&negation).map(|_| BinaryTruth::False)
+    }
+}
+
+/// `prove_or_disprove` in `profile`: counterexamples, then search with the
+/// profile's rules and goal axioms, then induction if the profile has it.
+///
+/// Counterexamples are evaluated in the standard model, so they refute a
+/// goal in every profile whose axioms hold there.
+pub fn prove_with_profile(
+    goal: &HashNode<PeanoContent>,
+    stores: &PeanoStores,
+    max_nodes: usize,
+    max_witness: u64,
+    profile: &AxiomProfile,
+) -> ProofOutcome {
+    if let Some(witness) = find_counterexample(goal, max_witness) {
+        return ProofOutcome::Disproved { witness };
+    }
+
+    let goals = profile.goal_checker(stores.domain());
+    if let Some(result) = prove_pa_with_goals(goal, stores, max_nodes, &profile.rules, &goals) {
+        return ProofOutcome::Proved(result);
+    }
+    if !profile.induction {
+        return ProofOutcome::Unknown;
+    }
+
+    let variables: BTreeSet<u32> = goal.value.terms().into_iter().flat_map(variables).collect();
+    variables
+        .iter()
+        .find_map(|&variable| prove_by_induction_with_rules(goal, variable, stores, max_nodes, &profile.rules).ok())
+        .map_or(ProofOutcome::Unknown, ProofOutcome::ProvedByInduction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prove(text: &str, profile: &AxiomProfile) -> ProofOutcome {
+        let stores = PeanoStores::new();
+        let proposition = Parser::with_stores(text, &stores).parse_formula().unwrap();
+        prove_with_profile(proposition.value.as_domain().unwrap(), &stores, 2000, 4, profile)
+    }
+
+    #[test]
+    fn test_profiles_by_name() {
+        for name in PROFILE_NAMES {
+            assert_eq!(AxiomProfile::named(name).unwrap().name, name);
+        }
+        assert!(AxiomProfile::named("zfc").is_none());
+
+        let q = AxiomProfile::robinson();
+        assert_eq!(q.axioms.len(), 7);
+        assert_eq!(q.rules.len(), ROBINSON_RULES.len());
+        assert!(!q.induction);
+    }
+
+    #[test]
+    fn test_induction_only_where_allowed() {
+        assert!(matches!(prove("0 + x = x", &AxiomProfile::peano()), ProofOutcome::ProvedByInduction(_)));
+        assert!(matches!(prove("0 + x = x", &AxiomProfile::peano_without_induction()), ProofOutcome::Unknown));
+        assert!(matches!(prove("0 + x = x", &AxiomProfile::robinson()), ProofOutcome::Unknown));
+        assert!(matches!(prove("2 * 2 = 4", &AxiomProfile::robinson()), ProofOutcome::Proved(_)));
+    }
+
+    #[test]
+    fn test_user_axioms() {
+        let profile = AxiomProfile::robinson()
+            .with_axiom("zero_add", "0 + x = x")
+            .unwrap()
+            .with_axiom("even_square", "Even(x * x) || Odd(x)")
+            .unwrap();
+        assert_eq!(profile.axioms.len(), 9);
+        assert!(profile.rules.iter().any(|rule| rule.name == "zero_add"));
+
+        let ProofOutcome::Proved(result) = prove("0 + (y + 1) = S(y)", &profile) else {
+            panic!("the user axiom should rewrite 0 + ...");
+        };
+        assert!(result.steps.iter().any(|step| step.rule_name.starts_with("zero_add")));
+        assert!(AxiomProfile::peano().with_axiom("bad", "x +").is_err());
+        let ordering = AxiomProfile::robinson().with_axiom_file(concat!(env!("CARGO_MANIFEST_DIR"), "/axioms/ordering.axioms"));
+        assert_eq!(ordering.unwrap().goal_axioms.len(), 5);
+
+        // Goal axioms settle atoms outright, positively or negatively.
+        let stores = PeanoStores::new();
+        let checker = profile.goal_checker(stores.domain());
+        let atom = |text: &str| Parser::new(text).parse_formula().unwrap().value.as_domain().unwrap().clone();
+        assert_eq!(checker.check(&atom("0 + (y * 2) = y * 2")), Some(BinaryTruth::True));
+        assert_eq!(checker.check(&atom("S(y + 1) = 0")), Some(BinaryTruth::False));
+        assert_eq!(checker.check(&atom("y = 0")), None);
+    }
+}
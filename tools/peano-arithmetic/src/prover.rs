@@ -3,14 +3,21 @@
 //! This module provides a thin wrapper around the core `Prover` type,
 //! specializing it for Peano Arithmetic with default implementations.
 
-use crate::syntax::PeanoContent;
+use crate::syntax::{numeral_step, ArithmeticExpression, NumeralForm, PeanoContent};
+use crate::counterexample::{find_counterexample, Counterexample};
 use crate::goal::AxiomPatternChecker;
 use crate::axioms::peano_arithmetic_rules;
+use crate::eval::{ground_equality, variables};
+use crate::induction::{prove_by_induction, InductionProof};
+use crate::printer::Printer;
+use crate::stores::PeanoStores;
+use std::collections::BTreeSet;
+use std::time::Duration;
 use corpus_classical_logic::BinaryTruth;
 use corpus_core::{
-    base::nodes::{HashNode, NodeStorage},
-    proving::{Prover, SizeCostEstimator, GoalChecker, CostEstimator},
-    rewriting::RewriteRule,
+    base::nodes::HashNode,
+    proving::{NoopObserver, Prover, ProofFailure, SearchObserver, SizeCostEstimator, GoalChecker, InferenceRule, LayeredRewriter, NaturalDeduction, ProofCertificate, StateExpander},
+    rewriting::{injectivity_rules, InjectivityRule, RewriteDirection, RewriteResult, RewriteRule, Substitution},
 };
 
 /// Type alias for the PA prover with default implementations.
@@ -35,7 +42,9 @@ pub type PeanoProver = Prover<PeanoContent, SizeCostEstimator, BinaryTruth, Axio
 /// // and arithmetic rewrite rules
 /// ```
 pub fn create_prover(max_nodes: usize) -> PeanoProver {
-    Prover::new(max_nodes, SizeCostEstimator, AxiomPatternChecker::new())
+    Prover::builder(SizeCostEstimator, AxiomPatternChecker::new())
+        .max_nodes(max_nodes)
+        .build()
 }
 
 /// Custom proof function for PA that handles the type mismatch between
@@ -45,125 +54,198 @@ pub fn create_prover(max_nodes: usize) -> PeanoProver {
 /// of the equality, checking if the result matches an axiom pattern.
 pub fn prove_pa(
     initial_expr: &HashNode<PeanoContent>,
-    store: &NodeStorage<PeanoContent>,
+    stores: &PeanoStores,
     max_nodes: usize,
 ) -> Option<crate::prover::ProofResult<PeanoContent, BinaryTruth>> {
-    use std::collections::{BinaryHeap, HashSet};
-    use crate::prover::{ProofState, ProofStep, ProofResult};
-
-    let arithmetic_rules = peano_arithmetic_rules();
-    let goal_checker = AxiomPatternChecker::new();
-    let cost_estimator = SizeCostEstimator;
-
-    let mut heap = BinaryHeap::new();
-    let mut visited = HashSet::new();
-    let mut nodes_explored = 0usize;
-
-    let initial_cost = cost_estimator.estimate_cost(initial_expr);
-    let initial_state = ProofState {
-        expr: initial_expr.clone(),
-        steps: Vec::new(),
-        estimated_cost: initial_cost,
-    };
+    prove_pa_with_rules(initial_expr, stores, max_nodes, &peano_arithmetic_rules())
+}
 
-    heap.push(initial_state);
+/// `prove_pa` with the given arithmetic rules in place of the PA axioms,
+/// e.g. with an induction hypothesis added.
+pub fn prove_pa_with_rules(
+    initial_expr: &HashNode<PeanoContent>,
+    stores: &PeanoStores,
+    max_nodes: usize,
+    arithmetic_rules: &[RewriteRule<crate::syntax::ArithmeticExpression>],
+) -> Option<crate::prover::ProofResult<PeanoContent, BinaryTruth>> {
+    prove_pa_with_goals(initial_expr, stores, max_nodes, arithmetic_rules, &NoGoals)
+}
 
-    while let Some(state) = heap.pop() {
-        nodes_explored += 1;
+/// `prove_pa_with_rules` that also closes any state `goals` settles, e.g.
+/// instances of an axiom profile's goal axioms.
+pub fn prove_pa_with_goals(
+    initial_expr: &HashNode<PeanoContent>,
+    stores: &PeanoStores,
+    max_nodes: usize,
+    arithmetic_rules: &[RewriteRule<crate::syntax::ArithmeticExpression>],
+    goals: &dyn GoalChecker<PeanoContent, BinaryTruth>,
+) -> Option<crate::prover::ProofResult<PeanoContent, BinaryTruth>> {
+    search_pa_with_goals(initial_expr, stores, max_nodes, arithmetic_rules, goals).ok()
+}
 
-        if nodes_explored > max_nodes {
-            return None;
-        }
+/// `prove_pa_with_goals`, keeping why the search failed and how close it got.
+pub fn search_pa_with_goals(
+    initial_expr: &HashNode<PeanoContent>,
+    stores: &PeanoStores,
+    max_nodes: usize,
+    arithmetic_rules: &[RewriteRule<crate::syntax::ArithmeticExpression>],
+    goals: &dyn GoalChecker<PeanoContent, BinaryTruth>,
+) -> Result<crate::prover::ProofResult<PeanoContent, BinaryTruth>, ProofFailure<PeanoContent>> {
+    search_pa_observed(initial_expr, stores, max_nodes, None, arithmetic_rules, goals, &mut NoopObserver)
+}
 
-        // Check if we've reached the goal (matches an axiom pattern)
-        if let Some(truth) = goal_checker.check(&state.expr) {
-            return Some(ProofResult {
-                steps: state.steps,
-                nodes_explored,
-                final_expr: state.expr,
-                truth_result: truth,
-            });
-        }
+/// `search_pa_with_goals` reporting search events to `observer`, which may
+/// stop the search, and giving up once `timeout` has passed.
+pub fn search_pa_observed(
+    initial_expr: &HashNode<PeanoContent>,
+    stores: &PeanoStores,
+    max_nodes: usize,
+    timeout: Option<Duration>,
+    arithmetic_rules: &[RewriteRule<crate::syntax::ArithmeticExpression>],
+    goals: &dyn GoalChecker<PeanoContent, BinaryTruth>,
+    observer: &mut dyn SearchObserver<PeanoContent, BinaryTruth>,
+) -> Result<crate::prover::ProofResult<PeanoContent, BinaryTruth>, ProofFailure<PeanoContent>> {
+    let mut prover: Prover<_, _, BinaryTruth, _> =
+        Prover::builder(SizeCostEstimator, PeanoGoalChecker { goals }).max_nodes(max_nodes).build();
+    prover.set_timeout(timeout);
+    let expander = PeanoExpander {
+        rewriter: LayeredRewriter::new(arithmetic_rules, &stores.content_store, &stores.expression_store),
+        injectivity: injectivity_rules::<ArithmeticExpression>(),
+        stores,
+    };
+    prover.prove_with_expander_and_observer(initial_expr, &expander, observer)
+}
 
-        let key = state.expr.hash();
-        if visited.contains(&key) {
-            continue;
-        }
-        visited.insert(key);
-
-        // Get all rewrites by applying arithmetic rules to subterms
-        for (rewritten_expr, rule_name) in get_all_rewrites_with_names(&state.expr, store, &arithmetic_rules) {
-            let cost = cost_estimator.estimate_cost(&rewritten_expr);
-            heap.push(ProofState {
-                expr: rewritten_expr.clone(),
-                steps: {
-                    let mut new_steps = state.steps.clone();
-                    new_steps.push(ProofStep {
-                        rule_name,
-                        old_expr: state.expr.clone(),
-                        new_expr: rewritten_expr,
-                    });
-                    new_steps
-                },
-                estimated_cost: cost,
-            });
-        }
+/// Closes states matching an axiom pattern, or failing that, states whose
+/// sides are ground and can be compared, before consulting `goals`.
+struct PeanoGoalChecker<'a> {
+    goals: &'a dyn GoalChecker<PeanoContent, BinaryTruth>,
+}
+
+impl GoalChecker<PeanoContent, BinaryTruth> for PeanoGoalChecker<'_> {
+    fn check(&self, expr: &HashNode<PeanoContent>) -> Option<BinaryTruth> {
+        AxiomPatternChecker::new()
+            .check(expr)
+            .or_else(|| ground_equality(&expr.value).map(BinaryTruth::from))
+            .or_else(|| self.goals.check(expr))
     }
+}
+
+/// The goal checker settling nothing.
+pub struct NoGoals;
 
-    None
+impl GoalChecker<PeanoContent, BinaryTruth> for NoGoals {
+    fn check(&self, _expr: &HashNode<PeanoContent>) -> Option<BinaryTruth> {
+        None
+    }
 }
 
-/// Helper function to get rewrites with rule names.
-fn get_all_rewrites_with_names(
-    equality: &HashNode<PeanoContent>,
-    store: &NodeStorage<PeanoContent>,
-    arithmetic_rules: &[RewriteRule<crate::syntax::ArithmeticExpression>],
-) -> Vec<(HashNode<PeanoContent>, String)> {
-    let mut results = Vec::new();
+/// The successors of a PA state: arithmetic rules and numeral steps at every
+/// subterm of every argument, then the rules rewriting whole relations:
+/// injectivity of the constructors `ArithmeticExpression` declares
+/// injective, and the parity rules.
+///
+/// Positions of term rewrites start with the index of the rewritten argument
+/// (`0` for the left side, `1` for the right), followed by the path to the
+/// rewritten subterm; they are empty for rewrites of the whole relation.
+struct PeanoExpander<'a> {
+    rewriter: LayeredRewriter<'a, PeanoContent, ArithmeticExpression>,
+    injectivity: Vec<InjectivityRule>,
+    stores: &'a PeanoStores,
+}
 
-    // This function only handles Equals, not Arithmetic
-    let PeanoContent::Equals(left, right) = equality.value.as_ref() else {
-        return results;
-    };
-    let arith_store = NodeStorage::<crate::syntax::ArithmeticExpression>::new();
-
-    // Try each arithmetic rule on both sides
-    for rule in arithmetic_rules {
-        // Forward direction on left
-        if let Some(new_left) = rule.apply(left, &arith_store) {
-            let new_content = PeanoContent::Equals(new_left, right.clone());
-            let new_expr = HashNode::from_store(new_content, store);
-            results.push((new_expr, rule.name.clone()));
-        }
+impl StateExpander<PeanoContent> for PeanoExpander<'_> {
+    fn expand(&self, expr: &HashNode<PeanoContent>) -> Vec<RewriteResult<PeanoContent>> {
+        let arith_store = self.rewriter.inner_store();
+        // Numerals are unfolded and folded in place, relating `2` and `S(S(0))`
+        let mut results = self.rewriter.rewrites_with(expr, |subterm| {
+            let numerals = [(NumeralForm::Successor, "numeral_successor"), (NumeralForm::Decimal, "numeral_decimal")]
+                .into_iter()
+                .filter_map(|(form, name)| {
+                    numeral_step(subterm, form, arith_store).map(|term| (term, name.to_string(), RewriteDirection::Forward))
+                });
+            let rules = self.rewriter.rule_rewrites(subterm).into_iter().map(|(term, name, direction)| match direction {
+                RewriteDirection::Backward => (term, format!("{}_reverse", name), direction),
+                _ => (term, name, direction),
+            });
+            numerals.chain(rules).collect()
+        });
 
-        // Reverse direction on left
-        if let Some(new_left) = rule.apply_reverse(left, &arith_store) {
-            let new_content = PeanoContent::Equals(new_left, right.clone());
-            let new_expr = HashNode::from_store(new_content, store);
-            results.push((new_expr, format!("{}_reverse", rule.name)));
+        // Injectivity at the top level, e.g. S(x) = S(y) -> x = y
+        let mut relations = Vec::new();
+        if let PeanoContent::Equals(left, right) = expr.value.as_ref() {
+            for rule in &self.injectivity {
+                if let Some((left, right)) = rule.apply(left, right) {
+                    let equality = HashNode::from_store(PeanoContent::Equals(left, right), &self.stores.content_store);
+                    relations.push((equality, rule.name.clone()));
+                }
+            }
         }
+        let parity = crate::parity::apply_parity_rules(expr, &self.stores.content_store);
+        relations.extend(parity.into_iter().map(|(term, name)| (term, name.to_string())));
+        results.extend(relations.into_iter().map(|(term, rule_name)| RewriteResult {
+            term,
+            substitution: Substitution::new(),
+            rule_name,
+            direction: RewriteDirection::Forward,
+            position: Vec::new(),
+        }));
+        results
+    }
+}
 
-        // Forward direction on right
-        if let Some(new_right) = rule.apply(right, &arith_store) {
-            let new_content = PeanoContent::Equals(left.clone(), new_right);
-            let new_expr = HashNode::from_store(new_content, store);
-            results.push((new_expr, rule.name.clone()));
-        }
+/// Outcome of attempting to settle a PA goal.
+pub enum ProofOutcome {
+    /// The search reached a goal state (the result's truth value says whether
+    /// the statement was proved or shown contradictory).
+    Proved(ProofResult<PeanoContent, BinaryTruth>),
+    /// Direct search failed but induction on one variable succeeded.
+    ProvedByInduction(InductionProof),
+    /// A concrete instance of the variables falsifies the equality.
+    Disproved { witness: Counterexample },
+    /// Neither a proof nor a counterexample was found within the limits.
+    Unknown,
+}
 
-        // Reverse direction on right
-        if let Some(new_right) = rule.apply_reverse(right, &arith_store) {
-            let new_content = PeanoContent::Equals(left.clone(), new_right);
-            let new_expr = HashNode::from_store(new_content, store);
-            results.push((new_expr, format!("{}_reverse", rule.name)));
-        }
+/// Try to refute `initial_expr` with small instances before searching for a proof.
+///
+/// Variables are instantiated with every combination of numerals in
+/// `0..=max_witness` and both sides evaluated. Only if no instance fails is the
+/// node budget spent on `prove_pa`, and if that fails, on induction over
+/// each variable in turn.
+pub fn prove_or_disprove(
+    initial_expr: &HashNode<PeanoContent>,
+    stores: &PeanoStores,
+    max_nodes: usize,
+    max_witness: u64,
+) -> ProofOutcome {
+    if let Some(witness) = find_counterexample(initial_expr, max_witness) {
+        return ProofOutcome::Disproved { witness };
     }
 
-    // Try successor injectivity at the top level: S(x) = S(y) -> x = y
-    if let Some(rewritten) = crate::syntax::apply_successor_injectivity(equality, store) {
-        results.push((rewritten, "successor_injectivity".to_string()));
+    if let Some(result) = prove_pa(initial_expr, stores, max_nodes) {
+        return ProofOutcome::Proved(result);
     }
 
-    results
+    let variables: BTreeSet<u32> = initial_expr.value.terms().into_iter().flat_map(variables).collect();
+    variables
+        .iter()
+        .find_map(|&variable| prove_by_induction(initial_expr, variable, stores, max_nodes).ok())
+        .map_or(ProofOutcome::Unknown, ProofOutcome::ProvedByInduction)
+}
+
+/// The natural-deduction rendering of a proof's certificate.
+pub fn derivation_text(result: &ProofResult<PeanoContent, BinaryTruth>) -> String {
+    derivation_text_with(result, &Printer::default())
+}
+
+/// `derivation_text` with each formula rendered by `printer`.
+pub fn derivation_text_with(result: &ProofResult<PeanoContent, BinaryTruth>, printer: &Printer) -> String {
+    // Read backwards, every PA step (injectivity included) substitutes equals.
+    let renderer = NaturalDeduction::new()
+        .with_default(InferenceRule::Replacement)
+        .with_closing("axiom");
+    renderer.derive_with(&ProofCertificate::from_result(result), &[], printer).to_string()
 }
 
 // Re-export commonly used types from core for convenience
@@ -186,14 +268,68 @@ impl ProofResultExt for ProofResult<PeanoContent, BinaryTruth> {
         println!();
 
         if !self.steps.is_empty() {
-            println!("Proof steps:");
-            for (i, step) in self.steps.iter().enumerate() {
-                println!("  {}. Apply \"{}\":", i + 1, step.rule_name);
-                println!("     {} → {}", step.old_expr, step.new_expr);
-            }
+            println!("Derivation:");
+            print!("{}", derivation_text(self));
             println!();
         }
 
-        println!("Final: {} {}", self.final_expr, if self.truth_result == BinaryTruth::False { "✗" } else { "✓" });
+        println!("Final: {} {}", Printer::default().content(&self.final_expr.value), if self.truth_result == BinaryTruth::False { "✗" } else { "✓" });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    #[test]
+    fn test_numerals_meet_successors() {
+        let stores = PeanoStores::new();
+        let proposition = Parser::with_stores("EQ (PLUS (/0) (1)) (S (/0))", &stores).parse_proposition().unwrap();
+        let equality = proposition.value.as_domain().unwrap();
+
+        let result = prove_pa(equality, &stores, 1000).expect("x + 1 = S(x)");
+        assert_eq!(result.truth_result, BinaryTruth::True);
+        assert!(result.steps.iter().any(|step| step.rule_name == "numeral_successor"));
+    }
+
+    #[test]
+    fn test_power_unfolds_to_products() {
+        let stores = PeanoStores::new();
+        // x ^ 1 = 1 * x through power_successor and power_zero.
+        let proposition = Parser::new("EQ (^ (/0) (1)) (TIMES (1) (/0))").parse_proposition().unwrap();
+        let equality = proposition.value.as_domain().unwrap();
+
+        let result = prove_pa(equality, &stores, 5000).expect("x ^ 1 = 1 * x");
+        assert_eq!(result.truth_result, BinaryTruth::True);
+        assert!(result.steps.iter().any(|step| step.rule_name == "power_successor"));
+    }
+
+    #[test]
+    fn test_ground_equalities_are_evaluated() {
+        let stores = PeanoStores::new();
+        for (text, truth) in [("EQ (TIMES (12) (13)) (156)", BinaryTruth::True), ("EQ (^ (2) (10)) (1000)", BinaryTruth::False)] {
+            let proposition = Parser::new(text).parse_proposition().unwrap();
+            let result = prove_pa(proposition.value.as_domain().unwrap(), &stores, 10).expect("ground goals are decided");
+            assert_eq!(result.truth_result, truth);
+            assert_eq!((result.nodes_explored, result.steps.len()), (1, 0));
+        }
+    }
+
+    #[test]
+    fn test_search_shares_the_parsers_nodes() {
+        let stores = PeanoStores::new();
+        let proposition = Parser::with_stores("(x + y) + 2 = x + (y + 2)", &stores).parse_formula().unwrap();
+        let goal = proposition.value.as_domain().unwrap();
+        prove_pa(goal, &stores, 2000).expect("(x + y) + 2 = x + (y + 2)");
+
+        // The goal's sides are the very nodes the search rewrote, and a
+        // second search reaches nothing new.
+        let interned = stores.len();
+        let again = Parser::with_stores("(x + y) + 2 = x + (y + 2)", &stores).parse_formula().unwrap();
+        let terms = (goal.value.terms(), again.value.as_domain().unwrap().value.terms());
+        assert!(std::sync::Arc::ptr_eq(&terms.0[0].value, &terms.1[0].value));
+        prove_pa(goal, &stores, 2000).unwrap();
+        assert_eq!(stores.len(), interned);
     }
 }
@@ -1,7 +1,48 @@
 use corpus_core::nodes::{HashNode, NodeStorage};
-use corpus_core::rewriting::RewriteRule;
+use corpus_core::rewriting::{Pattern, RewriteDirection, RewriteRule, RewriteSystem};
 
-use crate::syntax::ArithmeticExpression;
+use crate::axioms::peano_arithmetic_rules;
+use crate::eval::variables;
+use crate::syntax::{normalize_numerals, ArithmeticExpression, NumeralForm};
+
+/// The equality `left = right` as a rewrite rule whose variables match any term.
+///
+/// A side may only be rewritten into one whose variables it binds, so the
+/// rule runs in whichever directions allow that; returns `None` if neither
+/// side's variables cover the other's.
+pub fn equality_rule(
+    name: impl Into<String>,
+    left: &HashNode<ArithmeticExpression>,
+    right: &HashNode<ArithmeticExpression>,
+) -> Option<RewriteRule<ArithmeticExpression>> {
+    let (left_vars, right_vars) = (variables(left), variables(right));
+    let direction = match (right_vars.is_subset(&left_vars), left_vars.is_subset(&right_vars)) {
+        (true, true) => RewriteDirection::Both,
+        (true, false) => RewriteDirection::Forward,
+        (false, true) => RewriteDirection::Backward,
+        (false, false) => return None,
+    };
+    let pattern = |term: &HashNode<ArithmeticExpression>| {
+        Pattern::from_term(term, &|t: &HashNode<ArithmeticExpression>| match t.value.as_ref() {
+            ArithmeticExpression::DeBruijn(index) => Some(Pattern::var(*index)),
+            _ => None,
+        })
+    };
+    Some(RewriteRule::new(name, pattern(left), pattern(right), direction))
+}
+
+/// `term` rewritten with the PA rules until none applies, e.g. `x * 2` to
+/// `0 + x + x`, with numerals in decimal.
+///
+/// The rules recurse on successors, so numerals are expanded first.
+pub fn normal_form(
+    term: &HashNode<ArithmeticExpression>,
+    store: &NodeStorage<ArithmeticExpression>,
+) -> HashNode<ArithmeticExpression> {
+    let system = RewriteSystem::with_rules("pa", peano_arithmetic_rules());
+    let expanded = normalize_numerals(term, NumeralForm::Successor, store);
+    normalize_numerals(&system.normalize(&expanded, store), NumeralForm::Decimal, store)
+}
 
 pub fn apply_rule(
     rule: &RewriteRule<ArithmeticExpression>,
@@ -36,15 +77,43 @@ pub fn rewrite_subterms(
     }
 
     match term.value.as_ref() {
-        ArithmeticExpression::Add(left, right) => {
+        ArithmeticExpression::Add(left, right)
+        | ArithmeticExpression::Monus(left, right)
+        | ArithmeticExpression::Multiply(left, right)
+        | ArithmeticExpression::Power(left, right) => {
             results.extend(rewrite_subterms(rules, left, store));
             results.extend(rewrite_subterms(rules, right, store));
         }
-        ArithmeticExpression::Successor(inner) => {
+        ArithmeticExpression::Successor(inner) | ArithmeticExpression::Predecessor(inner) => {
             results.extend(rewrite_subterms(rules, inner, store));
         }
+        ArithmeticExpression::Function(_, args) => {
+            for arg in args {
+                results.extend(rewrite_subterms(rules, arg, store));
+            }
+        }
         ArithmeticExpression::Number(_) | ArithmeticExpression::DeBruijn(_) => {}
     }
 
     results
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use crate::printer::Printer;
+    use crate::stores::PeanoStores;
+
+    #[test]
+    fn test_normal_form() {
+        let stores = PeanoStores::new();
+        let normal = |text: &str| {
+            let term = Parser::with_stores(text, &stores).parse_term().unwrap();
+            Printer::default().term(&normal_form(&term, &stores.expression_store))
+        };
+        assert_eq!(normal("2 * 3 + P(1)"), "6");
+        assert_eq!(normal("x * 2 + 0"), "0 + /0 + /0");
+        assert_eq!(normal("x - S(y)"), "P(/0 ∸ /1)");
+    }
+}
@@ -0,0 +1,346 @@
+//! Proof scripts: files of named theorems proven one after another.
+//!
+//! ```text
+//! # Comments start with '#'.
+//! theorem zero_add: EQ (PLUS (0) (/0)) (/0)
+//!   by induction on /0
+//! theorem shift: 2 + x = x + 2
+//!   hint use zero_add
+//!   hint max_nodes 20000
+//! ```
+//!
+//! Each `theorem` line may be followed by indented directives: `by induction
+//! on /i` proves it by induction on that variable, `hint use <name>` adds an
+//! earlier proven equality as a rewrite rule, and `hint max_nodes <n>`
+//! changes the search budget for this theorem. Quantified theorems are
+//! decided by Presburger arithmetic.
+
+use std::fmt;
+use std::time::Duration;
+
+use corpus_classical_logic::BinaryTruth;
+use corpus_core::clock::Stopwatch;
+use corpus_core::rewriting::RewriteRule;
+
+use crate::axioms::peano_arithmetic_rules;
+use crate::counterexample::find_formula_counterexample;
+use crate::induction::prove_by_induction_with_rules;
+use crate::parsing::Parser;
+use crate::presburger::decide;
+use crate::prover::{derivation_text, prove_pa_with_rules};
+use crate::rewrite::equality_rule;
+use crate::stores::PeanoStores;
+use crate::syntax::{ArithmeticExpression, PeanoContent};
+
+/// Largest numeral tried when refuting a quantified theorem.
+const MAX_WITNESS: u64 = 8;
+
+/// A directive attached to a theorem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hint {
+    /// Use the named earlier theorem as a rewrite rule.
+    Use(String),
+    /// Search at most this many states.
+    MaxNodes(usize),
+}
+
+/// One `theorem` block of a script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptEntry {
+    pub name: String,
+    pub formula: String,
+    /// The variable of a `by induction on /i` directive.
+    pub induction: Option<u32>,
+    pub hints: Vec<Hint>,
+    /// Line of the `theorem` keyword, counting from 1.
+    pub line: usize,
+}
+
+/// A script line that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// Split a script into its theorems.
+pub fn parse_script(text: &str) -> Result<Vec<ScriptEntry>, ScriptError> {
+    let mut entries: Vec<ScriptEntry> = Vec::new();
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let error = |message: String| ScriptError { line, message };
+        let content = raw.split('#').next().unwrap_or("").trim();
+        if content.is_empty() {
+            continue;
+        }
+
+        if let Some(rest) = content.strip_prefix("theorem ") {
+            let (name, formula) = rest.split_once(':').ok_or_else(|| error("expected 'theorem <name>: <formula>'".into()))?;
+            let name = name.trim();
+            if name.is_empty() || entries.iter().any(|entry| entry.name == name) {
+                return Err(error(format!("missing or duplicate theorem name '{}'", name)));
+            }
+            entries.push(ScriptEntry {
+                name: name.to_string(),
+                formula: formula.trim().to_string(),
+                induction: None,
+                hints: Vec::new(),
+                line,
+            });
+            continue;
+        }
+
+        let entry = entries.last_mut().ok_or_else(|| error("directive before any theorem".into()))?;
+        if let Some(variable) = content.strip_prefix("by induction on") {
+            let variable = variable.trim().trim_start_matches('/');
+            entry.induction = Some(variable.parse().map_err(|_| error(format!("not a variable: {}", variable)))?);
+        } else if let Some(name) = content.strip_prefix("hint use ") {
+            entry.hints.push(Hint::Use(name.trim().to_string()));
+        } else if let Some(count) = content.strip_prefix("hint max_nodes ") {
+            let count = count.trim().parse().map_err(|_| error(format!("not a node count: {}", count.trim())))?;
+            entry.hints.push(Hint::MaxNodes(count));
+        } else {
+            return Err(error(format!("unknown directive '{}'", content)));
+        }
+    }
+    Ok(entries)
+}
+
+/// How a theorem fared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryStatus {
+    Proved,
+    ProvedByInduction,
+    /// Decided valid by Presburger arithmetic.
+    Decided,
+    Failed(String),
+}
+
+/// The result of running one theorem.
+#[derive(Debug, Clone)]
+pub struct EntryReport {
+    pub name: String,
+    pub status: EntryStatus,
+    pub steps: usize,
+    pub nodes_explored: usize,
+    pub elapsed: Duration,
+    /// Rendered derivation, when certificates were requested.
+    pub certificate: Option<String>,
+}
+
+impl EntryReport {
+    pub fn passed(&self) -> bool {
+        !matches!(self.status, EntryStatus::Failed(_))
+    }
+}
+
+impl fmt::Display for EntryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = match &self.status {
+            EntryStatus::Proved => "PASS".to_string(),
+            EntryStatus::ProvedByInduction => "PASS (induction)".to_string(),
+            EntryStatus::Decided => "PASS (Presburger)".to_string(),
+            EntryStatus::Failed(reason) => format!("FAIL ({})", reason),
+        };
+        write!(
+            f,
+            "{:<24} {} - {} steps, {} nodes, {:.2?}",
+            self.name, verdict, self.steps, self.nodes_explored, self.elapsed
+        )
+    }
+}
+
+/// Reports for every theorem of a script, in order.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptReport {
+    pub entries: Vec<EntryReport>,
+}
+
+impl ScriptReport {
+    pub fn passed(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.passed()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.entries.len() - self.passed()
+    }
+}
+
+impl fmt::Display for ScriptReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        write!(f, "{} passed, {} failed", self.passed(), self.failed())
+    }
+}
+
+/// Prove every theorem of `entries` in order, each with at most `max_nodes`
+/// states unless hinted otherwise, rendering derivations if `certificates`.
+///
+/// Proven equalities become available to later `hint use` directives.
+pub fn run_script(entries: &[ScriptEntry], max_nodes: usize, certificates: bool) -> ScriptReport {
+    let mut proven: Vec<RewriteRule<ArithmeticExpression>> = Vec::new();
+    let mut report = ScriptReport::default();
+
+    for entry in entries {
+        let started = Stopwatch::start();
+        let mut entry_report = run_entry(entry, max_nodes, certificates, &proven);
+        entry_report.elapsed = started.elapsed();
+
+        if entry_report.passed()
+            && let Some(rule) = theorem_rule(entry)
+        {
+            proven.push(rule);
+        }
+        report.entries.push(entry_report);
+    }
+    report
+}
+
+/// A proven equality as a rule named after its theorem.
+fn theorem_rule(entry: &ScriptEntry) -> Option<RewriteRule<ArithmeticExpression>> {
+    let proposition = Parser::new(&entry.formula).parse_formula().ok()?;
+    let PeanoContent::Equals(left, right) = proposition.value.as_domain()?.value.as_ref() else {
+        return None;
+    };
+    equality_rule(entry.name.clone(), left, right)
+}
+
+fn run_entry(
+    entry: &ScriptEntry,
+    max_nodes: usize,
+    certificates: bool,
+    proven: &[RewriteRule<ArithmeticExpression>],
+) -> EntryReport {
+    let mut report = EntryReport {
+        name: entry.name.clone(),
+        status: EntryStatus::Failed(String::new()),
+        steps: 0,
+        nodes_explored: 0,
+        elapsed: Duration::ZERO,
+        certificate: None,
+    };
+    let fail = |mut report: EntryReport, reason: String| {
+        report.status = EntryStatus::Failed(reason);
+        report
+    };
+
+    let stores = PeanoStores::new();
+    let proposition = match Parser::with_stores(&entry.formula, &stores).parse_formula() {
+        Ok(proposition) => proposition,
+        Err(e) => return fail(report, format!("parse error: {}", e)),
+    };
+    let Some(content) = proposition.value.as_domain() else {
+        return match decide(&proposition.value) {
+            Some(true) => EntryReport { status: EntryStatus::Decided, ..report },
+            _ if let Some(witness) = find_formula_counterexample(&proposition.value, MAX_WITNESS) => {
+                fail(report, format!("not valid, {}", witness))
+            }
+            Some(false) => fail(report, "not valid".to_string()),
+            None => fail(report, "outside Presburger arithmetic".to_string()),
+        };
+    };
+
+    let mut rules = peano_arithmetic_rules();
+    let mut max_nodes = max_nodes;
+    for hint in &entry.hints {
+        match hint {
+            Hint::MaxNodes(count) => max_nodes = *count,
+            Hint::Use(name) => match proven.iter().find(|rule| &rule.name == name) {
+                Some(rule) => rules.push(rule.clone()),
+                None => return fail(report, format!("no proven equality named '{}'", name)),
+            },
+        }
+    }
+
+    if let Some(variable) = entry.induction {
+        return match prove_by_induction_with_rules(content, variable, &stores, max_nodes, &rules) {
+            Ok(proof) => {
+                report.steps = proof.base.steps.len() + proof.step.steps.len();
+                report.nodes_explored = proof.base.nodes_explored + proof.step.nodes_explored;
+                if certificates {
+                    report.certificate = Some(format!(
+                        "Base case:\n{}\nStep case:\n{}",
+                        derivation_text(&proof.base),
+                        derivation_text(&proof.step)
+                    ));
+                }
+                EntryReport { status: EntryStatus::ProvedByInduction, ..report }
+            }
+            Err(case) => fail(report, format!("{:?} case not proven", case)),
+        };
+    }
+
+    match prove_pa_with_rules(content, &stores, max_nodes, &rules) {
+        Some(result) if result.truth_result == BinaryTruth::True => {
+            report.steps = result.steps.len();
+            report.nodes_explored = result.nodes_explored;
+            if certificates {
+                report.certificate = Some(derivation_text(&result));
+            }
+            EntryReport { status: EntryStatus::Proved, ..report }
+        }
+        Some(_) => fail(report, "refuted".to_string()),
+        None => fail(report, "search limit reached".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = "\
+# Left identity and left successor need induction; 2 + x then follows from them.
+theorem zero_add: EQ (PLUS (0) (/0)) (/0)
+  by induction on /0
+theorem one_one: EQ (PLUS (1) (1)) (2)
+theorem succ_add: EQ (PLUS (S (/0)) (/1)) (S (PLUS (/0) (/1)))
+  by induction on /1
+theorem unbounded: FORALL (EXISTS (LT (/1) (/0)))
+theorem two_add: EQ (PLUS (2) (/0)) (S (S (/0)))
+  hint use succ_add
+  hint use zero_add
+theorem wrong: EQ (PLUS (/0) (1)) (/0)
+  hint max_nodes 50
+";
+
+    #[test]
+    fn test_parse_script() {
+        let entries = parse_script(SCRIPT).unwrap();
+        assert_eq!(entries.len(), 6);
+        assert_eq!(entries[0].induction, Some(0));
+        assert_eq!(entries[0].line, 2);
+        assert_eq!(entries[5].hints, vec![Hint::MaxNodes(50)]);
+
+        let error = parse_script("theorem a: EQ (0) (0)\n  by magic").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(parse_script("  hint use a").is_err());
+    }
+
+    #[test]
+    fn test_run_script() {
+        let entries = parse_script(SCRIPT).unwrap();
+        let report = run_script(&entries, 2000, true);
+
+        let statuses: Vec<_> = report.entries.iter().map(|entry| entry.status.clone()).collect();
+        assert_eq!(statuses[..5], [
+            EntryStatus::ProvedByInduction,
+            EntryStatus::Proved,
+            EntryStatus::ProvedByInduction,
+            EntryStatus::Decided,
+            EntryStatus::Proved,
+        ]);
+        assert!(!report.entries[5].passed());
+        assert_eq!((report.passed(), report.failed()), (5, 1));
+        assert!(report.entries[1].certificate.as_ref().is_some_and(|text| text.contains("axiom")));
+    }
+}
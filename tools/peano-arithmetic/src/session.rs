@@ -0,0 +1,330 @@
+//! State and commands of the interactive `peano` prover.
+//!
+//! A session holds an axiom profile, the rewrite rules in force (the
+//! profile's rules plus any assumed equalities), a theorem database, the node
+//! budget, and whether proofs are traced. Each input line is one command:
+//!
+//! - `prove <formula>`: prove an atom by search, or decide a quantified
+//!   formula by Presburger arithmetic
+//! - `assume <equality>`: add an equality as a rewrite rule, its variables
+//!   matching any term
+//! - `def <name>(<params>) := <term>`: define a function, whose unfolding
+//!   rule joins the rules of every profile
+//! - `theorems`: list the stored theorems
+//! - `use <name>`: add a stored equality as a rewrite rule
+//! - `rules`: list the rules in force
+//! - `profile <name>`: switch to another axiom profile, dropping assumptions
+//! - `set max_nodes <n>`: change the node budget
+//! - `trace on|off`: print whole derivations or just outcomes
+//! - `help`, `quit`
+//!
+//! Formulas may be written infix (`x + 0 = x`) or in the prefix form
+//! (`EQ (PLUS (/0) (0)) (/0)`).
+//!
+//! Atoms proven without assumptions are recorded in the database, and a goal
+//! already stored there is answered without searching.
+
+use corpus_classical_logic::BinaryTruth;
+use corpus_core::rewriting::RewriteRule;
+
+use crate::counterexample::{find_formula_counterexample, Counterexample};
+use crate::database::{theorem_rule, StoredTheorem, TheoremDatabase};
+use crate::parsing::Parser;
+use crate::presburger::decide;
+use crate::profiles::{prove_with_profile, AxiomProfile, PROFILE_NAMES};
+use crate::prover::{derivation_text, prove_pa_with_rules, ProofOutcome};
+use crate::rewrite::equality_rule;
+use crate::stores::PeanoStores;
+use crate::syntax::{ArithmeticExpression, PeanoContent};
+
+/// Largest numeral tried when searching for counterexamples.
+const MAX_WITNESS: u64 = 8;
+
+pub const HELP: &str = "\
+Commands:
+  prove <formula>       prove an atom, or decide a quantified formula
+  assume <equality>     add an equality as a rewrite rule
+  def f(x, ...) := <t>  define a function by a term
+  theorems              list the stored theorems
+  use <name>            add a stored equality as a rewrite rule
+  rules                 list the rules in force
+  profile <name>        switch to pa, pa-no-induction or q
+  set max_nodes <n>     change the search budget
+  trace on|off          print whole derivations or just outcomes
+  help                  show this message
+  quit                  leave";
+
+/// Response to one command.
+pub enum Reply {
+    /// Outcome of proving an atom.
+    Outcome(Box<ProofOutcome>),
+    /// An atom already in the theorem database.
+    Stored(StoredTheorem),
+    /// Verdict on a quantified formula, `None` outside the linear fragment.
+    Decided(Option<bool>),
+    /// An instance falsifying a quantified formula.
+    Refuted(Counterexample),
+    /// Text to show the user.
+    Message(String),
+    Quit,
+}
+
+pub struct Session {
+    profile: AxiomProfile,
+    rules: Vec<RewriteRule<ArithmeticExpression>>,
+    /// Unfolding rules of the defined functions.
+    definitions: Vec<RewriteRule<ArithmeticExpression>>,
+    assumptions: usize,
+    /// Stored theorems added to the rules by `use`.
+    used: usize,
+    database: TheoremDatabase,
+    max_nodes: usize,
+    trace: bool,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::with_database(TheoremDatabase::new())
+    }
+
+    /// A session recording its theorems in `database`.
+    pub fn with_database(database: TheoremDatabase) -> Self {
+        let profile = AxiomProfile::peano();
+        Self {
+            rules: profile.rules.clone(),
+            definitions: Vec::new(),
+            profile,
+            assumptions: 0,
+            used: 0,
+            database,
+            max_nodes: 10000,
+            trace: false,
+        }
+    }
+
+    pub fn database(&self) -> &TheoremDatabase {
+        &self.database
+    }
+
+    /// Whether proofs should be printed in full.
+    pub fn trace(&self) -> bool {
+        self.trace
+    }
+
+    /// Run one command line.
+    pub fn execute(&mut self, line: &str) -> Result<Reply, String> {
+        let line = line.trim();
+        let (command, argument) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let argument = argument.trim();
+        match command {
+            "prove" => self.prove(argument),
+            "assume" => self.assume(argument),
+            "def" => self.define(line),
+            "theorems" => Ok(Reply::Message(
+                self.database
+                    .theorems()
+                    .map(|theorem| format!("  - {}: {}", theorem.name, theorem.formula))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )),
+            "use" => self.use_theorem(argument),
+            "rules" => Ok(Reply::Message(
+                self.rules.iter().map(|rule| format!("  - {}", rule.name)).collect::<Vec<_>>().join("\n"),
+            )),
+            "profile" => {
+                let mut profile = AxiomProfile::named(argument)
+                    .ok_or_else(|| format!("Unknown profile '{}' (expected one of {})", argument, PROFILE_NAMES.join(", ")))?;
+                profile.rules.extend(self.definitions.iter().cloned());
+                self.rules = profile.rules.clone();
+                self.profile = profile;
+                self.assumptions = 0;
+                self.used = 0;
+                Ok(Reply::Message(format!("profile {}: {} axioms, {} rules", argument, self.profile.axioms.len(), self.rules.len())))
+            }
+            "set" => match argument.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["max_nodes", value] => {
+                    self.max_nodes = value.parse().map_err(|_| format!("Not a node count: {}", value))?;
+                    Ok(Reply::Message(format!("max_nodes = {}", self.max_nodes)))
+                }
+                _ => Err("Usage: set max_nodes <n>".to_string()),
+            },
+            "trace" => {
+                self.trace = match argument {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err("Usage: trace on|off".to_string()),
+                };
+                Ok(Reply::Message(format!("trace {}", argument)))
+            }
+            "help" => Ok(Reply::Message(HELP.to_string())),
+            "quit" | "exit" => Ok(Reply::Quit),
+            "" => Ok(Reply::Message(String::new())),
+            _ => Err(format!("Unknown command '{}' (try help)", command)),
+        }
+    }
+
+    /// Define a function, replacing any earlier definition of it.
+    fn define(&mut self, line: &str) -> Result<Reply, String> {
+        let definition = Parser::new(line).parse_definition()?;
+        let rule = definition.unfolding_rule();
+        for rules in [&mut self.definitions, &mut self.profile.rules, &mut self.rules] {
+            rules.retain(|existing| existing.name != rule.name);
+            rules.push(rule.clone());
+        }
+        Ok(Reply::Message(format!("defined {} ({})", definition.name, rule.name)))
+    }
+
+    fn prove(&mut self, formula: &str) -> Result<Reply, String> {
+        let stores = PeanoStores::new();
+        let proposition = Parser::with_stores(formula, &stores).parse_formula()?;
+        let Some(content) = proposition.value.as_domain() else {
+            let verdict = decide(&proposition.value);
+            if verdict != Some(true)
+                && let Some(witness) = find_formula_counterexample(&proposition.value, MAX_WITNESS)
+            {
+                return Ok(Reply::Refuted(witness));
+            }
+            return Ok(Reply::Decided(verdict));
+        };
+        if let Some(theorem) = self.database.lookup(&content.value) {
+            return Ok(Reply::Stored(theorem.clone()));
+        }
+        // Counterexamples and induction only speak for the profile alone.
+        let outcome = if self.assumptions == 0 && self.used == 0 {
+            prove_with_profile(content, &stores, self.max_nodes, MAX_WITNESS, &self.profile)
+        } else {
+            prove_pa_with_rules(content, &stores, self.max_nodes, &self.rules)
+                .filter(|result| result.truth_result == BinaryTruth::True)
+                .map_or(ProofOutcome::Unknown, ProofOutcome::Proved)
+        };
+
+        // Stored theorems are proven, so only assumptions keep a proof out.
+        let certificate = match &outcome {
+            ProofOutcome::Proved(result) if result.truth_result == BinaryTruth::True => Some(derivation_text(result)),
+            ProofOutcome::ProvedByInduction(proof) => Some(format!(
+                "Base case:\n{}\nStep case:\n{}",
+                derivation_text(&proof.base),
+                derivation_text(&proof.step)
+            )),
+            _ => None,
+        };
+        if let Some(certificate) = certificate
+            && self.assumptions == 0
+        {
+            let name = format!("theorem_{}", self.database.len() + 1);
+            self.database.record(&name, &content.value, &certificate).map_err(|e| format!("Could not store {}: {}", name, e))?;
+        }
+        Ok(Reply::Outcome(Box::new(outcome)))
+    }
+
+    fn use_theorem(&mut self, name: &str) -> Result<Reply, String> {
+        let theorem = self.database.named(name).ok_or_else(|| format!("No stored theorem named '{}'", name))?;
+        let rule = theorem_rule(theorem).ok_or_else(|| format!("{} is not an equality usable as a rule", name))?;
+        let message = format!("{}: {}", name, theorem.formula);
+        self.used += 1;
+        self.rules.push(rule);
+        Ok(Reply::Message(message))
+    }
+
+    fn assume(&mut self, formula: &str) -> Result<Reply, String> {
+        let proposition = Parser::new(formula).parse_formula()?;
+        let Some(PeanoContent::Equals(left, right)) = proposition.value.as_domain().map(|content| content.value.as_ref())
+        else {
+            return Err("Only equalities can be assumed".to_string());
+        };
+
+        let name = format!("assumption_{}", self.assumptions + 1);
+        let rule = equality_rule(name.clone(), left, right).ok_or("Each side has variables the other lacks")?;
+        self.assumptions += 1;
+        self.rules.push(rule);
+        Ok(Reply::Message(format!("{}: {} = {}", name, left, right)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assumption_enables_proof() {
+        let mut session = Session::new();
+        // Commutativity is out of reach for a plain search...
+        session.execute("set max_nodes 200").ok().unwrap();
+        let Ok(Reply::Outcome(outcome)) = session.execute("prove EQ (PLUS (/0) (/1)) (PLUS (/1) (/0))") else {
+            panic!("prove should reply with an outcome");
+        };
+        assert!(matches!(*outcome, ProofOutcome::Unknown), "commutativity should not be found in 200 nodes");
+
+        // ...but immediate once assumed.
+        session.execute("assume EQ (PLUS (/0) (/1)) (PLUS (/1) (/0))").ok().unwrap();
+        let Ok(Reply::Outcome(outcome)) = session.execute("prove EQ (PLUS (2) (/0)) (PLUS (/0) (2))") else {
+            panic!("prove should reply with an outcome");
+        };
+        let ProofOutcome::Proved(result) = *outcome else {
+            panic!("instance of the assumption should be proved");
+        };
+        assert!(result.steps.iter().any(|step| step.rule_name.starts_with("assumption_1")));
+    }
+
+    #[test]
+    fn test_commands() {
+        let mut session = Session::new();
+        assert!(matches!(session.execute("prove FORALL (LE (0) (/0))"), Ok(Reply::Decided(Some(true)))));
+        assert!(matches!(session.execute("prove forall x. exists y. x < y"), Ok(Reply::Decided(Some(true)))));
+        let Ok(Reply::Refuted(witness)) = session.execute("prove forall x. x * x = x") else {
+            panic!("x * x = x fails at 2");
+        };
+        assert_eq!(witness.to_string(), "fails at x=2: 4 ≠ 2");
+        assert!(matches!(session.execute("trace on"), Ok(Reply::Message(_))));
+        assert!(session.trace());
+        assert!(session.execute("assume LT (0) (1)").is_err());
+        assert!(session.execute("assume EQ (/0) (/1)").is_err());
+        assert!(session.execute("frobnicate").is_err());
+        assert!(matches!(session.execute("profile q"), Ok(Reply::Message(_))));
+        assert!(matches!(session.execute("prove 0 + x = x"), Ok(Reply::Outcome(outcome)) if matches!(*outcome, ProofOutcome::Unknown)));
+        assert!(session.execute("profile zfc").is_err());
+        assert!(matches!(session.execute("quit"), Ok(Reply::Quit)));
+    }
+
+    #[test]
+    fn test_theorems_are_stored_and_used() {
+        let mut session = Session::new();
+        assert!(matches!(session.execute("prove 0 + x = x"), Ok(Reply::Outcome(_))));
+        let Ok(Reply::Stored(theorem)) = session.execute("prove EQ (PLUS (0) (/0)) (/0)") else {
+            panic!("the proven goal should be found in the database");
+        };
+        assert_eq!(theorem.name, "theorem_1");
+        assert!(theorem.certificate.starts_with("Base case:"));
+
+        assert!(session.execute("use theorem_2").is_err());
+        session.execute("use theorem_1").ok().unwrap();
+        assert!(session.rules.iter().any(|rule| rule.name == "theorem_1"));
+    }
+
+    #[test]
+    fn test_defined_functions() {
+        let mut session = Session::new();
+        assert!(session.execute("prove square(3) = 9").is_err());
+        session.execute("def square(x) := x * x").ok().unwrap();
+        session.execute("def quad(x, y) := square(x) + y").ok().unwrap();
+        assert!(session.execute("def square(x, y) := x * y").is_err());
+
+        let Ok(Reply::Outcome(outcome)) = session.execute("prove quad(3, y) = 9 + y") else {
+            panic!("prove should reply with an outcome");
+        };
+        let ProofOutcome::Proved(result) = *outcome else {
+            panic!("the definitions should unfold");
+        };
+        assert!(result.steps.iter().any(|step| step.rule_name == "quad_def"));
+
+        // Definitions survive a change of profile.
+        session.execute("profile q").ok().unwrap();
+        assert!(session.rules.iter().any(|rule| rule.name == "square_def"));
+    }
+}
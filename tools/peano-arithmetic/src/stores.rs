@@ -0,0 +1,63 @@
+//! The node stores shared by one run of the PA pipeline.
+//!
+//! Hash-consing only pays off when every stage interns into the same store:
+//! a term rewritten during search is then the very node the parser built,
+//! and a subterm reached along two rewrite paths is allocated once. Parsing
+//! (`Parser::with_stores`), axiom parsing, the search's rewriting, induction
+//! and profile goal checking all take a `PeanoStores` for this reason.
+
+use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
+use corpus_core::expression::LogicalExpression;
+use corpus_core::nodes::NodeStorage;
+
+use crate::domain::PeanoDomain;
+use crate::syntax::{ArithmeticExpression, PeanoContent, PeanoExpression};
+
+/// One store per node type of PA formulas.
+pub struct PeanoStores {
+    pub peano_store: NodeStorage<PeanoExpression>,
+    pub expression_store: NodeStorage<ArithmeticExpression>,
+    pub content_store: NodeStorage<PeanoContent>,
+    pub logical_store: NodeStorage<LogicalExpression<BinaryTruth, PeanoContent, ClassicalOperator>>,
+}
+
+impl Default for PeanoStores {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PeanoStores {
+    pub fn new() -> Self {
+        Self {
+            peano_store: NodeStorage::new(),
+            expression_store: NodeStorage::new(),
+            content_store: NodeStorage::new(),
+            logical_store: NodeStorage::new(),
+        }
+    }
+
+    /// The first-order view of PA over these stores.
+    pub fn domain(&self) -> PeanoDomain<'_> {
+        PeanoDomain::new(&self.content_store, &self.expression_store)
+    }
+
+    /// Nodes interned across all stores.
+    pub fn len(&self) -> usize {
+        self.peano_store.len() + self.expression_store.len() + self.content_store.len() + self.logical_store.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// A run's stores can be handed to, and shared between, search threads.
+#[allow(dead_code)]
+const _: () = {
+    fn assert_send_sync<S: Send + Sync>() {}
+
+    fn stores_are_send_sync() {
+        assert_send_sync::<PeanoStores>();
+    }
+};
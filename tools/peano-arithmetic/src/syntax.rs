@@ -3,7 +3,10 @@ use core::fmt;
 use corpus_classical_logic::{BinaryTruth, ClassicalOperator};
 use corpus_core::expression::{DomainContent, DomainExpression};
 use corpus_core::nodes::{HashNode, HashNodeInner, NodeStorage, Hashing};
-use corpus_core::rewriting::RewriteRule;
+use corpus_core::proving::SubtermRewritable;
+use corpus_core::rewriting::{InjectiveConstructors, RewriteRule};
+
+use crate::functions;
 
 pub type PeanoExpression = DomainExpression<BinaryTruth, PeanoContent>;
 
@@ -17,6 +20,86 @@ pub enum PeanoContent {
         HashNode<ArithmeticExpression>,
         HashNode<ArithmeticExpression>,
     ),
+    /// Strict order: the left side is less than the right.
+    LessThan(
+        HashNode<ArithmeticExpression>,
+        HashNode<ArithmeticExpression>,
+    ),
+    /// Non-strict order: the left side is at most the right.
+    LessEq(
+        HashNode<ArithmeticExpression>,
+        HashNode<ArithmeticExpression>,
+    ),
+    /// Divisibility: the left side divides the right, i.e. `∃k. right = left * k`.
+    Divides(
+        HashNode<ArithmeticExpression>,
+        HashNode<ArithmeticExpression>,
+    ),
+    /// Evenness, i.e. `∃k. x = k + k`.
+    Even(HashNode<ArithmeticExpression>),
+    /// Oddness, i.e. `∃k. x = S(k + k)`.
+    Odd(HashNode<ArithmeticExpression>),
+}
+
+impl PeanoContent {
+    /// The two sides of an equality or comparison.
+    pub fn sides(&self) -> Option<(&HashNode<ArithmeticExpression>, &HashNode<ArithmeticExpression>)> {
+        match self {
+            PeanoContent::Equals(left, right)
+            | PeanoContent::LessThan(left, right)
+            | PeanoContent::LessEq(left, right)
+            | PeanoContent::Divides(left, right) => Some((left, right)),
+            PeanoContent::Arithmetic(_) | PeanoContent::Even(_) | PeanoContent::Odd(_) => None,
+        }
+    }
+
+    /// The argument terms of a relation or predicate, left to right.
+    pub fn terms(&self) -> Vec<&HashNode<ArithmeticExpression>> {
+        match self {
+            PeanoContent::Even(term) | PeanoContent::Odd(term) => vec![term],
+            relation => relation.sides().map_or_else(Vec::new, |(left, right)| vec![left, right]),
+        }
+    }
+
+    /// The same relation between new sides, or `None` for a bare arithmetic expression.
+    pub fn with_sides(
+        &self,
+        left: HashNode<ArithmeticExpression>,
+        right: HashNode<ArithmeticExpression>,
+    ) -> Option<Self> {
+        match self {
+            PeanoContent::Equals(..) => Some(PeanoContent::Equals(left, right)),
+            PeanoContent::LessThan(..) => Some(PeanoContent::LessThan(left, right)),
+            PeanoContent::LessEq(..) => Some(PeanoContent::LessEq(left, right)),
+            PeanoContent::Divides(..) => Some(PeanoContent::Divides(left, right)),
+            PeanoContent::Arithmetic(_) | PeanoContent::Even(_) | PeanoContent::Odd(_) => None,
+        }
+    }
+
+    /// The same relation or predicate over new terms, or `None` if their
+    /// number does not match `terms`.
+    pub fn with_terms(&self, mut terms: Vec<HashNode<ArithmeticExpression>>) -> Option<Self> {
+        match (self, terms.len()) {
+            (PeanoContent::Even(_), 1) => Some(PeanoContent::Even(terms.pop()?)),
+            (PeanoContent::Odd(_), 1) => Some(PeanoContent::Odd(terms.pop()?)),
+            (_, 2) => {
+                let right = terms.pop()?;
+                self.with_sides(terms.pop()?, right)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The core prover rewrites the terms of a relation with arithmetic rules.
+impl SubtermRewritable<ArithmeticExpression> for PeanoContent {
+    fn inner_terms(&self) -> Vec<HashNode<ArithmeticExpression>> {
+        self.terms().into_iter().cloned().collect()
+    }
+
+    fn with_inner_terms(&self, terms: Vec<HashNode<ArithmeticExpression>>) -> Option<Self> {
+        self.with_terms(terms)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -27,8 +110,27 @@ pub enum ArithmeticExpression {
         HashNode<ArithmeticExpression>,
     ),
     Successor(HashNode<ArithmeticExpression>),
+    /// Predecessor, with `P(0) = 0`.
+    Predecessor(HashNode<ArithmeticExpression>),
+    /// Truncated subtraction `x ∸ y`, which is `0` when `y ≥ x`.
+    Monus(
+        HashNode<ArithmeticExpression>,
+        HashNode<ArithmeticExpression>,
+    ),
+    Multiply(
+        HashNode<ArithmeticExpression>,
+        HashNode<ArithmeticExpression>,
+    ),
+    /// Exponentiation `x ^ y`, with `x ^ 0 = S(0)`.
+    Power(
+        HashNode<ArithmeticExpression>,
+        HashNode<ArithmeticExpression>,
+    ),
     Number(u64),
     DeBruijn(u32),
+    /// Application of a user-defined function symbol, by its opcode (see
+    /// `functions`).
+    Function(u64, Vec<HashNode<ArithmeticExpression>>),
 }
 
 impl fmt::Display for PeanoContent {
@@ -36,6 +138,11 @@ impl fmt::Display for PeanoContent {
         match self {
             PeanoContent::Arithmetic(expr) => write!(f, "{}", expr),
             PeanoContent::Equals(left, right) => write!(f, "{} = {}", left, right),
+            PeanoContent::LessThan(left, right) => write!(f, "{} < {}", left, right),
+            PeanoContent::LessEq(left, right) => write!(f, "{} ≤ {}", left, right),
+            PeanoContent::Divides(left, right) => write!(f, "{} ∣ {}", left, right),
+            PeanoContent::Even(term) => write!(f, "Even({})", term),
+            PeanoContent::Odd(term) => write!(f, "Odd({})", term),
         }
     }
 }
@@ -49,8 +156,16 @@ impl fmt::Display for ArithmeticExpression {
         match self {
             ArithmeticExpression::Add(left, right) => write!(f, "({} + {})", left, right),
             ArithmeticExpression::Successor(inner) => write!(f, "S({})", inner),
+            ArithmeticExpression::Predecessor(inner) => write!(f, "P({})", inner),
+            ArithmeticExpression::Monus(left, right) => write!(f, "({} ∸ {})", left, right),
+            ArithmeticExpression::Multiply(left, right) => write!(f, "({} * {})", left, right),
+            ArithmeticExpression::Power(left, right) => write!(f, "({} ^ {})", left, right),
             ArithmeticExpression::Number(n) => write!(f, "{}", n),
             ArithmeticExpression::DeBruijn(idx) => write!(f, "/{}", idx),
+            ArithmeticExpression::Function(opcode, args) => {
+                let args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "{}({})", functions::name(*opcode), args.join(", "))
+            }
         }
     }
 }
@@ -65,13 +180,27 @@ impl HashNodeInner for PeanoContent {
                 let hashes = vec![left.hash(), right.hash()];
                 Hashing::root_hash(Hashing::opcode("equals"), &hashes)
             }
+            PeanoContent::LessThan(left, right) => {
+                Hashing::root_hash(Hashing::opcode("less_than"), &[left.hash(), right.hash()])
+            }
+            PeanoContent::LessEq(left, right) => {
+                Hashing::root_hash(Hashing::opcode("less_eq"), &[left.hash(), right.hash()])
+            }
+            PeanoContent::Divides(left, right) => {
+                Hashing::root_hash(Hashing::opcode("divides"), &[left.hash(), right.hash()])
+            }
+            PeanoContent::Even(term) => Hashing::root_hash(Hashing::opcode("even"), &[term.hash()]),
+            PeanoContent::Odd(term) => Hashing::root_hash(Hashing::opcode("odd"), &[term.hash()]),
         }
     }
 
     fn size(&self) -> u64 {
         match self {
-            PeanoContent::Arithmetic(expr) => 1 + expr.size(),
-            PeanoContent::Equals(left, right) => 1 + left.size() + right.size(),
+            PeanoContent::Arithmetic(expr) | PeanoContent::Even(expr) | PeanoContent::Odd(expr) => 1 + expr.size(),
+            PeanoContent::Equals(left, right)
+            | PeanoContent::LessThan(left, right)
+            | PeanoContent::LessEq(left, right)
+            | PeanoContent::Divides(left, right) => 1 + left.size() + right.size(),
         }
     }
 
@@ -80,6 +209,11 @@ impl HashNodeInner for PeanoContent {
     }
 }
 
+/// `S(x) = S(y)` only when `x = y`, the second Peano axiom.
+impl InjectiveConstructors for ArithmeticExpression {
+    const INJECTIVE: &'static [&'static str] = &["successor"];
+}
+
 impl HashNodeInner for ArithmeticExpression {
     fn hash(&self) -> u64 {
         match self {
@@ -89,10 +223,26 @@ impl HashNodeInner for ArithmeticExpression {
             ArithmeticExpression::Successor(inner) => {
                 Hashing::root_hash(Hashing::opcode("successor"), &[inner.hash()])
             }
+            ArithmeticExpression::Predecessor(inner) => {
+                Hashing::root_hash(Hashing::opcode("predecessor"), &[inner.hash()])
+            }
+            ArithmeticExpression::Monus(left, right) => {
+                Hashing::root_hash(Hashing::opcode("monus"), &[left.hash(), right.hash()])
+            }
+            ArithmeticExpression::Multiply(left, right) => {
+                Hashing::root_hash(Hashing::opcode("multiply"), &[left.hash(), right.hash()])
+            }
+            ArithmeticExpression::Power(left, right) => {
+                Hashing::root_hash(Hashing::opcode("power"), &[left.hash(), right.hash()])
+            }
             ArithmeticExpression::Number(n) => Hashing::root_hash(Hashing::opcode("number"), &[*n]),
             ArithmeticExpression::DeBruijn(idx) => {
                 Hashing::root_hash(Hashing::opcode("debruijn"), &[*idx as u64])
             }
+            ArithmeticExpression::Function(opcode, args) => {
+                let hashes: Vec<_> = args.iter().map(|arg| arg.hash()).collect();
+                Hashing::root_hash(*opcode, &hashes)
+            }
         }
     }
 
@@ -100,8 +250,13 @@ impl HashNodeInner for ArithmeticExpression {
         match self {
             ArithmeticExpression::Add(left, right) => 1 + left.size() + right.size(),
             ArithmeticExpression::Successor(inner) => 1 + inner.size(),
+            ArithmeticExpression::Predecessor(inner) => 1 + inner.size(),
+            ArithmeticExpression::Monus(left, right) => 1 + left.size() + right.size(),
+            ArithmeticExpression::Multiply(left, right) => 1 + left.size() + right.size(),
+            ArithmeticExpression::Power(left, right) => 1 + left.size() + right.size(),
             ArithmeticExpression::Number(_) => 1,
             ArithmeticExpression::DeBruijn(_) => 1,
+            ArithmeticExpression::Function(_, args) => 1 + args.iter().map(|arg| arg.size()).sum::<u64>(),
         }
     }
 
@@ -113,6 +268,19 @@ impl HashNodeInner for ArithmeticExpression {
             ArithmeticExpression::Successor(inner) => {
                 Some((Hashing::opcode("successor"), vec![inner.clone()]))
             }
+            ArithmeticExpression::Predecessor(inner) => {
+                Some((Hashing::opcode("predecessor"), vec![inner.clone()]))
+            }
+            ArithmeticExpression::Monus(left, right) => {
+                Some((Hashing::opcode("monus"), vec![left.clone(), right.clone()]))
+            }
+            ArithmeticExpression::Multiply(left, right) => {
+                Some((Hashing::opcode("multiply"), vec![left.clone(), right.clone()]))
+            }
+            ArithmeticExpression::Power(left, right) => {
+                Some((Hashing::opcode("power"), vec![left.clone(), right.clone()]))
+            }
+            ArithmeticExpression::Function(opcode, args) => Some((*opcode, args.clone())),
             ArithmeticExpression::Number(_) | ArithmeticExpression::DeBruijn(_) => None,
         }
     }
@@ -138,6 +306,30 @@ impl HashNodeInner for ArithmeticExpression {
                     store,
                 ))
             }
+            o if o == Hashing::opcode("predecessor") && children.len() == 1 => {
+                Some(HashNode::from_store(
+                    ArithmeticExpression::Predecessor(children[0].clone()),
+                    store,
+                ))
+            }
+            o if o == Hashing::opcode("monus") && children.len() == 2 => {
+                Some(HashNode::from_store(
+                    ArithmeticExpression::Monus(children[0].clone(), children[1].clone()),
+                    store,
+                ))
+            }
+            o if o == Hashing::opcode("multiply") && children.len() == 2 => {
+                Some(HashNode::from_store(
+                    ArithmeticExpression::Multiply(children[0].clone(), children[1].clone()),
+                    store,
+                ))
+            }
+            o if o == Hashing::opcode("power") && children.len() == 2 => {
+                Some(HashNode::from_store(
+                    ArithmeticExpression::Power(children[0].clone(), children[1].clone()),
+                    store,
+                ))
+            }
             o if o == Hashing::opcode("number") && children.len() == 1 => {
                 let n = children[0].hash();
                 Some(HashNode::from_store(ArithmeticExpression::Number(n), store))
@@ -146,6 +338,9 @@ impl HashNodeInner for ArithmeticExpression {
                 let idx = children[0].hash() as u32;
                 Some(HashNode::from_store(ArithmeticExpression::DeBruijn(idx), store))
             }
+            o if functions::symbol(o).is_some_and(|symbol| symbol.arity == children.len()) => {
+                Some(HashNode::from_store(ArithmeticExpression::Function(o, children), store))
+            }
             _ => None,
         }
     }
@@ -218,29 +413,79 @@ pub fn wrap_arithmetic_rules_for_equality(
     }).collect()
 }
 
-/// Apply successor injectivity rewrite: S(x) = S(y) -> x = y
+/// Which of the two numeral spellings normalization produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumeralForm {
+    /// `S(S(0))` becomes `2`.
+    #[default]
+    Decimal,
+    /// `2` becomes `S(S(0))`.
+    Successor,
+}
+
+/// Rewrite the numeral at the root of `term` one step towards `form`:
+/// `S(n)` to `n+1`, or `n+1` to `S(n)`.
 ///
-/// If both sides of the equality are successor expressions, rewrite to
-/// the equality of their inner terms.
-pub fn apply_successor_injectivity(
-    equality: &HashNode<PeanoContent>,
-    store: &NodeStorage<PeanoContent>,
-) -> Option<HashNode<PeanoContent>> {
-    // This function only handles Equals, not Arithmetic
-    let PeanoContent::Equals(left, right) = equality.value.as_ref() else {
-        return None;
+/// Returns `None` if the root is already in `form`.
+pub fn numeral_step(
+    term: &HashNode<ArithmeticExpression>,
+    form: NumeralForm,
+    store: &NodeStorage<ArithmeticExpression>,
+) -> Option<HashNode<ArithmeticExpression>> {
+    let expr = match (form, term.value.as_ref()) {
+        (NumeralForm::Decimal, ArithmeticExpression::Successor(inner)) => match inner.value.as_ref() {
+            ArithmeticExpression::Number(n) => ArithmeticExpression::Number(n.checked_add(1)?),
+            _ => return None,
+        },
+        (NumeralForm::Successor, ArithmeticExpression::Number(n)) if *n > 0 => {
+            let previous = HashNode::from_store(ArithmeticExpression::Number(n - 1), store);
+            ArithmeticExpression::Successor(previous)
+        }
+        _ => return None,
     };
+    Some(HashNode::from_store(expr, store))
+}
 
-    // Check if both sides are Successor expressions
-    let ArithmeticExpression::Successor(left_inner) = left.value.as_ref() else {
-        return None;
+/// Rewrite every numeral in `term` into `form`, so that `2` and `S(S(0))`
+/// normalize to the same node.
+pub fn normalize_numerals(
+    term: &HashNode<ArithmeticExpression>,
+    form: NumeralForm,
+    store: &NodeStorage<ArithmeticExpression>,
+) -> HashNode<ArithmeticExpression> {
+    let term = match term.value.decompose() {
+        Some((opcode, children)) => {
+            let children = children.iter().map(|child| normalize_numerals(child, form, store)).collect();
+            ArithmeticExpression::construct_from_parts(opcode, children, store).expect("rebuilt from its own parts")
+        }
+        None => term.clone(),
     };
+    match numeral_step(&term, form, store) {
+        Some(step) => normalize_numerals(&step, form, store),
+        None => term,
+    }
+}
 
-    let ArithmeticExpression::Successor(right_inner) = right.value.as_ref() else {
-        return None;
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
 
-    // Create new equality: left_inner = right_inner
-    let new_content = PeanoContent::Equals(left_inner.clone(), right_inner.clone());
-    Some(HashNode::from_store(new_content, store))
+    fn left_side(text: &str, form: NumeralForm) -> HashNode<ArithmeticExpression> {
+        let proposition = Parser::new(text).with_numerals(form).parse_proposition().unwrap();
+        let content = proposition.value.as_domain().unwrap().clone();
+        content.value.sides().unwrap().0.clone()
+    }
+
+    #[test]
+    fn test_numeral_normalization() {
+        let decimal = left_side("EQ (PLUS (S (S (0))) (/0)) (0)", NumeralForm::Decimal);
+        assert_eq!(decimal.to_string(), "(2 + /0)");
+
+        let successor = left_side("EQ (PLUS (2) (/0)) (0)", NumeralForm::Successor);
+        assert_eq!(successor.to_string(), "(S(S(0)) + /0)");
+
+        let store = NodeStorage::new();
+        assert_eq!(normalize_numerals(&successor, NumeralForm::Decimal, &store), decimal);
+    }
 }
@@ -0,0 +1,630 @@
+//! Importing TPTP problems in the arithmetic fragment.
+//!
+//! A problem is a sequence of annotated formulas `fof(name, role, φ).` or
+//! `tff(name, role, φ).`; `%` and `/* … */` comments are ignored. Formulas
+//! use the TPTP connectives `~ & | => <= <=> <~> ~| ~&`, the quantifiers
+//! `! [X, Y] : φ` and `? [X] : φ`, and the atoms `s = t`, `s != t`,
+//! `$less`, `$lesseq`, `$greater` and `$greatereq`. Terms are variables,
+//! numerals, `$sum` and `$product`, and the function symbols `s`/`succ`,
+//! `plus`, `times` and `zero` of the usual Peano axiomatisations.
+//!
+//! Quantifiers range over ℕ. Variables typed `$int`, `$rat` or `$real`
+//! would range over a larger domain, so such problems are rejected as
+//! inappropriate rather than misjudged; other types, such as a declared
+//! `nat`, are read as ℕ, and `type` declarations are skipped.
+//!
+//! Axioms must be universally closed equations, which become rewrite rules
+//! for the conjecture. `solve` settles the conjecture with a `BatchProver`
+//! and reports the outcome as an SZS status.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+use corpus_classical_logic::ClassicalOperator;
+use corpus_core::expression::LogicalExpression;
+use corpus_core::nodes::HashNode;
+
+use crate::batch::{BatchProver, GoalStatus};
+use crate::counterexample::find_counterexample;
+use crate::divisibility::PeanoLogicalExpression;
+use crate::rewrite::equality_rule;
+use crate::stores::PeanoStores;
+use crate::syntax::{ArithmeticExpression, PeanoContent, PeanoExpression};
+
+/// The SZS statuses a problem can be given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SzsStatus {
+    /// The conjecture follows from the axioms.
+    Theorem,
+    /// The axioms hold and the conjecture fails in ℕ.
+    CounterSatisfiable,
+    /// The node budget ran out.
+    ResourceOut,
+    /// The prover stopped without an answer for another reason.
+    GaveUp,
+    /// The problem lies outside the arithmetic fragment.
+    Inappropriate,
+    SyntaxError,
+}
+
+impl fmt::Display for SzsStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SzsStatus::Theorem => "Theorem",
+            SzsStatus::CounterSatisfiable => "CounterSatisfiable",
+            SzsStatus::ResourceOut => "ResourceOut",
+            SzsStatus::GaveUp => "GaveUp",
+            SzsStatus::Inappropriate => "Inappropriate",
+            SzsStatus::SyntaxError => "SyntaxError",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Why a problem could not be imported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportError {
+    /// The input is not well-formed TPTP.
+    Syntax(String),
+    /// The input is TPTP, but outside the fragment the importer reads.
+    Unsupported(String),
+}
+
+impl ImportError {
+    pub fn status(&self) -> SzsStatus {
+        match self {
+            ImportError::Syntax(_) => SzsStatus::SyntaxError,
+            ImportError::Unsupported(_) => SzsStatus::Inappropriate,
+        }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Syntax(reason) => write!(f, "syntax error: {}", reason),
+            ImportError::Unsupported(reason) => write!(f, "unsupported: {}", reason),
+        }
+    }
+}
+
+fn syntax<T>(reason: impl Into<String>) -> Result<T, ImportError> {
+    Err(ImportError::Syntax(reason.into()))
+}
+
+fn unsupported<T>(reason: impl Into<String>) -> Result<T, ImportError> {
+    Err(ImportError::Unsupported(reason.into()))
+}
+
+/// An imported problem: named axioms and at most one named conjecture.
+pub struct TptpProblem {
+    pub axioms: Vec<(String, HashNode<PeanoLogicalExpression>)>,
+    pub conjecture: Option<(String, HashNode<PeanoLogicalExpression>)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Dot,
+    Colon,
+    Not,
+    And,
+    Or,
+    Nand,
+    Nor,
+    Implies,
+    /// `<=`, implication from right to left.
+    Implied,
+    Iff,
+    Xor,
+    Eq,
+    Neq,
+    Forall,
+    Exists,
+    /// `>` and `*` of type signatures, which are skipped.
+    TypeSymbol,
+    /// A functor, predicate or role, starting with a lower-case letter.
+    Word(String),
+    /// A variable, starting with an upper-case letter.
+    Variable(String),
+    /// A defined symbol such as `$sum`.
+    Defined(String),
+    Number(u64),
+    /// A single-quoted name.
+    Quoted(String),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, ImportError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '%' => {
+                chars.by_ref().find(|&c| c == '\n');
+                continue;
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                continue;
+            }
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            ',' => Token::Comma,
+            '.' => Token::Dot,
+            ':' => Token::Colon,
+            '&' => Token::And,
+            '|' => Token::Or,
+            '?' => Token::Exists,
+            '>' | '*' => Token::TypeSymbol,
+            '~' if chars.next_if_eq(&'&').is_some() => Token::Nand,
+            '~' if chars.next_if_eq(&'|').is_some() => Token::Nor,
+            '~' => Token::Not,
+            '!' if chars.next_if_eq(&'=').is_some() => Token::Neq,
+            '!' => Token::Forall,
+            '=' if chars.next_if_eq(&'>').is_some() => Token::Implies,
+            '=' => Token::Eq,
+            '<' if chars.next_if_eq(&'~').is_some() => {
+                if chars.next_if_eq(&'>').is_none() {
+                    return syntax("expected '<~>'");
+                }
+                Token::Xor
+            }
+            '<' if chars.next_if_eq(&'=').is_some() => match chars.next_if_eq(&'>') {
+                Some(_) => Token::Iff,
+                None => Token::Implied,
+            },
+            '\'' => {
+                let name: String = chars.by_ref().take_while(|&c| c != '\'').collect();
+                Token::Quoted(name)
+            }
+            '-' | '+' if chars.peek().is_some_and(char::is_ascii_digit) => {
+                return unsupported(format!("signed numeral starting with '{}'", c));
+            }
+            c if c.is_ascii_digit() => {
+                let mut digits = c.to_string();
+                while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                    digits.push(digit);
+                }
+                let mut lookahead = chars.clone();
+                if lookahead.next().is_some_and(|c| c == '/' || c == '.') && lookahead.next().is_some_and(|c| c.is_ascii_digit()) {
+                    return unsupported(format!("non-integer numeral after {}", digits));
+                }
+                Token::Number(digits.parse().map_err(|_| ImportError::Unsupported(format!("numeral {} is too large", digits)))?)
+            }
+            c if c.is_alphabetic() || c == '$' => {
+                let mut word = c.to_string();
+                while let Some(c) = chars.next_if(|&c| c.is_alphanumeric() || c == '_' || c == '$') {
+                    word.push(c);
+                }
+                if c == '$' {
+                    Token::Defined(word)
+                } else if c.is_uppercase() {
+                    Token::Variable(word)
+                } else {
+                    Token::Word(word)
+                }
+            }
+            c => return syntax(format!("unexpected character '{}'", c)),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Reads the annotated formulas of a problem into `PeanoLogicalExpression`s.
+struct TptpParser<'a> {
+    tokens: Peekable<IntoIter<Token>>,
+    stores: &'a PeanoStores,
+    /// Variables bound by the enclosing quantifiers, innermost last.
+    bound: Vec<String>,
+}
+
+impl<'a> TptpParser<'a> {
+    fn next(&mut self) -> Result<Token, ImportError> {
+        match self.tokens.next() {
+            Some(token) => Ok(token),
+            None => syntax("unexpected end of input"),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ImportError> {
+        match self.next()? {
+            token if token == expected => Ok(()),
+            token => syntax(format!("expected {:?}, found {:?}", expected, token)),
+        }
+    }
+
+    /// Skip tokens up to the parenthesis closing the current one, leaving
+    /// it to be consumed.
+    fn skip_to_close(&mut self) -> Result<(), ImportError> {
+        let mut depth = 0;
+        loop {
+            match self.tokens.peek() {
+                Some(Token::RParen) if depth == 0 => return Ok(()),
+                Some(Token::LParen | Token::LBracket) => depth += 1,
+                Some(Token::RParen | Token::RBracket) => depth -= 1,
+                Some(_) => {}
+                None => return syntax("unclosed parenthesis"),
+            }
+            self.tokens.next();
+        }
+    }
+
+    fn problem(&mut self) -> Result<TptpProblem, ImportError> {
+        let mut problem = TptpProblem { axioms: Vec::new(), conjecture: None };
+        while self.tokens.peek().is_some() {
+            let language = match self.next()? {
+                Token::Word(word) => word,
+                token => return syntax(format!("expected an annotated formula, found {:?}", token)),
+            };
+            if !matches!(language.as_str(), "fof" | "tff") {
+                return unsupported(format!("{} input", language));
+            }
+            self.expect(Token::LParen)?;
+            let name = match self.next()? {
+                Token::Word(name) | Token::Quoted(name) => name,
+                Token::Number(n) => n.to_string(),
+                token => return syntax(format!("expected a formula name, found {:?}", token)),
+            };
+            self.expect(Token::Comma)?;
+            let role = match self.next()? {
+                Token::Word(role) => role,
+                token => return syntax(format!("expected the role of {}, found {:?}", name, token)),
+            };
+            self.expect(Token::Comma)?;
+
+            match role.as_str() {
+                "type" => self.skip_to_close()?,
+                "axiom" | "hypothesis" | "definition" | "lemma" | "theorem" => {
+                    let formula = self.formula()?;
+                    problem.axioms.push((name, formula));
+                }
+                "conjecture" if problem.conjecture.is_some() => return unsupported("more than one conjecture"),
+                "conjecture" => problem.conjecture = Some((name, self.formula()?)),
+                _ => return unsupported(format!("role {} of {}", role, name)),
+            }
+            if self.tokens.peek() == Some(&Token::Comma) {
+                self.skip_to_close()?;
+            }
+            self.expect(Token::RParen)?;
+            self.expect(Token::Dot)?;
+        }
+        Ok(problem)
+    }
+
+    fn formula(&mut self) -> Result<HashNode<PeanoLogicalExpression>, ImportError> {
+        let left = self.unitary()?;
+        let operator = match self.tokens.peek() {
+            Some(Token::And) => ClassicalOperator::And,
+            Some(Token::Or) => ClassicalOperator::Or,
+            Some(Token::Implies | Token::Implied | Token::Iff | Token::Xor | Token::Nand | Token::Nor) => {
+                let connective = self.next()?;
+                let right = self.unitary()?;
+                return Ok(match connective {
+                    Token::Implies => self.compound(ClassicalOperator::Implies, vec![left, right]),
+                    Token::Implied => self.compound(ClassicalOperator::Implies, vec![right, left]),
+                    Token::Iff => self.compound(ClassicalOperator::Iff, vec![left, right]),
+                    Token::Xor => self.negated(ClassicalOperator::Iff, vec![left, right]),
+                    Token::Nand => self.negated(ClassicalOperator::And, vec![left, right]),
+                    _ => self.negated(ClassicalOperator::Or, vec![left, right]),
+                });
+            }
+            _ => return Ok(left),
+        };
+
+        // `&` and `|` associate, but do not mix without parentheses.
+        let connective = self.tokens.peek().cloned();
+        let mut formula = left;
+        while self.tokens.peek() == connective.as_ref() {
+            self.tokens.next();
+            let right = self.unitary()?;
+            formula = self.compound(operator, vec![formula, right]);
+        }
+        Ok(formula)
+    }
+
+    fn unitary(&mut self) -> Result<HashNode<PeanoLogicalExpression>, ImportError> {
+        match self.tokens.peek() {
+            Some(Token::Not) => {
+                self.tokens.next();
+                let inner = self.unitary()?;
+                Ok(self.compound(ClassicalOperator::Not, vec![inner]))
+            }
+            Some(Token::Forall | Token::Exists) => {
+                let quantifier = match self.next()? {
+                    Token::Forall => ClassicalOperator::Forall,
+                    _ => ClassicalOperator::Exists,
+                };
+                self.quantified(quantifier)
+            }
+            Some(Token::LParen) => {
+                self.tokens.next();
+                let formula = self.formula()?;
+                self.expect(Token::RParen)?;
+                Ok(formula)
+            }
+            _ => self.atom(),
+        }
+    }
+
+    /// Parse `[X, Y: nat] : body` after a quantifier.
+    fn quantified(&mut self, quantifier: ClassicalOperator) -> Result<HashNode<PeanoLogicalExpression>, ImportError> {
+        self.expect(Token::LBracket)?;
+        let mut count = 0;
+        loop {
+            match self.next()? {
+                Token::Variable(name) => self.bound.push(name),
+                token => return syntax(format!("expected a variable, found {:?}", token)),
+            }
+            count += 1;
+            if self.tokens.peek() == Some(&Token::Colon) {
+                self.tokens.next();
+                match self.next()? {
+                    Token::Defined(domain) if matches!(domain.as_str(), "$int" | "$rat" | "$real") => {
+                        return unsupported(format!("quantifier over {}", domain));
+                    }
+                    Token::Defined(_) | Token::Word(_) => {}
+                    token => return syntax(format!("expected a type, found {:?}", token)),
+                }
+            }
+            match self.next()? {
+                Token::Comma => continue,
+                Token::RBracket => break,
+                token => return syntax(format!("expected ',' or ']', found {:?}", token)),
+            }
+        }
+        self.expect(Token::Colon)?;
+
+        let mut body = self.unitary()?;
+        for _ in 0..count {
+            self.bound.pop();
+            body = self.compound(quantifier, vec![body]);
+        }
+        Ok(body)
+    }
+
+    fn atom(&mut self) -> Result<HashNode<PeanoLogicalExpression>, ImportError> {
+        if let Some(Token::Defined(predicate)) = self.tokens.peek().cloned()
+            && matches!(predicate.as_str(), "$less" | "$lesseq" | "$greater" | "$greatereq")
+        {
+            self.tokens.next();
+            let [left, right] = self.arguments(&predicate)?;
+            let content = match predicate.as_str() {
+                "$less" => PeanoContent::LessThan(left, right),
+                "$lesseq" => PeanoContent::LessEq(left, right),
+                "$greater" => PeanoContent::LessThan(right, left),
+                _ => PeanoContent::LessEq(right, left),
+            };
+            return Ok(self.relation(content));
+        }
+
+        let left = self.term()?;
+        match self.next()? {
+            Token::Eq => {
+                let right = self.term()?;
+                Ok(self.relation(PeanoContent::Equals(left, right)))
+            }
+            Token::Neq => {
+                let right = self.term()?;
+                let equality = self.relation(PeanoContent::Equals(left, right));
+                Ok(self.compound(ClassicalOperator::Not, vec![equality]))
+            }
+            token => unsupported(format!("predicate other than '=' before {:?}", token)),
+        }
+    }
+
+    fn term(&mut self) -> Result<HashNode<ArithmeticExpression>, ImportError> {
+        let expr = match self.next()? {
+            Token::Number(n) => ArithmeticExpression::Number(n),
+            Token::Variable(name) => match self.bound.iter().rposition(|bound| *bound == name) {
+                Some(position) => ArithmeticExpression::DeBruijn((self.bound.len() - 1 - position) as u32),
+                None => return syntax(format!("unbound variable {}", name)),
+            },
+            Token::Word(name) if name == "zero" => ArithmeticExpression::Number(0),
+            Token::Word(name) if matches!(name.as_str(), "s" | "succ") => {
+                let [inner] = self.arguments(&name)?;
+                ArithmeticExpression::Successor(inner)
+            }
+            Token::Word(name) | Token::Defined(name)
+                if matches!(name.as_str(), "plus" | "times" | "$sum" | "$product") =>
+            {
+                let [left, right] = self.arguments(&name)?;
+                match name.as_str() {
+                    "plus" | "$sum" => ArithmeticExpression::Add(left, right),
+                    _ => ArithmeticExpression::Multiply(left, right),
+                }
+            }
+            Token::Word(name) | Token::Defined(name) => return unsupported(format!("function symbol {}", name)),
+            token => return syntax(format!("expected a term, found {:?}", token)),
+        };
+        Ok(HashNode::from_store(expr, &self.stores.expression_store))
+    }
+
+    /// Parse the `N` arguments of `functor`, e.g. `(X, 2)`.
+    fn arguments<const N: usize>(&mut self, functor: &str) -> Result<[HashNode<ArithmeticExpression>; N], ImportError> {
+        self.expect(Token::LParen)?;
+        let mut args = vec![self.term()?];
+        while self.tokens.peek() == Some(&Token::Comma) {
+            self.tokens.next();
+            args.push(self.term()?);
+        }
+        self.expect(Token::RParen)?;
+        let found = args.len();
+        match <[_; N]>::try_from(args) {
+            Ok(args) => Ok(args),
+            Err(_) => syntax(format!("{} takes {} arguments, found {}", functor, N, found)),
+        }
+    }
+
+    fn compound(
+        &self,
+        operator: ClassicalOperator,
+        operands: Vec<HashNode<PeanoLogicalExpression>>,
+    ) -> HashNode<PeanoLogicalExpression> {
+        HashNode::from_store(LogicalExpression::compound(operator, operands), &self.stores.logical_store)
+    }
+
+    fn negated(
+        &self,
+        operator: ClassicalOperator,
+        operands: Vec<HashNode<PeanoLogicalExpression>>,
+    ) -> HashNode<PeanoLogicalExpression> {
+        let inner = self.compound(operator, operands);
+        self.compound(ClassicalOperator::Not, vec![inner])
+    }
+
+    fn relation(&self, content: PeanoContent) -> HashNode<PeanoLogicalExpression> {
+        let content = HashNode::from_store(content, &self.stores.content_store);
+        HashNode::from_store(LogicalExpression::atomic(content), &self.stores.logical_store)
+    }
+}
+
+/// Import the problem `text`, interning its formulas into `stores`.
+pub fn parse_problem(text: &str, stores: &PeanoStores) -> Result<TptpProblem, ImportError> {
+    let mut parser = TptpParser {
+        tokens: tokenize(text)?.into_iter().peekable(),
+        stores,
+        bound: Vec::new(),
+    };
+    parser.problem()
+}
+
+/// The atom under the leading universal quantifiers of `formula`, if that
+/// is all there is. Its variables, bound by the stripped quantifiers, read
+/// as free variables with the same indices.
+fn universal_atom(formula: &HashNode<PeanoLogicalExpression>) -> Option<HashNode<PeanoContent>> {
+    match formula.value.as_ref() {
+        LogicalExpression::Atomic(content) => Some(content.clone()),
+        LogicalExpression::Compound { operator: ClassicalOperator::Forall, operands, .. } => {
+            universal_atom(operands.first()?)
+        }
+        _ => None,
+    }
+}
+
+/// `formula` as a goal of the PA pipeline, with universally closed atoms
+/// opened so that rewriting and induction apply to them.
+fn proposition(formula: &HashNode<PeanoLogicalExpression>, stores: &PeanoStores) -> HashNode<PeanoExpression> {
+    let expr = match universal_atom(formula) {
+        Some(content) => PeanoExpression::domain(content),
+        None => PeanoExpression::logical(formula.clone()),
+    };
+    HashNode::from_store(expr, &stores.peano_store)
+}
+
+/// Settle the conjecture of `problem` with `prover`, after adding the axioms
+/// to its rules.
+///
+/// A refutation only makes the problem counter-satisfiable when ℕ is a model
+/// of the axioms, so it is reported as such only when no axiom has a small
+/// counterexample.
+pub fn solve(problem: &TptpProblem, prover: &mut BatchProver) -> SzsStatus {
+    let mut axioms = Vec::new();
+    for (name, axiom) in &problem.axioms {
+        let Some(content) = universal_atom(axiom) else {
+            return SzsStatus::Inappropriate;
+        };
+        let PeanoContent::Equals(left, right) = content.value.as_ref() else {
+            return SzsStatus::Inappropriate;
+        };
+        let Some(rule) = equality_rule(name.as_str(), left, right) else {
+            return SzsStatus::Inappropriate;
+        };
+        prover.assume(rule);
+        axioms.push(content);
+    }
+    let Some((_, conjecture)) = &problem.conjecture else {
+        return SzsStatus::Inappropriate;
+    };
+
+    let goal = proposition(conjecture, prover.stores());
+    match prover.settle_proposition(&goal).0 {
+        status if status.proved() => SzsStatus::Theorem,
+        GoalStatus::Disproved if axioms.iter().all(|axiom| find_counterexample(axiom, 8).is_none()) => {
+            SzsStatus::CounterSatisfiable
+        }
+        GoalStatus::Timeout => SzsStatus::ResourceOut,
+        GoalStatus::Error(_) => SzsStatus::Inappropriate,
+        _ => SzsStatus::GaveUp,
+    }
+}
+
+/// Import and solve the problem `text`, searching at most `max_nodes` states.
+pub fn run_problem(text: &str, max_nodes: usize) -> SzsStatus {
+    let mut prover = BatchProver::new(max_nodes);
+    match parse_problem(text, prover.stores()) {
+        Ok(problem) => solve(&problem, &mut prover),
+        Err(e) => e.status(),
+    }
+}
+
+/// The SZS status line for the problem `name`.
+pub fn szs_line(status: SzsStatus, name: &str) -> String {
+    format!("% SZS status {} for {}", status, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROBLEM: &str = "\
+% Addition commutes with a successor.
+tff(nat_type, type, nat: $tType).
+fof(add_succ, axiom, ![X, Y]: plus(X, s(Y)) = s(plus(X, Y))).
+/* The conjecture. */
+fof(goal, conjecture, ![X: nat]: plus(X, s(0)) = s(X), [file('x')]).
+";
+
+    #[test]
+    fn test_parse_problem() {
+        let stores = PeanoStores::new();
+        let problem = parse_problem(PROBLEM, &stores).unwrap();
+        assert_eq!(problem.axioms.len(), 1);
+        assert_eq!(problem.axioms[0].0, "add_succ");
+        let (name, conjecture) = problem.conjecture.unwrap();
+        assert_eq!(name, "goal");
+        assert_eq!(universal_atom(&conjecture).unwrap().to_string(), "(/0 + S(0)) = S(/0)");
+
+        let error = |text| parse_problem(text, &stores).err().map(|e| e.status());
+        assert_eq!(error("fof(a, conjecture, X = 0)."), Some(SzsStatus::SyntaxError));
+        assert_eq!(error("fof(a, conjecture, ![X: $int]: X = X)."), Some(SzsStatus::Inappropriate));
+        assert_eq!(error("fof(a, conjecture, ![X]: p(X))."), Some(SzsStatus::Inappropriate));
+        assert_eq!(error("cnf(a, axiom, X = X)."), Some(SzsStatus::Inappropriate));
+    }
+
+    #[test]
+    fn test_szs_statuses() {
+        assert_eq!(run_problem(PROBLEM, 2000), SzsStatus::Theorem);
+        assert_eq!(run_problem("fof(c, conjecture, ![X]: $sum(X, 0) = X).", 2000), SzsStatus::Theorem);
+        assert_eq!(run_problem("fof(c, conjecture, ![X]: ?[Y]: $greater(Y, X)).", 2000), SzsStatus::Theorem);
+        assert_eq!(
+            run_problem("fof(c, conjecture, ![X]: $less(X, $product(X, X))).", 2000),
+            SzsStatus::CounterSatisfiable
+        );
+        assert_eq!(run_problem("fof(c, conjecture, ![X]: $sum(X, 1) = X).", 2000), SzsStatus::CounterSatisfiable);
+        // Refuting the conjecture says nothing when the axioms fail in ℕ.
+        assert_eq!(
+            run_problem("fof(a, axiom, ![X]: s(X) = X). fof(c, conjecture, 1 = 2).", 2000),
+            SzsStatus::GaveUp
+        );
+        assert_eq!(
+            run_problem("fof(c, conjecture, ![X]: $product(X, X) != $sum(X, X)).", 2000),
+            SzsStatus::Inappropriate
+        );
+        assert_eq!(szs_line(SzsStatus::Theorem, "NUM001"), "% SZS status Theorem for NUM001");
+    }
+}
@@ -0,0 +1,83 @@
+//! The prover behind a single call returning JSON, for the wasm bindings
+//! and other embedders that only pass strings.
+//!
+//! The call runs no threads and reads no clock, so it works on
+//! `wasm32-unknown-unknown`; `max_nodes` is its only budget.
+
+use corpus_core::proving::Json;
+use corpus_core::rewriting::RewriteDirection;
+
+use crate::batch::{BatchProver, GoalReport};
+
+/// Settle `formula` as a goal file line would be, as
+///
+/// ```text
+/// {"goal": "...", "status": "proved (induction)", "proved": true,
+///  "nodes_explored": 42, "proofs": [{"case": "base case", "steps":
+///  [{"rule": "add_zero", "direction": "forward", "formula": "..."}]}]}
+/// ```
+///
+/// `case` is absent for direct proofs; a `"reason"` field says why the
+/// search for a goal left open gave up.
+pub fn prove_json(formula: &str, max_nodes: usize) -> Json {
+    report_json(&BatchProver::new(max_nodes).prove(1, formula))
+}
+
+fn report_json(report: &GoalReport) -> Json {
+    let proofs = report
+        .proofs
+        .iter()
+        .map(|proof| {
+            let steps = proof
+                .steps
+                .iter()
+                .map(|step| {
+                    Json::object([
+                        ("rule", Json::string(step.rule.as_str())),
+                        ("direction", Json::string(direction(step.direction))),
+                        ("formula", Json::string(step.formula.as_str())),
+                    ])
+                })
+                .collect();
+            let mut fields = Vec::new();
+            fields.extend(proof.case.map(|case| ("case", Json::string(case))));
+            fields.push(("steps", Json::Array(steps)));
+            Json::object(fields)
+        })
+        .collect();
+    let mut fields = vec![
+        ("goal", Json::string(report.goal.as_str())),
+        ("status", Json::string(report.status.to_string())),
+        ("proved", Json::Bool(report.status.proved())),
+        ("nodes_explored", Json::Number(report.nodes_explored as u64)),
+        ("proofs", Json::Array(proofs)),
+    ];
+    fields.extend(report.failure.as_ref().map(|failure| ("reason", Json::string(failure.reason.to_string()))));
+    Json::object(fields)
+}
+
+fn direction(direction: RewriteDirection) -> &'static str {
+    match direction {
+        RewriteDirection::Forward => "forward",
+        RewriteDirection::Backward => "backward",
+        RewriteDirection::Both => "both",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prove_json() {
+        let json = prove_json("0 + x = x", 2000);
+        assert_eq!(json.get("status"), Some(&Json::string("proved (induction)")));
+        assert_eq!(json.get("proved"), Some(&Json::Bool(true)));
+        let Some(Json::Array(proofs)) = json.get("proofs") else { panic!("no proofs in {}", json) };
+        assert_eq!(proofs[0].get("case"), Some(&Json::string("base case")));
+
+        let json = prove_json("x + = 1", 2000);
+        assert_eq!(json.get("proved"), Some(&Json::Bool(false)));
+        assert!(json.to_string().starts_with("{\"goal\":\"x + = 1\",\"status\":\"error: parse error"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Just enough HTTP/1.1 to serve one request per connection.
+
+use std::io::{self, BufRead, Write};
+
+/// Request heads and bodies longer than this are refused.
+const MAX_REQUEST_LEN: usize = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    /// The request target without its query string.
+    pub path: String,
+    pub body: String,
+}
+
+fn malformed(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Read a request line, headers and, if there is a `Content-Length`, a body.
+pub fn read_request(reader: &mut impl BufRead) -> io::Result<HttpRequest> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(malformed("malformed request line"));
+    };
+    let request = HttpRequest {
+        method: method.to_string(),
+        path: target.split('?').next().unwrap_or(target).to_string(),
+        body: String::new(),
+    };
+
+    let mut content_length = 0;
+    let mut head_len = line.len();
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Err(malformed("headers not terminated"));
+        }
+        head_len += header.len();
+        if head_len > MAX_REQUEST_LEN {
+            return Err(malformed("request head too long"));
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(malformed("malformed header"));
+        };
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.trim().parse().map_err(|_| malformed("malformed Content-Length"))?;
+        }
+    }
+    if content_length > MAX_REQUEST_LEN {
+        return Err(malformed("request body too long"));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8(body).map_err(|_| malformed("request body is not UTF-8"))?;
+    Ok(HttpRequest { body, ..request })
+}
+
+/// Write a complete response and announce that the connection will close.
+pub fn write_response(writer: &mut impl Write, status: u16, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        content_type,
+        body.len(),
+        body
+    )?;
+    writer.flush()
+}
+
+/// Write the head of a `text/event-stream` response, whose events follow
+/// until the connection closes.
+pub fn write_event_stream_head(writer: &mut impl Write) -> io::Result<()> {
+    write!(writer, "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n")?;
+    writer.flush()
+}
+
+/// Write one server-sent event.
+pub fn write_event(writer: &mut impl Write, event: &str, data: &str) -> io::Result<()> {
+    write!(writer, "event: {}\ndata: {}\n\n", event, data)?;
+    writer.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_request() {
+        let text = "POST /prove?pretty HTTP/1.1\r\nHost: localhost\r\ncontent-length: 11\r\n\r\n{\"goal\": 1}";
+        let request = read_request(&mut text.as_bytes()).unwrap();
+        assert_eq!(request, HttpRequest { method: "POST".to_string(), path: "/prove".to_string(), body: "{\"goal\": 1}".to_string() });
+
+        let request = read_request(&mut "GET /proof/1 HTTP/1.1\r\n\r\n".as_bytes()).unwrap();
+        assert_eq!((request.method.as_str(), request.path.as_str(), request.body.as_str()), ("GET", "/proof/1", ""));
+        assert!(read_request(&mut "GET /proof/1 HTTP/1.1\r\nHost".as_bytes()).is_err());
+
+        let mut response = Vec::new();
+        write_response(&mut response, 404, "application/json", "{}").unwrap();
+        assert_eq!(String::from_utf8(response).unwrap(), "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}");
+    }
+}
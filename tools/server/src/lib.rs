@@ -0,0 +1,298 @@
+//! An HTTP front end for a `ProverService`.
+//!
+//! Endpoints:
+//!
+//! - `POST /prove` with `{"goal": "...", "max_nodes": 10000, "timeout_ms": 5000}`
+//!   queues a goal and answers `202` with `{"id": 1}`. Both budgets are
+//!   optional and capped by the server's `Limits`.
+//! - `GET /proof/{id}` reports the goal's status, and once it is proved its
+//!   certificate.
+//! - `GET /proof/{id}/events` streams the goal's protocol events as
+//!   server-sent events, named by their `type`, until it is proved or fails.
+//!
+//! Goals are proved one at a time by a single worker thread, which owns the
+//! service. The service is built on that thread, so it need not be `Send`.
+//! Every connection carries one request and is then closed.
+
+pub mod http;
+pub mod peano;
+
+use std::io::{self, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use corpus_core::proving::{Json, SearchControl, ToJson};
+use corpus_protocol::service::PROGRESS_INTERVAL;
+use corpus_protocol::{Event, Outcome, ProverService};
+
+use crate::http::{read_request, write_event, write_event_stream_head, write_response, HttpRequest};
+
+/// Budgets for one goal: the defaults for requests that give none, and the
+/// most a request may ask for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_nodes: u64,
+    pub timeout: Duration,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self { max_nodes: 100_000, timeout: Duration::from_secs(30) }
+    }
+}
+
+/// A goal waiting for the worker.
+struct Submission {
+    id: u64,
+    goal: String,
+    max_nodes: usize,
+    timeout: Duration,
+}
+
+/// A submitted goal and the events reported for it so far.
+struct Job {
+    goal: String,
+    events: Vec<Event>,
+    certificate: Option<Json>,
+}
+
+impl Job {
+    fn finished(&self) -> bool {
+        matches!(self.events.last(), Some(Event::Proved { .. } | Event::Failed { .. }))
+    }
+
+    fn status(&self) -> &'static str {
+        match self.events.last() {
+            None => "pending",
+            Some(Event::Proved { .. }) => "proved",
+            Some(Event::Failed { .. }) => "failed",
+            Some(_) => "running",
+        }
+    }
+
+    fn to_json(&self, id: u64) -> Json {
+        let mut fields = vec![("id", Json::Number(id)), ("goal", Json::string(self.goal.as_str())), ("status", Json::string(self.status()))];
+        let nodes_explored = self.events.iter().rev().find_map(|event| match event {
+            Event::Progress { nodes_explored, .. } => Some(*nodes_explored),
+            _ => None,
+        });
+        fields.push(("nodes_explored", Json::Number(nodes_explored.unwrap_or(0))));
+        match self.events.last() {
+            Some(Event::Proved { steps, .. }) => fields.push(("steps", Json::Number(*steps))),
+            Some(Event::Failed { reason, .. }) => fields.push(("reason", Json::string(reason.as_str()))),
+            _ => {}
+        }
+        if let Some(certificate) = &self.certificate {
+            fields.push(("certificate", certificate.clone()));
+        }
+        Json::object(fields)
+    }
+}
+
+/// Jobs by id, counting from 1, shared by the worker and the connections.
+struct Board {
+    jobs: Mutex<Vec<Job>>,
+    changed: Condvar,
+}
+
+impl Board {
+    fn update(&self, id: u64, f: impl FnOnce(&mut Job)) {
+        f(&mut self.jobs.lock().unwrap()[id as usize - 1]);
+        self.changed.notify_all();
+    }
+}
+
+/// The HTTP server. Clones share their jobs and worker.
+#[derive(Clone)]
+pub struct Server {
+    board: Arc<Board>,
+    submissions: Sender<Submission>,
+    limits: Limits,
+}
+
+impl Server {
+    /// Start the worker, which proves goals with the service `make_service`
+    /// builds on its thread.
+    pub fn start<S, F>(make_service: F, limits: Limits) -> Self
+    where
+        S: ProverService,
+        F: FnOnce() -> S + Send + 'static,
+    {
+        let board = Arc::new(Board { jobs: Mutex::new(Vec::new()), changed: Condvar::new() });
+        let (submissions, queue) = mpsc::channel();
+        let worker = Arc::clone(&board);
+        thread::spawn(move || work(make_service(), &worker, queue));
+        Self { board, submissions, limits }
+    }
+
+    /// Serve connections from `listener`, each on a thread of its own.
+    pub fn listen(&self, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let mut stream = stream?;
+            let server = self.clone();
+            thread::spawn(move || {
+                if let Ok(reader) = stream.try_clone() {
+                    let _ = server.handle(reader, &mut stream);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Answer the one request read from `reader`.
+    pub fn handle(&self, reader: impl Read, writer: &mut impl Write) -> io::Result<()> {
+        let request = match read_request(&mut BufReader::new(reader)) {
+            Ok(request) => request,
+            Err(e) => return error(writer, 400, &e.to_string()),
+        };
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+        match (request.method.as_str(), segments.as_slice()) {
+            ("POST", ["prove"]) => self.submit(&request, writer),
+            ("GET", ["proof", id]) => match self.job_json(id) {
+                Some(json) => write_response(writer, 200, "application/json", &json.to_string()),
+                None => error(writer, 404, "no such proof"),
+            },
+            ("GET", ["proof", id, "events"]) => self.stream_events(id, writer),
+            (_, ["prove"] | ["proof", _] | ["proof", _, "events"]) => error(writer, 405, "method not allowed"),
+            _ => error(writer, 404, "not found"),
+        }
+    }
+
+    fn submit(&self, request: &HttpRequest, writer: &mut impl Write) -> io::Result<()> {
+        let body = match Json::parse(&request.body) {
+            Ok(body) => body,
+            Err(e) => return error(writer, 400, &format!("malformed JSON: {}", e)),
+        };
+        let Some(Json::String(goal)) = body.get("goal") else {
+            return error(writer, 400, "missing string field `goal`");
+        };
+        let budget = |key, default: u64| match body.get(key) {
+            None => Ok(default),
+            Some(Json::Number(value)) => Ok((*value).min(default)),
+            Some(_) => Err(format!("field `{}` is not a number", key)),
+        };
+        let (max_nodes, timeout_ms) = match (budget("max_nodes", self.limits.max_nodes), budget("timeout_ms", self.limits.timeout.as_millis() as u64)) {
+            (Ok(max_nodes), Ok(timeout_ms)) => (max_nodes, timeout_ms),
+            (Err(message), _) | (_, Err(message)) => return error(writer, 400, &message),
+        };
+
+        let id = {
+            let mut jobs = self.board.jobs.lock().unwrap();
+            jobs.push(Job { goal: goal.clone(), events: Vec::new(), certificate: None });
+            jobs.len() as u64
+        };
+        let submission = Submission {
+            id,
+            goal: goal.clone(),
+            max_nodes: usize::try_from(max_nodes).unwrap_or(usize::MAX),
+            timeout: Duration::from_millis(timeout_ms),
+        };
+        if self.submissions.send(submission).is_err() {
+            self.board.update(id, |job| job.events.push(Event::Failed { id, reason: "the prover has stopped".to_string() }));
+        }
+        write_response(writer, 202, "application/json", &Json::object([("id", Json::Number(id))]).to_string())
+    }
+
+    fn job_json(&self, id: &str) -> Option<Json> {
+        let id = id.parse::<u64>().ok()?;
+        let jobs = self.board.jobs.lock().unwrap();
+        jobs.get((id as usize).checked_sub(1)?).map(|job| job.to_json(id))
+    }
+
+    fn stream_events(&self, id: &str, writer: &mut impl Write) -> io::Result<()> {
+        let Some(id) = id.parse::<u64>().ok().filter(|id| (1..=self.board.jobs.lock().unwrap().len() as u64).contains(id)) else {
+            return error(writer, 404, "no such proof");
+        };
+        write_event_stream_head(writer)?;
+        let mut sent = 0;
+        loop {
+            let (events, finished) = {
+                let jobs = self.board.jobs.lock().unwrap();
+                let jobs = self.board.changed.wait_while(jobs, |jobs| jobs[id as usize - 1].events.len() == sent).unwrap();
+                let job = &jobs[id as usize - 1];
+                (job.events[sent..].to_vec(), job.finished())
+            };
+            sent += events.len();
+            for event in &events {
+                let json = event.to_json();
+                let Some(Json::String(name)) = json.get("type") else { continue };
+                write_event(writer, name, &json.to_string())?;
+            }
+            if finished {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn error(writer: &mut impl Write, status: u16, message: &str) -> io::Result<()> {
+    write_response(writer, status, "application/json", &Json::object([("error", Json::string(message))]).to_string())
+}
+
+/// Prove submissions in order until every `Server` is dropped.
+fn work<S: ProverService>(mut service: S, board: &Board, queue: Receiver<Submission>) {
+    for Submission { id, goal, max_nodes, timeout } in queue {
+        board.update(id, |job| job.events.push(Event::Accepted { id }));
+        let started = Instant::now();
+        let mut timed_out = false;
+        let mut next_report = PROGRESS_INTERVAL;
+        let outcome = service.prove(&goal, max_nodes, &mut |nodes_explored| {
+            if nodes_explored >= next_report {
+                next_report = nodes_explored + PROGRESS_INTERVAL;
+                board.update(id, |job| job.events.push(Event::Progress { id, nodes_explored: nodes_explored as u64 }));
+            }
+            timed_out = started.elapsed() >= timeout;
+            if timed_out { SearchControl::Abort } else { SearchControl::Continue }
+        });
+        board.update(id, |job| match outcome {
+            _ if timed_out => job.events.push(Event::Failed { id, reason: "time limit reached".to_string() }),
+            Outcome::Proved { steps, certificate } => {
+                job.certificate = Some(certificate);
+                job.events.push(Event::Proved { id, steps: steps as u64 });
+            }
+            Outcome::Failed(reason) => job.events.push(Event::Failed { id, reason }),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peano::PeanoService;
+
+    fn exchange(server: &Server, request: &str) -> String {
+        let mut response = Vec::new();
+        server.handle(request.as_bytes(), &mut response).unwrap();
+        String::from_utf8(response).unwrap()
+    }
+
+    fn post(server: &Server, body: &str) -> String {
+        exchange(server, &format!("POST /prove HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body))
+    }
+
+    #[test]
+    fn test_prove_over_http() {
+        let server = Server::start(PeanoService::new, Limits::default());
+        assert!(post(&server, r#"{"goal": "x + S(0) = S(x)", "max_nodes": 1000}"#).ends_with("\r\n\r\n{\"id\":1}"));
+
+        let events = exchange(&server, "GET /proof/1/events HTTP/1.1\r\n\r\n");
+        assert!(events.starts_with("HTTP/1.1 200 OK\r\nContent-Type: text/event-stream"));
+        assert!(events.contains("event: accepted\ndata: {\"type\":\"accepted\",\"id\":1}\n\n"));
+        assert!(events.ends_with("\n\n") && events.contains("event: proved\n"));
+
+        let status = exchange(&server, "GET /proof/1 HTTP/1.1\r\n\r\n");
+        assert!(status.contains("\"status\":\"proved\"") && status.contains("\"certificate\":{\"schema\":\"corpus-proof/1\""));
+
+        // A budget of no time fails at the first report.
+        post(&server, r#"{"goal": "x + y = y + x", "timeout_ms": 0}"#);
+        let events = exchange(&server, "GET /proof/2/events HTTP/1.1\r\n\r\n");
+        assert!(events.contains("data: {\"type\":\"failed\",\"id\":2,\"reason\":\"time limit reached\"}"));
+
+        assert!(post(&server, r#"{"max_nodes": 10}"#).starts_with("HTTP/1.1 400 Bad Request"));
+        assert!(exchange(&server, "GET /proof/3 HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 404 Not Found"));
+        assert!(exchange(&server, "DELETE /proof/1 HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 405 Method Not Allowed"));
+    }
+}
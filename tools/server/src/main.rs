@@ -0,0 +1,51 @@
+use std::net::TcpListener;
+use std::time::Duration;
+
+use corpus_server::peano::PeanoService;
+use corpus_server::{Limits, Server};
+
+const USAGE: &str = "\
+Usage: corpus-server [--addr=HOST:PORT] [--max-nodes=N] [--timeout-ms=N]
+
+Serves the PA prover over HTTP (default address 127.0.0.1:8080):
+
+  POST /prove              {\"goal\": \"...\", \"max_nodes\": N, \"timeout_ms\": N}
+  GET  /proof/{id}         status, and the certificate once proved
+  GET  /proof/{id}/events  progress as server-sent events
+
+--max-nodes and --timeout-ms set the budget of goals that give none and
+cap those that do.";
+
+fn main() {
+    let mut addr = "127.0.0.1:8080".to_string();
+    let mut limits = Limits::default();
+    for arg in std::env::args().skip(1) {
+        if let Some(value) = arg.strip_prefix("--addr=") {
+            addr = value.to_string();
+        } else if let Some(value) = arg.strip_prefix("--max-nodes=") {
+            limits.max_nodes = value.parse().unwrap_or_else(|_| usage());
+        } else if let Some(value) = arg.strip_prefix("--timeout-ms=") {
+            limits.timeout = Duration::from_millis(value.parse().unwrap_or_else(|_| usage()));
+        } else if matches!(arg.as_str(), "help" | "--help" | "-h") {
+            println!("{}", USAGE);
+            return;
+        } else {
+            usage();
+        }
+    }
+
+    let listener = TcpListener::bind(&addr).unwrap_or_else(|e| {
+        eprintln!("Error: cannot listen on {}: {}", addr, e);
+        std::process::exit(1);
+    });
+    eprintln!("Listening on http://{}", addr);
+    if let Err(e) = Server::start(PeanoService::new, limits).listen(listener) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
@@ -0,0 +1,82 @@
+//! The PA prover as a `ProverService`.
+
+use corpus_classical_logic::BinaryTruth;
+use corpus_core::proving::{ProofCertificate, ProofState, SearchControl, SearchObserver, ToJson};
+use corpus_core::rewriting::RewriteRule;
+use corpus_protocol::{Outcome, ProverService};
+use peano_arithmetic::axioms::peano_arithmetic_rules;
+use peano_arithmetic::parsing::Parser;
+use peano_arithmetic::prover::{search_pa_observed, NoGoals};
+use peano_arithmetic::stores::PeanoStores;
+use peano_arithmetic::syntax::{ArithmeticExpression, PeanoContent};
+
+/// Proves equations of PA from the PA rules. Certificates are written in the
+/// `corpus-proof/1` schema, formulas as printed by the parser's syntax.
+pub struct PeanoService {
+    stores: PeanoStores,
+    rules: Vec<RewriteRule<ArithmeticExpression>>,
+}
+
+impl PeanoService {
+    pub fn new() -> Self {
+        Self { stores: PeanoStores::new(), rules: peano_arithmetic_rules() }
+    }
+}
+
+impl Default for PeanoService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Forwards the states explored to a progress callback.
+struct Reporter<'a>(&'a mut dyn FnMut(usize) -> SearchControl);
+
+impl SearchObserver<PeanoContent, BinaryTruth> for Reporter<'_> {
+    fn on_state_expanded(&mut self, _state: &ProofState<PeanoContent>, nodes_explored: usize) -> SearchControl {
+        (self.0)(nodes_explored)
+    }
+}
+
+impl ProverService for PeanoService {
+    fn prove(&mut self, goal: &str, max_nodes: usize, progress: &mut dyn FnMut(usize) -> SearchControl) -> Outcome {
+        let proposition = match Parser::with_stores(goal, &self.stores).parse_formula() {
+            Ok(proposition) => proposition,
+            Err(e) => return Outcome::Failed(format!("parse error: {}", e)),
+        };
+        let Some(content) = proposition.value.as_domain() else {
+            return Outcome::Failed("not an atomic formula".to_string());
+        };
+        match search_pa_observed(content, &self.stores, max_nodes, None, &self.rules, &NoGoals, &mut Reporter(progress)) {
+            Ok(result) if result.truth_result == BinaryTruth::True => Outcome::Proved {
+                steps: result.steps.len(),
+                certificate: ProofCertificate::from_result(&result).to_json(),
+            },
+            Ok(_) => Outcome::Failed("disproved".to_string()),
+            Err(failure) => Outcome::Failed(failure.reason.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use corpus_core::proving::Json;
+
+    #[test]
+    fn test_peano_service() {
+        let mut service = PeanoService::new();
+        let mut reports = 0;
+        let outcome = service.prove("x + S(0) = S(x)", 1000, &mut |_| {
+            reports += 1;
+            SearchControl::Continue
+        });
+        let Outcome::Proved { steps, certificate } = outcome else { panic!("expected a proof, got {:?}", outcome) };
+        assert!(steps > 0 && reports > 0);
+        assert!(matches!(certificate.get("steps"), Some(Json::Array(written)) if written.len() == steps));
+
+        let aborted = service.prove("x + y = y + x", 1000, &mut |_| SearchControl::Abort);
+        assert_eq!(aborted, Outcome::Failed("aborted".to_string()));
+        assert!(matches!(service.prove("x + = 1", 1000, &mut |_| SearchControl::Continue), Outcome::Failed(reason) if reason.starts_with("parse error")));
+    }
+}